@@ -0,0 +1,160 @@
+//! Vector recall benchmarks: HNSW index vs brute-force cosine scan.
+//!
+//! Benchmarks cover:
+//!   - Raw top-k search over in-memory vectors (index vs exact scan)
+//!   - `SqliteMemory::recall` with `sqlite_ann_index` on and off
+//!
+//! Run: `cargo bench --bench memory_ann`
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::Arc;
+
+use zeroclaw::memory::embeddings::EmbeddingProvider;
+use zeroclaw::memory::hnsw::HnswIndex;
+use zeroclaw::memory::sqlite::SqliteMemory;
+use zeroclaw::memory::vector::cosine_similarity;
+use zeroclaw::memory::{Memory, MemoryCategory};
+
+use async_trait::async_trait;
+
+const DIMS: usize = 384;
+
+/// Deterministic pseudo-random vectors (LCG), so runs are comparable.
+fn random_vectors(count: usize, dims: usize, seed: u64) -> Vec<Vec<f32>> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            (0..dims)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1_442_695_040_888_963_407);
+                    (state >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn brute_force_top_k(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<(usize, f32)> {
+    let mut scored: Vec<(usize, f32)> = vectors
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i, cosine_similarity(query, v)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(k);
+    scored
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Benchmark: top-k search over raw vectors
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn bench_vector_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vector_search_top10");
+
+    for size in [1_000, 10_000] {
+        let vectors = random_vectors(size, DIMS, 42);
+        let ids: Vec<String> = (0..size).map(|i| format!("mem-{i}")).collect();
+        let index = HnswIndex::build(
+            DIMS,
+            ids.iter()
+                .zip(&vectors)
+                .map(|(id, v)| (id.as_str(), v.as_slice())),
+        );
+        let query = random_vectors(1, DIMS, 7).remove(0);
+
+        group.bench_with_input(BenchmarkId::new("brute_force", size), &size, |b, _| {
+            b.iter(|| brute_force_top_k(black_box(&vectors), black_box(&query), 10))
+        });
+        group.bench_with_input(BenchmarkId::new("hnsw", size), &size, |b, _| {
+            b.iter(|| index.search(black_box(&query), 10))
+        });
+    }
+
+    group.finish();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Benchmark: SqliteMemory recall end to end
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Bag-of-words hashing embedder: no network, stable vectors.
+struct HashEmbedding;
+
+#[async_trait]
+impl EmbeddingProvider for HashEmbedding {
+    fn name(&self) -> &str {
+        "hash"
+    }
+
+    fn dimensions(&self) -> usize {
+        DIMS
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut emb = vec![0.0; DIMS];
+                for word in text.split_whitespace() {
+                    let bucket = word
+                        .bytes()
+                        .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b.into()));
+                    emb[bucket % DIMS] += 1.0;
+                }
+                emb
+            })
+            .collect())
+    }
+}
+
+fn seeded_memory(rt: &tokio::runtime::Runtime, dir: &Path, ann: bool) -> SqliteMemory {
+    let mem = SqliteMemory::with_embedder(dir, Arc::new(HashEmbedding), 0.7, 0.3, 10_000, None)
+        .unwrap()
+        .with_ann_index(ann);
+    rt.block_on(async {
+        for i in 0..5_000 {
+            let content = format!(
+                "note {i} about topic{} with tag{} and detail{}",
+                i % 97,
+                i % 13,
+                i % 389
+            );
+            mem.store(&format!("key_{i}"), &content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+    });
+    mem
+}
+
+fn bench_sqlite_recall(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("sqlite_recall_top10");
+    group.sample_size(20);
+
+    for ann in [false, true] {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = seeded_memory(&rt, tmp.path(), ann);
+        let label = if ann { "hnsw" } else { "brute_force" };
+
+        group.bench_function(label, |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    mem.recall(black_box("topic42 tag7 detail100"), 10, None)
+                        .await
+                        .unwrap()
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_vector_search, bench_sqlite_recall);
+criterion_main!(benches);
@@ -266,6 +266,7 @@ impl Agent {
             Some(&config.storage.provider.config),
             &config.workspace_dir,
             config.api_key.as_deref(),
+            &config.reliability,
         )?);
 
         let composio_key = if config.composio.enabled {
@@ -311,6 +312,14 @@ impl Agent {
             &config.model_routes,
             &model_name,
         )?;
+        let response_cache =
+            memory::create_response_cache(&config.memory, &config.workspace_dir).map(Arc::new);
+        let provider = providers::caching::wrap_with_response_cache(
+            provider,
+            response_cache.as_ref(),
+            config,
+            Arc::clone(&observer),
+        );
 
         let dispatcher_choice = config.agent.tool_dispatcher.as_str();
         let tool_dispatcher: Box<dyn ToolDispatcher> = match dispatcher_choice {
@@ -333,10 +342,10 @@ impl Agent {
             .memory(memory)
             .observer(observer)
             .tool_dispatcher(tool_dispatcher)
-            .memory_loader(Box::new(DefaultMemoryLoader::new(
-                5,
-                config.memory.min_relevance_score,
-            )))
+            .memory_loader(Box::new(
+                DefaultMemoryLoader::new(5, config.memory.min_relevance_score)
+                    .with_reranker(memory::reranker::create_memory_reranker(config).map(Arc::new)),
+            ))
             .prompt_builder(SystemPromptBuilder::with_defaults())
             .config(config.agent.clone())
             .model_name(model_name)
@@ -570,6 +579,7 @@ impl Agent {
                         } else {
                             None
                         },
+                        ..Default::default()
                     },
                     &effective_model,
                     self.temperature,
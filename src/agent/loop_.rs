@@ -1,4 +1,7 @@
-use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
+use crate::approval::{
+    current_non_cli_turn, ApprovalManager, ApprovalRequest, ApprovalResponse,
+    PendingNonCliApprovalRequest,
+};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::multimodal;
@@ -24,6 +27,7 @@ mod context;
 mod execution;
 mod history;
 mod parsing;
+mod streaming;
 
 use context::{build_context, build_hardware_context};
 use execution::{
@@ -40,6 +44,7 @@ use parsing::{
     parse_perl_style_tool_calls, parse_structured_tool_calls, parse_tool_call_value,
     parse_tool_calls, parse_tool_calls_from_json_value, tool_call_signature, ParsedToolCall,
 };
+use streaming::{stream_llm_turn, StreamedTurn};
 
 /// Minimum characters per chunk when relaying LLM text to a streaming draft.
 const STREAM_CHUNK_MIN_CHARS: usize = 80;
@@ -363,6 +368,7 @@ pub(crate) async fn run_tool_call_loop(
         .collect();
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
     let turn_id = Uuid::new_v4().to_string();
+    let non_cli_turn = current_non_cli_turn();
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
     let bypass_non_cli_approval_for_turn =
         approval.is_some_and(|mgr| channel_name != "cli" && mgr.consume_non_cli_allow_all_once());
@@ -446,14 +452,35 @@ pub(crate) async fn run_tool_call_loop(
             None
         };
 
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: &prepared_messages.messages,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
+        // Stream the call when a draft is listening and the provider can carry
+        // the full request over its streaming API; otherwise use plain chat.
+        // Text is only relayed live when it cannot contain prompt-guided tool
+        // call markup.
+        let stream_tx = on_delta
+            .as_ref()
+            .filter(|_| provider.supports_streaming_tool_calls());
+        let live_text = use_native_tools || tool_specs.is_empty();
+        let request = ChatRequest {
+            messages: &prepared_messages.messages,
+            tools: request_tools,
+            ..ChatRequest::default()
+        };
+        let chat_future = async {
+            if let Some(tx) = stream_tx {
+                if let Some(turn) =
+                    stream_llm_turn(provider, request, model, temperature, live_text, tx).await?
+                {
+                    return Ok(turn);
+                }
+            }
+            provider
+                .chat(request, model, temperature)
+                .await
+                .map(|response| StreamedTurn {
+                    response,
+                    text_streamed: false,
+                })
+        };
 
         let chat_result = if let Some(token) = cancellation_token.as_ref() {
             tokio::select! {
@@ -464,9 +491,14 @@ pub(crate) async fn run_tool_call_loop(
             chat_future.await
         };
 
+        let text_streamed;
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
-                Ok(resp) => {
+                Ok(StreamedTurn {
+                    response: resp,
+                    text_streamed: streamed,
+                }) => {
+                    text_streamed = streamed;
                     let (resp_input_tokens, resp_output_tokens) = resp
                         .usage
                         .as_ref()
@@ -631,9 +663,10 @@ pub(crate) async fn run_tool_call_loop(
                 }),
             );
             // No tool calls — this is the final response.
-            // If a streaming sender is provided, relay the text in small chunks
-            // so the channel can progressively update the draft message.
-            if let Some(ref tx) = on_delta {
+            // If a streaming sender is provided and the text was not already
+            // streamed live, relay it in small chunks so the channel can
+            // progressively update the draft message.
+            if let Some(tx) = on_delta.as_ref().filter(|_| !text_streamed) {
                 // Clear accumulated progress lines before streaming the final answer.
                 let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
                 // Split on whitespace boundaries, accumulating chunks of at least
@@ -757,57 +790,118 @@ pub(crate) async fn run_tool_call_loop(
 
             // ── Approval hook ────────────────────────────────
             if let Some(mgr) = approval {
-                if bypass_non_cli_approval_for_turn {
+                let check = mgr.check(&tool_name, &tool_args);
+                let denial = if check.is_denied() {
+                    // Deny rules hold even for one-time non-CLI bypass turns.
+                    mgr.record_rule_decision(
+                        &tool_name,
+                        &tool_args,
+                        ApprovalResponse::No,
+                        channel_name,
+                        check.rule.as_deref(),
+                    );
+                    Some(format!(
+                        "Denied by approval rule '{}'.",
+                        check.rule.as_deref().unwrap_or_default()
+                    ))
+                } else if bypass_non_cli_approval_for_turn {
                     mgr.record_decision(
                         &tool_name,
                         &tool_args,
                         ApprovalResponse::Yes,
                         channel_name,
                     );
-                } else if mgr.needs_approval(&tool_name) {
+                    None
+                } else if check.needs_prompt() {
                     let request = ApprovalRequest {
                         tool_name: tool_name.clone(),
                         arguments: tool_args.clone(),
+                        rule: check.rule.clone(),
                     };
 
-                    // Only CLI supports interactive prompts today. For non-CLI channels,
-                    // fail closed instead of silently auto-approving privileged tools.
+                    // Non-CLI channels cannot prompt mid-turn. They run only with a grant
+                    // from an earlier approval prompt and otherwise fail closed instead of
+                    // silently auto-approving privileged tools; interactive channels get
+                    // a prompt for the blocked call once the turn ends.
+                    let mut opened: Option<PendingNonCliApprovalRequest> = None;
                     let decision = if channel_name == "cli" {
                         mgr.prompt_cli(&request)
+                    } else if check.rule.is_none()
+                        && mgr.take_non_cli_tool_grant(
+                            &tool_name,
+                            non_cli_turn.as_ref().map(|turn| &turn.requester),
+                        )
+                    {
+                        ApprovalResponse::Yes
                     } else {
+                        if let Some(turn) = non_cli_turn
+                            .as_ref()
+                            .filter(|turn| turn.interactive && check.rule.is_none())
+                        {
+                            let req = mgr.create_non_cli_pending_request(
+                                &tool_name,
+                                &turn.requester.sender,
+                                &turn.requester.channel,
+                                &turn.requester.reply_target,
+                                Some("blocked during an agent reply".to_string()),
+                            );
+                            turn.push_blocked(req.clone());
+                            opened = Some(req);
+                        }
                         ApprovalResponse::No
                     };
 
-                    mgr.record_decision(&tool_name, &tool_args, decision, channel_name);
+                    mgr.record_rule_decision(
+                        &tool_name,
+                        &tool_args,
+                        decision,
+                        channel_name,
+                        check.rule.as_deref(),
+                    );
 
-                    if decision == ApprovalResponse::No {
-                        let denied = "Denied by user.".to_string();
-                        runtime_trace::record_event(
-                            "tool_call_result",
-                            Some(channel_name),
-                            Some(provider_name),
-                            Some(model),
-                            Some(&turn_id),
-                            Some(false),
-                            Some(&denied),
-                            serde_json::json!({
-                                "iteration": iteration + 1,
-                                "tool": tool_name.clone(),
-                                "arguments": scrub_credentials(&tool_args.to_string()),
-                            }),
-                        );
-                        ordered_results[idx] = Some((
-                            tool_name.clone(),
-                            call.tool_call_id.clone(),
-                            ToolExecutionOutcome {
-                                output: denied.clone(),
-                                success: false,
-                                error_reason: Some(denied),
-                                duration: Duration::ZERO,
-                            },
-                        ));
-                        continue;
-                    }
+                    (decision == ApprovalResponse::No).then(|| match (&check.rule, &opened) {
+                        (Some(rule), _) if channel_name != "cli" => format!(
+                            "Denied: approval rule '{rule}' requires interactive approval, \
+                             which this channel cannot provide."
+                        ),
+                        (_, Some(req)) => format!(
+                            "Not run: `{tool_name}` needs approval. Approval request `{}` \
+                             has been sent to this chat; retry after it is approved.",
+                            req.request_id
+                        ),
+                        _ => "Denied by user.".to_string(),
+                    })
+                } else {
+                    None
+                };
+
+                if let Some(denied) = denial {
+                    runtime_trace::record_event(
+                        "tool_call_result",
+                        Some(channel_name),
+                        Some(provider_name),
+                        Some(model),
+                        Some(&turn_id),
+                        Some(false),
+                        Some(&denied),
+                        serde_json::json!({
+                            "iteration": iteration + 1,
+                            "tool": tool_name.clone(),
+                            "arguments": scrub_credentials(&tool_args.to_string()),
+                            "approval_rule": check.rule,
+                        }),
+                    );
+                    ordered_results[idx] = Some((
+                        tool_name.clone(),
+                        call.tool_call_id.clone(),
+                        ToolExecutionOutcome {
+                            output: denied.clone(),
+                            success: false,
+                            error_reason: Some(denied),
+                            duration: Duration::ZERO,
+                        },
+                    ));
+                    continue;
                 }
             }
 
@@ -1155,6 +1249,7 @@ pub async fn run(
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
+        &config.reliability,
     )?);
     tracing::info!(backend = mem.name(), "Memory initialized");
 
@@ -1197,6 +1292,7 @@ pub async fn run(
         tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
         tools_registry.extend(peripheral_tools);
     }
+    tools_registry.extend(tools::mcp_tools(&config.mcp, &security).await);
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
@@ -1219,6 +1315,7 @@ pub async fn run(
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        azure_openai: config.provider.azure_openai.clone(),
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -1230,6 +1327,14 @@ pub async fn run(
         model_name,
         &provider_runtime_options,
     )?;
+    let response_cache =
+        memory::create_response_cache(&config.memory, &config.workspace_dir).map(Arc::new);
+    let provider = providers::caching::wrap_with_response_cache(
+        provider,
+        response_cache.as_ref(),
+        &config,
+        Arc::clone(&observer),
+    );
 
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
@@ -1391,7 +1496,10 @@ pub async fn run(
 
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = if interactive {
-        Some(ApprovalManager::from_config(&config.autonomy))
+        Some(
+            ApprovalManager::from_config(&config.autonomy)
+                .with_workspace_dir(&config.workspace_dir),
+        )
     } else {
         None
     };
@@ -1648,6 +1756,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
+        &config.reliability,
     )?);
 
     let (composio_key, composio_entity_id) = if config.composio.enabled {
@@ -1676,6 +1785,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
+    tools_registry.extend(tools::mcp_tools(&config.mcp, &security).await);
 
     let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
     let model_name = config
@@ -1692,6 +1802,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        azure_openai: config.provider.azure_openai.clone(),
     };
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
@@ -1702,6 +1813,14 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         &model_name,
         &provider_runtime_options,
     )?;
+    let response_cache =
+        memory::create_response_cache(&config.memory, &config.workspace_dir).map(Arc::new);
+    let provider = providers::caching::wrap_with_response_cache(
+        provider,
+        response_cache.as_ref(),
+        &config,
+        Arc::clone(&observer),
+    );
 
     let hardware_rag: Option<crate::rag::HardwareRag> = config
         .peripherals
@@ -1849,6 +1968,7 @@ mod tests {
         assert!(scrubbed.contains("\"api_key\": \"sk-1*[REDACTED]\""));
         assert!(scrubbed.contains("public"));
     }
+    use crate::approval::{with_non_cli_turn, NonCliRequester, NonCliTurn};
     use crate::memory::{Memory, MemoryCategory, SqliteMemory};
     use crate::observability::NoopObserver;
     use crate::providers::traits::ProviderCapabilities;
@@ -1982,6 +2102,52 @@ mod tests {
         }
     }
 
+    /// Serves each turn through `stream_chat`; `chat` must not be reached.
+    struct StreamingScriptedProvider {
+        turns: Mutex<VecDeque<Vec<crate::providers::traits::StreamChunk>>>,
+    }
+
+    #[async_trait]
+    impl Provider for StreamingScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("streaming provider should not use non-streaming chat");
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        fn supports_streaming_tool_calls(&self) -> bool {
+            true
+        }
+
+        fn stream_chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+            _options: crate::providers::traits::StreamOptions,
+        ) -> futures_util::stream::BoxStream<
+            'static,
+            crate::providers::traits::StreamResult<crate::providers::traits::StreamChunk>,
+        > {
+            use futures_util::StreamExt;
+            let chunks = self
+                .turns
+                .lock()
+                .expect("turns lock should be valid")
+                .pop_front()
+                .unwrap_or_default();
+            futures_util::stream::iter(chunks.into_iter().map(Ok)).boxed()
+        }
+    }
+
     struct CountingTool {
         name: String,
         invocations: Arc<AtomicUsize>,
@@ -2284,6 +2450,92 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_streams_tool_progress_and_final_text() {
+        use crate::providers::traits::{StreamChunk, ToolCallDelta};
+
+        let provider = StreamingScriptedProvider {
+            turns: Mutex::new(VecDeque::from([
+                vec![
+                    StreamChunk::reasoning("plan"),
+                    StreamChunk::delta("Counting."),
+                    StreamChunk::tool_call(ToolCallDelta {
+                        index: 0,
+                        id: Some("call_1".into()),
+                        name: Some("count_tool".into()),
+                        arguments: "{\"value\":".into(),
+                    }),
+                    StreamChunk::tool_call(ToolCallDelta {
+                        index: 0,
+                        arguments: "\"A\"}".into(),
+                        ..ToolCallDelta::default()
+                    }),
+                ],
+                vec![StreamChunk::delta("All "), StreamChunk::delta("done.")],
+            ])),
+        };
+
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("count"),
+        ];
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(256);
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            Some(tx),
+            None,
+            &[],
+        )
+        .await
+        .expect("streamed loop should complete");
+
+        assert_eq!(result, "All done.");
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+        assert!(history
+            .iter()
+            .any(|msg| msg.role == "tool" && msg.content.contains("counted:A")));
+
+        let mut deltas = Vec::new();
+        while let Ok(delta) = rx.try_recv() {
+            deltas.push(delta);
+        }
+        assert!(deltas
+            .iter()
+            .any(|d| d.starts_with(DRAFT_PROGRESS_SENTINEL) && d.contains("Preparing count_tool")));
+        assert!(deltas
+            .iter()
+            .any(|d| d.starts_with(DRAFT_PROGRESS_SENTINEL) && d.contains("Reasoning")));
+        // Final text is streamed live once, not relayed again afterwards.
+        let last_clear = deltas
+            .iter()
+            .rposition(|d| d == DRAFT_CLEAR_SENTINEL)
+            .expect("final answer should clear progress");
+        let final_text: String = deltas[last_clear + 1..]
+            .iter()
+            .filter(|d| !d.starts_with(DRAFT_PROGRESS_SENTINEL))
+            .cloned()
+            .collect();
+        assert_eq!(final_text, "All done.");
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_executes_multiple_tools_with_ordered_results() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -2425,6 +2677,216 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_uses_one_time_non_cli_grant() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"shell","arguments":{"command":"echo hi"}}
+</tool_call>"#,
+            "done",
+        ]);
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "shell",
+            50,
+            Arc::clone(&active),
+            Arc::clone(&max_active),
+        ))];
+
+        let approval_mgr = ApprovalManager::from_config(&crate::config::AutonomyConfig::default());
+        let alice = NonCliRequester::new("alice", "telegram", "chat-1");
+        approval_mgr.grant_non_cli_once("shell", &alice);
+
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run shell"),
+        ];
+        let observer = NoopObserver;
+
+        let result = with_non_cli_turn(
+            NonCliTurn::new(alice.clone(), false),
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &observer,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                Some(&approval_mgr),
+                "telegram",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("tool loop should complete");
+
+        assert_eq!(result, "done");
+        assert_eq!(max_active.load(Ordering::SeqCst), 1);
+        assert!(!approval_mgr.take_non_cli_tool_grant("shell", Some(&alice)));
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_opens_approval_request_for_blocked_non_cli_call() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"shell","arguments":{"command":"echo hi"}}
+</tool_call>"#,
+            "done",
+        ]);
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "shell",
+            50,
+            Arc::clone(&active),
+            Arc::clone(&max_active),
+        ))];
+
+        // A grant from another chat must not cover this one.
+        let approval_mgr = ApprovalManager::from_config(&crate::config::AutonomyConfig::default());
+        approval_mgr.grant_non_cli_once(
+            "shell",
+            &NonCliRequester::new("alice", "telegram", "chat-2"),
+        );
+        let turn = NonCliTurn::new(NonCliRequester::new("alice", "telegram", "chat-1"), true);
+
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run shell"),
+        ];
+        let observer = NoopObserver;
+
+        let result = with_non_cli_turn(
+            turn.clone(),
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &observer,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                Some(&approval_mgr),
+                "telegram",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                &[],
+            ),
+        )
+        .await
+        .expect("tool loop should complete");
+
+        assert_eq!(result, "done");
+        assert_eq!(max_active.load(Ordering::SeqCst), 0);
+        let blocked = turn.take_blocked();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].tool_name, "shell");
+        assert_eq!(blocked[0].requested_by, "alice");
+        assert_eq!(blocked[0].requested_reply_target, "chat-1");
+        assert!(history
+            .iter()
+            .any(|m| m.content.contains(&blocked[0].request_id)));
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_applies_argument_aware_approval_rules() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"shell","arguments":{"command":"rm -rf build"}}
+</tool_call>
+<tool_call>
+{"name":"shell","arguments":{"command":"echo hi"}}
+</tool_call>"#,
+            "done",
+        ]);
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "shell",
+            10,
+            Arc::clone(&active),
+            Arc::clone(&max_active),
+        ))];
+
+        let approval_mgr = ApprovalManager::from_config(&crate::config::AutonomyConfig {
+            level: crate::security::AutonomyLevel::Full,
+            approval_rules: vec![crate::config::ApprovalRuleConfig {
+                name: Some("no rm".into()),
+                tool: "shell".into(),
+                command: Some("rm *".into()),
+                command_regex: None,
+                min_risk: None,
+                paths: vec![],
+                hosts: vec![],
+                action: crate::config::ApprovalRuleAction::Deny,
+            }],
+            ..crate::config::AutonomyConfig::default()
+        });
+
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("clean up"),
+        ];
+        let observer = NoopObserver;
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            Some(&approval_mgr),
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+        )
+        .await
+        .expect("tool loop should complete");
+
+        assert_eq!(result, "done");
+        assert_eq!(
+            max_active.load(Ordering::SeqCst),
+            1,
+            "echo should still run"
+        );
+        let tool_results = history
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]"))
+            .expect("tool results message should be present");
+        assert_eq!(
+            tool_results
+                .content
+                .matches("Denied by approval rule 'no rm'.")
+                .count(),
+            1
+        );
+        let log = approval_mgr.audit_log();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].rule.as_deref(), Some("no rm"));
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_consumes_one_time_non_cli_allow_all_token() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -11,8 +11,10 @@ pub(super) async fn build_context(
 ) -> String {
     let mut context = String::new();
 
-    // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    // Pull relevant memories for this message, within the sender's
+    // namespaces when a channel handler installed a scope.
+    let scope = memory::namespace::current();
+    if let Ok(entries) = memory::namespace::recall(mem, scope.as_ref(), user_msg, 5, &[]).await {
         let relevant: Vec<_> = entries
             .iter()
             .filter(|e| match e.score {
@@ -1,6 +1,7 @@
 use super::parsing::ParsedToolCall;
 use super::{scrub_credentials, ToolLoopCancelled};
 use crate::approval::ApprovalManager;
+use crate::config::ApprovalRuleAction;
 use crate::observability::{Observer, ObserverEvent};
 use crate::tools::Tool;
 use anyhow::Result;
@@ -107,7 +108,10 @@ pub(super) fn should_execute_tools_in_parallel(
     }
 
     if let Some(mgr) = approval {
-        if tool_calls.iter().any(|call| mgr.needs_approval(&call.name)) {
+        if tool_calls
+            .iter()
+            .any(|call| mgr.check(&call.name, &call.arguments).action != ApprovalRuleAction::Allow)
+        {
             // Approval-gated calls must keep sequential handling so the caller can
             // enforce CLI prompt/deny policy consistently.
             return false;
@@ -0,0 +1,88 @@
+use super::{DRAFT_CLEAR_SENTINEL, DRAFT_PROGRESS_SENTINEL};
+use crate::providers::traits::{StreamAccumulator, StreamOptions};
+use crate::providers::{ChatRequest, ChatResponse, Provider};
+use anyhow::Result;
+use futures_util::StreamExt;
+use tokio::sync::mpsc::Sender;
+
+/// Outcome of a streamed LLM turn.
+pub(super) struct StreamedTurn {
+    pub response: ChatResponse,
+    /// True when response text was forwarded to the draft as it arrived, so
+    /// the caller must not relay it a second time.
+    pub text_streamed: bool,
+}
+
+/// Run one LLM call through [`Provider::stream_chat`], surfacing progress on
+/// the draft channel while chunks arrive:
+/// - text deltas are forwarded live when `live_text` is set (the first one
+///   clears the progress lines above it),
+/// - each tool call is announced as soon as its name is known,
+/// - reasoning is announced once.
+///
+/// Returns `Ok(None)` when the stream fails before producing anything so the
+/// caller can fall back to the non-streaming path.
+pub(super) async fn stream_llm_turn(
+    provider: &dyn Provider,
+    request: ChatRequest<'_>,
+    model: &str,
+    temperature: f64,
+    live_text: bool,
+    tx: &Sender<String>,
+) -> Result<Option<StreamedTurn>> {
+    let mut stream = provider.stream_chat(request, model, temperature, StreamOptions::new(true));
+    let mut accumulator = StreamAccumulator::new();
+    let mut received_any = false;
+    let mut text_streamed = false;
+    let mut reasoning_announced = false;
+    let mut tools_announced = false;
+
+    while let Some(item) = stream.next().await {
+        let chunk = match item {
+            Ok(chunk) => chunk,
+            Err(e) if !received_any => {
+                tracing::warn!("Streaming LLM call failed, falling back to non-streaming: {e}");
+                return Ok(None);
+            }
+            Err(e) => return Err(anyhow::anyhow!("Streaming LLM call failed: {e}")),
+        };
+        received_any |= !chunk.is_empty();
+        let started_tools = accumulator.push(&chunk);
+
+        if !chunk.reasoning_delta.is_empty() && !reasoning_announced {
+            reasoning_announced = true;
+            let _ = tx
+                .send(format!("{DRAFT_PROGRESS_SENTINEL}\u{1f9e0} Reasoning...\n"))
+                .await;
+        }
+
+        if live_text && !chunk.delta.is_empty() && !tools_announced {
+            if !text_streamed {
+                text_streamed = true;
+                let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
+            }
+            let _ = tx.send(chunk.delta.clone()).await;
+        }
+
+        for name in started_tools {
+            if text_streamed && !tools_announced {
+                // Keep progress lines off the streamed preamble's last line.
+                let _ = tx.send("\n".to_string()).await;
+            }
+            tools_announced = true;
+            let _ = tx
+                .send(format!(
+                    "{DRAFT_PROGRESS_SENTINEL}\u{1f527} Preparing {name}...\n"
+                ))
+                .await;
+        }
+    }
+
+    if !received_any {
+        return Ok(None);
+    }
+    Ok(Some(StreamedTurn {
+        response: accumulator.finish(),
+        text_streamed,
+    }))
+}
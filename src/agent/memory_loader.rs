@@ -1,6 +1,8 @@
+use crate::memory::reranker::MemoryReranker;
 use crate::memory::{self, Memory};
 use async_trait::async_trait;
 use std::fmt::Write;
+use std::sync::Arc;
 
 #[async_trait]
 pub trait MemoryLoader: Send + Sync {
@@ -11,6 +13,7 @@ pub trait MemoryLoader: Send + Sync {
 pub struct DefaultMemoryLoader {
     limit: usize,
     min_relevance_score: f64,
+    reranker: Option<Arc<MemoryReranker>>,
 }
 
 impl Default for DefaultMemoryLoader {
@@ -18,6 +21,7 @@ impl Default for DefaultMemoryLoader {
         Self {
             limit: 5,
             min_relevance_score: 0.4,
+            reranker: None,
         }
     }
 }
@@ -27,8 +31,15 @@ impl DefaultMemoryLoader {
         Self {
             limit: limit.max(1),
             min_relevance_score,
+            reranker: None,
         }
     }
+
+    /// Recall a larger candidate set and let `reranker` pick the top entries.
+    pub fn with_reranker(mut self, reranker: Option<Arc<MemoryReranker>>) -> Self {
+        self.reranker = reranker;
+        self
+    }
 }
 
 #[async_trait]
@@ -38,21 +49,33 @@ impl MemoryLoader for DefaultMemoryLoader {
         memory: &dyn Memory,
         user_message: &str,
     ) -> anyhow::Result<String> {
-        let entries = memory.recall(user_message, self.limit, None).await?;
+        let scope = memory::namespace::current();
+        let fetch = self
+            .reranker
+            .as_ref()
+            .map_or(self.limit, |reranker| reranker.candidate_limit(self.limit));
+        let entries =
+            memory::namespace::recall(memory, scope.as_ref(), user_message, fetch, &[]).await?;
         if entries.is_empty() {
             return Ok(String::new());
         }
 
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .filter(|entry| !memory::is_assistant_autosave_key(&entry.key))
+            .filter(|entry| {
+                entry
+                    .score
+                    .is_none_or(|score| score >= self.min_relevance_score)
+            })
+            .collect();
+        if let Some(reranker) = &self.reranker {
+            entries = reranker.rerank(user_message, entries).await;
+            entries.truncate(self.limit);
+        }
+
         let mut context = String::from("[Memory context]\n");
         for entry in entries {
-            if memory::is_assistant_autosave_key(&entry.key) {
-                continue;
-            }
-            if let Some(score) = entry.score {
-                if score < self.min_relevance_score {
-                    continue;
-                }
-            }
             let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
         }
 
@@ -69,7 +92,7 @@ impl MemoryLoader for DefaultMemoryLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::memory::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
     use std::sync::Arc;
 
     struct MockMemory;
@@ -106,6 +129,8 @@ mod tests {
                 timestamp: "now".into(),
                 session_id: None,
                 score: None,
+                metadata: MemoryMetadata::default(),
+                namespace: crate::memory::DEFAULT_NAMESPACE.into(),
             }])
         }
 
@@ -209,6 +234,8 @@ mod tests {
                     timestamp: "now".into(),
                     session_id: None,
                     score: Some(0.95),
+                    metadata: MemoryMetadata::default(),
+                    namespace: crate::memory::DEFAULT_NAMESPACE.into(),
                 },
                 MemoryEntry {
                     id: "2".into(),
@@ -218,6 +245,8 @@ mod tests {
                     timestamp: "now".into(),
                     session_id: None,
                     score: Some(0.9),
+                    metadata: MemoryMetadata::default(),
+                    namespace: crate::memory::DEFAULT_NAMESPACE.into(),
                 },
             ]),
         };
@@ -227,4 +256,49 @@ mod tests {
         assert!(!context.contains("assistant_resp_legacy"));
         assert!(!context.contains("fabricated detail"));
     }
+
+    struct ReverseReranker;
+
+    #[async_trait]
+    impl crate::memory::reranker::Reranker for ReverseReranker {
+        fn name(&self) -> &str {
+            "reverse"
+        }
+
+        async fn rerank(&self, _query: &str, documents: &[&str]) -> anyhow::Result<Vec<usize>> {
+            Ok((0..documents.len()).rev().collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn default_loader_keeps_top_reranked_entries() {
+        let entry = |id: &str, content: &str| MemoryEntry {
+            id: id.into(),
+            key: id.into(),
+            content: content.into(),
+            category: MemoryCategory::Core,
+            timestamp: "now".into(),
+            session_id: None,
+            score: Some(0.8),
+            metadata: MemoryMetadata::default(),
+            namespace: crate::memory::DEFAULT_NAMESPACE.into(),
+        };
+        let memory = MockMemoryWithEntries {
+            entries: Arc::new(vec![
+                entry("keyword_hit", "mentions the word but is off-topic"),
+                entry("relevant", "actually answers the question"),
+            ]),
+        };
+        let reranker = MemoryReranker::new(
+            Box::new(ReverseReranker),
+            10,
+            16,
+            std::time::Duration::from_secs(1),
+        );
+        let loader = DefaultMemoryLoader::new(1, 0.0).with_reranker(Some(Arc::new(reranker)));
+
+        let context = loader.load_context(&memory, "question").await.unwrap();
+        assert!(context.contains("- relevant:"));
+        assert!(!context.contains("keyword_hit"));
+    }
 }
@@ -3,13 +3,20 @@
 //! Provides a pre-execution hook that prompts the user before tool calls,
 //! with session-scoped "Always" allowlists and audit logging.
 
-use crate::config::{AutonomyConfig, NonCliNaturalLanguageApprovalMode};
-use crate::security::AutonomyLevel;
+mod rules;
+
+pub use rules::ApprovalRule;
+
+use crate::config::{ApprovalRuleAction, AutonomyConfig, NonCliNaturalLanguageApprovalMode};
+use crate::security::{AutonomyLevel, SecurityPolicy};
 use chrono::{Duration, Utc};
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 // ── Types ────────────────────────────────────────────────────────
@@ -19,6 +26,28 @@ use uuid::Uuid;
 pub struct ApprovalRequest {
     pub tool_name: String,
     pub arguments: serde_json::Value,
+    /// Label of the approval rule that asked for this prompt, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
+/// Result of checking one tool call against the approval policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalCheck {
+    pub action: ApprovalRuleAction,
+    /// Label of the approval rule that decided the call; `None` when the
+    /// tool-name policy (`auto_approve`, `always_ask`, session allowlist) did.
+    pub rule: Option<String>,
+}
+
+impl ApprovalCheck {
+    pub fn needs_prompt(&self) -> bool {
+        self.action == ApprovalRuleAction::Ask
+    }
+
+    pub fn is_denied(&self) -> bool {
+        self.action == ApprovalRuleAction::Deny
+    }
 }
 
 /// The user's response to an approval request.
@@ -41,6 +70,9 @@ pub struct ApprovalLogEntry {
     pub arguments_summary: String,
     pub decision: ApprovalResponse,
     pub channel: String,
+    /// Approval rule that decided or prompted the call, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
 }
 
 /// A pending non-CLI approval request that still requires explicit confirmation.
@@ -56,6 +88,86 @@ pub struct PendingNonCliApprovalRequest {
     pub expires_at: String,
 }
 
+/// One sender in one chat: the scope of a non-CLI "Approve once" grant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NonCliRequester {
+    pub sender: String,
+    pub channel: String,
+    pub reply_target: String,
+}
+
+impl NonCliRequester {
+    pub fn new(
+        sender: impl Into<String>,
+        channel: impl Into<String>,
+        reply_target: impl Into<String>,
+    ) -> Self {
+        Self {
+            sender: sender.into(),
+            channel: channel.into(),
+            reply_target: reply_target.into(),
+        }
+    }
+
+    /// The sender and chat that opened `req`.
+    pub fn of_request(req: &PendingNonCliApprovalRequest) -> Self {
+        Self::new(
+            &req.requested_by,
+            &req.requested_channel,
+            &req.requested_reply_target,
+        )
+    }
+}
+
+tokio::task_local! {
+    static NON_CLI_TURN: NonCliTurn;
+}
+
+/// Approval context for one non-CLI message. Channel handlers install it for
+/// the tool loop (`with_non_cli_turn`), so the approval hook can match
+/// one-time grants to the requester and open prompts for blocked calls
+/// without extra plumbing.
+#[derive(Debug, Clone)]
+pub struct NonCliTurn {
+    pub requester: NonCliRequester,
+    /// Whether the channel can render approval buttons for blocked calls.
+    pub interactive: bool,
+    blocked: Arc<Mutex<Vec<PendingNonCliApprovalRequest>>>,
+}
+
+impl NonCliTurn {
+    pub fn new(requester: NonCliRequester, interactive: bool) -> Self {
+        Self {
+            requester,
+            interactive,
+            blocked: Arc::default(),
+        }
+    }
+
+    /// Remember a pending request opened for a blocked call.
+    pub fn push_blocked(&self, req: PendingNonCliApprovalRequest) {
+        let mut blocked = self.blocked.lock();
+        if !blocked.iter().any(|r| r.request_id == req.request_id) {
+            blocked.push(req);
+        }
+    }
+
+    /// Requests opened for calls blocked during the turn, for prompting.
+    pub fn take_blocked(&self) -> Vec<PendingNonCliApprovalRequest> {
+        std::mem::take(&mut *self.blocked.lock())
+    }
+}
+
+/// Run `fut` with `turn` as the current non-CLI approval context.
+pub async fn with_non_cli_turn<F: Future>(turn: NonCliTurn, fut: F) -> F::Output {
+    NON_CLI_TURN.scope(turn, fut).await
+}
+
+/// The non-CLI approval context installed for the running task, if any.
+pub fn current_non_cli_turn() -> Option<NonCliTurn> {
+    NON_CLI_TURN.try_with(Clone::clone).ok()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PendingApprovalError {
     NotFound,
@@ -83,6 +195,9 @@ pub struct ApprovalManager {
     non_cli_allowlist: Mutex<HashSet<String>>,
     /// One-time non-CLI bypass tokens that allow a full tool loop turn without prompts.
     non_cli_allow_all_once_remaining: Mutex<u32>,
+    /// One-time non-CLI grants from "Approve once" decisions, per tool and
+    /// requester.
+    non_cli_once_grants: Mutex<HashMap<(String, NonCliRequester), u32>>,
     /// Optional allowlist of senders allowed to manage non-CLI approvals.
     non_cli_approval_approvers: RwLock<HashSet<String>>,
     /// Default natural-language handling mode for non-CLI approval-management commands.
@@ -94,6 +209,11 @@ pub struct ApprovalManager {
     pending_non_cli_requests: Mutex<HashMap<String, PendingNonCliApprovalRequest>>,
     /// Audit trail of approval decisions.
     audit_log: Mutex<Vec<ApprovalLogEntry>>,
+    /// Argument-aware rules from config, evaluated in order.
+    rules: Vec<ApprovalRule>,
+    /// The configured autonomy policy: classifies command risk for `min_risk`
+    /// rule conditions and supplies the workspace that `paths` resolve against.
+    risk_policy: SecurityPolicy,
 }
 
 impl ApprovalManager {
@@ -130,6 +250,7 @@ impl ApprovalManager {
             session_allowlist: Mutex::new(HashSet::new()),
             non_cli_allowlist: Mutex::new(HashSet::new()),
             non_cli_allow_all_once_remaining: Mutex::new(0),
+            non_cli_once_grants: Mutex::new(HashMap::new()),
             non_cli_approval_approvers: RwLock::new(Self::normalize_non_cli_approvers(
                 &config.non_cli_approval_approvers,
             )),
@@ -143,6 +264,62 @@ impl ApprovalManager {
             ),
             pending_non_cli_requests: Mutex::new(HashMap::new()),
             audit_log: Mutex::new(Vec::new()),
+            rules: config
+                .approval_rules
+                .iter()
+                .filter_map(|rule| match ApprovalRule::compile(rule) {
+                    Ok(rule) => Some(rule),
+                    Err(e) => {
+                        tracing::warn!("Ignoring invalid approval rule for '{}': {e:#}", rule.tool);
+                        None
+                    }
+                })
+                .collect(),
+            risk_policy: SecurityPolicy::from_config(config, Path::new(".")),
+        }
+    }
+
+    /// Resolve approval rule `paths` against `workspace_dir` instead of the
+    /// current directory.
+    #[must_use]
+    pub fn with_workspace_dir(mut self, workspace_dir: &Path) -> Self {
+        self.risk_policy.workspace_dir = workspace_dir.to_path_buf();
+        self
+    }
+
+    /// Check a tool call, including its arguments, against the approval policy.
+    ///
+    /// The first matching `[[autonomy.approval_rules]]` entry decides; `deny`
+    /// applies at every autonomy level while `ask`/`allow` only matter in
+    /// supervised mode. Without a matching rule this falls back to
+    /// [`Self::needs_approval`].
+    pub fn check(&self, tool_name: &str, args: &serde_json::Value) -> ApprovalCheck {
+        let matched = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, args, &self.risk_policy));
+
+        if let Some(rule) = matched {
+            let action = match rule.action() {
+                ApprovalRuleAction::Deny => ApprovalRuleAction::Deny,
+                ApprovalRuleAction::Ask if self.autonomy_level == AutonomyLevel::Supervised => {
+                    ApprovalRuleAction::Ask
+                }
+                _ => ApprovalRuleAction::Allow,
+            };
+            return ApprovalCheck {
+                action,
+                rule: Some(rule.label().to_string()),
+            };
+        }
+
+        ApprovalCheck {
+            action: if self.needs_approval(tool_name) {
+                ApprovalRuleAction::Ask
+            } else {
+                ApprovalRuleAction::Allow
+            },
+            rule: None,
         }
     }
 
@@ -187,6 +364,18 @@ impl ApprovalManager {
         args: &serde_json::Value,
         decision: ApprovalResponse,
         channel: &str,
+    ) {
+        self.record_rule_decision(tool_name, args, decision, channel, None);
+    }
+
+    /// Record an approval decision together with the rule that drove it.
+    pub fn record_rule_decision(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+        decision: ApprovalResponse,
+        channel: &str,
+        rule: Option<&str>,
     ) {
         // If "Always", add to session allowlist.
         if decision == ApprovalResponse::Always {
@@ -202,6 +391,7 @@ impl ApprovalManager {
             arguments_summary: summary,
             decision,
             channel: channel.to_string(),
+            rule: rule.map(ToString::to_string),
         };
         let mut log = self.audit_log.lock();
         log.push(entry);
@@ -240,6 +430,48 @@ impl ApprovalManager {
         self.non_cli_allowlist.lock().clone()
     }
 
+    /// Grant `requester` a single non-CLI execution of a specific tool.
+    ///
+    /// Returns the number of unused one-time grants for that tool and requester.
+    pub fn grant_non_cli_once(&self, tool_name: &str, requester: &NonCliRequester) -> u32 {
+        let mut grants = self.non_cli_once_grants.lock();
+        let remaining = grants
+            .entry((tool_name.to_string(), requester.clone()))
+            .or_insert(0);
+        *remaining = remaining.saturating_add(1);
+        *remaining
+    }
+
+    /// Check for a human grant covering a prompted non-CLI tool call.
+    ///
+    /// Session grants are kept; a one-time grant is consumed and only counts
+    /// for the requester (sender and chat) it was granted to.
+    pub fn take_non_cli_tool_grant(
+        &self,
+        tool_name: &str,
+        requester: Option<&NonCliRequester>,
+    ) -> bool {
+        if self.is_non_cli_session_granted(tool_name) {
+            return true;
+        }
+        let Some(requester) = requester else {
+            return false;
+        };
+        let key = (tool_name.to_string(), requester.clone());
+        let mut grants = self.non_cli_once_grants.lock();
+        match grants.get_mut(&key) {
+            Some(remaining) if *remaining > 1 => {
+                *remaining -= 1;
+                true
+            }
+            Some(_) => {
+                grants.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Grant one non-CLI "allow all tools/commands for one turn" token.
     ///
     /// Returns the remaining token count after increment.
@@ -473,6 +705,43 @@ impl ApprovalManager {
         Ok(req)
     }
 
+    /// Resolve a pending non-CLI approval request from an interactive prompt.
+    ///
+    /// Unlike [`Self::confirm_non_cli_pending_request`], someone other than the
+    /// requester may resolve it when they are a configured approver; the
+    /// decision must still come from the chat where the request was made.
+    pub fn resolve_non_cli_pending_request(
+        &self,
+        request_id: &str,
+        resolved_by: &str,
+        resolved_channel: &str,
+        resolved_reply_target: &str,
+    ) -> Result<PendingNonCliApprovalRequest, PendingApprovalError> {
+        let is_configured_approver = !self.non_cli_approval_approvers.read().is_empty()
+            && self.is_non_cli_approval_actor_allowed(resolved_channel, resolved_by);
+
+        let mut pending = self.pending_non_cli_requests.lock();
+        prune_expired_pending_requests(&mut pending);
+
+        let Some(req) = pending.remove(request_id) else {
+            return Err(PendingApprovalError::NotFound);
+        };
+
+        if is_pending_request_expired(&req) {
+            return Err(PendingApprovalError::Expired);
+        }
+
+        if req.requested_channel != resolved_channel
+            || req.requested_reply_target != resolved_reply_target
+            || (req.requested_by != resolved_by && !is_configured_approver)
+        {
+            pending.insert(req.request_id.clone(), req);
+            return Err(PendingApprovalError::RequesterMismatch);
+        }
+
+        Ok(req)
+    }
+
     /// List active pending non-CLI approval requests.
     pub fn list_non_cli_pending_requests(
         &self,
@@ -524,6 +793,9 @@ fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
     eprintln!();
     eprintln!("🔧 Agent wants to execute: {}", request.tool_name);
     eprintln!("   {summary}");
+    if let Some(rule) = &request.rule {
+        eprintln!("   Rule: {rule}");
+    }
     eprint!("   [Y]es / [N]o / [A]lways for {}: ", request.tool_name);
     let _ = io::stderr().flush();
 
@@ -811,6 +1083,76 @@ mod tests {
         assert_eq!(err, PendingApprovalError::RequesterMismatch);
     }
 
+    #[test]
+    fn resolve_pending_non_cli_approval_allows_configured_approver() {
+        let mgr = ApprovalManager::from_config(&AutonomyConfig {
+            non_cli_approval_approvers: vec!["telegram:admin".to_string()],
+            ..supervised_config()
+        });
+        let req = mgr.create_non_cli_pending_request("shell", "alice", "telegram", "chat-1", None);
+
+        let err = mgr
+            .resolve_non_cli_pending_request(&req.request_id, "bob", "telegram", "chat-1")
+            .expect_err("non-approver should not resolve someone else's request");
+        assert_eq!(err, PendingApprovalError::RequesterMismatch);
+
+        let err = mgr
+            .resolve_non_cli_pending_request(&req.request_id, "admin", "telegram", "chat-2")
+            .expect_err("decision must come from the requesting chat");
+        assert_eq!(err, PendingApprovalError::RequesterMismatch);
+
+        let resolved = mgr
+            .resolve_non_cli_pending_request(&req.request_id, "admin", "telegram", "chat-1")
+            .expect("configured approver should resolve");
+        assert_eq!(resolved.tool_name, "shell");
+    }
+
+    #[test]
+    fn resolve_pending_non_cli_approval_without_approvers_requires_requester() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        let req = mgr.create_non_cli_pending_request("shell", "alice", "slack", "C1", None);
+
+        assert_eq!(
+            mgr.resolve_non_cli_pending_request(&req.request_id, "bob", "slack", "C1"),
+            Err(PendingApprovalError::RequesterMismatch)
+        );
+        assert!(mgr
+            .resolve_non_cli_pending_request(&req.request_id, "alice", "slack", "C1")
+            .is_ok());
+    }
+
+    #[test]
+    fn non_cli_once_grants_are_consumed_and_session_grants_kept() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        let alice = NonCliRequester::new("alice", "telegram", "chat-1");
+        assert!(!mgr.take_non_cli_tool_grant("shell", Some(&alice)));
+
+        assert_eq!(mgr.grant_non_cli_once("shell", &alice), 1);
+        assert!(mgr.take_non_cli_tool_grant("shell", Some(&alice)));
+        assert!(!mgr.take_non_cli_tool_grant("shell", Some(&alice)));
+
+        mgr.grant_non_cli_session("file_write");
+        assert!(mgr.take_non_cli_tool_grant("file_write", Some(&alice)));
+        assert!(mgr.take_non_cli_tool_grant("file_write", None));
+    }
+
+    #[test]
+    fn non_cli_once_grants_are_scoped_to_requester_and_chat() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        let alice = NonCliRequester::new("alice", "telegram", "chat-1");
+        mgr.grant_non_cli_once("shell", &alice);
+
+        for other in [
+            NonCliRequester::new("bob", "telegram", "chat-1"),
+            NonCliRequester::new("alice", "telegram", "chat-2"),
+            NonCliRequester::new("alice", "slack", "chat-1"),
+        ] {
+            assert!(!mgr.take_non_cli_tool_grant("shell", Some(&other)));
+        }
+        assert!(!mgr.take_non_cli_tool_grant("shell", None));
+        assert!(mgr.take_non_cli_tool_grant("shell", Some(&alice)));
+    }
+
     #[test]
     fn list_pending_non_cli_approvals_filters_scope() {
         let mgr = ApprovalManager::from_config(&supervised_config());
@@ -1053,9 +1395,130 @@ mod tests {
         let req = ApprovalRequest {
             tool_name: "shell".into(),
             arguments: serde_json::json!({"command": "echo hi"}),
+            rule: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let parsed: ApprovalRequest = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.tool_name, "shell");
+        assert!(parsed.rule.is_none());
+    }
+
+    // ── approval rules ───────────────────────────────────────
+
+    fn rule(
+        tool: &str,
+        command: Option<&str>,
+        action: ApprovalRuleAction,
+    ) -> crate::config::ApprovalRuleConfig {
+        crate::config::ApprovalRuleConfig {
+            name: None,
+            tool: tool.into(),
+            command: command.map(Into::into),
+            command_regex: None,
+            min_risk: None,
+            paths: vec![],
+            hosts: vec![],
+            action,
+        }
+    }
+
+    #[test]
+    fn approval_rules_decide_by_arguments_in_order() {
+        let mgr = ApprovalManager::from_config(&AutonomyConfig {
+            approval_rules: vec![
+                rule("shell", Some("rm *"), ApprovalRuleAction::Deny),
+                rule("shell", Some("ls*"), ApprovalRuleAction::Allow),
+                rule("file_read", None, ApprovalRuleAction::Ask),
+            ],
+            ..supervised_config()
+        });
+
+        let rm = mgr.check("shell", &serde_json::json!({"command": "rm -rf build"}));
+        assert!(rm.is_denied());
+        assert_eq!(rm.rule.as_deref(), Some("shell command `rm *` → deny"));
+
+        // `shell` is in always_ask, but the allow rule is more specific.
+        let ls = mgr.check("shell", &serde_json::json!({"command": "ls -la"}));
+        assert_eq!(ls.action, ApprovalRuleAction::Allow);
+        assert!(ls.rule.is_some());
+
+        // The allow rule never covers a chained command.
+        let chained = mgr.check("shell", &serde_json::json!({"command": "ls && rm -rf ~"}));
+        assert!(chained.needs_prompt());
+        assert!(chained.rule.is_none());
+
+        // No rule matches: falls back to the tool-name policy.
+        let cat = mgr.check("shell", &serde_json::json!({"command": "cat x"}));
+        assert!(cat.needs_prompt());
+        assert!(cat.rule.is_none());
+
+        // An ask rule overrides auto_approve and the session allowlist.
+        mgr.record_decision(
+            "file_read",
+            &serde_json::json!({}),
+            ApprovalResponse::Always,
+            "cli",
+        );
+        assert!(mgr
+            .check("file_read", &serde_json::json!({"path": "a"}))
+            .needs_prompt());
+    }
+
+    #[test]
+    fn approval_deny_rules_apply_under_full_autonomy() {
+        let mgr = ApprovalManager::from_config(&AutonomyConfig {
+            approval_rules: vec![
+                rule("shell", Some("rm *"), ApprovalRuleAction::Deny),
+                rule("shell", None, ApprovalRuleAction::Ask),
+            ],
+            ..full_config()
+        });
+        assert!(mgr
+            .check("shell", &serde_json::json!({"command": "rm x"}))
+            .is_denied());
+        assert_eq!(
+            mgr.check("shell", &serde_json::json!({"command": "ls"}))
+                .action,
+            ApprovalRuleAction::Allow
+        );
+    }
+
+    #[test]
+    fn approval_path_rules_resolve_against_workspace_dir() {
+        let mgr = ApprovalManager::from_config(&AutonomyConfig {
+            approval_rules: vec![crate::config::ApprovalRuleConfig {
+                paths: vec!["notes".into()],
+                ..rule("file_write", None, ApprovalRuleAction::Allow)
+            }],
+            ..supervised_config()
+        })
+        .with_workspace_dir(Path::new("/srv/ws"));
+
+        let inside = mgr.check(
+            "file_write",
+            &serde_json::json!({"path": "/srv/ws/notes/a.md"}),
+        );
+        assert_eq!(inside.action, ApprovalRuleAction::Allow);
+        assert!(inside.rule.is_some());
+
+        let escaped = mgr.check(
+            "file_write",
+            &serde_json::json!({"path": "notes/../../.ssh/authorized_keys"}),
+        );
+        assert!(escaped.rule.is_none());
+    }
+
+    #[test]
+    fn record_rule_decision_keeps_rule_in_audit_log() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        mgr.record_rule_decision(
+            "shell",
+            &serde_json::json!({"command": "rm x"}),
+            ApprovalResponse::No,
+            "telegram",
+            Some("no rm"),
+        );
+        let log = mgr.audit_log();
+        assert_eq!(log[0].rule.as_deref(), Some("no rm"));
     }
 }
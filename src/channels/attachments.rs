@@ -0,0 +1,632 @@
+use super::traits::{Attachment, AttachmentData, AttachmentKind, ChannelMessage};
+use crate::tools::url_validation::is_private_or_local_host;
+use anyhow::Context;
+use futures_util::StreamExt;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+
+/// Workspace subdirectory where downloaded inbound attachments are saved.
+pub const INBOUND_ATTACHMENT_DIR: &str = "channel_files";
+
+/// Largest inbound attachment a channel should download (20 MB, Telegram's
+/// Bot API ceiling and comfortably above most chat platforms' image sizes).
+pub const MAX_INBOUND_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Redirects followed when downloading an outgoing attachment URL.
+const MAX_OUTGOING_REDIRECTS: usize = 5;
+
+/// Text documents up to this size are inlined into the user turn so the
+/// model can read them without a tool call.
+const INLINE_TEXT_MAX_BYTES: usize = 64 * 1024;
+
+fn find_matching_close(s: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_http_url(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// Build an attachment from a marker or bare path/URL target.
+pub fn attachment_from_target(kind: AttachmentKind, target: &str) -> Attachment {
+    if is_http_url(target) {
+        let name = target
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(target)
+            .rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty() && segment.contains('.'));
+        let attachment = Attachment::from_url(kind, target);
+        match name {
+            Some(name) => attachment.with_filename(name),
+            None => attachment,
+        }
+    } else {
+        Attachment::from_path(kind, target.strip_prefix("file://").unwrap_or(target))
+    }
+}
+
+/// Split `[IMAGE:...]`, `[DOCUMENT:...]`, `[VIDEO:...]`, `[AUDIO:...]` and
+/// `[VOICE:...]` markers out of a reply.
+///
+/// Returns the text with markers removed and the attachments they named.
+/// Brackets that do not form a recognised marker stay in the text.
+pub fn parse_markers(message: &str) -> (String, Vec<Attachment>) {
+    let mut cleaned = String::with_capacity(message.len());
+    let mut attachments = Vec::new();
+    let mut cursor = 0;
+
+    while cursor < message.len() {
+        let Some(open_rel) = message[cursor..].find('[') else {
+            cleaned.push_str(&message[cursor..]);
+            break;
+        };
+
+        let open = cursor + open_rel;
+        cleaned.push_str(&message[cursor..open]);
+
+        let Some(close_rel) = find_matching_close(&message[open + 1..]) else {
+            cleaned.push_str(&message[open..]);
+            break;
+        };
+
+        let close = open + 1 + close_rel;
+        let marker = &message[open + 1..close];
+
+        let parsed = marker.split_once(':').and_then(|(kind, target)| {
+            let kind = AttachmentKind::from_marker(kind)?;
+            let target = target.trim();
+            if target.is_empty() {
+                return None;
+            }
+            Some(attachment_from_target(kind, target))
+        });
+
+        if let Some(attachment) = parsed {
+            attachments.push(attachment);
+        } else {
+            cleaned.push_str(&message[open..=close]);
+        }
+
+        cursor = close + 1;
+    }
+
+    (cleaned.trim().to_string(), attachments)
+}
+
+/// Guess a MIME type from a file name or URL extension.
+pub fn mime_from_name(name: &str) -> &'static str {
+    let normalized = name.split(['?', '#']).next().unwrap_or(name);
+    mime_guess::from_path(normalized)
+        .first_raw()
+        .unwrap_or("application/octet-stream")
+}
+
+/// Reduce an untrusted filename to a safe basename.
+pub fn sanitize_filename(file_name: &str) -> Option<String> {
+    let basename = Path::new(file_name).file_name()?.to_str()?.trim();
+    if basename.is_empty() || basename == "." || basename == ".." {
+        return None;
+    }
+
+    let sanitized: String = basename
+        .replace(['/', '\\'], "_")
+        .chars()
+        .take(128)
+        .collect();
+    if sanitized.is_empty() || sanitized == "." || sanitized == ".." {
+        None
+    } else {
+        Some(sanitized)
+    }
+}
+
+/// Download an attachment, refusing anything over `max_bytes`.
+pub async fn fetch_bytes(
+    request: reqwest::RequestBuilder,
+    max_bytes: u64,
+) -> anyhow::Result<Vec<u8>> {
+    let resp = request.send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        anyhow::bail!("attachment download failed ({status})");
+    }
+    if let Some(len) = resp.content_length() {
+        if len > max_bytes {
+            anyhow::bail!("attachment is {len} bytes, over the {max_bytes} byte limit");
+        }
+    }
+    // Content-Length may be missing or wrong; enforce the cap as chunks arrive.
+    let mut bytes = Vec::new();
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if (bytes.len() + chunk.len()) as u64 > max_bytes {
+            anyhow::bail!("attachment is over the {max_bytes} byte limit");
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Refuse outgoing attachment URLs that are not http(s) or point at a
+/// loopback, private or link-local host, using the same guard as the
+/// `http_request` tool. Checked on the parsed URL so numeric host forms
+/// (`http://2130706433/`) are normalized first.
+fn check_outgoing_url(url: &reqwest::Url) -> anyhow::Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Only http:// and https:// URLs are allowed");
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("URL must include a host"))?;
+    if is_private_or_local_host(host) {
+        anyhow::bail!("Blocked local/private host: {host}");
+    }
+    Ok(())
+}
+
+/// Client for agent-supplied attachment URLs: every redirect hop goes
+/// through [`check_outgoing_url`].
+fn outgoing_client(service_key: &str) -> anyhow::Result<reqwest::Client> {
+    let policy = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_OUTGOING_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if let Err(e) = check_outgoing_url(attempt.url()) {
+            attempt.error(e.to_string())
+        } else {
+            attempt.follow()
+        }
+    });
+    let builder = reqwest::Client::builder()
+        .redirect(policy)
+        .timeout(Duration::from_secs(60))
+        .connect_timeout(Duration::from_secs(10));
+    Ok(crate::config::apply_runtime_proxy_to_builder(builder, service_key).build()?)
+}
+
+/// Resolve an outgoing local attachment path, refusing anything outside the
+/// workspace. `/workspace/...` is accepted as an alias for the workspace root.
+pub fn resolve_workspace_file(workspace: Option<&Path>, target: &str) -> anyhow::Result<PathBuf> {
+    let workspace = workspace.ok_or_else(|| {
+        anyhow::anyhow!("workspace_dir is not configured; local file attachments are disabled")
+    })?;
+    if target.contains('\0') {
+        anyhow::bail!("attachment path contains null byte");
+    }
+    let workspace_root = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+
+    let target_path = if let Some(rel) = target.strip_prefix("/workspace/") {
+        workspace.join(rel)
+    } else if target == "/workspace" {
+        workspace.to_path_buf()
+    } else {
+        let path = Path::new(target);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            workspace.join(path)
+        }
+    };
+
+    let resolved = target_path
+        .canonicalize()
+        .with_context(|| format!("attachment path not found: {target}"))?;
+
+    if !resolved.starts_with(&workspace_root) {
+        anyhow::bail!("attachment path escapes workspace: {target}");
+    }
+
+    if !resolved.is_file() {
+        anyhow::bail!("attachment path is not a file: {}", resolved.display());
+    }
+
+    Ok(resolved)
+}
+
+/// Load an outgoing attachment's bytes for upload.
+///
+/// Local paths must resolve inside the workspace; remote URLs must be public
+/// http(s) hosts (redirects included) and are downloaded with the same size
+/// cap as inbound files, through the proxy configured for `service_key`.
+pub async fn load_outgoing(
+    attachment: &Attachment,
+    workspace: Option<&Path>,
+    service_key: &str,
+) -> anyhow::Result<Vec<u8>> {
+    match &attachment.data {
+        AttachmentData::Bytes(bytes) => Ok(bytes.clone()),
+        AttachmentData::Path(path) => {
+            let target = path.to_string_lossy();
+            let resolved = resolve_workspace_file(workspace, target.trim())?;
+            fs::read(&resolved)
+                .await
+                .with_context(|| format!("failed to read attachment {}", resolved.display()))
+        }
+        AttachmentData::Url(url) => {
+            let url = reqwest::Url::parse(url.trim())
+                .with_context(|| format!("invalid attachment URL '{url}'"))?;
+            check_outgoing_url(&url)?;
+            let client = outgoing_client(service_key)?;
+            fetch_bytes(client.get(url), MAX_INBOUND_ATTACHMENT_BYTES).await
+        }
+    }
+}
+
+/// Write in-memory attachment bytes to `<workspace>/channel_files/<channel>/`
+/// and return the saved path.
+pub async fn save_to_workspace(
+    workspace: &Path,
+    channel: &str,
+    message_id: &str,
+    index: usize,
+    attachment: &Attachment,
+    bytes: &[u8],
+) -> anyhow::Result<PathBuf> {
+    let channel_dir = sanitize_filename(channel).unwrap_or_else(|| "channel".to_string());
+    let stem: String = message_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(64)
+        .collect();
+    let file_name = attachment
+        .filename
+        .as_deref()
+        .and_then(sanitize_filename)
+        .unwrap_or_else(|| attachment.file_name_or_default());
+    let file_name = format!("{stem}_{index}_{file_name}");
+
+    fs::create_dir_all(workspace).await?;
+    let workspace_root = fs::canonicalize(workspace)
+        .await
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    let save_dir = workspace.join(INBOUND_ATTACHMENT_DIR).join(channel_dir);
+    fs::create_dir_all(&save_dir).await?;
+    let resolved_save_dir = fs::canonicalize(&save_dir).await.with_context(|| {
+        format!(
+            "failed to resolve attachment save directory: {}",
+            save_dir.display()
+        )
+    })?;
+    if !resolved_save_dir.starts_with(&workspace_root) {
+        anyhow::bail!(
+            "attachment save directory escapes workspace: {}",
+            save_dir.display()
+        );
+    }
+
+    let output_path = resolved_save_dir.join(file_name);
+    match fs::symlink_metadata(&output_path).await {
+        Ok(meta) if meta.file_type().is_symlink() || !meta.is_file() => {
+            anyhow::bail!(
+                "refusing to overwrite attachment path: {}",
+                output_path.display()
+            );
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    fs::write(&output_path, bytes).await?;
+    Ok(output_path)
+}
+
+fn kind_label(kind: AttachmentKind) -> &'static str {
+    match kind {
+        AttachmentKind::Image => "Image",
+        AttachmentKind::Document => "Document",
+        AttachmentKind::Audio => "Audio",
+        AttachmentKind::Voice => "Voice",
+        AttachmentKind::Video => "Video",
+    }
+}
+
+/// Render one attachment for the agent, given the path or URL it can be
+/// reached at.
+///
+/// Images become `[IMAGE:<reference>]`, which
+/// `multimodal::prepare_messages_for_provider` turns into provider image
+/// blocks; everything else is a `[Kind: name] <reference>` line that file
+/// tools can follow.
+pub fn describe_attachment(attachment: &Attachment, reference: &str) -> String {
+    let mut block = if attachment.kind == AttachmentKind::Image {
+        format!("[IMAGE:{reference}]")
+    } else {
+        let name = attachment.file_name_or_default();
+        let mut header = format!("[{}: {name}", kind_label(attachment.kind));
+        if let Some(mime) = attachment.mime.as_deref() {
+            header.push_str(", ");
+            header.push_str(mime);
+        }
+        if let Some(size) = attachment.size {
+            let _ = write!(header, ", {size} bytes");
+        }
+        format!("{header}] {reference}")
+    };
+
+    if let Some(caption) = attachment.caption.as_deref() {
+        block.push_str("\n\n");
+        block.push_str(caption);
+    }
+    block
+}
+
+/// Build the user turn the agent sees for an inbound message.
+///
+/// Attachments that arrived as bytes are saved into the workspace first so
+/// both the multimodal pipeline and file tools can open them by path; small
+/// text documents are also inlined.
+pub async fn compose_inbound_content(workspace: &Path, msg: &ChannelMessage) -> String {
+    if msg.attachments.is_empty() {
+        return msg.content.clone();
+    }
+
+    let mut parts = Vec::new();
+    if !msg.content.trim().is_empty() {
+        parts.push(msg.content.clone());
+    }
+
+    for (index, attachment) in msg.attachments.iter().enumerate() {
+        let reference = match &attachment.data {
+            AttachmentData::Bytes(bytes) => {
+                match save_to_workspace(workspace, &msg.channel, &msg.id, index, attachment, bytes)
+                    .await
+                {
+                    Ok(path) => path.display().to_string(),
+                    Err(e) => {
+                        tracing::warn!(
+                            channel = %msg.channel,
+                            "failed to save inbound attachment: {e}"
+                        );
+                        continue;
+                    }
+                }
+            }
+            AttachmentData::Path(path) => path.display().to_string(),
+            AttachmentData::Url(url) => url.clone(),
+        };
+
+        let mut block = describe_attachment(attachment, &reference);
+
+        if let AttachmentData::Bytes(bytes) = &attachment.data {
+            let is_text = attachment.mime_or_guess().starts_with("text/");
+            if attachment.kind == AttachmentKind::Document
+                && is_text
+                && bytes.len() <= INLINE_TEXT_MAX_BYTES
+            {
+                if let Ok(text) = std::str::from_utf8(bytes) {
+                    block.push('\n');
+                    block.push_str(text);
+                }
+            }
+        }
+
+        parts.push(block);
+    }
+
+    parts.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_with(attachments: Vec<Attachment>) -> ChannelMessage {
+        ChannelMessage {
+            id: "discord_42".into(),
+            sender: "alice".into(),
+            reply_target: "chan".into(),
+            content: "look at this".into(),
+            channel: "discord".into(),
+            timestamp: 0,
+            thread_ts: None,
+            attachments,
+        }
+    }
+
+    #[test]
+    fn parse_markers_extracts_multiple_types() {
+        let (text, attachments) = parse_markers(
+            "Here you go [IMAGE:/tmp/a.png] and [DOCUMENT:https://example.com/r.pdf] done",
+        );
+        assert_eq!(text, "Here you go  and  done");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(
+            attachments[0].data,
+            AttachmentData::Path(PathBuf::from("/tmp/a.png"))
+        );
+        assert_eq!(attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(
+            attachments[1].data,
+            AttachmentData::Url("https://example.com/r.pdf".into())
+        );
+        assert_eq!(attachments[1].filename.as_deref(), Some("r.pdf"));
+    }
+
+    #[test]
+    fn parse_markers_keeps_unknown_and_unclosed_brackets() {
+        let (text, attachments) = parse_markers("[NOTE:x] and [IMAGE:] then [oops");
+        assert!(attachments.is_empty());
+        assert_eq!(text, "[NOTE:x] and [IMAGE:] then [oops");
+    }
+
+    #[test]
+    fn parse_markers_handles_brackets_in_filename() {
+        let (_, attachments) = parse_markers("[DOCUMENT:/tmp/report [v2].pdf]");
+        assert_eq!(
+            attachments[0].target().as_deref(),
+            Some("/tmp/report [v2].pdf")
+        );
+    }
+
+    #[test]
+    fn send_message_split_merges_markers_and_explicit_attachments() {
+        let message =
+            super::super::SendMessage::new("hi [VOICE:/tmp/n.ogg]", "bob").with_attachments(vec![
+                Attachment::from_bytes(AttachmentKind::Document, b"x".to_vec())
+                    .with_filename("x.txt"),
+            ]);
+        let (text, attachments) = message.split_attachments();
+        assert_eq!(text, "hi");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].kind, AttachmentKind::Voice);
+        assert_eq!(attachments[1].mime_or_guess(), "text/plain");
+    }
+
+    #[tokio::test]
+    async fn compose_saves_bytes_and_references_images_and_documents() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let msg = message_with(vec![
+            Attachment::from_bytes(AttachmentKind::Image, vec![0x89, b'P', b'N', b'G'])
+                .with_filename("cat.png")
+                .with_mime("image/png"),
+            Attachment::from_bytes(AttachmentKind::Document, b"line one".to_vec())
+                .with_filename("notes.txt")
+                .with_mime("text/plain"),
+            Attachment::from_url(AttachmentKind::Video, "https://cdn.example/v.mp4")
+                .with_caption("clip"),
+        ]);
+
+        let content = compose_inbound_content(tmp.path(), &msg).await;
+        let saved_dir = tmp.path().join(INBOUND_ATTACHMENT_DIR).join("discord");
+        let image_path = fs::canonicalize(saved_dir.join("discord_42_0_cat.png"))
+            .await
+            .unwrap();
+        assert!(content.starts_with("look at this\n\n"));
+        assert!(content.contains(&format!("[IMAGE:{}]", image_path.display())));
+        assert!(content.contains("[Document: notes.txt, text/plain, 8 bytes]"));
+        assert!(content.contains("\nline one"));
+        assert!(content.contains("[Video: video.bin] https://cdn.example/v.mp4\n\nclip"));
+        assert_eq!(
+            crate::multimodal::count_image_markers(&[crate::providers::ChatMessage::user(content)]),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn compose_without_attachments_returns_content_unchanged() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let msg = message_with(Vec::new());
+        assert_eq!(
+            compose_inbound_content(tmp.path(), &msg).await,
+            "look at this"
+        );
+    }
+
+    #[tokio::test]
+    async fn load_outgoing_confines_paths_to_workspace() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("chart.png"), b"png").unwrap();
+        std::fs::write(outside.path().join("secret.txt"), b"nope").unwrap();
+
+        let inside = Attachment::from_path(AttachmentKind::Image, "/workspace/chart.png");
+        assert_eq!(
+            load_outgoing(&inside, Some(workspace.path()), "channel.slack")
+                .await
+                .unwrap(),
+            b"png"
+        );
+
+        let escaping =
+            Attachment::from_path(AttachmentKind::Document, outside.path().join("secret.txt"));
+        assert!(
+            load_outgoing(&escaping, Some(workspace.path()), "channel.slack")
+                .await
+                .is_err()
+        );
+        assert!(load_outgoing(&inside, None, "channel.slack").await.is_err());
+    }
+
+    /// Serve one canned HTTP response on a local port and return its URL.
+    async fn serve_once(response: &'static [u8]) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response).await;
+        });
+        format!("http://{addr}/file")
+    }
+
+    #[tokio::test]
+    async fn load_outgoing_refuses_private_hosts() {
+        for url in [
+            "http://127.0.0.1/x.png",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://2130706433/x.png",
+            "http://[::1]/x.png",
+            "http://localhost:8080/x.png",
+            "file:///etc/passwd",
+        ] {
+            let attachment = Attachment::from_url(AttachmentKind::Image, url);
+            let err = load_outgoing(&attachment, None, "channel.slack")
+                .await
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("Blocked local/private host")
+                    || err.to_string().contains("Only http"),
+                "{url}: {err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn outgoing_redirects_to_private_hosts_are_refused() {
+        let url = serve_once(
+            b"HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\n\
+              Content-Length: 0\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+        let client = outgoing_client("channel.slack").unwrap();
+        let err = fetch_bytes(client.get(url), 1024).await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Blocked local/private host: 169.254.169.254"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_bytes_caps_bodies_without_content_length() {
+        let url = serve_once(
+            b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n\
+              0123456789012345678901234567890123456789",
+        )
+        .await;
+        let err = fetch_bytes(reqwest::Client::new().get(url), 16)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("over the 16 byte limit"), "{err}");
+    }
+
+    #[test]
+    fn sanitize_filename_strips_path_traversal() {
+        assert_eq!(
+            sanitize_filename("../../etc/passwd").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(sanitize_filename(".."), None);
+    }
+}
@@ -1,12 +1,15 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
-use anyhow::Context;
+use super::attachments::{fetch_bytes, parse_markers, MAX_INBOUND_ATTACHMENT_BYTES};
+use super::traits::{
+    Attachment, AttachmentData, AttachmentKind, Channel, ChannelMessage, InteractivePrompt,
+    PromptActionStyle, SendMessage,
+};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::Mutex;
 use reqwest::multipart::{Form, Part};
 use serde_json::json;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
@@ -82,42 +85,110 @@ impl DiscordChannel {
     }
 
     fn resolve_local_attachment_path(&self, target: &str) -> anyhow::Result<PathBuf> {
-        let workspace = self.workspace_dir.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("workspace_dir is not configured; local file attachments are disabled")
-        })?;
-        let workspace_root = workspace
-            .canonicalize()
-            .unwrap_or_else(|_| workspace.to_path_buf());
-
-        let target_path = if let Some(rel) = target.strip_prefix("/workspace/") {
-            workspace.join(rel)
-        } else if target == "/workspace" {
-            workspace.to_path_buf()
-        } else {
-            let path = Path::new(target);
-            if path.is_absolute() {
-                path.to_path_buf()
-            } else {
-                workspace.join(path)
-            }
-        };
-
-        let resolved = target_path
-            .canonicalize()
-            .with_context(|| format!("attachment path not found: {target}"))?;
+        super::attachments::resolve_workspace_file(self.workspace_dir.as_deref(), target)
+    }
 
-        if !resolved.starts_with(&workspace_root) {
-            anyhow::bail!("attachment path escapes workspace: {target}");
+    /// Parse a message-component `INTERACTION_CREATE` payload into
+    /// `(interaction_id, interaction_token, message)`.
+    /// The message is `None` when the clicking user is not allowlisted.
+    fn parse_component_interaction(
+        &self,
+        d: &serde_json::Value,
+    ) -> Option<(String, String, Option<ChannelMessage>)> {
+        // Type 3 = MESSAGE_COMPONENT
+        if d.get("type").and_then(serde_json::Value::as_u64) != Some(3) {
+            return None;
+        }
+        let interaction_id = d.get("id").and_then(|i| i.as_str())?.to_string();
+        let token = d.get("token").and_then(|t| t.as_str())?.to_string();
+
+        // Guild interactions carry `member.user`, DMs carry `user`.
+        let user_id = d
+            .get("member")
+            .and_then(|m| m.get("user"))
+            .or_else(|| d.get("user"))
+            .and_then(|u| u.get("id"))
+            .and_then(|i| i.as_str())
+            .unwrap_or("");
+        if !self.is_user_allowed(user_id) {
+            tracing::warn!("Discord: ignoring button press from unauthorized user: {user_id}");
+            return Some((interaction_id, token, None));
         }
 
-        if !resolved.is_file() {
-            anyhow::bail!("attachment path is not a file: {}", resolved.display());
+        if let (Some(ref gid), Some(g)) = (
+            &self.guild_id,
+            d.get("guild_id").and_then(serde_json::Value::as_str),
+        ) {
+            if g != gid {
+                return Some((interaction_id, token, None));
+            }
         }
 
-        Ok(resolved)
+        let custom_id = d
+            .get("data")
+            .and_then(|data| data.get("custom_id"))
+            .and_then(|c| c.as_str())?;
+        let channel_id = d.get("channel_id").and_then(|c| c.as_str()).unwrap_or("");
+
+        let channel_msg = ChannelMessage {
+            id: format!("discord_interaction_{interaction_id}"),
+            sender: user_id.to_string(),
+            reply_target: if channel_id.is_empty() {
+                user_id.to_string()
+            } else {
+                channel_id.to_string()
+            },
+            content: custom_id.to_string(),
+            channel: "discord".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            attachments: Vec::new(),
+        };
+        Some((interaction_id, token, Some(channel_msg)))
+    }
+
+    /// Acknowledge a component interaction without editing the original message
+    /// (`DEFERRED_UPDATE_MESSAGE`), so Discord does not show "interaction failed".
+    async fn ack_component_interaction(&self, interaction_id: &str, token: &str) {
+        let url =
+            format!("https://discord.com/api/v10/interactions/{interaction_id}/{token}/callback");
+        if let Err(e) = self
+            .http_client()
+            .post(&url)
+            .json(&json!({ "type": 6 }))
+            .send()
+            .await
+        {
+            tracing::debug!("Discord: failed to acknowledge interaction {interaction_id}: {e}");
+        }
     }
 }
 
+/// Build a single action row of buttons for an [`InteractivePrompt`].
+fn prompt_components(prompt: &InteractivePrompt) -> serde_json::Value {
+    let buttons: Vec<serde_json::Value> = prompt
+        .actions
+        .iter()
+        .map(|action| {
+            let style = match action.style {
+                PromptActionStyle::Primary => 1,
+                PromptActionStyle::Secondary => 2,
+                PromptActionStyle::Danger => 4,
+            };
+            json!({
+                "type": 2,
+                "style": style,
+                "label": action.label,
+                "custom_id": action.value,
+            })
+        })
+        .collect();
+    json!([{ "type": 1, "components": buttons }])
+}
+
 fn normalize_group_reply_allowed_sender_ids(sender_ids: Vec<String>) -> Vec<String> {
     let mut normalized = sender_ids
         .into_iter()
@@ -129,16 +200,16 @@ fn normalize_group_reply_allowed_sender_ids(sender_ids: Vec<String>) -> Vec<Stri
     normalized
 }
 
-/// Process Discord message attachments and return a string to append to the
-/// agent message context.
+/// Convert Discord message attachments into typed attachments.
 ///
-/// Only `text/*` MIME types are fetched and inlined. All other types are
-/// silently skipped. Fetch errors are logged as warnings.
+/// Files are downloaded right away because Discord's CDN URLs are signed and
+/// expire. Anything over [`MAX_INBOUND_ATTACHMENT_BYTES`] or failing to
+/// download is skipped with a warning.
 async fn process_attachments(
     attachments: &[serde_json::Value],
     client: &reqwest::Client,
-) -> String {
-    let mut parts: Vec<String> = Vec::new();
+) -> Vec<Attachment> {
+    let mut collected = Vec::new();
     for att in attachments {
         let ct = att
             .get("content_type")
@@ -152,125 +223,43 @@ async fn process_attachments(
             tracing::warn!(name, "discord: attachment has no url, skipping");
             continue;
         };
-        if ct.starts_with("text/") {
-            match client.get(url).send().await {
-                Ok(resp) if resp.status().is_success() => {
-                    if let Ok(text) = resp.text().await {
-                        parts.push(format!("[{name}]\n{text}"));
-                    }
-                }
-                Ok(resp) => {
-                    tracing::warn!(name, status = %resp.status(), "discord attachment fetch failed");
-                }
-                Err(e) => {
-                    tracing::warn!(name, error = %e, "discord attachment fetch error");
-                }
-            }
-        } else {
-            tracing::debug!(
-                name,
-                content_type = ct,
-                "discord: skipping unsupported attachment type"
-            );
-        }
-    }
-    parts.join("\n---\n")
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum DiscordAttachmentKind {
-    Image,
-    Document,
-    Video,
-    Audio,
-    Voice,
-}
-
-impl DiscordAttachmentKind {
-    fn from_marker(kind: &str) -> Option<Self> {
-        match kind.trim().to_ascii_uppercase().as_str() {
-            "IMAGE" | "PHOTO" => Some(Self::Image),
-            "DOCUMENT" | "FILE" => Some(Self::Document),
-            "VIDEO" => Some(Self::Video),
-            "AUDIO" => Some(Self::Audio),
-            "VOICE" => Some(Self::Voice),
-            _ => None,
-        }
-    }
-
-    fn marker_name(&self) -> &'static str {
-        match self {
-            Self::Image => "IMAGE",
-            Self::Document => "DOCUMENT",
-            Self::Video => "VIDEO",
-            Self::Audio => "AUDIO",
-            Self::Voice => "VOICE",
+        let size = att.get("size").and_then(serde_json::Value::as_u64);
+        if size.is_some_and(|size| size > MAX_INBOUND_ATTACHMENT_BYTES) {
+            tracing::warn!(name, size, "discord: attachment too large, skipping");
+            continue;
         }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct DiscordAttachment {
-    kind: DiscordAttachmentKind,
-    target: String,
-}
-
-fn parse_attachment_markers(message: &str) -> (String, Vec<DiscordAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0usize;
 
-    while let Some(rel_start) = message[cursor..].find('[') {
-        let start = cursor + rel_start;
-        cleaned.push_str(&message[cursor..start]);
-
-        let Some(rel_end) = message[start..].find(']') else {
-            cleaned.push_str(&message[start..]);
-            cursor = message.len();
-            break;
+        let mime = if ct.is_empty() {
+            super::attachments::mime_from_name(name)
+        } else {
+            ct
         };
-        let end = start + rel_end;
-        let marker_text = &message[start + 1..end];
-
-        let parsed = marker_text.split_once(':').and_then(|(kind, target)| {
-            let kind = DiscordAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
-                return None;
+        match fetch_bytes(client.get(url), MAX_INBOUND_ATTACHMENT_BYTES).await {
+            Ok(bytes) => collected.push(
+                Attachment::from_bytes(AttachmentKind::from_mime(mime), bytes)
+                    .with_filename(name)
+                    .with_mime(mime),
+            ),
+            Err(e) => {
+                tracing::warn!(name, error = %e, "discord attachment fetch failed");
             }
-            Some(DiscordAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
-
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            cleaned.push_str(&message[start..=end]);
         }
-
-        cursor = end + 1;
     }
-
-    if cursor < message.len() {
-        cleaned.push_str(&message[cursor..]);
-    }
-
-    (cleaned.trim().to_string(), attachments)
+    collected
 }
 
+/// Split outgoing attachments into uploadable files (local paths and
+/// in-memory bytes), remote URLs (inlined as links) and unresolved markers.
 fn classify_outgoing_attachments(
-    attachments: &[DiscordAttachment],
-) -> (Vec<DiscordAttachment>, Vec<String>, Vec<String>) {
+    attachments: &[Attachment],
+) -> (Vec<Attachment>, Vec<String>, Vec<String>) {
     let mut local_files = Vec::new();
     let mut remote_urls = Vec::new();
     let unresolved_markers = Vec::new();
 
     for attachment in attachments {
-        let target = attachment.target.trim();
-        if target.starts_with("https://") || target.starts_with("http://") {
-            remote_urls.push(target.to_string());
+        if let AttachmentData::Url(url) = &attachment.data {
+            remote_urls.push(url.trim().to_string());
             continue;
         }
 
@@ -332,27 +321,16 @@ async fn send_discord_message_with_files(
     bot_token: &str,
     recipient: &str,
     content: &str,
-    files: &[PathBuf],
+    files: &[(String, Vec<u8>)],
 ) -> anyhow::Result<()> {
     let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
 
     let mut form = Form::new().text("payload_json", json!({ "content": content }).to_string());
 
-    for (idx, path) in files.iter().enumerate() {
-        let bytes = tokio::fs::read(path).await.map_err(|error| {
-            anyhow::anyhow!(
-                "Discord attachment read failed for '{}': {error}",
-                path.display()
-            )
-        })?;
-        let filename = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("attachment.bin")
-            .to_string();
+    for (idx, (filename, bytes)) in files.iter().enumerate() {
         form = form.part(
             format!("files[{idx}]"),
-            Part::bytes(bytes).file_name(filename),
+            Part::bytes(bytes.clone()).file_name(filename.clone()),
         );
     }
 
@@ -559,15 +537,38 @@ impl Channel for DiscordChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         let raw_content = super::strip_tool_call_tags(&message.content);
-        let (cleaned_content, parsed_attachments) = parse_attachment_markers(&raw_content);
+        let (cleaned_content, mut parsed_attachments) = parse_markers(&raw_content);
+        parsed_attachments.extend(message.attachments.iter().cloned());
         let (local_attachment_targets, remote_urls, mut unresolved_markers) =
             classify_outgoing_attachments(&parsed_attachments);
         let mut local_files = Vec::new();
 
         for attachment in &local_attachment_targets {
-            let target = attachment.target.trim();
+            let path = match &attachment.data {
+                AttachmentData::Path(path) => path,
+                AttachmentData::Bytes(bytes) => {
+                    local_files.push((attachment.file_name_or_default(), bytes.clone()));
+                    continue;
+                }
+                AttachmentData::Url(_) => continue,
+            };
+            let target = path.to_string_lossy();
+            let target = target.trim();
             match self.resolve_local_attachment_path(target) {
-                Ok(path) => local_files.push(path),
+                Ok(path) => {
+                    let bytes = tokio::fs::read(&path).await.map_err(|error| {
+                        anyhow::anyhow!(
+                            "Discord attachment read failed for '{}': {error}",
+                            path.display()
+                        )
+                    })?;
+                    let filename = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or("attachment.bin")
+                        .to_string();
+                    local_files.push((filename, bytes));
+                }
                 Err(error) => {
                     tracing::warn!(
                         target,
@@ -627,6 +628,50 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    fn supports_interactive_prompts(&self) -> bool {
+        true
+    }
+
+    async fn send_interactive_prompt(&self, prompt: &InteractivePrompt) -> anyhow::Result<()> {
+        if prompt.actions.len() > 5 {
+            anyhow::bail!("Discord action rows hold at most 5 buttons");
+        }
+        if let Some(action) = prompt.actions.iter().find(|a| a.value.len() > 100) {
+            anyhow::bail!(
+                "Discord custom_id for '{}' exceeds 100 characters",
+                action.label
+            );
+        }
+
+        let url = format!(
+            "https://discord.com/api/v10/channels/{}/messages",
+            prompt.recipient
+        );
+        let body = json!({
+            "content": super::strip_tool_call_tags(&prompt.text),
+            "components": prompt_components(prompt),
+        });
+        let resp = self
+            .http_client()
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&err);
+            anyhow::bail!("Discord send prompt failed ({status}): {sanitized}");
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = Self::bot_user_id_from_token(&self.bot_token).unwrap_or_default();
@@ -751,8 +796,26 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
+
+                    // Button presses on interactive prompts
+                    if event_type == "INTERACTION_CREATE" {
+                        let Some((interaction_id, token, pressed)) = event
+                            .get("d")
+                            .and_then(|d| self.parse_component_interaction(d))
+                        else {
+                            continue;
+                        };
+                        self.ack_component_interaction(&interaction_id, &token).await;
+                        if let Some(channel_msg) = pressed {
+                            if tx.send(channel_msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Otherwise only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     if event_type != "MESSAGE_CREATE" {
                         continue;
                     }
@@ -795,13 +858,21 @@ impl Channel for DiscordChannel {
                         is_group_message && self.is_group_sender_trigger_enabled(author_id);
                     let require_mention =
                         self.mention_only && is_group_message && !allow_sender_without_mention;
-                    let Some(clean_content) =
-                        normalize_incoming_content(content, require_mention, &bot_user_id)
-                    else {
-                        continue;
-                    };
+                    let has_attachments = d
+                        .get("attachments")
+                        .and_then(|a| a.as_array())
+                        .is_some_and(|a| !a.is_empty());
+                    let clean_content =
+                        match normalize_incoming_content(content, require_mention, &bot_user_id) {
+                            Some(clean) => clean,
+                            // Attachment-only messages carry no text to normalize.
+                            None if has_attachments && !require_mention && content.trim().is_empty() => {
+                                String::new()
+                            }
+                            None => continue,
+                        };
 
-                    let attachment_text = {
+                    let attachments = {
                         let atts = d
                             .get("attachments")
                             .and_then(|a| a.as_array())
@@ -809,11 +880,6 @@ impl Channel for DiscordChannel {
                             .unwrap_or_default();
                         process_attachments(&atts, &self.http_client()).await
                     };
-                    let final_content = if attachment_text.is_empty() {
-                        clean_content
-                    } else {
-                        format!("{clean_content}\n\n[Attachments]\n{attachment_text}")
-                    };
 
                     let message_id = d.get("id").and_then(|i| i.as_str()).unwrap_or("");
                     let channel_id = d
@@ -861,13 +927,14 @@ impl Channel for DiscordChannel {
                         } else {
                             channel_id.clone()
                         },
-                        content: final_content,
+                        content: clean_content,
                         channel: "discord".to_string(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -1113,6 +1180,61 @@ mod tests {
         assert!(cleaned.is_none());
     }
 
+    #[test]
+    fn component_interaction_maps_custom_id_to_message() {
+        let ch = DiscordChannel::new("token".into(), None, vec!["111".into()], false, false);
+        let d = json!({
+            "id": "900",
+            "type": 3,
+            "token": "itoken",
+            "channel_id": "C1",
+            "guild_id": "G1",
+            "member": { "user": { "id": "111" } },
+            "data": { "custom_id": "/approve-decide apr-1234abcd session", "component_type": 2 }
+        });
+
+        let (id, token, msg) = ch.parse_component_interaction(&d).unwrap();
+        let msg = msg.expect("allowlisted user press should produce a message");
+        assert_eq!(id, "900");
+        assert_eq!(token, "itoken");
+        assert_eq!(msg.sender, "111");
+        assert_eq!(msg.reply_target, "C1");
+        assert_eq!(msg.content, "/approve-decide apr-1234abcd session");
+    }
+
+    #[test]
+    fn component_interaction_from_unlisted_user_is_acked_but_dropped() {
+        let ch = DiscordChannel::new("token".into(), None, vec!["111".into()], false, false);
+        let d = json!({
+            "id": "901",
+            "type": 3,
+            "token": "itoken",
+            "channel_id": "C1",
+            "user": { "id": "999" },
+            "data": { "custom_id": "/approve-decide apr-1234abcd once" }
+        });
+
+        let (_, _, msg) = ch.parse_component_interaction(&d).unwrap();
+        assert!(msg.is_none());
+
+        let slash_command = json!({ "id": "902", "type": 2, "token": "t" });
+        assert!(ch.parse_component_interaction(&slash_command).is_none());
+    }
+
+    #[test]
+    fn prompt_components_map_styles_to_discord_buttons() {
+        use crate::channels::traits::PromptAction;
+
+        let prompt = InteractivePrompt::new("Approve?", "C1")
+            .with_action(PromptAction::new("Yes", "/yes", PromptActionStyle::Primary))
+            .with_action(PromptAction::new("No", "/no", PromptActionStyle::Danger));
+        let components = prompt_components(&prompt);
+        assert_eq!(components[0]["type"], 1);
+        assert_eq!(components[0]["components"][0]["style"], 1);
+        assert_eq!(components[0]["components"][1]["style"], 4);
+        assert_eq!(components[0]["components"][1]["custom_id"], "/no");
+    }
+
     #[test]
     fn normalize_group_reply_allowed_sender_ids_trims_and_deduplicates() {
         let normalized = normalize_group_reply_allowed_sender_ids(vec![
@@ -1547,13 +1669,20 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn process_attachments_skips_unsupported_types() {
+    async fn process_attachments_skips_missing_url_and_oversized_files() {
         let client = reqwest::Client::new();
-        let attachments = vec![serde_json::json!({
-            "url": "https://cdn.discordapp.com/attachments/123/456/doc.pdf",
-            "filename": "doc.pdf",
-            "content_type": "application/pdf"
-        })];
+        let attachments = vec![
+            serde_json::json!({
+                "filename": "doc.pdf",
+                "content_type": "application/pdf"
+            }),
+            serde_json::json!({
+                "url": "https://cdn.discordapp.com/attachments/123/456/big.mp4",
+                "filename": "big.mp4",
+                "content_type": "video/mp4",
+                "size": MAX_INBOUND_ATTACHMENT_BYTES + 1
+            }),
+        ];
         let result = process_attachments(&attachments, &client).await;
         assert!(result.is_empty());
     }
@@ -1561,20 +1690,23 @@ mod tests {
     #[test]
     fn parse_attachment_markers_extracts_supported_markers() {
         let input = "Report\n[IMAGE:https://example.com/a.png]\n[DOCUMENT:/tmp/a.pdf]";
-        let (cleaned, attachments) = parse_attachment_markers(input);
+        let (cleaned, attachments) = parse_markers(input);
 
         assert_eq!(cleaned, "Report");
         assert_eq!(attachments.len(), 2);
-        assert_eq!(attachments[0].kind, DiscordAttachmentKind::Image);
-        assert_eq!(attachments[0].target, "https://example.com/a.png");
-        assert_eq!(attachments[1].kind, DiscordAttachmentKind::Document);
-        assert_eq!(attachments[1].target, "/tmp/a.pdf");
+        assert_eq!(attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(
+            attachments[0].target().as_deref(),
+            Some("https://example.com/a.png")
+        );
+        assert_eq!(attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(attachments[1].target().as_deref(), Some("/tmp/a.pdf"));
     }
 
     #[test]
     fn parse_attachment_markers_keeps_invalid_marker_text() {
         let input = "Hello [NOT_A_MARKER:foo] world";
-        let (cleaned, attachments) = parse_attachment_markers(input);
+        let (cleaned, attachments) = parse_markers(input);
 
         assert_eq!(cleaned, input);
         assert!(attachments.is_empty());
@@ -1587,24 +1719,23 @@ mod tests {
         std::fs::write(&file_path, b"fake").expect("write fixture");
 
         let attachments = vec![
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Image,
-                target: file_path.to_string_lossy().to_string(),
-            },
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Image,
-                target: "https://example.com/remote.png".to_string(),
-            },
-            DiscordAttachment {
-                kind: DiscordAttachmentKind::Video,
-                target: "/tmp/does-not-exist.mp4".to_string(),
-            },
+            Attachment::from_path(AttachmentKind::Image, &file_path),
+            Attachment::from_url(AttachmentKind::Image, "https://example.com/remote.png"),
+            Attachment::from_path(AttachmentKind::Video, "/tmp/does-not-exist.mp4"),
+            Attachment::from_bytes(AttachmentKind::Document, b"data".to_vec()),
         ];
 
         let (locals, remotes, unresolved) = classify_outgoing_attachments(&attachments);
-        assert_eq!(locals.len(), 2);
-        assert_eq!(locals[0].target, file_path.to_string_lossy());
-        assert_eq!(locals[1].target, "/tmp/does-not-exist.mp4");
+        assert_eq!(locals.len(), 3);
+        assert_eq!(
+            locals[0].target().as_deref(),
+            Some(file_path.to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            locals[1].target().as_deref(),
+            Some("/tmp/does-not-exist.mp4")
+        );
+        assert_eq!(locals[2].data, AttachmentData::Bytes(b"data".to_vec()));
         assert_eq!(remotes, vec!["https://example.com/remote.png".to_string()]);
         assert!(unresolved.is_empty());
     }
@@ -1628,7 +1759,7 @@ mod tests {
             .with_workspace_dir(PathBuf::from("/tmp/discord-workspace"));
         assert_eq!(
             channel.workspace_dir.as_deref(),
-            Some(Path::new("/tmp/discord-workspace"))
+            Some(std::path::Path::new("/tmp/discord-workspace"))
         );
     }
 
@@ -14,7 +14,8 @@ use async_imap::types::Fetch;
 use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
-use lettre::message::SinglePart;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as MailAttachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
@@ -23,6 +24,8 @@ use rustls_pki_types::DnsName;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
@@ -33,7 +36,10 @@ use tokio_rustls::TlsConnector;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachments::{
+    load_outgoing, mime_from_name, parse_markers, MAX_INBOUND_ATTACHMENT_BYTES,
+};
+use super::traits::{Attachment, AttachmentKind, Channel, ChannelMessage, SendMessage};
 
 /// Email channel configuration
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -118,6 +124,7 @@ type ImapSession = Session<TlsStream<TcpStream>>;
 pub struct EmailChannel {
     pub config: EmailConfig,
     seen_messages: Arc<Mutex<HashSet<String>>>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl EmailChannel {
@@ -125,9 +132,16 @@ impl EmailChannel {
         Self {
             config,
             seen_messages: Arc::new(Mutex::new(HashSet::new())),
+            workspace_dir: None,
         }
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     /// Check if a sender email is in the allowlist
     pub fn is_sender_allowed(&self, email: &str) -> bool {
         if self.config.allowed_senders.is_empty() {
@@ -191,18 +205,44 @@ impl EmailChannel {
         if let Some(html) = parsed.body_html(0) {
             return Self::strip_html(html.as_ref());
         }
+        if parsed.attachments().next().is_some() {
+            // Attachment-only mail; the files travel as typed attachments.
+            return String::new();
+        }
+        "(no readable content)".to_string()
+    }
+
+    /// Collect MIME attachments from a parsed email, skipping oversized parts
+    fn extract_attachments(parsed: &mail_parser::Message) -> Vec<Attachment> {
+        let mut attachments = Vec::new();
         for part in parsed.attachments() {
             let part: &mail_parser::MessagePart = part;
-            if let Some(ct) = MimeHeaders::content_type(part) {
-                if ct.ctype() == "text" {
-                    if let Ok(text) = std::str::from_utf8(part.contents()) {
-                        let name = MimeHeaders::attachment_name(part).unwrap_or("file");
-                        return format!("[Attachment: {}]\n{}", name, text);
-                    }
-                }
+            let bytes = part.contents();
+            if bytes.len() as u64 > MAX_INBOUND_ATTACHMENT_BYTES {
+                warn!(
+                    "Skipping oversized email attachment ({} bytes)",
+                    bytes.len()
+                );
+                continue;
+            }
+            let name = MimeHeaders::attachment_name(part).map(str::to_string);
+            let mime = MimeHeaders::content_type(part)
+                .map(|ct| match ct.subtype() {
+                    Some(sub) => format!("{}/{}", ct.ctype(), sub),
+                    None => ct.ctype().to_string(),
+                })
+                .or_else(|| name.as_deref().map(|n| mime_from_name(n).to_string()))
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+
+            let mut attachment =
+                Attachment::from_bytes(AttachmentKind::from_mime(&mime), bytes.to_vec())
+                    .with_mime(mime);
+            if let Some(name) = name {
+                attachment = attachment.with_filename(name);
             }
+            attachments.push(attachment);
         }
-        "(no readable content)".to_string()
+        attachments
     }
 
     /// Connect to IMAP server with TLS and authenticate
@@ -265,7 +305,10 @@ impl EmailChannel {
                     let sender = Self::extract_sender(&parsed);
                     let subject = parsed.subject().unwrap_or("(no subject)").to_string();
                     let body_text = Self::extract_text(&parsed);
-                    let content = format!("Subject: {}\n\n{}", subject, body_text);
+                    let content = format!("Subject: {}\n\n{}", subject, body_text)
+                        .trim_end()
+                        .to_string();
+                    let attachments = Self::extract_attachments(&parsed);
                     let msg_id = parsed
                         .message_id()
                         .map(|s| s.to_string())
@@ -302,6 +345,7 @@ impl EmailChannel {
                         sender,
                         content,
                         timestamp: ts,
+                        attachments,
                     });
                 }
             }
@@ -460,6 +504,7 @@ impl EmailChannel {
                 channel: "email".to_string(),
                 timestamp: email.timestamp,
                 thread_ts: None,
+                attachments: email.attachments,
             };
 
             if tx.send(msg).await.is_err() {
@@ -495,6 +540,7 @@ struct ParsedEmail {
     sender: String,
     content: String,
     timestamp: u64,
+    attachments: Vec<Attachment>,
 }
 
 /// Result from waiting on IDLE
@@ -524,11 +570,38 @@ impl Channel for EmailChannel {
             ("ZeroClaw Message", message.content.as_str())
         };
 
-        let email = Message::builder()
+        let (body, mut attachments) = parse_markers(body);
+        attachments.extend(message.attachments.iter().cloned());
+
+        let builder = Message::builder()
             .from(self.config.from_address.parse()?)
             .to(message.recipient.parse()?)
-            .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
+            .subject(subject);
+
+        let email = if attachments.is_empty() {
+            builder.singlepart(SinglePart::plain(body))?
+        } else {
+            let mut text = body;
+            let mut parts = Vec::with_capacity(attachments.len());
+            for attachment in &attachments {
+                let bytes =
+                    load_outgoing(attachment, self.workspace_dir.as_deref(), "channel.email")
+                        .await?;
+                let name = attachment.file_name_or_default();
+                if let Some(caption) = attachment.caption.as_deref() {
+                    let _ = write!(text, "\n\n{name}: {caption}");
+                }
+                let content_type = ContentType::parse(&attachment.mime_or_guess())
+                    .unwrap_or(ContentType::parse("application/octet-stream")?);
+                parts.push(MailAttachment::new(name).body(bytes, content_type));
+            }
+            let mut multipart =
+                MultiPart::mixed().singlepart(SinglePart::plain(text.trim().to_string()));
+            for part in parts {
+                multipart = multipart.singlepart(part);
+            }
+            builder.multipart(multipart)?
+        };
 
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
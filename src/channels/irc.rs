@@ -304,7 +304,7 @@ impl IrcChannel {
 
 /// Certificate verifier that accepts any certificate (for `verify_tls=false`).
 #[derive(Debug)]
-struct NoVerify;
+pub(super) struct NoVerify;
 
 impl rustls::client::danger::ServerCertVerifier for NoVerify {
     fn verify_server_cert(
@@ -580,6 +580,7 @@ impl Channel for IrcChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        attachments: Vec::new(),
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -250,6 +250,7 @@ impl LinqChannel {
             channel: "linq".to_string(),
             timestamp,
             thread_ts: None,
+            attachments: Vec::new(),
         });
 
         messages
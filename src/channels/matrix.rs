@@ -1,6 +1,8 @@
-use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::channels::attachments::{load_outgoing, mime_from_name, MAX_INBOUND_ATTACHMENT_BYTES};
+use crate::channels::traits::{Attachment, AttachmentKind, Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use matrix_sdk::{
+    attachment::AttachmentConfig,
     authentication::matrix::MatrixSession,
     config::SyncSettings,
     ruma::{
@@ -30,6 +32,7 @@ pub struct MatrixChannel {
     session_owner_hint: Option<String>,
     session_device_id_hint: Option<String>,
     zeroclaw_dir: Option<PathBuf>,
+    workspace_dir: Option<PathBuf>,
     resolved_room_id_cache: Arc<RwLock<Option<String>>>,
     sdk_client: Arc<OnceCell<MatrixSdkClient>>,
     http_client: Client,
@@ -169,6 +172,7 @@ impl MatrixChannel {
             session_owner_hint: Self::normalize_optional_field(owner_hint),
             session_device_id_hint: Self::normalize_optional_field(device_id_hint),
             zeroclaw_dir,
+            workspace_dir: None,
             resolved_room_id_cache: Arc::new(RwLock::new(None)),
             sdk_client: Arc::new(OnceCell::new()),
             http_client: Client::new(),
@@ -180,6 +184,12 @@ impl MatrixChannel {
         self
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn encode_path_segment(value: &str) -> String {
         fn should_encode(byte: u8) -> bool {
             !matches!(
@@ -224,7 +234,83 @@ impl MatrixChannel {
     }
 
     fn is_supported_message_type(msgtype: &str) -> bool {
-        matches!(msgtype, "m.text" | "m.notice")
+        matches!(
+            msgtype,
+            "m.text" | "m.notice" | "m.image" | "m.file" | "m.audio" | "m.video"
+        )
+    }
+
+    /// Split a media body into `(filename, caption)`. When a separate
+    /// `filename` is present the body is a caption, otherwise it is the name.
+    fn media_name_and_caption(body: &str, filename: Option<&str>) -> (String, Option<String>) {
+        match filename {
+            Some(name) if name != body => (name.to_string(), Some(body.to_string())),
+            _ => (body.to_string(), None),
+        }
+    }
+
+    /// Download the media of an image/file/audio/video event.
+    ///
+    /// Returns the caption (if any) and the attachment; `None` for text
+    /// events and for media that is too large or fails to download.
+    async fn media_attachment(room: &Room, msgtype: &MessageType) -> Option<(String, Attachment)> {
+        let media = room.client().media();
+        let (kind, body, filename, mime, size, data) = match msgtype {
+            MessageType::Image(content) => (
+                AttachmentKind::Image,
+                &content.body,
+                content.filename.as_deref(),
+                content.info.as_ref().and_then(|i| i.mimetype.clone()),
+                content.info.as_ref().and_then(|i| i.size).map(u64::from),
+                media.get_file(content, true).await,
+            ),
+            MessageType::File(content) => (
+                AttachmentKind::Document,
+                &content.body,
+                content.filename.as_deref(),
+                content.info.as_ref().and_then(|i| i.mimetype.clone()),
+                content.info.as_ref().and_then(|i| i.size).map(u64::from),
+                media.get_file(content, true).await,
+            ),
+            MessageType::Audio(content) => (
+                AttachmentKind::Audio,
+                &content.body,
+                content.filename.as_deref(),
+                content.info.as_ref().and_then(|i| i.mimetype.clone()),
+                content.info.as_ref().and_then(|i| i.size).map(u64::from),
+                media.get_file(content, true).await,
+            ),
+            MessageType::Video(content) => (
+                AttachmentKind::Video,
+                &content.body,
+                content.filename.as_deref(),
+                content.info.as_ref().and_then(|i| i.mimetype.clone()),
+                content.info.as_ref().and_then(|i| i.size).map(u64::from),
+                media.get_file(content, true).await,
+            ),
+            _ => return None,
+        };
+
+        if size.is_some_and(|size| size > MAX_INBOUND_ATTACHMENT_BYTES) {
+            tracing::warn!("Matrix: media attachment too large, skipping");
+            return None;
+        }
+        let bytes = match data {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return None,
+            Err(error) => {
+                let safe_error = Self::sanitize_error_for_log(&error);
+                tracing::warn!("Matrix: failed to download media: {safe_error}");
+                return None;
+            }
+        };
+
+        let (name, caption) = Self::media_name_and_caption(body, filename);
+        let mime = mime.unwrap_or_else(|| mime_from_name(&name).to_string());
+        let attachment = Attachment::from_bytes(kind, bytes)
+            .with_filename(name)
+            .with_mime(mime);
+        Some((caption.unwrap_or_default(), attachment))
     }
 
     fn has_non_empty_body(body: &str) -> bool {
@@ -692,8 +778,31 @@ impl Channel for MatrixChannel {
             anyhow::bail!("Matrix room '{}' is not in joined state", target_room_id);
         }
 
-        room.send(RoomMessageEventContent::text_markdown(&message.content))
+        let (text, attachments) = message.split_attachments();
+        if !text.is_empty() || attachments.is_empty() {
+            room.send(RoomMessageEventContent::text_markdown(&text))
+                .await?;
+        }
+
+        for attachment in &attachments {
+            let bytes =
+                load_outgoing(attachment, self.workspace_dir.as_deref(), "channel.matrix").await?;
+            let content_type: mime_guess::mime::Mime = attachment
+                .mime_or_guess()
+                .parse()
+                .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM);
+            room.send_attachment(
+                attachment.file_name_or_default(),
+                &content_type,
+                bytes,
+                AttachmentConfig::new(),
+            )
             .await?;
+            if let Some(caption) = attachment.caption.as_deref() {
+                room.send(RoomMessageEventContent::text_markdown(caption))
+                    .await?;
+            }
+        }
 
         Ok(())
     }
@@ -773,13 +882,16 @@ impl Channel for MatrixChannel {
                     return;
                 }
 
-                let body = match &event.content.msgtype {
-                    MessageType::Text(content) => content.body.clone(),
-                    MessageType::Notice(content) => content.body.clone(),
-                    _ => return,
+                let (body, attachment) = match &event.content.msgtype {
+                    MessageType::Text(content) => (content.body.clone(), None),
+                    MessageType::Notice(content) => (content.body.clone(), None),
+                    other => match MatrixChannel::media_attachment(&room, other).await {
+                        Some((caption, attachment)) => (caption, Some(attachment)),
+                        None => return,
+                    },
                 };
 
-                if !MatrixChannel::has_non_empty_body(&body) {
+                if attachment.is_none() && !MatrixChannel::has_non_empty_body(&body) {
                     return;
                 }
 
@@ -831,6 +943,7 @@ impl Channel for MatrixChannel {
                         .unwrap_or_default()
                         .as_secs(),
                     thread_ts: None,
+                    attachments: attachment.into_iter().collect(),
                 };
 
                 let _ = tx.send(msg).await;
@@ -1018,8 +1131,25 @@ mod tests {
     fn supported_message_type_detection() {
         assert!(MatrixChannel::is_supported_message_type("m.text"));
         assert!(MatrixChannel::is_supported_message_type("m.notice"));
-        assert!(!MatrixChannel::is_supported_message_type("m.image"));
-        assert!(!MatrixChannel::is_supported_message_type("m.file"));
+        assert!(MatrixChannel::is_supported_message_type("m.image"));
+        assert!(MatrixChannel::is_supported_message_type("m.file"));
+        assert!(!MatrixChannel::is_supported_message_type("m.location"));
+    }
+
+    #[test]
+    fn media_body_is_caption_only_when_filename_differs() {
+        assert_eq!(
+            MatrixChannel::media_name_and_caption("cat.png", None),
+            ("cat.png".to_string(), None)
+        );
+        assert_eq!(
+            MatrixChannel::media_name_and_caption("cat.png", Some("cat.png")),
+            ("cat.png".to_string(), None)
+        );
+        assert_eq!(
+            MatrixChannel::media_name_and_caption("look at this", Some("cat.png")),
+            ("cat.png".to_string(), Some("look at this".to_string()))
+        );
     }
 
     #[test]
@@ -344,6 +344,7 @@ impl MattermostChannel {
             #[allow(clippy::cast_sign_loss)]
             timestamp: (create_at / 1000) as u64,
             thread_ts: None,
+            attachments: Vec::new(),
         })
     }
 }
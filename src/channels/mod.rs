@@ -14,6 +14,7 @@
 //! To add a new channel, implement [`Channel`] in a new submodule and wire it into
 //! [`start_channels`]. See `AGENTS.md` §7.2 for the full change playbook.
 
+pub mod attachments;
 pub mod clawdtalk;
 pub mod cli;
 pub mod dingtalk;
@@ -30,8 +31,10 @@ pub mod mattermost;
 pub mod nextcloud_talk;
 pub mod nostr;
 pub mod qq;
+pub mod sessions;
 pub mod signal;
 pub mod slack;
+pub mod teams;
 pub mod telegram;
 pub mod traits;
 pub mod transcription;
@@ -41,6 +44,8 @@ pub mod whatsapp;
 pub mod whatsapp_storage;
 #[cfg(feature = "whatsapp-web")]
 pub mod whatsapp_web;
+pub mod xmpp;
+pub mod zulip;
 
 pub use clawdtalk::ClawdTalkChannel;
 pub use cli::CliChannel;
@@ -60,21 +65,27 @@ pub use nostr::NostrChannel;
 pub use qq::QQChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
+pub use teams::TeamsChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
 pub use wati::WatiChannel;
 pub use whatsapp::WhatsAppChannel;
 #[cfg(feature = "whatsapp-web")]
 pub use whatsapp_web::WhatsAppWebChannel;
+pub use xmpp::XmppChannel;
+pub use zulip::ZulipChannel;
 
 use crate::agent::loop_::{
     build_shell_policy_instructions, build_tool_instructions_from_specs, run_tool_call_loop,
     scrub_credentials,
 };
-use crate::approval::{ApprovalManager, PendingApprovalError};
+use crate::approval::{
+    with_non_cli_turn, ApprovalManager, ApprovalResponse, NonCliRequester, NonCliTurn,
+    PendingApprovalError, PendingNonCliApprovalRequest,
+};
 use crate::config::{Config, NonCliNaturalLanguageApprovalMode};
 use crate::identity;
-use crate::memory::{self, Memory};
+use crate::memory::{self, Memory, MemoryScope};
 use crate::observability::{self, runtime_trace, Observer};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
@@ -159,6 +170,7 @@ enum ChannelRuntimeCommand {
     RequestAllToolsOnce,
     RequestToolApproval(String),
     ConfirmToolApproval(String),
+    DecideToolApproval(String),
     ListPendingApprovals,
     ApproveTool(String),
     UnapproveTool(String),
@@ -235,7 +247,10 @@ struct ChannelRuntimeContext {
     auto_save_memory: bool,
     max_tool_iterations: usize,
     min_relevance_score: f64,
+    memory_namespaces: crate::config::MemoryNamespaceConfig,
+    memory_reranker: Option<Arc<memory::reranker::MemoryReranker>>,
     conversation_histories: ConversationHistoryMap,
+    session_store: Option<Arc<sessions::SessionStore>>,
     provider_cache: ProviderCacheMap,
     route_overrides: RouteSelectionMap,
     api_key: Option<String>,
@@ -449,6 +464,12 @@ fn channel_delivery_instructions(channel_name: &str) -> Option<&'static str> {
              - You can combine text and media in one response — text is sent first, then each attachment.\n\
              - Use tool results silently: answer the latest user message directly, and do not narrate delayed/internal tool execution bookkeeping.",
         ),
+        "discord" | "slack" | "matrix" | "signal" | "email" => Some(
+            "Attachments on this channel:\n\
+             - To send files, use markers: [IMAGE:<path-or-url>], [DOCUMENT:<path-or-url>], [VIDEO:<path-or-url>], [AUDIO:<path-or-url>], or [VOICE:<path-or-url>]\n\
+             - Local paths must point inside the workspace.\n\
+             - Keep normal text outside markers and never wrap markers in code fences.",
+        ),
         _ => None,
     }
 }
@@ -703,6 +724,7 @@ fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRun
         "/approve-all-once" => Some(ChannelRuntimeCommand::RequestAllToolsOnce),
         "/approve-request" => Some(ChannelRuntimeCommand::RequestToolApproval(tail)),
         "/approve-confirm" => Some(ChannelRuntimeCommand::ConfirmToolApproval(tail)),
+        "/approve-decide" => Some(ChannelRuntimeCommand::DecideToolApproval(tail)),
         "/approve-pending" => Some(ChannelRuntimeCommand::ListPendingApprovals),
         "/approve" => Some(ChannelRuntimeCommand::ApproveTool(tail)),
         "/unapprove" => Some(ChannelRuntimeCommand::UnapproveTool(tail)),
@@ -843,6 +865,7 @@ fn is_approval_management_command(command: &ChannelRuntimeCommand) -> bool {
         ChannelRuntimeCommand::RequestAllToolsOnce
             | ChannelRuntimeCommand::RequestToolApproval(_)
             | ChannelRuntimeCommand::ConfirmToolApproval(_)
+            | ChannelRuntimeCommand::DecideToolApproval(_)
             | ChannelRuntimeCommand::ListPendingApprovals
             | ChannelRuntimeCommand::ApproveTool(_)
             | ChannelRuntimeCommand::UnapproveTool(_)
@@ -1485,11 +1508,36 @@ fn set_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str, next: Chan
     }
 }
 
+/// Write one sender's history through to the session store, if enabled.
+/// Called with the history lock held: the snapshot and its revision are taken
+/// here, and the SQLite write runs on the blocking pool. Revisions keep a
+/// late stale snapshot from overwriting a newer one.
+fn persist_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str, turns: &[ChatMessage]) {
+    let Some(store) = ctx.session_store.as_ref() else {
+        return;
+    };
+    let store = Arc::clone(store);
+    let revision = store.next_revision();
+    let key = sender_key.to_string();
+    let turns = turns.to_vec();
+    let write = move || {
+        if let Err(e) = store.save_revision(&key, revision, &turns) {
+            tracing::warn!("Failed to persist channel session {key}: {e}");
+        }
+    };
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => drop(handle.spawn_blocking(write)),
+        Err(_) => write(),
+    }
+}
+
 fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
-    ctx.conversation_histories
+    let mut histories = ctx
+        .conversation_histories
         .lock()
-        .unwrap_or_else(|e| e.into_inner())
-        .remove(sender_key);
+        .unwrap_or_else(|e| e.into_inner());
+    histories.remove(sender_key);
+    persist_sender_history(ctx, sender_key, &[]);
 }
 
 fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool {
@@ -1520,10 +1568,12 @@ fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool
 
     if compacted.is_empty() {
         turns.clear();
+        persist_sender_history(ctx, sender_key, turns);
         return false;
     }
 
     *turns = compacted;
+    persist_sender_history(ctx, sender_key, turns);
     true
 }
 
@@ -1537,6 +1587,7 @@ fn append_sender_turn(ctx: &ChannelRuntimeContext, sender_key: &str, turn: ChatM
     while turns.len() > MAX_CHANNEL_HISTORY {
         turns.remove(0);
     }
+    persist_sender_history(ctx, sender_key, turns);
 }
 
 fn rollback_orphan_user_turn(
@@ -1560,12 +1611,48 @@ fn rollback_orphan_user_turn(
     }
 
     turns.pop();
+    persist_sender_history(ctx, sender_key, turns);
     if turns.is_empty() {
         histories.remove(sender_key);
     }
     true
 }
 
+/// Open the channel session store and restore persisted histories with the
+/// same normalization and trim applied to live turns. A store that cannot be
+/// opened disables persistence instead of blocking channel startup.
+fn open_channel_session_store(
+    workspace_dir: &std::path::Path,
+) -> (
+    Option<Arc<sessions::SessionStore>>,
+    HashMap<String, Vec<ChatMessage>>,
+) {
+    let store = match memory::encryption::runtime_cipher()
+        .and_then(|cipher| Ok(sessions::SessionStore::open(workspace_dir)?.with_cipher(cipher)))
+    {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("Channel session persistence disabled: {e}");
+            return (None, HashMap::new());
+        }
+    };
+    let stored = store.load_all().unwrap_or_else(|e| {
+        tracing::warn!("Failed to restore channel sessions: {e}");
+        HashMap::new()
+    });
+
+    let restored = stored
+        .into_iter()
+        .filter_map(|(key, turns)| {
+            let mut turns = normalize_cached_channel_turns(turns);
+            let excess = turns.len().saturating_sub(MAX_CHANNEL_HISTORY);
+            turns.drain(..excess);
+            (!turns.is_empty()).then_some((key, turns))
+        })
+        .collect();
+    (Some(Arc::new(store)), restored)
+}
+
 fn should_skip_memory_context_entry(key: &str, content: &str) -> bool {
     if memory::is_assistant_autosave_key(key) {
         return true;
@@ -1699,6 +1786,9 @@ fn build_models_help_response(current: &ChannelRouteSelection, workspace_dir: &P
     response.push_str("Request supervised tool approval with `/approve-request <tool-name>`.\n");
     response.push_str("Request one-time all-tools approval with `/approve-all-once`.\n");
     response.push_str("Confirm approval with `/approve-confirm <request-id>`.\n");
+    response.push_str(
+        "Answer approval buttons with `/approve-decide <request-id> <once|session|deny>`.\n",
+    );
     response.push_str("List pending requests with `/approve-pending`.\n");
     response.push_str("Approve supervised tools with `/approve <tool-name>`.\n");
     response.push_str("Revoke approval with `/unapprove <tool-name>`.\n");
@@ -1742,6 +1832,9 @@ fn build_providers_help_response(current: &ChannelRouteSelection) -> String {
     response.push_str("Request supervised tool approval with `/approve-request <tool-name>`.\n");
     response.push_str("Request one-time all-tools approval with `/approve-all-once`.\n");
     response.push_str("Confirm approval with `/approve-confirm <request-id>`.\n");
+    response.push_str(
+        "Answer approval buttons with `/approve-decide <request-id> <once|session|deny>`.\n",
+    );
     response.push_str("List pending requests with `/approve-pending`.\n");
     response.push_str("Approve supervised tools with `/approve <tool-name>`.\n");
     response.push_str("Revoke approval with `/unapprove <tool-name>`.\n");
@@ -1767,6 +1860,170 @@ fn build_providers_help_response(current: &ChannelRouteSelection) -> String {
     response
 }
 
+fn parse_approval_decision(token: &str) -> Option<ApprovalResponse> {
+    match token.to_ascii_lowercase().as_str() {
+        "once" | "yes" => Some(ApprovalResponse::Yes),
+        "session" | "always" => Some(ApprovalResponse::Always),
+        "deny" | "no" => Some(ApprovalResponse::No),
+        _ => None,
+    }
+}
+
+/// Render a pending approval request as Approve once / Approve for session /
+/// Deny buttons. Each button replays `/approve-decide` as the user who presses it.
+fn build_approval_prompt(
+    req: &PendingNonCliApprovalRequest,
+    reply_target: &str,
+    thread_ts: Option<String>,
+) -> traits::InteractivePrompt {
+    use traits::{InteractivePrompt, PromptAction, PromptActionStyle};
+
+    let decide = |decision: &str| format!("/approve-decide {} {decision}", req.request_id);
+    let mut text = format!(
+        "Approval requested for {}.\nRequest ID: `{}`\nExpires: `{}`",
+        approval_target_label(&req.tool_name),
+        req.request_id,
+        req.expires_at
+    );
+    if let Some(reason) = req.reason.as_deref().filter(|r| !r.trim().is_empty()) {
+        let _ = write!(text, "\nReason: {reason}");
+    }
+
+    let mut prompt = InteractivePrompt::new(text, reply_target)
+        .in_thread(thread_ts)
+        .with_action(PromptAction::new(
+            "Approve once",
+            decide("once"),
+            PromptActionStyle::Primary,
+        ));
+    if req.tool_name != APPROVAL_ALL_TOOLS_ONCE_TOKEN {
+        prompt = prompt.with_action(PromptAction::new(
+            "Approve for session",
+            decide("session"),
+            PromptActionStyle::Secondary,
+        ));
+    }
+    prompt.with_action(PromptAction::new(
+        "Deny",
+        decide("deny"),
+        PromptActionStyle::Danger,
+    ))
+}
+
+/// Apply an approval-prompt decision (`/approve-decide <request-id> <decision>`).
+fn decide_pending_approval(
+    ctx: &ChannelRuntimeContext,
+    raw_args: &str,
+    sender: &str,
+    source_channel: &str,
+    reply_target: &str,
+) -> String {
+    let mut parts = raw_args.split_whitespace();
+    let (Some(request_id), Some(decision)) =
+        (parts.next(), parts.next().and_then(parse_approval_decision))
+    else {
+        return "Usage: `/approve-decide <request-id> <once|session|deny>`".to_string();
+    };
+
+    let req = match ctx.approval_manager.resolve_non_cli_pending_request(
+        request_id,
+        sender,
+        source_channel,
+        reply_target,
+    ) {
+        Ok(req) => req,
+        Err(err) => {
+            let (reason, response) = match err {
+                PendingApprovalError::NotFound => (
+                    "pending request not found",
+                    format!(
+                        "Pending approval request `{request_id}` was not found or was already answered."
+                    ),
+                ),
+                PendingApprovalError::Expired => (
+                    "pending request expired",
+                    format!("Pending approval request `{request_id}` has expired."),
+                ),
+                PendingApprovalError::RequesterMismatch => (
+                    "pending request decider mismatch",
+                    format!(
+                        "Pending approval request `{request_id}` can only be answered in the chat that created it, by the requester or a configured approver."
+                    ),
+                ),
+            };
+            runtime_trace::record_event(
+                "approval_request_decided",
+                Some(source_channel),
+                None,
+                None,
+                None,
+                Some(false),
+                Some(reason),
+                serde_json::json!({
+                    "request_id": request_id,
+                    "sender": sender,
+                    "channel": source_channel,
+                }),
+            );
+            return response;
+        }
+    };
+
+    let tool_name = req.tool_name.as_str();
+    let target = approval_target_label(tool_name);
+    let response = match decision {
+        ApprovalResponse::No => format!("Denied {target} from request `{request_id}`."),
+        _ if tool_name == APPROVAL_ALL_TOOLS_ONCE_TOKEN => {
+            let remaining = ctx.approval_manager.grant_non_cli_allow_all_once();
+            format!(
+                "Approved one-time all-tools bypass from request `{request_id}`.\nApplies to the next non-CLI agent tool-execution turn only.\nQueued one-time all-tools bypass tokens: `{remaining}`."
+            )
+        }
+        ApprovalResponse::Yes => {
+            ctx.approval_manager
+                .grant_non_cli_once(tool_name, &NonCliRequester::of_request(&req));
+            format!(
+                "Approved `{target}` once from request `{request_id}`.\nThe next supervised call of this tool may run."
+            )
+        }
+        ApprovalResponse::Always => {
+            ctx.approval_manager.grant_non_cli_session(tool_name);
+            format!(
+                "Approved `{target}` for this runtime session from request `{request_id}`.\nThis grant is not persisted to config; use `/approve {tool_name}` for that."
+            )
+        }
+    };
+
+    runtime_trace::record_event(
+        "approval_request_decided",
+        Some(source_channel),
+        None,
+        None,
+        None,
+        Some(true),
+        Some("pending request decided"),
+        serde_json::json!({
+            "request_id": request_id,
+            "tool_name": tool_name,
+            "decision": decision,
+            "requested_by": req.requested_by,
+            "sender": sender,
+            "channel": source_channel,
+        }),
+    );
+
+    if decision != ApprovalResponse::No
+        && tool_name != APPROVAL_ALL_TOOLS_ONCE_TOKEN
+        && is_non_cli_tool_excluded(ctx, tool_name)
+    {
+        format!(
+            "{response}\nNote: `{tool_name}` is currently listed in `autonomy.non_cli_excluded_tools` for this runtime, so it stays unavailable on channels."
+        )
+    } else {
+        response
+    }
+}
+
 async fn handle_runtime_command_if_needed(
     ctx: &ChannelRuntimeContext,
     msg: &traits::ChannelMessage,
@@ -1893,6 +2150,7 @@ async fn handle_runtime_command_if_needed(
         }
     }
 
+    let mut approval_prompt: Option<PendingNonCliApprovalRequest> = None;
     let response = match command {
         ChannelRuntimeCommand::ShowProviders => build_providers_help_response(&current),
         ChannelRuntimeCommand::SetProvider(raw_provider) => {
@@ -1952,6 +2210,7 @@ async fn handle_runtime_command_if_needed(
                 reply_target,
                 Some("human-confirmed one-time bypass request for all tools/commands".to_string()),
             );
+            approval_prompt = Some(req.clone());
             runtime_trace::record_event(
                 "approval_request_created",
                 Some(source_channel),
@@ -2008,6 +2267,7 @@ async fn handle_runtime_command_if_needed(
                     reply_target,
                     None,
                 );
+                approval_prompt = Some(req.clone());
                 runtime_trace::record_event(
                     "approval_request_created",
                     Some(source_channel),
@@ -2149,6 +2409,9 @@ async fn handle_runtime_command_if_needed(
                 }
             }
         }
+        ChannelRuntimeCommand::DecideToolApproval(raw_args) => {
+            decide_pending_approval(ctx, &raw_args, sender, source_channel, reply_target)
+        }
         ChannelRuntimeCommand::ListPendingApprovals => {
             let rows = ctx.approval_manager.list_non_cli_pending_requests(
                 Some(sender),
@@ -2271,10 +2534,20 @@ async fn handle_runtime_command_if_needed(
         }
     };
 
-    if let Err(err) = channel
-        .send(&SendMessage::new(response, &msg.reply_target).in_thread(msg.thread_ts.clone()))
-        .await
-    {
+    let delivery = match approval_prompt {
+        Some(req) if channel.supports_interactive_prompts() => {
+            let prompt = build_approval_prompt(&req, &msg.reply_target, msg.thread_ts.clone());
+            channel.send_interactive_prompt(&prompt).await
+        }
+        _ => {
+            channel
+                .send(
+                    &SendMessage::new(response, &msg.reply_target).in_thread(msg.thread_ts.clone()),
+                )
+                .await
+        }
+    };
+    if let Err(err) = delivery {
         tracing::warn!(
             "Failed to send runtime command response on {}: {err}",
             channel.name()
@@ -2286,19 +2559,31 @@ async fn handle_runtime_command_if_needed(
 
 async fn build_memory_context(
     mem: &dyn Memory,
+    scope: Option<&MemoryScope>,
     user_msg: &str,
     min_relevance_score: f64,
+    reranker: Option<&memory::reranker::MemoryReranker>,
 ) -> String {
     let mut context = String::new();
+    let fetch = reranker.map_or(5, |reranker| reranker.candidate_limit(5));
 
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    if let Ok(entries) = memory::namespace::recall(mem, scope, user_msg, fetch, &[]).await {
         let mut included = 0usize;
         let mut used_chars = 0usize;
 
-        for entry in entries.iter().filter(|e| match e.score {
-            Some(score) => score >= min_relevance_score,
-            None => true, // keep entries without a score (e.g. non-vector backends)
-        }) {
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .filter(|e| match e.score {
+                Some(score) => score >= min_relevance_score,
+                None => true, // keep entries without a score (e.g. non-vector backends)
+            })
+            .collect();
+        if let Some(reranker) = reranker {
+            entries.retain(|e| !should_skip_memory_context_entry(&e.key, &e.content));
+            entries = reranker.rerank(user_msg, entries).await;
+        }
+
+        for entry in &entries {
             if included >= MEMORY_CONTEXT_MAX_ENTRIES {
                 break;
             }
@@ -2762,7 +3047,14 @@ async fn process_channel_message(
     if let Err(err) = maybe_apply_runtime_config_update(ctx.as_ref()).await {
         tracing::warn!("Failed to apply runtime config update: {err}");
     }
-    if handle_runtime_command_if_needed(ctx.as_ref(), &msg, target_channel.as_ref()).await {
+    // Boxed so the rarely-taken command path does not inflate every worker future.
+    if Box::pin(handle_runtime_command_if_needed(
+        ctx.as_ref(),
+        &msg,
+        target_channel.as_ref(),
+    ))
+    .await
+    {
         return;
     }
 
@@ -2790,15 +3082,37 @@ async fn process_channel_message(
             return;
         }
     };
+    // Multi-user channels keep each sender's memories apart; the local CLI
+    // stays on the global namespace.
+    let memory_scope = if msg.channel == "cli" {
+        None
+    } else {
+        MemoryScope::for_sender(
+            ctx.memory.as_ref(),
+            &ctx.memory_namespaces,
+            &msg.channel,
+            &msg.reply_target,
+            &msg.sender,
+        )
+    };
+
     if ctx.auto_save_memory && msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
         let autosave_key = conversation_memory_key(&msg);
+        let category = crate::memory::MemoryCategory::Conversation;
+        let namespace = memory_scope
+            .as_ref()
+            .map_or(memory::DEFAULT_NAMESPACE, |scope| {
+                scope.write_namespace(&category)
+            });
         let _ = ctx
             .memory
-            .store(
+            .store_in(
+                namespace,
                 &autosave_key,
                 &msg.content,
-                crate::memory::MemoryCategory::Conversation,
+                category,
                 None,
+                &memory::MemoryMetadata::default(),
             )
             .await;
     }
@@ -2813,8 +3127,14 @@ async fn process_channel_message(
         .get(&history_key)
         .is_some_and(|turns| !turns.is_empty());
 
+    // Typed attachments become workspace paths and image references here, so
+    // the multimodal pipeline and file tools see them without channel-specific
+    // text conventions.
+    let user_content =
+        attachments::compose_inbound_content(ctx.workspace_dir.as_path(), &msg).await;
+
     // Preserve user turn before the LLM call so interrupted requests keep context.
-    append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::user(&msg.content));
+    append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::user(&user_content));
 
     // Build history from per-sender conversation cache.
     let prior_turns_raw = ctx
@@ -2829,11 +3149,17 @@ async fn process_channel_message(
     // Only enrich with memory context when there is no prior conversation
     // history. Follow-up turns already include context from previous messages.
     if !had_prior_history {
-        let memory_context =
-            build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
+        let memory_context = build_memory_context(
+            ctx.memory.as_ref(),
+            memory_scope.as_ref(),
+            &msg.content,
+            ctx.min_relevance_score,
+            ctx.memory_reranker.as_deref(),
+        )
+        .await;
         if let Some(last_turn) = prior_turns.last_mut() {
             if last_turn.role == "user" && !memory_context.is_empty() {
-                last_turn.content = format!("{memory_context}{}", msg.content);
+                last_turn.content = format!("{memory_context}{user_content}");
             }
         }
     }
@@ -2960,13 +3286,19 @@ async fn process_channel_message(
         Cancelled,
     }
 
+    let approval_turn = NonCliTurn::new(
+        NonCliRequester::new(&msg.sender, &msg.channel, &msg.reply_target),
+        target_channel
+            .as_ref()
+            .is_some_and(|channel| channel.supports_interactive_prompts()),
+    );
     let timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
     let llm_result = tokio::select! {
         () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
         result = tokio::time::timeout(
             Duration::from_secs(timeout_budget_secs),
-            run_tool_call_loop(
+            with_non_cli_turn(approval_turn.clone(), memory::namespace::with_scope(memory_scope, run_tool_call_loop(
                 active_provider.as_ref(),
                 &mut history,
                 ctx.tools_registry.as_ref(),
@@ -2983,7 +3315,7 @@ async fn process_channel_message(
                 delta_tx,
                 ctx.hooks.as_deref(),
                 &excluded_tools_snapshot,
-            ),
+            ))),
         ) => LlmExecutionResult::Completed(result),
     };
 
@@ -3290,7 +3622,7 @@ async fn process_channel_message(
                     .downcast_ref::<providers::ProviderCapabilityError>()
                     .is_some_and(|capability| capability.capability.eq_ignore_ascii_case("vision"));
                 let rolled_back = should_rollback_user_turn
-                    && rollback_orphan_user_turn(ctx.as_ref(), &history_key, &msg.content);
+                    && rollback_orphan_user_turn(ctx.as_ref(), &history_key, &user_content);
 
                 if !rolled_back {
                     // Close the orphan user turn so subsequent messages don't
@@ -3366,6 +3698,19 @@ async fn process_channel_message(
         }
     }
 
+    // Offer approval buttons for supervised calls the turn had to block.
+    if let Some(channel) = target_channel.as_ref() {
+        for req in approval_turn.take_blocked() {
+            let prompt = build_approval_prompt(&req, &msg.reply_target, msg.thread_ts.clone());
+            if let Err(err) = channel.send_interactive_prompt(&prompt).await {
+                tracing::warn!(
+                    "Failed to send approval prompt on {}: {err}",
+                    channel.name()
+                );
+            }
+        }
+    }
+
     // Swap 👀 → ✅ (or ⚠️ on error) to signal processing is complete
     if let Some(channel) = target_channel.as_ref() {
         let _ = channel
@@ -4008,7 +4353,9 @@ fn collect_configured_channels(
                 .with_group_reply_policy(
                     sl.effective_group_reply_mode().requires_mention(),
                     sl.group_reply_allowed_sender_ids(),
-                ),
+                )
+                .with_app_token(sl.app_token.clone())
+                .with_workspace_dir(config.workspace_dir.clone()),
             ),
         });
     }
@@ -4051,7 +4398,8 @@ fn collect_configured_channels(
                     mx.device_id.clone(),
                     config.config_path.parent().map(|path| path.to_path_buf()),
                 )
-                .with_mention_only(mx.mention_only),
+                .with_mention_only(mx.mention_only)
+                .with_workspace_dir(config.workspace_dir.clone()),
             ),
         });
     }
@@ -4067,14 +4415,17 @@ fn collect_configured_channels(
     if let Some(ref sig) = config.channels_config.signal {
         channels.push(ConfiguredChannel {
             display_name: "Signal",
-            channel: Arc::new(SignalChannel::new(
-                sig.http_url.clone(),
-                sig.account.clone(),
-                sig.group_id.clone(),
-                sig.allowed_from.clone(),
-                sig.ignore_attachments,
-                sig.ignore_stories,
-            )),
+            channel: Arc::new(
+                SignalChannel::new(
+                    sig.http_url.clone(),
+                    sig.account.clone(),
+                    sig.group_id.clone(),
+                    sig.allowed_from.clone(),
+                    sig.ignore_attachments,
+                    sig.ignore_stories,
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            ),
         });
     }
 
@@ -4091,12 +4442,15 @@ fn collect_configured_channels(
                 if wa.is_cloud_config() {
                     channels.push(ConfiguredChannel {
                         display_name: "WhatsApp",
-                        channel: Arc::new(WhatsAppChannel::new(
-                            wa.access_token.clone().unwrap_or_default(),
-                            wa.phone_number_id.clone().unwrap_or_default(),
-                            wa.verify_token.clone().unwrap_or_default(),
-                            wa.allowed_numbers.clone(),
-                        )),
+                        channel: Arc::new(
+                            WhatsAppChannel::new(
+                                wa.access_token.clone().unwrap_or_default(),
+                                wa.phone_number_id.clone().unwrap_or_default(),
+                                wa.verify_token.clone().unwrap_or_default(),
+                                wa.allowed_numbers.clone(),
+                            )
+                            .with_workspace_dir(config.workspace_dir.clone()),
+                        ),
                     });
                 } else {
                     tracing::warn!("WhatsApp Cloud API configured but missing required fields (phone_number_id, access_token, verify_token)");
@@ -4108,12 +4462,15 @@ fn collect_configured_channels(
                 if wa.is_web_config() {
                     channels.push(ConfiguredChannel {
                         display_name: "WhatsApp",
-                        channel: Arc::new(WhatsAppWebChannel::new(
-                            wa.session_path.clone().unwrap_or_default(),
-                            wa.pair_phone.clone(),
-                            wa.pair_code.clone(),
-                            wa.allowed_numbers.clone(),
-                        )),
+                        channel: Arc::new(
+                            WhatsAppWebChannel::new(
+                                wa.session_path.clone().unwrap_or_default(),
+                                wa.pair_phone.clone(),
+                                wa.pair_code.clone(),
+                                wa.allowed_numbers.clone(),
+                            )
+                            .with_workspace_dir(config.workspace_dir.clone()),
+                        ),
                     });
                 } else {
                     tracing::warn!("WhatsApp Web configured but session_path not set");
@@ -4166,7 +4523,10 @@ fn collect_configured_channels(
     if let Some(ref email_cfg) = config.channels_config.email {
         channels.push(ConfiguredChannel {
             display_name: "Email",
-            channel: Arc::new(EmailChannel::new(email_cfg.clone())),
+            channel: Arc::new(
+                EmailChannel::new(email_cfg.clone())
+                    .with_workspace_dir(config.workspace_dir.clone()),
+            ),
         });
     }
 
@@ -4188,6 +4548,31 @@ fn collect_configured_channels(
         });
     }
 
+    if let Some(ref xmpp) = config.channels_config.xmpp {
+        channels.push(ConfiguredChannel {
+            display_name: "XMPP",
+            channel: Arc::new(XmppChannel::from_config(xmpp)),
+        });
+    }
+
+    if let Some(ref zulip) = config.channels_config.zulip {
+        channels.push(ConfiguredChannel {
+            display_name: "Zulip",
+            channel: Arc::new(
+                ZulipChannel::from_config(zulip).with_workspace_dir(config.workspace_dir.clone()),
+            ),
+        });
+    }
+
+    if let Some(ref teams) = config.channels_config.teams {
+        channels.push(ConfiguredChannel {
+            display_name: "Teams",
+            channel: Arc::new(
+                TeamsChannel::from_config(teams).with_workspace_dir(config.workspace_dir.clone()),
+            ),
+        });
+    }
+
     #[cfg(feature = "channel-lark")]
     if let Some(ref lk) = config.channels_config.lark {
         if lk.use_feishu {
@@ -4367,8 +4752,13 @@ pub async fn start_channels(config: Config) -> Result<()> {
         custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
         max_tokens_override: None,
         model_support_vision: config.model_support_vision,
+        azure_openai: config.provider.azure_openai.clone(),
     };
-    let provider: Arc<dyn Provider> = Arc::from(
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let response_cache =
+        memory::create_response_cache(&config.memory, &config.workspace_dir).map(Arc::new);
+    let provider: Arc<dyn Provider> = Arc::from(providers::caching::wrap_with_response_cache(
         create_resilient_provider_nonblocking(
             &provider_name,
             config.api_key.clone(),
@@ -4377,7 +4767,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
             provider_runtime_options.clone(),
         )
         .await?,
-    );
+        response_cache.as_ref(),
+        &config,
+        Arc::clone(&observer),
+    ));
 
     // Warm up the provider connection pool (TLS handshake, DNS, HTTP/2 setup)
     // so the first real message doesn't hit a cold-start timeout.
@@ -4399,8 +4792,6 @@ pub async fn start_channels(config: Config) -> Result<()> {
         );
     }
 
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -4414,6 +4805,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
+        &config.reliability,
     )?);
     let (composio_key, composio_entity_id) = if config.composio.enabled {
         (
@@ -4425,7 +4817,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let mut built_tools = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
@@ -4439,7 +4831,9 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
-    ));
+    );
+    built_tools.extend(tools::mcp_tools(&config.mcp, &security).await);
+    let tools_registry = Arc::new(built_tools);
 
     let skills = crate::skills::load_skills_with_config(&workspace, &config);
 
@@ -4653,6 +5047,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .as_ref()
         .is_some_and(|tg| tg.interrupt_on_new_message);
 
+    let (session_store, restored_histories) = if config.channels_config.persist_sessions {
+        open_channel_session_store(&config.workspace_dir)
+    } else {
+        (None, HashMap::new())
+    };
+    if !restored_histories.is_empty() {
+        println!(
+            "  💾 Restored {} conversation session(s)",
+            restored_histories.len()
+        );
+    }
+
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
         provider: Arc::clone(&provider),
@@ -4666,7 +5072,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
         auto_save_memory: config.memory.auto_save,
         max_tool_iterations: config.agent.max_tool_iterations,
         min_relevance_score: config.memory.min_relevance_score,
-        conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+        memory_namespaces: config.memory.namespaces.clone(),
+        memory_reranker: memory::reranker::create_memory_reranker(&config).map(Arc::new),
+        conversation_histories: Arc::new(Mutex::new(restored_histories)),
+        session_store,
         provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
         route_overrides: Arc::new(Mutex::new(HashMap::new())),
         api_key: config.api_key.clone(),
@@ -4691,7 +5100,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
         )),
         query_classification: config.query_classification.clone(),
         model_routes: config.model_routes.clone(),
-        approval_manager: Arc::new(ApprovalManager::from_config(&config.autonomy)),
+        approval_manager: Arc::new(
+            ApprovalManager::from_config(&config.autonomy)
+                .with_workspace_dir(&config.workspace_dir),
+        ),
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
@@ -4802,9 +5214,53 @@ mod tests {
             parse_runtime_command("slack", "/approvals"),
             Some(ChannelRuntimeCommand::ListApprovals)
         );
+        assert_eq!(
+            parse_runtime_command("slack", "/approve-decide apr-deadbeef once"),
+            Some(ChannelRuntimeCommand::DecideToolApproval(
+                "apr-deadbeef once".to_string()
+            ))
+        );
         assert_eq!(parse_runtime_command("slack", "/models"), None);
     }
 
+    #[test]
+    fn build_approval_prompt_offers_once_session_and_deny() {
+        let req = PendingNonCliApprovalRequest {
+            request_id: "apr-deadbeef".to_string(),
+            tool_name: "shell".to_string(),
+            requested_by: "alice".to_string(),
+            requested_channel: "telegram".to_string(),
+            requested_reply_target: "chat-1".to_string(),
+            reason: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            expires_at: "2026-01-01T00:30:00Z".to_string(),
+        };
+
+        let prompt = build_approval_prompt(&req, "chat-1", None);
+        let values: Vec<&str> = prompt.actions.iter().map(|a| a.value.as_str()).collect();
+        assert_eq!(prompt.recipient, "chat-1");
+        assert_eq!(
+            values,
+            vec![
+                "/approve-decide apr-deadbeef once",
+                "/approve-decide apr-deadbeef session",
+                "/approve-decide apr-deadbeef deny",
+            ]
+        );
+
+        let all_tools = PendingNonCliApprovalRequest {
+            tool_name: APPROVAL_ALL_TOOLS_ONCE_TOKEN.to_string(),
+            ..req
+        };
+        let prompt = build_approval_prompt(&all_tools, "chat-1", None);
+        assert_eq!(prompt.actions.len(), 2);
+        assert_eq!(
+            parse_approval_decision("SESSION"),
+            Some(ApprovalResponse::Always)
+        );
+        assert_eq!(parse_approval_decision("maybe"), None);
+    }
+
     #[test]
     fn parse_runtime_command_supports_natural_language_approval_intents() {
         assert_eq!(
@@ -4988,7 +5444,10 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5042,7 +5501,10 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5074,6 +5536,70 @@ mod tests {
         assert_eq!(turns[0].content, "hello");
     }
 
+    #[test]
+    fn sender_history_persists_and_restores_across_restart() {
+        let tmp = TempDir::new().unwrap();
+        let sender = "telegram_u9".to_string();
+        let (store, restored) = open_channel_session_store(tmp.path());
+        assert!(restored.is_empty());
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: store,
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(tmp.path().to_path_buf()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
+            query_classification: crate::config::QueryClassificationConfig::default(),
+            model_routes: Vec::new(),
+            approval_manager: Arc::new(ApprovalManager::from_config(
+                &crate::config::AutonomyConfig::default(),
+            )),
+        };
+
+        for i in 0..(MAX_CHANNEL_HISTORY + 5) {
+            append_sender_turn(&ctx, &sender, ChatMessage::user(format!("q{i}")));
+            append_sender_turn(&ctx, &sender, ChatMessage::assistant(format!("a{i}")));
+        }
+        append_sender_turn(&ctx, &sender, ChatMessage::user("orphan"));
+        assert!(rollback_orphan_user_turn(&ctx, &sender, "orphan"));
+        append_sender_turn(&ctx, "slack_gone", ChatMessage::user("bye"));
+        clear_sender_history(&ctx, "slack_gone");
+        drop(ctx);
+
+        let (_store, restored) = open_channel_session_store(tmp.path());
+        assert_eq!(restored.len(), 1);
+        let turns = &restored[&sender];
+        // Trimming left a leading assistant turn, which normalization drops.
+        assert_eq!(turns.len(), MAX_CHANNEL_HISTORY - 2);
+        assert_eq!(turns[0].role, "user");
+        assert_eq!(
+            turns.last().unwrap().content,
+            format!("a{}", MAX_CHANNEL_HISTORY + 4)
+        );
+    }
+
     #[test]
     fn rollback_orphan_user_turn_removes_only_latest_matching_user_turn() {
         let sender = "telegram_u3".to_string();
@@ -5099,7 +5625,10 @@ mod tests {
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5697,7 +6226,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5727,6 +6259,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -5774,7 +6307,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5804,6 +6340,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -5817,6 +6354,145 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(!sent_messages[0].contains("mock_price"));
     }
 
+    #[derive(Default)]
+    struct PromptRecordingChannel {
+        sent_messages: tokio::sync::Mutex<Vec<String>>,
+        prompts: tokio::sync::Mutex<Vec<traits::InteractivePrompt>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for PromptRecordingChannel {
+        fn name(&self) -> &str {
+            "prompt-channel"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.sent_messages
+                .lock()
+                .await
+                .push(format!("{}:{}", message.recipient, message.content));
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn supports_interactive_prompts(&self) -> bool {
+            true
+        }
+
+        async fn send_interactive_prompt(
+            &self,
+            prompt: &traits::InteractivePrompt,
+        ) -> anyhow::Result<()> {
+            self.prompts.lock().await.push(prompt.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_prompts_for_blocked_tool_and_scopes_once_grant() {
+        let channel_impl = Arc::new(PromptRecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let approval_manager = Arc::new(ApprovalManager::from_config(
+            &crate::config::AutonomyConfig::default(),
+        ));
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(ToolCallingProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 10,
+            min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Mutex::new(Vec::new())),
+            query_classification: crate::config::QueryClassificationConfig::default(),
+            model_routes: Vec::new(),
+            approval_manager: Arc::clone(&approval_manager),
+            multimodal: crate::config::MultimodalConfig::default(),
+            hooks: None,
+        });
+        let message = |content: &str| traits::ChannelMessage {
+            id: "msg-1".to_string(),
+            sender: "alice".to_string(),
+            reply_target: "chat-42".to_string(),
+            content: content.to_string(),
+            channel: "prompt-channel".to_string(),
+            timestamp: 1,
+            thread_ts: None,
+            attachments: Vec::new(),
+        };
+
+        process_channel_message(
+            Arc::clone(&runtime_ctx),
+            message("What is the BTC price now?"),
+            CancellationToken::new(),
+        )
+        .await;
+
+        let request_id = {
+            let prompts = channel_impl.prompts.lock().await;
+            assert_eq!(prompts.len(), 1, "blocked call should open one prompt");
+            assert_eq!(prompts[0].recipient, "chat-42");
+            assert!(prompts[0].text.contains("mock_price"));
+            let pending = approval_manager.list_non_cli_pending_requests(
+                Some("alice"),
+                Some("prompt-channel"),
+                Some("chat-42"),
+            );
+            assert_eq!(pending.len(), 1);
+            assert_eq!(
+                prompts[0].actions[0].value,
+                format!("/approve-decide {} once", pending[0].request_id)
+            );
+            pending[0].request_id.clone()
+        };
+
+        process_channel_message(
+            runtime_ctx,
+            message(&format!("/approve-decide {request_id} once")),
+            CancellationToken::new(),
+        )
+        .await;
+
+        for other in [
+            NonCliRequester::new("bob", "prompt-channel", "chat-42"),
+            NonCliRequester::new("alice", "prompt-channel", "chat-7"),
+        ] {
+            assert!(!approval_manager.take_non_cli_tool_grant("mock_price", Some(&other)));
+        }
+        assert!(approval_manager.take_non_cli_tool_grant(
+            "mock_price",
+            Some(&NonCliRequester::new("alice", "prompt-channel", "chat-42"))
+        ));
+    }
+
     #[tokio::test]
     async fn process_channel_message_telegram_does_not_persist_tool_summary_prefix() {
         let channel_impl = Arc::new(TelegramRecordingChannel::default());
@@ -5838,7 +6514,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5868,6 +6547,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -5916,7 +6596,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -5946,6 +6629,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -5993,7 +6677,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6023,6 +6710,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "draft-streaming-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6062,7 +6750,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6092,6 +6783,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6126,7 +6818,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6156,6 +6851,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6199,7 +6895,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6229,6 +6928,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6299,7 +6999,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6342,6 +7045,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6434,7 +7138,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6471,6 +7178,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6544,7 +7252,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6575,6 +7286,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6649,7 +7361,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6680,6 +7395,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6744,7 +7460,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6775,6 +7494,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6809,6 +7529,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6883,7 +7604,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -6914,6 +7638,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -6948,6 +7673,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7028,7 +7754,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7059,6 +7788,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7143,7 +7873,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7174,6 +7907,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7238,7 +7972,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7269,6 +8006,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7298,6 +8036,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7355,7 +8094,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7383,6 +8125,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7411,6 +8154,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7470,7 +8214,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(route_overrides)),
             api_key: None,
@@ -7500,6 +8247,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7546,7 +8294,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7576,6 +8327,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7634,7 +8386,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7667,6 +8422,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 4,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7774,7 +8530,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: Some("http://127.0.0.1:11434".to_string()),
@@ -7874,7 +8633,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 12,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7904,6 +8666,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -7939,7 +8702,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 3,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -7969,6 +8735,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -8065,6 +8832,8 @@ BTC is currently around $65,000 based on latest tool output."#
                 timestamp: "2026-02-20T00:00:00Z".to_string(),
                 session_id: None,
                 score: Some(0.9),
+                metadata: crate::memory::MemoryMetadata::default(),
+                namespace: crate::memory::DEFAULT_NAMESPACE.into(),
             }])
         }
 
@@ -8116,7 +8885,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8145,6 +8917,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
         })
         .await
         .unwrap();
@@ -8156,6 +8929,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
         })
         .await
         .unwrap();
@@ -8201,7 +8975,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8231,6 +9008,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             })
             .await
             .unwrap();
@@ -8243,6 +9021,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             })
             .await
             .unwrap();
@@ -8298,7 +9077,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8328,6 +9110,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             })
             .await
             .unwrap();
@@ -8340,6 +9123,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             })
             .await
             .unwrap();
@@ -8377,7 +9161,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8407,6 +9194,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -8441,7 +9229,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 10,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8471,6 +9262,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -8847,6 +9639,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
         };
 
         assert_eq!(conversation_memory_key(&msg), "slack_U123_msg_abc123");
@@ -8862,6 +9655,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -8871,6 +9665,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
         };
 
         assert_ne!(
@@ -8892,6 +9687,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -8901,6 +9697,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            attachments: Vec::new(),
         };
 
         mem.store(
@@ -8934,7 +9731,7 @@ BTC is currently around $65,000 based on latest tool output."#
             .await
             .unwrap();
 
-        let context = build_memory_context(&mem, "age", 0.0).await;
+        let context = build_memory_context(&mem, None, "age", 0.0, None).await;
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("Age is 45"));
     }
@@ -8962,7 +9759,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -8992,6 +9792,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9007,6 +9808,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9052,7 +9854,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9082,6 +9887,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9142,7 +9948,10 @@ BTC is currently around $65,000 based on latest tool output."#
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(histories)),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9172,6 +9981,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9773,7 +10583,10 @@ BTC is currently around $65,000 based on latest tool output."#;
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9804,6 +10617,7 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9844,7 +10658,10 @@ BTC is currently around $65,000 based on latest tool output."#;
             auto_save_memory: false,
             max_tool_iterations: 5,
             min_relevance_score: 0.0,
+            memory_namespaces: crate::config::MemoryNamespaceConfig::default(),
+            memory_reranker: None,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_store: None,
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -9874,6 +10691,7 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -9889,6 +10707,7 @@ BTC is currently around $65,000 based on latest tool output."#;
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                attachments: Vec::new(),
             },
             CancellationToken::new(),
         )
@@ -0,0 +1,525 @@
+//! Persistent channel conversation sessions.
+//!
+//! The channel runtime keeps per-sender history in memory for fast access and
+//! writes every change through to a SQLite database in the workspace
+//! (`sessions/sessions.db`) so conversations survive daemon restarts.
+//! Turn content is encrypted when `[memory] encrypt_at_rest` is enabled.
+
+use crate::config::Config;
+use crate::memory::encryption::{self, MemoryCipher};
+use crate::providers::ChatMessage;
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use console::style;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Summary row for `zeroclaw sessions list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub key: String,
+    pub turns: usize,
+    pub updated_at: String,
+}
+
+/// One exported session.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExport {
+    pub key: String,
+    pub updated_at: String,
+    pub turns: Vec<ChatMessage>,
+}
+
+/// SQLite-backed store of channel conversation turns, keyed by the channel
+/// runtime's conversation history key (`<channel>_[<thread>_]<sender>`).
+pub struct SessionStore {
+    conn: Mutex<Connection>,
+    db_path: PathBuf,
+    cipher: Option<Arc<MemoryCipher>>,
+    /// Last handed-out snapshot revision (see [`Self::next_revision`]).
+    revision: AtomicU64,
+    /// Newest revision written per key; guards against stale snapshots.
+    written: Mutex<HashMap<String, u64>>,
+}
+
+impl SessionStore {
+    /// Open (or create) the session database under `workspace_dir`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = workspace_dir.join("sessions").join("sessions.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(&db_path).context("SQLite failed to open session database")?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             PRAGMA foreign_keys = ON;",
+        )?;
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+            cipher: None,
+            revision: AtomicU64::new(0),
+            written: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Encrypt stored turn content with `cipher` (see [`MemoryCipher`]).
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Arc<MemoryCipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    fn init_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                key         TEXT PRIMARY KEY,
+                created_at  TEXT NOT NULL,
+                updated_at  TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS session_turns (
+                session_key TEXT NOT NULL REFERENCES sessions(key) ON DELETE CASCADE,
+                seq         INTEGER NOT NULL,
+                role        TEXT NOT NULL,
+                content     TEXT NOT NULL,
+                PRIMARY KEY (session_key, seq)
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Path of the backing database file.
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Replace the stored turns of `key`. An empty slice deletes the session.
+    pub fn save(&self, key: &str, turns: &[ChatMessage]) -> Result<()> {
+        if turns.is_empty() {
+            self.delete(key)?;
+            return Ok(());
+        }
+
+        let now = Local::now().to_rfc3339();
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO sessions (key, created_at, updated_at) VALUES (?1, ?2, ?2)
+             ON CONFLICT(key) DO UPDATE SET updated_at = excluded.updated_at",
+            params![key, now],
+        )?;
+        tx.execute(
+            "DELETE FROM session_turns WHERE session_key = ?1",
+            params![key],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO session_turns (session_key, seq, role, content)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (seq, turn) in turns.iter().enumerate() {
+                let content = encryption::encrypt_field(self.cipher.as_deref(), &turn.content)?;
+                insert.execute(params![key, seq as i64, turn.role, content])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Reserve a revision for a history snapshot. Take it while the snapshot
+    /// is consistent (under the caller's history lock).
+    pub fn next_revision(&self) -> u64 {
+        self.revision.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// [`Self::save`] a snapshot taken at `revision`, unless a newer snapshot
+    /// of `key` was already written. Returns whether the write happened.
+    pub fn save_revision(&self, key: &str, revision: u64, turns: &[ChatMessage]) -> Result<bool> {
+        let mut written = self.written.lock();
+        if written.get(key).is_some_and(|&last| last >= revision) {
+            return Ok(false);
+        }
+        self.save(key, turns)?;
+        written.insert(key.to_string(), revision);
+        Ok(true)
+    }
+
+    /// Load the turns of one session, oldest first.
+    pub fn load(&self, key: &str) -> Result<Option<Vec<ChatMessage>>> {
+        let conn = self.conn.lock();
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM sessions WHERE key = ?1",
+                params![key],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !exists {
+            return Ok(None);
+        }
+        Ok(Some(self.load_turns(&conn, key)?))
+    }
+
+    /// Load every stored session, keyed by conversation history key.
+    pub fn load_all(&self) -> Result<HashMap<String, Vec<ChatMessage>>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT session_key, role, content FROM session_turns
+             ORDER BY session_key, seq",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ChatMessage {
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                },
+            ))
+        })?;
+
+        let mut sessions: HashMap<String, Vec<ChatMessage>> = HashMap::new();
+        for row in rows {
+            let (key, mut turn) = row?;
+            turn.content = encryption::decrypt_field(self.cipher.as_deref(), &turn.content)?;
+            sessions.entry(key).or_default().push(turn);
+        }
+        Ok(sessions)
+    }
+
+    /// List stored sessions, most recently updated first.
+    pub fn list(&self) -> Result<Vec<SessionSummary>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.key, s.updated_at, COUNT(t.seq)
+             FROM sessions s LEFT JOIN session_turns t ON t.session_key = s.key
+             GROUP BY s.key
+             ORDER BY s.updated_at DESC, s.key",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SessionSummary {
+                key: row.get(0)?,
+                updated_at: row.get(1)?,
+                turns: usize::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    /// Delete one session. Returns whether it existed.
+    pub fn delete(&self, key: &str) -> Result<bool> {
+        let conn = self.conn.lock();
+        let removed = conn.execute("DELETE FROM sessions WHERE key = ?1", params![key])?;
+        Ok(removed > 0)
+    }
+
+    /// Export one or all sessions with their turns.
+    pub fn export(&self, key: Option<&str>) -> Result<Vec<SessionExport>> {
+        let summaries = self.list()?;
+        let conn = self.conn.lock();
+        summaries
+            .into_iter()
+            .filter(|summary| key.is_none_or(|key| summary.key == key))
+            .map(|summary| {
+                Ok(SessionExport {
+                    turns: self.load_turns(&conn, &summary.key)?,
+                    key: summary.key,
+                    updated_at: summary.updated_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Rewrite every turn under the cipher's active key, or as plaintext when
+    /// the cipher is decrypt-only or absent. Returns the number of turns.
+    pub fn rekey(&self) -> Result<usize> {
+        let cipher = self.cipher.as_deref();
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let turns: Vec<(String, i64, String)> = {
+            let mut stmt = tx.prepare("SELECT session_key, seq, content FROM session_turns")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        {
+            let mut update = tx.prepare(
+                "UPDATE session_turns SET content = ?1 WHERE session_key = ?2 AND seq = ?3",
+            )?;
+            for (key, seq, stored) in &turns {
+                let plaintext = encryption::decrypt_field(cipher, stored)?;
+                let content = encryption::encrypt_field(cipher, &plaintext)?;
+                update.execute(params![content, key, seq])?;
+            }
+        }
+        tx.commit()?;
+        Ok(turns.len())
+    }
+
+    fn load_turns(&self, conn: &Connection, key: &str) -> Result<Vec<ChatMessage>> {
+        let mut stmt = conn.prepare(
+            "SELECT role, content FROM session_turns WHERE session_key = ?1 ORDER BY seq",
+        )?;
+        let rows = stmt.query_map(params![key], |row| {
+            Ok(ChatMessage {
+                role: row.get(0)?,
+                content: row.get(1)?,
+            })
+        })?;
+        rows.map(|row| {
+            let mut turn = row?;
+            turn.content = encryption::decrypt_field(self.cipher.as_deref(), &turn.content)?;
+            Ok(turn)
+        })
+        .collect()
+    }
+
+    /// Resolve an exact key or a unique key prefix.
+    fn resolve_key(&self, key: &str) -> Result<String> {
+        let summaries = self.list()?;
+        if summaries.iter().any(|summary| summary.key == key) {
+            return Ok(key.to_string());
+        }
+        let matches: Vec<_> = summaries
+            .iter()
+            .filter(|summary| summary.key.starts_with(key))
+            .collect();
+        match matches.len() {
+            0 => bail!("No session found for key: {key}"),
+            1 => Ok(matches[0].key.clone()),
+            n => {
+                let keys: Vec<_> = matches.iter().map(|s| s.key.as_str()).collect();
+                bail!(
+                    "Prefix '{key}' matched {n} sessions: {}. Specify a longer prefix.",
+                    keys.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Handle `zeroclaw sessions <subcommand>` CLI commands.
+pub fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    let store =
+        SessionStore::open(&config.workspace_dir)?.with_cipher(encryption::runtime_cipher()?);
+    match command {
+        crate::SessionCommands::List => handle_list(&store),
+        crate::SessionCommands::Show { key } => handle_show(&store, &key),
+        crate::SessionCommands::Export { key, output } => {
+            handle_export(&store, key.as_deref(), output.as_deref())
+        }
+        crate::SessionCommands::Delete { key, yes } => handle_delete(&store, &key, yes),
+    }
+}
+
+fn handle_list(store: &SessionStore) -> Result<()> {
+    let sessions = store.list()?;
+    if sessions.is_empty() {
+        println!("No channel sessions stored.");
+        return Ok(());
+    }
+
+    println!(
+        "Channel sessions ({} in {}):\n",
+        sessions.len(),
+        store.db_path().display()
+    );
+    for session in &sessions {
+        println!(
+            "- {} ({} turns, updated {})",
+            style(&session.key).white().bold(),
+            session.turns,
+            session.updated_at,
+        );
+    }
+    Ok(())
+}
+
+fn handle_show(store: &SessionStore, key: &str) -> Result<()> {
+    let key = store.resolve_key(key)?;
+    let turns = store.load(&key)?.unwrap_or_default();
+
+    println!("Session: {}\n", style(&key).white().bold());
+    for turn in &turns {
+        println!("[{}] {}", style(&turn.role).cyan(), turn.content);
+    }
+    Ok(())
+}
+
+fn handle_export(store: &SessionStore, key: Option<&str>, output: Option<&Path>) -> Result<()> {
+    let key = key.map(|key| store.resolve_key(key)).transpose()?;
+    let sessions = store.export(key.as_deref())?;
+    let json = serde_json::to_string_pretty(&sessions)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "{} Exported {} session(s) to {}",
+                style("✓").green().bold(),
+                sessions.len(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+fn handle_delete(store: &SessionStore, key: &str, yes: bool) -> Result<()> {
+    let key = store.resolve_key(key)?;
+
+    if !yes {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("  Delete session '{key}'?"))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    if store.delete(&key)? {
+        println!("{} Deleted session: {key}", style("✓").green().bold());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_revision_skips_stale_snapshots() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let older = store.next_revision();
+        let newer = store.next_revision();
+
+        assert!(store
+            .save_revision("telegram_alice", newer, &[ChatMessage::user("new")])
+            .unwrap());
+        assert!(!store
+            .save_revision("telegram_alice", older, &[ChatMessage::user("old")])
+            .unwrap());
+        assert!(store
+            .save_revision("slack_bob", older, &[ChatMessage::user("other")])
+            .unwrap());
+
+        let turns = store.load("telegram_alice").unwrap().unwrap();
+        assert_eq!(turns[0].content, "new");
+    }
+
+    #[test]
+    fn save_load_and_replace_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+
+        store
+            .save(
+                "telegram_alice",
+                &[ChatMessage::user("hi"), ChatMessage::assistant("hello")],
+            )
+            .unwrap();
+        store
+            .save("slack_bob", &[ChatMessage::user("ping")])
+            .unwrap();
+        store
+            .save("telegram_alice", &[ChatMessage::user("again")])
+            .unwrap();
+
+        // Reopen to prove the data is on disk.
+        drop(store);
+        let store = SessionStore::open(tmp.path()).unwrap();
+        let all = store.load_all().unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["telegram_alice"].len(), 1);
+        assert_eq!(all["telegram_alice"][0].content, "again");
+        assert_eq!(store.load("missing").unwrap().map(|t| t.len()), None);
+
+        let summaries = store.list().unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert!(summaries
+            .iter()
+            .any(|s| s.key == "slack_bob" && s.turns == 1));
+    }
+
+    #[test]
+    fn encrypted_turns_round_trip_and_rekey() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = Arc::new(MemoryCipher::open(tmp.path(), true).unwrap());
+        let store = SessionStore::open(tmp.path())
+            .unwrap()
+            .with_cipher(Some(cipher.clone()));
+        store
+            .save("telegram_alice", &[ChatMessage::user("my pin is 4321")])
+            .unwrap();
+
+        let raw: String = store
+            .conn
+            .lock()
+            .query_row("SELECT content FROM session_turns", [], |row| row.get(0))
+            .unwrap();
+        assert!(MemoryCipher::is_encrypted(&raw));
+        assert_eq!(
+            store.load("telegram_alice").unwrap().unwrap()[0].content,
+            "my pin is 4321"
+        );
+
+        // Rekey to plaintext with a decrypt-only cipher.
+        let reader = Arc::new(MemoryCipher::open(tmp.path(), false).unwrap());
+        let store = store.with_cipher(Some(reader));
+        assert_eq!(store.rekey().unwrap(), 1);
+        let plain = SessionStore::open(tmp.path()).unwrap();
+        assert_eq!(
+            plain.load_all().unwrap()["telegram_alice"][0].content,
+            "my pin is 4321"
+        );
+    }
+
+    #[test]
+    fn delete_and_empty_save_remove_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        store.save("a", &[ChatMessage::user("x")]).unwrap();
+        store.save("b", &[ChatMessage::user("y")]).unwrap();
+
+        assert!(store.delete("a").unwrap());
+        assert!(!store.delete("a").unwrap());
+        store.save("b", &[]).unwrap();
+        assert!(store.load_all().unwrap().is_empty());
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn resolve_key_accepts_unique_prefix_only() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path()).unwrap();
+        store
+            .save("telegram_alice", &[ChatMessage::user("x")])
+            .unwrap();
+        store
+            .save("telegram_bob", &[ChatMessage::user("y")])
+            .unwrap();
+
+        assert_eq!(store.resolve_key("telegram_a").unwrap(), "telegram_alice");
+        assert!(store.resolve_key("telegram_").is_err());
+        assert!(store.resolve_key("slack").is_err());
+
+        let export = store.export(Some("telegram_bob")).unwrap();
+        assert_eq!(export.len(), 1);
+        assert_eq!(export[0].turns[0].content, "y");
+    }
+}
@@ -1,8 +1,11 @@
-use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::channels::attachments::{load_outgoing, mime_from_name, MAX_INBOUND_ATTACHMENT_BYTES};
+use crate::channels::traits::{Attachment, AttachmentKind, Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::Deserialize;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
@@ -28,6 +31,7 @@ pub struct SignalChannel {
     allowed_from: Vec<String>,
     ignore_attachments: bool,
     ignore_stories: bool,
+    workspace_dir: Option<PathBuf>,
 }
 
 // ── signal-cli SSE event JSON shapes ────────────────────────────
@@ -64,6 +68,23 @@ struct DataMessage {
     attachments: Option<Vec<serde_json::Value>>,
 }
 
+/// Attachment metadata as reported in `dataMessage.attachments`.
+#[derive(Debug, Deserialize)]
+struct SignalAttachment {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(rename = "contentType", default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    caption: Option<String>,
+    #[serde(rename = "voiceNote", default)]
+    voice_note: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct GroupInfo {
     #[serde(rename = "groupId", default)]
@@ -87,9 +108,16 @@ impl SignalChannel {
             allowed_from,
             ignore_attachments,
             ignore_stories,
+            workspace_dir: None,
         }
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn http_client(&self) -> Client {
         let builder = Client::builder().connect_timeout(Duration::from_secs(10));
         let builder = crate::config::apply_runtime_proxy_to_builder(builder, "channel.signal");
@@ -215,6 +243,113 @@ impl SignalChannel {
         Ok(parsed.get("result").cloned())
     }
 
+    fn attachment_metadata(data_msg: &DataMessage) -> Vec<SignalAttachment> {
+        data_msg
+            .attachments
+            .iter()
+            .flatten()
+            .filter_map(|value| serde_json::from_value(value.clone()).ok())
+            .filter(|attachment: &SignalAttachment| attachment.id.is_some())
+            .collect()
+    }
+
+    /// Fetch attachment bytes from the daemon with `getAttachment`, which
+    /// works whether or not signal-cli shares a filesystem with us.
+    async fn fetch_attachments(
+        &self,
+        data_msg: &DataMessage,
+        reply_target: &str,
+    ) -> Vec<Attachment> {
+        let mut attachments = Vec::new();
+        for meta in Self::attachment_metadata(data_msg) {
+            let Some(id) = meta.id.as_deref() else {
+                continue;
+            };
+            if meta
+                .size
+                .is_some_and(|size| size > MAX_INBOUND_ATTACHMENT_BYTES)
+            {
+                tracing::warn!("Signal: attachment {id} too large, skipping");
+                continue;
+            }
+
+            let mut params = serde_json::json!({
+                "account": &self.account,
+                "id": id,
+            });
+            match Self::parse_recipient_target(reply_target) {
+                RecipientTarget::Direct(number) => params["recipient"] = serde_json::json!(number),
+                RecipientTarget::Group(group_id) => params["groupId"] = serde_json::json!(group_id),
+            }
+
+            let encoded = match self.rpc_request("getAttachment", params).await {
+                Ok(Some(result)) => result
+                    .get("data")
+                    .and_then(|d| d.as_str())
+                    .or_else(|| result.as_str())
+                    .map(str::to_string),
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!("Signal: getAttachment {id} failed: {e}");
+                    continue;
+                }
+            };
+            let Some(bytes) = encoded.and_then(|data| STANDARD.decode(data).ok()) else {
+                tracing::warn!("Signal: getAttachment {id} returned no data");
+                continue;
+            };
+
+            let mime = meta
+                .content_type
+                .clone()
+                .or_else(|| {
+                    meta.filename
+                        .as_deref()
+                        .map(|n| mime_from_name(n).to_string())
+                })
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            let kind = if meta.voice_note {
+                AttachmentKind::Voice
+            } else {
+                AttachmentKind::from_mime(&mime)
+            };
+            let mut attachment = Attachment::from_bytes(kind, bytes).with_mime(mime);
+            if let Some(name) = meta.filename {
+                attachment = attachment.with_filename(name);
+            }
+            if let Some(caption) = meta.caption {
+                attachment = attachment.with_caption(caption);
+            }
+            attachments.push(attachment);
+        }
+        attachments
+    }
+
+    /// Build the channel message for an envelope, downloading its attachments.
+    async fn receive_envelope(&self, envelope: &Envelope) -> Option<ChannelMessage> {
+        let mut msg = self.process_envelope(envelope)?;
+        if !self.ignore_attachments {
+            if let Some(data_msg) = envelope.data_message.as_ref() {
+                msg.attachments = self.fetch_attachments(data_msg, &msg.reply_target).await;
+            }
+        }
+        if msg.content.is_empty() && msg.attachments.is_empty() {
+            return None;
+        }
+        Some(msg)
+    }
+
+    /// Encode an outgoing attachment as the data URI signal-cli accepts in
+    /// `send.attachments`.
+    fn attachment_data_uri(attachment: &Attachment, bytes: &[u8]) -> String {
+        format!(
+            "data:{};filename={};base64,{}",
+            attachment.mime_or_guess(),
+            attachment.file_name_or_default(),
+            STANDARD.encode(bytes)
+        )
+    }
+
     /// Process a single SSE envelope, returning a ChannelMessage if valid.
     fn process_envelope(&self, envelope: &Envelope) -> Option<ChannelMessage> {
         // Skip story messages when configured
@@ -232,7 +367,13 @@ impl SignalChannel {
             }
         }
 
-        let text = data_msg.message.as_deref().filter(|t| !t.is_empty())?;
+        let has_attachments =
+            !self.ignore_attachments && !Self::attachment_metadata(data_msg).is_empty();
+        let text = match data_msg.message.as_deref().filter(|t| !t.is_empty()) {
+            Some(text) => text,
+            None if has_attachments => "",
+            None => return None,
+        };
         let sender = Self::sender(envelope)?;
 
         if !self.is_sender_allowed(&sender) {
@@ -266,6 +407,7 @@ impl SignalChannel {
             channel: "signal".to_string(),
             timestamp: timestamp / 1000, // millis → secs
             thread_ts: None,
+            attachments: Vec::new(),
         })
     }
 }
@@ -277,19 +419,31 @@ impl Channel for SignalChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let params = match Self::parse_recipient_target(&message.recipient) {
+        let (text, attachments) = message.split_attachments();
+        let mut params = match Self::parse_recipient_target(&message.recipient) {
             RecipientTarget::Direct(number) => serde_json::json!({
                 "recipient": [number],
-                "message": &message.content,
+                "message": &text,
                 "account": &self.account,
             }),
             RecipientTarget::Group(group_id) => serde_json::json!({
                 "groupId": group_id,
-                "message": &message.content,
+                "message": &text,
                 "account": &self.account,
             }),
         };
 
+        if !attachments.is_empty() {
+            let mut encoded = Vec::with_capacity(attachments.len());
+            for attachment in &attachments {
+                let bytes =
+                    load_outgoing(attachment, self.workspace_dir.as_deref(), "channel.signal")
+                        .await?;
+                encoded.push(Self::attachment_data_uri(attachment, &bytes));
+            }
+            params["attachments"] = serde_json::json!(encoded);
+        }
+
         self.rpc_request("send", params).await?;
         Ok(())
     }
@@ -370,7 +524,7 @@ impl Channel for SignalChannel {
                             match serde_json::from_str::<SseEnvelope>(&current_data) {
                                 Ok(sse) => {
                                     if let Some(ref envelope) = sse.envelope {
-                                        if let Some(msg) = self.process_envelope(envelope) {
+                                        if let Some(msg) = self.receive_envelope(envelope).await {
                                             if tx.send(msg).await.is_err() {
                                                 return Ok(());
                                             }
@@ -397,7 +551,7 @@ impl Channel for SignalChannel {
                 match serde_json::from_str::<SseEnvelope>(&current_data) {
                     Ok(sse) => {
                         if let Some(ref envelope) = sse.envelope {
-                            if let Some(msg) = self.process_envelope(envelope) {
+                            if let Some(msg) = self.receive_envelope(envelope).await {
                                 let _ = tx.send(msg).await;
                             }
                         }
@@ -856,6 +1010,42 @@ mod tests {
         assert!(ch.process_envelope(&env).is_none());
     }
 
+    #[test]
+    fn process_envelope_accepts_attachment_only_when_enabled() {
+        let ch = make_channel();
+        let env = Envelope {
+            source: Some("+1111111111".to_string()),
+            source_number: Some("+1111111111".to_string()),
+            data_message: Some(DataMessage {
+                message: None,
+                timestamp: Some(1_700_000_000_000),
+                group_info: None,
+                attachments: Some(vec![serde_json::json!({
+                    "id": "abc123.png",
+                    "contentType": "image/png",
+                    "size": 42
+                })]),
+            }),
+            story_message: None,
+            timestamp: Some(1_700_000_000_000),
+        };
+        let msg = ch.process_envelope(&env).unwrap();
+        assert!(msg.content.is_empty());
+
+        let meta = SignalChannel::attachment_metadata(env.data_message.as_ref().unwrap());
+        assert_eq!(meta.len(), 1);
+        assert_eq!(meta[0].content_type.as_deref(), Some("image/png"));
+        assert_eq!(meta[0].size, Some(42));
+    }
+
+    #[test]
+    fn attachment_data_uri_includes_mime_and_filename() {
+        let attachment = Attachment::from_bytes(AttachmentKind::Document, b"hi".to_vec())
+            .with_filename("notes.txt");
+        let uri = SignalChannel::attachment_data_uri(&attachment, b"hi");
+        assert_eq!(uri, "data:text/plain;filename=notes.txt;base64,aGk=");
+    }
+
     #[test]
     fn sse_envelope_deserializes() {
         let json = r#"{
@@ -1,28 +1,55 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachments::{fetch_bytes, load_outgoing, MAX_INBOUND_ATTACHMENT_BYTES};
+use super::traits::{
+    Attachment, AttachmentKind, Channel, ChannelMessage, InteractivePrompt, PromptActionStyle,
+    SendMessage,
+};
 use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio_tungstenite::tungstenite::Message;
 
-/// Slack channel — polls conversations.history via Web API
+/// Slack channel — polls conversations.history via Web API.
+/// With an app-level token, button presses also arrive over Socket Mode.
 pub struct SlackChannel {
     bot_token: String,
+    app_token: Option<String>,
     channel_id: Option<String>,
     allowed_users: Vec<String>,
     mention_only: bool,
     group_reply_allowed_sender_ids: Vec<String>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl SlackChannel {
     pub fn new(bot_token: String, channel_id: Option<String>, allowed_users: Vec<String>) -> Self {
         Self {
             bot_token,
+            app_token: None,
             channel_id,
             allowed_users,
             mention_only: false,
             group_reply_allowed_sender_ids: Vec::new(),
+            workspace_dir: None,
         }
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    /// Configure the app-level token (`xapp-...`) that enables Socket Mode
+    /// for Block Kit button presses. Blank tokens are ignored.
+    pub fn with_app_token(mut self, app_token: Option<String>) -> Self {
+        self.app_token = app_token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        self
+    }
+
     /// Configure group-chat trigger policy.
     pub fn with_group_reply_policy(
         mut self,
@@ -242,6 +269,340 @@ impl SlackChannel {
         Ok(channels)
     }
 
+    /// Check a Slack Web API response, which reports most errors as HTTP 200
+    /// with `"ok": false`.
+    async fn parse_api_response(
+        resp: reqwest::Response,
+        method: &str,
+    ) -> anyhow::Result<serde_json::Value> {
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+
+        if !status.is_success() {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Slack {method} failed ({status}): {sanitized}");
+        }
+
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack {method} failed: {err}");
+        }
+        Ok(parsed)
+    }
+
+    async fn post_message(
+        &self,
+        channel: &str,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut body = serde_json::json!({
+            "channel": channel,
+            "text": text
+        });
+
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        Self::parse_api_response(resp, "chat.postMessage").await?;
+        Ok(())
+    }
+
+    /// Upload a file with the external upload flow
+    /// (`files.getUploadURLExternal` → POST bytes → `files.completeUploadExternal`).
+    async fn upload_file(
+        &self,
+        channel: &str,
+        thread_ts: Option<&str>,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let client = self.http_client();
+        let bytes =
+            load_outgoing(attachment, self.workspace_dir.as_deref(), "channel.slack").await?;
+        let filename = attachment.file_name_or_default();
+
+        let resp = client
+            .post("https://slack.com/api/files.getUploadURLExternal")
+            .bearer_auth(&self.bot_token)
+            .form(&[
+                ("filename", filename.clone()),
+                ("length", bytes.len().to_string()),
+            ])
+            .send()
+            .await?;
+        let ticket = Self::parse_api_response(resp, "files.getUploadURLExternal").await?;
+        let (Some(upload_url), Some(file_id)) = (
+            ticket.get("upload_url").and_then(|v| v.as_str()),
+            ticket.get("file_id").and_then(|v| v.as_str()),
+        ) else {
+            anyhow::bail!("Slack files.getUploadURLExternal returned no upload_url/file_id");
+        };
+
+        let upload = client
+            .post(upload_url)
+            .header(reqwest::header::CONTENT_TYPE, attachment.mime_or_guess())
+            .body(bytes)
+            .send()
+            .await?;
+        if !upload.status().is_success() {
+            anyhow::bail!("Slack file upload failed ({})", upload.status());
+        }
+
+        let mut complete = serde_json::json!({
+            "files": [{ "id": file_id, "title": filename }],
+            "channel_id": channel,
+        });
+        if let Some(ts) = thread_ts {
+            complete["thread_ts"] = serde_json::json!(ts);
+        }
+        if let Some(caption) = attachment.caption.as_deref() {
+            complete["initial_comment"] = serde_json::json!(caption);
+        }
+        let resp = client
+            .post("https://slack.com/api/files.completeUploadExternal")
+            .bearer_auth(&self.bot_token)
+            .json(&complete)
+            .send()
+            .await?;
+        Self::parse_api_response(resp, "files.completeUploadExternal").await?;
+        Ok(())
+    }
+
+    /// Download the files shared with a Slack message. `url_private` links
+    /// need the bot token, so they are fetched here rather than later.
+    async fn fetch_message_files(&self, msg: &serde_json::Value) -> Vec<Attachment> {
+        let mut attachments = Vec::new();
+        let Some(files) = msg.get("files").and_then(|f| f.as_array()) else {
+            return attachments;
+        };
+        let client = self.http_client();
+
+        for file in files {
+            let name = file.get("name").and_then(|v| v.as_str()).unwrap_or("file");
+            let Some(url) = file
+                .get("url_private_download")
+                .or_else(|| file.get("url_private"))
+                .and_then(|v| v.as_str())
+            else {
+                tracing::debug!(name, "Slack: shared file has no private URL, skipping");
+                continue;
+            };
+            if file
+                .get("size")
+                .and_then(serde_json::Value::as_u64)
+                .is_some_and(|size| size > MAX_INBOUND_ATTACHMENT_BYTES)
+            {
+                tracing::warn!(name, "Slack: shared file too large, skipping");
+                continue;
+            }
+            let mime = file
+                .get("mimetype")
+                .and_then(|v| v.as_str())
+                .unwrap_or_else(|| super::attachments::mime_from_name(name));
+            let kind = if file.get("subtype").and_then(|v| v.as_str()) == Some("slack_audio") {
+                AttachmentKind::Voice
+            } else {
+                AttachmentKind::from_mime(mime)
+            };
+
+            let request = client.get(url).bearer_auth(&self.bot_token);
+            match fetch_bytes(request, MAX_INBOUND_ATTACHMENT_BYTES).await {
+                Ok(bytes) => attachments.push(
+                    Attachment::from_bytes(kind, bytes)
+                        .with_filename(name)
+                        .with_mime(mime),
+                ),
+                Err(e) => tracing::warn!(name, "Slack: failed to download shared file: {e}"),
+            }
+        }
+        attachments
+    }
+
+    /// Render an [`InteractivePrompt`] as a section plus an actions block.
+    fn prompt_blocks(prompt: &InteractivePrompt) -> serde_json::Value {
+        let buttons: Vec<serde_json::Value> = prompt
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| {
+                let mut button = serde_json::json!({
+                    "type": "button",
+                    "text": { "type": "plain_text", "text": action.label },
+                    "value": action.value,
+                    "action_id": format!("zeroclaw_prompt_{idx}"),
+                });
+                match action.style {
+                    PromptActionStyle::Primary => button["style"] = "primary".into(),
+                    PromptActionStyle::Danger => button["style"] = "danger".into(),
+                    PromptActionStyle::Secondary => {}
+                }
+                button
+            })
+            .collect();
+
+        serde_json::json!([
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": prompt.text }
+            },
+            {
+                "type": "actions",
+                "elements": buttons
+            }
+        ])
+    }
+
+    /// Map a Socket Mode `block_actions` payload to an inbound message whose
+    /// content is the pressed button's value.
+    fn parse_block_action(&self, payload: &serde_json::Value) -> Option<ChannelMessage> {
+        if payload.get("type").and_then(|t| t.as_str()) != Some("block_actions") {
+            return None;
+        }
+
+        let user = payload
+            .get("user")
+            .and_then(|u| u.get("id"))
+            .and_then(|id| id.as_str())?;
+        if !self.is_user_allowed(user) {
+            tracing::warn!("Slack: ignoring button press from unauthorized user: {user}");
+            return None;
+        }
+
+        let container = payload.get("container");
+        let channel_id = payload
+            .get("channel")
+            .and_then(|c| c.get("id"))
+            .or_else(|| container.and_then(|c| c.get("channel_id")))
+            .and_then(|id| id.as_str())?;
+        if let Some(scoped) = self.configured_channel_id() {
+            if scoped != channel_id {
+                return None;
+            }
+        }
+
+        let action = payload
+            .get("actions")
+            .and_then(|a| a.as_array())
+            .and_then(|a| a.first())?;
+        let value = action.get("value").and_then(|v| v.as_str())?;
+        let action_ts = action
+            .get("action_ts")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default();
+        let thread_ts = container
+            .and_then(|c| c.get("thread_ts"))
+            .or_else(|| payload.get("message").and_then(|m| m.get("thread_ts")))
+            .and_then(|t| t.as_str())
+            .map(str::to_string);
+
+        Some(ChannelMessage {
+            id: format!("slack_{channel_id}_action_{action_ts}"),
+            sender: user.to_string(),
+            reply_target: channel_id.to_string(),
+            content: value.to_string(),
+            channel: "slack".to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts,
+            attachments: Vec::new(),
+        })
+    }
+
+    /// Keep a Socket Mode connection open, reconnecting when Slack asks or the
+    /// socket drops. Returns once the message receiver is gone.
+    async fn run_socket_mode(
+        &self,
+        app_token: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        loop {
+            match self.socket_mode_session(app_token, tx).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => tracing::debug!("Slack Socket Mode: reconnecting"),
+                Err(e) => {
+                    tracing::warn!("Slack Socket Mode error: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    }
+
+    /// Run one Socket Mode connection. `Ok(true)` means the receiver closed.
+    async fn socket_mode_session(
+        &self,
+        app_token: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<bool> {
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/apps.connections.open")
+            .bearer_auth(app_token)
+            .send()
+            .await?;
+        let opened = Self::parse_api_response(resp, "apps.connections.open").await?;
+        let url = opened
+            .get("url")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Slack apps.connections.open returned no url"))?;
+
+        let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
+        let (mut write, mut read) = ws_stream.split();
+        tracing::info!("Slack Socket Mode connected");
+
+        while let Some(frame) = read.next().await {
+            let text = match frame? {
+                Message::Text(t) => t,
+                Message::Close(_) => return Ok(false),
+                _ => continue,
+            };
+            let Ok(envelope) = serde_json::from_str::<serde_json::Value>(text.as_ref()) else {
+                continue;
+            };
+
+            // Every envelope must be acknowledged within three seconds.
+            if let Some(envelope_id) = envelope.get("envelope_id").and_then(|e| e.as_str()) {
+                let ack = serde_json::json!({ "envelope_id": envelope_id });
+                write.send(Message::Text(ack.to_string().into())).await?;
+            }
+
+            match envelope.get("type").and_then(|t| t.as_str()) {
+                Some("disconnect") => return Ok(false),
+                Some("interactive") => {
+                    let Some(msg) = envelope
+                        .get("payload")
+                        .and_then(|payload| self.parse_block_action(payload))
+                    else {
+                        continue;
+                    };
+                    if tx.send(msg).await.is_err() {
+                        return Ok(true);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(false)
+    }
+
     fn slack_now_ts() -> String {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -268,12 +629,33 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = message.split_attachments();
+        let thread_ts = message.thread_ts.as_deref();
+
+        if !text.is_empty() || attachments.is_empty() {
+            self.post_message(&message.recipient, &text, thread_ts)
+                .await?;
+        }
+
+        for attachment in &attachments {
+            self.upload_file(&message.recipient, thread_ts, attachment)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn supports_interactive_prompts(&self) -> bool {
+        self.app_token.is_some()
+    }
+
+    async fn send_interactive_prompt(&self, prompt: &InteractivePrompt) -> anyhow::Result<()> {
         let mut body = serde_json::json!({
-            "channel": message.recipient,
-            "text": message.content
+            "channel": prompt.recipient,
+            "text": prompt.text,
+            "blocks": Self::prompt_blocks(prompt),
         });
-
-        if let Some(ref ts) = message.thread_ts {
+        if let Some(ts) = prompt.thread_ts.as_deref() {
             body["thread_ts"] = serde_json::json!(ts);
         }
 
@@ -285,31 +667,39 @@ impl Channel for SlackChannel {
             .send()
             .await?;
 
-        let status = resp.status();
-        let body = resp
-            .text()
-            .await
-            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        Self::parse_api_response(resp, "chat.postMessage").await?;
+        Ok(())
+    }
 
-        if !status.is_success() {
-            let sanitized = crate::providers::sanitize_api_error(&body);
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {sanitized}");
-        }
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let Some(app_token) = self.app_token.as_deref() else {
+            return self.poll_history(&tx).await;
+        };
 
-        // Slack returns 200 for most app-level errors; check JSON "ok" field
-        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
-        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
-            let err = parsed
-                .get("error")
-                .and_then(|e| e.as_str())
-                .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
+        tokio::select! {
+            result = self.poll_history(&tx) => result,
+            result = self.run_socket_mode(app_token, &tx) => result,
         }
+    }
 
-        Ok(())
+    async fn health_check(&self) -> bool {
+        self.http_client()
+            .get("https://slack.com/api/auth.test")
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
     }
+}
 
-    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+impl SlackChannel {
+    /// Poll `conversations.history` for new messages. Returns once the
+    /// receiver is gone.
+    async fn poll_history(
+        &self,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
         let bot_user_id = self.get_bot_user_id().await.unwrap_or_default();
         let scoped_channel = self.configured_channel_id();
         let mut discovered_channels: Vec<String> = Vec::new();
@@ -435,8 +825,13 @@ impl Channel for SlackChannel {
                             continue;
                         }
 
+                        let has_files = msg
+                            .get("files")
+                            .and_then(|f| f.as_array())
+                            .is_some_and(|f| !f.is_empty());
+
                         // Skip empty or already-seen
-                        if text.is_empty() || ts <= last_ts {
+                        if (text.is_empty() && !has_files) || ts <= last_ts {
                             continue;
                         }
 
@@ -445,13 +840,25 @@ impl Channel for SlackChannel {
                             is_group_message && self.is_group_sender_trigger_enabled(user);
                         let require_mention =
                             self.mention_only && is_group_message && !allow_sender_without_mention;
-                        let Some(normalized_text) =
-                            Self::normalize_incoming_content(text, require_mention, &bot_user_id)
-                        else {
-                            continue;
+                        let normalized_text = match Self::normalize_incoming_content(
+                            text,
+                            require_mention,
+                            &bot_user_id,
+                        ) {
+                            Some(normalized) => normalized,
+                            // File shares without text still reach the agent.
+                            None if has_files && !require_mention && text.trim().is_empty() => {
+                                String::new()
+                            }
+                            None => continue,
                         };
 
                         last_ts_by_channel.insert(channel_id.clone(), ts.to_string());
+                        let attachments = if has_files {
+                            self.fetch_message_files(msg).await
+                        } else {
+                            Vec::new()
+                        };
 
                         let channel_msg = ChannelMessage {
                             id: format!("slack_{channel_id}_{ts}"),
@@ -464,6 +871,7 @@ impl Channel for SlackChannel {
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: Self::inbound_thread_ts(msg, ts),
+                            attachments,
                         };
 
                         if tx.send(channel_msg).await.is_err() {
@@ -474,16 +882,6 @@ impl Channel for SlackChannel {
             }
         }
     }
-
-    async fn health_check(&self) -> bool {
-        self.http_client()
-            .get("https://slack.com/api/auth.test")
-            .bearer_auth(&self.bot_token)
-            .send()
-            .await
-            .map(|r| r.status().is_success())
-            .unwrap_or(false)
-    }
 }
 
 #[cfg(test)]
@@ -559,6 +957,66 @@ mod tests {
         assert_eq!(ids, vec!["C1".to_string(), "C4".to_string()]);
     }
 
+    #[test]
+    fn app_token_enables_interactive_prompts() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert!(!ch.supports_interactive_prompts());
+
+        let blank =
+            SlackChannel::new("xoxb-fake".into(), None, vec![]).with_app_token(Some("  ".into()));
+        assert!(!blank.supports_interactive_prompts());
+
+        let socket = SlackChannel::new("xoxb-fake".into(), None, vec![])
+            .with_app_token(Some("xapp-fake".into()));
+        assert!(socket.supports_interactive_prompts());
+    }
+
+    #[test]
+    fn prompt_blocks_render_buttons_with_styles() {
+        use crate::channels::traits::PromptAction;
+
+        let prompt = InteractivePrompt::new("Approve?", "C1")
+            .with_action(PromptAction::new("Yes", "/yes", PromptActionStyle::Primary))
+            .with_action(PromptAction::new(
+                "Later",
+                "/later",
+                PromptActionStyle::Secondary,
+            ));
+        let blocks = SlackChannel::prompt_blocks(&prompt);
+        let buttons = &blocks[1]["elements"];
+        assert_eq!(blocks[0]["text"]["text"], "Approve?");
+        assert_eq!(buttons[0]["value"], "/yes");
+        assert_eq!(buttons[0]["style"], "primary");
+        assert!(buttons[1].get("style").is_none());
+        assert_ne!(buttons[0]["action_id"], buttons[1]["action_id"]);
+    }
+
+    #[test]
+    fn parse_block_action_maps_button_value() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U1".into()]);
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U1" },
+            "channel": { "id": "C1" },
+            "container": { "type": "message", "channel_id": "C1", "thread_ts": "123.001" },
+            "actions": [{ "value": "/approve-decide apr-1234abcd deny", "action_ts": "456.7" }]
+        });
+
+        let msg = ch.parse_block_action(&payload).unwrap();
+        assert_eq!(msg.sender, "U1");
+        assert_eq!(msg.reply_target, "C1");
+        assert_eq!(msg.content, "/approve-decide apr-1234abcd deny");
+        assert_eq!(msg.thread_ts.as_deref(), Some("123.001"));
+
+        let stranger = serde_json::json!({
+            "type": "block_actions",
+            "user": { "id": "U9" },
+            "channel": { "id": "C1" },
+            "actions": [{ "value": "/approve-decide apr-1234abcd once" }]
+        });
+        assert!(ch.parse_block_action(&stranger).is_none());
+    }
+
     #[test]
     fn empty_allowlist_denies_everyone() {
         let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
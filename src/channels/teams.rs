@@ -0,0 +1,1249 @@
+use super::traits::{
+    AttachmentData, Channel, ChannelMessage, InteractivePrompt, PromptActionStyle, SendMessage,
+};
+use crate::config::schema::TeamsConfig;
+use anyhow::Context;
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+const CONNECTOR_SCOPE: &str = "https://api.botframework.com/.default";
+const CONVERSATIONS_FILE: &str = "teams_conversations.json";
+/// Clock skew tolerated when checking `exp` / `nbf`.
+const TOKEN_CLOCK_SKEW_SECS: i64 = 300;
+/// Signing keys are re-fetched at least this often (Bot Framework rotates daily).
+const SIGNING_KEYS_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Unknown `kid`s trigger a refresh, but no more often than this.
+const SIGNING_KEYS_MIN_REFRESH: Duration = Duration::from_secs(5 * 60);
+/// Teams hides the typing indicator after ~3 seconds.
+const TYPING_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+/// Key in an adaptive card `Action.Submit` payload that carries the action value.
+const CARD_ACTION_KEY: &str = "zeroclaw_action";
+
+/// Where to reach a conversation: the connector `serviceUrl` Teams sent it
+/// from. Stored so replies and proactive messages (e.g. cron deliveries) can
+/// be sent later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationReference {
+    pub service_url: String,
+    /// Conversation ID without any `;messageid=` thread suffix.
+    pub conversation_id: String,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+struct SigningKeys {
+    fetched_at: Instant,
+    keys: Vec<Value>,
+}
+
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Microsoft Teams channel via Bot Framework.
+///
+/// Activities are received by the gateway (`POST /teams`), which validates
+/// the bearer token with [`TeamsChannel::verify_request`] and forwards
+/// messages to the running channel via [`forward_to_runtime`]. Replies,
+/// typing indicators and adaptive-card prompts are posted to the connector
+/// service with client-credential tokens.
+///
+/// `reply_target` is the conversation ID; in team channels `thread_ts` holds
+/// the root message ID of the reply chain.
+pub struct TeamsChannel {
+    app_id: String,
+    app_password: String,
+    token_endpoint: String,
+    openid_metadata_url: String,
+    token_issuer: String,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+    workspace_dir: Option<PathBuf>,
+    conversations: Mutex<HashMap<String, ConversationReference>>,
+    signing_keys: tokio::sync::Mutex<Option<SigningKeys>>,
+    access_token: tokio::sync::Mutex<Option<AccessToken>>,
+    typing_handles: Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
+}
+
+fn runtime_inbox() -> &'static Mutex<Option<mpsc::Sender<ChannelMessage>>> {
+    static INBOX: OnceLock<Mutex<Option<mpsc::Sender<ChannelMessage>>>> = OnceLock::new();
+    INBOX.get_or_init(|| Mutex::new(None))
+}
+
+/// Hand a message received by the gateway to the running Teams channel.
+///
+/// Returns the message back when no channel runtime is listening (e.g. the
+/// gateway runs on its own) so the caller can answer it directly.
+pub async fn forward_to_runtime(msg: ChannelMessage) -> Result<(), ChannelMessage> {
+    let sender = runtime_inbox().lock().clone().filter(|tx| !tx.is_closed());
+    match sender {
+        Some(tx) => tx.send(msg).await.map_err(|e| e.0),
+        None => Err(msg),
+    }
+}
+
+impl TeamsChannel {
+    pub fn from_config(config: &TeamsConfig) -> Self {
+        let tenant = config
+            .tenant_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|tenant| !tenant.is_empty())
+            .unwrap_or("botframework.com");
+        let token_endpoint = config
+            .token_endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .map_or_else(
+                || format!("https://login.microsoftonline.com/{tenant}/oauth2/v2.0/token"),
+                str::to_string,
+            );
+
+        Self {
+            app_id: config.app_id.trim().to_string(),
+            app_password: config.app_password.clone(),
+            token_endpoint,
+            openid_metadata_url: config.openid_metadata_url.trim().to_string(),
+            token_issuer: config.token_issuer.trim().to_string(),
+            allowed_users: config.allowed_users.clone(),
+            mention_only: config.effective_group_reply_mode().requires_mention(),
+            group_reply_allowed_sender_ids: config.group_reply_allowed_sender_ids(),
+            workspace_dir: None,
+            conversations: Mutex::new(HashMap::new()),
+            signing_keys: tokio::sync::Mutex::new(None),
+            access_token: tokio::sync::Mutex::new(None),
+            typing_handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Workspace used to persist conversation references for proactive messages.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.teams")
+    }
+
+    /// Check an Entra object ID or Teams user ID against `allowed_users`.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, ids: &[&str]) -> bool {
+        matches_any(&self.allowed_users, ids)
+    }
+
+    fn is_group_sender_trigger_enabled(&self, ids: &[&str]) -> bool {
+        matches_any(&self.group_reply_allowed_sender_ids, ids)
+    }
+
+    // ── Inbound ─────────────────────────────────────────────────
+
+    /// Validate the `Authorization` header of an inbound activity.
+    ///
+    /// The bearer JWT must be RS256-signed by a key from the configured
+    /// OpenID metadata, issued by `token_issuer` for this bot's app ID, be
+    /// within its validity window, and (when present) carry the activity's
+    /// `serviceUrl` and a key endorsement for its `channelId`.
+    pub async fn verify_request(
+        &self,
+        authorization: Option<&str>,
+        activity: &Value,
+    ) -> anyhow::Result<()> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("missing bearer token"))?;
+
+        let mut parts = token.split('.');
+        let (Some(header_b64), Some(claims_b64), Some(signature_b64), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("malformed token");
+        };
+        let header: Value = serde_json::from_slice(&decode_b64url(header_b64)?)
+            .context("malformed token header")?;
+        let claims: Value = serde_json::from_slice(&decode_b64url(claims_b64)?)
+            .context("malformed token claims")?;
+        let signature = decode_b64url(signature_b64)?;
+
+        if header.get("alg").and_then(Value::as_str) != Some("RS256") {
+            anyhow::bail!("unsupported token algorithm");
+        }
+        let kid = header
+            .get("kid")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("token has no key id"))?;
+        let key = self.signing_key(kid).await?;
+
+        let n = decode_b64url(key.get("n").and_then(Value::as_str).unwrap_or_default())?;
+        let e = decode_b64url(key.get("e").and_then(Value::as_str).unwrap_or_default())?;
+        let signing_input = &token[..header_b64.len() + 1 + claims_b64.len()];
+        ring::signature::RsaPublicKeyComponents { n: &n, e: &e }
+            .verify(
+                &ring::signature::RSA_PKCS1_2048_8192_SHA256,
+                signing_input.as_bytes(),
+                &signature,
+            )
+            .map_err(|_| anyhow::anyhow!("invalid token signature"))?;
+
+        let channel_id = activity
+            .get("channelId")
+            .and_then(Value::as_str)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("activity has no channelId"))?;
+        if let Some(endorsements) = key.get("endorsements").and_then(Value::as_array) {
+            if !endorsements.is_empty()
+                && !endorsements.iter().any(|e| e.as_str() == Some(channel_id))
+            {
+                anyhow::bail!("signing key is not endorsed for channel '{channel_id}'");
+            }
+        }
+
+        validate_claims(
+            &claims,
+            &self.token_issuer,
+            &self.app_id,
+            activity.get("serviceUrl").and_then(Value::as_str),
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    async fn signing_key(&self, kid: &str) -> anyhow::Result<Value> {
+        let mut cache = self.signing_keys.lock().await;
+        let find = |keys: &SigningKeys| {
+            keys.keys
+                .iter()
+                .find(|key| key.get("kid").and_then(Value::as_str) == Some(kid))
+                .cloned()
+        };
+
+        if let Some(keys) = cache.as_ref() {
+            let age = keys.fetched_at.elapsed();
+            if age < SIGNING_KEYS_MAX_AGE {
+                if let Some(key) = find(keys) {
+                    return Ok(key);
+                }
+                if age < SIGNING_KEYS_MIN_REFRESH {
+                    anyhow::bail!("unknown signing key '{kid}'");
+                }
+            }
+        }
+
+        let keys = SigningKeys {
+            fetched_at: Instant::now(),
+            keys: self.fetch_signing_keys().await?,
+        };
+        let key = find(&keys);
+        *cache = Some(keys);
+        key.ok_or_else(|| anyhow::anyhow!("unknown signing key '{kid}'"))
+    }
+
+    async fn fetch_signing_keys(&self) -> anyhow::Result<Vec<Value>> {
+        let client = self.http_client();
+        let metadata: Value = client
+            .get(&self.openid_metadata_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let jwks_uri = metadata
+            .get("jwks_uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("OpenID metadata has no jwks_uri"))?;
+        let jwks: Value = client
+            .get(jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(jwks
+            .get("keys")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Remember where the activity's conversation lives so later replies and
+    /// proactive messages can reach it.
+    pub async fn remember_conversation(&self, activity: &Value) -> anyhow::Result<()> {
+        let Some(reference) = conversation_reference(activity) else {
+            return Ok(());
+        };
+        let changed = {
+            let mut conversations = self.conversations.lock();
+            let previous =
+                conversations.insert(reference.conversation_id.clone(), reference.clone());
+            previous.as_ref() != Some(&reference)
+        };
+        if changed {
+            self.save_conversations().await?;
+        }
+        Ok(())
+    }
+
+    fn conversations_path(&self) -> Option<PathBuf> {
+        self.workspace_dir
+            .as_ref()
+            .map(|dir| dir.join("state").join(CONVERSATIONS_FILE))
+    }
+
+    async fn save_conversations(&self) -> anyhow::Result<()> {
+        let Some(path) = self.conversations_path() else {
+            return Ok(());
+        };
+        // Merge with what other processes (gateway / channel runtime) stored.
+        let mut merged = load_conversations(&path).await;
+        merged.extend(
+            self.conversations
+                .lock()
+                .iter()
+                .map(|(id, reference)| (id.clone(), reference.clone())),
+        );
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, serde_json::to_vec_pretty(&merged)?).await?;
+        Ok(())
+    }
+
+    async fn conversation(&self, conversation_id: &str) -> anyhow::Result<ConversationReference> {
+        let base = base_conversation_id(conversation_id);
+        if let Some(reference) = self.conversations.lock().get(base) {
+            return Ok(reference.clone());
+        }
+        if let Some(path) = self.conversations_path() {
+            let stored = load_conversations(&path).await;
+            let found = stored.get(base).cloned();
+            self.conversations.lock().extend(stored);
+            if let Some(reference) = found {
+                return Ok(reference);
+            }
+        }
+        anyhow::bail!(
+            "Teams has no conversation reference for '{base}'; the bot must receive a message there first"
+        )
+    }
+
+    pub fn parse_activity(&self, activity: &Value) -> Option<ChannelMessage> {
+        if activity.get("type").and_then(Value::as_str) != Some("message") {
+            return None;
+        }
+
+        let from = activity.get("from")?;
+        let from_id = from.get("id").and_then(Value::as_str).unwrap_or_default();
+        let aad_id = from
+            .get("aadObjectId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let bot_id = activity
+            .get("recipient")
+            .and_then(|r| r.get("id"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if from_id.is_empty() || from_id == bot_id {
+            return None;
+        }
+
+        let sender_ids = [aad_id, from_id];
+        if !self.is_user_allowed(&sender_ids) {
+            tracing::warn!("Teams: ignoring message from unauthorized user: {from_id}");
+            return None;
+        }
+
+        let conversation = activity.get("conversation")?;
+        let conversation_id = conversation.get("id").and_then(Value::as_str)?;
+        let is_group_message = conversation
+            .get("conversationType")
+            .and_then(Value::as_str)
+            .is_some_and(|kind| kind != "personal");
+        let thread_ts = thread_message_id(conversation_id).map(str::to_string);
+
+        // Adaptive card submits carry the button value instead of text and
+        // are never mention-gated.
+        let card_action = activity
+            .get("value")
+            .and_then(|v| v.get(CARD_ACTION_KEY))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let content = if let Some(action) = card_action {
+            action
+        } else {
+            let text = activity
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let (content, mentioned) = strip_bot_mentions(text, activity, bot_id);
+            let require_mention = is_group_message
+                && self.mention_only
+                && !self.is_group_sender_trigger_enabled(&sender_ids);
+            if require_mention && !mentioned {
+                return None;
+            }
+            content
+        };
+        if content.is_empty() {
+            return None;
+        }
+
+        let activity_id = activity
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        Some(ChannelMessage {
+            id: format!("teams_{activity_id}"),
+            sender: if aad_id.is_empty() { from_id } else { aad_id }.to_string(),
+            reply_target: base_conversation_id(conversation_id).to_string(),
+            content,
+            channel: "teams".to_string(),
+            timestamp: activity
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+                .and_then(|ts| u64::try_from(ts.timestamp()).ok())
+                .unwrap_or_default(),
+            thread_ts,
+            attachments: Vec::new(),
+        })
+    }
+
+    // ── Outbound ────────────────────────────────────────────────
+
+    async fn connector_token(&self) -> anyhow::Result<String> {
+        let mut cached = self.access_token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Instant::now() {
+                return Ok(token.value.clone());
+            }
+        }
+
+        let resp = self
+            .http_client()
+            .post(&self.token_endpoint)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.app_id.as_str()),
+                ("client_secret", self.app_password.as_str()),
+                ("scope", CONNECTOR_SCOPE),
+            ])
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Teams token request failed ({status}): {sanitized}");
+        }
+
+        let body: Value = resp.json().await?;
+        let value = body
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Teams token response has no access_token"))?
+            .to_string();
+        let expires_in = body
+            .get("expires_in")
+            .and_then(Value::as_u64)
+            .unwrap_or(3600);
+        *cached = Some(AccessToken {
+            value: value.clone(),
+            // Refresh a minute early so in-flight requests never carry a stale token.
+            expires_at: Instant::now() + Duration::from_secs(expires_in.saturating_sub(60)),
+        });
+        Ok(value)
+    }
+
+    /// Post an activity to a conversation, inside the reply chain of
+    /// `thread` when given.
+    async fn post_activity(
+        &self,
+        recipient: &str,
+        thread: Option<&str>,
+        mut activity: Value,
+    ) -> anyhow::Result<()> {
+        let reference = self.conversation(recipient).await?;
+        let conversation_id = match thread.filter(|t| !t.is_empty()) {
+            Some(thread) => format!("{};messageid={thread}", reference.conversation_id),
+            None => reference.conversation_id.clone(),
+        };
+        activity["conversation"] = json!({ "id": conversation_id });
+        if !self.app_id.is_empty() {
+            activity["from"] = json!({ "id": format!("28:{}", self.app_id) });
+        }
+
+        let url = format!(
+            "{}/v3/conversations/{}/activities",
+            reference.service_url.trim_end_matches('/'),
+            urlencoding::encode(&conversation_id)
+        );
+        let token = self.connector_token().await?;
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(token)
+            .json(&activity)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Teams send activity failed ({status}): {sanitized}");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for TeamsChannel {
+    fn name(&self) -> &str {
+        "teams"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = message.split_attachments();
+        let mut cards = Vec::new();
+        for attachment in &attachments {
+            if let AttachmentData::Url(url) = &attachment.data {
+                cards.push(json!({
+                    "contentType": attachment.mime_or_guess(),
+                    "contentUrl": url,
+                    "name": attachment.file_name_or_default(),
+                }));
+            } else {
+                tracing::warn!(
+                    "Teams: skipping local attachment '{}' (bots can only share files by URL)",
+                    attachment.file_name_or_default()
+                );
+            }
+        }
+        if text.trim().is_empty() && cards.is_empty() {
+            return Ok(());
+        }
+
+        let mut activity = json!({
+            "type": "message",
+            "text": text,
+            "textFormat": "markdown",
+        });
+        if !cards.is_empty() {
+            activity["attachments"] = Value::Array(cards);
+        }
+        self.post_activity(&message.recipient, message.thread_ts.as_deref(), activity)
+            .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        // Teams pushes activities to the gateway's POST /teams route, which
+        // forwards them here.
+        *runtime_inbox().lock() = Some(tx.clone());
+        tracing::info!(
+            "Teams channel active (webhook mode). \
+            Point the Azure Bot messaging endpoint at your gateway's /teams route."
+        );
+        tx.closed().await;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        match self.connector_token().await {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::debug!("Teams health check failed: {e}");
+                false
+            }
+        }
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.stop_typing(recipient).await?;
+
+        let reference = self.conversation(recipient).await?;
+        let url = format!(
+            "{}/v3/conversations/{}/activities",
+            reference.service_url.trim_end_matches('/'),
+            urlencoding::encode(&reference.conversation_id)
+        );
+        let token = self.connector_token().await?;
+        let client = self.http_client();
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let result = client
+                    .post(&url)
+                    .bearer_auth(&token)
+                    .json(&json!({ "type": "typing" }))
+                    .send()
+                    .await;
+                if let Ok(r) = result {
+                    if !r.status().is_success() {
+                        tracing::debug!(status = %r.status(), "Teams typing indicator failed");
+                    }
+                }
+                tokio::time::sleep(TYPING_REFRESH_INTERVAL).await;
+            }
+        });
+
+        self.typing_handles
+            .lock()
+            .insert(recipient.to_string(), handle);
+        Ok(())
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        if let Some(handle) = self.typing_handles.lock().remove(recipient) {
+            handle.abort();
+        }
+        Ok(())
+    }
+
+    fn supports_interactive_prompts(&self) -> bool {
+        true
+    }
+
+    async fn send_interactive_prompt(&self, prompt: &InteractivePrompt) -> anyhow::Result<()> {
+        let activity = json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": prompt_card(prompt),
+            }],
+        });
+        self.post_activity(&prompt.recipient, prompt.thread_ts.as_deref(), activity)
+            .await
+    }
+}
+
+fn matches_any(entries: &[String], ids: &[&str]) -> bool {
+    entries.iter().any(|entry| {
+        let entry = entry.trim();
+        entry == "*"
+            || ids
+                .iter()
+                .any(|id| !id.is_empty() && entry.eq_ignore_ascii_case(id))
+    })
+}
+
+fn decode_b64url(value: &str) -> anyhow::Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .context("invalid base64url in token")
+}
+
+/// Check issuer, audience, validity window and `serviceurl` binding.
+fn validate_claims(
+    claims: &Value,
+    issuer: &str,
+    app_id: &str,
+    service_url: Option<&str>,
+    now: i64,
+) -> anyhow::Result<()> {
+    if claims.get("iss").and_then(Value::as_str) != Some(issuer) {
+        anyhow::bail!("unexpected token issuer");
+    }
+
+    let audience_ok = match claims.get("aud") {
+        Some(Value::String(aud)) => aud == app_id,
+        Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(app_id)),
+        _ => false,
+    };
+    if !audience_ok {
+        anyhow::bail!("token audience does not match app_id");
+    }
+
+    let exp = claims
+        .get("exp")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| anyhow::anyhow!("token has no expiry"))?;
+    if now > exp + TOKEN_CLOCK_SKEW_SECS {
+        anyhow::bail!("token expired");
+    }
+    if let Some(nbf) = claims.get("nbf").and_then(Value::as_i64) {
+        if now + TOKEN_CLOCK_SKEW_SECS < nbf {
+            anyhow::bail!("token not yet valid");
+        }
+    }
+
+    // The activity's serviceUrl receives our connector token in replies, so
+    // it must be the one Bot Framework bound into the token.
+    let claimed = claims
+        .get("serviceurl")
+        .or_else(|| claims.get("serviceUrl"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("token has no serviceurl claim"))?;
+    let actual = service_url.ok_or_else(|| anyhow::anyhow!("activity has no serviceUrl"))?;
+    if claimed.trim_end_matches('/') != actual.trim_end_matches('/') {
+        anyhow::bail!("token serviceurl does not match activity");
+    }
+
+    Ok(())
+}
+
+fn conversation_reference(activity: &Value) -> Option<ConversationReference> {
+    let service_url = activity.get("serviceUrl").and_then(Value::as_str)?;
+    let conversation = activity.get("conversation")?;
+    let conversation_id = conversation.get("id").and_then(Value::as_str)?;
+    let tenant_id = conversation
+        .get("tenantId")
+        .or_else(|| activity.get("channelData")?.get("tenant")?.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some(ConversationReference {
+        service_url: service_url.to_string(),
+        conversation_id: base_conversation_id(conversation_id).to_string(),
+        tenant_id,
+    })
+}
+
+async fn load_conversations(path: &std::path::Path) -> HashMap<String, ConversationReference> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("Teams: ignoring unreadable {}: {e}", path.display());
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// `19:abc@thread.tacv2;messageid=123` → `19:abc@thread.tacv2`.
+fn base_conversation_id(conversation_id: &str) -> &str {
+    conversation_id
+        .split_once(";messageid=")
+        .map_or(conversation_id, |(base, _)| base)
+}
+
+/// `19:abc@thread.tacv2;messageid=123` → `123`.
+fn thread_message_id(conversation_id: &str) -> Option<&str> {
+    conversation_id
+        .split_once(";messageid=")
+        .map(|(_, id)| id)
+        .filter(|id| !id.is_empty())
+}
+
+/// Remove `<at>...</at>` mentions of the bot. Returns the remaining text and
+/// whether the bot was mentioned.
+fn strip_bot_mentions(text: &str, activity: &Value, bot_id: &str) -> (String, bool) {
+    let mut stripped = text.to_string();
+    let mut mentioned = false;
+    for entity in activity
+        .get("entities")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if entity.get("type").and_then(Value::as_str) != Some("mention") {
+            continue;
+        }
+        let mentions_bot = entity
+            .get("mentioned")
+            .and_then(|m| m.get("id"))
+            .and_then(Value::as_str)
+            .is_some_and(|id| !bot_id.is_empty() && id == bot_id);
+        if !mentions_bot {
+            continue;
+        }
+        mentioned = true;
+        if let Some(mention_text) = entity.get("text").and_then(Value::as_str) {
+            stripped = stripped.replace(mention_text, "");
+        }
+    }
+    (stripped.trim().to_string(), mentioned)
+}
+
+/// Render a prompt as an adaptive card whose buttons submit
+/// `{ "zeroclaw_action": <value> }`.
+fn prompt_card(prompt: &InteractivePrompt) -> Value {
+    let actions: Vec<Value> = prompt
+        .actions
+        .iter()
+        .map(|action| {
+            let mut button = json!({
+                "type": "Action.Submit",
+                "title": action.label,
+                "data": { CARD_ACTION_KEY: action.value },
+            });
+            match action.style {
+                PromptActionStyle::Primary => button["style"] = json!("positive"),
+                PromptActionStyle::Danger => button["style"] = json!("destructive"),
+                PromptActionStyle::Secondary => {}
+            }
+            button
+        })
+        .collect();
+
+    json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.4",
+        "body": [{ "type": "TextBlock", "text": prompt.text, "wrap": true }],
+        "actions": actions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::PromptAction;
+    use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+    use wiremock::matchers::{body_partial_json, body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_KEY: &[u8] = include_bytes!("../../tests/fixtures/teams_test_key.pk8");
+    const APP_ID: &str = "app-123";
+    const SERVICE_URL: &str = "https://smba.trafficmanager.net/amer/";
+
+    fn config() -> TeamsConfig {
+        toml::from_str(&format!(
+            r#"
+app_id = "{APP_ID}"
+app_password = "secret"
+allowed_users = ["*"]
+"#
+        ))
+        .unwrap()
+    }
+
+    fn channel_for(server: &MockServer) -> TeamsChannel {
+        let mut cfg = config();
+        cfg.openid_metadata_url = format!("{}/openid", server.uri());
+        cfg.token_endpoint = Some(format!("{}/token", server.uri()));
+        TeamsChannel::from_config(&cfg)
+    }
+
+    fn activity(conversation_id: &str, conversation_type: &str, text: &str) -> Value {
+        json!({
+            "type": "message",
+            "id": "1700000000123",
+            "timestamp": "2026-10-17T12:00:00.000Z",
+            "serviceUrl": SERVICE_URL,
+            "channelId": "msteams",
+            "from": { "id": "29:alice", "aadObjectId": "aad-alice", "name": "Alice" },
+            "recipient": { "id": "28:app-123", "name": "Claw" },
+            "conversation": {
+                "id": conversation_id,
+                "conversationType": conversation_type,
+                "tenantId": "tenant-1"
+            },
+            "text": text
+        })
+    }
+
+    fn sign_token(claims: &Value, kid: &str) -> String {
+        let key = RsaKeyPair::from_pkcs8(TEST_KEY).unwrap();
+        let header = URL_SAFE_NO_PAD.encode(json!({ "alg": "RS256", "kid": kid }).to_string());
+        let body = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let input = format!("{header}.{body}");
+        let mut signature = vec![0; key.public().modulus_len()];
+        key.sign(
+            &RSA_PKCS1_SHA256,
+            &ring::rand::SystemRandom::new(),
+            input.as_bytes(),
+            &mut signature,
+        )
+        .unwrap();
+        format!("{input}.{}", URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    fn valid_claims() -> Value {
+        let now = chrono::Utc::now().timestamp();
+        json!({
+            "iss": "https://api.botframework.com",
+            "aud": APP_ID,
+            "exp": now + 600,
+            "nbf": now - 60,
+            "serviceurl": SERVICE_URL,
+        })
+    }
+
+    async fn mount_signing_keys(server: &MockServer, endorsements: &[&str]) {
+        let key = RsaKeyPair::from_pkcs8(TEST_KEY).unwrap();
+        let public = key.public().clone();
+        let components: ring::rsa::PublicKeyComponents<Vec<u8>> = (&public).into();
+        Mock::given(method("GET"))
+            .and(path("/openid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "issuer": "https://api.botframework.com",
+                "jwks_uri": format!("{}/keys", server.uri()),
+            })))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/keys"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "keys": [{
+                    "kty": "RSA",
+                    "kid": "key-1",
+                    "n": URL_SAFE_NO_PAD.encode(&components.n),
+                    "e": URL_SAFE_NO_PAD.encode(&components.e),
+                    "endorsements": endorsements,
+                }]
+            })))
+            .mount(server)
+            .await;
+    }
+
+    async fn mount_token(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .and(body_string_contains("client_id=app-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "token_type": "Bearer",
+                "expires_in": 3600,
+                "access_token": "connector-token"
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn teams_config_defaults() {
+        let cfg = config();
+        assert_eq!(
+            cfg.openid_metadata_url,
+            "https://login.botframework.com/v1/.well-known/openidconfiguration"
+        );
+        assert_eq!(cfg.token_issuer, "https://api.botframework.com");
+        assert!(!cfg.effective_group_reply_mode().requires_mention());
+
+        let channel = TeamsChannel::from_config(&cfg);
+        assert_eq!(
+            channel.token_endpoint,
+            "https://login.microsoftonline.com/botframework.com/oauth2/v2.0/token"
+        );
+        assert!(channel.supports_interactive_prompts());
+    }
+
+    #[test]
+    fn personal_message_parses_sender_and_target() {
+        let channel = TeamsChannel::from_config(&config());
+        let msg = channel
+            .parse_activity(&activity("a:personal-1", "personal", "hello"))
+            .unwrap();
+        assert_eq!(msg.id, "teams_1700000000123");
+        assert_eq!(msg.sender, "aad-alice");
+        assert_eq!(msg.reply_target, "a:personal-1");
+        assert_eq!(msg.content, "hello");
+        assert!(msg.thread_ts.is_none());
+        assert_eq!(msg.timestamp, 1_792_238_400);
+    }
+
+    #[test]
+    fn channel_message_strips_mention_and_keeps_thread() {
+        let mut cfg = config();
+        cfg.group_reply = Some(crate::config::schema::GroupReplyConfig {
+            mode: Some(crate::config::schema::GroupReplyMode::MentionOnly),
+            allowed_sender_ids: vec![],
+        });
+        let channel = TeamsChannel::from_config(&cfg);
+
+        let conversation = "19:ops@thread.tacv2;messageid=1699999999000";
+        assert!(channel
+            .parse_activity(&activity(conversation, "channel", "no mention"))
+            .is_none());
+
+        let mut mentioned = activity(conversation, "channel", "<at>Claw</at> deploy status?");
+        mentioned["entities"] = json!([{
+            "type": "mention",
+            "text": "<at>Claw</at>",
+            "mentioned": { "id": "28:app-123", "name": "Claw" }
+        }]);
+        let msg = channel.parse_activity(&mentioned).unwrap();
+        assert_eq!(msg.content, "deploy status?");
+        assert_eq!(msg.reply_target, "19:ops@thread.tacv2");
+        assert_eq!(msg.thread_ts.as_deref(), Some("1699999999000"));
+    }
+
+    #[test]
+    fn card_submit_becomes_action_value_and_allowlist_applies() {
+        let mut cfg = config();
+        cfg.allowed_users = vec!["AAD-ALICE".into()];
+        let channel = TeamsChannel::from_config(&cfg);
+
+        let mut submit = activity("a:personal-1", "personal", "");
+        submit["value"] = json!({ "zeroclaw_action": "/approve-decide apr-1 once" });
+        let msg = channel.parse_activity(&submit).unwrap();
+        assert_eq!(msg.content, "/approve-decide apr-1 once");
+
+        let mut stranger = activity("a:personal-1", "personal", "hi");
+        stranger["from"] = json!({ "id": "29:mallory", "aadObjectId": "aad-mallory" });
+        assert!(channel.parse_activity(&stranger).is_none());
+
+        let mut typing = activity("a:personal-1", "personal", "hi");
+        typing["type"] = json!("typing");
+        assert!(channel.parse_activity(&typing).is_none());
+    }
+
+    #[test]
+    fn validate_claims_checks_issuer_audience_and_window() {
+        let now = chrono::Utc::now().timestamp();
+        let issuer = "https://api.botframework.com";
+        let claims = valid_claims();
+        assert!(validate_claims(&claims, issuer, APP_ID, Some(SERVICE_URL), now).is_ok());
+
+        let mut wrong_aud = claims.clone();
+        wrong_aud["aud"] = json!("someone-else");
+        assert!(validate_claims(&wrong_aud, issuer, APP_ID, None, now).is_err());
+
+        let mut wrong_iss = claims.clone();
+        wrong_iss["iss"] = json!("https://evil.example.com");
+        assert!(validate_claims(&wrong_iss, issuer, APP_ID, None, now).is_err());
+
+        let mut expired = claims.clone();
+        expired["exp"] = json!(now - TOKEN_CLOCK_SKEW_SECS - 1);
+        assert!(validate_claims(&expired, issuer, APP_ID, None, now).is_err());
+
+        assert!(validate_claims(
+            &claims,
+            issuer,
+            APP_ID,
+            Some("https://attacker.example.com/"),
+            now
+        )
+        .is_err());
+        assert!(validate_claims(&claims, issuer, APP_ID, None, now).is_err());
+
+        let mut unbound = claims.clone();
+        unbound.as_object_mut().unwrap().remove("serviceurl");
+        assert!(validate_claims(&unbound, issuer, APP_ID, Some(SERVICE_URL), now).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_request_accepts_signed_token() {
+        let server = MockServer::start().await;
+        mount_signing_keys(&server, &["msteams"]).await;
+        let channel = channel_for(&server);
+        let body = activity("a:personal-1", "personal", "hi");
+
+        let token = sign_token(&valid_claims(), "key-1");
+        channel
+            .verify_request(Some(&format!("Bearer {token}")), &body)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_request_rejects_bad_tokens() {
+        let server = MockServer::start().await;
+        mount_signing_keys(&server, &["skype"]).await;
+        let channel = channel_for(&server);
+        let body = activity("a:personal-1", "personal", "hi");
+
+        assert!(channel.verify_request(None, &body).await.is_err());
+
+        let token = sign_token(&valid_claims(), "key-1");
+        let tampered = {
+            let mut claims = valid_claims();
+            claims["aud"] = json!("other-app");
+            let forged = URL_SAFE_NO_PAD.encode(claims.to_string());
+            let parts: Vec<&str> = token.split('.').collect();
+            format!("{}.{forged}.{}", parts[0], parts[2])
+        };
+        let err = channel
+            .verify_request(Some(&format!("Bearer {tampered}")), &body)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("signature"));
+
+        // Key is endorsed for a different channel.
+        let err = channel
+            .verify_request(Some(&format!("Bearer {token}")), &body)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("endorsed"));
+
+        let unknown_kid = sign_token(&valid_claims(), "key-2");
+        assert!(channel
+            .verify_request(Some(&format!("Bearer {unknown_kid}")), &body)
+            .await
+            .is_err());
+
+        // No channelId cannot slip past the endorsement check.
+        let mut no_channel = body.clone();
+        no_channel.as_object_mut().unwrap().remove("channelId");
+        let err = channel
+            .verify_request(Some(&format!("Bearer {token}")), &no_channel)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("channelId"));
+
+        // Endorsed channel, but the token does not bind a serviceurl.
+        let mut skype = body.clone();
+        skype["channelId"] = json!("skype");
+        let mut claims = valid_claims();
+        claims.as_object_mut().unwrap().remove("serviceurl");
+        let unbound = sign_token(&claims, "key-1");
+        let err = channel
+            .verify_request(Some(&format!("Bearer {unbound}")), &skype)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("serviceurl"));
+        channel
+            .verify_request(Some(&format!("Bearer {token}")), &skype)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_replies_in_thread_via_connector() {
+        let server = MockServer::start().await;
+        mount_token(&server).await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/v3/conversations/19%3Aops%40thread.tacv2%3Bmessageid%3D1699999999000/activities",
+            ))
+            .and(header("authorization", "Bearer connector-token"))
+            .and(body_partial_json(json!({
+                "type": "message",
+                "text": "done",
+                "conversation": { "id": "19:ops@thread.tacv2;messageid=1699999999000" }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = channel_for(&server);
+        let mut inbound = activity(
+            "19:ops@thread.tacv2;messageid=1699999999000",
+            "channel",
+            "hi",
+        );
+        inbound["serviceUrl"] = json!(server.uri());
+        channel.remember_conversation(&inbound).await.unwrap();
+
+        channel
+            .send(
+                &SendMessage::new("done", "19:ops@thread.tacv2")
+                    .in_thread(Some("1699999999000".into())),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn proactive_send_uses_persisted_conversation_reference() {
+        let server = MockServer::start().await;
+        mount_token(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/v3/conversations/a%3Apersonal-1/activities"))
+            .and(body_partial_json(json!({ "text": "daily report" })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": "2" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let workspace = tempfile::tempdir().unwrap();
+        let gateway_side = channel_for(&server).with_workspace_dir(workspace.path().into());
+        let mut inbound = activity("a:personal-1", "personal", "hi");
+        inbound["serviceUrl"] = json!(server.uri());
+        gateway_side.remember_conversation(&inbound).await.unwrap();
+
+        // A fresh instance (e.g. the cron scheduler) finds the stored reference.
+        let cron_side = channel_for(&server).with_workspace_dir(workspace.path().into());
+        cron_side
+            .send(&SendMessage::new("daily report", "a:personal-1"))
+            .await
+            .unwrap();
+
+        let unknown = cron_side
+            .send(&SendMessage::new("hi", "a:unknown"))
+            .await
+            .unwrap_err();
+        assert!(unknown.to_string().contains("no conversation reference"));
+    }
+
+    #[tokio::test]
+    async fn approval_prompt_is_sent_as_adaptive_card() {
+        let server = MockServer::start().await;
+        mount_token(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/v3/conversations/a%3Apersonal-1/activities"))
+            .and(body_partial_json(json!({
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "content": {
+                        "type": "AdaptiveCard",
+                        "actions": [
+                            {
+                                "type": "Action.Submit",
+                                "title": "Approve once",
+                                "style": "positive",
+                                "data": { "zeroclaw_action": "/approve-decide apr-1 once" }
+                            },
+                            {
+                                "title": "Deny",
+                                "style": "destructive",
+                                "data": { "zeroclaw_action": "/approve-decide apr-1 deny" }
+                            }
+                        ]
+                    }
+                }]
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "id": "3" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = channel_for(&server);
+        let mut inbound = activity("a:personal-1", "personal", "hi");
+        inbound["serviceUrl"] = json!(server.uri());
+        channel.remember_conversation(&inbound).await.unwrap();
+
+        let prompt = InteractivePrompt::new("Approval requested", "a:personal-1")
+            .with_action(PromptAction::new(
+                "Approve once",
+                "/approve-decide apr-1 once",
+                PromptActionStyle::Primary,
+            ))
+            .with_action(PromptAction::new(
+                "Deny",
+                "/approve-decide apr-1 deny",
+                PromptActionStyle::Danger,
+            ));
+        channel.send_interactive_prompt(&prompt).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn typing_posts_typing_activity() {
+        let server = MockServer::start().await;
+        mount_token(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/v3/conversations/a%3Apersonal-1/activities"))
+            .and(body_partial_json(json!({ "type": "typing" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1..)
+            .mount(&server)
+            .await;
+
+        let channel = channel_for(&server);
+        let mut inbound = activity("a:personal-1", "personal", "hi");
+        inbound["serviceUrl"] = json!(server.uri());
+        channel.remember_conversation(&inbound).await.unwrap();
+
+        channel.start_typing("a:personal-1").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        channel.stop_typing("a:personal-1").await.unwrap();
+    }
+}
@@ -1,4 +1,8 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachments::attachment_from_target;
+use super::traits::{
+    Attachment, AttachmentData, AttachmentKind, Channel, ChannelMessage, InteractivePrompt,
+    SendMessage,
+};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
 use anyhow::Context;
@@ -135,34 +139,6 @@ fn build_telegram_ack_reaction_request(
     })
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum TelegramAttachmentKind {
-    Image,
-    Document,
-    Video,
-    Audio,
-    Voice,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct TelegramAttachment {
-    kind: TelegramAttachmentKind,
-    target: String,
-}
-
-impl TelegramAttachmentKind {
-    fn from_marker(marker: &str) -> Option<Self> {
-        match marker.trim().to_ascii_uppercase().as_str() {
-            "IMAGE" | "PHOTO" => Some(Self::Image),
-            "DOCUMENT" | "FILE" => Some(Self::Document),
-            "VIDEO" => Some(Self::Video),
-            "AUDIO" => Some(Self::Audio),
-            "VOICE" => Some(Self::Voice),
-            _ => None,
-        }
-    }
-}
-
 /// Check whether a file path has a recognized image extension.
 fn is_image_extension(path: &Path) -> bool {
     path.extension()
@@ -176,25 +152,19 @@ fn is_image_extension(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// Build the user-facing content string for an incoming attachment.
+/// Classify an incoming attachment for the agent.
 ///
-/// Photos and Documents with a recognized image extension use `[IMAGE:/path]`
-/// so the multimodal pipeline can validate vision capability and send them
-/// as proper image content blocks. Non-image files use `[Document: name] /path`.
-fn format_attachment_content(
-    kind: IncomingAttachmentKind,
-    local_filename: &str,
-    local_path: &Path,
-) -> String {
+/// Photos and Documents with a recognized image extension become image
+/// attachments so the multimodal pipeline can validate vision capability and
+/// send them as proper image content blocks. Non-image files stay documents.
+fn incoming_attachment_kind(kind: IncomingAttachmentKind, local_path: &Path) -> AttachmentKind {
     match kind {
         IncomingAttachmentKind::Photo | IncomingAttachmentKind::Document
             if is_image_extension(local_path) =>
         {
-            format!("[IMAGE:{}]", local_path.display())
-        }
-        _ => {
-            format!("[Document: {}] {}", local_filename, local_path.display())
+            AttachmentKind::Image
         }
+        _ => AttachmentKind::Document,
     }
 }
 
@@ -324,32 +294,7 @@ async fn resolve_workspace_attachment_output_path(
     Ok(output_path)
 }
 
-fn infer_attachment_kind_from_target(target: &str) -> Option<TelegramAttachmentKind> {
-    let normalized = target
-        .split('?')
-        .next()
-        .unwrap_or(target)
-        .split('#')
-        .next()
-        .unwrap_or(target);
-
-    let extension = Path::new(normalized)
-        .extension()
-        .and_then(|ext| ext.to_str())?
-        .to_ascii_lowercase();
-
-    match extension.as_str() {
-        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Some(TelegramAttachmentKind::Image),
-        "mp4" | "mov" | "mkv" | "avi" | "webm" => Some(TelegramAttachmentKind::Video),
-        "mp3" | "m4a" | "wav" | "flac" => Some(TelegramAttachmentKind::Audio),
-        "ogg" | "oga" | "opus" => Some(TelegramAttachmentKind::Voice),
-        "pdf" | "txt" | "md" | "csv" | "json" | "zip" | "tar" | "gz" | "doc" | "docx" | "xls"
-        | "xlsx" | "ppt" | "pptx" => Some(TelegramAttachmentKind::Document),
-        _ => None,
-    }
-}
-
-fn parse_path_only_attachment(message: &str) -> Option<TelegramAttachment> {
+fn parse_path_only_attachment(message: &str) -> Option<Attachment> {
     let trimmed = message.trim();
     if trimmed.is_empty() || trimmed.contains('\n') {
         return None;
@@ -361,16 +306,13 @@ fn parse_path_only_attachment(message: &str) -> Option<TelegramAttachment> {
     }
 
     let candidate = candidate.strip_prefix("file://").unwrap_or(candidate);
-    let kind = infer_attachment_kind_from_target(candidate)?;
+    let kind = AttachmentKind::from_target(candidate)?;
 
     if !is_http_url(candidate) && !Path::new(candidate).exists() {
         return None;
     }
 
-    Some(TelegramAttachment {
-        kind,
-        target: candidate.to_string(),
-    })
+    Some(attachment_from_target(kind, candidate))
 }
 
 /// Delegate to the shared `strip_tool_call_tags` in the parent module.
@@ -378,69 +320,6 @@ fn strip_tool_call_tags(message: &str) -> String {
     super::strip_tool_call_tags(message)
 }
 
-fn find_matching_close(s: &str) -> Option<usize> {
-    let mut depth = 1usize;
-    for (i, ch) in s.char_indices() {
-        match ch {
-            '[' => depth += 1,
-            ']' => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(i);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
-}
-
-fn parse_attachment_markers(message: &str) -> (String, Vec<TelegramAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0;
-
-    while cursor < message.len() {
-        let Some(open_rel) = message[cursor..].find('[') else {
-            cleaned.push_str(&message[cursor..]);
-            break;
-        };
-
-        let open = cursor + open_rel;
-        cleaned.push_str(&message[cursor..open]);
-
-        let Some(close_rel) = find_matching_close(&message[open + 1..]) else {
-            cleaned.push_str(&message[open..]);
-            break;
-        };
-
-        let close = open + 1 + close_rel;
-        let marker = &message[open + 1..close];
-
-        let parsed = marker.split_once(':').and_then(|(kind, target)| {
-            let kind = TelegramAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
-                return None;
-            }
-            Some(TelegramAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
-
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            cleaned.push_str(&message[open..=close]);
-        }
-
-        cursor = close + 1;
-    }
-
-    (cleaned.trim().to_string(), attachments)
-}
-
 /// Telegram Bot API maximum file download size (20 MB).
 const TELEGRAM_MAX_FILE_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
 
@@ -1318,21 +1197,26 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             return None;
         }
 
-        // Build message content.
-        // Photos with image extensions use [IMAGE:] marker so the multimodal
-        // pipeline validates vision capability. Non-image files always get
-        // [Document:] format regardless of Telegram's classification.
-        let mut content = format_attachment_content(attachment.kind, &local_filename, &local_path);
-        if let Some(caption) = &attachment.caption {
-            if !caption.is_empty() {
-                use std::fmt::Write;
-                let _ = write!(content, "\n\n{caption}");
-            }
-        }
+        // The caption is the message text; the saved file travels as a typed
+        // attachment. Photos with image extensions are image attachments so
+        // the multimodal pipeline validates vision capability. Non-image
+        // files are always documents regardless of Telegram's classification.
+        let typed_attachment = Attachment::from_path(
+            incoming_attachment_kind(attachment.kind, &local_path),
+            local_path.clone(),
+        )
+        .with_filename(local_filename.clone())
+        .with_mime(super::attachments::mime_from_name(&local_filename))
+        .with_size(file_data.len() as u64);
+        let mut content = attachment.caption.clone().unwrap_or_default();
 
         // Prepend reply context if replying to another message
         if let Some(quote) = self.extract_reply_context(message) {
-            content = format!("{quote}\n\n{content}");
+            content = if content.is_empty() {
+                quote
+            } else {
+                format!("{quote}\n\n{content}")
+            };
         }
 
         Some(ChannelMessage {
@@ -1346,6 +1230,7 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: vec![typed_attachment],
         })
     }
 
@@ -1506,6 +1391,7 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
         })
     }
 
@@ -1665,9 +1551,97 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            attachments: Vec::new(),
         })
     }
 
+    /// Parse an inline-keyboard press into `(callback_query_id, message)`.
+    /// The message is `None` when the presser is not allowed to talk to the bot.
+    fn parse_callback_query(
+        &self,
+        update: &serde_json::Value,
+    ) -> Option<(String, Option<ChannelMessage>)> {
+        let query = update.get("callback_query")?;
+        let query_id = query
+            .get("id")
+            .and_then(serde_json::Value::as_str)?
+            .to_string();
+
+        let (username, sender_id, sender_identity) = Self::extract_sender_info(query);
+        let mut identities = vec![username.as_str()];
+        if let Some(id) = sender_id.as_deref() {
+            identities.push(id);
+        }
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            return Some((query_id, None));
+        }
+
+        let data = query.get("data").and_then(serde_json::Value::as_str)?;
+        let message = query.get("message")?;
+        let chat_id = message
+            .get("chat")
+            .and_then(|chat| chat.get("id"))
+            .and_then(serde_json::Value::as_i64)?
+            .to_string();
+        let thread_id = message
+            .get("message_thread_id")
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+        let reply_target = match thread_id.as_deref() {
+            Some(tid) => format!("{chat_id}:{tid}"),
+            None => chat_id.clone(),
+        };
+
+        Some((
+            query_id.clone(),
+            Some(ChannelMessage {
+                id: format!("telegram_{chat_id}_cb_{query_id}"),
+                sender: sender_identity,
+                reply_target,
+                content: data.to_string(),
+                channel: "telegram".to_string(),
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                thread_ts: thread_id,
+                attachments: Vec::new(),
+            }),
+        ))
+    }
+
+    /// Stop the client-side spinner on a pressed inline-keyboard button.
+    async fn answer_callback_query(&self, query_id: &str, text: Option<&str>) {
+        let mut body = serde_json::json!({ "callback_query_id": query_id });
+        if let Some(text) = text {
+            body["text"] = serde_json::Value::String(text.to_string());
+        }
+        if let Err(e) = self
+            .http_client()
+            .post(self.api_url("answerCallbackQuery"))
+            .json(&body)
+            .send()
+            .await
+        {
+            let sanitized = Self::sanitize_telegram_error(&e.to_string());
+            tracing::debug!("Telegram answerCallbackQuery failed: {sanitized}");
+        }
+    }
+
+    fn inline_keyboard(prompt: &InteractivePrompt) -> serde_json::Value {
+        let row: Vec<serde_json::Value> = prompt
+            .actions
+            .iter()
+            .map(|action| {
+                serde_json::json!({
+                    "text": action.label,
+                    "callback_data": action.value,
+                })
+            })
+            .collect();
+        serde_json::json!({ "inline_keyboard": [row] })
+    }
+
     /// Download a Telegram photo by file_id, resize to fit within 1024px, and return as base64 data URI.
     async fn resolve_photo_data_uri(&self, file_id: &str) -> anyhow::Result<String> {
         use base64::Engine as _;
@@ -2001,71 +1975,101 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         &self,
         chat_id: &str,
         thread_id: Option<&str>,
-        attachment: &TelegramAttachment,
+        attachment: &Attachment,
     ) -> anyhow::Result<()> {
-        let target = attachment.target.trim();
+        let caption = attachment.caption.as_deref();
+
+        let path = match &attachment.data {
+            AttachmentData::Url(url) => {
+                let target = url.trim();
+                let result = match attachment.kind {
+                    AttachmentKind::Image => {
+                        self.send_photo_by_url(chat_id, thread_id, target, caption)
+                            .await
+                    }
+                    AttachmentKind::Document => {
+                        self.send_document_by_url(chat_id, thread_id, target, caption)
+                            .await
+                    }
+                    AttachmentKind::Video => {
+                        self.send_video_by_url(chat_id, thread_id, target, caption)
+                            .await
+                    }
+                    AttachmentKind::Audio => {
+                        self.send_audio_by_url(chat_id, thread_id, target, caption)
+                            .await
+                    }
+                    AttachmentKind::Voice => {
+                        self.send_voice_by_url(chat_id, thread_id, target, caption)
+                            .await
+                    }
+                };
 
-        if is_http_url(target) {
-            let result = match attachment.kind {
-                TelegramAttachmentKind::Image => {
-                    self.send_photo_by_url(chat_id, thread_id, target, None)
-                        .await
-                }
-                TelegramAttachmentKind::Document => {
-                    self.send_document_by_url(chat_id, thread_id, target, None)
-                        .await
-                }
-                TelegramAttachmentKind::Video => {
-                    self.send_video_by_url(chat_id, thread_id, target, None)
-                        .await
+                // If sending media by URL failed (e.g. Telegram can't fetch the URL,
+                // wrong content type, etc.), fall back to sending the URL as a text link
+                // instead of losing the reply entirely.
+                if let Err(e) = result {
+                    tracing::warn!(
+                        url = target,
+                        error = %e,
+                        "Telegram send media by URL failed; falling back to text link"
+                    );
+                    let kind_label = match attachment.kind {
+                        AttachmentKind::Image => "Image",
+                        AttachmentKind::Document => "Document",
+                        AttachmentKind::Video => "Video",
+                        AttachmentKind::Audio => "Audio",
+                        AttachmentKind::Voice => "Voice",
+                    };
+                    let fallback_text = format!("{kind_label}: {target}");
+                    self.send_text_chunks(&fallback_text, chat_id, thread_id)
+                        .await?;
                 }
-                TelegramAttachmentKind::Audio => {
-                    self.send_audio_by_url(chat_id, thread_id, target, None)
+
+                return Ok(());
+            }
+            AttachmentData::Bytes(bytes) => {
+                let file_name = attachment.file_name_or_default();
+                return match attachment.kind {
+                    AttachmentKind::Image => {
+                        self.send_photo_bytes(
+                            chat_id,
+                            thread_id,
+                            bytes.clone(),
+                            &file_name,
+                            caption,
+                        )
                         .await
-                }
-                TelegramAttachmentKind::Voice => {
-                    self.send_voice_by_url(chat_id, thread_id, target, None)
+                    }
+                    _ => {
+                        self.send_document_bytes(
+                            chat_id,
+                            thread_id,
+                            bytes.clone(),
+                            &file_name,
+                            caption,
+                        )
                         .await
-                }
-            };
-
-            // If sending media by URL failed (e.g. Telegram can't fetch the URL,
-            // wrong content type, etc.), fall back to sending the URL as a text link
-            // instead of losing the reply entirely.
-            if let Err(e) = result {
-                tracing::warn!(
-                    url = target,
-                    error = %e,
-                    "Telegram send media by URL failed; falling back to text link"
-                );
-                let kind_label = match attachment.kind {
-                    TelegramAttachmentKind::Image => "Image",
-                    TelegramAttachmentKind::Document => "Document",
-                    TelegramAttachmentKind::Video => "Video",
-                    TelegramAttachmentKind::Audio => "Audio",
-                    TelegramAttachmentKind::Voice => "Voice",
+                    }
                 };
-                let fallback_text = format!("{kind_label}: {target}");
-                self.send_text_chunks(&fallback_text, chat_id, thread_id)
-                    .await?;
             }
-
-            return Ok(());
-        }
+            AttachmentData::Path(path) => path,
+        };
 
         let workspace = self.workspace_dir.as_ref().ok_or_else(|| {
             anyhow::anyhow!("workspace_dir is not configured; local file attachments are disabled")
         })?;
-        let path = resolve_workspace_attachment_path(workspace, target)?;
+        let target = path.to_string_lossy();
+        let path = resolve_workspace_attachment_path(workspace, target.trim())?;
 
         match attachment.kind {
-            TelegramAttachmentKind::Image => self.send_photo(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Document => {
-                self.send_document(chat_id, thread_id, &path, None).await
+            AttachmentKind::Image => self.send_photo(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Document => {
+                self.send_document(chat_id, thread_id, &path, caption).await
             }
-            TelegramAttachmentKind::Video => self.send_video(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Audio => self.send_audio(chat_id, thread_id, &path, None).await,
-            TelegramAttachmentKind::Voice => self.send_voice(chat_id, thread_id, &path, None).await,
+            AttachmentKind::Video => self.send_video(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Audio => self.send_audio(chat_id, thread_id, &path, caption).await,
+            AttachmentKind::Voice => self.send_voice(chat_id, thread_id, &path, caption).await,
         }
     }
 
@@ -2497,6 +2501,46 @@ impl Channel for TelegramChannel {
         self.stream_mode != StreamMode::Off
     }
 
+    fn supports_interactive_prompts(&self) -> bool {
+        true
+    }
+
+    async fn send_interactive_prompt(&self, prompt: &InteractivePrompt) -> anyhow::Result<()> {
+        if let Some(action) = prompt.actions.iter().find(|a| a.value.len() > 64) {
+            anyhow::bail!(
+                "Telegram callback data for '{}' exceeds 64 bytes",
+                action.label
+            );
+        }
+
+        let (chat_id, thread_id) = Self::parse_reply_target(&prompt.recipient);
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": Self::markdown_to_telegram_html(&prompt.text),
+            "parse_mode": "HTML",
+            "reply_markup": Self::inline_keyboard(prompt),
+        });
+        if let Some(tid) = thread_id {
+            body["message_thread_id"] = serde_json::Value::String(tid);
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            let sanitized = Self::sanitize_telegram_error(&err);
+            anyhow::bail!(
+                "Telegram sendMessage with inline keyboard failed ({status}): {sanitized}"
+            );
+        }
+        Ok(())
+    }
+
     async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
         if self.stream_mode == StreamMode::Off {
             return Ok(None);
@@ -2626,7 +2670,7 @@ impl Channel for TelegramChannel {
         self.last_draft_edit.lock().remove(&chat_id);
 
         // Parse attachments before processing
-        let (text_without_markers, attachments) = parse_attachment_markers(text);
+        let (text_without_markers, attachments) = super::attachments::parse_markers(text);
 
         // Parse message ID once for reuse
         let msg_id = match message_id.parse::<i64>() {
@@ -2770,16 +2814,17 @@ impl Channel for TelegramChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        // Strip tool_call tags before processing to prevent Markdown parsing failures
-        let content = strip_tool_call_tags(&message.content);
-
         // Parse recipient: "chat_id" or "chat_id:thread_id" format
         let (chat_id, thread_id) = match message.recipient.split_once(':') {
             Some((chat, thread)) => (chat, Some(thread)),
             None => (message.recipient.as_str(), None),
         };
 
-        let (text_without_markers, attachments) = parse_attachment_markers(&content);
+        // Strip tool_call tags before processing to prevent Markdown parsing failures
+        let content = strip_tool_call_tags(&message.content);
+
+        let (text_without_markers, mut attachments) = super::attachments::parse_markers(&content);
+        attachments.extend(message.attachments.iter().cloned());
 
         if !attachments.is_empty() {
             if !text_without_markers.is_empty() {
@@ -2822,7 +2867,7 @@ impl Channel for TelegramChannel {
             let probe = serde_json::json!({
                 "offset": offset,
                 "timeout": 0,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
             match self.http_client().post(&url).json(&probe).send().await {
                 Err(e) => {
@@ -2897,7 +2942,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.http_client().post(&url).json(&body).send().await {
@@ -2960,6 +3005,19 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
+                    if let Some((query_id, pressed)) = self.parse_callback_query(update) {
+                        let Some(msg) = pressed else {
+                            self.answer_callback_query(&query_id, Some("Not authorized"))
+                                .await;
+                            continue;
+                        };
+                        self.answer_callback_query(&query_id, None).await;
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
+                        continue;
+                    }
+
                     let msg = if let Some(m) = self.parse_update_message(update) {
                         m
                     } else if let Some(m) = self.try_parse_voice_message(update).await {
@@ -3163,6 +3221,60 @@ mod tests {
         assert!(guard.is_some());
     }
 
+    #[test]
+    fn parse_callback_query_maps_press_to_message() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()], false);
+        let update = serde_json::json!({
+            "update_id": 1,
+            "callback_query": {
+                "id": "cb-1",
+                "from": { "id": 555, "username": "alice" },
+                "message": {
+                    "message_id": 9,
+                    "chat": { "id": -100_200, "type": "supergroup" },
+                    "message_thread_id": 7
+                },
+                "data": "/approve-decide apr-1234abcd once"
+            }
+        });
+
+        let (query_id, msg) = ch.parse_callback_query(&update).unwrap();
+        let msg = msg.expect("allowed user press should produce a message");
+        assert_eq!(query_id, "cb-1");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "-100200:7");
+        assert_eq!(msg.content, "/approve-decide apr-1234abcd once");
+    }
+
+    #[test]
+    fn parse_callback_query_rejects_unlisted_user() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()], false);
+        let update = serde_json::json!({
+            "callback_query": {
+                "id": "cb-2",
+                "from": { "id": 777, "username": "mallory" },
+                "message": { "message_id": 9, "chat": { "id": 1 } },
+                "data": "/approve-decide apr-1234abcd once"
+            }
+        });
+
+        let (query_id, msg) = ch.parse_callback_query(&update).unwrap();
+        assert_eq!(query_id, "cb-2");
+        assert!(msg.is_none());
+    }
+
+    #[test]
+    fn inline_keyboard_uses_action_values_as_callback_data() {
+        use crate::channels::traits::{PromptAction, PromptActionStyle};
+
+        let prompt = InteractivePrompt::new("Approve?", "1")
+            .with_action(PromptAction::new("Yes", "/yes", PromptActionStyle::Primary))
+            .with_action(PromptAction::new("No", "/no", PromptActionStyle::Danger));
+        let keyboard = TelegramChannel::inline_keyboard(&prompt);
+        assert_eq!(keyboard["inline_keyboard"][0][0]["text"], "Yes");
+        assert_eq!(keyboard["inline_keyboard"][0][1]["callback_data"], "/no");
+    }
+
     #[test]
     fn supports_draft_updates_respects_stream_mode() {
         let off = TelegramChannel::new("fake-token".into(), vec!["*".into()], false);
@@ -3376,20 +3488,23 @@ mod tests {
     #[test]
     fn parse_attachment_markers_extracts_multiple_types() {
         let message = "Here are files [IMAGE:/tmp/a.png] and [DOCUMENT:https://example.com/a.pdf]";
-        let (cleaned, attachments) = parse_attachment_markers(message);
+        let (cleaned, attachments) = super::super::attachments::parse_markers(message);
 
         assert_eq!(cleaned, "Here are files  and");
         assert_eq!(attachments.len(), 2);
-        assert_eq!(attachments[0].kind, TelegramAttachmentKind::Image);
-        assert_eq!(attachments[0].target, "/tmp/a.png");
-        assert_eq!(attachments[1].kind, TelegramAttachmentKind::Document);
-        assert_eq!(attachments[1].target, "https://example.com/a.pdf");
+        assert_eq!(attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(attachments[0].target().unwrap(), "/tmp/a.png");
+        assert_eq!(attachments[1].kind, AttachmentKind::Document);
+        assert_eq!(
+            attachments[1].target().unwrap(),
+            "https://example.com/a.pdf"
+        );
     }
 
     #[test]
     fn parse_attachment_markers_keeps_invalid_markers_in_text() {
         let message = "Report [UNKNOWN:/tmp/a.bin]";
-        let (cleaned, attachments) = parse_attachment_markers(message);
+        let (cleaned, attachments) = super::super::attachments::parse_markers(message);
 
         assert_eq!(cleaned, "Report [UNKNOWN:/tmp/a.bin]");
         assert!(attachments.is_empty());
@@ -3398,13 +3513,13 @@ mod tests {
     #[test]
     fn parse_attachment_markers_handles_brackets_in_filename() {
         let message = "Here it is [VIDEO:/mnt/clips/Butters - What What [G4PvTrTp7Tc].mp4]";
-        let (cleaned, attachments) = parse_attachment_markers(message);
+        let (cleaned, attachments) = super::super::attachments::parse_markers(message);
 
         assert_eq!(cleaned, "Here it is");
         assert_eq!(attachments.len(), 1);
-        assert_eq!(attachments[0].kind, TelegramAttachmentKind::Video);
+        assert_eq!(attachments[0].kind, AttachmentKind::Video);
         assert_eq!(
-            attachments[0].target,
+            attachments[0].target().unwrap(),
             "/mnt/clips/Butters - What What [G4PvTrTp7Tc].mp4"
         );
     }
@@ -3412,7 +3527,7 @@ mod tests {
     #[test]
     fn parse_attachment_markers_unclosed_bracket_falls_back_to_text() {
         let message = "send [VIDEO:/path/file[broken.mp4";
-        let (cleaned, attachments) = parse_attachment_markers(message);
+        let (cleaned, attachments) = super::super::attachments::parse_markers(message);
         assert_eq!(cleaned, "send [VIDEO:/path/file[broken.mp4");
         assert!(attachments.is_empty());
     }
@@ -3426,8 +3541,8 @@ mod tests {
         let parsed = parse_path_only_attachment(image_path.to_string_lossy().as_ref())
             .expect("expected attachment");
 
-        assert_eq!(parsed.kind, TelegramAttachmentKind::Image);
-        assert_eq!(parsed.target, image_path.to_string_lossy());
+        assert_eq!(parsed.kind, AttachmentKind::Image);
+        assert_eq!(parsed.target().unwrap(), image_path.to_string_lossy());
     }
 
     #[test]
@@ -3526,8 +3641,8 @@ mod tests {
     #[test]
     fn infer_attachment_kind_from_target_detects_document_extension() {
         assert_eq!(
-            infer_attachment_kind_from_target("https://example.com/files/specs.pdf?download=1"),
-            Some(TelegramAttachmentKind::Document)
+            AttachmentKind::from_target("https://example.com/files/specs.pdf?download=1"),
+            Some(AttachmentKind::Document)
         );
     }
 
@@ -4996,6 +5111,21 @@ mod tests {
 
     // ── Attachment content format tests ──────────────────────────────
 
+    /// What the agent sees for a Telegram attachment saved at `local_path`.
+    fn format_attachment_content(
+        kind: IncomingAttachmentKind,
+        local_filename: &str,
+        local_path: &Path,
+    ) -> String {
+        let attachment =
+            Attachment::from_path(incoming_attachment_kind(kind, local_path), local_path)
+                .with_filename(local_filename);
+        crate::channels::attachments::describe_attachment(
+            &attachment,
+            &local_path.display().to_string(),
+        )
+    }
+
     /// Photo attachments with image extension must use `[IMAGE:/path]` marker
     /// so the multimodal pipeline validates vision capability on the provider.
     #[test]
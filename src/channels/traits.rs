@@ -1,4 +1,224 @@
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+/// Kind of media carried by an [`Attachment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttachmentKind {
+    Image,
+    Document,
+    Audio,
+    Voice,
+    Video,
+}
+
+impl AttachmentKind {
+    /// Parse the tag of a `[KIND:target]` reply marker.
+    pub fn from_marker(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_uppercase().as_str() {
+            "IMAGE" | "PHOTO" => Some(Self::Image),
+            "DOCUMENT" | "FILE" => Some(Self::Document),
+            "VIDEO" => Some(Self::Video),
+            "AUDIO" => Some(Self::Audio),
+            "VOICE" => Some(Self::Voice),
+            _ => None,
+        }
+    }
+
+    /// Canonical marker tag, as used in `channel_delivery_instructions`.
+    pub fn marker_name(self) -> &'static str {
+        match self {
+            Self::Image => "IMAGE",
+            Self::Document => "DOCUMENT",
+            Self::Video => "VIDEO",
+            Self::Audio => "AUDIO",
+            Self::Voice => "VOICE",
+        }
+    }
+
+    /// Classify a MIME type; anything unrecognised is a document.
+    pub fn from_mime(mime: &str) -> Self {
+        let mime = mime.trim().to_ascii_lowercase();
+        if mime.starts_with("image/") {
+            Self::Image
+        } else if mime.starts_with("video/") {
+            Self::Video
+        } else if mime == "audio/ogg" || mime == "audio/opus" {
+            Self::Voice
+        } else if mime.starts_with("audio/") {
+            Self::Audio
+        } else {
+            Self::Document
+        }
+    }
+
+    /// Infer the kind from a path or URL extension.
+    pub fn from_target(target: &str) -> Option<Self> {
+        let normalized = target.split(['?', '#']).next().unwrap_or(target);
+        let extension = Path::new(normalized)
+            .extension()
+            .and_then(|ext| ext.to_str())?
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Some(Self::Image),
+            "mp4" | "mov" | "mkv" | "avi" | "webm" => Some(Self::Video),
+            "mp3" | "m4a" | "wav" | "flac" => Some(Self::Audio),
+            "ogg" | "oga" | "opus" => Some(Self::Voice),
+            "pdf" | "txt" | "md" | "csv" | "json" | "zip" | "tar" | "gz" | "doc" | "docx"
+            | "xls" | "xlsx" | "ppt" | "pptx" => Some(Self::Document),
+            _ => None,
+        }
+    }
+}
+
+/// Where an attachment's content lives.
+#[derive(Clone, PartialEq, Eq)]
+pub enum AttachmentData {
+    /// Content already downloaded into memory.
+    Bytes(Vec<u8>),
+    /// A file on the local filesystem (usually inside the workspace).
+    Path(PathBuf),
+    /// A remote `http(s)` URL.
+    Url(String),
+}
+
+impl std::fmt::Debug for AttachmentData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Url(url) => f.debug_tuple("Url").field(url).finish(),
+        }
+    }
+}
+
+/// A file, image or voice note travelling alongside a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    pub mime: Option<String>,
+    pub filename: Option<String>,
+    /// Size in bytes, when the platform reports it or the bytes are at hand.
+    pub size: Option<u64>,
+    pub data: AttachmentData,
+    pub caption: Option<String>,
+}
+
+impl Attachment {
+    /// Attachment backed by in-memory bytes.
+    pub fn from_bytes(kind: AttachmentKind, bytes: Vec<u8>) -> Self {
+        Self {
+            kind,
+            mime: None,
+            filename: None,
+            size: Some(bytes.len() as u64),
+            data: AttachmentData::Bytes(bytes),
+            caption: None,
+        }
+    }
+
+    /// Attachment backed by a local file.
+    pub fn from_path(kind: AttachmentKind, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string);
+        Self {
+            kind,
+            mime: None,
+            filename,
+            size: None,
+            data: AttachmentData::Path(path),
+            caption: None,
+        }
+    }
+
+    /// Attachment referenced by a remote URL.
+    pub fn from_url(kind: AttachmentKind, url: impl Into<String>) -> Self {
+        Self {
+            kind,
+            mime: None,
+            filename: None,
+            size: None,
+            data: AttachmentData::Url(url.into()),
+            caption: None,
+        }
+    }
+
+    pub fn with_mime(mut self, mime: impl Into<String>) -> Self {
+        self.mime = Some(mime.into());
+        self
+    }
+
+    pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        let caption = caption.into();
+        if !caption.trim().is_empty() {
+            self.caption = Some(caption);
+        }
+        self
+    }
+
+    /// Path or URL the attachment points at; `None` for in-memory bytes.
+    pub fn target(&self) -> Option<String> {
+        match &self.data {
+            AttachmentData::Bytes(_) => None,
+            AttachmentData::Path(path) => Some(path.display().to_string()),
+            AttachmentData::Url(url) => Some(url.clone()),
+        }
+    }
+
+    /// Read the attachment content, loading it from disk when needed.
+    /// Remote URLs are left to the channel, which knows how to authenticate.
+    pub async fn read_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        match &self.data {
+            AttachmentData::Bytes(bytes) => Ok(bytes.clone()),
+            AttachmentData::Path(path) => tokio::fs::read(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("failed to read attachment {}: {e}", path.display())),
+            AttachmentData::Url(url) => {
+                anyhow::bail!("attachment {url} is remote; fetch it before reading")
+            }
+        }
+    }
+
+    /// Best-effort filename for uploads.
+    pub fn file_name_or_default(&self) -> String {
+        self.filename.clone().unwrap_or_else(|| {
+            let ext = self
+                .mime
+                .as_deref()
+                .and_then(|mime| mime.split('/').nth(1))
+                .map(|sub| sub.split([';', '+']).next().unwrap_or(sub).trim())
+                .filter(|sub| !sub.is_empty() && sub.chars().all(|c| c.is_ascii_alphanumeric()))
+                .unwrap_or("bin");
+            format!("{}.{ext}", self.kind.marker_name().to_ascii_lowercase())
+        })
+    }
+
+    /// MIME type, falling back to a guess from the filename.
+    pub fn mime_or_guess(&self) -> String {
+        if let Some(mime) = self.mime.as_deref().filter(|m| !m.trim().is_empty()) {
+            return mime.to_string();
+        }
+        let name = self
+            .filename
+            .clone()
+            .or_else(|| self.target())
+            .unwrap_or_default();
+        super::attachments::mime_from_name(&name).to_string()
+    }
+}
 
 /// A message received from or sent to a channel
 #[derive(Debug, Clone)]
@@ -12,6 +232,8 @@ pub struct ChannelMessage {
     /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
     /// When set, replies should be posted as threaded responses.
     pub thread_ts: Option<String>,
+    /// Files, images and voice notes that arrived with the message.
+    pub attachments: Vec<Attachment>,
 }
 
 /// Message to send through a channel
@@ -22,6 +244,8 @@ pub struct SendMessage {
     pub subject: Option<String>,
     /// Platform thread identifier for threaded replies (e.g. Slack `thread_ts`).
     pub thread_ts: Option<String>,
+    /// Media to deliver with the message.
+    pub attachments: Vec<Attachment>,
 }
 
 impl SendMessage {
@@ -32,6 +256,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: None,
             thread_ts: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -46,6 +271,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: Some(subject.into()),
             thread_ts: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -54,6 +280,87 @@ impl SendMessage {
         self.thread_ts = thread_ts;
         self
     }
+
+    /// Attach media to deliver alongside the text.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments.extend(attachments);
+        self
+    }
+
+    /// Text to deliver plus every attachment, including any legacy
+    /// `[IMAGE:...]`-style markers still embedded in `content`.
+    ///
+    /// Channels call this instead of parsing markers themselves so that
+    /// model replies, cron deliveries and explicit attachments all take the
+    /// same path.
+    pub fn split_attachments(&self) -> (String, Vec<Attachment>) {
+        let (text, mut attachments) = super::attachments::parse_markers(&self.content);
+        attachments.extend(self.attachments.iter().cloned());
+        (text, attachments)
+    }
+}
+
+/// Visual emphasis for a button in an [`InteractivePrompt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptActionStyle {
+    Primary,
+    Secondary,
+    Danger,
+}
+
+/// One button of an interactive prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptAction {
+    pub label: String,
+    /// Delivered back as the `content` of a [`ChannelMessage`] from whoever
+    /// pressed the button, so it must be short (Telegram allows 64 bytes).
+    pub value: String,
+    pub style: PromptActionStyle,
+}
+
+impl PromptAction {
+    pub fn new(
+        label: impl Into<String>,
+        value: impl Into<String>,
+        style: PromptActionStyle,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+            style,
+        }
+    }
+}
+
+/// A message rendered with buttons (inline keyboard, Block Kit actions,
+/// message components) on channels that support them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteractivePrompt {
+    pub text: String,
+    pub recipient: String,
+    pub thread_ts: Option<String>,
+    pub actions: Vec<PromptAction>,
+}
+
+impl InteractivePrompt {
+    pub fn new(text: impl Into<String>, recipient: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            recipient: recipient.into(),
+            thread_ts: None,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn in_thread(mut self, thread_ts: Option<String>) -> Self {
+        self.thread_ts = thread_ts;
+        self
+    }
+
+    pub fn with_action(mut self, action: PromptAction) -> Self {
+        self.actions.push(action);
+        self
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -123,6 +430,23 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Whether this channel can render [`InteractivePrompt`] buttons and
+    /// deliver presses back through `listen`.
+    fn supports_interactive_prompts(&self) -> bool {
+        false
+    }
+
+    /// Send a prompt with buttons. A press arrives as a [`ChannelMessage`]
+    /// whose `sender` is the user who pressed and whose `content` is the
+    /// action's `value`. The default sends the prompt text only.
+    async fn send_interactive_prompt(&self, prompt: &InteractivePrompt) -> anyhow::Result<()> {
+        self.send(
+            &SendMessage::new(prompt.text.clone(), &prompt.recipient)
+                .in_thread(prompt.thread_ts.clone()),
+        )
+        .await
+    }
+
     /// Add a reaction (emoji) to a message.
     ///
     /// `channel_id` is the platform channel/conversation identifier (e.g. Discord channel ID).
@@ -176,6 +500,7 @@ mod tests {
                 channel: "dummy".into(),
                 timestamp: 123,
                 thread_ts: None,
+                attachments: Vec::new(),
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -192,6 +517,7 @@ mod tests {
             channel: "dummy".into(),
             timestamp: 999,
             thread_ts: None,
+            attachments: Vec::new(),
         };
 
         let cloned = message.clone();
@@ -230,6 +556,19 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn default_interactive_prompt_falls_back_to_text() {
+        let channel = DummyChannel;
+        assert!(!channel.supports_interactive_prompts());
+
+        let prompt = InteractivePrompt::new("Approve?", "chat-1").with_action(PromptAction::new(
+            "Yes",
+            "/yes",
+            PromptActionStyle::Primary,
+        ));
+        assert!(channel.send_interactive_prompt(&prompt).await.is_ok());
+    }
+
     #[tokio::test]
     async fn default_draft_methods_return_success() {
         let channel = DummyChannel;
@@ -1,7 +1,14 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachments::{fetch_bytes, load_outgoing, MAX_INBOUND_ATTACHMENT_BYTES};
+use super::traits::{
+    Attachment, AttachmentData, AttachmentKind, Channel, ChannelMessage, SendMessage,
+};
 use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use std::path::PathBuf;
 use uuid::Uuid;
 
+const GRAPH_API_BASE: &str = "https://graph.facebook.com/v18.0";
+
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -27,6 +34,7 @@ pub struct WhatsAppChannel {
     endpoint_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl WhatsAppChannel {
@@ -41,9 +49,16 @@ impl WhatsAppChannel {
             endpoint_id,
             verify_token,
             allowed_numbers,
+            workspace_dir: None,
         }
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.whatsapp")
     }
@@ -105,20 +120,24 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
+                    // Text messages carry a body; media messages carry a media id
+                    // that `download_media` resolves once the payload is parsed.
+                    let mut attachments = Vec::new();
                     let content = if let Some(text_obj) = msg.get("text") {
                         text_obj
                             .get("body")
                             .and_then(|b| b.as_str())
                             .unwrap_or("")
                             .to_string()
+                    } else if let Some(attachment) = Self::parse_media(msg) {
+                        attachments.push(attachment);
+                        String::new()
                     } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
+                        tracing::debug!("WhatsApp: skipping unsupported message from {from}");
                         continue;
                     };
 
-                    if content.is_empty() {
+                    if content.is_empty() && attachments.is_empty() {
                         continue;
                     }
 
@@ -142,6 +161,7 @@ impl WhatsAppChannel {
                         channel: "whatsapp".to_string(),
                         timestamp,
                         thread_ts: None,
+                        attachments,
                     });
                 }
             }
@@ -149,37 +169,168 @@ impl WhatsAppChannel {
 
         messages
     }
-}
 
-#[async_trait]
-impl Channel for WhatsAppChannel {
-    fn name(&self) -> &str {
-        "whatsapp"
+    /// Map an image/audio/video/document webhook message to an attachment
+    /// that still points at the Graph media endpoint.
+    fn parse_media(msg: &serde_json::Value) -> Option<Attachment> {
+        let msg_type = msg.get("type").and_then(|t| t.as_str())?;
+        let media = msg.get(msg_type)?;
+        let kind = match msg_type {
+            "image" => AttachmentKind::Image,
+            "video" => AttachmentKind::Video,
+            "document" => AttachmentKind::Document,
+            "audio" if media.get("voice").and_then(|v| v.as_bool()) == Some(true) => {
+                AttachmentKind::Voice
+            }
+            "audio" => AttachmentKind::Audio,
+            _ => return None,
+        };
+        let id = media.get("id").and_then(|i| i.as_str())?;
+
+        let mut attachment = Attachment::from_url(kind, format!("{GRAPH_API_BASE}/{id}"));
+        if let Some(mime) = media.get("mime_type").and_then(|m| m.as_str()) {
+            attachment = attachment.with_mime(mime);
+        }
+        if let Some(filename) = media.get("filename").and_then(|f| f.as_str()) {
+            attachment = attachment.with_filename(filename);
+        }
+        if let Some(caption) = media.get("caption").and_then(|c| c.as_str()) {
+            attachment = attachment.with_caption(caption);
+        }
+        Some(attachment)
     }
 
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.endpoint_id
-        );
+    /// Replace Graph media references on parsed messages with the downloaded
+    /// bytes. Media that cannot be fetched is dropped, along with messages
+    /// left with neither text nor attachments.
+    pub async fn download_media(&self, messages: &mut Vec<ChannelMessage>) {
+        let client = self.http_client();
+        for msg in messages.iter_mut() {
+            let mut resolved = Vec::with_capacity(msg.attachments.len());
+            for mut attachment in std::mem::take(&mut msg.attachments) {
+                let AttachmentData::Url(endpoint) = &attachment.data else {
+                    resolved.push(attachment);
+                    continue;
+                };
+                match self.fetch_media(&client, endpoint).await {
+                    Ok(bytes) => {
+                        attachment.size = Some(bytes.len() as u64);
+                        attachment.data = AttachmentData::Bytes(bytes);
+                        resolved.push(attachment);
+                    }
+                    Err(e) => tracing::warn!("WhatsApp: failed to download media: {e}"),
+                }
+            }
+            msg.attachments = resolved;
+        }
+        messages.retain(|msg| !msg.content.is_empty() || !msg.attachments.is_empty());
+    }
 
-        // Normalize recipient (remove leading + if present for API)
-        let to = message
-            .recipient
-            .strip_prefix('+')
-            .unwrap_or(&message.recipient);
+    async fn fetch_media(
+        &self,
+        client: &reqwest::Client,
+        endpoint: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        ensure_https(endpoint)?;
+        let meta: serde_json::Value = client
+            .get(endpoint)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if meta
+            .get("file_size")
+            .and_then(serde_json::Value::as_u64)
+            .is_some_and(|size| size > MAX_INBOUND_ATTACHMENT_BYTES)
+        {
+            anyhow::bail!("media exceeds {MAX_INBOUND_ATTACHMENT_BYTES} bytes");
+        }
+        let url = meta
+            .get("url")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("media lookup returned no url"))?;
+        ensure_https(url)?;
+        fetch_bytes(
+            client.get(url).bearer_auth(&self.access_token),
+            MAX_INBOUND_ATTACHMENT_BYTES,
+        )
+        .await
+    }
+
+    /// Upload media bytes and return the media id to reference in a message.
+    async fn upload_media(
+        &self,
+        attachment: &Attachment,
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let url = format!("{GRAPH_API_BASE}/{}/media", self.endpoint_id);
+        ensure_https(&url)?;
+        let mime = attachment.mime_or_guess();
+        let part = Part::bytes(bytes)
+            .file_name(attachment.file_name_or_default())
+            .mime_str(&mime)?;
+        let form = Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime)
+            .part("file", part);
+
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(&error_body);
+            tracing::error!("WhatsApp media upload failed: {status} — {sanitized}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+        let body: serde_json::Value = resp.json().await?;
+        body.get("id")
+            .and_then(|i| i.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media upload returned no id"))
+    }
 
-        let body = serde_json::json!({
+    /// Build the `type`/media object pair for an outgoing attachment message.
+    fn media_message_body(
+        to: &str,
+        attachment: &Attachment,
+        media: serde_json::Value,
+    ) -> serde_json::Value {
+        let media_type = match attachment.kind {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Video => "video",
+            AttachmentKind::Audio | AttachmentKind::Voice => "audio",
+            AttachmentKind::Document => "document",
+        };
+        let mut media = media;
+        // Audio messages reject captions; documents keep their file name.
+        if media_type != "audio" {
+            if let Some(caption) = attachment.caption.as_deref() {
+                media["caption"] = serde_json::json!(caption);
+            }
+        }
+        if media_type == "document" {
+            media["filename"] = serde_json::json!(attachment.file_name_or_default());
+        }
+        let mut body = serde_json::json!({
             "messaging_product": "whatsapp",
             "recipient_type": "individual",
             "to": to,
-            "type": "text",
-            "text": {
-                "preview_url": false,
-                "body": message.content
-            }
+            "type": media_type,
         });
+        body[media_type] = media;
+        body
+    }
+
+    async fn post_message(&self, body: &serde_json::Value) -> anyhow::Result<()> {
+        let url = format!("{GRAPH_API_BASE}/{}/messages", self.endpoint_id);
 
         ensure_https(&url)?;
 
@@ -188,7 +339,7 @@ impl Channel for WhatsAppChannel {
             .post(&url)
             .bearer_auth(&self.access_token)
             .header("Content-Type", "application/json")
-            .json(&body)
+            .json(body)
             .send()
             .await?;
 
@@ -202,6 +353,57 @@ impl Channel for WhatsAppChannel {
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl Channel for WhatsAppChannel {
+    fn name(&self) -> &str {
+        "whatsapp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
+        // Normalize recipient (remove leading + if present for API)
+        let to = message
+            .recipient
+            .strip_prefix('+')
+            .unwrap_or(&message.recipient);
+
+        let (text, attachments) = message.split_attachments();
+
+        if !text.is_empty() || attachments.is_empty() {
+            let body = serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "text",
+                "text": {
+                    "preview_url": false,
+                    "body": text
+                }
+            });
+            self.post_message(&body).await?;
+        }
+
+        for attachment in &attachments {
+            let media = if let AttachmentData::Url(link) = &attachment.data {
+                serde_json::json!({ "link": link })
+            } else {
+                let bytes = load_outgoing(
+                    attachment,
+                    self.workspace_dir.as_deref(),
+                    "channel.whatsapp",
+                )
+                .await?;
+                let id = self.upload_media(attachment, bytes).await?;
+                serde_json::json!({ "id": id })
+            };
+            self.post_message(&Self::media_message_body(to, attachment, media))
+                .await?;
+        }
+
+        Ok(())
+    }
 
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         // WhatsApp uses webhooks (push-based), not polling.
@@ -220,7 +422,7 @@ impl Channel for WhatsAppChannel {
 
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
-        let url = format!("https://graph.facebook.com/v18.0/{}", self.endpoint_id);
+        let url = format!("{GRAPH_API_BASE}/{}", self.endpoint_id);
 
         if ensure_https(&url).is_err() {
             return false;
@@ -350,7 +552,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_non_text_message_skipped() {
+    fn whatsapp_parse_image_message_has_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -368,7 +570,14 @@ mod tests {
         });
 
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty(), "Non-text messages should be skipped");
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].content.is_empty());
+        assert_eq!(msgs[0].attachments.len(), 1);
+        assert_eq!(msgs[0].attachments[0].kind, AttachmentKind::Image);
+        assert_eq!(
+            msgs[0].attachments[0].target().as_deref(),
+            Some("https://graph.facebook.com/v18.0/img123")
+        );
     }
 
     #[test]
@@ -737,7 +946,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_audio_message_skipped() {
+    fn whatsapp_parse_audio_message_has_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -754,11 +963,14 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        let attachment = &msgs[0].attachments[0];
+        assert_eq!(attachment.kind, AttachmentKind::Audio);
+        assert_eq!(attachment.mime.as_deref(), Some("audio/ogg"));
     }
 
     #[test]
-    fn whatsapp_parse_video_message_skipped() {
+    fn whatsapp_parse_video_message_has_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -775,11 +987,12 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].attachments[0].kind, AttachmentKind::Video);
     }
 
     #[test]
-    fn whatsapp_parse_document_message_skipped() {
+    fn whatsapp_parse_document_message_has_attachment() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -796,7 +1009,35 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        let attachment = &msgs[0].attachments[0];
+        assert_eq!(attachment.kind, AttachmentKind::Document);
+        assert_eq!(attachment.filename.as_deref(), Some("file.pdf"));
+    }
+
+    #[test]
+    fn whatsapp_media_message_body_sets_caption_and_filename() {
+        let attachment = Attachment::from_path(AttachmentKind::Document, "/tmp/report.pdf")
+            .with_caption("Q3 numbers");
+        let body = WhatsAppChannel::media_message_body(
+            "15551234567",
+            &attachment,
+            serde_json::json!({ "id": "media-1" }),
+        );
+        assert_eq!(body["type"], "document");
+        assert_eq!(body["document"]["id"], "media-1");
+        assert_eq!(body["document"]["caption"], "Q3 numbers");
+        assert_eq!(body["document"]["filename"], "report.pdf");
+
+        let voice = Attachment::from_url(AttachmentKind::Voice, "https://example.com/a.ogg")
+            .with_caption("ignored");
+        let body = WhatsAppChannel::media_message_body(
+            "15551234567",
+            &voice,
+            serde_json::json!({ "link": "https://example.com/a.ogg" }),
+        );
+        assert_eq!(body["type"], "audio");
+        assert!(body["audio"].get("caption").is_none());
     }
 
     #[test]
@@ -26,132 +26,27 @@
 //! This channel is automatically selected when `session_path` is set in the config.
 //! The Cloud API channel is used when `phone_number_id` is set.
 
+#[cfg(feature = "whatsapp-web")]
+use super::traits::{Attachment, AttachmentKind};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use super::whatsapp_storage::RusqliteStore;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use parking_lot::Mutex;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::select;
 
 // ── Media attachment support ──────────────────────────────────────────
 
-/// Supported WhatsApp media attachment kinds.
-#[cfg(feature = "whatsapp-web")]
-#[derive(Debug, Clone, Copy)]
-enum WaAttachmentKind {
-    Image,
-    Document,
-    Video,
-    Audio,
-}
-
+/// Map an attachment kind to the wa-rs `MediaType` used for upload encryption.
 #[cfg(feature = "whatsapp-web")]
-impl WaAttachmentKind {
-    /// Parse from the marker prefix (case-insensitive).
-    fn from_marker(s: &str) -> Option<Self> {
-        match s.to_ascii_uppercase().as_str() {
-            "IMAGE" => Some(Self::Image),
-            "DOCUMENT" => Some(Self::Document),
-            "VIDEO" => Some(Self::Video),
-            "AUDIO" => Some(Self::Audio),
-            _ => None,
-        }
-    }
-
-    /// Map to the wa-rs `MediaType` used for upload encryption.
-    fn media_type(self) -> wa_rs_core::download::MediaType {
-        match self {
-            Self::Image => wa_rs_core::download::MediaType::Image,
-            Self::Document => wa_rs_core::download::MediaType::Document,
-            Self::Video => wa_rs_core::download::MediaType::Video,
-            Self::Audio => wa_rs_core::download::MediaType::Audio,
-        }
-    }
-}
-
-/// A parsed media attachment from `[KIND:path]` markers in the response text.
-#[cfg(feature = "whatsapp-web")]
-#[derive(Debug, Clone)]
-struct WaAttachment {
-    kind: WaAttachmentKind,
-    target: String,
-}
-
-/// Parse `[IMAGE:/path]`, `[DOCUMENT:/path]`, etc. markers out of a message.
-/// Returns the cleaned text (markers removed) and a vec of attachments.
-#[cfg(feature = "whatsapp-web")]
-fn parse_wa_attachment_markers(message: &str) -> (String, Vec<WaAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0;
-
-    while cursor < message.len() {
-        let Some(open_rel) = message[cursor..].find('[') else {
-            cleaned.push_str(&message[cursor..]);
-            break;
-        };
-
-        let open = cursor + open_rel;
-        cleaned.push_str(&message[cursor..open]);
-
-        let Some(close_rel) = message[open..].find(']') else {
-            cleaned.push_str(&message[open..]);
-            break;
-        };
-
-        let close = open + close_rel;
-        let marker = &message[open + 1..close];
-
-        let parsed = marker.split_once(':').and_then(|(kind, target)| {
-            let kind = WaAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
-                return None;
-            }
-            Some(WaAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
-
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            // Not a valid media marker — keep the original text.
-            cleaned.push_str(&message[open..=close]);
-        }
-
-        cursor = close + 1;
-    }
-
-    (cleaned.trim().to_string(), attachments)
-}
-
-/// Infer MIME type from file extension.
-#[cfg(feature = "whatsapp-web")]
-fn mime_from_path(path: &std::path::Path) -> &'static str {
-    match path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase()
-        .as_str()
-    {
-        "png" => "image/png",
-        "jpg" | "jpeg" => "image/jpeg",
-        "gif" => "image/gif",
-        "webp" => "image/webp",
-        "mp4" => "video/mp4",
-        "mov" => "video/quicktime",
-        "mp3" => "audio/mpeg",
-        "ogg" | "opus" => "audio/ogg",
-        "pdf" => "application/pdf",
-        "doc" => "application/msword",
-        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
-        "xls" => "application/vnd.ms-excel",
-        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
-        _ => "application/octet-stream",
+fn media_type(kind: AttachmentKind) -> wa_rs_core::download::MediaType {
+    match kind {
+        AttachmentKind::Image => wa_rs_core::download::MediaType::Image,
+        AttachmentKind::Document => wa_rs_core::download::MediaType::Document,
+        AttachmentKind::Video => wa_rs_core::download::MediaType::Video,
+        AttachmentKind::Audio | AttachmentKind::Voice => wa_rs_core::download::MediaType::Audio,
     }
 }
 
@@ -185,6 +80,8 @@ pub struct WhatsAppWebChannel {
     client: Arc<Mutex<Option<Arc<wa_rs::Client>>>>,
     /// Message sender channel
     tx: Arc<Mutex<Option<tokio::sync::mpsc::Sender<ChannelMessage>>>>,
+    /// Workspace directory that local attachment paths must resolve inside
+    workspace_dir: Option<PathBuf>,
 }
 
 impl WhatsAppWebChannel {
@@ -211,9 +108,17 @@ impl WhatsAppWebChannel {
             bot_handle: Arc::new(Mutex::new(None)),
             client: Arc::new(Mutex::new(None)),
             tx: Arc::new(Mutex::new(None)),
+            workspace_dir: None,
         }
     }
 
+    /// Configure workspace directory used for validating local attachment paths.
+    #[cfg(feature = "whatsapp-web")]
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     /// Check if a phone number is allowed (E.164 format: +1234567890)
     #[cfg(feature = "whatsapp-web")]
     fn is_number_allowed(&self, phone: &str) -> bool {
@@ -361,18 +266,17 @@ impl WhatsAppWebChannel {
         &self,
         client: &Arc<wa_rs::Client>,
         to: &wa_rs_binary::jid::Jid,
-        attachment: &WaAttachment,
+        attachment: &Attachment,
     ) -> Result<()> {
-        use std::path::Path;
-
-        let path = Path::new(&attachment.target);
-        if !path.exists() {
-            anyhow::bail!("Media file not found: {}", attachment.target);
-        }
-
-        let data = tokio::fs::read(path).await?;
+        let data = super::attachments::load_outgoing(
+            attachment,
+            self.workspace_dir.as_deref(),
+            "channel.whatsapp",
+        )
+        .await?;
         let file_len = data.len() as u64;
-        let mimetype = mime_from_path(path).to_string();
+        let mimetype = attachment.mime_or_guess();
+        let caption = attachment.caption.clone();
 
         tracing::info!(
             "WhatsApp Web: uploading {:?} ({} bytes, {})",
@@ -381,10 +285,10 @@ impl WhatsAppWebChannel {
             mimetype
         );
 
-        let upload = client.upload(data, attachment.kind.media_type()).await?;
+        let upload = client.upload(data, media_type(attachment.kind)).await?;
 
         let outgoing = match attachment.kind {
-            WaAttachmentKind::Image => wa_rs_proto::whatsapp::Message {
+            AttachmentKind::Image => wa_rs_proto::whatsapp::Message {
                 image_message: Some(Box::new(wa_rs_proto::whatsapp::message::ImageMessage {
                     url: Some(upload.url),
                     direct_path: Some(upload.direct_path),
@@ -393,16 +297,13 @@ impl WhatsAppWebChannel {
                     file_sha256: Some(upload.file_sha256),
                     file_length: Some(upload.file_length),
                     mimetype: Some(mimetype),
+                    caption,
                     ..Default::default()
                 })),
                 ..Default::default()
             },
-            WaAttachmentKind::Document => {
-                let file_name = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("file")
-                    .to_string();
+            AttachmentKind::Document => {
+                let file_name = attachment.file_name_or_default();
                 wa_rs_proto::whatsapp::Message {
                     document_message: Some(Box::new(
                         wa_rs_proto::whatsapp::message::DocumentMessage {
@@ -414,13 +315,14 @@ impl WhatsAppWebChannel {
                             file_length: Some(upload.file_length),
                             mimetype: Some(mimetype),
                             file_name: Some(file_name),
+                            caption,
                             ..Default::default()
                         },
                     )),
                     ..Default::default()
                 }
             }
-            WaAttachmentKind::Video => wa_rs_proto::whatsapp::Message {
+            AttachmentKind::Video => wa_rs_proto::whatsapp::Message {
                 video_message: Some(Box::new(wa_rs_proto::whatsapp::message::VideoMessage {
                     url: Some(upload.url),
                     direct_path: Some(upload.direct_path),
@@ -429,11 +331,12 @@ impl WhatsAppWebChannel {
                     file_sha256: Some(upload.file_sha256),
                     file_length: Some(upload.file_length),
                     mimetype: Some(mimetype),
+                    caption,
                     ..Default::default()
                 })),
                 ..Default::default()
             },
-            WaAttachmentKind::Audio => wa_rs_proto::whatsapp::Message {
+            AttachmentKind::Audio | AttachmentKind::Voice => wa_rs_proto::whatsapp::Message {
                 audio_message: Some(Box::new(wa_rs_proto::whatsapp::message::AudioMessage {
                     url: Some(upload.url),
                     direct_path: Some(upload.direct_path),
@@ -485,8 +388,8 @@ impl Channel for WhatsAppWebChannel {
 
         let to = self.recipient_to_jid(&message.recipient)?;
 
-        // Parse media attachment markers from the response text.
-        let (text_without_markers, attachments) = parse_wa_attachment_markers(&message.content);
+        // Split media attachment markers out of the response text.
+        let (text_without_markers, attachments) = message.split_attachments();
 
         // Send any text portion first.
         if !text_without_markers.is_empty() {
@@ -505,15 +408,18 @@ impl Channel for WhatsAppWebChannel {
         // Send each media attachment.
         for attachment in &attachments {
             if let Err(e) = self.send_media_attachment(&client, &to, attachment).await {
+                let target = attachment
+                    .target()
+                    .unwrap_or_else(|| attachment.file_name_or_default());
                 tracing::error!(
                     "WhatsApp Web: failed to send {:?} attachment {}: {}",
                     attachment.kind,
-                    attachment.target,
+                    target,
                     e
                 );
                 // Fall back to sending the path as text so the user knows something went wrong.
                 let fallback = wa_rs_proto::whatsapp::Message {
-                    conversation: Some(format!("[Failed to send media: {}]", attachment.target)),
+                    conversation: Some(format!("[Failed to send media: {target}]")),
                     ..Default::default()
                 };
                 let _ = client.send_message(to.clone(), fallback).await;
@@ -651,6 +557,7 @@ impl Channel for WhatsAppWebChannel {
                                         content: trimmed.to_string(),
                                         timestamp: chrono::Utc::now().timestamp() as u64,
                                         thread_ts: None,
+                                        attachments: Vec::new(),
                                     })
                                     .await
                                 {
@@ -828,6 +735,10 @@ impl WhatsAppWebChannel {
     ) -> Self {
         Self { _private: () }
     }
+
+    pub fn with_workspace_dir(self, _dir: PathBuf) -> Self {
+        self
+    }
 }
 
 #[cfg(not(feature = "whatsapp-web"))]
@@ -967,41 +878,14 @@ mod tests {
 
     #[test]
     #[cfg(feature = "whatsapp-web")]
-    fn parse_wa_markers_image() {
-        let msg = "Here is the timeline [IMAGE:/tmp/chart.png]";
-        let (text, attachments) = parse_wa_attachment_markers(msg);
-        assert_eq!(text, "Here is the timeline");
-        assert_eq!(attachments.len(), 1);
-        assert_eq!(attachments[0].target, "/tmp/chart.png");
-        assert!(matches!(attachments[0].kind, WaAttachmentKind::Image));
-    }
-
-    #[test]
-    #[cfg(feature = "whatsapp-web")]
-    fn parse_wa_markers_multiple() {
-        let msg = "Text [IMAGE:/a.png] more [DOCUMENT:/b.pdf]";
-        let (text, attachments) = parse_wa_attachment_markers(msg);
-        assert_eq!(text, "Text  more");
-        assert_eq!(attachments.len(), 2);
-        assert!(matches!(attachments[0].kind, WaAttachmentKind::Image));
-        assert!(matches!(attachments[1].kind, WaAttachmentKind::Document));
-    }
-
-    #[test]
-    #[cfg(feature = "whatsapp-web")]
-    fn parse_wa_markers_no_markers() {
-        let msg = "Just regular text";
-        let (text, attachments) = parse_wa_attachment_markers(msg);
-        assert_eq!(text, "Just regular text");
-        assert!(attachments.is_empty());
-    }
-
-    #[test]
-    #[cfg(feature = "whatsapp-web")]
-    fn parse_wa_markers_unknown_kind_preserved() {
-        let msg = "Check [UNKNOWN:/foo] out";
-        let (text, attachments) = parse_wa_attachment_markers(msg);
-        assert_eq!(text, "Check [UNKNOWN:/foo] out");
-        assert!(attachments.is_empty());
+    fn whatsapp_web_media_type_maps_voice_to_audio() {
+        assert!(matches!(
+            media_type(AttachmentKind::Voice),
+            wa_rs_core::download::MediaType::Audio
+        ));
+        assert!(matches!(
+            media_type(AttachmentKind::Image),
+            wa_rs_core::download::MediaType::Image
+        ));
     }
 }
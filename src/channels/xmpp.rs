@@ -0,0 +1,1905 @@
+use crate::channels::traits::{AttachmentData, Channel, ChannelMessage, SendMessage};
+use crate::config::schema::{StreamMode, XmppConfig, XmppTlsMode};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+// Use tokio_rustls's re-export of rustls types
+use tokio_rustls::rustls;
+
+/// How often an XEP-0199 ping is sent to the server while idle.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// If nothing arrives for this long (pings included) the connection is dead.
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Upper bound for a single buffered stanza; larger input is treated as abuse.
+const MAX_STANZA_BYTES: usize = 1024 * 1024;
+
+/// Nesting limit for the stanza parser.
+const MAX_XML_DEPTH: usize = 32;
+
+const NS_TLS: &str = "urn:ietf:params:xml:ns:xmpp-tls";
+const NS_SASL: &str = "urn:ietf:params:xml:ns:xmpp-sasl";
+const NS_BIND: &str = "urn:ietf:params:xml:ns:xmpp-bind";
+const NS_SESSION: &str = "urn:ietf:params:xml:ns:xmpp-session";
+const NS_PING: &str = "urn:xmpp:ping";
+const NS_DELAY: &str = "urn:xmpp:delay";
+const NS_MUC: &str = "http://jabber.org/protocol/muc";
+const NS_MUC_USER: &str = "http://jabber.org/protocol/muc#user";
+const NS_CHAT_STATES: &str = "http://jabber.org/protocol/chatstates";
+const NS_CORRECT: &str = "urn:xmpp:message-correct:0";
+const NS_RETRACT: &str = "urn:xmpp:message-retract:1";
+const NS_FALLBACK: &str = "urn:xmpp:fallback:0";
+const NS_OOB: &str = "jabber:x:oob";
+
+/// Monotonic counter to ensure unique message IDs under burst traffic.
+static MSG_SEQ: AtomicU64 = AtomicU64::new(0);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Byte stream an XML stream can run over (plain TCP, TLS, or a test pipe).
+trait Transport: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Transport for T {}
+
+type BoxedTransport = Box<dyn Transport>;
+type WriteHalf = tokio::io::WriteHalf<BoxedTransport>;
+
+/// XMPP client channel.
+///
+/// Connects with STARTTLS or direct TLS, authenticates with SASL, answers
+/// 1:1 chats and joins MUC rooms. Partial responses are streamed with
+/// XEP-0308 message corrections and typing uses XEP-0085 chat states.
+pub struct XmppChannel {
+    jid: String,
+    password: String,
+    server: Option<String>,
+    port: u16,
+    tls_mode: XmppTlsMode,
+    verify_tls: bool,
+    resource: String,
+    rooms: Vec<String>,
+    nickname: String,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+    stream_mode: StreamMode,
+    draft_update_interval_ms: u64,
+    /// Shared write half of the active stream for sending stanzas.
+    writer: Arc<tokio::sync::Mutex<Option<WriteHalf>>>,
+    /// MUC occupant JID (`room@service/nick`) → real bare JID, for rooms that disclose it.
+    occupants: Mutex<HashMap<String, String>>,
+    last_draft_edit: Mutex<HashMap<String, Instant>>,
+}
+
+impl XmppChannel {
+    pub fn from_config(config: &XmppConfig) -> Self {
+        let jid = bare_jid(config.jid.trim()).to_string();
+        let nickname = config
+            .nickname
+            .as_deref()
+            .map(str::trim)
+            .filter(|nick| !nick.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| jid.split('@').next().unwrap_or("zeroclaw").to_string());
+        let port = config.port.unwrap_or(match config.tls_mode {
+            XmppTlsMode::Starttls => 5222,
+            XmppTlsMode::DirectTls => 5223,
+        });
+
+        Self {
+            jid,
+            password: config.password.clone(),
+            server: config
+                .server
+                .as_deref()
+                .map(str::trim)
+                .filter(|server| !server.is_empty())
+                .map(str::to_string),
+            port,
+            tls_mode: config.tls_mode,
+            verify_tls: config.verify_tls,
+            resource: config
+                .resource
+                .clone()
+                .filter(|resource| !resource.trim().is_empty())
+                .unwrap_or_else(|| "zeroclaw".to_string()),
+            rooms: config
+                .rooms
+                .iter()
+                .map(|room| bare_jid(room.trim()).to_string())
+                .filter(|room| !room.is_empty())
+                .collect(),
+            nickname,
+            allowed_users: config.allowed_users.clone(),
+            mention_only: config.effective_group_reply_mode().requires_mention(),
+            group_reply_allowed_sender_ids: config.group_reply_allowed_sender_ids(),
+            stream_mode: config.stream_mode,
+            draft_update_interval_ms: config.draft_update_interval_ms,
+            writer: Arc::new(tokio::sync::Mutex::new(None)),
+            occupants: Mutex::new(HashMap::new()),
+            last_draft_edit: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn domain(&self) -> anyhow::Result<&str> {
+        match self.jid.split_once('@') {
+            Some((local, domain)) if !local.is_empty() && !domain.is_empty() => Ok(domain),
+            _ => anyhow::bail!("XMPP jid must look like user@domain, got '{}'", self.jid),
+        }
+    }
+
+    fn local_part(&self) -> &str {
+        self.jid.split('@').next().unwrap_or_default()
+    }
+
+    /// Check a JID against the allowlist (case-insensitive). `"*"` allows everyone.
+    fn is_user_allowed(&self, jid: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(jid))
+    }
+
+    fn is_group_sender_trigger_enabled(&self, jid: &str) -> bool {
+        self.group_reply_allowed_sender_ids
+            .iter()
+            .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(jid))
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        self.rooms.iter().any(|room| room.eq_ignore_ascii_case(jid))
+    }
+
+    /// Message type for a reply target: rooms get `groupchat`, everything else `chat`.
+    fn message_type_for(&self, recipient: &str) -> &'static str {
+        if self.is_room(recipient) {
+            "groupchat"
+        } else {
+            "chat"
+        }
+    }
+
+    /// Open TCP and secure it per `tls_mode`. The returned stream is not yet opened.
+    async fn connect(&self) -> anyhow::Result<XmlStream<BoxedTransport>> {
+        let domain = self.domain()?;
+        let host = self.server.as_deref().unwrap_or(domain);
+        let addr = format!("{host}:{}", self.port);
+        let tcp = tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(&addr))
+            .await
+            .map_err(|_| anyhow::anyhow!("XMPP connect to {addr} timed out"))??;
+
+        let tcp = match self.tls_mode {
+            XmppTlsMode::DirectTls => tcp,
+            XmppTlsMode::Starttls => {
+                let mut plain = XmlStream::new(tcp);
+                negotiate_starttls(&mut plain, domain).await?;
+                plain.into_inner()?
+            }
+        };
+
+        let tls = self.tls_connect(tcp, domain).await?;
+        Ok(XmlStream::new(Box::new(tls)))
+    }
+
+    async fn tls_connect(
+        &self,
+        tcp: tokio::net::TcpStream,
+        domain: &str,
+    ) -> anyhow::Result<tokio_rustls::client::TlsStream<tokio::net::TcpStream>> {
+        let tls_config = if self.verify_tls {
+            let root_store: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            rustls::ClientConfig::builder()
+                .with_root_certificates(root_store)
+                .with_no_client_auth()
+        } else {
+            rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(super::irc::NoVerify))
+                .with_no_client_auth()
+        };
+
+        // The certificate is issued for the XMPP domain, not the connect host.
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+        let server_name = rustls::pki_types::ServerName::try_from(domain.to_string())?;
+        Ok(connector.connect(server_name, tcp).await?)
+    }
+
+    /// Authenticate with SASL and bind a resource. Returns the bound full JID.
+    async fn login<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        stream: &mut XmlStream<S>,
+    ) -> anyhow::Result<String> {
+        let domain = self.domain()?;
+
+        let features = stream.open(domain).await?;
+        self.authenticate(stream, &features).await?;
+
+        // SASL success requires a stream restart.
+        let features = stream.open(domain).await?;
+        if features.child_ns("bind", NS_BIND).is_none() {
+            anyhow::bail!("XMPP server did not offer resource binding");
+        }
+        stream
+            .write(&format!(
+                "<iq type='set' id='bind_1'><bind xmlns='{NS_BIND}'><resource>{}</resource></bind></iq>",
+                escape(&self.resource)
+            ))
+            .await?;
+        let reply = stream.expect_iq_result("bind_1").await?;
+        let full_jid = reply
+            .child("bind")
+            .and_then(|bind| bind.child("jid"))
+            .map(Element::text)
+            .unwrap_or_else(|| format!("{}/{}", self.jid, self.resource));
+
+        // Legacy session establishment, only when the server insists on it.
+        if features
+            .child_ns("session", NS_SESSION)
+            .is_some_and(|session| session.child("optional").is_none())
+        {
+            stream
+                .write(&format!(
+                    "<iq type='set' id='sess_1'><session xmlns='{NS_SESSION}'/></iq>"
+                ))
+                .await?;
+            stream.expect_iq_result("sess_1").await?;
+        }
+
+        Ok(full_jid)
+    }
+
+    async fn authenticate<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        stream: &mut XmlStream<S>,
+        features: &Element,
+    ) -> anyhow::Result<()> {
+        let offered: Vec<String> = features
+            .child_ns("mechanisms", NS_SASL)
+            .map(|mechanisms| {
+                mechanisms
+                    .elements()
+                    .filter(|m| m.local_name() == "mechanism")
+                    .map(|m| m.text().trim().to_ascii_uppercase())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if offered.iter().any(|m| m == "SCRAM-SHA-256") {
+            let scram = ScramSha256::new(self.local_part(), &self.password);
+            stream
+                .write(&format!(
+                    "<auth xmlns='{NS_SASL}' mechanism='SCRAM-SHA-256'>{}</auth>",
+                    STANDARD.encode(scram.client_first())
+                ))
+                .await?;
+
+            let challenge = stream.next_stanza().await?;
+            let server_first = sasl_payload(&challenge, "challenge")?;
+            let client_final = scram.client_final(&server_first)?;
+            stream
+                .write(&format!(
+                    "<response xmlns='{NS_SASL}'>{}</response>",
+                    STANDARD.encode(client_final.message)
+                ))
+                .await?;
+
+            let success = stream.next_stanza().await?;
+            let server_final = sasl_payload(&success, "success")?;
+            return verify_scram_server_final(&server_final, &client_final.server_signature);
+        }
+
+        if offered.iter().any(|m| m == "PLAIN") {
+            let credentials = format!("\0{}\0{}", self.local_part(), self.password);
+            stream
+                .write(&format!(
+                    "<auth xmlns='{NS_SASL}' mechanism='PLAIN'>{}</auth>",
+                    STANDARD.encode(credentials)
+                ))
+                .await?;
+            let reply = stream.next_stanza().await?;
+            sasl_payload(&reply, "success")?;
+            return Ok(());
+        }
+
+        anyhow::bail!(
+            "XMPP server offers no supported SASL mechanism (offered: {})",
+            offered.join(", ")
+        )
+    }
+
+    fn join_stanza(&self, room: &str) -> String {
+        format!(
+            "<presence to='{}'><x xmlns='{NS_MUC}'><history maxstanzas='0'/></x></presence>",
+            escape(&format!("{room}/{}", self.nickname))
+        )
+    }
+
+    /// Write a raw stanza on the active connection.
+    async fn write_stanza(&self, stanza: &str) -> anyhow::Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XMPP not connected"))?;
+        writer.write_all(stanza.as_bytes()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Map an inbound `<message/>` to a channel message, applying the
+    /// allowlist and MUC mention gating.
+    fn parse_message(&self, stanza: &Element) -> Option<ChannelMessage> {
+        let kind = stanza.attr("type").unwrap_or("normal");
+        if kind == "error" {
+            return None;
+        }
+        let from = stanza.attr("from")?;
+        let body = stanza.child("body").map(Element::text)?;
+        if body.trim().is_empty() {
+            return None;
+        }
+
+        let (sender, reply_target, content) = if kind == "groupchat" {
+            let room = bare_jid(from);
+            let nick = jid_resource(from)?;
+            if !self.is_room(room) || nick == self.nickname {
+                return None;
+            }
+            // Rooms replay recent history on join; only answer live messages.
+            if stanza.child_ns("delay", NS_DELAY).is_some() {
+                return None;
+            }
+
+            let real_jid = self.occupants.lock().get(from).cloned();
+            let identities: Vec<&str> = std::iter::once(from).chain(real_jid.as_deref()).collect();
+            if !identities.iter().any(|id| self.is_user_allowed(id)) {
+                tracing::debug!("XMPP: ignoring MUC message from unauthorized occupant {from}");
+                return None;
+            }
+
+            let bypass_mention = identities
+                .iter()
+                .any(|id| self.is_group_sender_trigger_enabled(id));
+            let content =
+                normalize_muc_content(&body, &self.nickname, self.mention_only && !bypass_mention)?;
+            let sender = real_jid.unwrap_or_else(|| from.to_string());
+            (sender, room.to_string(), content)
+        } else {
+            let sender = bare_jid(from);
+            if sender.eq_ignore_ascii_case(&self.jid) {
+                return None;
+            }
+            if !self.is_user_allowed(sender) {
+                tracing::warn!("XMPP: ignoring message from unauthorized user: {sender}");
+                return None;
+            }
+            (
+                sender.to_string(),
+                sender.to_string(),
+                body.trim().to_string(),
+            )
+        };
+
+        let id = match stanza.attr("id") {
+            Some(id) if !id.is_empty() => format!("xmpp_{id}"),
+            _ => format!(
+                "xmpp_{}_{}",
+                chrono::Utc::now().timestamp_millis(),
+                MSG_SEQ.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+
+        Some(ChannelMessage {
+            id,
+            sender,
+            reply_target,
+            content,
+            channel: "xmpp".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            attachments: Vec::new(),
+        })
+    }
+
+    /// Remember real JIDs disclosed in MUC presence so the allowlist can match them.
+    fn track_presence(&self, stanza: &Element) {
+        let Some(from) = stanza.attr("from") else {
+            return;
+        };
+        if !self.is_room(bare_jid(from)) {
+            return;
+        }
+
+        match stanza.attr("type") {
+            Some("error") => {
+                let condition = stanza
+                    .child("error")
+                    .and_then(|error| error.elements().next())
+                    .map_or("unknown", Element::local_name);
+                tracing::warn!("XMPP: joining {} failed: {condition}", bare_jid(from));
+            }
+            Some("unavailable") => {
+                self.occupants.lock().remove(from);
+            }
+            _ => {
+                let real_jid = stanza
+                    .child_ns("x", NS_MUC_USER)
+                    .and_then(|x| x.child("item"))
+                    .and_then(|item| item.attr("jid"))
+                    .map(|jid| bare_jid(jid).to_string());
+                if let Some(real_jid) = real_jid {
+                    self.occupants.lock().insert(from.to_string(), real_jid);
+                }
+            }
+        }
+    }
+
+    async fn run_session(
+        &self,
+        mut reader: XmlStream<tokio::io::ReadHalf<BoxedTransport>>,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let domain = self.domain()?.to_string();
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await;
+        let mut last_activity = Instant::now();
+
+        loop {
+            let event = tokio::select! {
+                _ = keepalive.tick() => {
+                    if last_activity.elapsed() >= READ_TIMEOUT {
+                        anyhow::bail!("XMPP read timed out (no data for {READ_TIMEOUT:?})");
+                    }
+                    let seq = MSG_SEQ.fetch_add(1, Ordering::Relaxed);
+                    self.write_stanza(&format!(
+                        "<iq type='get' id='ping_{seq}' to='{}'><ping xmlns='{NS_PING}'/></iq>",
+                        escape(&domain)
+                    ))
+                    .await?;
+                    continue;
+                }
+                // `read_event` only consumes its buffer once a whole stanza is
+                // parsed, so dropping it for a keepalive tick loses nothing.
+                event = reader.read_event() => event?,
+            };
+            last_activity = Instant::now();
+
+            let stanza = match event {
+                StreamEvent::Stanza(stanza) => stanza,
+                StreamEvent::Open(_) => continue,
+                StreamEvent::Close => anyhow::bail!("XMPP stream closed by server"),
+            };
+
+            match stanza.local_name() {
+                "message" => {
+                    if let Some(msg) = self.parse_message(&stanza) {
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                "presence" => self.track_presence(&stanza),
+                "iq" => {
+                    if let Some(reply) = iq_reply(&stanza) {
+                        self.write_stanza(&reply).await?;
+                    }
+                }
+                "error" => anyhow::bail!("XMPP stream error: {}", stream_error_condition(&stanza)),
+                _ => {}
+            }
+        }
+    }
+
+    async fn send_correction(
+        &self,
+        recipient: &str,
+        original_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let extra = format!(
+            "<replace id='{}' xmlns='{NS_CORRECT}'/>",
+            escape(original_id)
+        );
+        self.write_stanza(&message_stanza(
+            recipient,
+            self.message_type_for(recipient),
+            &new_message_id(),
+            text,
+            &extra,
+        ))
+        .await
+    }
+}
+
+#[async_trait]
+impl Channel for XmppChannel {
+    fn name(&self) -> &str {
+        "xmpp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (mut body, attachments) = message.split_attachments();
+        let mut extra = String::new();
+        for attachment in &attachments {
+            if let AttachmentData::Url(url) = &attachment.data {
+                if !body.is_empty() {
+                    body.push('\n');
+                }
+                body.push_str(url);
+                let _ = write!(extra, "<x xmlns='{NS_OOB}'><url>{}</url></x>", escape(url));
+            } else {
+                tracing::warn!(
+                    "XMPP: skipping local attachment '{}' (HTTP upload is not supported)",
+                    attachment.file_name_or_default()
+                );
+            }
+        }
+        if body.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.write_stanza(&message_stanza(
+            &message.recipient,
+            self.message_type_for(&message.recipient),
+            &new_message_id(),
+            &body,
+            &extra,
+        ))
+        .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!(
+            "XMPP channel connecting as {} ({:?})...",
+            self.jid,
+            self.tls_mode
+        );
+
+        let mut stream = self.connect().await?;
+        let full_jid = self.login(&mut stream).await?;
+        tracing::info!("XMPP bound as {full_jid}");
+
+        stream.write("<presence/>").await?;
+        for room in &self.rooms {
+            stream.write(&self.join_stanza(room)).await?;
+            tracing::info!("XMPP joining {room} as {}", self.nickname);
+        }
+
+        let (reader, writer) = stream.split();
+        *self.writer.lock().await = Some(writer);
+
+        let result = self.run_session(reader, &tx).await;
+        *self.writer.lock().await = None;
+        self.occupants.lock().clear();
+        result
+    }
+
+    async fn health_check(&self) -> bool {
+        // Full connectivity check: TLS + SASL + bind, then close the stream.
+        let result = async {
+            let mut stream = self.connect().await?;
+            self.login(&mut stream).await?;
+            stream.write("</stream:stream>").await
+        }
+        .await;
+
+        if let Err(e) = &result {
+            tracing::debug!("XMPP health check failed: {e}");
+        }
+        result.is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        let stanza = chat_state_stanza(recipient, self.message_type_for(recipient), "composing");
+        // Typing is best-effort; a dropped connection is reported by `listen`.
+        let _ = self.write_stanza(&stanza).await;
+        Ok(())
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        let stanza = chat_state_stanza(recipient, self.message_type_for(recipient), "active");
+        let _ = self.write_stanza(&stanza).await;
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let id = new_message_id();
+        self.write_stanza(&message_stanza(
+            &message.recipient,
+            self.message_type_for(&message.recipient),
+            &id,
+            &message.content,
+            "",
+        ))
+        .await?;
+        self.last_draft_edit
+            .lock()
+            .insert(message.recipient.clone(), Instant::now());
+        Ok(Some(id))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<Option<String>> {
+        // Rate-limit corrections per conversation
+        {
+            let last_edits = self.last_draft_edit.lock();
+            if let Some(last_time) = last_edits.get(recipient) {
+                let elapsed = u64::try_from(last_time.elapsed().as_millis()).unwrap_or(u64::MAX);
+                if elapsed < self.draft_update_interval_ms {
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.send_correction(recipient, message_id, text).await?;
+        self.last_draft_edit
+            .lock()
+            .insert(recipient.to_string(), Instant::now());
+        Ok(None)
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        self.send_correction(recipient, message_id, text).await
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        let extra = format!(
+            "<retract id='{}' xmlns='{NS_RETRACT}'/><fallback xmlns='{NS_FALLBACK}' for='{NS_RETRACT}'/>",
+            escape(message_id)
+        );
+        self.write_stanza(&message_stanza(
+            recipient,
+            self.message_type_for(recipient),
+            &new_message_id(),
+            "This message was retracted.",
+            &extra,
+        ))
+        .await
+    }
+}
+
+// ── JID helpers ──────────────────────────────────────────────
+
+/// `user@domain/resource` → `user@domain`.
+fn bare_jid(jid: &str) -> &str {
+    jid.split_once('/').map_or(jid, |(bare, _)| bare)
+}
+
+/// `room@service/nick` → `nick`.
+fn jid_resource(jid: &str) -> Option<&str> {
+    jid.split_once('/')
+        .map(|(_, resource)| resource)
+        .filter(|resource| !resource.is_empty())
+}
+
+fn new_message_id() -> String {
+    format!("zc-{}", Uuid::new_v4().simple())
+}
+
+// ── Stanza builders ──────────────────────────────────────────
+
+fn message_stanza(to: &str, kind: &str, id: &str, body: &str, extra: &str) -> String {
+    format!(
+        "<message to='{}' type='{kind}' id='{}'><body>{}</body>{extra}</message>",
+        escape(to),
+        escape(id),
+        escape(body)
+    )
+}
+
+fn chat_state_stanza(to: &str, kind: &str, state: &str) -> String {
+    format!(
+        "<message to='{}' type='{kind}'><{state} xmlns='{NS_CHAT_STATES}'/></message>",
+        escape(to)
+    )
+}
+
+/// Answer server `get`/`set` IQs: pong for XEP-0199 pings, `service-unavailable` otherwise.
+fn iq_reply(stanza: &Element) -> Option<String> {
+    let kind = stanza.attr("type")?;
+    if kind != "get" && kind != "set" {
+        return None;
+    }
+    let id = escape(stanza.attr("id").unwrap_or_default());
+    let to = stanza
+        .attr("from")
+        .map(|from| format!(" to='{}'", escape(from)))
+        .unwrap_or_default();
+
+    if kind == "get" && stanza.child_ns("ping", NS_PING).is_some() {
+        Some(format!("<iq type='result' id='{id}'{to}/>"))
+    } else {
+        Some(format!(
+            "<iq type='error' id='{id}'{to}><error type='cancel'><service-unavailable xmlns='urn:ietf:params:xml:ns:xmpp-stanzas'/></error></iq>"
+        ))
+    }
+}
+
+fn stream_error_condition(stanza: &Element) -> String {
+    stanza
+        .elements()
+        .find(|child| child.local_name() != "text")
+        .map_or_else(
+            || "unknown".to_string(),
+            |child| child.local_name().to_string(),
+        )
+}
+
+/// Strip a leading `nick:` / `nick,` / `@nick` address and apply mention gating.
+///
+/// Returns `None` when the message should be ignored.
+fn normalize_muc_content(body: &str, nickname: &str, require_mention: bool) -> Option<String> {
+    let body = body.trim();
+    if body.is_empty() {
+        return None;
+    }
+
+    let addressed = strip_prefix_ignore_ascii_case(body, &format!("@{nickname}"))
+        .or_else(|| strip_prefix_ignore_ascii_case(body, nickname))
+        .filter(|rest| {
+            rest.is_empty() || rest.starts_with([':', ',', ' ', '\t', '\n', '!', '?', '.'])
+        });
+    if let Some(rest) = addressed {
+        let rest = rest.trim_start_matches([':', ',']).trim();
+        return if rest.is_empty() {
+            None
+        } else {
+            Some(rest.to_string())
+        };
+    }
+
+    if require_mention && !mentions_nickname(body, nickname) {
+        return None;
+    }
+    Some(body.to_string())
+}
+
+fn strip_prefix_ignore_ascii_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+/// Whether `nickname` appears in `text` as a whole word (case-insensitive).
+fn mentions_nickname(text: &str, nickname: &str) -> bool {
+    if nickname.is_empty() {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let text_lower = text.to_lowercase();
+    let nick_lower = nickname.to_lowercase();
+    text_lower.match_indices(&nick_lower).any(|(idx, matched)| {
+        let before = text_lower[..idx].chars().next_back();
+        let after = text_lower[idx + matched.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+// ── SASL ─────────────────────────────────────────────────────
+
+/// Decode the base64 payload of a SASL `<challenge/>` or `<success/>`,
+/// turning `<failure/>` into an error.
+fn sasl_payload(stanza: &Element, expected: &str) -> anyhow::Result<String> {
+    if stanza.local_name() == "failure" {
+        let condition = stanza
+            .elements()
+            .find(|child| child.local_name() != "text")
+            .map_or("unknown", Element::local_name);
+        anyhow::bail!("XMPP SASL authentication failed: {condition}");
+    }
+    if stanza.local_name() != expected {
+        anyhow::bail!(
+            "XMPP SASL: expected <{expected}/>, got <{}/>",
+            stanza.local_name()
+        );
+    }
+
+    let encoded = stanza.text();
+    let encoded = encoded.trim();
+    if encoded.is_empty() || encoded == "=" {
+        return Ok(String::new());
+    }
+    let decoded = STANDARD.decode(encoded)?;
+    Ok(String::from_utf8(decoded)?)
+}
+
+/// Client side of SCRAM-SHA-256 (RFC 5802 / RFC 7677) without channel binding.
+struct ScramSha256 {
+    password: String,
+    client_nonce: String,
+    client_first_bare: String,
+}
+
+struct ScramClientFinal {
+    message: String,
+    server_signature: Vec<u8>,
+}
+
+impl ScramSha256 {
+    fn new(username: &str, password: &str) -> Self {
+        Self::with_nonce(username, password, &Uuid::new_v4().simple().to_string())
+    }
+
+    fn with_nonce(username: &str, password: &str, client_nonce: &str) -> Self {
+        let username = username.replace('=', "=3D").replace(',', "=2C");
+        Self {
+            password: password.to_string(),
+            client_nonce: client_nonce.to_string(),
+            client_first_bare: format!("n={username},r={client_nonce}"),
+        }
+    }
+
+    fn client_first(&self) -> String {
+        format!("n,,{}", self.client_first_bare)
+    }
+
+    fn client_final(&self, server_first: &str) -> anyhow::Result<ScramClientFinal> {
+        let attr = |key: &str| {
+            server_first
+                .split(',')
+                .find_map(|part| part.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+        };
+        let nonce = attr("r").ok_or_else(|| anyhow::anyhow!("SCRAM: missing server nonce"))?;
+        if !nonce.starts_with(&self.client_nonce) {
+            anyhow::bail!("SCRAM: server nonce does not extend the client nonce");
+        }
+        let salt =
+            STANDARD.decode(attr("s").ok_or_else(|| anyhow::anyhow!("SCRAM: missing salt"))?)?;
+        let iterations: u32 = attr("i")
+            .ok_or_else(|| anyhow::anyhow!("SCRAM: missing iteration count"))?
+            .parse()?;
+        if iterations == 0 {
+            anyhow::bail!("SCRAM: invalid iteration count");
+        }
+
+        let salted_password = hi(self.password.as_bytes(), &salt, iterations)?;
+        let client_key = hmac_sha256(&salted_password, b"Client Key")?;
+        let stored_key = Sha256::digest(&client_key);
+        let without_proof = format!("c=biws,r={nonce}");
+        let auth_message = format!("{},{server_first},{without_proof}", self.client_first_bare);
+
+        let client_signature = hmac_sha256(&stored_key, auth_message.as_bytes())?;
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(key, sig)| key ^ sig)
+            .collect();
+        let server_key = hmac_sha256(&salted_password, b"Server Key")?;
+        let server_signature = hmac_sha256(&server_key, auth_message.as_bytes())?;
+
+        Ok(ScramClientFinal {
+            message: format!("{without_proof},p={}", STANDARD.encode(proof)),
+            server_signature,
+        })
+    }
+}
+
+fn verify_scram_server_final(server_final: &str, expected_signature: &[u8]) -> anyhow::Result<()> {
+    let Some(verifier) = server_final.strip_prefix("v=") else {
+        anyhow::bail!("SCRAM: server did not send a verifier");
+    };
+    if STANDARD.decode(verifier)? != expected_signature {
+        anyhow::bail!("SCRAM: server signature mismatch");
+    }
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key)
+        .map_err(|e| anyhow::anyhow!("HMAC key error: {e}"))?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// PBKDF2-HMAC-SHA-256 for a single output block (`Hi()` in RFC 5802).
+fn hi(password: &[u8], salt: &[u8], iterations: u32) -> anyhow::Result<Vec<u8>> {
+    let mut block = salt.to_vec();
+    block.extend_from_slice(&1_u32.to_be_bytes());
+    let mut u = hmac_sha256(password, &block)?;
+    let mut result = u.clone();
+    for _ in 1..iterations {
+        u = hmac_sha256(password, &u)?;
+        for (acc, byte) in result.iter_mut().zip(u.iter()) {
+            *acc ^= byte;
+        }
+    }
+    Ok(result)
+}
+
+// ── XML stream ───────────────────────────────────────────────
+
+/// Upgrade a freshly connected stream with STARTTLS. Refuses servers that
+/// do not offer it rather than continuing in plain text.
+async fn negotiate_starttls<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut XmlStream<S>,
+    domain: &str,
+) -> anyhow::Result<()> {
+    let features = stream.open(domain).await?;
+    if features.child_ns("starttls", NS_TLS).is_none() {
+        anyhow::bail!(
+            "XMPP server does not offer STARTTLS; refusing to continue without TLS (use tls_mode = \"direct_tls\" if it expects TLS on connect)"
+        );
+    }
+    stream
+        .write(&format!("<starttls xmlns='{NS_TLS}'/>"))
+        .await?;
+    let reply = stream.next_stanza().await?;
+    if reply.local_name() != "proceed" {
+        anyhow::bail!("XMPP STARTTLS rejected by server");
+    }
+    Ok(())
+}
+
+/// A minimal XML element, enough for XMPP stanzas.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    /// Name without namespace prefix (`stream:features` → `features`).
+    fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn child(&self, local_name: &str) -> Option<&Element> {
+        self.elements()
+            .find(|child| child.local_name() == local_name)
+    }
+
+    fn child_ns(&self, local_name: &str, ns: &str) -> Option<&Element> {
+        self.elements()
+            .find(|child| child.local_name() == local_name && child.attr("xmlns") == Some(ns))
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StreamEvent {
+    /// `<stream:stream ...>` header.
+    Open(Element),
+    /// A complete top-level stanza.
+    Stanza(Element),
+    /// `</stream:stream>`.
+    Close,
+}
+
+/// Buffered reader/writer that frames an XMPP XML stream into stanzas.
+struct XmlStream<S> {
+    io: S,
+    buf: Vec<u8>,
+}
+
+impl<S> XmlStream<S> {
+    fn new(io: S) -> Self {
+        Self {
+            io,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Give back the raw transport, e.g. for a TLS upgrade. Bytes buffered
+    /// past the last stanza would be unauthenticated plain text, so they are
+    /// rejected instead of silently carried over.
+    fn into_inner(self) -> anyhow::Result<S> {
+        if self.buf.iter().any(|b| !b.is_ascii_whitespace()) {
+            anyhow::bail!("XMPP server sent data before the TLS handshake");
+        }
+        Ok(self.io)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> XmlStream<S> {
+    async fn write(&mut self, data: &str) -> anyhow::Result<()> {
+        self.io.write_all(data.as_bytes()).await?;
+        self.io.flush().await?;
+        Ok(())
+    }
+
+    /// Send a stream header and return the server's `<stream:features/>`.
+    async fn open(&mut self, domain: &str) -> anyhow::Result<Element> {
+        self.write(&format!(
+            "<?xml version='1.0'?><stream:stream xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' to='{}' version='1.0' xml:lang='en'>",
+            escape(domain)
+        ))
+        .await?;
+
+        match self.read_event().await? {
+            StreamEvent::Open(_) => {}
+            StreamEvent::Stanza(stanza) if stanza.local_name() == "error" => {
+                anyhow::bail!("XMPP stream error: {}", stream_error_condition(&stanza))
+            }
+            other => anyhow::bail!("XMPP: expected stream header, got {other:?}"),
+        }
+
+        let features = self.next_stanza().await?;
+        if features.local_name() != "features" {
+            anyhow::bail!("XMPP: expected stream features, got <{}/>", features.name);
+        }
+        Ok(features)
+    }
+
+    /// Wait for the result of the IQ with `id`, skipping unrelated stanzas.
+    async fn expect_iq_result(&mut self, id: &str) -> anyhow::Result<Element> {
+        loop {
+            let stanza = self.next_stanza().await?;
+            if stanza.local_name() != "iq" || stanza.attr("id") != Some(id) {
+                continue;
+            }
+            if stanza.attr("type") == Some("result") {
+                return Ok(stanza);
+            }
+            let condition = stanza
+                .child("error")
+                .and_then(|error| error.elements().next())
+                .map_or("unknown", Element::local_name);
+            anyhow::bail!("XMPP iq '{id}' failed: {condition}");
+        }
+    }
+
+    fn split(self) -> (XmlStream<tokio::io::ReadHalf<S>>, tokio::io::WriteHalf<S>) {
+        let (reader, writer) = tokio::io::split(self.io);
+        (
+            XmlStream {
+                io: reader,
+                buf: self.buf,
+            },
+            writer,
+        )
+    }
+}
+
+impl<S: AsyncRead + Unpin> XmlStream<S> {
+    async fn next_stanza(&mut self) -> anyhow::Result<Element> {
+        match self.read_event().await? {
+            StreamEvent::Stanza(stanza) if stanza.name == "stream:error" => {
+                anyhow::bail!("XMPP stream error: {}", stream_error_condition(&stanza))
+            }
+            StreamEvent::Stanza(stanza) => Ok(stanza),
+            StreamEvent::Close => anyhow::bail!("XMPP stream closed by server"),
+            StreamEvent::Open(_) => anyhow::bail!("XMPP: unexpected stream restart"),
+        }
+    }
+
+    async fn read_event(&mut self) -> anyhow::Result<StreamEvent> {
+        loop {
+            let parsed = {
+                let text = match std::str::from_utf8(&self.buf) {
+                    Ok(text) => text,
+                    // A multi-byte character may be split across reads.
+                    Err(e) if e.error_len().is_none() => {
+                        std::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap_or_default()
+                    }
+                    Err(_) => anyhow::bail!("XMPP stream is not valid UTF-8"),
+                };
+                parse_event(text)?
+            };
+            if let Some((event, consumed)) = parsed {
+                self.buf.drain(..consumed);
+                return Ok(event);
+            }
+
+            if self.buf.len() > MAX_STANZA_BYTES {
+                anyhow::bail!("XMPP stanza exceeds {MAX_STANZA_BYTES} bytes");
+            }
+            let mut chunk = [0_u8; 8192];
+            let n = self.io.read(&mut chunk).await?;
+            if n == 0 {
+                anyhow::bail!("XMPP connection closed by server");
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+}
+
+/// Parse the next stream-level event from `input`.
+///
+/// Returns `Ok(None)` when more input is needed, otherwise the event and
+/// the number of bytes it consumed.
+fn parse_event(input: &str) -> anyhow::Result<Option<(StreamEvent, usize)>> {
+    let mut pos = 0;
+    loop {
+        let rest = &input[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+        let rest = trimmed;
+
+        if rest.is_empty() {
+            return Ok(None);
+        }
+        if rest.starts_with("<?") {
+            let Some(end) = rest.find("?>") else {
+                return Ok(None);
+            };
+            pos += end + 2;
+            continue;
+        }
+        if rest.starts_with("<!") {
+            if let Some(end) = rest.strip_prefix("<!--").and_then(|c| c.find("-->")) {
+                pos += 4 + end + 3;
+                continue;
+            }
+            if rest.starts_with("<!--") || "<!--".starts_with(rest) {
+                return Ok(None);
+            }
+            anyhow::bail!("XMPP: unsupported XML markup at stream level");
+        }
+        if rest.starts_with("</") {
+            let Some(end) = rest.find('>') else {
+                return Ok(None);
+            };
+            return Ok(Some((StreamEvent::Close, pos + end + 1)));
+        }
+        if !rest.starts_with('<') {
+            anyhow::bail!("XMPP: unexpected character data at stream level");
+        }
+
+        let Some((element, self_closing, after)) = parse_start_tag(input, pos)? else {
+            return Ok(None);
+        };
+        if element.name == "stream:stream" && !self_closing {
+            return Ok(Some((StreamEvent::Open(element), after)));
+        }
+        if self_closing {
+            return Ok(Some((StreamEvent::Stanza(element), after)));
+        }
+        return Ok(parse_content(input, after, element, 1)?
+            .map(|(element, end)| (StreamEvent::Stanza(element), end)));
+    }
+}
+
+/// Parse a start tag beginning at `pos` (which points at `<`).
+fn parse_start_tag(input: &str, pos: usize) -> anyhow::Result<Option<(Element, bool, usize)>> {
+    let bytes = input.as_bytes();
+    let len = bytes.len();
+    let is_delim = |b: u8| b.is_ascii_whitespace() || b == b'>' || b == b'/' || b == b'=';
+
+    let mut i = pos + 1;
+    let name_start = i;
+    while i < len && !is_delim(bytes[i]) {
+        i += 1;
+    }
+    if i >= len {
+        return Ok(None);
+    }
+    if i == name_start {
+        anyhow::bail!("XMPP: malformed start tag");
+    }
+    let mut element = Element {
+        name: input[name_start..i].to_string(),
+        ..Element::default()
+    };
+
+    loop {
+        while i < len && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= len {
+            return Ok(None);
+        }
+        match bytes[i] {
+            b'>' => return Ok(Some((element, false, i + 1))),
+            b'/' => {
+                if i + 1 >= len {
+                    return Ok(None);
+                }
+                if bytes[i + 1] != b'>' {
+                    anyhow::bail!("XMPP: malformed empty-element tag <{}>", element.name);
+                }
+                return Ok(Some((element, true, i + 2)));
+            }
+            _ => {
+                let attr_start = i;
+                while i < len && !is_delim(bytes[i]) {
+                    i += 1;
+                }
+                let attr_name = &input[attr_start..i];
+                while i < len && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                if i >= len {
+                    return Ok(None);
+                }
+                if bytes[i] != b'=' || attr_name.is_empty() {
+                    anyhow::bail!("XMPP: malformed attribute in <{}>", element.name);
+                }
+                i += 1;
+                while i < len && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                if i >= len {
+                    return Ok(None);
+                }
+                let quote = bytes[i];
+                if quote != b'\'' && quote != b'"' {
+                    anyhow::bail!("XMPP: unquoted attribute in <{}>", element.name);
+                }
+                let value_start = i + 1;
+                let Some(value_len) = input[value_start..].find(char::from(quote)) else {
+                    return Ok(None);
+                };
+                element.attrs.push((
+                    attr_name.to_string(),
+                    unescape(&input[value_start..value_start + value_len]),
+                ));
+                i = value_start + value_len + 1;
+            }
+        }
+    }
+}
+
+/// Parse children and text of `element` up to its end tag.
+fn parse_content(
+    input: &str,
+    mut pos: usize,
+    mut element: Element,
+    depth: usize,
+) -> anyhow::Result<Option<(Element, usize)>> {
+    if depth > MAX_XML_DEPTH {
+        anyhow::bail!("XMPP: stanza nesting exceeds {MAX_XML_DEPTH} levels");
+    }
+
+    loop {
+        let rest = &input[pos..];
+        let Some(lt) = rest.find('<') else {
+            return Ok(None);
+        };
+        if lt > 0 {
+            element.children.push(Node::Text(unescape(&rest[..lt])));
+        }
+        pos += lt;
+        let rest = &input[pos..];
+
+        if let Some(close) = rest.strip_prefix("</") {
+            let Some(gt) = close.find('>') else {
+                return Ok(None);
+            };
+            let name = close[..gt].trim();
+            if name != element.name {
+                anyhow::bail!("XMPP: mismatched end tag </{name}> for <{}>", element.name);
+            }
+            return Ok(Some((element, pos + 2 + gt + 1)));
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let Some(end) = cdata.find("]]>") else {
+                    return Ok(None);
+                };
+                element.children.push(Node::Text(cdata[..end].to_string()));
+                pos += 9 + end + 3;
+                continue;
+            }
+            let terminator = if rest.starts_with("<?") { "?>" } else { "-->" };
+            if rest.starts_with("<!--") || rest.starts_with("<?") {
+                let Some(end) = rest.find(terminator) else {
+                    return Ok(None);
+                };
+                pos += end + terminator.len();
+                continue;
+            }
+            if "<![CDATA[".starts_with(rest) || "<!--".starts_with(rest) {
+                return Ok(None);
+            }
+            anyhow::bail!("XMPP: unsupported XML markup in <{}>", element.name);
+        }
+
+        let Some((child, self_closing, after)) = parse_start_tag(input, pos)? else {
+            return Ok(None);
+        };
+        if self_closing {
+            element.children.push(Node::Element(child));
+            pos = after;
+            continue;
+        }
+        let Some((child, end)) = parse_content(input, after, child, depth + 1)? else {
+            return Ok(None);
+        };
+        element.children.push(Node::Element(child));
+        pos = end;
+    }
+}
+
+/// Escape text for XML character data and single- or double-quoted attributes.
+/// Control characters XML 1.0 cannot carry are dropped.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if u32::from(c) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(raw: &str) -> String {
+    if !raw.contains('&') {
+        return raw.to_string();
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let tail = &rest[amp..];
+        let decoded = tail.find(';').and_then(|semi| {
+            let entity = &tail[1..semi];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => {
+                    if let Some(hex) = entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                    {
+                        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                    } else {
+                        entity
+                            .strip_prefix('#')
+                            .and_then(|dec| dec.parse::<u32>().ok())
+                            .and_then(char::from_u32)
+                    }
+                }
+            };
+            c.map(|c| (c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &tail[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{GroupReplyConfig, GroupReplyMode};
+    use tokio::io::DuplexStream;
+
+    fn test_config() -> XmppConfig {
+        XmppConfig {
+            jid: "bot@example.org".into(),
+            password: "secret".into(),
+            server: None,
+            port: None,
+            tls_mode: XmppTlsMode::Starttls,
+            verify_tls: true,
+            resource: None,
+            rooms: vec!["ops@conference.example.org".into()],
+            nickname: None,
+            allowed_users: vec!["alice@example.org".into()],
+            group_reply: None,
+            stream_mode: StreamMode::Off,
+            draft_update_interval_ms: 1000,
+        }
+    }
+
+    fn stanza(xml: &str) -> Element {
+        match parse_event(xml).unwrap() {
+            Some((StreamEvent::Stanza(element), _)) => element,
+            other => panic!("expected stanza, got {other:?}"),
+        }
+    }
+
+    /// Read from the scripted server side until `needle` shows up.
+    async fn read_until(server: &mut DuplexStream, needle: &str) -> String {
+        let mut seen = String::new();
+        let mut chunk = [0_u8; 4096];
+        while !seen.contains(needle) {
+            let n = server.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "client closed before sending {needle}; got {seen}");
+            seen.push_str(std::str::from_utf8(&chunk[..n]).unwrap());
+        }
+        seen
+    }
+
+    const SERVER_HEADER: &str = "<?xml version='1.0'?><stream:stream xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' id='s1' from='example.org' version='1.0'>";
+
+    // ── Config ───────────────────────────────────────────────
+
+    #[test]
+    fn from_config_derives_defaults() {
+        let ch = XmppChannel::from_config(&test_config());
+        assert_eq!(ch.name(), "xmpp");
+        assert_eq!(ch.domain().unwrap(), "example.org");
+        assert_eq!(ch.nickname, "bot");
+        assert_eq!(ch.resource, "zeroclaw");
+        assert_eq!(ch.port, 5222);
+        assert!(!ch.mention_only);
+
+        let mut direct = test_config();
+        direct.tls_mode = XmppTlsMode::DirectTls;
+        direct.jid = "bot@example.org/laptop".into();
+        let ch = XmppChannel::from_config(&direct);
+        assert_eq!(ch.port, 5223);
+        assert_eq!(ch.jid, "bot@example.org");
+    }
+
+    #[test]
+    fn domain_rejects_malformed_jid() {
+        let mut config = test_config();
+        config.jid = "example.org".into();
+        assert!(XmppChannel::from_config(&config).domain().is_err());
+    }
+
+    // ── XML framing ──────────────────────────────────────────
+
+    #[test]
+    fn parse_event_reads_stanza_with_attributes_and_entities() {
+        let xml = "<message from=\"a@b/c\" type='chat'><body>1 &lt; 2 &amp;&#x263A;</body><active xmlns='http://jabber.org/protocol/chatstates'/></message> <next/>";
+        let (event, consumed) = parse_event(xml).unwrap().unwrap();
+        let StreamEvent::Stanza(message) = event else {
+            panic!("expected stanza");
+        };
+        assert_eq!(message.attr("from"), Some("a@b/c"));
+        assert_eq!(message.child("body").unwrap().text(), "1 < 2 &☺");
+        assert!(message.child_ns("active", NS_CHAT_STATES).is_some());
+        assert_eq!(xml[consumed..].trim(), "<next/>");
+    }
+
+    #[test]
+    fn parse_event_waits_for_complete_stanza() {
+        assert!(parse_event("").unwrap().is_none());
+        assert!(parse_event("<message><body>hi</bo").unwrap().is_none());
+        assert!(parse_event("<message to='a").unwrap().is_none());
+        assert!(parse_event("  <!-").unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_event_recognizes_stream_header_and_close() {
+        let (event, consumed) = parse_event(SERVER_HEADER).unwrap().unwrap();
+        assert!(matches!(event, StreamEvent::Open(ref header) if header.attr("id") == Some("s1")));
+        assert_eq!(consumed, SERVER_HEADER.len());
+
+        let (event, _) = parse_event("</stream:stream>").unwrap().unwrap();
+        assert_eq!(event, StreamEvent::Close);
+    }
+
+    #[test]
+    fn parse_event_rejects_mismatched_and_deep_nesting() {
+        assert!(parse_event("<a><b></a>").is_err());
+
+        let deep = format!("{}{}", "<x>".repeat(64), "</x>".repeat(64));
+        assert!(parse_event(&deep).is_err());
+    }
+
+    #[test]
+    fn escape_round_trips_and_drops_control_chars() {
+        let raw = "<a href=\"x\">'&'</a>\u{1}";
+        let escaped = escape(raw);
+        assert!(!escaped.contains('\u{1}'));
+        assert_eq!(unescape(&escaped), "<a href=\"x\">'&'</a>");
+        assert_eq!(unescape("&bogus; &"), "&bogus; &");
+    }
+
+    // ── SASL ─────────────────────────────────────────────────
+
+    #[test]
+    fn scram_sha256_matches_rfc7677_vector() {
+        let scram = ScramSha256::with_nonce("user", "pencil", "rOprNGfwEbeRWgbNEkqO");
+        assert_eq!(scram.client_first(), "n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+        let server_first = "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+        let client_final = scram.client_final(server_first).unwrap();
+        assert_eq!(
+            client_final.message,
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+        );
+        verify_scram_server_final(
+            "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=",
+            &client_final.server_signature,
+        )
+        .unwrap();
+        assert!(verify_scram_server_final("v=AAAA", &client_final.server_signature).is_err());
+    }
+
+    #[test]
+    fn scram_rejects_foreign_server_nonce() {
+        let scram = ScramSha256::with_nonce("user", "pencil", "abc");
+        assert!(scram
+            .client_final("r=xyz,s=QSXCR+Q6sek8bf92,i=4096")
+            .is_err());
+    }
+
+    // ── Scripted server ──────────────────────────────────────
+
+    #[tokio::test]
+    async fn login_authenticates_with_plain_and_binds_resource() {
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let ch = XmppChannel::from_config(&test_config());
+
+        let script = tokio::spawn(async move {
+            read_until(&mut server, "<stream:stream").await;
+            server
+                .write_all(format!("{SERVER_HEADER}<stream:features><mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms></stream:features>").as_bytes())
+                .await
+                .unwrap();
+
+            let auth = read_until(&mut server, "</auth>").await;
+            assert!(auth.contains("mechanism='PLAIN'"));
+            assert!(auth.contains(&STANDARD.encode("\0bot\0secret")));
+            server
+                .write_all(format!("<success xmlns='{NS_SASL}'/>").as_bytes())
+                .await
+                .unwrap();
+
+            read_until(&mut server, "<stream:stream").await;
+            server
+                .write_all(format!("{SERVER_HEADER}<stream:features><bind xmlns='{NS_BIND}'/></stream:features>").as_bytes())
+                .await
+                .unwrap();
+
+            let bind = read_until(&mut server, "</iq>").await;
+            assert!(bind.contains("<resource>zeroclaw</resource>"));
+            server
+                .write_all(format!("<iq type='result' id='bind_1'><bind xmlns='{NS_BIND}'><jid>bot@example.org/zeroclaw</jid></bind></iq>").as_bytes())
+                .await
+                .unwrap();
+            server
+        });
+
+        let mut stream = XmlStream::new(client);
+        let full_jid = ch.login(&mut stream).await.unwrap();
+        assert_eq!(full_jid, "bot@example.org/zeroclaw");
+        script.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn login_reports_sasl_failure_condition() {
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let ch = XmppChannel::from_config(&test_config());
+
+        let script = tokio::spawn(async move {
+            read_until(&mut server, "<stream:stream").await;
+            server
+                .write_all(format!("{SERVER_HEADER}<stream:features><mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms></stream:features>").as_bytes())
+                .await
+                .unwrap();
+            read_until(&mut server, "</auth>").await;
+            server
+                .write_all(
+                    format!("<failure xmlns='{NS_SASL}'><not-authorized/></failure>").as_bytes(),
+                )
+                .await
+                .unwrap();
+            server
+        });
+
+        let mut stream = XmlStream::new(client);
+        let err = ch.login(&mut stream).await.unwrap_err();
+        assert!(err.to_string().contains("not-authorized"), "{err}");
+        script.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn starttls_negotiation_requires_offer_and_proceed() {
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let script = tokio::spawn(async move {
+            read_until(&mut server, "<stream:stream").await;
+            server
+                .write_all(format!("{SERVER_HEADER}<stream:features><starttls xmlns='{NS_TLS}'><required/></starttls></stream:features>").as_bytes())
+                .await
+                .unwrap();
+            read_until(&mut server, "<starttls").await;
+            server
+                .write_all(format!("<proceed xmlns='{NS_TLS}'/>").as_bytes())
+                .await
+                .unwrap();
+            server
+        });
+        let mut stream = XmlStream::new(client);
+        negotiate_starttls(&mut stream, "example.org")
+            .await
+            .unwrap();
+        assert!(stream.into_inner().is_ok());
+        script.await.unwrap();
+
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let script = tokio::spawn(async move {
+            read_until(&mut server, "<stream:stream").await;
+            server
+                .write_all(format!("{SERVER_HEADER}<stream:features><mechanisms xmlns='{NS_SASL}'><mechanism>PLAIN</mechanism></mechanisms></stream:features>").as_bytes())
+                .await
+                .unwrap();
+            server
+        });
+        let mut stream = XmlStream::new(client);
+        let err = negotiate_starttls(&mut stream, "example.org")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not offer STARTTLS"));
+        script.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_writes_chat_and_groupchat_stanzas() {
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let ch = XmppChannel::from_config(&test_config());
+        let (_, writer) = XmlStream::new(Box::new(client) as BoxedTransport).split();
+        *ch.writer.lock().await = Some(writer);
+
+        ch.send(&SendMessage::new("a < b", "alice@example.org"))
+            .await
+            .unwrap();
+        let direct = read_until(&mut server, "</message>").await;
+        assert!(direct.contains("to='alice@example.org' type='chat'"));
+        assert!(direct.contains("<body>a &lt; b</body>"));
+
+        ch.send(&SendMessage::new(
+            "hello room",
+            "ops@conference.example.org",
+        ))
+        .await
+        .unwrap();
+        let room = read_until(&mut server, "</message>").await;
+        assert!(room.contains("type='groupchat'"));
+    }
+
+    #[tokio::test]
+    async fn draft_updates_use_message_corrections() {
+        let (client, mut server) = tokio::io::duplex(16 * 1024);
+        let mut config = test_config();
+        config.stream_mode = StreamMode::Partial;
+        config.draft_update_interval_ms = 0;
+        let ch = XmppChannel::from_config(&config);
+        assert!(ch.supports_draft_updates());
+        let (_, writer) = XmlStream::new(Box::new(client) as BoxedTransport).split();
+        *ch.writer.lock().await = Some(writer);
+
+        let draft_id = ch
+            .send_draft(&SendMessage::new("thinking", "alice@example.org"))
+            .await
+            .unwrap()
+            .unwrap();
+        read_until(&mut server, "</message>").await;
+
+        ch.finalize_draft("alice@example.org", &draft_id, "done")
+            .await
+            .unwrap();
+        let correction = read_until(&mut server, "</message>").await;
+        let correction = stanza(&correction);
+        assert_eq!(correction.child("body").unwrap().text(), "done");
+        let replace = correction.child_ns("replace", NS_CORRECT).unwrap();
+        assert_eq!(replace.attr("id"), Some(draft_id.as_str()));
+        assert_ne!(correction.attr("id"), Some(draft_id.as_str()));
+    }
+
+    #[tokio::test]
+    async fn send_without_connection_errors() {
+        let ch = XmppChannel::from_config(&test_config());
+        let err = ch
+            .send(&SendMessage::new("hi", "alice@example.org"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not connected"));
+        // Typing indicators are best-effort.
+        assert!(ch.start_typing("alice@example.org").await.is_ok());
+    }
+
+    // ── Inbound messages ─────────────────────────────────────
+
+    #[test]
+    fn parse_message_accepts_allowlisted_direct_chat() {
+        let ch = XmppChannel::from_config(&test_config());
+        let msg = ch
+            .parse_message(&stanza(
+                "<message from='Alice@example.org/phone' type='chat' id='m1'><body> hi there </body></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.id, "xmpp_m1");
+        assert_eq!(msg.sender, "Alice@example.org");
+        assert_eq!(msg.reply_target, "Alice@example.org");
+        assert_eq!(msg.content, "hi there");
+        assert_eq!(msg.channel, "xmpp");
+    }
+
+    #[test]
+    fn parse_message_rejects_unlisted_and_bodyless_messages() {
+        let ch = XmppChannel::from_config(&test_config());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='mallory@example.org/x' type='chat'><body>hi</body></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='alice@example.org/x' type='chat'><composing xmlns='http://jabber.org/protocol/chatstates'/></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='alice@example.org/x' type='error'><body>bounced</body></message>"
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn parse_message_uses_real_jid_from_muc_presence() {
+        let ch = XmppChannel::from_config(&test_config());
+        let groupchat = stanza(
+            "<message from='ops@conference.example.org/al' type='groupchat'><body>status?</body></message>",
+        );
+        assert!(ch.parse_message(&groupchat).is_none());
+
+        ch.track_presence(&stanza(
+            "<presence from='ops@conference.example.org/al'><x xmlns='http://jabber.org/protocol/muc#user'><item jid='alice@example.org/laptop' role='participant'/></x></presence>",
+        ));
+        let msg = ch.parse_message(&groupchat).unwrap();
+        assert_eq!(msg.sender, "alice@example.org");
+        assert_eq!(msg.reply_target, "ops@conference.example.org");
+
+        ch.track_presence(&stanza(
+            "<presence from='ops@conference.example.org/al' type='unavailable'/>",
+        ));
+        assert!(ch.parse_message(&groupchat).is_none());
+    }
+
+    #[test]
+    fn parse_message_skips_own_echo_history_and_unknown_rooms() {
+        let mut config = test_config();
+        config.allowed_users = vec!["*".into()];
+        let ch = XmppChannel::from_config(&config);
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='ops@conference.example.org/bot' type='groupchat'><body>echo</body></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='ops@conference.example.org/al' type='groupchat'><body>old</body><delay xmlns='urn:xmpp:delay' stamp='2024-01-01T00:00:00Z'/></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_message(&stanza(
+                "<message from='other@conference.example.org/al' type='groupchat'><body>hi</body></message>"
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn muc_mention_gating_follows_group_reply_config() {
+        let mut config = test_config();
+        config.allowed_users = vec!["*".into()];
+        config.group_reply = Some(GroupReplyConfig {
+            mode: Some(GroupReplyMode::MentionOnly),
+            allowed_sender_ids: vec!["ops@conference.example.org/boss".into()],
+        });
+        let ch = XmppChannel::from_config(&config);
+        assert!(ch.mention_only);
+
+        let chatter = stanza(
+            "<message from='ops@conference.example.org/al' type='groupchat'><body>lunch?</body></message>",
+        );
+        assert!(ch.parse_message(&chatter).is_none());
+
+        let addressed = stanza(
+            "<message from='ops@conference.example.org/al' type='groupchat'><body>Bot: deploy status</body></message>",
+        );
+        assert_eq!(
+            ch.parse_message(&addressed).unwrap().content,
+            "deploy status"
+        );
+
+        let boss = stanza(
+            "<message from='ops@conference.example.org/boss' type='groupchat'><body>lunch?</body></message>",
+        );
+        assert_eq!(ch.parse_message(&boss).unwrap().content, "lunch?");
+    }
+
+    #[test]
+    fn normalize_muc_content_handles_address_forms() {
+        assert_eq!(
+            normalize_muc_content("@bot ping", "bot", true).as_deref(),
+            Some("ping")
+        );
+        assert_eq!(
+            normalize_muc_content("hey bot, ping", "bot", true).as_deref(),
+            Some("hey bot, ping")
+        );
+        assert!(normalize_muc_content("robots ping", "bot", true).is_none());
+        assert!(normalize_muc_content("bot:", "bot", false).is_none());
+        assert_eq!(
+            normalize_muc_content("bother", "bot", false).as_deref(),
+            Some("bother")
+        );
+    }
+
+    #[test]
+    fn iq_reply_answers_ping_and_rejects_other_requests() {
+        let pong = iq_reply(&stanza(
+            "<iq type='get' id='p1' from='example.org'><ping xmlns='urn:xmpp:ping'/></iq>",
+        ))
+        .unwrap();
+        assert_eq!(pong, "<iq type='result' id='p1' to='example.org'/>");
+
+        let unsupported = iq_reply(&stanza(
+            "<iq type='get' id='v1' from='a@b/c'><query xmlns='jabber:iq:version'/></iq>",
+        ))
+        .unwrap();
+        assert!(unsupported.contains("service-unavailable"));
+
+        assert!(iq_reply(&stanza("<iq type='result' id='x'/>")).is_none());
+    }
+}
@@ -0,0 +1,1160 @@
+use super::attachments::load_outgoing;
+use super::traits::{Attachment, AttachmentData, Channel, ChannelMessage, SendMessage};
+use crate::config::schema::{StreamMode, ZulipConfig};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use reqwest::multipart::{Form, Part};
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Topic used for stream messages that carry no thread (e.g. cron deliveries).
+const DEFAULT_TOPIC: &str = "zeroclaw";
+/// Zulip holds a long poll open for about a minute before sending a heartbeat.
+const EVENTS_POLL_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+/// Typing notifications expire after ~15s; refresh well before that.
+const TYPING_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Zulip channel — receives through the event queue long-poll API.
+///
+/// Stream messages are answered in the same topic: `reply_target` is
+/// `stream:<stream_id>` and `thread_ts` carries the topic name. Direct
+/// messages use `dm:<user_id>[,<user_id>...]` (the other participants).
+pub struct ZulipChannel {
+    site: String,
+    email: String,
+    api_key: String,
+    streams: Vec<String>,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    group_reply_allowed_sender_ids: Vec<String>,
+    stream_mode: StreamMode,
+    draft_update_interval_ms: u64,
+    workspace_dir: Option<PathBuf>,
+    last_draft_edit: Mutex<HashMap<String, Instant>>,
+    typing_handles: Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
+}
+
+/// The bot's own account, used to skip its messages and strip mentions.
+#[derive(Debug, Clone, Default)]
+struct BotIdentity {
+    user_id: i64,
+    full_name: String,
+}
+
+/// A registered event queue and the last event consumed from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EventQueue {
+    id: String,
+    last_event_id: i64,
+}
+
+/// Parsed form of a `stream:` / `dm:` recipient.
+#[derive(Debug, PartialEq, Eq)]
+enum Destination<'a> {
+    /// Stream name or ID.
+    Stream(&'a str),
+    /// User IDs or emails.
+    Direct(Vec<&'a str>),
+}
+
+impl ZulipChannel {
+    pub fn from_config(config: &ZulipConfig) -> Self {
+        Self {
+            site: config.site.trim().trim_end_matches('/').to_string(),
+            email: config.email.trim().to_string(),
+            api_key: config.api_key.clone(),
+            streams: config
+                .streams
+                .iter()
+                .map(|stream| stream.trim().trim_start_matches('#').to_string())
+                .filter(|stream| !stream.is_empty())
+                .collect(),
+            allowed_users: config.allowed_users.clone(),
+            mention_only: config.effective_group_reply_mode().requires_mention(),
+            group_reply_allowed_sender_ids: config.group_reply_allowed_sender_ids(),
+            stream_mode: config.stream_mode,
+            draft_update_interval_ms: config.draft_update_interval_ms,
+            workspace_dir: None,
+            last_draft_edit: Mutex::new(HashMap::new()),
+            typing_handles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Workspace used to resolve local attachment paths for upload.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.zulip")
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.http_client()
+            .request(method, format!("{}/api/v1/{path}", self.site))
+            .basic_auth(&self.email, Some(&self.api_key))
+    }
+
+    /// Send a request and return the JSON body, failing on HTTP or API errors.
+    async fn call(&self, request: reqwest::RequestBuilder, action: &str) -> anyhow::Result<Value> {
+        let resp = request.send().await?;
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+        let parsed: Option<Value> = serde_json::from_str(&body).ok();
+        let api_ok = parsed
+            .as_ref()
+            .and_then(|v| v.get("result"))
+            .and_then(Value::as_str)
+            .is_none_or(|result| result == "success");
+        if !status.is_success() || !api_ok {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Zulip {action} failed ({status}): {sanitized}");
+        }
+        parsed.ok_or_else(|| anyhow::anyhow!("Zulip {action} returned invalid JSON"))
+    }
+
+    /// Check a sender against `allowed_users` by email or numeric user ID.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, email: &str, user_id: i64) -> bool {
+        matches_user(&self.allowed_users, email, user_id)
+    }
+
+    fn is_group_sender_trigger_enabled(&self, email: &str, user_id: i64) -> bool {
+        matches_user(&self.group_reply_allowed_sender_ids, email, user_id)
+    }
+
+    fn is_stream_enabled(&self, stream_id: i64, stream_name: &str) -> bool {
+        self.streams.is_empty()
+            || self.streams.iter().any(|stream| {
+                stream.eq_ignore_ascii_case(stream_name) || *stream == stream_id.to_string()
+            })
+    }
+
+    async fn fetch_identity(&self) -> anyhow::Result<BotIdentity> {
+        let me = self
+            .call(self.request(Method::GET, "users/me"), "users/me")
+            .await?;
+        Ok(BotIdentity {
+            user_id: me
+                .get("user_id")
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            full_name: me
+                .get("full_name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    async fn register_queue(&self) -> anyhow::Result<EventQueue> {
+        let registered = self
+            .call(
+                self.request(Method::POST, "register").form(&[
+                    ("event_types", r#"["message"]"#),
+                    ("apply_markdown", "false"),
+                ]),
+                "register",
+            )
+            .await?;
+        let id = registered
+            .get("queue_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Zulip register returned no queue_id"))?
+            .to_string();
+        let last_event_id = registered
+            .get("last_event_id")
+            .and_then(Value::as_i64)
+            .unwrap_or(-1);
+        Ok(EventQueue { id, last_event_id })
+    }
+
+    /// Long-poll the queue. `Ok(None)` means the server garbage-collected
+    /// the queue and it must be registered again.
+    async fn get_events(&self, queue: &EventQueue) -> anyhow::Result<Option<Vec<Value>>> {
+        let resp = self
+            .request(Method::GET, "events")
+            .query(&[
+                ("queue_id", queue.id.clone()),
+                ("last_event_id", queue.last_event_id.to_string()),
+            ])
+            .timeout(EVENTS_POLL_TIMEOUT)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body: Value = resp.json().await?;
+
+        if body.get("code").and_then(Value::as_str) == Some("BAD_EVENT_QUEUE_ID") {
+            return Ok(None);
+        }
+        if !status.is_success() || body.get("result").and_then(Value::as_str) != Some("success") {
+            let msg = body.get("msg").and_then(Value::as_str).unwrap_or_default();
+            let sanitized = crate::providers::sanitize_api_error(msg);
+            anyhow::bail!("Zulip events failed ({status}): {sanitized}");
+        }
+
+        Ok(Some(
+            body.get("events")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default(),
+        ))
+    }
+
+    fn parse_message_event(&self, event: &Value, bot: &BotIdentity) -> Option<ChannelMessage> {
+        let message = event.get("message")?;
+        let id = message.get("id").and_then(Value::as_i64)?;
+        let sender_id = message
+            .get("sender_id")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+        let sender_email = message
+            .get("sender_email")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let text = message
+            .get("content")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        if sender_id == bot.user_id || text.trim().is_empty() {
+            return None;
+        }
+
+        if !self.is_user_allowed(sender_email, sender_id) {
+            tracing::warn!("Zulip: ignoring message from unauthorized user: {sender_email}");
+            return None;
+        }
+
+        let (reply_target, thread_ts, is_group_message) =
+            match message.get("type").and_then(Value::as_str) {
+                Some("stream") => {
+                    let stream_id = message.get("stream_id").and_then(Value::as_i64)?;
+                    let stream_name = message
+                        .get("display_recipient")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    if !self.is_stream_enabled(stream_id, stream_name) {
+                        return None;
+                    }
+                    let topic = message
+                        .get("subject")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    (format!("stream:{stream_id}"), Some(topic.to_string()), true)
+                }
+                Some("private") => {
+                    let others: Vec<String> = message
+                        .get("display_recipient")
+                        .and_then(Value::as_array)
+                        .map(|users| {
+                            users
+                                .iter()
+                                .filter_map(|user| user.get("id").and_then(Value::as_i64))
+                                .filter(|user_id| *user_id != bot.user_id)
+                                .map(|user_id| user_id.to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    if others.is_empty() {
+                        return None;
+                    }
+                    let is_group = others.len() > 1;
+                    (format!("dm:{}", others.join(",")), None, is_group)
+                }
+                _ => return None,
+            };
+
+        let require_mention = is_group_message
+            && self.mention_only
+            && !self.is_group_sender_trigger_enabled(sender_email, sender_id);
+        if require_mention && !has_flag(event, "mentioned") {
+            return None;
+        }
+
+        let content = strip_bot_mention(text, bot);
+        if content.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: format!("zulip_{id}"),
+            sender: sender_email.to_string(),
+            reply_target,
+            content,
+            channel: "zulip".to_string(),
+            timestamp: message
+                .get("timestamp")
+                .and_then(Value::as_u64)
+                .unwrap_or_default(),
+            thread_ts,
+            attachments: Vec::new(),
+        })
+    }
+
+    /// Post a message and return its Zulip message ID.
+    async fn post_message(
+        &self,
+        recipient: &str,
+        topic: Option<&str>,
+        content: &str,
+    ) -> anyhow::Result<i64> {
+        let form: Vec<(&str, String)> = match parse_recipient(recipient)? {
+            Destination::Stream(stream) => vec![
+                ("type", "stream".to_string()),
+                ("to", stream.to_string()),
+                ("topic", topic.unwrap_or(DEFAULT_TOPIC).to_string()),
+                ("content", content.to_string()),
+            ],
+            Destination::Direct(users) => vec![
+                ("type", "private".to_string()),
+                ("to", direct_recipients_json(&users)),
+                ("content", content.to_string()),
+            ],
+        };
+
+        let sent = self
+            .call(
+                self.request(Method::POST, "messages").form(&form),
+                "send message",
+            )
+            .await?;
+        sent.get("id")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| anyhow::anyhow!("Zulip send message returned no id"))
+    }
+
+    async fn edit_message(&self, message_id: &str, content: &str) -> anyhow::Result<()> {
+        let id = raw_message_id(message_id);
+        self.call(
+            self.request(Method::PATCH, &format!("messages/{id}"))
+                .form(&[("content", content)]),
+            "edit message",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Upload a file and return the server-relative link to it.
+    async fn upload_file(&self, attachment: &Attachment) -> anyhow::Result<String> {
+        let bytes =
+            load_outgoing(attachment, self.workspace_dir.as_deref(), "channel.zulip").await?;
+        let form = Form::new().part(
+            "file",
+            Part::bytes(bytes).file_name(attachment.file_name_or_default()),
+        );
+        let uploaded = self
+            .call(
+                self.request(Method::POST, "user_uploads").multipart(form),
+                "upload",
+            )
+            .await?;
+        uploaded
+            .get("url")
+            .or_else(|| uploaded.get("uri"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Zulip upload returned no url"))
+    }
+
+    async fn set_typing(&self, users: &[&str], op: &str) -> anyhow::Result<()> {
+        self.call(
+            self.request(Method::POST, "typing").form(&[
+                ("type", "private".to_string()),
+                ("op", op.to_string()),
+                ("to", direct_recipients_json(users)),
+            ]),
+            "typing",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for ZulipChannel {
+    fn name(&self) -> &str {
+        "zulip"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (mut content, attachments) = message.split_attachments();
+        for attachment in &attachments {
+            let url = match &attachment.data {
+                AttachmentData::Url(url) => url.clone(),
+                _ => self.upload_file(attachment).await?,
+            };
+            let label = attachment
+                .caption
+                .clone()
+                .unwrap_or_else(|| attachment.file_name_or_default());
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            let _ = write!(content, "[{label}]({url})");
+        }
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.post_message(&message.recipient, message.thread_ts.as_deref(), &content)
+            .await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let bot = self.fetch_identity().await?;
+        tracing::info!("Zulip channel listening as {} on {}", self.email, self.site);
+
+        let mut retry_delay = Duration::from_secs(1);
+        'register: loop {
+            let mut queue = match self.register_queue().await {
+                Ok(queue) => queue,
+                Err(e) => {
+                    tracing::warn!("Zulip register error: {e}; retrying in {retry_delay:?}");
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    continue;
+                }
+            };
+
+            loop {
+                match self.get_events(&queue).await {
+                    Ok(Some(events)) => {
+                        retry_delay = Duration::from_secs(1);
+                        for event in &events {
+                            if let Some(event_id) = event.get("id").and_then(Value::as_i64) {
+                                queue.last_event_id = queue.last_event_id.max(event_id);
+                            }
+                            if event.get("type").and_then(Value::as_str) != Some("message") {
+                                continue;
+                            }
+                            if let Some(msg) = self.parse_message_event(event, &bot) {
+                                if tx.send(msg).await.is_err() {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        tracing::info!("Zulip event queue {} expired; re-registering", queue.id);
+                        continue 'register;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Zulip poll error: {e}; retrying in {retry_delay:?}");
+                        tokio::time::sleep(retry_delay).await;
+                        retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.fetch_identity().await.is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        // Stream typing needs the topic, which the recipient does not carry;
+        // only direct messages show an indicator.
+        let to = match parse_recipient(recipient) {
+            Ok(Destination::Direct(users)) => direct_recipients_json(&users),
+            _ => return Ok(()),
+        };
+        self.stop_typing(recipient).await?;
+
+        let client = self.http_client();
+        let url = format!("{}/api/v1/typing", self.site);
+        let (email, api_key) = (self.email.clone(), self.api_key.clone());
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let result = client
+                    .post(&url)
+                    .basic_auth(&email, Some(&api_key))
+                    .form(&[("type", "private"), ("op", "start"), ("to", to.as_str())])
+                    .send()
+                    .await;
+                if let Ok(r) = result {
+                    if !r.status().is_success() {
+                        tracing::debug!(status = %r.status(), "Zulip typing indicator failed");
+                    }
+                }
+                tokio::time::sleep(TYPING_REFRESH_INTERVAL).await;
+            }
+        });
+
+        self.typing_handles
+            .lock()
+            .insert(recipient.to_string(), handle);
+        Ok(())
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        let handle = self.typing_handles.lock().remove(recipient);
+        if let Some(handle) = handle {
+            handle.abort();
+            if let Ok(Destination::Direct(users)) = parse_recipient(recipient) {
+                // Best-effort; the indicator also expires on its own.
+                let _ = self.set_typing(&users, "stop").await;
+            }
+        }
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let id = self
+            .post_message(
+                &message.recipient,
+                message.thread_ts.as_deref(),
+                &message.content,
+            )
+            .await?;
+        self.last_draft_edit
+            .lock()
+            .insert(message.recipient.clone(), Instant::now());
+        Ok(Some(id.to_string()))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<Option<String>> {
+        // Rate-limit edits per conversation
+        {
+            let last_edits = self.last_draft_edit.lock();
+            if let Some(last_time) = last_edits.get(recipient) {
+                let elapsed = u64::try_from(last_time.elapsed().as_millis()).unwrap_or(u64::MAX);
+                if elapsed < self.draft_update_interval_ms {
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.edit_message(message_id, text).await?;
+        self.last_draft_edit
+            .lock()
+            .insert(recipient.to_string(), Instant::now());
+        Ok(None)
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        self.edit_message(message_id, text).await
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.last_draft_edit.lock().remove(recipient);
+        let id = raw_message_id(message_id);
+        self.call(
+            self.request(Method::DELETE, &format!("messages/{id}")),
+            "delete message",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn add_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> anyhow::Result<()> {
+        let Some(name) = zulip_emoji_name(emoji) else {
+            tracing::debug!("Zulip: no emoji name for reaction {emoji:?}; skipping");
+            return Ok(());
+        };
+        let id = raw_message_id(message_id);
+        self.call(
+            self.request(Method::POST, &format!("messages/{id}/reactions"))
+                .form(&[("emoji_name", name)]),
+            "add reaction",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> anyhow::Result<()> {
+        let Some(name) = zulip_emoji_name(emoji) else {
+            return Ok(());
+        };
+        let id = raw_message_id(message_id);
+        self.call(
+            self.request(Method::DELETE, &format!("messages/{id}/reactions"))
+                .query(&[("emoji_name", name)]),
+            "remove reaction",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+fn matches_user(entries: &[String], email: &str, user_id: i64) -> bool {
+    let user_id = user_id.to_string();
+    entries.iter().any(|entry| {
+        let entry = entry.trim();
+        entry == "*" || entry == user_id || (!email.is_empty() && entry.eq_ignore_ascii_case(email))
+    })
+}
+
+fn has_flag(event: &Value, flag: &str) -> bool {
+    event
+        .get("flags")
+        .and_then(Value::as_array)
+        .is_some_and(|flags| flags.iter().any(|f| f.as_str() == Some(flag)))
+}
+
+/// Remove `@**Bot Name**` / `@**Bot Name|123**` mentions (and their silent
+/// `@_**...**` forms) of the bot from raw message Markdown.
+fn strip_bot_mention(text: &str, bot: &BotIdentity) -> String {
+    if bot.full_name.is_empty() {
+        return text.trim().to_string();
+    }
+    let mut stripped = text.to_string();
+    for prefix in ["@**", "@_**"] {
+        for mention in [
+            format!("{prefix}{}|{}**", bot.full_name, bot.user_id),
+            format!("{prefix}{}**", bot.full_name),
+        ] {
+            stripped = stripped.replace(&mention, "");
+        }
+    }
+    stripped.trim().to_string()
+}
+
+fn parse_recipient(recipient: &str) -> anyhow::Result<Destination<'_>> {
+    if let Some(stream) = recipient.strip_prefix("stream:") {
+        let stream = stream.trim();
+        if !stream.is_empty() {
+            return Ok(Destination::Stream(stream));
+        }
+    } else if let Some(users) = recipient.strip_prefix("dm:") {
+        let users: Vec<&str> = users
+            .split(',')
+            .map(str::trim)
+            .filter(|user| !user.is_empty())
+            .collect();
+        if !users.is_empty() {
+            return Ok(Destination::Direct(users));
+        }
+    }
+    anyhow::bail!(
+        "Zulip recipient must be 'stream:<stream>' or 'dm:<user>[,<user>...]', got '{recipient}'"
+    )
+}
+
+/// Zulip accepts user IDs or emails in `to`; IDs must be JSON numbers.
+fn direct_recipients_json(users: &[&str]) -> String {
+    let values: Vec<Value> = users
+        .iter()
+        .map(|user| {
+            user.parse::<i64>()
+                .map_or_else(|_| json!(user), |id| json!(id))
+        })
+        .collect();
+    Value::Array(values).to_string()
+}
+
+fn raw_message_id(message_id: &str) -> &str {
+    message_id.strip_prefix("zulip_").unwrap_or(message_id)
+}
+
+/// Zulip reactions are addressed by emoji name rather than by the Unicode
+/// character, so map the emoji the runtime uses.
+fn zulip_emoji_name(emoji: &str) -> Option<&'static str> {
+    match emoji.trim_end_matches('\u{FE0F}') {
+        "\u{1F440}" => Some("eyes"),
+        "\u{2705}" => Some("check"),
+        "\u{26A0}" => Some("warning"),
+        "\u{274C}" => Some("cross_mark"),
+        "\u{1F44D}" => Some("+1"),
+        "\u{1F44E}" => Some("-1"),
+        "\u{1F389}" => Some("tada"),
+        "\u{1F914}" => Some("thinking"),
+        "\u{2764}" => Some("heart"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::AttachmentKind;
+    use wiremock::matchers::{basic_auth, body_string_contains, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config(site: &str) -> ZulipConfig {
+        toml::from_str(&format!(
+            r#"
+site = "{site}/"
+email = "bot@example.com"
+api_key = "secret"
+allowed_users = ["*"]
+"#
+        ))
+        .unwrap()
+    }
+
+    fn bot() -> BotIdentity {
+        BotIdentity {
+            user_id: 100,
+            full_name: "Claw Bot".to_string(),
+        }
+    }
+
+    fn stream_event(content: &str, flags: &[&str]) -> Value {
+        json!({
+            "type": "message",
+            "id": 3,
+            "flags": flags,
+            "message": {
+                "id": 501,
+                "type": "stream",
+                "stream_id": 42,
+                "display_recipient": "engineering",
+                "subject": "deploys",
+                "sender_id": 7,
+                "sender_email": "alice@example.com",
+                "content": content,
+                "timestamp": 1_700_000_000
+            }
+        })
+    }
+
+    fn direct_event(content: &str, recipients: &[i64]) -> Value {
+        let users: Vec<Value> = recipients.iter().map(|id| json!({ "id": id })).collect();
+        json!({
+            "type": "message",
+            "id": 4,
+            "flags": [],
+            "message": {
+                "id": 502,
+                "type": "private",
+                "display_recipient": users,
+                "sender_id": 7,
+                "sender_email": "alice@example.com",
+                "content": content,
+                "timestamp": 1_700_000_001
+            }
+        })
+    }
+
+    async fn mock_message_id(server: &MockServer, id: i64) {
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "result": "success", "id": id })),
+            )
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn zulip_config_defaults() {
+        let cfg = config("https://chat.example.com");
+        assert!(cfg.streams.is_empty());
+        assert_eq!(cfg.stream_mode, StreamMode::Off);
+        assert_eq!(cfg.draft_update_interval_ms, 1000);
+        assert!(!cfg.effective_group_reply_mode().requires_mention());
+
+        let channel = ZulipChannel::from_config(&cfg);
+        assert_eq!(channel.site, "https://chat.example.com");
+        assert!(!channel.supports_draft_updates());
+    }
+
+    #[test]
+    fn parse_recipient_handles_streams_and_direct_messages() {
+        assert_eq!(
+            parse_recipient("stream:42").unwrap(),
+            Destination::Stream("42")
+        );
+        assert_eq!(
+            parse_recipient("dm:7, bob@example.com").unwrap(),
+            Destination::Direct(vec!["7", "bob@example.com"])
+        );
+        assert!(parse_recipient("stream:").is_err());
+        assert!(parse_recipient("42").is_err());
+        assert_eq!(
+            direct_recipients_json(&["7", "bob@example.com"]),
+            r#"[7,"bob@example.com"]"#
+        );
+    }
+
+    #[test]
+    fn stream_message_replies_in_topic() {
+        let channel = ZulipChannel::from_config(&config("https://chat.example.com"));
+        let msg = channel
+            .parse_message_event(&stream_event("hello", &[]), &bot())
+            .unwrap();
+        assert_eq!(msg.id, "zulip_501");
+        assert_eq!(msg.sender, "alice@example.com");
+        assert_eq!(msg.reply_target, "stream:42");
+        assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+        assert_eq!(msg.channel, "zulip");
+        assert_eq!(msg.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn mention_only_gates_streams_but_not_direct_messages() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.group_reply = Some(crate::config::schema::GroupReplyConfig {
+            mode: Some(crate::config::schema::GroupReplyMode::MentionOnly),
+            allowed_sender_ids: vec![],
+        });
+        let channel = ZulipChannel::from_config(&cfg);
+
+        assert!(channel
+            .parse_message_event(&stream_event("hello", &[]), &bot())
+            .is_none());
+        let msg = channel
+            .parse_message_event(
+                &stream_event("@**Claw Bot|100** status?", &["mentioned"]),
+                &bot(),
+            )
+            .unwrap();
+        assert_eq!(msg.content, "status?");
+
+        let dm = channel
+            .parse_message_event(&direct_event("hi", &[7, 100]), &bot())
+            .unwrap();
+        assert_eq!(dm.reply_target, "dm:7");
+        assert!(dm.thread_ts.is_none());
+
+        // Group DMs are gated like streams.
+        assert!(channel
+            .parse_message_event(&direct_event("hi all", &[7, 8, 100]), &bot())
+            .is_none());
+    }
+
+    #[test]
+    fn allowed_sender_ids_bypass_mention_gating() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.group_reply = Some(crate::config::schema::GroupReplyConfig {
+            mode: Some(crate::config::schema::GroupReplyMode::MentionOnly),
+            allowed_sender_ids: vec!["ALICE@example.com".into()],
+        });
+        let channel = ZulipChannel::from_config(&cfg);
+        assert!(channel
+            .parse_message_event(&stream_event("hello", &[]), &bot())
+            .is_some());
+    }
+
+    #[test]
+    fn ignores_own_unauthorized_and_unlisted_stream_messages() {
+        let mut cfg = config("https://chat.example.com");
+        cfg.allowed_users = vec!["7".into()];
+        cfg.streams = vec!["#Engineering".into()];
+        let channel = ZulipChannel::from_config(&cfg);
+        assert!(channel
+            .parse_message_event(&stream_event("hello", &[]), &bot())
+            .is_some());
+
+        let mut own = stream_event("hello", &[]);
+        own["message"]["sender_id"] = json!(100);
+        assert!(channel.parse_message_event(&own, &bot()).is_none());
+
+        let mut stranger = stream_event("hello", &[]);
+        stranger["message"]["sender_id"] = json!(8);
+        stranger["message"]["sender_email"] = json!("mallory@example.com");
+        assert!(channel.parse_message_event(&stranger, &bot()).is_none());
+
+        let mut other_stream = stream_event("hello", &[]);
+        other_stream["message"]["stream_id"] = json!(43);
+        other_stream["message"]["display_recipient"] = json!("random");
+        assert!(channel.parse_message_event(&other_stream, &bot()).is_none());
+    }
+
+    #[test]
+    fn emoji_names_cover_runtime_reactions() {
+        assert_eq!(zulip_emoji_name("\u{1F440}"), Some("eyes"));
+        assert_eq!(zulip_emoji_name("\u{2705}"), Some("check"));
+        assert_eq!(zulip_emoji_name("\u{26A0}\u{FE0F}"), Some("warning"));
+        assert_eq!(zulip_emoji_name("\u{1F984}"), None);
+    }
+
+    #[tokio::test]
+    async fn send_replies_in_stream_topic() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .and(basic_auth("bot@example.com", "secret"))
+            .and(body_string_contains("type=stream&to=42&topic=deploys"))
+            .and(body_string_contains("content=done"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "result": "success", "id": 9 })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        channel
+            .send(&SendMessage::new("done", "stream:42").in_thread(Some("deploys".into())))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_without_thread_uses_default_topic_and_direct_ids() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .and(body_string_contains("topic=zeroclaw"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "result": "success", "id": 9 })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .and(body_string_contains("type=private&to=%5B7%2C8%5D"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "result": "success", "id": 10 })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        channel
+            .send(&SendMessage::new("report", "stream:engineering"))
+            .await
+            .unwrap();
+        channel
+            .send(&SendMessage::new("hi", "dm:7,8"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_uploads_in_memory_attachments() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/user_uploads"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": "success",
+                "uri": "/user_uploads/1/ab/report.txt"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .and(body_string_contains(
+                "%5Breport.txt%5D%28%2Fuser_uploads%2F1%2Fab%2Freport.txt%29",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "result": "success", "id": 9 })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        let attachment = Attachment::from_bytes(AttachmentKind::Document, b"ok".to_vec())
+            .with_filename("report.txt");
+        channel
+            .send(&SendMessage::new("", "dm:7").with_attachments(vec![attachment]))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_surfaces_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "result": "error",
+                "code": "STREAM_DOES_NOT_EXIST",
+                "msg": "Stream 'nope' does not exist"
+            })))
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        let err = channel
+            .send(&SendMessage::new("hi", "stream:nope"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Zulip send message failed"));
+    }
+
+    #[tokio::test]
+    async fn drafts_are_sent_then_edited() {
+        let server = MockServer::start().await;
+        mock_message_id(&server, 99).await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/v1/messages/99"))
+            .and(body_string_contains("content=final+answer"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "result": "success" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut cfg = config(&server.uri());
+        cfg.stream_mode = StreamMode::Partial;
+        let channel = ZulipChannel::from_config(&cfg);
+        assert!(channel.supports_draft_updates());
+
+        let id = channel
+            .send_draft(&SendMessage::new("...", "stream:42").in_thread(Some("deploys".into())))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, "99");
+
+        // Inside the rate-limit window the edit is skipped without a request.
+        assert!(channel
+            .update_draft("stream:42", &id, "partial")
+            .await
+            .unwrap()
+            .is_none());
+        channel
+            .finalize_draft("stream:42", &id, "final answer")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reactions_use_zulip_emoji_names() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/messages/501/reactions"))
+            .and(body_string_contains("emoji_name=eyes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "result": "success" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v1/messages/501/reactions"))
+            .and(query_param("emoji_name", "eyes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "result": "success" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        channel
+            .add_reaction("stream:42", "zulip_501", "\u{1F440}")
+            .await
+            .unwrap();
+        channel
+            .remove_reaction("stream:42", "zulip_501", "\u{1F440}")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn listen_reregisters_after_queue_expiry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": "success",
+                "user_id": 100,
+                "full_name": "Claw Bot"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/register"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": "success",
+                "queue_id": "q1",
+                "last_event_id": -1
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/register"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": "success",
+                "queue_id": "q2",
+                "last_event_id": 2
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/events"))
+            .and(query_param("queue_id", "q1"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "result": "error",
+                "code": "BAD_EVENT_QUEUE_ID",
+                "msg": "Bad event queue ID: q1",
+                "queue_id": "q1"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/events"))
+            .and(query_param("queue_id", "q2"))
+            .and(query_param("last_event_id", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": "success",
+                "events": [
+                    { "type": "heartbeat", "id": 3 },
+                    stream_event("hello", &[]),
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/events"))
+            .and(query_param("queue_id", "q2"))
+            .and(query_param("last_event_id", "3"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "result": "success", "events": [] }))
+                    .set_delay(Duration::from_secs(30)),
+            )
+            .mount(&server)
+            .await;
+
+        let channel = ZulipChannel::from_config(&config(&server.uri()));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let listener = tokio::spawn(async move { channel.listen(tx).await });
+
+        let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("message within timeout")
+            .expect("listener still running");
+        listener.abort();
+
+        assert_eq!(msg.content, "hello");
+        assert_eq!(msg.reply_target, "stream:42");
+        assert_eq!(msg.thread_ts.as_deref(), Some("deploys"));
+    }
+}
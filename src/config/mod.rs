@@ -5,20 +5,23 @@ pub mod traits;
 pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
-    AgentConfig, AgentsIpcConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig,
-    BrowserConfig, BuiltinHooksConfig, ChannelsConfig, ClassificationRule, ComposioConfig, Config,
-    CoordinationConfig, CostConfig, CronConfig, DelegateAgentConfig, DiscordConfig,
-    DockerRuntimeConfig, EmbeddingRouteConfig, EstopConfig, FeishuConfig, GatewayConfig,
-    GroupReplyConfig, GroupReplyMode, HardwareConfig, HardwareTransport, HeartbeatConfig,
-    HooksConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
-    MemoryConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig,
+    AgentConfig, AgentsIpcConfig, ApprovalRiskLevel, ApprovalRuleAction, ApprovalRuleConfig,
+    AuditConfig, AutonomyConfig, AzureOpenAiAuthMode, AzureOpenAiConfig,
+    AzureOpenAiDeploymentConfig, BrowserComputerUseConfig, BrowserConfig, BuiltinHooksConfig,
+    ChannelsConfig, ClassificationRule, ComposioConfig, Config, CoordinationConfig, CostConfig,
+    CronConfig, DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, EmbeddingRouteConfig,
+    EstopConfig, FeishuConfig, GatewayConfig, GroupReplyConfig, GroupReplyMode, HardwareConfig,
+    HardwareTransport, HeartbeatConfig, HooksConfig, HttpRequestConfig, IMessageConfig,
+    IdentityConfig, LarkConfig, MatrixConfig, McpConfig, McpServeConfig, McpServerConfig,
+    McpTransportKind, MemoryConfig, MemoryConsolidationConfig, MemoryNamespaceConfig,
+    MemoryRerankConfig, MemorySharing, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig,
     NonCliNaturalLanguageApprovalMode, ObservabilityConfig, OtpConfig, OtpMethod,
     PeripheralBoardConfig, PeripheralsConfig, ProviderConfig, ProxyConfig, ProxyScope,
-    QdrantConfig, QueryClassificationConfig, ReliabilityConfig, ResearchPhaseConfig,
+    QdrantConfig, QueryClassificationConfig, ReliabilityConfig, RerankBackend, ResearchPhaseConfig,
     ResearchTrigger, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
     SchedulerConfig, SecretsConfig, SecurityConfig, SkillsConfig, SkillsPromptInjectionMode,
-    SlackConfig, StorageConfig, StorageProviderConfig, StorageProviderSection, StreamMode,
-    SyscallAnomalyConfig, TelegramConfig, TranscriptionConfig, TunnelConfig,
+    SlackConfig, SopConfig, StorageConfig, StorageProviderConfig, StorageProviderSection,
+    StreamMode, SyscallAnomalyConfig, TelegramConfig, TranscriptionConfig, TunnelConfig,
     WasmCapabilityEscalationMode, WasmModuleHashPolicy, WasmRuntimeConfig, WasmSecurityConfig,
     WebFetchConfig, WebSearchConfig, WebhookConfig,
 };
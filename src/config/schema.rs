@@ -24,6 +24,7 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "provider.openrouter",
     "channel.dingtalk",
     "channel.discord",
+    "channel.email",
     "channel.feishu",
     "channel.lark",
     "channel.matrix",
@@ -32,14 +33,18 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "channel.qq",
     "channel.signal",
     "channel.slack",
+    "channel.teams",
     "channel.telegram",
     "channel.wati",
     "channel.whatsapp",
+    "channel.zulip",
     "tool.browser",
     "tool.composio",
     "tool.http_request",
+    "tool.mcp",
     "tool.pushover",
     "memory.embeddings",
+    "memory.rerank",
     "tunnel.custom",
     "transcription.groq",
 ];
@@ -200,6 +205,10 @@ pub struct Config {
     #[serde(default)]
     pub composio: ComposioConfig,
 
+    /// MCP client: external Model Context Protocol servers exposed as tools (`[mcp]`).
+    #[serde(default)]
+    pub mcp: McpConfig,
+
     /// Secrets encryption configuration (`[secrets]`).
     #[serde(default)]
     pub secrets: SecretsConfig,
@@ -264,6 +273,10 @@ pub struct Config {
     #[serde(default)]
     pub agents_ipc: AgentsIpcConfig,
 
+    /// Standard Operating Procedures (`[sop]`).
+    #[serde(default)]
+    pub sop: SopConfig,
+
     /// Vision support override for the active provider/model.
     /// - `None` (default): use provider's built-in default
     /// - `Some(true)`: force vision support on (e.g. Ollama running llava)
@@ -296,6 +309,71 @@ pub struct ProviderConfig {
     /// (e.g. OpenAI Codex `/responses` reasoning effort).
     #[serde(default)]
     pub reasoning_level: Option<String>,
+    /// Azure OpenAI settings for the `azure` provider (`[provider.azure_openai]`).
+    #[serde(default)]
+    pub azure_openai: AzureOpenAiConfig,
+}
+
+fn default_azure_openai_api_version() -> String {
+    "2024-10-21".into()
+}
+
+/// Azure OpenAI resource settings (`[provider.azure_openai]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AzureOpenAiConfig {
+    /// Resource endpoint (e.g. `"https://my-resource.openai.azure.com"`).
+    /// Falls back to `api_url`, then the `AZURE_OPENAI_ENDPOINT` env var.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// `api-version` query parameter sent with every request. Default: `"2024-10-21"`.
+    #[serde(default = "default_azure_openai_api_version")]
+    pub api_version: String,
+    /// How requests authenticate. Default: `api_key`.
+    #[serde(default)]
+    pub auth: AzureOpenAiAuthMode,
+    /// Model id → deployment mappings. Models without a mapping are sent to a
+    /// deployment of the same name.
+    #[serde(default)]
+    pub deployments: Vec<AzureOpenAiDeploymentConfig>,
+}
+
+impl Default for AzureOpenAiConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            api_version: default_azure_openai_api_version(),
+            auth: AzureOpenAiAuthMode::default(),
+            deployments: Vec::new(),
+        }
+    }
+}
+
+/// Azure OpenAI authentication mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AzureOpenAiAuthMode {
+    /// `api-key` header with the resource key (`api_key` or `AZURE_OPENAI_API_KEY`).
+    #[default]
+    ApiKey,
+    /// Microsoft Entra ID bearer token: `api_key` or `AZURE_OPENAI_AD_TOKEN` as a
+    /// pre-issued token, otherwise a client-credentials token from
+    /// `AZURE_TENANT_ID` / `AZURE_CLIENT_ID` / `AZURE_CLIENT_SECRET`.
+    EntraId,
+}
+
+/// Maps a model id to an Azure OpenAI deployment.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AzureOpenAiDeploymentConfig {
+    /// Model id used in `default_model`, model routes and delegate configs.
+    pub model: String,
+    /// Deployment name in the Azure OpenAI resource.
+    pub deployment: String,
+    /// Endpoint override for deployments hosted in another resource.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// `api-version` override for this deployment.
+    #[serde(default)]
+    pub api_version: Option<String>,
 }
 
 // ── Delegate Agents ──────────────────────────────────────────────
@@ -374,6 +452,9 @@ impl std::fmt::Debug for Config {
             self.channels_config.nextcloud_talk.is_some(),
             self.channels_config.email.is_some(),
             self.channels_config.irc.is_some(),
+            self.channels_config.xmpp.is_some(),
+            self.channels_config.zulip.is_some(),
+            self.channels_config.teams.is_some(),
             self.channels_config.lark.is_some(),
             self.channels_config.feishu.is_some(),
             self.channels_config.dingtalk.is_some(),
@@ -559,6 +640,61 @@ impl Default for AgentsIpcConfig {
     }
 }
 
+// ── SOP ─────────────────────────────────────────────────────────
+
+fn default_sop_max_concurrent_total() -> usize {
+    4
+}
+
+fn default_sop_approval_timeout_secs() -> u64 {
+    300
+}
+
+fn default_sop_max_finished_runs() -> usize {
+    100
+}
+
+/// Standard Operating Procedure engine configuration (`[sop]` section).
+///
+/// SOP definitions live in `<workspace>/sops/<name>/SOP.toml` (+ `SOP.md`).
+/// Run state is persisted to `<workspace>/sop/runs.db` so in-flight runs and
+/// pending approvals survive daemon restarts.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SopConfig {
+    /// Enable the SOP engine and register the `sop_*` tools.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory containing SOP definitions. Defaults to `<workspace>/sops`.
+    #[serde(default)]
+    pub sops_dir: Option<String>,
+    /// Execution mode for SOPs that do not declare one in `SOP.toml`.
+    #[serde(default)]
+    pub default_execution_mode: crate::sop::SopExecutionMode,
+    /// Maximum number of in-flight runs across all SOPs.
+    #[serde(default = "default_sop_max_concurrent_total")]
+    pub max_concurrent_total: usize,
+    /// Seconds a run may wait for approval before critical/high-priority SOPs
+    /// are auto-approved (`0` disables the timeout).
+    #[serde(default = "default_sop_approval_timeout_secs")]
+    pub approval_timeout_secs: u64,
+    /// Number of finished runs kept for status queries (`0` keeps all).
+    #[serde(default = "default_sop_max_finished_runs")]
+    pub max_finished_runs: usize,
+}
+
+impl Default for SopConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sops_dir: None,
+            default_execution_mode: crate::sop::SopExecutionMode::default(),
+            max_concurrent_total: default_sop_max_concurrent_total(),
+            approval_timeout_secs: default_sop_approval_timeout_secs(),
+            max_finished_runs: default_sop_max_finished_runs(),
+        }
+    }
+}
+
 fn default_coordination_enabled() -> bool {
     true
 }
@@ -1024,6 +1160,12 @@ pub struct GatewayConfig {
     /// Node-control protocol scaffold (`[gateway.node_control]`).
     #[serde(default)]
     pub node_control: NodeControlConfig,
+
+    /// Model ids that `/v1/chat/completions` serves with the full agent loop,
+    /// executing ZeroClaw's registered tools server-side (e.g. `["zeroclaw-agent"]`).
+    /// Requests for these models may not carry client-supplied `tools`.
+    #[serde(default)]
+    pub agent_models: Vec<String>,
 }
 
 /// Node-control scaffold settings under `[gateway.node_control]`.
@@ -1091,10 +1233,142 @@ impl Default for GatewayConfig {
             idempotency_ttl_secs: default_idempotency_ttl_secs(),
             idempotency_max_keys: default_gateway_idempotency_max_keys(),
             node_control: NodeControlConfig::default(),
+            agent_models: Vec::new(),
+        }
+    }
+}
+
+// ── MCP (Model Context Protocol) ────────────────────────────────
+
+/// Transport used to reach an MCP server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransportKind {
+    /// Spawn the server as a child process and speak JSON-RPC over stdin/stdout.
+    #[default]
+    Stdio,
+    /// Streamable HTTP transport (JSON-RPC over POST, JSON or SSE responses).
+    #[serde(alias = "streamable_http", alias = "streamable-http")]
+    Http,
+}
+
+/// MCP configuration (`[mcp]` section).
+///
+/// Each configured server is connected at startup and its tools are registered
+/// next to the native tools, named `<server>__<tool>`. The `serve` subsection
+/// controls `zeroclaw mcp serve` and the gateway `/mcp` route.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct McpConfig {
+    /// Enable the MCP client (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// MCP servers to connect to.
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+    /// Expose ZeroClaw's own tools as an MCP server.
+    #[serde(default)]
+    pub serve: McpServeConfig,
+}
+
+/// MCP server-side configuration (`[mcp.serve]`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct McpServeConfig {
+    /// Mount the streamable HTTP endpoint at `POST /mcp` on the gateway (default: false).
+    /// Requests use the same bearer-token pairing as the rest of the gateway API.
+    #[serde(default)]
+    pub gateway_enabled: bool,
+    /// Only publish these tools (empty = all registered tools).
+    #[serde(default)]
+    pub include_tools: Vec<String>,
+    /// Never publish these tools.
+    #[serde(default)]
+    pub exclude_tools: Vec<String>,
+}
+
+/// Configuration for a single MCP server (`[[mcp.servers]]`).
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpServerConfig {
+    /// Server name; used as the tool name prefix (`[A-Za-z0-9_-]`).
+    pub name: String,
+    /// Transport: "stdio" (default) or "http".
+    #[serde(default)]
+    pub transport: McpTransportKind,
+    /// Executable to spawn (stdio transport).
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments passed to `command` (stdio transport).
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables for the spawned server (stdio transport).
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory for the spawned server (stdio transport).
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Endpoint URL (http transport).
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Extra HTTP headers sent with every request (http transport).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Per-request timeout in seconds (default: 30).
+    #[serde(default = "default_mcp_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Only register these remote tools (empty = all).
+    #[serde(default)]
+    pub include_tools: Vec<String>,
+    /// Never register these remote tools.
+    #[serde(default)]
+    pub exclude_tools: Vec<String>,
+    /// Honor the server's `readOnlyHint` annotations. Off by default: the
+    /// hint is advisory, so every tool of an untrusted server counts as a
+    /// side-effecting action.
+    #[serde(default)]
+    pub trust_annotations: bool,
+}
+
+fn default_mcp_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for McpServerConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            transport: McpTransportKind::default(),
+            command: None,
+            args: Vec::new(),
+            env: HashMap::new(),
+            cwd: None,
+            url: None,
+            headers: HashMap::new(),
+            timeout_secs: default_mcp_timeout_secs(),
+            include_tools: Vec::new(),
+            exclude_tools: Vec::new(),
+            trust_annotations: false,
         }
     }
 }
 
+impl std::fmt::Debug for McpServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpServerConfig")
+            .field("name", &self.name)
+            .field("transport", &self.transport)
+            .field("command", &self.command)
+            .field("args", &self.args)
+            .field("env_keys", &self.env.keys().collect::<Vec<_>>())
+            .field("cwd", &self.cwd)
+            .field("url", &self.url)
+            .field("header_keys", &self.headers.keys().collect::<Vec<_>>())
+            .field("timeout_secs", &self.timeout_secs)
+            .field("include_tools", &self.include_tools)
+            .field("exclude_tools", &self.exclude_tools)
+            .field("trust_annotations", &self.trust_annotations)
+            .finish()
+    }
+}
+
 // ── Composio (managed tool surface) ─────────────────────────────
 
 /// Composio managed OAuth tools integration (`[composio]` section).
@@ -1999,6 +2273,219 @@ impl Default for QdrantConfig {
     }
 }
 
+/// How recalled memories are reranked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankBackend {
+    /// Ask a chat model (normally a cheap `hint:rerank` model route) to order
+    /// the candidates.
+    #[default]
+    Llm,
+    /// Call a Cohere/Jina-style `/rerank` endpoint.
+    Http,
+}
+
+/// Rerank stage for memory recall (`[memory.rerank]`).
+///
+/// Recall fetches `candidates` entries, the reranker reorders them, and only
+/// the top entries are injected into the prompt. If the reranker fails or
+/// times out, the original recall order is used.
+///
+/// ```toml
+/// [memory.rerank]
+/// enabled = true
+/// backend = "llm"          # uses [[model_routes]] hint = "rerank"
+///
+/// # or a dedicated endpoint:
+/// # backend = "http"
+/// # endpoint = "https://api.cohere.com/v2/rerank"
+/// # model = "rerank-v3.5"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MemoryRerankConfig {
+    /// Enable reranking of recalled memories. Default: false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Reranker backend: "llm" or "http". Default: "llm".
+    #[serde(default)]
+    pub backend: RerankBackend,
+    /// Number of recall candidates passed to the reranker. Default: 20.
+    #[serde(default = "default_rerank_candidates")]
+    pub candidates: usize,
+    /// Model to use. For `llm`, a model name or `hint:<name>` route
+    /// (default `hint:rerank`); for `http`, the rerank model sent to the endpoint.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Rerank endpoint URL (required for `http`).
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Bearer token for the rerank endpoint.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Reranked orderings cached per query and candidate set. Default: 256.
+    #[serde(default = "default_rerank_cache_size")]
+    pub cache_size: usize,
+    /// Give up and keep the recall order after this many seconds. Default: 10.
+    #[serde(default = "default_rerank_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_rerank_candidates() -> usize {
+    20
+}
+
+fn default_rerank_cache_size() -> usize {
+    256
+}
+
+fn default_rerank_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for MemoryRerankConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: RerankBackend::default(),
+            candidates: default_rerank_candidates(),
+            model: None,
+            endpoint: None,
+            api_key: None,
+            cache_size: default_rerank_cache_size(),
+            timeout_secs: default_rerank_timeout_secs(),
+        }
+    }
+}
+
+/// Native memory consolidation (`[memory.consolidation]`).
+///
+/// Extracts atomic facts from auto-saved conversation turns with a
+/// structured LLM call and reconciles them with existing `core` memories:
+/// new facts are added, near-duplicates are merged, and facts that update an
+/// existing entry supersede it. Every run produces a diff report; changes are
+/// only written when applied (`zeroclaw memory consolidate --apply`, or
+/// `auto_apply` for the nightly job).
+///
+/// ```toml
+/// [memory.consolidation]
+/// enabled = true
+/// model = "hint:consolidation"   # or a model name; defaults to default_model
+/// auto_apply = false
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MemoryConsolidationConfig {
+    /// Run the native engine for the nightly consolidation cron job instead
+    /// of an agent prompt. Default: false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Model for fact extraction, a model name or `hint:<name>` route.
+    /// Defaults to `default_model`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Apply scheduled runs immediately instead of leaving a pending plan
+    /// for review. Default: false.
+    #[serde(default)]
+    pub auto_apply: bool,
+    /// Embedding similarity at or above which a fact is a duplicate of an
+    /// existing entry. Default: 0.92.
+    #[serde(default = "default_consolidation_duplicate_threshold")]
+    pub duplicate_threshold: f64,
+    /// Embedding similarity at or above which the model is asked whether a
+    /// fact duplicates or updates an existing entry. Default: 0.75.
+    #[serde(default = "default_consolidation_related_threshold")]
+    pub related_threshold: f64,
+    /// Conversation turns processed per run. Default: 200.
+    #[serde(default = "default_consolidation_max_turns")]
+    pub max_turns: usize,
+}
+
+fn default_consolidation_duplicate_threshold() -> f64 {
+    0.92
+}
+
+fn default_consolidation_related_threshold() -> f64 {
+    0.75
+}
+
+fn default_consolidation_max_turns() -> usize {
+    200
+}
+
+impl Default for MemoryConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: None,
+            auto_apply: false,
+            duplicate_threshold: default_consolidation_duplicate_threshold(),
+            related_threshold: default_consolidation_related_threshold(),
+            max_turns: default_consolidation_max_turns(),
+        }
+    }
+}
+
+/// Who can read a memory category written from a chat channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemorySharing {
+    /// Visible to everyone (the `default` namespace).
+    Global,
+    /// Shared by everyone in the same chat or group.
+    Chat,
+    /// Only visible to the sender.
+    #[default]
+    Private,
+}
+
+/// Per-user memory namespaces for channels (`[memory.namespaces]`).
+///
+/// Channel messages read their sender's private namespace, their chat's
+/// shared namespace and the global `default` namespace. Writes go to the
+/// namespace chosen by the category's sharing rule. The CLI, the gateway API
+/// and pre-existing entries live in `default`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MemoryNamespaceConfig {
+    /// Isolate channel memories per sender/chat. Default: true.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Sharing for `core` memories. Default: "chat".
+    #[serde(default = "default_core_sharing")]
+    pub core: MemorySharing,
+    /// Sharing for `daily` memories. Default: "private".
+    #[serde(default)]
+    pub daily: MemorySharing,
+    /// Sharing for `conversation` memories (auto-save). Default: "private".
+    #[serde(default)]
+    pub conversation: MemorySharing,
+    /// Sharing for `knowledge` (ingested documents). Default: "global".
+    #[serde(default = "default_knowledge_sharing")]
+    pub knowledge: MemorySharing,
+    /// Sharing for custom categories. Default: "private".
+    #[serde(default)]
+    pub custom: MemorySharing,
+}
+
+fn default_core_sharing() -> MemorySharing {
+    MemorySharing::Chat
+}
+
+fn default_knowledge_sharing() -> MemorySharing {
+    MemorySharing::Global
+}
+
+impl Default for MemoryNamespaceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            core: default_core_sharing(),
+            daily: MemorySharing::Private,
+            conversation: MemorySharing::Private,
+            knowledge: default_knowledge_sharing(),
+            custom: MemorySharing::Private,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct MemoryConfig {
@@ -2021,13 +2508,14 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "azure" | "azure:URL" | "ollama" | "ollama:URL"
+    /// | "gemini" | "cohere" | "bedrock" | "voyage" | "custom:URL"
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
     /// Embedding model name (e.g. "text-embedding-3-small")
     #[serde(default = "default_embedding_model")]
     pub embedding_model: String,
-    /// Embedding vector dimensions
+    /// Embedding vector dimensions (0 = use the model's known size)
     #[serde(default = "default_embedding_dims")]
     pub embedding_dimensions: usize,
     /// Weight for vector similarity in hybrid search (0.0–1.0)
@@ -2041,6 +2529,16 @@ pub struct MemoryConfig {
     /// context from bleeding into conversations. Default: 0.4
     #[serde(default = "default_min_relevance_score")]
     pub min_relevance_score: f64,
+    /// How much an entry's importance (0.0–1.0) scales its recall score.
+    /// Scores are multiplied by 1 ± this weight at importance 1.0/0.0; 0 disables.
+    #[serde(default = "default_importance_weight")]
+    pub importance_weight: f64,
+    /// Share of the recall score that decays with entry age (0.0–1.0); 0 disables.
+    #[serde(default = "default_recency_weight")]
+    pub recency_weight: f64,
+    /// Age in days at which the decaying share of the score is halved
+    #[serde(default = "default_recency_half_life_days")]
+    pub recency_half_life_days: f64,
     /// Max embedding cache entries before LRU eviction
     #[serde(default = "default_cache_size")]
     pub embedding_cache_size: usize,
@@ -2058,6 +2556,13 @@ pub struct MemoryConfig {
     /// Max number of cached responses before LRU eviction (default: 5000)
     #[serde(default = "default_response_cache_max")]
     pub response_cache_max_entries: usize,
+    /// Also serve near-duplicate prompts by embedding similarity (default: false).
+    /// Requires a configured `embedding_provider`.
+    #[serde(default)]
+    pub response_cache_semantic: bool,
+    /// Minimum cosine similarity for a semantic cache hit (default: 0.95)
+    #[serde(default = "default_response_cache_similarity")]
+    pub response_cache_similarity_threshold: f64,
 
     // ── Memory Snapshot (soul backup to Markdown) ─────────────
     /// Enable periodic export of core memories to MEMORY_SNAPSHOT.md
@@ -2075,12 +2580,41 @@ pub struct MemoryConfig {
     /// None = wait indefinitely (default). Recommended max: 300.
     #[serde(default)]
     pub sqlite_open_timeout_secs: Option<u64>,
+    /// For sqlite backend: use a persisted HNSW index (`brain.hnsw`) for vector
+    /// recall instead of scanning every embedding. Default: true.
+    #[serde(default = "default_true")]
+    pub sqlite_ann_index: bool,
+
+    // ── Encryption at rest ─────────────────────────────────────
+    /// Encrypt stored memory content, snapshots, channel sessions, cron output
+    /// and cost records with ChaCha20-Poly1305. Data keys live in
+    /// `~/.zeroclaw/.memory_keys`, wrapped with the secret store key.
+    /// Existing plaintext stays readable; `zeroclaw memory rekey` encrypts it.
+    #[serde(default)]
+    pub encrypt_at_rest: bool,
 
     // ── Qdrant backend options ─────────────────────────────────
     /// Configuration for Qdrant vector database backend.
     /// Only used when `backend = "qdrant"`.
     #[serde(default)]
     pub qdrant: QdrantConfig,
+
+    // ── Namespaces (multi-user channels) ───────────────────────
+    /// Per-sender/per-chat isolation of channel memories.
+    #[serde(default)]
+    pub namespaces: MemoryNamespaceConfig,
+
+    // ── Rerank (recall precision) ──────────────────────────────
+    /// Optional rerank stage applied to recalled memories before they are
+    /// injected into the prompt.
+    #[serde(default)]
+    pub rerank: MemoryRerankConfig,
+
+    // ── Consolidation ──────────────────────────────────────────
+    /// Fact extraction and deduplicating consolidation of conversation turns
+    /// into `core` memories.
+    #[serde(default)]
+    pub consolidation: MemoryConsolidationConfig,
 }
 
 fn default_embedding_provider() -> String {
@@ -2113,6 +2647,15 @@ fn default_keyword_weight() -> f64 {
 fn default_min_relevance_score() -> f64 {
     0.4
 }
+fn default_importance_weight() -> f64 {
+    0.3
+}
+fn default_recency_weight() -> f64 {
+    0.2
+}
+fn default_recency_half_life_days() -> f64 {
+    30.0
+}
 fn default_cache_size() -> usize {
     10_000
 }
@@ -2125,6 +2668,9 @@ fn default_response_cache_ttl() -> u32 {
 fn default_response_cache_max() -> usize {
     5_000
 }
+fn default_response_cache_similarity() -> f64 {
+    0.95
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -2141,16 +2687,26 @@ impl Default for MemoryConfig {
             vector_weight: default_vector_weight(),
             keyword_weight: default_keyword_weight(),
             min_relevance_score: default_min_relevance_score(),
+            importance_weight: default_importance_weight(),
+            recency_weight: default_recency_weight(),
+            recency_half_life_days: default_recency_half_life_days(),
             embedding_cache_size: default_cache_size(),
             chunk_max_tokens: default_chunk_size(),
             response_cache_enabled: false,
             response_cache_ttl_minutes: default_response_cache_ttl(),
             response_cache_max_entries: default_response_cache_max(),
+            response_cache_semantic: false,
+            response_cache_similarity_threshold: default_response_cache_similarity(),
             snapshot_enabled: false,
             snapshot_on_hygiene: false,
             auto_hydrate: true,
             sqlite_open_timeout_secs: None,
+            sqlite_ann_index: true,
+            encrypt_at_rest: false,
             qdrant: QdrantConfig::default(),
+            namespaces: MemoryNamespaceConfig::default(),
+            rerank: MemoryRerankConfig::default(),
+            consolidation: MemoryConsolidationConfig::default(),
         }
     }
 }
@@ -2348,6 +2904,81 @@ pub struct AutonomyConfig {
     #[serde(default)]
     pub non_cli_natural_language_approval_mode_by_channel:
         HashMap<String, NonCliNaturalLanguageApprovalMode>,
+
+    /// Argument-aware approval rules (`[[autonomy.approval_rules]]`).
+    ///
+    /// Evaluated in order before `always_ask` / `auto_approve`; the first
+    /// rule whose conditions all match a tool call decides it. `deny` is
+    /// enforced at every autonomy level, `ask` and `allow` only matter in
+    /// supervised mode.
+    #[serde(default)]
+    pub approval_rules: Vec<ApprovalRuleConfig>,
+}
+
+/// Outcome of an approval rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalRuleAction {
+    /// Run without prompting.
+    Allow,
+    /// Require interactive approval, even after "Always" or `auto_approve`.
+    Ask,
+    /// Refuse the call without prompting.
+    Deny,
+}
+
+/// Shell command risk threshold used by approval rules.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalRiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// One argument-aware approval rule.
+///
+/// Every condition that is set must match; a rule without conditions matches
+/// every call to `tool`.
+///
+/// ```toml
+/// [[autonomy.approval_rules]]
+/// tool = "shell"
+/// command = "git push*"
+/// action = "ask"
+///
+/// [[autonomy.approval_rules]]
+/// tool = "http_request"
+/// hosts = ["api.github.com"]
+/// action = "allow"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ApprovalRuleConfig {
+    /// Label shown when the rule fires. Defaults to a summary of the conditions.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Tool name the rule applies to, or `"*"` for any tool.
+    pub tool: String,
+    /// Glob matched against the `command` argument (e.g. `"rm *"`).
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Regular expression matched against the `command` argument.
+    #[serde(default)]
+    pub command_regex: Option<String>,
+    /// Match when the `command` argument's risk level is at least this level.
+    #[serde(default)]
+    pub min_risk: Option<ApprovalRiskLevel>,
+    /// Path prefixes matched against the `path` argument (`file_write`, `file_edit`).
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Host patterns matched against the `url` argument (`http_request`,
+    /// `web_fetch`). `example.com` also matches subdomains; `*` matches any host.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// What to do when the rule matches.
+    pub action: ApprovalRuleAction,
 }
 
 fn default_auto_approve() -> Vec<String> {
@@ -2448,6 +3079,7 @@ impl Default for AutonomyConfig {
             non_cli_approval_approvers: Vec::new(),
             non_cli_natural_language_approval_mode: NonCliNaturalLanguageApprovalMode::default(),
             non_cli_natural_language_approval_mode_by_channel: HashMap::new(),
+            approval_rules: Vec::new(),
         }
     }
 }
@@ -2974,7 +3606,8 @@ pub struct ModelRouteConfig {
 pub struct EmbeddingRouteConfig {
     /// Route hint name (e.g. "semantic", "archive", "faq")
     pub hint: String,
-    /// Embedding provider (`none`, `openai`, or `custom:<url>`)
+    /// Embedding provider (`none`, `openai`, `azure`, `azure:<endpoint>`, `ollama`,
+    /// `ollama:<url>`, `gemini`, `cohere`, `bedrock`, `voyage`, or `custom:<url>`)
     pub provider: String,
     /// Embedding model to use with that provider
     pub model: String,
@@ -3242,6 +3875,12 @@ pub struct ChannelsConfig {
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
     pub irc: Option<IrcConfig>,
+    /// XMPP (Jabber) channel configuration.
+    pub xmpp: Option<XmppConfig>,
+    /// Zulip channel configuration.
+    pub zulip: Option<ZulipConfig>,
+    /// Microsoft Teams (Bot Framework) channel configuration.
+    pub teams: Option<TeamsConfig>,
     /// Lark channel configuration.
     pub lark: Option<LarkConfig>,
     /// Feishu channel configuration.
@@ -3260,6 +3899,13 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
+    /// Persist per-sender conversation history to `sessions/sessions.db` in
+    /// the workspace so channel conversations survive daemon restarts.
+    /// Turns are stored in plaintext unless `[memory] encrypt_at_rest` is on,
+    /// so this is opt-in (the onboarding wizard asks).
+    /// Default: `false`.
+    #[serde(default)]
+    pub persist_sessions: bool,
 }
 
 impl ChannelsConfig {
@@ -3319,6 +3965,18 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(self.irc.as_ref())),
                 self.irc.is_some()
             ),
+            (
+                Box::new(ConfigWrapper::new(self.xmpp.as_ref())),
+                self.xmpp.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(self.zulip.as_ref())),
+                self.zulip.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(self.teams.as_ref())),
+                self.teams.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(self.lark.as_ref())),
                 self.lark.is_some(),
@@ -3380,6 +4038,9 @@ impl Default for ChannelsConfig {
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            teams: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -3387,6 +4048,7 @@ impl Default for ChannelsConfig {
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
+            persist_sessions: false,
         }
     }
 }
@@ -3951,6 +4613,190 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// How the XMPP channel secures the client-to-server connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum XmppTlsMode {
+    /// Connect in plain text and upgrade with STARTTLS (port 5222). Servers
+    /// that do not offer STARTTLS are rejected.
+    #[default]
+    Starttls,
+    /// Open TLS before the XML stream (XEP-0368, port 5223).
+    DirectTls,
+}
+
+/// XMPP (Jabber) channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct XmppConfig {
+    /// Bot account JID (e.g. `"zeroclaw@example.org"`).
+    pub jid: String,
+    /// Account password. Authenticated with SCRAM-SHA-256 when offered, else PLAIN over TLS.
+    pub password: String,
+    /// Host to connect to. Defaults to the JID domain (SRV records are not resolved).
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Port to connect to. Defaults to 5222 for STARTTLS and 5223 for direct TLS.
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Transport security mode: `starttls` (default) or `direct_tls`.
+    #[serde(default)]
+    pub tls_mode: XmppTlsMode,
+    /// Verify the server TLS certificate (default: true).
+    #[serde(default = "default_true")]
+    pub verify_tls: bool,
+    /// Resource to bind (default: `"zeroclaw"`).
+    #[serde(default)]
+    pub resource: Option<String>,
+    /// MUC rooms to join (e.g. `"ops@conference.example.org"`).
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Nickname used in MUC rooms. Defaults to the JID local part.
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Allowed sender bare JIDs, or `"*"`. Empty = deny all.
+    /// In MUC rooms, occupant JIDs (`room@service/nick`) also match, and
+    /// real JIDs match when the room discloses them.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group-chat trigger controls for MUC rooms.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+    /// Streaming mode for progressive responses via XEP-0308 message corrections.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft corrections.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+}
+
+impl ChannelConfig for XmppConfig {
+    fn name() -> &'static str {
+        "XMPP"
+    }
+    fn desc() -> &'static str {
+        "Jabber/XMPP with MUC rooms"
+    }
+}
+
+impl XmppConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::AllMessages)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+/// Zulip channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ZulipConfig {
+    /// Zulip server URL (e.g. `"https://chat.example.com"`).
+    pub site: String,
+    /// Bot account email.
+    pub email: String,
+    /// Bot API key.
+    pub api_key: String,
+    /// Stream names or IDs to answer in. Empty = every stream the bot is subscribed to.
+    #[serde(default)]
+    pub streams: Vec<String>,
+    /// Allowed sender emails or numeric user IDs, or `"*"`. Empty = deny all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group-chat trigger controls for streams and group DMs.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+    /// Streaming mode for progressive responses via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft edits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+}
+
+impl ChannelConfig for ZulipConfig {
+    fn name() -> &'static str {
+        "Zulip"
+    }
+    fn desc() -> &'static str {
+        "Zulip streams, topics and DMs"
+    }
+}
+
+impl ZulipConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::AllMessages)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
+fn default_teams_openid_metadata_url() -> String {
+    "https://login.botframework.com/v1/.well-known/openidconfiguration".into()
+}
+
+fn default_teams_token_issuer() -> String {
+    "https://api.botframework.com".into()
+}
+
+/// Microsoft Teams channel configuration (Azure Bot / Bot Framework).
+///
+/// Activities arrive on the gateway's `POST /teams` route; replies go through
+/// the Bot Framework connector service.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TeamsConfig {
+    /// Microsoft App ID of the bot registration. Inbound tokens must carry it as audience.
+    pub app_id: String,
+    /// Client secret of the bot registration.
+    pub app_password: String,
+    /// Entra tenant for single-tenant bots. Defaults to `botframework.com` (multi-tenant).
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// OpenID metadata document used to fetch the keys that sign inbound requests.
+    #[serde(default = "default_teams_openid_metadata_url")]
+    pub openid_metadata_url: String,
+    /// Expected `iss` claim of inbound tokens.
+    #[serde(default = "default_teams_token_issuer")]
+    pub token_issuer: String,
+    /// OAuth token endpoint for outbound calls. Defaults to the tenant's
+    /// `login.microsoftonline.com` v2.0 token endpoint.
+    #[serde(default)]
+    pub token_endpoint: Option<String>,
+    /// Allowed Entra object IDs or Teams user IDs (`29:...`), or `"*"`. Empty = deny all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group-chat trigger controls for team channels and group chats.
+    #[serde(default)]
+    pub group_reply: Option<GroupReplyConfig>,
+}
+
+impl ChannelConfig for TeamsConfig {
+    fn name() -> &'static str {
+        "Teams"
+    }
+    fn desc() -> &'static str {
+        "Microsoft Teams via Bot Framework"
+    }
+}
+
+impl TeamsConfig {
+    #[must_use]
+    pub fn effective_group_reply_mode(&self) -> GroupReplyMode {
+        resolve_group_reply_mode(self.group_reply.as_ref(), None, GroupReplyMode::AllMessages)
+    }
+
+    #[must_use]
+    pub fn group_reply_allowed_sender_ids(&self) -> Vec<String> {
+        clone_group_reply_allowed_sender_ids(self.group_reply.as_ref())
+    }
+}
+
 /// How ZeroClaw receives events from Feishu / Lark.
 ///
 /// - `websocket` (default) — persistent WSS long-connection; no public URL required.
@@ -4629,6 +5475,7 @@ impl Default for Config {
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -4646,6 +5493,7 @@ impl Default for Config {
             query_classification: QueryClassificationConfig::default(),
             transcription: TranscriptionConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
+            sop: SopConfig::default(),
             model_support_vision: None,
         }
     }
@@ -5102,6 +5950,27 @@ fn decrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        decrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        decrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
+    if let Some(ref mut teams) = channels.teams {
+        decrypt_secret(
+            store,
+            &mut teams.app_password,
+            "config.channels_config.teams.app_password",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         decrypt_secret(
             store,
@@ -5264,6 +6133,27 @@ fn encrypt_channel_secrets(
             "config.channels_config.irc.sasl_password",
         )?;
     }
+    if let Some(ref mut xmpp) = channels.xmpp {
+        encrypt_secret(
+            store,
+            &mut xmpp.password,
+            "config.channels_config.xmpp.password",
+        )?;
+    }
+    if let Some(ref mut zulip) = channels.zulip {
+        encrypt_secret(
+            store,
+            &mut zulip.api_key,
+            "config.channels_config.zulip.api_key",
+        )?;
+    }
+    if let Some(ref mut teams) = channels.teams {
+        encrypt_secret(
+            store,
+            &mut teams.app_password,
+            "config.channels_config.teams.app_password",
+        )?;
+    }
     if let Some(ref mut lark) = channels.lark {
         encrypt_secret(
             store,
@@ -5494,6 +6384,10 @@ impl Config {
             decrypt_channel_secrets(&store, &mut config.channels_config)?;
 
             config.apply_env_overrides();
+            crate::memory::encryption::set_runtime_encryption(
+                config.memory.encrypt_at_rest,
+                &zeroclaw_dir,
+            );
             config.validate()?;
             tracing::info!(
                 path = %config.config_path.display(),
@@ -5517,6 +6411,10 @@ impl Config {
             }
 
             config.apply_env_overrides();
+            crate::memory::encryption::set_runtime_encryption(
+                config.memory.encrypt_at_rest,
+                &zeroclaw_dir,
+            );
             config.validate()?;
             tracing::info!(
                 path = %config.config_path.display(),
@@ -5717,6 +6615,11 @@ impl Config {
             }
         }
 
+        for (i, rule) in self.autonomy.approval_rules.iter().enumerate() {
+            crate::approval::ApprovalRule::compile(rule)
+                .with_context(|| format!("autonomy.approval_rules[{i}] is invalid"))?;
+        }
+
         // Security OTP / estop
         if self.security.otp.token_ttl_secs == 0 {
             anyhow::bail!("security.otp.token_ttl_secs must be greater than 0");
@@ -5936,6 +6839,50 @@ impl Config {
             anyhow::bail!("coordination.max_seen_message_ids must be greater than 0");
         }
 
+        // MCP servers
+        let mut seen_mcp_servers = std::collections::HashSet::new();
+        for (i, server) in self.mcp.servers.iter().enumerate() {
+            let name = server.name.trim();
+            if name.is_empty() {
+                anyhow::bail!("mcp.servers[{i}].name must not be empty");
+            }
+            if !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                anyhow::bail!("mcp.servers[{i}].name contains invalid characters: {name}");
+            }
+            if !seen_mcp_servers.insert(name.to_string()) {
+                anyhow::bail!("mcp.servers contains duplicate server name: {name}");
+            }
+            if server.timeout_secs == 0 {
+                anyhow::bail!("mcp.servers[{i}].timeout_secs must be greater than 0");
+            }
+            match server.transport {
+                McpTransportKind::Stdio => {
+                    if server
+                        .command
+                        .as_deref()
+                        .map(str::trim)
+                        .is_none_or(str::is_empty)
+                    {
+                        anyhow::bail!("mcp.servers[{i}].command is required for stdio transport");
+                    }
+                }
+                McpTransportKind::Http => {
+                    let url = server.url.as_deref().map(str::trim).unwrap_or_default();
+                    if url.is_empty() {
+                        anyhow::bail!("mcp.servers[{i}].url is required for http transport");
+                    }
+                    let parsed = reqwest::Url::parse(url)
+                        .with_context(|| format!("mcp.servers[{i}].url is not a valid URL"))?;
+                    if !matches!(parsed.scheme(), "http" | "https") {
+                        anyhow::bail!("mcp.servers[{i}].url must use http/https");
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -6666,6 +7613,47 @@ allowed_roots = []
             .contains("autonomy.non_cli_excluded_tools contains duplicate entry"));
     }
 
+    #[test]
+    async fn config_validate_rejects_invalid_approval_rule_patterns() {
+        let mut cfg = Config::default();
+        cfg.autonomy.approval_rules = vec![ApprovalRuleConfig {
+            name: None,
+            tool: "shell".into(),
+            command: None,
+            command_regex: Some("(".into()),
+            min_risk: None,
+            paths: vec![],
+            hosts: vec![],
+            action: ApprovalRuleAction::Deny,
+        }];
+        let err = cfg.validate().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("autonomy.approval_rules[0] is invalid"));
+
+        let parsed: AutonomyConfig = toml::from_str(
+            r#"
+level = "supervised"
+workspace_only = true
+allowed_commands = []
+forbidden_paths = []
+max_actions_per_hour = 10
+max_cost_per_day_cents = 100
+
+[[approval_rules]]
+tool = "shell"
+min_risk = "high"
+action = "deny"
+"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.approval_rules.len(), 1);
+        assert_eq!(
+            parsed.approval_rules[0].min_risk,
+            Some(ApprovalRiskLevel::High)
+        );
+    }
+
     #[test]
     async fn runtime_config_default() {
         let r = RuntimeConfig::default();
@@ -6767,6 +7755,7 @@ default_temperature = 0.7
         assert_eq!(m.purge_after_days, 30);
         assert_eq!(m.conversation_retention_days, 30);
         assert!(m.sqlite_open_timeout_secs.is_none());
+        assert!(m.sqlite_ann_index);
     }
 
     #[test]
@@ -6785,6 +7774,9 @@ default_temperature = 0.7
         assert!(c.cli);
         assert!(c.telegram.is_none());
         assert!(c.discord.is_none());
+        assert!(!c.persist_sessions);
+        let parsed: ChannelsConfig = toml::from_str("cli = true").unwrap();
+        assert!(!parsed.persist_sessions);
     }
 
     // ── Serde round-trip ─────────────────────────────────────
@@ -6824,6 +7816,7 @@ default_temperature = 0.7
                 non_cli_natural_language_approval_mode:
                     NonCliNaturalLanguageApprovalMode::RequestConfirm,
                 non_cli_natural_language_approval_mode_by_channel: HashMap::new(),
+                approval_rules: vec![],
             },
             security: SecurityConfig::default(),
             runtime: RuntimeConfig {
@@ -6872,6 +7865,9 @@ default_temperature = 0.7
                 nextcloud_talk: None,
                 email: None,
                 irc: None,
+                xmpp: None,
+                zulip: None,
+                teams: None,
                 lark: None,
                 feishu: None,
                 dingtalk: None,
@@ -6879,12 +7875,14 @@ default_temperature = 0.7
                 nostr: None,
                 clawdtalk: None,
                 message_timeout_secs: 300,
+                persist_sessions: true,
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -6901,6 +7899,7 @@ default_temperature = 0.7
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
+            sop: SopConfig::default(),
             model_support_vision: None,
         };
 
@@ -6993,6 +7992,37 @@ reasoning_enabled = false
         assert_eq!(parsed.runtime.reasoning_enabled, Some(false));
     }
 
+    #[test]
+    async fn provider_azure_openai_deserializes() {
+        let raw = r#"
+default_temperature = 0.7
+
+[provider.azure_openai]
+endpoint = "https://res.openai.azure.com"
+auth = "entra_id"
+
+[[provider.azure_openai.deployments]]
+model = "gpt-4o"
+deployment = "prod-gpt4o"
+api_version = "2025-01-01-preview"
+"#;
+
+        let parsed: Config = toml::from_str(raw).unwrap();
+        let azure = &parsed.provider.azure_openai;
+        assert_eq!(
+            azure.endpoint.as_deref(),
+            Some("https://res.openai.azure.com")
+        );
+        assert_eq!(azure.api_version, "2024-10-21");
+        assert_eq!(azure.auth, AzureOpenAiAuthMode::EntraId);
+        assert_eq!(azure.deployments.len(), 1);
+        assert_eq!(azure.deployments[0].deployment, "prod-gpt4o");
+        assert_eq!(
+            azure.deployments[0].api_version.as_deref(),
+            Some("2025-01-01-preview")
+        );
+    }
+
     #[test]
     async fn runtime_wasm_deserializes() {
         let raw = r#"
@@ -7253,6 +8283,7 @@ tool_dispatcher = "xml"
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -7269,6 +8300,7 @@ tool_dispatcher = "xml"
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
             agents_ipc: AgentsIpcConfig::default(),
+            sop: SopConfig::default(),
             model_support_vision: None,
         };
 
@@ -7764,6 +8796,9 @@ allowed_users = ["@ops:matrix.org"]
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            teams: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -7771,6 +8806,7 @@ allowed_users = ["@ops:matrix.org"]
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            persist_sessions: true,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -8042,6 +9078,9 @@ channel_id = "C123"
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
+            zulip: None,
+            teams: None,
             lark: None,
             feishu: None,
             dingtalk: None,
@@ -8049,6 +9088,7 @@ channel_id = "C123"
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            persist_sessions: true,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -8141,6 +9181,7 @@ channel_id = "C123"
                 auth_token: Some("node-token".into()),
                 allowed_node_ids: vec!["node-1".into(), "node-2".into()],
             },
+            agent_models: vec!["zeroclaw-agent".into()],
         };
         let toml_str = toml::to_string(&g).unwrap();
         let parsed: GatewayConfig = toml::from_str(&toml_str).unwrap();
@@ -8162,6 +9203,7 @@ channel_id = "C123"
             parsed.node_control.allowed_node_ids,
             vec!["node-1", "node-2"]
         );
+        assert_eq!(parsed.agent_models, vec!["zeroclaw-agent"]);
     }
 
     #[test]
@@ -1,5 +1,6 @@
 use super::types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
 use crate::config::schema::CostConfig;
+use crate::memory::encryption::{self, MemoryCipher};
 use anyhow::{anyhow, Context, Result};
 use chrono::{Datelike, NaiveDate, Utc};
 use parking_lot::{Mutex, MutexGuard};
@@ -18,11 +19,13 @@ pub struct CostTracker {
 }
 
 impl CostTracker {
-    /// Create a new cost tracker.
+    /// Create a new cost tracker. Records are encrypted when
+    /// `[memory] encrypt_at_rest` is enabled.
     pub fn new(config: CostConfig, workspace_dir: &Path) -> Result<Self> {
         let storage_path = resolve_storage_path(workspace_dir)?;
+        let cipher = encryption::runtime_cipher()?;
 
-        let storage = CostStorage::new(&storage_path).with_context(|| {
+        let storage = CostStorage::new(&storage_path, cipher).with_context(|| {
             format!("Failed to open cost storage at {}", storage_path.display())
         })?;
 
@@ -175,6 +178,33 @@ impl CostTracker {
     }
 }
 
+/// Rewrite `state/costs.jsonl` under `cipher`'s active key, or as plaintext
+/// when it is decrypt-only or absent. Returns the number of records rewritten.
+pub fn rekey_storage(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<usize> {
+    let path = resolve_storage_path(workspace_dir)?;
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read cost storage from {}", path.display()))?;
+    let mut output = String::with_capacity(raw.len());
+    let mut count = 0;
+    for line in raw.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let plaintext = encryption::decrypt_field(cipher, line)?;
+        output.push_str(&encryption::encrypt_field(cipher, &plaintext)?);
+        output.push('\n');
+        count += 1;
+    }
+
+    let tmp_path = path.with_extension("jsonl.tmp");
+    fs::write(&tmp_path, output)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, &path)
+        .with_context(|| format!("Failed to replace cost storage at {}", path.display()))?;
+    Ok(count)
+}
+
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
     let storage_path = workspace_dir.join("state").join("costs.jsonl");
     let legacy_path = workspace_dir.join(".zeroclaw").join("costs.db");
@@ -228,6 +258,7 @@ fn build_session_model_stats(session_costs: &[CostRecord]) -> HashMap<String, Mo
 /// Persistent storage for cost records.
 struct CostStorage {
     path: PathBuf,
+    cipher: Option<Arc<MemoryCipher>>,
     daily_cost_usd: f64,
     monthly_cost_usd: f64,
     cached_day: NaiveDate,
@@ -237,7 +268,7 @@ struct CostStorage {
 
 impl CostStorage {
     /// Create or open cost storage.
-    fn new(path: &Path) -> Result<Self> {
+    fn new(path: &Path, cipher: Option<Arc<MemoryCipher>>) -> Result<Self> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create directory {}", parent.display()))?;
@@ -246,6 +277,7 @@ impl CostStorage {
         let now = Utc::now();
         let mut storage = Self {
             path: path.to_path_buf(),
+            cipher,
             daily_cost_usd: 0.0,
             monthly_cost_usd: 0.0,
             cached_day: now.date_naive(),
@@ -287,8 +319,19 @@ impl CostStorage {
             if trimmed.is_empty() {
                 continue;
             }
+            let line = match encryption::decrypt_field(self.cipher.as_deref(), trimmed) {
+                Ok(line) => line,
+                Err(error) => {
+                    tracing::warn!(
+                        "Skipping unreadable cost record at {}:{}: {error}",
+                        self.path.display(),
+                        line_number + 1
+                    );
+                    continue;
+                }
+            };
 
-            match serde_json::from_str::<CostRecord>(trimmed) {
+            match serde_json::from_str::<CostRecord>(&line) {
                 Ok(record) => on_record(record),
                 Err(error) => {
                     tracing::warn!(
@@ -349,7 +392,9 @@ impl CostStorage {
             .open(&self.path)
             .with_context(|| format!("Failed to open cost storage at {}", self.path.display()))?;
 
-        writeln!(file, "{}", serde_json::to_string(&record)?)
+        let line =
+            encryption::encrypt_field(self.cipher.as_deref(), &serde_json::to_string(&record)?)?;
+        writeln!(file, "{line}")
             .with_context(|| format!("Failed to write cost record to {}", self.path.display()))?;
         file.sync_all()
             .with_context(|| format!("Failed to sync cost storage at {}", self.path.display()))?;
@@ -53,6 +53,9 @@ store a brief note confirming the check was performed and skip the MEMORY.md upd
 /// Schedule: 3:00 AM daily (local time), configurable via `schedule_expr`.
 /// Job type: agent with `__consolidate` marker in the name.
 /// Session target: isolated (does not disturb main sessions).
+///
+/// With `[memory.consolidation] enabled`, the scheduler runs the native
+/// engine (`memory::consolidation`) for this job instead of the prompt.
 pub fn create_consolidation_job(config: &Config) -> Result<CronJob> {
     create_consolidation_job_with_schedule(config, DEFAULT_SCHEDULE_EXPR, None)
 }
@@ -16,7 +16,7 @@ pub use schedule::{
 #[allow(unused_imports)]
 pub use store::{
     add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    record_last_run, record_run, rekey_outputs, remove_job, reschedule_after_run, update_job,
 };
 pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
 
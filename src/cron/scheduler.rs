@@ -1,7 +1,9 @@
 use crate::channels::{
-    Channel, DiscordChannel, MattermostChannel, SendMessage, SlackChannel, TelegramChannel,
+    Channel, DiscordChannel, MattermostChannel, SendMessage, SlackChannel, TeamsChannel,
+    TelegramChannel,
 };
 use crate::config::Config;
+use crate::cron::consolidation::CONSOLIDATION_JOB_NAME;
 use crate::cron::{
     due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
     update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
@@ -160,6 +162,13 @@ async fn run_agent_job(
             "blocked by security policy: action budget exhausted".to_string(),
         );
     }
+    if job.name.as_deref() == Some(CONSOLIDATION_JOB_NAME) && config.memory.consolidation.enabled {
+        return match Box::pin(crate::memory::consolidation::run_scheduled(config)).await {
+            Ok(summary) => (true, summary),
+            Err(e) => (false, format!("memory consolidation failed: {e}")),
+        };
+    }
+
     let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
     let prompt = job.prompt.clone().unwrap_or_default();
     let prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
@@ -346,7 +355,8 @@ pub(crate) async fn deliver_announcement(
                 sl.bot_token.clone(),
                 sl.channel_id.clone(),
                 sl.allowed_users.clone(),
-            );
+            )
+            .with_workspace_dir(config.workspace_dir.clone());
             channel.send(&SendMessage::new(output, target)).await?;
         }
         "mattermost" => {
@@ -365,6 +375,18 @@ pub(crate) async fn deliver_announcement(
             );
             channel.send(&SendMessage::new(output, target)).await?;
         }
+        "teams" => {
+            let tm = config
+                .channels_config
+                .teams
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("teams channel not configured"))?;
+            // Proactive send: uses the conversation reference stored when
+            // the target conversation last messaged the bot.
+            let channel =
+                TeamsChannel::from_config(tm).with_workspace_dir(config.workspace_dir.clone());
+            channel.send(&SendMessage::new(output, target)).await?;
+        }
         other => anyhow::bail!("unsupported delivery channel: {other}"),
     }
 
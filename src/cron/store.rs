@@ -3,6 +3,7 @@ use crate::cron::{
     next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
     CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
+use crate::memory::encryption::{self, MemoryCipher};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::types::{FromSqlResult, ValueRef};
@@ -262,7 +263,7 @@ pub fn record_last_run(
     output: &str,
 ) -> Result<()> {
     let status = if success { "ok" } else { "error" };
-    let bounded_output = truncate_cron_output(output);
+    let bounded_output = seal_output(&truncate_cron_output(output))?;
     with_connection(config, |conn| {
         conn.execute(
             "UPDATE cron_jobs
@@ -284,7 +285,7 @@ pub fn reschedule_after_run(
     let now = Utc::now();
     let next_run = next_run_for_schedule(&job.schedule, now)?;
     let status = if success { "ok" } else { "error" };
-    let bounded_output = truncate_cron_output(output);
+    let bounded_output = seal_output(&truncate_cron_output(output))?;
 
     with_connection(config, |conn| {
         conn.execute(
@@ -313,7 +314,9 @@ pub fn record_run(
     output: Option<&str>,
     duration_ms: i64,
 ) -> Result<()> {
-    let bounded_output = output.map(truncate_cron_output);
+    let bounded_output = output
+        .map(|output| seal_output(&truncate_cron_output(output)))
+        .transpose()?;
     with_connection(config, |conn| {
         // Wrap INSERT + pruning DELETE in an explicit transaction so that
         // if the DELETE fails, the INSERT is rolled back and the run table
@@ -354,6 +357,52 @@ pub fn record_run(
     })
 }
 
+/// Encrypt run output when `[memory] encrypt_at_rest` is enabled.
+fn seal_output(output: &str) -> Result<String> {
+    encryption::encrypt_field(encryption::runtime_cipher()?.as_deref(), output)
+}
+
+/// Decrypt run output read from the cron DB.
+fn open_output(output: Option<String>) -> rusqlite::Result<Option<String>> {
+    match output {
+        Some(raw) if MemoryCipher::is_encrypted(&raw) => encryption::runtime_cipher()
+            .and_then(|cipher| encryption::decrypt_field(cipher.as_deref(), &raw))
+            .map(Some)
+            .map_err(sql_conversion_error),
+        other => Ok(other),
+    }
+}
+
+/// Rewrite stored run output under `cipher`'s active key, or as plaintext
+/// when it is decrypt-only or absent. Returns the number of values rewritten.
+pub fn rekey_outputs(config: &Config, cipher: Option<&MemoryCipher>) -> Result<usize> {
+    with_connection(config, |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut count = 0;
+        for (table, column) in [("cron_jobs", "last_output"), ("cron_runs", "output")] {
+            let rows: Vec<(i64, String)> = {
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL"
+                ))?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+            let mut update = tx.prepare(&format!(
+                "UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"
+            ))?;
+            for (rowid, stored) in &rows {
+                let plaintext = encryption::decrypt_field(cipher, stored)?;
+                let sealed = encryption::encrypt_field(cipher, &plaintext)?;
+                update.execute(params![sealed, rowid])?;
+            }
+            count += rows.len();
+        }
+        tx.commit()
+            .context("Failed to commit cron output rekey transaction")?;
+        Ok(count)
+    })
+}
+
 fn truncate_cron_output(output: &str) -> String {
     if output.len() <= MAX_CRON_OUTPUT_BYTES {
         return output.to_string();
@@ -393,7 +442,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
                 finished_at: parse_rfc3339(&row.get::<_, String>(3)?)
                     .map_err(sql_conversion_error)?,
                 status: row.get(4)?,
-                output: row.get(5)?,
+                output: open_output(row.get(5)?)?,
                 duration_ms: row.get(6)?,
             })
         })?;
@@ -449,7 +498,7 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
             None => None,
         },
         last_status: row.get(15)?,
-        last_output: row.get(16)?,
+        last_output: open_output(row.get(16)?)?,
     })
 }
 
@@ -824,6 +873,37 @@ mod tests {
         assert!(runs.is_empty());
     }
 
+    #[test]
+    fn rekey_outputs_encrypts_and_restores_run_output() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo secret").unwrap();
+        let start = Utc::now();
+        record_run(&config, &job.id, start, start, "ok", Some("secret"), 1).unwrap();
+        reschedule_after_run(&config, &job, true, "secret").unwrap();
+
+        let raw_outputs = || {
+            with_connection(&config, |conn| {
+                let run: String =
+                    conn.query_row("SELECT output FROM cron_runs", [], |row| row.get(0))?;
+                let last: String =
+                    conn.query_row("SELECT last_output FROM cron_jobs", [], |row| row.get(0))?;
+                Ok([run, last])
+            })
+            .unwrap()
+        };
+
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+        assert_eq!(rekey_outputs(&config, Some(&cipher)).unwrap(), 2);
+        assert!(raw_outputs()
+            .iter()
+            .all(|value| MemoryCipher::is_encrypted(value)));
+
+        let reader = MemoryCipher::open(tmp.path(), false).unwrap();
+        assert_eq!(rekey_outputs(&config, Some(&reader)).unwrap(), 2);
+        assert_eq!(raw_outputs(), ["secret".to_string(), "secret".to_string()]);
+    }
+
     #[test]
     fn record_run_truncates_large_output() {
         let tmp = TempDir::new().unwrap();
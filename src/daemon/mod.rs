@@ -7,6 +7,8 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
+/// How often the daemon expires overdue SOP approvals and step deadlines.
+const SOP_TIMEOUT_POLL_SECONDS: u64 = 5;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
@@ -49,7 +51,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
                 max_backoff,
                 move || {
                     let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    async move { Box::pin(crate::channels::start_channels(cfg)).await }
                 },
             ));
         } else {
@@ -87,6 +89,18 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if let Some(engine) = crate::sop::shared_engine(&config) {
+        handles.push(spawn_component_supervisor(
+            "sop",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let engine = std::sync::Arc::clone(&engine);
+                async move { run_sop_timeout_worker(engine).await }
+            },
+        ));
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
@@ -173,6 +187,23 @@ where
     })
 }
 
+/// Drive SOP timeouts: without this tick a step past its `timeout_secs` (or
+/// an approval past `approval_timeout_secs`) would stay active until an agent
+/// next touched the run.
+async fn run_sop_timeout_worker(
+    engine: std::sync::Arc<std::sync::Mutex<crate::sop::SopEngine>>,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(SOP_TIMEOUT_POLL_SECONDS));
+    loop {
+        interval.tick().await;
+        let actions = engine
+            .lock()
+            .map_err(|e| anyhow::anyhow!("SOP engine lock poisoned: {e}"))?
+            .check_timeouts();
+        crate::sop::dispatch::process_timeout_actions(&actions);
+    }
+}
+
 async fn run_heartbeat_worker(config: Config) -> Result<()> {
     let observer: std::sync::Arc<dyn crate::observability::Observer> =
         std::sync::Arc::from(crate::observability::create_observer(&config.observability));
@@ -629,27 +629,58 @@ fn provider_validation_error(name: &str) -> Option<String> {
 }
 
 fn embedding_provider_validation_error(name: &str) -> Option<String> {
+    const NAMED: &[&str] = &[
+        "none",
+        "openai",
+        "openrouter",
+        "azure",
+        "azure-openai",
+        "azure_openai",
+        "ollama",
+        "gemini",
+        "google",
+        "google-gemini",
+        "cohere",
+        "bedrock",
+        "aws-bedrock",
+        "voyage",
+        "voyageai",
+    ];
+
     let normalized = name.trim();
-    if normalized.eq_ignore_ascii_case("none") || normalized.eq_ignore_ascii_case("openai") {
+    if NAMED
+        .iter()
+        .any(|known| normalized.eq_ignore_ascii_case(known))
+    {
         return None;
     }
 
-    let Some(url) = normalized.strip_prefix("custom:") else {
-        return Some("supported values: none, openai, custom:<url>".into());
+    let Some((kind, url)) = ["custom", "azure", "ollama"].into_iter().find_map(|kind| {
+        normalized
+            .strip_prefix(kind)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(|url| (kind, url))
+    }) else {
+        return Some(
+            "supported values: none, openai, openrouter, azure, ollama, gemini, cohere, bedrock, voyage, custom:<url>, azure:<url>, ollama:<url>"
+                .into(),
+        );
     };
 
     let url = url.trim();
     if url.is_empty() {
-        return Some("custom provider requires a non-empty URL after 'custom:'".into());
+        return Some(format!(
+            "{kind} provider requires a non-empty URL after '{kind}:'"
+        ));
     }
 
     match reqwest::Url::parse(url) {
         Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
         Ok(parsed) => Some(format!(
-            "custom provider URL must use http/https, got '{}'",
+            "{kind} provider URL must use http/https, got '{}'",
             parsed.scheme()
         )),
-        Err(err) => Some(format!("invalid custom provider URL: {err}")),
+        Err(err) => Some(format!("invalid {kind} provider URL: {err}")),
     }
 }
 
@@ -1216,6 +1247,25 @@ mod tests {
         assert_eq!(route_item.unwrap().severity, Severity::Warn);
     }
 
+    #[test]
+    fn embedding_provider_validation_accepts_native_providers() {
+        for name in [
+            "ollama",
+            "ollama:http://gpu:11434",
+            "gemini",
+            "cohere",
+            "bedrock",
+            "voyage",
+        ] {
+            assert!(
+                embedding_provider_validation_error(name).is_none(),
+                "{name} should be accepted"
+            );
+        }
+        assert!(embedding_provider_validation_error("ollama:").is_some());
+        assert!(embedding_provider_validation_error("ollama:ftp://host").is_some());
+    }
+
     #[test]
     fn config_validation_warns_missing_embedding_hint_target() {
         let mut config = Config::default();
@@ -23,7 +23,7 @@ fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
 }
 
 /// Verify bearer token against PairingGuard. Returns error response if unauthorized.
-fn require_auth(
+pub(super) fn require_auth(
     state: &AppState,
     headers: &HeaderMap,
 ) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
@@ -50,6 +50,8 @@ fn require_auth(
 pub struct MemoryQuery {
     pub query: Option<String>,
     pub category: Option<String>,
+    /// Memory namespace; defaults to the global namespace.
+    pub namespace: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -57,6 +59,16 @@ pub struct MemoryStoreBody {
     pub key: String,
     pub content: String,
     pub category: Option<String>,
+    pub namespace: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MemoryNamespaceQuery {
+    pub namespace: Option<String>,
+}
+
+fn memory_namespace(namespace: Option<&str>) -> &str {
+    namespace.unwrap_or(crate::memory::DEFAULT_NAMESPACE)
 }
 
 #[derive(Deserialize)]
@@ -66,6 +78,17 @@ pub struct CronAddBody {
     pub command: String,
 }
 
+#[derive(Deserialize)]
+pub struct SopRunsQuery {
+    pub sop: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SopRetryQuery {
+    pub step: Option<u32>,
+}
+
 // ── Handlers ────────────────────────────────────────────────────
 
 /// GET /api/status — system status overview
@@ -382,9 +405,10 @@ pub async fn handle_api_memory_list(
         return e.into_response();
     }
 
+    let namespaces = vec![memory_namespace(params.namespace.as_deref()).to_string()];
     if let Some(ref query) = params.query {
         // Search mode
-        match state.mem.recall(query, 50, None).await {
+        match state.mem.recall_in(&namespaces, query, 50, None, &[]).await {
             Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -398,10 +422,15 @@ pub async fn handle_api_memory_list(
             "core" => crate::memory::MemoryCategory::Core,
             "daily" => crate::memory::MemoryCategory::Daily,
             "conversation" => crate::memory::MemoryCategory::Conversation,
+            "knowledge" => crate::memory::MemoryCategory::Knowledge,
             other => crate::memory::MemoryCategory::Custom(other.to_string()),
         });
 
-        match state.mem.list(category.as_ref(), None).await {
+        match state
+            .mem
+            .list_in(&namespaces, category.as_ref(), None)
+            .await
+        {
             Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -429,13 +458,21 @@ pub async fn handle_api_memory_store(
             "core" => crate::memory::MemoryCategory::Core,
             "daily" => crate::memory::MemoryCategory::Daily,
             "conversation" => crate::memory::MemoryCategory::Conversation,
+            "knowledge" => crate::memory::MemoryCategory::Knowledge,
             other => crate::memory::MemoryCategory::Custom(other.to_string()),
         })
         .unwrap_or(crate::memory::MemoryCategory::Core);
 
     match state
         .mem
-        .store(&body.key, &body.content, category, None)
+        .store_in(
+            memory_namespace(body.namespace.as_deref()),
+            &body.key,
+            &body.content,
+            category,
+            None,
+            &crate::memory::MemoryMetadata::default(),
+        )
         .await
     {
         Ok(()) => Json(serde_json::json!({"status": "ok"})).into_response(),
@@ -447,17 +484,22 @@ pub async fn handle_api_memory_store(
     }
 }
 
-/// DELETE /api/memory/:key — delete a memory entry
+/// DELETE /api/memory/:key?namespace= — delete a memory entry
 pub async fn handle_api_memory_delete(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(key): Path<String>,
+    Query(params): Query<MemoryNamespaceQuery>,
 ) -> impl IntoResponse {
     if let Err(e) = require_auth(&state, &headers) {
         return e.into_response();
     }
 
-    match state.mem.forget(&key).await {
+    match state
+        .mem
+        .forget_in(memory_namespace(params.namespace.as_deref()), &key)
+        .await
+    {
         Ok(deleted) => {
             Json(serde_json::json!({"status": "ok", "deleted": deleted})).into_response()
         }
@@ -469,6 +511,158 @@ pub async fn handle_api_memory_delete(
     }
 }
 
+/// Resolve the shared SOP engine, or a 404 response when `[sop]` is disabled.
+fn sop_engine(
+    state: &AppState,
+) -> Result<
+    std::sync::Arc<std::sync::Mutex<crate::sop::SopEngine>>,
+    (StatusCode, Json<serde_json::Value>),
+> {
+    let config = state.config.lock().clone();
+    crate::sop::shared_engine(&config).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "SOP engine is disabled ([sop] enabled = false)"})),
+        )
+    })
+}
+
+/// Run a mutating engine operation on a known run and render its next action.
+fn sop_run_operation(
+    state: &AppState,
+    run_id: &str,
+    op: impl FnOnce(&mut crate::sop::SopEngine) -> anyhow::Result<Option<crate::sop::SopRunAction>>,
+) -> axum::response::Response {
+    let engine = match sop_engine(state) {
+        Ok(engine) => engine,
+        Err(e) => return e.into_response(),
+    };
+    let mut engine = engine
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if engine.get_run(run_id).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("SOP run not found: {run_id}")})),
+        )
+            .into_response();
+    }
+    match op(&mut engine) {
+        Ok(action) => Json(serde_json::json!({
+            "status": "ok",
+            "run": engine.get_run(run_id),
+            "next": action,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sop/runs — list persisted SOP runs
+pub async fn handle_api_sop_runs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SopRunsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    let engine = match sop_engine(&state) {
+        Ok(engine) => engine,
+        Err(e) => return e.into_response(),
+    };
+    let engine = engine
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    let sop = params.sop.as_deref();
+    let mut runs: Vec<&crate::sop::SopRun> = engine
+        .active_runs()
+        .values()
+        .filter(|r| sop.is_none_or(|name| r.sop_name == name))
+        .collect();
+    runs.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    runs.extend(engine.finished_runs(sop));
+    if let Some(status) = params.status.as_deref() {
+        runs.retain(|r| r.status.to_string() == status);
+    }
+
+    Json(serde_json::json!({"runs": runs})).into_response()
+}
+
+/// GET /api/sop/runs/{id} — one SOP run with step results
+pub async fn handle_api_sop_run_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    let engine = match sop_engine(&state) {
+        Ok(engine) => engine,
+        Err(e) => return e.into_response(),
+    };
+    let engine = engine
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match engine.get_run(&run_id) {
+        Some(run) => Json(serde_json::json!({"run": run})).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("SOP run not found: {run_id}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/sop/runs/{id}/resume — re-issue the current step of a running run
+pub async fn handle_api_sop_run_resume(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    sop_run_operation(&state, &run_id, |engine| {
+        engine.resume_run(&run_id).map(Some)
+    })
+}
+
+/// POST /api/sop/runs/{id}/retry?step=N — re-run a step
+pub async fn handle_api_sop_run_retry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+    Query(params): Query<SopRetryQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    sop_run_operation(&state, &run_id, |engine| {
+        engine.retry_step(&run_id, params.step).map(Some)
+    })
+}
+
+/// POST /api/sop/runs/{id}/cancel — cancel an active run
+pub async fn handle_api_sop_run_cancel(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    sop_run_operation(&state, &run_id, |engine| {
+        engine.cancel_run(&run_id).map(|()| None)
+    })
+}
+
 /// GET /api/cost — cost summary
 pub async fn handle_api_cost(
     State(state): State<AppState>,
@@ -478,9 +672,15 @@ pub async fn handle_api_cost(
         return e.into_response();
     }
 
+    let response_cache = response_cache_summary(state.response_cache.as_deref());
+
     if let Some(ref tracker) = state.cost_tracker {
         match tracker.get_summary() {
-            Ok(summary) => Json(serde_json::json!({"cost": summary})).into_response(),
+            Ok(summary) => Json(serde_json::json!({
+                "cost": summary,
+                "response_cache": response_cache,
+            }))
+            .into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({"error": format!("Cost summary failed: {e}")})),
@@ -496,12 +696,33 @@ pub async fn handle_api_cost(
                 "total_tokens": 0,
                 "request_count": 0,
                 "by_model": {},
-            }
+            },
+            "response_cache": response_cache,
         }))
         .into_response()
     }
 }
 
+/// Response cache section of `/api/cost`: entry count plus lookup counters
+/// since the gateway started.
+fn response_cache_summary(
+    cache: Option<&crate::memory::response_cache::ResponseCache>,
+) -> serde_json::Value {
+    let Some(cache) = cache else {
+        return serde_json::json!({"enabled": false});
+    };
+    let entries = cache.stats().map_or(0, |(entries, _, _)| entries);
+    let counters = cache.counters();
+    serde_json::json!({
+        "enabled": true,
+        "entries": entries,
+        "hits": counters.hits,
+        "semantic_hits": counters.semantic_hits,
+        "misses": counters.misses,
+        "tokens_saved": counters.tokens_saved,
+    })
+}
+
 /// GET /api/cli-tools — discovered CLI tools
 pub async fn handle_api_cli_tools(
     State(state): State<AppState>,
@@ -666,6 +887,15 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
         mask_optional_secret(&mut irc.nickserv_password);
         mask_optional_secret(&mut irc.sasl_password);
     }
+    if let Some(xmpp) = masked.channels_config.xmpp.as_mut() {
+        mask_required_secret(&mut xmpp.password);
+    }
+    if let Some(zulip) = masked.channels_config.zulip.as_mut() {
+        mask_required_secret(&mut zulip.api_key);
+    }
+    if let Some(teams) = masked.channels_config.teams.as_mut() {
+        mask_required_secret(&mut teams.app_password);
+    }
     if let Some(lark) = masked.channels_config.lark.as_mut() {
         mask_required_secret(&mut lark.app_secret);
         mask_optional_secret(&mut lark.encrypt_key);
@@ -826,6 +1056,24 @@ fn restore_masked_sensitive_fields(
         );
         restore_optional_secret(&mut incoming_ch.sasl_password, &current_ch.sasl_password);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.xmpp.as_mut(),
+        current.channels_config.xmpp.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.password, &current_ch.password);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.zulip.as_mut(),
+        current.channels_config.zulip.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.api_key, &current_ch.api_key);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.teams.as_mut(),
+        current.channels_config.teams.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.app_password, &current_ch.app_password);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.lark.as_mut(),
         current.channels_config.lark.as_ref(),
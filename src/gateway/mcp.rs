@@ -0,0 +1,57 @@
+//! Streamable HTTP endpoint for the MCP server (`POST /mcp`).
+//!
+//! Stateless: every POST carries one JSON-RPC message and requests are
+//! answered with a single JSON body. Server-initiated SSE streams are not
+//! offered, so `GET /mcp` returns 405 as the spec allows.
+
+use super::api::require_auth;
+use super::AppState;
+use crate::mcp::McpServer;
+use axum::{
+    body::Bytes,
+    extract::{Extension, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+use std::sync::Arc;
+
+/// The MCP server mounted at `/mcp`. Like every other non-CLI surface it
+/// hides `autonomy.non_cli_excluded_tools`.
+pub(super) fn build_server(
+    tools: Arc<Vec<Box<dyn crate::tools::Tool>>>,
+    config: &crate::config::Config,
+) -> McpServer {
+    McpServer::new(tools, config, "mcp-http")
+        .with_excluded_tools(&config.autonomy.non_cli_excluded_tools)
+}
+
+/// POST /mcp — handle one MCP JSON-RPC message.
+pub async fn handle_mcp_post(
+    State(state): State<AppState>,
+    Extension(server): Extension<Arc<McpServer>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Ok(raw) = std::str::from_utf8(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Request body must be UTF-8 JSON"})),
+        )
+            .into_response();
+    };
+
+    match server.handle_raw(raw).await {
+        Some(response) => Json(response).into_response(),
+        // Notifications and responses are acknowledged without a body.
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// GET /mcp — no server-initiated stream is offered.
+pub async fn handle_mcp_get() -> impl IntoResponse {
+    StatusCode::METHOD_NOT_ALLOWED
+}
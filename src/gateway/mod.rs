@@ -8,18 +8,20 @@
 //! - Header sanitization (handled by axum/hyper)
 
 pub mod api;
+mod mcp;
 mod openai_compat;
 pub mod sse;
 pub mod static_files;
 pub mod ws;
 
 use crate::channels::{
-    Channel, LinqChannel, NextcloudTalkChannel, QQChannel, SendMessage, WatiChannel,
+    Channel, LinqChannel, NextcloudTalkChannel, QQChannel, SendMessage, TeamsChannel, WatiChannel,
     WhatsAppChannel,
 };
 use crate::config::Config;
 use crate::cost::CostTracker;
-use crate::memory::{self, Memory, MemoryCategory};
+use crate::memory::response_cache::ResponseCache;
+use crate::memory::{self, Memory, MemoryCategory, MemoryScope};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
@@ -34,7 +36,7 @@ use axum::{
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json},
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
 use parking_lot::Mutex;
 use std::collections::HashMap;
@@ -76,10 +78,53 @@ fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> S
     format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
 }
 
+fn teams_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("teams_{}_{}", msg.sender, msg.id)
+}
+
 fn qq_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
     format!("qq_{}_{}", msg.sender, msg.id)
 }
 
+/// Memory scope for a webhook channel message (per-sender namespaces).
+fn channel_memory_scope(
+    state: &AppState,
+    msg: &crate::channels::traits::ChannelMessage,
+) -> Option<MemoryScope> {
+    let namespaces = state.config.lock().memory.namespaces.clone();
+    MemoryScope::for_sender(
+        state.mem.as_ref(),
+        &namespaces,
+        &msg.channel,
+        &msg.reply_target,
+        &msg.sender,
+    )
+}
+
+/// Auto-save an inbound channel message into its scope's conversation namespace.
+async fn autosave_channel_message(
+    state: &AppState,
+    scope: Option<&MemoryScope>,
+    key: &str,
+    content: &str,
+) {
+    let category = MemoryCategory::Conversation;
+    let namespace = scope.map_or(memory::DEFAULT_NAMESPACE, |scope| {
+        scope.write_namespace(&category)
+    });
+    let _ = state
+        .mem
+        .store_in(
+            namespace,
+            key,
+            content,
+            category,
+            None,
+            &memory::MemoryMetadata::default(),
+        )
+        .await;
+}
+
 fn hash_webhook_secret(value: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -310,6 +355,7 @@ pub struct AppState {
     pub wati: Option<Arc<WatiChannel>>,
     pub qq: Option<Arc<QQChannel>>,
     pub qq_webhook_enabled: bool,
+    pub teams: Option<Arc<TeamsChannel>>,
     /// Observability backend for metrics scraping
     pub observer: Arc<dyn crate::observability::Observer>,
     /// Registered tool specs (for web dashboard tools page)
@@ -322,6 +368,8 @@ pub struct AppState {
     pub max_tool_iterations: usize,
     /// Cost tracker (optional, for web dashboard cost page)
     pub cost_tracker: Option<Arc<CostTracker>>,
+    /// Provider response cache (optional, hit/miss counters for the cost page)
+    pub response_cache: Option<Arc<ResponseCache>>,
     /// SSE broadcast channel for real-time events
     pub event_tx: tokio::sync::broadcast::Sender<serde_json::Value>,
 }
@@ -352,7 +400,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
 
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider_with_options(
+    let provider = providers::create_resilient_provider_with_options(
         config.default_provider.as_deref().unwrap_or("openrouter"),
         config.api_key.as_deref(),
         config.api_url.as_deref(),
@@ -367,8 +415,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             custom_provider_api_mode: config.provider_api.map(|mode| mode.as_compatible_mode()),
             max_tokens_override: None,
             model_support_vision: config.model_support_vision,
+            azure_openai: config.provider.azure_openai.clone(),
         },
-    )?);
+    )?;
     let model = config
         .default_model
         .clone()
@@ -379,6 +428,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
+        &config.reliability,
     )?);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
@@ -396,7 +446,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         (None, None)
     };
 
-    let tools_registry_exec: Arc<Vec<Box<dyn Tool>>> = Arc::new(tools::all_tools_with_runtime(
+    let mut built_tools = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
@@ -410,7 +460,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
-    ));
+    );
+    built_tools.extend(tools::mcp_tools(&config.mcp, &security).await);
+    let tools_registry_exec: Arc<Vec<Box<dyn Tool>>> = Arc::new(built_tools);
     let tools_registry: Arc<Vec<ToolSpec>> =
         Arc::new(tools_registry_exec.iter().map(|t| t.spec()).collect());
     let max_tool_iterations = config.agent.max_tool_iterations;
@@ -448,12 +500,15 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .as_ref()
         .filter(|wa| wa.is_cloud_config())
         .map(|wa| {
-            Arc::new(WhatsAppChannel::new(
-                wa.access_token.clone().unwrap_or_default(),
-                wa.phone_number_id.clone().unwrap_or_default(),
-                wa.verify_token.clone().unwrap_or_default(),
-                wa.allowed_numbers.clone(),
-            ))
+            Arc::new(
+                WhatsAppChannel::new(
+                    wa.access_token.clone().unwrap_or_default(),
+                    wa.phone_number_id.clone().unwrap_or_default(),
+                    wa.verify_token.clone().unwrap_or_default(),
+                    wa.allowed_numbers.clone(),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            )
         });
 
     // WhatsApp app secret for webhook signature verification
@@ -538,6 +593,12 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             ))
         });
 
+    // Teams channel (if configured)
+    let teams_channel: Option<Arc<TeamsChannel>> =
+        config.channels_config.teams.as_ref().map(|tm| {
+            Arc::new(TeamsChannel::from_config(tm).with_workspace_dir(config.workspace_dir.clone()))
+        });
+
     // Nextcloud Talk webhook secret for signature verification
     // Priority: environment variable > config file
     let nextcloud_talk_webhook_secret: Option<Arc<str>> =
@@ -624,12 +685,18 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     if nextcloud_talk_channel.is_some() {
         println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
     }
+    if teams_channel.is_some() {
+        println!("  POST /teams     — Microsoft Teams (Bot Framework) activities");
+    }
     if qq_webhook_enabled {
         println!("  POST /qq        — QQ Bot webhook (validation + events)");
     }
     if config.gateway.node_control.enabled {
         println!("  POST /api/node-control — experimental node-control RPC scaffold");
     }
+    if config.mcp.serve.gateway_enabled {
+        println!("  POST /mcp       — MCP server (streamable HTTP, bearer token required)");
+    }
     println!("  POST /v1/chat/completions — OpenAI-compatible chat");
     println!("  GET  /v1/models — list available models");
     println!("  GET  /api/*     — REST API (bearer token required)");
@@ -664,6 +731,21 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             event_tx.clone(),
         ));
 
+    let response_cache =
+        memory::create_response_cache(&config.memory, &config.workspace_dir).map(Arc::new);
+    let provider: Arc<dyn Provider> = Arc::from(providers::caching::wrap_with_response_cache(
+        provider,
+        response_cache.as_ref(),
+        &config,
+        Arc::clone(&broadcast_observer),
+    ));
+
+    let mcp_server = config
+        .mcp
+        .serve
+        .gateway_enabled
+        .then(|| Arc::new(mcp::build_server(Arc::clone(&tools_registry_exec), &config)));
+
     let state = AppState {
         config: config_state,
         provider,
@@ -685,12 +767,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         wati: wati_channel,
         qq: qq_channel,
         qq_webhook_enabled,
+        teams: teams_channel,
         observer: broadcast_observer,
         tools_registry,
         tools_registry_exec,
         multimodal: multimodal_config,
         max_tool_iterations,
         cost_tracker,
+        response_cache,
         event_tx,
     };
 
@@ -711,6 +795,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             openai_compat::CHAT_COMPLETIONS_MAX_BODY_SIZE,
         ));
 
+    // MCP streamable HTTP endpoint, mounted only when `[mcp.serve] gateway_enabled`.
+    let mcp_routes = match mcp_server {
+        Some(server) => Router::new()
+            .route("/mcp", post(mcp::handle_mcp_post).get(mcp::handle_mcp_get))
+            .layer(Extension(server)),
+        None => Router::new(),
+    };
+
     // Build router with middleware
     let app = Router::new()
         // ── Existing routes ──
@@ -725,6 +817,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/wati", post(handle_wati_webhook))
         .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
         .route("/qq", post(handle_qq_webhook))
+        .route("/teams", post(handle_teams_activity))
         // ── OpenAI-compatible endpoints ──
         .route("/v1/models", get(openai_compat::handle_v1_models))
         .merge(openai_compat_routes)
@@ -743,6 +836,20 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/memory", get(api::handle_api_memory_list))
         .route("/api/memory", post(api::handle_api_memory_store))
         .route("/api/memory/{key}", delete(api::handle_api_memory_delete))
+        .route("/api/sop/runs", get(api::handle_api_sop_runs))
+        .route("/api/sop/runs/{id}", get(api::handle_api_sop_run_get))
+        .route(
+            "/api/sop/runs/{id}/resume",
+            post(api::handle_api_sop_run_resume),
+        )
+        .route(
+            "/api/sop/runs/{id}/retry",
+            post(api::handle_api_sop_run_retry),
+        )
+        .route(
+            "/api/sop/runs/{id}/cancel",
+            post(api::handle_api_sop_run_cancel),
+        )
         .route("/api/cost", get(api::handle_api_cost))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
@@ -753,6 +860,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/ws/chat", get(ws::handle_ws_chat))
         // ── Static assets (web dashboard) ──
         .route("/_app/{*path}", get(static_files::handle_static))
+        // ── MCP server ──
+        .merge(mcp_routes)
         // ── Config PUT with larger body limit ──
         .merge(config_put_router)
         .with_state(state)
@@ -953,9 +1062,17 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
 }
 
 /// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk).
-async fn run_gateway_chat_with_tools(state: &AppState, message: &str) -> anyhow::Result<String> {
+async fn run_gateway_chat_with_tools(
+    state: &AppState,
+    scope: Option<MemoryScope>,
+    message: &str,
+) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    Box::pin(memory::namespace::with_scope(
+        scope,
+        crate::agent::process_message(config, message),
+    ))
+    .await
 }
 
 fn sanitize_gateway_response(response: &str, tools: &[Box<dyn Tool>]) -> String {
@@ -1468,7 +1585,8 @@ async fn handle_whatsapp_message(
     };
 
     // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
+    let mut messages = wa.parse_webhook_payload(&payload);
+    wa.download_media(&mut messages).await;
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status updates)
@@ -1483,16 +1601,19 @@ async fn handle_whatsapp_message(
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        let scope = channel_memory_scope(&state, msg);
+
         // Auto-save to memory
         if state.auto_save {
             let key = whatsapp_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
         }
 
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        let workspace_dir = state.config.lock().workspace_dir.clone();
+        let user_content =
+            crate::channels::attachments::compose_inbound_content(&workspace_dir, msg).await;
+
+        match run_gateway_chat_with_tools(&state, scope, &user_content).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -1601,17 +1722,16 @@ async fn handle_linq_webhook(
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        let scope = channel_memory_scope(&state, msg);
+
         // Auto-save to memory
         if state.auto_save {
             let key = linq_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
         }
 
         // Call the LLM
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        match run_gateway_chat_with_tools(&state, scope, &msg.content).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -1695,17 +1815,16 @@ async fn handle_wati_webhook(State(state): State<AppState>, body: Bytes) -> impl
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        let scope = channel_memory_scope(&state, msg);
+
         // Auto-save to memory
         if state.auto_save {
             let key = wati_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
         }
 
         // Call the LLM
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        match run_gateway_chat_with_tools(&state, scope, &msg.content).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -1803,15 +1922,14 @@ async fn handle_nextcloud_talk_webhook(
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        let scope = channel_memory_scope(&state, msg);
+
         if state.auto_save {
             let key = nextcloud_talk_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
         }
 
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        match run_gateway_chat_with_tools(&state, scope, &msg.content).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -1837,6 +1955,92 @@ async fn handle_nextcloud_talk_webhook(
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
 }
 
+/// POST /teams — incoming Bot Framework activity (Microsoft Teams)
+async fn handle_teams_activity(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref teams) = state.teams else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Teams not configured"})),
+        );
+    };
+
+    let Ok(activity) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    // ── Security: Verify the Bot Framework JWT ──
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if let Err(e) = teams.verify_request(authorization, &activity).await {
+        tracing::warn!("Teams activity authentication failed: {e}");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid token"})),
+        );
+    }
+
+    if let Err(e) = teams.remember_conversation(&activity).await {
+        tracing::warn!("Failed to store Teams conversation reference: {e}");
+    }
+
+    let Some(msg) = teams.parse_activity(&activity) else {
+        // Acknowledge non-message activities (typing, conversationUpdate, ...).
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    };
+
+    tracing::info!(
+        "Teams message from {}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    // Prefer the channel runtime (approvals, history, runtime commands); fall
+    // back to answering here when the gateway runs on its own. Bot Framework
+    // expects an answer within 15 seconds, so the reply is sent out of band.
+    if let Err(msg) = crate::channels::teams::forward_to_runtime(msg).await {
+        let teams = Arc::clone(teams);
+        let state = state.clone();
+        tokio::spawn(async move {
+            let scope = channel_memory_scope(&state, &msg);
+
+            if state.auto_save {
+                let key = teams_memory_key(&msg);
+                autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
+            }
+
+            let _ = teams.start_typing(&msg.reply_target).await;
+            let result = run_gateway_chat_with_tools(&state, scope, &msg.content).await;
+            let _ = teams.stop_typing(&msg.reply_target).await;
+
+            let reply = match result {
+                Ok(response) => {
+                    sanitize_gateway_response(&response, state.tools_registry_exec.as_ref())
+                }
+                Err(e) => {
+                    tracing::error!("LLM error for Teams message: {e:#}");
+                    "Sorry, I couldn't process your message right now.".to_string()
+                }
+            };
+            if let Err(e) = teams
+                .send(&SendMessage::new(reply, &msg.reply_target).in_thread(msg.thread_ts.clone()))
+                .await
+            {
+                tracing::error!("Failed to send Teams reply: {e}");
+            }
+        });
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+}
+
 /// POST /qq — incoming QQ Bot webhook (validation + events)
 async fn handle_qq_webhook(
     State(state): State<AppState>,
@@ -1894,15 +2098,14 @@ async fn handle_qq_webhook(
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        let scope = channel_memory_scope(&state, msg);
+
         if state.auto_save {
             let key = qq_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            autosave_channel_message(&state, scope.as_ref(), &key, &msg.content).await;
         }
 
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
+        match run_gateway_chat_with_tools(&state, scope, &msg.content).await {
             Ok(response) => {
                 let safe_response =
                     sanitize_gateway_response(&response, state.tools_registry_exec.as_ref());
@@ -2026,12 +2229,14 @@ mod tests {
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2082,12 +2287,14 @@ mod tests {
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer,
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2124,12 +2331,14 @@ mod tests {
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2167,12 +2376,14 @@ mod tests {
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2384,6 +2595,7 @@ mod tests {
             channel: "whatsapp".into(),
             timestamp: 1,
             thread_ts: None,
+            attachments: Vec::new(),
         };
 
         let key = whatsapp_memory_key(&msg);
@@ -2400,6 +2612,7 @@ mod tests {
             channel: "qq".into(),
             timestamp: 1,
             thread_ts: Some("msg-123".into()),
+            attachments: Vec::new(),
         };
 
         let key = qq_memory_key(&msg);
@@ -2440,6 +2653,132 @@ mod tests {
         }
     }
 
+    /// Named stand-in tool that counts executions.
+    struct CountingTool {
+        name: &'static str,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Counting tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {"command": {"type": "string"}}})
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "ok".to_string(),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn mcp_endpoint_hides_non_cli_excluded_tools() {
+        let mut config = Config::default();
+        config.autonomy.level = crate::security::AutonomyLevel::Full;
+        assert!(config
+            .autonomy
+            .non_cli_excluded_tools
+            .contains(&"shell".to_string()));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(CountingTool {
+                name: "shell",
+                calls: Arc::clone(&calls),
+            }),
+            Box::new(CountingTool {
+                name: "memory_recall",
+                calls: Arc::clone(&calls),
+            }),
+        ];
+        let server = Arc::new(mcp::build_server(Arc::new(tools), &config));
+        let state = AppState {
+            config: Arc::new(Mutex::new(config)),
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            teams: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            response_cache: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let post = |body: serde_json::Value| {
+            let state = state.clone();
+            let server = Arc::clone(&server);
+            async move {
+                let response = mcp::handle_mcp_post(
+                    State(state),
+                    axum::extract::Extension(server),
+                    HeaderMap::new(),
+                    axum::body::Bytes::from(body.to_string()),
+                )
+                .await
+                .into_response();
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let listed = post(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/list"
+        }))
+        .await;
+        let names: Vec<&str> = listed["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["memory_recall"]);
+
+        let called = post(serde_json::json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": {"name": "shell", "arguments": {"command": "id"}}
+        }))
+        .await;
+        assert!(called["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Unknown tool: shell"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
     #[test]
     fn sanitize_gateway_response_removes_tool_call_tags() {
         let input = r#"Before
@@ -2636,12 +2975,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2703,12 +3044,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2752,12 +3095,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2806,12 +3151,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2865,12 +3212,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2946,12 +3295,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -2999,12 +3350,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -3057,12 +3410,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -3120,20 +3475,22 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
-        let response = handle_nextcloud_talk_webhook(
+        let response = Box::pin(handle_nextcloud_talk_webhook(
             State(state),
             HeaderMap::new(),
             Bytes::from_static(br#"{"type":"message"}"#),
-        )
+        ))
         .await
         .into_response();
 
@@ -3179,12 +3536,14 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
@@ -3198,9 +3557,73 @@ Reminder set successfully."#;
             HeaderValue::from_str(invalid_signature).unwrap(),
         );
 
-        let response = handle_nextcloud_talk_webhook(State(state), headers, Bytes::from(body))
-            .await
-            .into_response();
+        let response = Box::pin(handle_nextcloud_talk_webhook(
+            State(state),
+            headers,
+            Bytes::from(body),
+        ))
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn teams_activity_rejects_missing_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let teams_config: crate::config::schema::TeamsConfig = toml::from_str(
+            r#"
+app_id = "app-123"
+app_password = "secret"
+allowed_users = ["*"]
+"#,
+        )
+        .unwrap();
+        let channel = Arc::new(TeamsChannel::from_config(&teams_config));
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            teams: Some(channel),
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            response_cache: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let body = r#"{"type":"message","text":"hi","from":{"id":"29:alice"},"conversation":{"id":"a:1"},"serviceUrl":"https://smba.trafficmanager.net/amer/"}"#;
+        let response = Box::pin(handle_teams_activity(
+            State(state),
+            HeaderMap::new(),
+            Bytes::from(body),
+        ))
+        .await
+        .into_response();
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
@@ -3231,20 +3654,22 @@ Reminder set successfully."#;
             wati: None,
             qq: None,
             qq_webhook_enabled: false,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
-        let response = handle_qq_webhook(
+        let response = Box::pin(handle_qq_webhook(
             State(state),
             HeaderMap::new(),
             Bytes::from_static(br#"{"op":13,"d":{"plain_token":"p","event_ts":"1"}}"#),
-        )
+        ))
         .await
         .into_response();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
@@ -3282,25 +3707,27 @@ Reminder set successfully."#;
             wati: None,
             qq: Some(qq),
             qq_webhook_enabled: true,
+            teams: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             tools_registry_exec: Arc::new(Vec::new()),
             multimodal: crate::config::MultimodalConfig::default(),
             max_tool_iterations: 10,
             cost_tracker: None,
+            response_cache: None,
             event_tx: tokio::sync::broadcast::channel(16).0,
         };
 
         let mut headers = HeaderMap::new();
         headers.insert("X-Bot-Appid", HeaderValue::from_static("11111111"));
 
-        let response = handle_qq_webhook(
+        let response = Box::pin(handle_qq_webhook(
             State(state),
             headers,
             Bytes::from_static(
                 br#"{"op":13,"d":{"plain_token":"Arq0D5A61EgUu4OxUvOp","event_ts":"1725442341"}}"#,
             ),
-        )
+        ))
         .await
         .into_response();
         assert_eq!(response.status(), StatusCode::OK);
@@ -3,9 +3,18 @@
 //! These endpoints allow ZeroClaw to act as a drop-in replacement for the
 //! OpenAI API, enabling any OpenAI-compatible client (e.g., `openai` Python
 //! library, `curl`, Aura) to send chat requests through the gateway.
+//!
+//! Client-supplied `tools` are forwarded to the provider and any `tool_calls`
+//! come back to the client, which executes them and replies with `tool`-role
+//! messages. Models listed in `[gateway].agent_models` instead run ZeroClaw's
+//! own agent loop, executing registered tools server-side.
 
 use super::AppState;
-use crate::providers::traits::{ChatMessage, StreamOptions};
+use crate::approval::ApprovalManager;
+use crate::providers::traits::{
+    ChatMessage, ChatRequest, StreamOptions, TokenUsage, ToolCall, ToolChoice,
+};
+use crate::tools::ToolSpec;
 use axum::{
     body::Body,
     extract::{ConnectInfo, State},
@@ -39,12 +48,88 @@ pub struct ChatCompletionsRequest {
     /// Whether to stream the response as SSE events.
     #[serde(default)]
     pub stream: Option<bool>,
+    /// Functions the model may call. The client executes them.
+    #[serde(default)]
+    pub tools: Vec<ChatCompletionsTool>,
+    /// `"none"`, `"auto"`, `"required"` or `{"type": "function", "function": {"name": ...}}`.
+    #[serde(default)]
+    pub tool_choice: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ChatCompletionsMessage {
     pub role: String,
-    pub content: String,
+    /// Text, or an array of `text` / `image_url` parts. Null for assistant
+    /// messages that only carry tool calls.
+    #[serde(default)]
+    pub content: Option<ChatCompletionsContent>,
+    /// Tool calls made by a previous assistant turn.
+    #[serde(default)]
+    pub tool_calls: Vec<ChatCompletionsToolCall>,
+    /// For `tool` messages: the call this result answers.
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ChatCompletionsContent {
+    Text(String),
+    Parts(Vec<ChatCompletionsContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatCompletionsContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: ChatCompletionsImageUrl,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionsImageUrl {
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionsTool {
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: ChatCompletionsFunction,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionsFunction {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// A tool call, as sent in assistant messages and returned in responses.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatCompletionsToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: ChatCompletionsFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatCompletionsFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
 }
 
 #[derive(Debug, Serialize)]
@@ -67,7 +152,10 @@ pub struct ChatCompletionsChoice {
 #[derive(Debug, Serialize)]
 pub struct ChatCompletionsResponseMessage {
     pub role: &'static str,
-    pub content: String,
+    /// Null when the model only requested tool calls.
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatCompletionsToolCall>,
 }
 
 #[derive(Debug, Serialize)]
@@ -100,6 +188,25 @@ struct ChunkDelta {
     role: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<ChunkToolCall>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChunkToolCall {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    function: ChunkFunctionCall,
+}
+
+#[derive(Debug, Serialize)]
+struct ChunkFunctionCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    arguments: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -116,6 +223,202 @@ pub struct ModelObject {
     pub owned_by: String,
 }
 
+// ══════════════════════════════════════════════════════════════════════════════
+// REQUEST CONVERSION
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Owned form of the request's `tool_choice`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RequestToolChoice {
+    None,
+    Auto,
+    Required,
+    Function(String),
+}
+
+impl RequestToolChoice {
+    fn parse(value: &serde_json::Value) -> Result<Self, String> {
+        match value {
+            serde_json::Value::String(mode) => match mode.as_str() {
+                "none" => Ok(Self::None),
+                "auto" => Ok(Self::Auto),
+                "required" => Ok(Self::Required),
+                other => Err(format!("unsupported tool_choice '{other}'")),
+            },
+            serde_json::Value::Object(_) => value
+                .pointer("/function/name")
+                .and_then(serde_json::Value::as_str)
+                .filter(|name| !name.is_empty())
+                .map(|name| Self::Function(name.to_string()))
+                .ok_or_else(|| "tool_choice object must name a function".to_string()),
+            _ => Err("tool_choice must be a string or object".to_string()),
+        }
+    }
+
+    fn as_tool_choice(&self) -> ToolChoice<'_> {
+        match self {
+            Self::None => ToolChoice::None,
+            Self::Auto => ToolChoice::Auto,
+            Self::Required => ToolChoice::Required,
+            Self::Function(name) => ToolChoice::Tool(name),
+        }
+    }
+}
+
+/// A validated request, converted to provider types.
+struct CompletionInput {
+    messages: Vec<ChatMessage>,
+    tools: Vec<ToolSpec>,
+    tool_choice: Option<RequestToolChoice>,
+}
+
+impl CompletionInput {
+    fn from_request(request: &ChatCompletionsRequest) -> Result<Self, String> {
+        let mut tools = Vec::with_capacity(request.tools.len());
+        for tool in &request.tools {
+            if tool.kind != "function" {
+                return Err(format!("unsupported tool type '{}'", tool.kind));
+            }
+            if tool.function.name.trim().is_empty() {
+                return Err("tool function name must not be empty".to_string());
+            }
+            tools.push(ToolSpec {
+                name: tool.function.name.clone(),
+                description: tool.function.description.clone().unwrap_or_default(),
+                parameters: tool
+                    .function
+                    .parameters
+                    .clone()
+                    .unwrap_or_else(|| serde_json::json!({"type": "object", "properties": {}})),
+            });
+        }
+
+        let tool_choice = request
+            .tool_choice
+            .as_ref()
+            .map(RequestToolChoice::parse)
+            .transpose()?;
+        if let Some(RequestToolChoice::Function(name)) = &tool_choice {
+            if !tools.iter().any(|tool| &tool.name == name) {
+                return Err(format!("tool_choice names unknown tool '{name}'"));
+            }
+        }
+
+        Ok(Self {
+            messages: request.messages.iter().map(convert_message).collect(),
+            tools,
+            tool_choice,
+        })
+    }
+
+    fn chat_request(&self) -> ChatRequest<'_> {
+        ChatRequest {
+            messages: &self.messages,
+            tools: (!self.tools.is_empty()).then_some(self.tools.as_slice()),
+            tool_choice: self
+                .tool_choice
+                .as_ref()
+                .map(RequestToolChoice::as_tool_choice),
+            ..ChatRequest::default()
+        }
+    }
+}
+
+impl ChatCompletionsMessage {
+    /// Message text; image parts become `[IMAGE:<url>]` markers.
+    fn text(&self) -> String {
+        match &self.content {
+            None => String::new(),
+            Some(ChatCompletionsContent::Text(text)) => text.clone(),
+            Some(ChatCompletionsContent::Parts(parts)) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ChatCompletionsContentPart::Text { text } => Some(text.clone()),
+                    ChatCompletionsContentPart::ImageUrl { image_url } => {
+                        Some(format!("[IMAGE:{}]", image_url.url))
+                    }
+                    ChatCompletionsContentPart::Unsupported => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Convert a client message into the history encoding providers expect for
+/// native tool calls (JSON bodies on assistant tool-call and tool messages).
+fn convert_message(message: &ChatCompletionsMessage) -> ChatMessage {
+    let text = message.text();
+
+    if message.role == "assistant" && !message.tool_calls.is_empty() {
+        let calls: Vec<serde_json::Value> = message
+            .tool_calls
+            .iter()
+            .map(|call| {
+                serde_json::json!({
+                    "id": call.id,
+                    "name": call.function.name,
+                    "arguments": call.function.arguments,
+                })
+            })
+            .collect();
+        let content = if text.trim().is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::Value::String(text)
+        };
+        return ChatMessage::assistant(
+            serde_json::json!({ "content": content, "tool_calls": calls }).to_string(),
+        );
+    }
+
+    if message.role == "tool" {
+        if let Some(tool_call_id) = &message.tool_call_id {
+            return ChatMessage::tool(
+                serde_json::json!({ "tool_call_id": tool_call_id, "content": text }).to_string(),
+            );
+        }
+    }
+
+    ChatMessage {
+        role: message.role.clone(),
+        content: text,
+    }
+}
+
+fn response_tool_calls(tool_calls: Vec<ToolCall>) -> Vec<ChatCompletionsToolCall> {
+    tool_calls
+        .into_iter()
+        .map(|call| ChatCompletionsToolCall {
+            id: call.id,
+            kind: function_type(),
+            function: ChatCompletionsFunctionCall {
+                name: call.name,
+                arguments: call.arguments,
+            },
+        })
+        .collect()
+}
+
+fn finish_reason(has_tool_calls: bool) -> &'static str {
+    if has_tool_calls {
+        "tool_calls"
+    } else {
+        "stop"
+    }
+}
+
+fn invalid_request(message: &str, code: &str) -> axum::response::Response {
+    let err = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "code": code
+        }
+    });
+    (StatusCode::BAD_REQUEST, Json(err)).into_response()
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // HANDLERS
 // ══════════════════════════════════════════════════════════════════════════════
@@ -210,15 +513,24 @@ pub async fn handle_v1_chat_completions(
     let temperature = request.temperature.unwrap_or(state.temperature);
     let stream = request.stream.unwrap_or(false);
 
-    // Convert messages to provider format
-    let messages: Vec<ChatMessage> = request
-        .messages
+    let input = match CompletionInput::from_request(&request) {
+        Ok(input) => input,
+        Err(message) => return invalid_request(&message, "invalid_tools"),
+    };
+
+    let is_agent_model = state
+        .config
+        .lock()
+        .gateway
+        .agent_models
         .iter()
-        .map(|m| ChatMessage {
-            role: m.role.clone(),
-            content: m.content.clone(),
-        })
-        .collect();
+        .any(|agent_model| agent_model == &model);
+    if is_agent_model && !input.tools.is_empty() {
+        return invalid_request(
+            "Agent models execute ZeroClaw's own tools; client-supplied tools are not supported",
+            "tools_not_supported",
+        );
+    }
 
     let provider_label = state
         .config
@@ -233,37 +545,34 @@ pub async fn handle_v1_chat_completions(
         .record_event(&crate::observability::ObserverEvent::LlmRequest {
             provider: provider_label.clone(),
             model: model.clone(),
-            messages_count: messages.len(),
+            messages_count: input.messages.len(),
         });
 
-    if stream {
-        handle_streaming(
+    if is_agent_model {
+        handle_agent(
             state,
-            messages,
+            input.messages,
             model,
-            temperature,
-            provider_label,
-            started_at,
-        )
-        .into_response()
-    } else {
-        handle_non_streaming(
-            state,
-            messages,
-            model,
-            temperature,
+            stream,
             provider_label,
             started_at,
         )
         .await
         .into_response()
+    } else if stream {
+        handle_streaming(state, input, model, temperature, provider_label, started_at)
+            .into_response()
+    } else {
+        handle_non_streaming(state, input, model, temperature, provider_label, started_at)
+            .await
+            .into_response()
     }
 }
 
 /// Non-streaming chat completions.
 async fn handle_non_streaming(
     state: AppState,
-    messages: Vec<ChatMessage>,
+    input: CompletionInput,
     model: String,
     temperature: f64,
     provider_label: String,
@@ -271,37 +580,24 @@ async fn handle_non_streaming(
 ) -> impl IntoResponse {
     match state
         .provider
-        .chat_with_history(&messages, &model, temperature)
+        .chat(input.chat_request(), &model, temperature)
         .await
     {
-        Ok(response_text) => {
+        Ok(response) => {
             let duration = started_at.elapsed();
             record_success(&state, &provider_label, &model, duration);
 
-            #[allow(clippy::cast_possible_truncation)]
-            let completion_tokens = (response_text.len() / 4) as u32;
-            #[allow(clippy::cast_possible_truncation)]
-            let prompt_tokens = messages.iter().map(|m| m.content.len() / 4).sum::<usize>() as u32;
-
-            let response = ChatCompletionsResponse {
-                id: format!("chatcmpl-{}", Uuid::new_v4()),
-                object: "chat.completion",
-                created: unix_timestamp(),
-                model: model.clone(),
-                choices: vec![ChatCompletionsChoice {
-                    index: 0,
-                    message: ChatCompletionsResponseMessage {
-                        role: "assistant",
-                        content: response_text,
-                    },
-                    finish_reason: "stop",
-                }],
-                usage: ChatCompletionsUsage {
-                    prompt_tokens,
-                    completion_tokens,
-                    total_tokens: prompt_tokens + completion_tokens,
-                },
-            };
+            let usage = completion_usage(
+                response.usage.as_ref(),
+                &input.messages,
+                response.text.as_deref().unwrap_or_default(),
+            );
+            let response = completion_response(
+                model,
+                response.text,
+                response_tool_calls(response.tool_calls),
+                usage,
+            );
 
             (
                 StatusCode::OK,
@@ -315,14 +611,7 @@ async fn handle_non_streaming(
             record_failure(&state, &provider_label, &model, duration, &sanitized);
 
             tracing::error!("/v1/chat/completions provider error: {sanitized}");
-            let err = serde_json::json!({
-                "error": {
-                    "message": "LLM request failed",
-                    "type": "server_error",
-                    "code": "provider_error"
-                }
-            });
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
+            provider_error_response()
         }
     }
 }
@@ -330,7 +619,7 @@ async fn handle_non_streaming(
 /// Streaming chat completions via SSE.
 fn handle_streaming(
     state: AppState,
-    messages: Vec<ChatMessage>,
+    input: CompletionInput,
     model: String,
     temperature: f64,
     provider_label: String,
@@ -339,38 +628,30 @@ fn handle_streaming(
     let request_id = format!("chatcmpl-{}", Uuid::new_v4());
     let created = unix_timestamp();
 
-    if !state.provider.supports_streaming() {
-        // Provider doesn't support streaming — fall back to a single-chunk response
+    let can_stream = state.provider.supports_streaming()
+        && (input.tools.is_empty() || state.provider.supports_streaming_tool_calls());
+    if !can_stream {
+        // Provider can't stream this request — fall back to a single-chunk response
         let model_clone = model.clone();
         let id = request_id.clone();
 
         let stream = futures_util::stream::once(async move {
             match state
                 .provider
-                .chat_with_history(&messages, &model_clone, temperature)
+                .chat(input.chat_request(), &model_clone, temperature)
                 .await
             {
-                Ok(text) => {
+                Ok(response) => {
                     let duration = started_at.elapsed();
                     record_success(&state, &provider_label, &model_clone, duration);
 
-                    let chunk = ChatCompletionsChunk {
-                        id: id.clone(),
-                        object: "chat.completion.chunk",
+                    let output = single_chunk_sse(
+                        &id,
                         created,
-                        model: model_clone,
-                        choices: vec![ChunkChoice {
-                            index: 0,
-                            delta: ChunkDelta {
-                                role: Some("assistant"),
-                                content: Some(text),
-                            },
-                            finish_reason: Some("stop"),
-                        }],
-                    };
-                    let json = serde_json::to_string(&chunk).unwrap_or_else(|_| "{}".to_string());
-                    let mut output = format!("data: {json}\n\n");
-                    output.push_str("data: [DONE]\n\n");
+                        &model_clone,
+                        response.text,
+                        response.tool_calls,
+                    );
                     Ok::<_, std::io::Error>(axum::body::Bytes::from(output))
                 }
                 Err(e) => {
@@ -385,19 +666,12 @@ fn handle_streaming(
             }
         });
 
-        return axum::response::Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/event-stream")
-            .header(header::CACHE_CONTROL, "no-cache")
-            .header(header::CONNECTION, "keep-alive")
-            .body(Body::from_stream(stream))
-            .unwrap()
-            .into_response();
+        return sse_response(Body::from_stream(stream));
     }
 
     // Provider supports native streaming
-    let provider_stream = state.provider.stream_chat_with_history(
-        &messages,
+    let provider_stream = state.provider.stream_chat(
+        input.chat_request(),
         &model,
         temperature,
         StreamOptions::new(true),
@@ -408,6 +682,7 @@ fn handle_streaming(
     let provider_label_for_stream = provider_label.clone();
     let mut first_chunk = true;
     let mut errored = false;
+    let mut saw_tool_calls = false;
 
     let sse_stream = provider_stream.map(move |result| match result {
         Ok(chunk) if chunk.is_final => {
@@ -420,7 +695,25 @@ fn handle_streaming(
                     duration,
                 );
             }
-            Ok::<_, std::io::Error>(axum::body::Bytes::from("data: [DONE]\n\n"))
+            let finish_chunk = ChatCompletionsChunk {
+                id: request_id.clone(),
+                object: "chat.completion.chunk",
+                created,
+                model: model_for_stream.clone(),
+                choices: vec![ChunkChoice {
+                    index: 0,
+                    delta: ChunkDelta {
+                        role: None,
+                        content: None,
+                        tool_calls: Vec::new(),
+                    },
+                    finish_reason: Some(finish_reason(saw_tool_calls)),
+                }],
+            };
+            let json = serde_json::to_string(&finish_chunk).unwrap_or_else(|_| "{}".to_string());
+            Ok::<_, std::io::Error>(axum::body::Bytes::from(format!(
+                "data: {json}\n\ndata: [DONE]\n\n"
+            )))
         }
         Ok(chunk) => {
             let role = if first_chunk {
@@ -430,6 +723,21 @@ fn handle_streaming(
                 None
             };
 
+            let tool_calls: Vec<ChunkToolCall> = chunk
+                .tool_call_deltas
+                .into_iter()
+                .map(|delta| ChunkToolCall {
+                    index: delta.index,
+                    kind: delta.id.is_some().then_some("function"),
+                    id: delta.id,
+                    function: ChunkFunctionCall {
+                        name: delta.name,
+                        arguments: delta.arguments,
+                    },
+                })
+                .collect();
+            saw_tool_calls |= !tool_calls.is_empty();
+
             let sse_chunk = ChatCompletionsChunk {
                 id: request_id.clone(),
                 object: "chat.completion.chunk",
@@ -444,6 +752,7 @@ fn handle_streaming(
                         } else {
                             Some(chunk.delta)
                         },
+                        tool_calls,
                     },
                     finish_reason: None,
                 }],
@@ -467,14 +776,99 @@ fn handle_streaming(
         }
     });
 
-    axum::response::Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "text/event-stream")
-        .header(header::CACHE_CONTROL, "no-cache")
-        .header(header::CONNECTION, "keep-alive")
-        .body(Body::from_stream(sse_stream))
-        .unwrap()
-        .into_response()
+    sse_response(Body::from_stream(sse_stream))
+}
+
+/// Agent-model completions: run the agent loop with ZeroClaw's registered
+/// tools and return only the final answer. Streaming requests receive it as
+/// a single chunk.
+async fn handle_agent(
+    state: AppState,
+    messages: Vec<ChatMessage>,
+    model: String,
+    stream: bool,
+    provider_label: String,
+    started_at: Instant,
+) -> axum::response::Response {
+    match run_agent_completion(&state, messages.clone(), &provider_label).await {
+        Ok(text) => {
+            let duration = started_at.elapsed();
+            record_success(&state, &provider_label, &model, duration);
+
+            if stream {
+                let id = format!("chatcmpl-{}", Uuid::new_v4());
+                let output = single_chunk_sse(&id, unix_timestamp(), &model, Some(text), vec![]);
+                return sse_response(Body::from(output));
+            }
+
+            let usage = completion_usage(None, &messages, &text);
+            let response = completion_response(model, Some(text), Vec::new(), usage);
+            (
+                StatusCode::OK,
+                Json(serde_json::to_value(response).unwrap()),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let duration = started_at.elapsed();
+            let sanitized = crate::providers::sanitize_api_error(&e.to_string());
+            record_failure(&state, &provider_label, &model, duration, &sanitized);
+
+            tracing::error!("/v1/chat/completions agent error: {sanitized}");
+            provider_error_response()
+        }
+    }
+}
+
+async fn run_agent_completion(
+    state: &AppState,
+    mut history: Vec<ChatMessage>,
+    provider_label: &str,
+) -> anyhow::Result<String> {
+    let (approval_manager, excluded_tools) = {
+        let config_guard = state.config.lock();
+        if !history.iter().any(|m| m.role == "system") {
+            let system_prompt = crate::channels::build_system_prompt(
+                &config_guard.workspace_dir,
+                &state.model,
+                &[],
+                &[],
+                Some(&config_guard.identity),
+                None,
+            );
+            history.insert(0, ChatMessage::system(system_prompt));
+        }
+        (
+            ApprovalManager::from_config(&config_guard.autonomy)
+                .with_workspace_dir(&config_guard.workspace_dir),
+            config_guard.autonomy.non_cli_excluded_tools.clone(),
+        )
+    };
+
+    let response = crate::agent::loop_::run_tool_call_loop(
+        state.provider.as_ref(),
+        &mut history,
+        state.tools_registry_exec.as_ref(),
+        state.observer.as_ref(),
+        provider_label,
+        &state.model,
+        state.temperature,
+        true, // silent - no console output
+        Some(&approval_manager),
+        "gateway",
+        &state.multimodal,
+        state.max_tool_iterations,
+        None, // cancellation token
+        None, // delta streaming
+        None, // hooks
+        &excluded_tools,
+    )
+    .await?;
+
+    Ok(super::sanitize_gateway_response(
+        &response,
+        state.tools_registry_exec.as_ref(),
+    ))
 }
 
 /// GET /v1/models — List available models.
@@ -501,14 +895,19 @@ pub async fn handle_v1_models(
         }
     }
 
+    let created = unix_timestamp();
+    let agent_models = state.config.lock().gateway.agent_models.clone();
     let response = ModelsResponse {
         object: "list",
-        data: vec![ModelObject {
-            id: state.model.clone(),
-            object: "model",
-            created: unix_timestamp(),
-            owned_by: "zeroclaw".to_string(),
-        }],
+        data: std::iter::once(state.model.clone())
+            .chain(agent_models)
+            .map(|id| ModelObject {
+                id,
+                object: "model",
+                created,
+                owned_by: "zeroclaw".to_string(),
+            })
+            .collect(),
     };
 
     (
@@ -528,6 +927,120 @@ fn unix_timestamp() -> u64 {
         .as_secs()
 }
 
+fn completion_response(
+    model: String,
+    text: Option<String>,
+    tool_calls: Vec<ChatCompletionsToolCall>,
+    usage: ChatCompletionsUsage,
+) -> ChatCompletionsResponse {
+    let finish = finish_reason(!tool_calls.is_empty());
+    ChatCompletionsResponse {
+        id: format!("chatcmpl-{}", Uuid::new_v4()),
+        object: "chat.completion",
+        created: unix_timestamp(),
+        model,
+        choices: vec![ChatCompletionsChoice {
+            index: 0,
+            message: ChatCompletionsResponseMessage {
+                role: "assistant",
+                content: if tool_calls.is_empty() {
+                    Some(text.unwrap_or_default())
+                } else {
+                    text
+                },
+                tool_calls,
+            },
+            finish_reason: finish,
+        }],
+        usage,
+    }
+}
+
+/// Provider-reported usage, falling back to a ~4 chars/token estimate.
+fn completion_usage(
+    usage: Option<&TokenUsage>,
+    messages: &[ChatMessage],
+    response_text: &str,
+) -> ChatCompletionsUsage {
+    #[allow(clippy::cast_possible_truncation)]
+    let prompt_tokens = usage.and_then(|u| u.input_tokens).map_or_else(
+        || messages.iter().map(|m| m.content.len() / 4).sum::<usize>() as u32,
+        |tokens| tokens as u32,
+    );
+    #[allow(clippy::cast_possible_truncation)]
+    let completion_tokens = usage
+        .and_then(|u| u.output_tokens)
+        .map_or((response_text.len() / 4) as u32, |tokens| tokens as u32);
+
+    ChatCompletionsUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    }
+}
+
+/// A whole response as one SSE chunk followed by `[DONE]`.
+fn single_chunk_sse(
+    id: &str,
+    created: u64,
+    model: &str,
+    text: Option<String>,
+    tool_calls: Vec<ToolCall>,
+) -> String {
+    let has_tool_calls = !tool_calls.is_empty();
+    let chunk = ChatCompletionsChunk {
+        id: id.to_string(),
+        object: "chat.completion.chunk",
+        created,
+        model: model.to_string(),
+        choices: vec![ChunkChoice {
+            index: 0,
+            delta: ChunkDelta {
+                role: Some("assistant"),
+                content: text.filter(|text| !text.is_empty()),
+                tool_calls: tool_calls
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, call)| ChunkToolCall {
+                        index,
+                        id: Some(call.id),
+                        kind: Some("function"),
+                        function: ChunkFunctionCall {
+                            name: Some(call.name),
+                            arguments: call.arguments,
+                        },
+                    })
+                    .collect(),
+            },
+            finish_reason: Some(finish_reason(has_tool_calls)),
+        }],
+    };
+    let json = serde_json::to_string(&chunk).unwrap_or_else(|_| "{}".to_string());
+    format!("data: {json}\n\ndata: [DONE]\n\n")
+}
+
+fn sse_response(body: Body) -> axum::response::Response {
+    axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::CONNECTION, "keep-alive")
+        .body(body)
+        .unwrap()
+        .into_response()
+}
+
+fn provider_error_response() -> axum::response::Response {
+    let err = serde_json::json!({
+        "error": {
+            "message": "LLM request failed",
+            "type": "server_error",
+            "code": "provider_error"
+        }
+    });
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
+}
+
 fn record_success(
     state: &AppState,
     provider_label: &str,
@@ -596,7 +1109,8 @@ mod tests {
         assert!(req.stream.is_none());
         assert_eq!(req.messages.len(), 1);
         assert_eq!(req.messages[0].role, "user");
-        assert_eq!(req.messages[0].content, "Hello");
+        assert_eq!(req.messages[0].text(), "Hello");
+        assert!(req.tools.is_empty());
     }
 
     #[test]
@@ -628,7 +1142,8 @@ mod tests {
                 index: 0,
                 message: ChatCompletionsResponseMessage {
                     role: "assistant",
-                    content: "Hello!".to_string(),
+                    content: Some("Hello!".to_string()),
+                    tool_calls: Vec::new(),
                 },
                 finish_reason: "stop",
             }],
@@ -674,6 +1189,7 @@ mod tests {
                 delta: ChunkDelta {
                     role: Some("assistant"),
                     content: Some("Hello".to_string()),
+                    tool_calls: Vec::new(),
                 },
                 finish_reason: None,
             }],
@@ -696,11 +1212,13 @@ mod tests {
                 delta: ChunkDelta {
                     role: None,
                     content: None,
+                    tool_calls: Vec::new(),
                 },
                 finish_reason: None,
             }],
         };
         let json = serde_json::to_string(&chunk).unwrap();
+        assert!(!json.contains("tool_calls"));
         assert!(!json.contains("role"));
         assert!(!json.contains("content"));
     }
@@ -717,4 +1235,444 @@ mod tests {
     fn body_size_limit_is_512kb() {
         assert_eq!(CHAT_COMPLETIONS_MAX_BODY_SIZE, 524_288);
     }
+
+    #[test]
+    fn tool_round_trip_messages_convert_to_native_history() {
+        let json = r#"{
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Weather?"},
+                    {"type": "image_url", "image_url": {"url": "https://x/map.png"}}
+                ]},
+                {"role": "assistant", "content": null, "tool_calls": [
+                    {"id": "call_1", "type": "function",
+                     "function": {"name": "get_weather", "arguments": "{\"city\":\"Oslo\"}"}}
+                ]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "4C and rain"}
+            ],
+            "tools": [{"type": "function", "function": {
+                "name": "get_weather",
+                "description": "Current weather",
+                "parameters": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }}],
+            "tool_choice": {"type": "function", "function": {"name": "get_weather"}}
+        }"#;
+        let req: ChatCompletionsRequest = serde_json::from_str(json).unwrap();
+        let input = CompletionInput::from_request(&req).unwrap();
+
+        assert_eq!(
+            input.messages[0].content,
+            "Weather?\n[IMAGE:https://x/map.png]"
+        );
+        let assistant: serde_json::Value =
+            serde_json::from_str(&input.messages[1].content).unwrap();
+        assert!(assistant["content"].is_null());
+        assert_eq!(assistant["tool_calls"][0]["id"], "call_1");
+        assert_eq!(assistant["tool_calls"][0]["name"], "get_weather");
+        let tool: serde_json::Value = serde_json::from_str(&input.messages[2].content).unwrap();
+        assert_eq!(input.messages[2].role, "tool");
+        assert_eq!(tool["tool_call_id"], "call_1");
+        assert_eq!(tool["content"], "4C and rain");
+
+        assert_eq!(input.tools[0].name, "get_weather");
+        let request = input.chat_request();
+        assert_eq!(request.tools.map(<[ToolSpec]>::len), Some(1));
+        assert_eq!(request.tool_choice, Some(ToolChoice::Tool("get_weather")));
+    }
+
+    #[test]
+    fn invalid_tool_choice_is_rejected() {
+        let req: ChatCompletionsRequest = serde_json::from_str(
+            r#"{"messages": [{"role": "user", "content": "hi"}], "tool_choice": "sometimes"}"#,
+        )
+        .unwrap();
+        assert!(CompletionInput::from_request(&req).is_err());
+
+        let req: ChatCompletionsRequest = serde_json::from_str(
+            r#"{"messages": [{"role": "user", "content": "hi"}],
+                "tool_choice": {"type": "function", "function": {"name": "missing"}}}"#,
+        )
+        .unwrap();
+        let err = CompletionInput::from_request(&req).err().unwrap();
+        assert!(err.contains("unknown tool"));
+    }
+
+    // ── Handler round trips ─────────────────────────────────────
+
+    use crate::config::Config;
+    use crate::gateway::{GatewayRateLimiter, IdempotencyStore};
+    use crate::providers::traits::{
+        ChatResponse, Provider, ProviderCapabilities, StreamChunk, StreamResult, ToolCallDelta,
+    };
+    use crate::security::pairing::PairingGuard;
+    use async_trait::async_trait;
+    use futures_util::stream::BoxStream;
+    use http_body_util::BodyExt;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Calls `get_weather` when offered tools, otherwise answers in text.
+    #[derive(Default)]
+    struct ToolCallingProvider {
+        seen_tools: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for ToolCallingProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                vision: false,
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("plain answer".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            let tools = request.tools.unwrap_or_default();
+            self.seen_tools
+                .lock()
+                .extend(tools.iter().map(|tool| tool.name.clone()));
+            if tools.is_empty() {
+                return Ok(ChatResponse {
+                    text: Some("plain answer".into()),
+                    tool_calls: Vec::new(),
+                    usage: None,
+                    reasoning_content: None,
+                });
+            }
+            Ok(ChatResponse {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "get_weather".into(),
+                    arguments: r#"{"city":"Oslo"}"#.into(),
+                }],
+                usage: Some(TokenUsage {
+                    input_tokens: Some(42),
+                    output_tokens: Some(7),
+                }),
+                reasoning_content: None,
+            })
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn supports_streaming_tool_calls(&self) -> bool {
+            true
+        }
+
+        fn stream_chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> BoxStream<'static, StreamResult<StreamChunk>> {
+            futures_util::stream::iter(vec![
+                Ok(StreamChunk::tool_call(ToolCallDelta {
+                    index: 0,
+                    id: Some("call_1".into()),
+                    name: Some("get_weather".into()),
+                    arguments: String::new(),
+                })),
+                Ok(StreamChunk::tool_call(ToolCallDelta {
+                    index: 0,
+                    id: None,
+                    name: None,
+                    arguments: r#"{"city":"Oslo"}"#.into(),
+                })),
+                Ok(StreamChunk::final_chunk()),
+            ])
+            .boxed()
+        }
+    }
+
+    fn test_state(provider: Arc<dyn Provider>, config: Config) -> AppState {
+        AppState {
+            config: Arc::new(Mutex::new(config)),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(crate::memory::NoneMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            wati: None,
+            qq: None,
+            qq_webhook_enabled: false,
+            teams: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            tools_registry_exec: Arc::new(Vec::new()),
+            multimodal: crate::config::MultimodalConfig::default(),
+            max_tool_iterations: 10,
+            cost_tracker: None,
+            response_cache: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        }
+    }
+
+    async fn post_completion(state: AppState, body: serde_json::Value) -> (StatusCode, String) {
+        let response = handle_v1_chat_completions(
+            State(state),
+            ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 30_300))),
+            HeaderMap::new(),
+            axum::body::Bytes::from(body.to_string()),
+        )
+        .await
+        .into_response();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    fn weather_tools() -> serde_json::Value {
+        serde_json::json!([{"type": "function", "function": {
+            "name": "get_weather",
+            "parameters": {"type": "object", "properties": {"city": {"type": "string"}}}
+        }}])
+    }
+
+    #[tokio::test]
+    async fn non_streaming_returns_tool_calls() {
+        let provider = Arc::new(ToolCallingProvider::default());
+        let state = test_state(provider.clone(), Config::default());
+        let (status, body) = post_completion(
+            state,
+            serde_json::json!({
+                "messages": [{"role": "user", "content": "Weather in Oslo?"}],
+                "tools": weather_tools(),
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let choice = &json["choices"][0];
+        assert_eq!(choice["finish_reason"], "tool_calls");
+        assert!(choice["message"]["content"].is_null());
+        let call = &choice["message"]["tool_calls"][0];
+        assert_eq!(call["id"], "call_1");
+        assert_eq!(call["type"], "function");
+        assert_eq!(call["function"]["name"], "get_weather");
+        assert_eq!(call["function"]["arguments"], r#"{"city":"Oslo"}"#);
+        assert_eq!(json["usage"]["prompt_tokens"], 42);
+        assert_eq!(*provider.seen_tools.lock(), vec!["get_weather"]);
+    }
+
+    #[tokio::test]
+    async fn streaming_emits_tool_call_deltas_and_finish_reason() {
+        let state = test_state(Arc::new(ToolCallingProvider::default()), Config::default());
+        let (status, body) = post_completion(
+            state,
+            serde_json::json!({
+                "messages": [{"role": "user", "content": "Weather in Oslo?"}],
+                "tools": weather_tools(),
+                "stream": true,
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let events: Vec<serde_json::Value> = body
+            .split("\n\n")
+            .filter_map(|event| event.strip_prefix("data: "))
+            .filter(|data| *data != "[DONE]")
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+        let first = &events[0]["choices"][0]["delta"];
+        assert_eq!(first["role"], "assistant");
+        assert_eq!(first["tool_calls"][0]["id"], "call_1");
+        assert_eq!(first["tool_calls"][0]["function"]["name"], "get_weather");
+        assert_eq!(
+            events[1]["choices"][0]["delta"]["tool_calls"][0]["function"]["arguments"],
+            r#"{"city":"Oslo"}"#
+        );
+        assert_eq!(events[2]["choices"][0]["finish_reason"], "tool_calls");
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[tokio::test]
+    async fn agent_model_runs_server_side_loop() {
+        let mut config = Config::default();
+        config.gateway.agent_models = vec!["zeroclaw-agent".into()];
+        let provider = Arc::new(ToolCallingProvider::default());
+        let state = test_state(provider.clone(), config);
+
+        let (status, body) = post_completion(
+            state.clone(),
+            serde_json::json!({
+                "model": "zeroclaw-agent",
+                "messages": [{"role": "user", "content": "hello"}],
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["model"], "zeroclaw-agent");
+        assert_eq!(json["choices"][0]["message"]["content"], "plain answer");
+        assert_eq!(json["choices"][0]["finish_reason"], "stop");
+
+        let (status, body) = post_completion(
+            state,
+            serde_json::json!({
+                "model": "zeroclaw-agent",
+                "messages": [{"role": "user", "content": "hello"}],
+                "tools": weather_tools(),
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("tools_not_supported"));
+    }
+
+    /// Asks for `shell` until a tool result comes back, then echoes it.
+    #[derive(Default)]
+    struct ShellCallingProvider {
+        seen_tools: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for ShellCallingProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                vision: false,
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("unused".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen_tools.lock().extend(
+                request
+                    .tools
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|tool| tool.name.clone()),
+            );
+            if let Some(result) = request.messages.iter().find(|m| m.role == "tool") {
+                return Ok(ChatResponse {
+                    text: Some(result.content.clone()),
+                    tool_calls: Vec::new(),
+                    usage: None,
+                    reasoning_content: None,
+                });
+            }
+            Ok(ChatResponse {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"id"}"#.into(),
+                }],
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    /// Stand-in `shell` tool that records whether it ran.
+    #[derive(Default)]
+    struct RecordingShell {
+        ran: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl crate::tools::Tool for RecordingShell {
+        fn name(&self) -> &str {
+            "shell"
+        }
+
+        fn description(&self) -> &str {
+            "Run a shell command"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {"command": {"type": "string"}}})
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            self.ran.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "uid=0(root)".into(),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn agent_model_honors_non_cli_excluded_tools() {
+        let mut config = Config::default();
+        config.gateway.agent_models = vec!["zeroclaw-agent".into()];
+        assert!(config
+            .autonomy
+            .non_cli_excluded_tools
+            .contains(&"shell".to_string()));
+        let provider = Arc::new(ShellCallingProvider::default());
+        let shell = RecordingShell::default();
+        let ran = shell.ran.clone();
+        let mut state = test_state(provider.clone(), config);
+        state.tools_registry_exec = Arc::new(vec![Box::new(shell)]);
+
+        let (status, body) = post_completion(
+            state,
+            serde_json::json!({
+                "model": "zeroclaw-agent",
+                "messages": [{"role": "user", "content": "who am i?"}],
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!provider.seen_tools.lock().contains(&"shell".to_string()));
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let content = json["choices"][0]["message"]["content"].as_str().unwrap();
+        assert!(content.contains("not available"), "{content}");
+    }
 }
@@ -77,6 +77,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
     let approval_manager = {
         let config_guard = state.config.lock();
         ApprovalManager::from_config(&config_guard.autonomy)
+            .with_workspace_dir(&config_guard.workspace_dir)
     };
 
     while let Some(msg) = socket.recv().await {
@@ -57,6 +57,7 @@ pub(crate) mod heartbeat;
 pub mod hooks;
 pub(crate) mod identity;
 pub(crate) mod integrations;
+pub mod mcp;
 pub mod memory;
 pub(crate) mod migration;
 pub(crate) mod multimodal;
@@ -69,6 +70,7 @@ pub mod runtime;
 pub(crate) mod security;
 pub(crate) mod service;
 pub(crate) mod skills;
+pub mod sop;
 pub mod tools;
 pub(crate) mod tunnel;
 pub mod update;
@@ -299,7 +301,7 @@ Examples:
 pub enum MemoryCommands {
     /// List memory entries with optional filters
     List {
-        /// Filter by category (core, daily, conversation, or custom name)
+        /// Filter by category (core, daily, conversation, knowledge, or custom name)
         #[arg(long)]
         category: Option<String>,
         /// Filter by session ID
@@ -331,6 +333,147 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Ingest documents (markdown, text, HTML, PDF) into the knowledge category
+    Ingest {
+        /// File or directory to ingest
+        path: std::path::PathBuf,
+        /// Re-ingest every file, even if its content is unchanged
+        #[arg(long)]
+        force: bool,
+        /// Max tokens per chunk (defaults to [memory] chunk_max_tokens)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+    /// Export memory entries as versioned JSONL (all categories and sessions)
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Include stored embedding vectors
+        #[arg(long)]
+        embeddings: bool,
+        /// Read from this backend instead of the configured one (sqlite, lucid, markdown, postgres, qdrant)
+        #[arg(long)]
+        backend: Option<String>,
+    },
+    /// Import entries from a `memory export` file
+    Import {
+        /// JSONL file produced by `zeroclaw memory export`
+        path: std::path::PathBuf,
+        /// Keep entries whose key already exists instead of overwriting them
+        #[arg(long)]
+        skip_existing: bool,
+        /// Write into this backend instead of the configured one
+        #[arg(long)]
+        backend: Option<String>,
+        /// Validate the file and report what would be imported
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-encrypt memory, sessions, snapshots, cron output and cost records
+    /// under a fresh key (or decrypt them when `encrypt_at_rest` is off)
+    /// Extract facts from conversation turns and consolidate them into core
+    /// memories (prints a diff; writes nothing without --apply)
+    Consolidate {
+        /// Apply the pending plan (or a fresh one) to memory
+        #[arg(long)]
+        apply: bool,
+        /// Discard the pending plan without applying it
+        #[arg(long, conflicts_with = "apply")]
+        discard: bool,
+    },
+    Rekey,
+}
+
+/// Channel conversation session subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List persisted channel conversation sessions
+    List,
+    /// Show the stored turns of a session
+    Show {
+        /// Session key (supports unique prefix match)
+        key: String,
+    },
+    /// Export sessions as JSON
+    Export {
+        /// Only export this session (supports unique prefix match)
+        key: Option<String>,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Delete a session
+    Delete {
+        /// Session key (supports unique prefix match)
+        key: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// Standard Operating Procedure subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SopCommands {
+    /// List loaded SOP definitions
+    List,
+    /// Validate SOP definitions
+    Validate {
+        /// Only validate this SOP
+        name: Option<String>,
+    },
+    /// Show an SOP definition with its steps
+    Show {
+        /// SOP name
+        name: String,
+    },
+    /// List persisted SOP runs (active by default)
+    Runs {
+        /// Only show runs of this SOP
+        #[arg(long)]
+        sop: Option<String>,
+        /// Include completed, failed and cancelled runs
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show a run with its step results and pending approval
+    Inspect {
+        /// Run ID
+        run_id: String,
+    },
+    /// Re-issue the current step of a running run
+    Resume {
+        /// Run ID
+        run_id: String,
+    },
+    /// Retry a step of an active, failed or cancelled run
+    Retry {
+        /// Run ID
+        run_id: String,
+        /// Step number (defaults to the last failed step)
+        #[arg(long)]
+        step: Option<u32>,
+    },
+    /// Cancel an active run
+    Cancel {
+        /// Run ID
+        run_id: String,
+    },
+}
+
+/// MCP (Model Context Protocol) subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpCommands {
+    /// Serve ZeroClaw's tools to MCP clients over stdio
+    Serve {
+        /// Only publish these tools (repeatable; defaults to `[mcp.serve]`)
+        #[arg(long = "tool")]
+        tools: Vec<String>,
+    },
 }
 
 /// Integration subcommands
@@ -70,6 +70,7 @@ mod heartbeat;
 mod hooks;
 mod identity;
 mod integrations;
+mod mcp;
 mod memory;
 mod migration;
 mod multimodal;
@@ -82,6 +83,7 @@ mod security;
 mod service;
 mod skillforge;
 mod skills;
+mod sop;
 mod tools;
 mod tunnel;
 mod update;
@@ -91,8 +93,9 @@ use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
-    ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, MigrateCommands,
-    PeripheralCommands, ServiceCommands, SkillCommands,
+    ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, McpCommands,
+    MigrateCommands, PeripheralCommands, ServiceCommands, SessionCommands, SkillCommands,
+    SopCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -457,25 +460,93 @@ Examples:
         peripheral_command: zeroclaw::PeripheralCommands,
     },
 
-    /// Manage agent memory (list, get, stats, clear)
+    /// Manage agent memory (list, get, stats, clear, ingest, export, import)
     #[command(long_about = "\
 Manage agent memory entries.
 
 List, inspect, and clear memory entries stored by the agent. \
 Supports filtering by category and session, pagination, and \
-batch clearing with confirmation.
+batch clearing with confirmation. `ingest` chunks a folder of \
+documents into the knowledge category for recall. `export` and \
+`import` move entries between backends as versioned JSONL.
 
 Examples:
   zeroclaw memory stats
   zeroclaw memory list
   zeroclaw memory list --category core --limit 10
   zeroclaw memory get <key>
-  zeroclaw memory clear --category conversation --yes")]
+  zeroclaw memory clear --category conversation --yes
+  zeroclaw memory ingest ./runbooks
+  zeroclaw memory export --backend sqlite --embeddings --output brain.jsonl
+  zeroclaw memory import brain.jsonl --backend postgres")]
     Memory {
         #[command(subcommand)]
         memory_command: MemoryCommands,
     },
 
+    /// Manage persisted channel conversation sessions
+    #[command(long_about = "\
+Manage channel conversation sessions.
+
+Channel conversations (Telegram, Slack, Discord, ...) are persisted per \
+sender in the workspace so they survive daemon restarts. List, inspect, \
+export or delete them here. Keys have the form \
+<channel>_[<thread>_]<sender> and accept a unique prefix.
+
+Examples:
+  zeroclaw sessions list
+  zeroclaw sessions show telegram_alice
+  zeroclaw sessions export --output sessions.json
+  zeroclaw sessions delete telegram_alice --yes")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Manage Standard Operating Procedures and their runs
+    #[command(long_about = "\
+Manage Standard Operating Procedures (SOPs).
+
+Definitions are loaded from <workspace>/sops/<name>/SOP.toml (+ SOP.md). \
+Run state, step results and pending approvals are persisted in the \
+workspace so runs survive daemon restarts; inspect and steer them here. \
+'resume', 'retry' and 'cancel' refuse to run while the daemon is up; use \
+the gateway /api/sop/runs endpoints then so the live engine sees the \
+change. 'resume' never approves a step; waiting steps are approved with \
+sop_approve. Steps are executed by the agent, not by this command.
+
+Examples:
+  zeroclaw sop list
+  zeroclaw sop validate deploy-prod
+  zeroclaw sop runs --all
+  zeroclaw sop inspect run-1739966400000-0001
+  zeroclaw sop resume run-1739966400000-0001
+  zeroclaw sop retry run-1739966400000-0001 --step 2
+  zeroclaw sop cancel run-1739966400000-0001")]
+    Sop {
+        #[command(subcommand)]
+        sop_command: SopCommands,
+    },
+
+    /// Expose ZeroClaw's tools over the Model Context Protocol
+    #[command(long_about = "\
+Expose ZeroClaw's tool registry to MCP clients.
+
+'serve' speaks MCP (newline-delimited JSON-RPC) on stdin/stdout so \
+editors and other agents can call tools such as shell, file_read or \
+memory_recall directly. Calls go through the same security policy, \
+autonomy level, approval rules and emergency stop as agent turns; \
+tools that would need an interactive approval are refused unless \
+listed in [autonomy].auto_approve. Logs go to stderr.
+
+Examples:
+  zeroclaw mcp serve
+  zeroclaw mcp serve --tool file_read --tool content_search")]
+    Mcp {
+        #[command(subcommand)]
+        mcp_command: McpCommands,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
@@ -693,7 +764,9 @@ enum MemoryCommands {
         offset: usize,
     },
     /// Get a specific memory entry by key
-    Get { key: String },
+    Get {
+        key: String,
+    },
     /// Show memory backend statistics and health
     Stats,
     /// Clear memories by category, by key, or clear all
@@ -707,6 +780,59 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Ingest documents (markdown, text, HTML, PDF) into the knowledge category
+    Ingest {
+        /// File or directory to ingest
+        path: std::path::PathBuf,
+        /// Re-ingest every file, even if its content is unchanged
+        #[arg(long)]
+        force: bool,
+        /// Max tokens per chunk (defaults to [memory] chunk_max_tokens)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+    },
+    /// Export memory entries as versioned JSONL (all categories and sessions)
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Include stored embedding vectors
+        #[arg(long)]
+        embeddings: bool,
+        /// Read from this backend instead of the configured one (sqlite, lucid, markdown, postgres, qdrant)
+        #[arg(long)]
+        backend: Option<String>,
+    },
+    /// Import entries from a `memory export` file
+    Import {
+        /// JSONL file produced by `zeroclaw memory export`
+        path: std::path::PathBuf,
+        /// Keep entries whose key already exists instead of overwriting them
+        #[arg(long)]
+        skip_existing: bool,
+        /// Write into this backend instead of the configured one
+        #[arg(long)]
+        backend: Option<String>,
+        /// Validate the file and report what would be imported
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-encrypt memory, sessions, snapshots, cron output and cost records
+    /// under a fresh key (or decrypt them when `encrypt_at_rest` is off)
+    /// Extract facts from conversation turns and consolidate them into core
+    /// memories (prints a diff; writes nothing without --apply)
+    Consolidate {
+        /// Apply the pending plan (or a fresh one) to memory
+        #[arg(long)]
+        apply: bool,
+        /// Discard the pending plan without applying it
+        #[arg(long, conflicts_with = "apply")]
+        discard: bool,
+    },
+    Rekey,
 }
 
 #[tokio::main]
@@ -736,12 +862,21 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Initialize logging - respects RUST_LOG env var, defaults to INFO
+    // Initialize logging - respects RUST_LOG env var, defaults to INFO.
+    // `mcp serve` owns stdout for protocol traffic, so its logs go to stderr.
+    let log_to_stderr = matches!(cli.command, Commands::Mcp { .. });
     let subscriber = fmt::Subscriber::builder()
         .with_timer(tracing_subscriber::fmt::time::ChronoLocal::rfc_3339())
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
+        .with_writer(move || -> Box<dyn Write> {
+            if log_to_stderr {
+                Box::new(std::io::stderr())
+            } else {
+                Box::new(std::io::stdout())
+            }
+        })
         .finish();
 
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
@@ -795,7 +930,7 @@ async fn main() -> Result<()> {
         }?;
         // Auto-start channels if user said yes during wizard
         if std::env::var("ZEROCLAW_AUTOSTART_CHANNELS").as_deref() == Ok("1") {
-            channels::start_channels(config).await?;
+            Box::pin(channels::start_channels(config)).await?;
         }
         return Ok(());
     }
@@ -1079,7 +1214,7 @@ async fn main() -> Result<()> {
         },
 
         Commands::Channel { channel_command } => match channel_command {
-            ChannelCommands::Start => channels::start_channels(config).await,
+            ChannelCommands::Start => Box::pin(channels::start_channels(config)).await,
             ChannelCommands::Doctor => channels::doctor_channels(config).await,
             other => channels::handle_command(other, &config).await,
         },
@@ -1098,6 +1233,14 @@ async fn main() -> Result<()> {
             memory::cli::handle_command(memory_command, &config).await
         }
 
+        Commands::Sessions { session_command } => {
+            channels::sessions::handle_command(session_command, &config)
+        }
+
+        Commands::Sop { sop_command } => sop::handle_command(sop_command, &config),
+
+        Commands::Mcp { mcp_command } => mcp::handle_command(mcp_command, &config).await,
+
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
         Commands::Hardware { hardware_command } => {
@@ -0,0 +1,231 @@
+//! MCP client session: handshake, tool discovery and tool invocation.
+
+use super::protocol::{
+    CallToolResult, Implementation, InitializeResult, ListToolsResult, McpToolDef,
+    MCP_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
+};
+use super::transport::{HttpTransport, McpTransport, StdioTransport};
+use crate::config::{McpServerConfig, McpTransportKind};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+/// Upper bound on `tools/list` pages, guarding against cursor loops.
+const MAX_TOOL_LIST_PAGES: usize = 32;
+
+/// An initialized connection to one MCP server.
+pub struct McpClient {
+    server_name: String,
+    server_info: Implementation,
+    protocol_version: String,
+    instructions: Option<String>,
+    transport: Box<dyn McpTransport>,
+}
+
+impl McpClient {
+    /// Connect using the transport declared in config and run the handshake.
+    pub async fn connect(config: &McpServerConfig) -> Result<Self> {
+        let transport: Box<dyn McpTransport> = match config.transport {
+            McpTransportKind::Stdio => Box::new(StdioTransport::spawn(config)?),
+            McpTransportKind::Http => Box::new(HttpTransport::new(config)?),
+        };
+        Self::initialize(&config.name, transport).await
+    }
+
+    /// Run `initialize` + `notifications/initialized` over an existing transport.
+    pub async fn initialize(server_name: &str, transport: Box<dyn McpTransport>) -> Result<Self> {
+        let params = json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": Implementation::zeroclaw(),
+        });
+        let raw = transport
+            .request("initialize", params)
+            .await
+            .with_context(|| format!("MCP server '{server_name}' failed to initialize"))?;
+        let init: InitializeResult = serde_json::from_value(raw).with_context(|| {
+            format!("MCP server '{server_name}' sent a malformed initialize result")
+        })?;
+
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&init.protocol_version.as_str()) {
+            transport.close().await;
+            anyhow::bail!(
+                "MCP server '{server_name}' speaks unsupported protocol version {}",
+                init.protocol_version
+            );
+        }
+        transport.set_protocol_version(&init.protocol_version);
+        transport
+            .notify("notifications/initialized", None)
+            .await
+            .with_context(|| {
+                format!("MCP server '{server_name}' rejected initialized notification")
+            })?;
+
+        tracing::info!(
+            server = server_name,
+            remote = %init.server_info.name,
+            version = %init.server_info.version,
+            protocol = %init.protocol_version,
+            "MCP server connected"
+        );
+
+        Ok(Self {
+            server_name: server_name.to_string(),
+            server_info: init.server_info,
+            protocol_version: init.protocol_version,
+            instructions: init.instructions,
+            transport,
+        })
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    pub fn server_info(&self) -> &Implementation {
+        &self.server_info
+    }
+
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+
+    pub fn instructions(&self) -> Option<&str> {
+        self.instructions.as_deref()
+    }
+
+    /// List all tools, following pagination cursors.
+    pub async fn list_tools(&self) -> Result<Vec<McpToolDef>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        for _ in 0..MAX_TOOL_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let raw = self.transport.request("tools/list", params).await?;
+            let page: ListToolsResult = serde_json::from_value(raw).with_context(|| {
+                format!(
+                    "MCP server '{}' sent a malformed tools/list result",
+                    self.server_name
+                )
+            })?;
+            tools.extend(page.tools);
+            match page.next_cursor {
+                Some(next) if !next.is_empty() => cursor = Some(next),
+                _ => return Ok(tools),
+            }
+        }
+
+        tracing::warn!(
+            server = %self.server_name,
+            "MCP tools/list pagination exceeded {MAX_TOOL_LIST_PAGES} pages; truncating"
+        );
+        Ok(tools)
+    }
+
+    /// Invoke a remote tool by its server-side name.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult> {
+        let arguments = if arguments.is_null() {
+            json!({})
+        } else {
+            arguments
+        };
+        let raw = self
+            .transport
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        serde_json::from_value(raw).with_context(|| {
+            format!(
+                "MCP server '{}' sent a malformed tools/call result",
+                self.server_name
+            )
+        })
+    }
+
+    /// Shut down the underlying transport.
+    pub async fn close(&self) {
+        self.transport.close().await;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Path to the stdio MCP server fixture used across MCP tests.
+    ///
+    /// Panics when `python3` is missing so the stdio transport is never
+    /// silently left untested.
+    pub(crate) fn fixture_server_config(name: &str) -> McpServerConfig {
+        let python = which::which("python3")
+            .expect("python3 is required to run the MCP stdio fixture tests");
+        let script =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mcp_stdio_server.py");
+        McpServerConfig {
+            name: name.into(),
+            command: Some(python.display().to_string()),
+            args: vec![script.display().to_string()],
+            timeout_secs: 10,
+            ..McpServerConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn connects_to_stdio_fixture_and_lists_tools() {
+        let config = fixture_server_config("fixture");
+        let client = McpClient::connect(&config).await.unwrap();
+        assert_eq!(client.server_info().name, "zeroclaw-test-fixture");
+        assert_eq!(client.protocol_version(), MCP_PROTOCOL_VERSION);
+
+        let tools = client.list_tools().await.unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        // The fixture paginates: echo on page one, add + fail on page two.
+        assert_eq!(names, vec!["echo", "add", "fail"]);
+        assert!(tools[1].is_read_only());
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn calls_tools_on_stdio_fixture() {
+        let config = fixture_server_config("fixture");
+        let client = McpClient::connect(&config).await.unwrap();
+
+        let echoed = client
+            .call_tool("echo", json!({"text": "hi there"}))
+            .await
+            .unwrap();
+        assert!(!echoed.is_error);
+        assert_eq!(echoed.to_text(), "hi there");
+
+        let sum = client
+            .call_tool("add", json!({"a": 2, "b": 40}))
+            .await
+            .unwrap();
+        assert_eq!(sum.to_text(), "42");
+
+        let failed = client.call_tool("fail", json!({})).await.unwrap();
+        assert!(failed.is_error);
+        assert!(failed.to_text().contains("intentional failure"));
+
+        let unknown = client.call_tool("missing", json!({})).await.unwrap_err();
+        assert!(unknown.to_string().contains("Unknown tool"));
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn connect_reports_spawn_failures() {
+        let config = McpServerConfig {
+            name: "ghost".into(),
+            command: Some("/nonexistent/zeroclaw-mcp-server".into()),
+            ..McpServerConfig::default()
+        };
+        let err = McpClient::connect(&config).await.err().unwrap();
+        assert!(err.to_string().contains("ghost"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Model Context Protocol (MCP) integration.
+//!
+//! The client side connects to external MCP servers declared under `[mcp]`
+//! (stdio or streamable HTTP), lists their tools and exposes each one as a
+//! regular [`Tool`](crate::tools::Tool) via [`McpTool`](crate::tools::McpTool),
+//! so remote tools go through the same `SecurityPolicy` and approval gates as
+//! native ones.
+//!
+//! The server side ([`server::McpServer`]) publishes ZeroClaw's own tool
+//! registry over stdio (`zeroclaw mcp serve`) or the gateway's `POST /mcp`.
+
+pub mod client;
+pub mod protocol;
+pub mod server;
+pub mod transport;
+
+pub use client::McpClient;
+#[allow(unused_imports)]
+pub use protocol::{CallToolResult, McpToolDef};
+pub use server::McpServer;
+
+use crate::config::Config;
+use crate::memory::{self, Memory};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Handle `zeroclaw mcp` subcommands.
+pub async fn handle_command(command: crate::McpCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::McpCommands::Serve { tools } => {
+            let mut config = config.clone();
+            if !tools.is_empty() {
+                config.mcp.serve.include_tools = tools;
+            }
+            let registry = build_native_tools(&config)?;
+            let server = McpServer::new(Arc::new(registry), &config, "mcp");
+            tracing::info!(
+                tools = server.published_tools().count(),
+                "Serving MCP over stdio"
+            );
+            server::serve_stdio(&server).await
+        }
+    }
+}
+
+/// Build the native tool registry for serving. Tools from `[mcp.servers]`
+/// are deliberately left out so a server listed in its own config cannot
+/// recurse into itself.
+fn build_native_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
+    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+        &config.memory,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        &config.reliability,
+    )?);
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let (composio_key, composio_entity_id) = if config.composio.enabled {
+        (
+            config.composio.api_key.as_deref(),
+            Some(config.composio.entity_id.as_str()),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(tools::all_tools_with_runtime(
+        Arc::new(config.clone()),
+        &security,
+        runtime,
+        mem,
+        composio_key,
+        composio_entity_id,
+        &config.browser,
+        &config.http_request,
+        &config.web_fetch,
+        &config.workspace_dir,
+        &config.agents,
+        config.api_key.as_deref(),
+        config,
+    ))
+}
+
+/// Maximum tool name length accepted by the major function-calling APIs.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Build the agent-facing tool name for a remote MCP tool: `<server>__<tool>`,
+/// restricted to `[A-Za-z0-9_-]` and capped at 64 characters.
+pub fn qualified_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |value: &str| -> String {
+        value
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    let mut name = format!("{}__{}", sanitize(server.trim()), sanitize(tool.trim()));
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualified_name_joins_server_and_tool() {
+        assert_eq!(
+            qualified_tool_name("github", "create_issue"),
+            "github__create_issue"
+        );
+    }
+
+    #[test]
+    fn qualified_name_sanitizes_and_truncates() {
+        assert_eq!(
+            qualified_tool_name("fs", "read.file/v2"),
+            "fs__read_file_v2"
+        );
+        let long = "x".repeat(100);
+        assert_eq!(qualified_tool_name("srv", &long).len(), MAX_TOOL_NAME_LEN);
+    }
+}
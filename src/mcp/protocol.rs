@@ -0,0 +1,388 @@
+//! JSON-RPC 2.0 envelopes and the subset of MCP message shapes used by
+//! ZeroClaw's client.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Protocol revision advertised during `initialize`.
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Older revisions accepted from servers that cannot negotiate the latest one.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+// ── JSON-RPC envelopes ───────────────────────────────────────────
+
+/// Outgoing JSON-RPC request (or notification when `id` is `None`).
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonRpcRequest<'a> {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl<'a> JsonRpcRequest<'a> {
+    pub fn request(id: u64, method: &'a str, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id: Some(id),
+            method,
+            params: Some(params),
+        }
+    }
+
+    pub fn notification(method: &'a str, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id: None,
+            method,
+            params,
+        }
+    }
+}
+
+/// JSON-RPC error object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl std::fmt::Display for JsonRpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JSON-RPC error {}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for JsonRpcError {}
+
+/// Standard JSON-RPC error codes.
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Any incoming JSON-RPC message, classified by which fields are present.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcMessage {
+    #[serde(default)]
+    pub id: Option<Value>,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcMessage {
+    /// A response to one of our requests (has an id, no method).
+    pub fn is_response(&self) -> bool {
+        self.method.is_none() && self.id.is_some()
+    }
+
+    /// A request from the peer that expects a reply.
+    pub fn is_request(&self) -> bool {
+        self.method.is_some() && self.id.is_some()
+    }
+
+    /// Numeric id of a response, if it is one we could have issued.
+    pub fn numeric_id(&self) -> Option<u64> {
+        match self.id.as_ref()? {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Convert a response into the `result` value or the peer's error.
+    pub fn into_result(self) -> Result<Value, JsonRpcError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(self.result.unwrap_or(Value::Null))
+    }
+}
+
+/// Build a JSON-RPC success response body.
+pub fn success_response(id: Value, result: Value) -> Value {
+    serde_json::json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": id,
+        "result": result,
+    })
+}
+
+/// Build a JSON-RPC error response body.
+pub fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    serde_json::json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+// ── MCP payloads ─────────────────────────────────────────────────
+
+/// Name/version pair exchanged during `initialize`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Implementation {
+    pub name: String,
+    pub version: String,
+}
+
+impl Implementation {
+    pub fn zeroclaw() -> Self {
+        Self {
+            name: "zeroclaw".into(),
+            version: env!("CARGO_PKG_VERSION").into(),
+        }
+    }
+}
+
+/// Result of the `initialize` handshake.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    pub protocol_version: String,
+    #[serde(default)]
+    pub capabilities: Value,
+    pub server_info: Implementation,
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+/// Behavioural hints a server may attach to a tool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+/// Tool definition returned by `tools/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolDef {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+impl McpToolDef {
+    /// Whether the server declared this tool free of side effects.
+    pub fn is_read_only(&self) -> bool {
+        self.annotations
+            .as_ref()
+            .and_then(|a| a.read_only_hint)
+            .unwrap_or(false)
+    }
+}
+
+pub fn empty_object_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+/// One page of `tools/list` results.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToolsResult {
+    #[serde(default)]
+    pub tools: Vec<McpToolDef>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// Embedded resource body inside a tool result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+/// A content block in a `tools/call` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    Audio {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    Resource {
+        resource: ResourceContents,
+    },
+    ResourceLink {
+        uri: String,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+/// Result of `tools/call`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResult {
+    #[serde(default)]
+    pub content: Vec<ContentBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+impl CallToolResult {
+    /// Flatten content blocks into plain text suitable for the LLM.
+    pub fn to_text(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        for block in &self.content {
+            match block {
+                ContentBlock::Text { text } => parts.push(text.clone()),
+                ContentBlock::Image { data, mime_type } => {
+                    parts.push(format!("[image: {mime_type}, {} bytes base64]", data.len()));
+                }
+                ContentBlock::Audio { data, mime_type } => {
+                    parts.push(format!("[audio: {mime_type}, {} bytes base64]", data.len()));
+                }
+                ContentBlock::Resource { resource } => match &resource.text {
+                    Some(text) => parts.push(text.clone()),
+                    None => parts.push(format!("[resource: {}]", resource.uri)),
+                },
+                ContentBlock::ResourceLink { uri, name } => match name {
+                    Some(name) => parts.push(format!("[resource link: {name} <{uri}>]")),
+                    None => parts.push(format!("[resource link: {uri}]")),
+                },
+                ContentBlock::Unsupported => parts.push("[unsupported content block]".into()),
+            }
+        }
+
+        if parts.is_empty() {
+            if let Some(structured) = &self.structured_content {
+                return structured.to_string();
+            }
+        }
+
+        parts.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn request_serializes_without_null_fields() {
+        let req = JsonRpcRequest::notification("notifications/initialized", None);
+        let value = serde_json::to_value(&req).unwrap();
+        assert_eq!(
+            value,
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"})
+        );
+
+        let req = JsonRpcRequest::request(7, "tools/list", json!({}));
+        let value = serde_json::to_value(&req).unwrap();
+        assert_eq!(value["id"], 7);
+        assert_eq!(value["params"], json!({}));
+    }
+
+    #[test]
+    fn message_classification() {
+        let resp: JsonRpcMessage =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 3, "result": {}})).unwrap();
+        assert!(resp.is_response());
+        assert_eq!(resp.numeric_id(), Some(3));
+
+        let req: JsonRpcMessage =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": "a", "method": "ping"})).unwrap();
+        assert!(req.is_request());
+        assert!(!req.is_response());
+
+        let err: JsonRpcMessage = serde_json::from_value(
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "nope"}}),
+        )
+        .unwrap();
+        assert_eq!(err.into_result().unwrap_err().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tool_def_defaults_schema_and_read_only_hint() {
+        let def: McpToolDef = serde_json::from_value(json!({"name": "echo"})).unwrap();
+        assert_eq!(def.input_schema["type"], "object");
+        assert!(!def.is_read_only());
+
+        let def: McpToolDef = serde_json::from_value(json!({
+            "name": "lookup",
+            "inputSchema": {"type": "object"},
+            "annotations": {"readOnlyHint": true}
+        }))
+        .unwrap();
+        assert!(def.is_read_only());
+    }
+
+    #[test]
+    fn call_result_flattens_content_blocks() {
+        let result: CallToolResult = serde_json::from_value(json!({
+            "content": [
+                {"type": "text", "text": "hello"},
+                {"type": "image", "data": "aGk=", "mimeType": "image/png"},
+                {"type": "resource", "resource": {"uri": "file:///a", "text": "body"}},
+                {"type": "something_new"}
+            ],
+            "isError": false
+        }))
+        .unwrap();
+        let text = result.to_text();
+        assert!(text.contains("hello"));
+        assert!(text.contains("[image: image/png, 4 bytes base64]"));
+        assert!(text.contains("body"));
+        assert!(text.contains("[unsupported content block]"));
+    }
+
+    #[test]
+    fn call_result_falls_back_to_structured_content() {
+        let result: CallToolResult = serde_json::from_value(json!({
+            "content": [],
+            "structuredContent": {"temperature": 21}
+        }))
+        .unwrap();
+        assert_eq!(result.to_text(), r#"{"temperature":21}"#);
+    }
+}
@@ -0,0 +1,495 @@
+//! MCP server: publishes ZeroClaw's tool registry to MCP clients.
+//!
+//! Calls are gated exactly like non-interactive channel turns in the agent
+//! loop: the emergency-stop state is re-read before every call, tools that
+//! would need a supervised-mode approval prompt are denied (an MCP client
+//! cannot answer one), and each tool still enforces its own
+//! `SecurityPolicy` (autonomy level, workspace scoping, rate limits).
+
+use super::protocol::{
+    error_response, success_response, Implementation, JsonRpcMessage, INVALID_PARAMS,
+    INVALID_REQUEST, MCP_PROTOCOL_VERSION, METHOD_NOT_FOUND, PARSE_ERROR,
+    SUPPORTED_PROTOCOL_VERSIONS,
+};
+use crate::agent::loop_::scrub_credentials;
+use crate::approval::{ApprovalManager, ApprovalResponse};
+use crate::config::{ApprovalRuleAction, Config, EstopConfig};
+use crate::security::EstopManager;
+use crate::tools::Tool;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+const SERVER_INSTRUCTIONS: &str = "ZeroClaw tools run inside the host's security policy. \
+Calls may be refused by autonomy settings, approval rules or an engaged emergency stop; \
+the refusal reason is returned as an error result.";
+
+/// Where to read emergency-stop state from before each call.
+struct EstopSource {
+    config: EstopConfig,
+    config_dir: PathBuf,
+}
+
+/// Serves a tool registry over MCP.
+pub struct McpServer {
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    include_tools: Vec<String>,
+    exclude_tools: Vec<String>,
+    approval: ApprovalManager,
+    estop: Option<EstopSource>,
+    channel: String,
+}
+
+impl McpServer {
+    /// Build a server over `tools` using the `[mcp.serve]`, `[autonomy]` and
+    /// `[security.estop]` settings from `config`. `channel` labels approval
+    /// audit entries (e.g. `mcp` or `mcp-http`).
+    pub fn new(tools: Arc<Vec<Box<dyn Tool>>>, config: &Config, channel: &str) -> Self {
+        let estop = config.security.estop.enabled.then(|| EstopSource {
+            config: config.security.estop.clone(),
+            config_dir: config
+                .config_path
+                .parent()
+                .map(PathBuf::from)
+                .unwrap_or_default(),
+        });
+
+        Self {
+            tools,
+            include_tools: config.mcp.serve.include_tools.clone(),
+            exclude_tools: config.mcp.serve.exclude_tools.clone(),
+            approval: ApprovalManager::from_config(&config.autonomy)
+                .with_workspace_dir(&config.workspace_dir),
+            estop,
+            channel: channel.to_string(),
+        }
+    }
+
+    /// Also hide `tools` from clients, on top of `[mcp.serve] exclude_tools`.
+    /// Remote transports pass `autonomy.non_cli_excluded_tools` here.
+    #[must_use]
+    pub fn with_excluded_tools(mut self, tools: &[String]) -> Self {
+        self.exclude_tools.extend(tools.iter().cloned());
+        self
+    }
+
+    /// Tools visible to MCP clients after `include_tools` / `exclude_tools`.
+    pub fn published_tools(&self) -> impl Iterator<Item = &dyn Tool> {
+        self.tools
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|tool| self.is_published(tool.name()))
+    }
+
+    fn is_published(&self, name: &str) -> bool {
+        (self.include_tools.is_empty() || self.include_tools.iter().any(|t| t == name))
+            && !self.exclude_tools.iter().any(|t| t == name)
+    }
+
+    /// Handle one raw JSON-RPC line. Returns the response to send, if any.
+    pub async fn handle_raw(&self, raw: &str) -> Option<Value> {
+        let value: Value = match serde_json::from_str(raw) {
+            Ok(value) => value,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Parse error: {e}"),
+                ))
+            }
+        };
+        if value.is_array() {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                "JSON-RPC batches are not supported",
+            ));
+        }
+        match serde_json::from_value::<JsonRpcMessage>(value) {
+            Ok(message) => self.handle_message(message).await,
+            Err(e) => Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                format!("Invalid request: {e}"),
+            )),
+        }
+    }
+
+    /// Handle one decoded JSON-RPC message. Notifications and stray
+    /// responses produce no reply.
+    pub async fn handle_message(&self, message: JsonRpcMessage) -> Option<Value> {
+        if !message.is_request() {
+            return None;
+        }
+        let id = message.id.unwrap_or(Value::Null);
+        let method = message.method.unwrap_or_default();
+        let params = message.params.unwrap_or(Value::Null);
+
+        let response = match method.as_str() {
+            "initialize" => success_response(id, self.initialize_result(&params)),
+            "ping" => success_response(id, json!({})),
+            "tools/list" => success_response(id, self.list_tools_result()),
+            "tools/call" => match self.call_tool(&params).await {
+                Ok(result) => success_response(id, result),
+                Err(message) => error_response(id, INVALID_PARAMS, message),
+            },
+            other => error_response(id, METHOD_NOT_FOUND, format!("Method not found: {other}")),
+        };
+        Some(response)
+    }
+
+    fn initialize_result(&self, params: &Value) -> Value {
+        let requested = params
+            .get("protocolVersion")
+            .and_then(Value::as_str)
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+        let version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) {
+            requested
+        } else {
+            MCP_PROTOCOL_VERSION
+        };
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": Implementation::zeroclaw(),
+            "instructions": SERVER_INSTRUCTIONS,
+        })
+    }
+
+    fn list_tools_result(&self) -> Value {
+        let tools: Vec<Value> = self
+            .published_tools()
+            .map(|tool| {
+                json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.parameters_schema(),
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    /// Execute `tools/call`. Protocol-level problems (bad params, unknown
+    /// tool) are `Err`; policy refusals and tool failures are `isError` results.
+    async fn call_tool(&self, params: &Value) -> Result<Value, String> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "tools/call requires a string 'name'".to_string())?;
+        let tool = self
+            .published_tools()
+            .find(|tool| tool.name() == name)
+            .ok_or_else(|| format!("Unknown tool: {name}"))?;
+        let args = match params.get("arguments") {
+            None | Some(Value::Null) => json!({}),
+            Some(args @ Value::Object(_)) => args.clone(),
+            Some(_) => return Err("tools/call 'arguments' must be an object".into()),
+        };
+
+        if let Some(reason) = self.estop_block_reason(name, &args) {
+            tracing::warn!(tool = name, "MCP tool call blocked: {reason}");
+            return Ok(tool_result(&reason, true));
+        }
+
+        let check = self.approval.check(name, &args);
+        if check.action != ApprovalRuleAction::Allow {
+            self.approval.record_rule_decision(
+                name,
+                &args,
+                ApprovalResponse::No,
+                &self.channel,
+                check.rule.as_deref(),
+            );
+            let message = match (&check.rule, check.is_denied()) {
+                (Some(rule), true) => format!("Tool '{name}' denied by approval rule '{rule}'."),
+                (Some(rule), false) => format!(
+                    "Tool '{name}' requires approval by rule '{rule}' and MCP clients \
+                     cannot answer approval prompts."
+                ),
+                (None, _) => format!(
+                    "Tool '{name}' requires approval under supervised autonomy and MCP clients \
+                     cannot answer approval prompts. Add it to [autonomy].auto_approve to allow it."
+                ),
+            };
+            return Ok(tool_result(&message, true));
+        }
+
+        match tool.execute(args).await {
+            Ok(result) if result.success => {
+                Ok(tool_result(&scrub_credentials(&result.output), false))
+            }
+            Ok(result) => {
+                let text = result
+                    .error
+                    .filter(|error| !error.is_empty())
+                    .unwrap_or(result.output);
+                Ok(tool_result(&scrub_credentials(&text), true))
+            }
+            Err(e) => Ok(tool_result(
+                &scrub_credentials(&format!("Error executing {name}: {e}")),
+                true,
+            )),
+        }
+    }
+
+    fn estop_block_reason(&self, name: &str, args: &Value) -> Option<String> {
+        let source = self.estop.as_ref()?;
+        // Re-read on every call so `zeroclaw estop` takes effect immediately.
+        match EstopManager::load(&source.config, &source.config_dir) {
+            Ok(manager) => manager.status().tool_call_block_reason(name, args),
+            Err(e) => Some(format!(
+                "Emergency stop state is unavailable ({e}); refusing tool call."
+            )),
+        }
+    }
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+/// Serve newline-delimited JSON-RPC over the given streams until EOF.
+pub async fn serve<R, W>(server: &McpServer, reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_raw(line).await {
+            let mut encoded = serde_json::to_vec(&response)?;
+            encoded.push(b'\n');
+            writer.write_all(&encoded).await?;
+            writer.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Serve on the process's stdin/stdout (`zeroclaw mcp serve`).
+pub async fn serve_stdio(server: &McpServer) -> Result<()> {
+    serve(server, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use crate::security::{AutonomyLevel, EstopLevel};
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingTool {
+        name: &'static str,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Counts invocations"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"text": {"type": "string"}}})
+        }
+
+        async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolResult {
+                success: true,
+                output: format!("ok: {}", args["text"].as_str().unwrap_or_default()),
+                error: None,
+            })
+        }
+    }
+
+    fn test_config(dir: &TempDir, level: AutonomyLevel) -> Config {
+        let mut config = Config {
+            config_path: dir.path().join("config.toml"),
+            workspace_dir: dir.path().join("workspace"),
+            ..Config::default()
+        };
+        config.autonomy = AutonomyConfig {
+            level,
+            ..AutonomyConfig::default()
+        };
+        config
+    }
+
+    fn server_with(config: &Config) -> (McpServer, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(CountingTool {
+                name: "echo",
+                calls: Arc::clone(&calls),
+            }),
+            Box::new(CountingTool {
+                name: "shell",
+                calls: Arc::clone(&calls),
+            }),
+        ];
+        (McpServer::new(Arc::new(tools), config, "mcp"), calls)
+    }
+
+    async fn call(server: &McpServer, name: &str) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": {"name": name, "arguments": {"text": "hi"}},
+        });
+        server.handle_raw(&request.to_string()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn initialize_and_list_publish_filtered_tools() {
+        let dir = TempDir::new().unwrap();
+        let mut config = test_config(&dir, AutonomyLevel::Full);
+        config.mcp.serve.exclude_tools = vec!["shell".into()];
+        let (server, _) = server_with(&config);
+
+        let init = server
+            .handle_raw(
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "zeroclaw");
+
+        assert!(server
+            .handle_raw(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let listed = server
+            .handle_raw(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let tools = listed["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert_eq!(tools[0]["inputSchema"]["type"], "object");
+
+        let hidden = call(&server, "shell").await;
+        assert_eq!(hidden["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn full_autonomy_executes_tools() {
+        let dir = TempDir::new().unwrap();
+        let (server, calls) = server_with(&test_config(&dir, AutonomyLevel::Full));
+
+        let response = call(&server, "echo").await;
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["isError"], false);
+        assert_eq!(response["result"]["content"][0]["text"], "ok: hi");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn supervised_tools_needing_approval_are_denied() {
+        let dir = TempDir::new().unwrap();
+        let mut config = test_config(&dir, AutonomyLevel::Supervised);
+        config.autonomy.auto_approve = vec!["echo".into()];
+        config.autonomy.always_ask = vec![];
+        let (server, calls) = server_with(&config);
+
+        let denied = call(&server, "shell").await;
+        assert_eq!(denied["result"]["isError"], true);
+        assert!(denied["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("requires approval"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let allowed = call(&server, "echo").await;
+        assert_eq!(allowed["result"]["isError"], false);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn engaged_estop_blocks_calls_without_restart() {
+        let dir = TempDir::new().unwrap();
+        let mut config = test_config(&dir, AutonomyLevel::Full);
+        config.security.estop.enabled = true;
+        config.security.estop.state_file = dir.path().join("estop.json").display().to_string();
+        let (server, calls) = server_with(&config);
+
+        assert_eq!(call(&server, "shell").await["result"]["isError"], false);
+
+        let mut manager = EstopManager::load(&config.security.estop, dir.path()).unwrap();
+        manager
+            .engage(EstopLevel::ToolFreeze(vec!["shell".into()]))
+            .unwrap();
+
+        let frozen = call(&server, "shell").await;
+        assert_eq!(frozen["result"]["isError"], true);
+        assert!(frozen["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("tool-freeze"));
+        assert_eq!(call(&server, "echo").await["result"]["isError"], false);
+
+        manager.engage(EstopLevel::KillAll).unwrap();
+        assert_eq!(call(&server, "echo").await["result"]["isError"], true);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn malformed_input_and_unknown_methods_get_errors() {
+        let dir = TempDir::new().unwrap();
+        let (server, _) = server_with(&test_config(&dir, AutonomyLevel::Full));
+
+        let parse = server.handle_raw("{not json").await.unwrap();
+        assert_eq!(parse["error"]["code"], PARSE_ERROR);
+
+        let unknown = server
+            .handle_raw(r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn serve_round_trips_over_streams() {
+        let dir = TempDir::new().unwrap();
+        let (server, _) = server_with(&test_config(&dir, AutonomyLevel::Full));
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        serve(&server, input.as_bytes(), &mut output).await.unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[1]["result"]["tools"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,589 @@
+//! MCP transports: newline-delimited JSON-RPC over a child process's stdio,
+//! and the streamable HTTP transport (POST with JSON or SSE responses).
+
+use super::protocol::{
+    error_response, success_response, JsonRpcError, JsonRpcMessage, JsonRpcRequest, INTERNAL_ERROR,
+    METHOD_NOT_FOUND,
+};
+use crate::config::McpServerConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Session header used by the streamable HTTP transport.
+const MCP_SESSION_HEADER: &str = "mcp-session-id";
+/// Protocol version header required after initialization (HTTP transport).
+const MCP_PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// A bidirectional JSON-RPC channel to one MCP server.
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    /// Send a request and wait for the matching response.
+    async fn request(&self, method: &str, params: Value) -> Result<Value>;
+
+    /// Send a notification (no response expected).
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()>;
+
+    /// Record the negotiated protocol version (used by HTTP for headers).
+    fn set_protocol_version(&self, _version: &str) {}
+
+    /// Release server-side resources. Best effort.
+    async fn close(&self) {}
+}
+
+// ── stdio ────────────────────────────────────────────────────────
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, JsonRpcError>>>>>;
+type SharedWriter = Arc<tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+
+/// Newline-delimited JSON-RPC over a pair of byte streams, normally the
+/// stdin/stdout of a spawned server process.
+pub struct StdioTransport {
+    server: String,
+    writer: SharedWriter,
+    pending: PendingMap,
+    next_id: AtomicU64,
+    closed: Arc<AtomicBool>,
+    timeout: Duration,
+    reader_task: JoinHandle<()>,
+    child: Option<tokio::sync::Mutex<tokio::process::Child>>,
+}
+
+impl StdioTransport {
+    /// Spawn the configured server command and attach to its stdio.
+    pub fn spawn(config: &McpServerConfig) -> Result<Self> {
+        let command = config
+            .command
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .with_context(|| format!("MCP server '{}' has no command", config.name))?;
+
+        let mut cmd = tokio::process::Command::new(command);
+        cmd.args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = config.cwd.as_deref().filter(|c| !c.trim().is_empty()) {
+            cmd.current_dir(shellexpand::tilde(cwd).as_ref());
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to spawn MCP server '{}' ({command})", config.name))?;
+
+        let stdin = child.stdin.take().context("MCP server stdin unavailable")?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("MCP server stdout unavailable")?;
+        if let Some(stderr) = child.stderr.take() {
+            let server = config.name.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(server = %server, "mcp stderr: {line}");
+                }
+            });
+        }
+
+        let mut transport = Self::from_streams(
+            &config.name,
+            stdout,
+            stdin,
+            Duration::from_secs(config.timeout_secs),
+        );
+        transport.child = Some(tokio::sync::Mutex::new(child));
+        Ok(transport)
+    }
+
+    /// Attach to an already-connected pair of streams.
+    pub fn from_streams<R, W>(server: &str, reader: R, writer: W, timeout: Duration) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let writer: SharedWriter = Arc::new(tokio::sync::Mutex::new(Box::new(writer)));
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let reader_task = tokio::spawn(read_loop(
+            server.to_string(),
+            reader,
+            writer.clone(),
+            pending.clone(),
+            closed.clone(),
+        ));
+
+        Self {
+            server: server.to_string(),
+            writer,
+            pending,
+            next_id: AtomicU64::new(1),
+            closed,
+            timeout,
+            reader_task,
+            child: None,
+        }
+    }
+
+    async fn write_message(&self, message: &impl serde::Serialize) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        let mut writer = self.writer.lock().await;
+        writer.write_all(line.as_bytes()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+async fn write_line(writer: &SharedWriter, value: &Value) -> std::io::Result<()> {
+    let mut line = value.to_string();
+    line.push('\n');
+    let mut writer = writer.lock().await;
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await
+}
+
+async fn read_loop<R>(
+    server: String,
+    reader: R,
+    writer: SharedWriter,
+    pending: PendingMap,
+    closed: Arc<AtomicBool>,
+) where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!(server = %server, "MCP stdio read error: {e}");
+                break;
+            }
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let message: JsonRpcMessage = match serde_json::from_str(trimmed) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!(server = %server, "Ignoring non-JSON-RPC line from MCP server: {e}");
+                continue;
+            }
+        };
+
+        if message.is_response() {
+            let Some(id) = message.numeric_id() else {
+                continue;
+            };
+            let waiter = pending.lock().remove(&id);
+            if let Some(waiter) = waiter {
+                let _ = waiter.send(message.into_result());
+            }
+        } else if message.is_request() {
+            let id = message.id.clone().unwrap_or(Value::Null);
+            let reply = match message.method.as_deref() {
+                Some("ping") => success_response(id, serde_json::json!({})),
+                Some(method) => error_response(
+                    id,
+                    METHOD_NOT_FOUND,
+                    format!("Client does not support '{method}'"),
+                ),
+                None => continue,
+            };
+            if let Err(e) = write_line(&writer, &reply).await {
+                tracing::warn!(server = %server, "Failed to answer MCP server request: {e}");
+            }
+        } else if let Some(method) = message.method.as_deref() {
+            tracing::debug!(server = %server, method, "MCP notification received");
+        }
+    }
+
+    closed.store(true, Ordering::SeqCst);
+    let waiters: Vec<_> = pending.lock().drain().map(|(_, tx)| tx).collect();
+    for waiter in waiters {
+        let _ = waiter.send(Err(JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: "MCP server closed the connection".into(),
+            data: None,
+        }));
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        if self.closed.load(Ordering::SeqCst) {
+            anyhow::bail!("MCP server '{}' is not running", self.server);
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+
+        let request = JsonRpcRequest::request(id, method, params);
+        if let Err(e) = self.write_message(&request).await {
+            self.pending.lock().remove(&id);
+            return Err(e)
+                .with_context(|| format!("Failed to write to MCP server '{}'", self.server));
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(result)) => result.map_err(|e| {
+                anyhow::anyhow!("MCP server '{}' returned {e} for {method}", self.server)
+            }),
+            Ok(Err(_)) => anyhow::bail!("MCP server '{}' dropped request {method}", self.server),
+            Err(_) => {
+                self.pending.lock().remove(&id);
+                anyhow::bail!(
+                    "MCP server '{}' timed out after {}s on {method}",
+                    self.server,
+                    self.timeout.as_secs()
+                )
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        self.write_message(&JsonRpcRequest::notification(method, params))
+            .await
+    }
+
+    async fn close(&self) {
+        self.reader_task.abort();
+        if let Some(child) = &self.child {
+            let _ = child.lock().await.kill().await;
+        }
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+// ── streamable HTTP ──────────────────────────────────────────────
+
+/// Streamable HTTP transport: every JSON-RPC message is a POST to a single
+/// endpoint; the server answers with `application/json` or a short SSE stream.
+pub struct HttpTransport {
+    server: String,
+    url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+    session_id: Mutex<Option<String>>,
+    protocol_version: Mutex<Option<String>>,
+    next_id: AtomicU64,
+}
+
+impl HttpTransport {
+    pub fn new(config: &McpServerConfig) -> Result<Self> {
+        let url = config
+            .url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .with_context(|| format!("MCP server '{}' has no url", config.name))?;
+
+        Ok(Self {
+            server: config.name.clone(),
+            url: url.to_string(),
+            headers: config.headers.clone(),
+            client: crate::config::build_runtime_proxy_client_with_timeouts(
+                "tool.mcp",
+                config.timeout_secs,
+                10,
+            ),
+            session_id: Mutex::new(None),
+            protocol_version: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    fn build_post(&self, body: &impl serde::Serialize) -> reqwest::RequestBuilder {
+        let mut builder = self
+            .client
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(body);
+        for (key, value) in &self.headers {
+            builder = builder.header(key, value);
+        }
+        if let Some(session) = self.session_id.lock().clone() {
+            builder = builder.header(MCP_SESSION_HEADER, session);
+        }
+        if let Some(version) = self.protocol_version.lock().clone() {
+            builder = builder.header(MCP_PROTOCOL_VERSION_HEADER, version);
+        }
+        builder
+    }
+
+    fn remember_session(&self, response: &reqwest::Response) {
+        if let Some(session) = response
+            .headers()
+            .get(MCP_SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            *self.session_id.lock() = Some(session.to_string());
+        }
+    }
+}
+
+/// Extract the JSON-RPC response with `id` from an SSE body.
+pub(crate) fn find_sse_response(body: &str, id: u64) -> Option<JsonRpcMessage> {
+    let mut data = String::new();
+    let mut events: Vec<String> = Vec::new();
+    for line in body.lines() {
+        if line.is_empty() {
+            if !data.is_empty() {
+                events.push(std::mem::take(&mut data));
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        }
+    }
+    if !data.is_empty() {
+        events.push(data);
+    }
+
+    events
+        .iter()
+        .filter_map(|event| serde_json::from_str::<JsonRpcMessage>(event).ok())
+        .find(|message| message.is_response() && message.numeric_id() == Some(id))
+}
+
+#[async_trait]
+impl McpTransport for HttpTransport {
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = JsonRpcRequest::request(id, method, params);
+
+        let response = self
+            .build_post(&request)
+            .send()
+            .await
+            .with_context(|| format!("MCP server '{}' request {method} failed", self.server))?;
+        self.remember_session(&response);
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "MCP server '{}' returned HTTP {status} for {method}: {}",
+                self.server,
+                crate::util::truncate_with_ellipsis(&body, 300)
+            );
+        }
+
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+        let body = response.text().await?;
+
+        let message = if is_sse {
+            find_sse_response(&body, id).with_context(|| {
+                format!(
+                    "MCP server '{}' closed the event stream without answering {method}",
+                    self.server
+                )
+            })?
+        } else {
+            serde_json::from_str::<JsonRpcMessage>(&body).with_context(|| {
+                format!(
+                    "MCP server '{}' sent invalid JSON for {method}",
+                    self.server
+                )
+            })?
+        };
+
+        message
+            .into_result()
+            .map_err(|e| anyhow::anyhow!("MCP server '{}' returned {e} for {method}", self.server))
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        let notification = JsonRpcRequest::notification(method, params);
+        let response = self.build_post(&notification).send().await?;
+        self.remember_session(&response);
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!(
+                "MCP server '{}' rejected notification {method}: HTTP {status}",
+                self.server
+            );
+        }
+        Ok(())
+    }
+
+    fn set_protocol_version(&self, version: &str) {
+        *self.protocol_version.lock() = Some(version.to_string());
+    }
+
+    async fn close(&self) {
+        let Some(session) = self.session_id.lock().clone() else {
+            return;
+        };
+        let mut builder = self
+            .client
+            .delete(&self.url)
+            .header(MCP_SESSION_HEADER, session);
+        for (key, value) in &self.headers {
+            builder = builder.header(key, value);
+        }
+        let _ = builder.send().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::duplex;
+
+    /// Spawn an in-process peer that answers each request via `handler`.
+    fn fake_server<F>(handler: F) -> StdioTransport
+    where
+        F: Fn(&JsonRpcMessage) -> Option<Value> + Send + 'static,
+    {
+        let (client_side, server_side) = duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client_side);
+        let (server_read, mut server_write) = tokio::io::split(server_side);
+
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(server_read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let message: JsonRpcMessage = serde_json::from_str(&line).unwrap();
+                if let Some(reply) = handler(&message) {
+                    let mut out = reply.to_string();
+                    out.push('\n');
+                    server_write.write_all(out.as_bytes()).await.unwrap();
+                }
+            }
+        });
+
+        StdioTransport::from_streams("fake", client_read, client_write, Duration::from_secs(2))
+    }
+
+    #[tokio::test]
+    async fn stdio_request_roundtrip() {
+        let transport = fake_server(|msg| {
+            msg.id
+                .clone()
+                .map(|id| success_response(id, json!({"echo": msg.method})))
+        });
+        let result = transport.request("tools/list", json!({})).await.unwrap();
+        assert_eq!(result["echo"], "tools/list");
+    }
+
+    #[tokio::test]
+    async fn stdio_surfaces_json_rpc_errors() {
+        let transport = fake_server(|msg| {
+            msg.id
+                .clone()
+                .map(|id| error_response(id, METHOD_NOT_FOUND, "no such method"))
+        });
+        let err = transport.request("bogus", json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("no such method"));
+    }
+
+    #[tokio::test]
+    async fn stdio_times_out_when_server_is_silent() {
+        let (client_side, _server_side) = duplex(1024);
+        let (read, write) = tokio::io::split(client_side);
+        let transport =
+            StdioTransport::from_streams("silent", read, write, Duration::from_millis(50));
+        let err = transport
+            .request("tools/list", json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(transport.pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stdio_fails_pending_requests_when_server_exits() {
+        let (client_side, server_side) = duplex(1024);
+        let (read, write) = tokio::io::split(client_side);
+        let transport = StdioTransport::from_streams("dying", read, write, Duration::from_secs(5));
+        drop(server_side);
+        let err = transport
+            .request("tools/list", json!({}))
+            .await
+            .unwrap_err();
+        let text = err.to_string();
+        assert!(
+            text.contains("closed") || text.contains("not running") || text.contains("write"),
+            "unexpected error: {text}"
+        );
+    }
+
+    #[test]
+    fn sse_response_is_matched_by_id() {
+        let body = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n\
+                    event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":4,\"result\":{\"ok\":true}}\n\n";
+        let message = find_sse_response(body, 4).unwrap();
+        assert_eq!(message.into_result().unwrap()["ok"], true);
+        assert!(find_sse_response(body, 5).is_none());
+    }
+
+    #[tokio::test]
+    async fn http_transport_tracks_session_and_parses_sse() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("mcp-session-id", "sess-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "data: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"tools\":[]}}\n\n",
+                "text/event-stream",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("mcp-session-id", "sess-1")
+                    .set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {}})),
+            )
+            .mount(&server)
+            .await;
+
+        let config = McpServerConfig {
+            name: "remote".into(),
+            transport: crate::config::McpTransportKind::Http,
+            url: Some(server.uri()),
+            ..McpServerConfig::default()
+        };
+        let transport = HttpTransport::new(&config).unwrap();
+        transport.request("initialize", json!({})).await.unwrap();
+        assert_eq!(transport.session_id.lock().as_deref(), Some("sess-1"));
+
+        let listed = transport.request("tools/list", json!({})).await.unwrap();
+        assert!(listed["tools"].as_array().unwrap().is_empty());
+    }
+}
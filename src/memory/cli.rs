@@ -3,10 +3,9 @@ use super::{
     classify_memory_backend, create_memory_for_migration, effective_memory_backend_name,
     MemoryBackendKind,
 };
+use super::{consolidation, encryption};
 use crate::config::Config;
-#[cfg(feature = "memory-postgres")]
-use anyhow::Context;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use console::style;
 
 /// Handle `zeroclaw memory <subcommand>` CLI commands.
@@ -23,6 +22,36 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
         crate::MemoryCommands::Clear { key, category, yes } => {
             handle_clear(config, key, category, yes).await
         }
+        crate::MemoryCommands::Ingest {
+            path,
+            force,
+            max_tokens,
+        } => handle_ingest(config, &path, force, max_tokens).await,
+        crate::MemoryCommands::Export {
+            output,
+            category,
+            embeddings,
+            backend,
+        } => {
+            handle_export(
+                config,
+                output.as_deref(),
+                category.as_deref(),
+                embeddings,
+                backend.as_deref(),
+            )
+            .await
+        }
+        crate::MemoryCommands::Import {
+            path,
+            skip_existing,
+            backend,
+            dry_run,
+        } => handle_import(config, &path, skip_existing, backend.as_deref(), dry_run).await,
+        crate::MemoryCommands::Consolidate { apply, discard } => {
+            handle_consolidate(config, apply, discard).await
+        }
+        crate::MemoryCommands::Rekey => handle_rekey(config).await,
     }
 }
 
@@ -60,7 +89,8 @@ fn create_cli_memory(config: &Config) -> Result<Box<dyn Memory>> {
                     &sp.table,
                     sp.connect_timeout_secs,
                     sp.tls,
-                )?;
+                )?
+                .with_recall_weights(super::scoring::RecallWeights::from_config(&config.memory));
                 Ok(Box::new(mem))
             }
             #[cfg(not(feature = "memory-postgres"))]
@@ -298,11 +328,276 @@ async fn handle_clear_key(mem: &dyn Memory, key: &str, yes: bool) -> Result<()>
     Ok(())
 }
 
+async fn handle_ingest(
+    config: &Config,
+    path: &std::path::Path,
+    force: bool,
+    max_tokens: Option<usize>,
+) -> Result<()> {
+    // Unlike the other subcommands, ingestion needs the embedding provider so
+    // chunks are searchable by vector recall.
+    let mem = super::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        &config.reliability,
+    )?;
+    if mem.name() == "none" {
+        bail!("Memory backend is 'none' (disabled). Nothing to ingest into.");
+    }
+
+    let options = super::ingest::IngestOptions {
+        max_tokens: max_tokens.unwrap_or(config.memory.chunk_max_tokens),
+        force,
+    };
+    println!(
+        "Ingesting {} into '{}' memory...",
+        path.display(),
+        mem.name()
+    );
+    let report = super::ingest::ingest_path(&*mem, &config.workspace_dir, path, options).await?;
+
+    println!(
+        "{} Ingested {} files ({} chunks), {} unchanged, {} skipped.",
+        style("✓").green().bold(),
+        report.files_ingested,
+        report.chunks_stored,
+        report.files_unchanged,
+        report.files_skipped,
+    );
+    if report.files_removed > 0 || report.chunks_removed > 0 {
+        println!(
+            "  Removed {} stale chunks ({} deleted files).",
+            report.chunks_removed, report.files_removed,
+        );
+    }
+
+    Ok(())
+}
+
+/// Create the full memory backend for export/import, optionally overriding
+/// the configured backend (`--backend`).
+///
+/// Unlike `create_cli_memory` this builds Qdrant and the embedding provider,
+/// and skips hygiene and snapshot hydration so entries move as-is.
+fn create_transfer_memory(config: &Config, backend: Option<&str>) -> Result<Box<dyn Memory>> {
+    let mut memory_config = config.memory.clone();
+    memory_config.hygiene_enabled = false;
+    memory_config.auto_hydrate = false;
+    let mut storage = config.storage.provider.config.clone();
+    if let Some(backend) = backend {
+        memory_config.backend = backend.to_string();
+        // A storage provider override would win; keep only its connection settings.
+        storage.provider.clear();
+    }
+
+    let mem = super::create_memory_with_storage_and_routes(
+        &memory_config,
+        &config.embedding_routes,
+        Some(&storage),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        &config.reliability,
+    )?;
+    if mem.name() == "none" {
+        bail!("Memory backend is 'none' (disabled). Choose a backend with --backend.");
+    }
+    Ok(mem)
+}
+
+async fn handle_export(
+    config: &Config,
+    output: Option<&std::path::Path>,
+    category: Option<&str>,
+    embeddings: bool,
+    backend: Option<&str>,
+) -> Result<()> {
+    let mem = create_transfer_memory(config, backend)?;
+    let options = super::transfer::ExportOptions {
+        category: category.map(parse_category),
+        embeddings,
+    };
+
+    match output {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let mut writer = std::io::BufWriter::new(file);
+            let count = super::transfer::export_jsonl(&*mem, &mut writer, &options).await?;
+            println!(
+                "{} Exported {count} entries from '{}' to {}",
+                style("✓").green().bold(),
+                mem.name(),
+                path.display()
+            );
+        }
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            super::transfer::export_jsonl(&*mem, &mut stdout, &options).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_import(
+    config: &Config,
+    path: &std::path::Path,
+    skip_existing: bool,
+    backend: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mem = create_transfer_memory(config, backend)?;
+    let options = super::transfer::ImportOptions {
+        skip_existing,
+        dry_run,
+    };
+
+    let report =
+        super::transfer::import_jsonl(&*mem, std::io::BufReader::new(file), options).await?;
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    println!(
+        "{} {verb} {} entries from '{}' export into '{}'.",
+        style("✓").green().bold(),
+        report.imported,
+        report.source_backend,
+        mem.name(),
+    );
+    if report.skipped_existing > 0 {
+        println!("  Skipped {} existing keys.", report.skipped_existing);
+    }
+
+    Ok(())
+}
+
+async fn handle_consolidate(config: &Config, apply: bool, discard: bool) -> Result<()> {
+    let workspace = &config.workspace_dir;
+    if discard {
+        if consolidation::discard_pending(workspace)? {
+            println!(
+                "{} Discarded the pending consolidation plan.",
+                style("✓").green().bold()
+            );
+        } else {
+            println!("No pending consolidation plan.");
+        }
+        return Ok(());
+    }
+
+    let mem = create_transfer_memory(config, None)?;
+    let pending = if apply {
+        consolidation::load_pending(workspace)?
+    } else {
+        None
+    };
+    let plan = match pending {
+        Some(plan) => {
+            println!("Applying the pending plan from {}.", plan.created_at);
+            plan
+        }
+        None => {
+            let engine = consolidation::create_consolidation_engine(config)?;
+            let since = consolidation::last_consolidated_turn(workspace)?;
+            engine.plan(&*mem, since.as_deref()).await?
+        }
+    };
+
+    if !apply {
+        consolidation::save_pending(workspace, &plan)?;
+        println!("{}", consolidation::render_report(&plan, None));
+        if !plan.changes.is_empty() {
+            println!("Review the changes above, then run `zeroclaw memory consolidate --apply`.");
+        }
+        return Ok(());
+    }
+
+    let (report, path) = consolidation::apply_and_record(&*mem, workspace, &plan).await?;
+    println!("{}", consolidation::render_report(&plan, Some(&report)));
+    println!(
+        "{} Applied {} changes ({} skipped). Report: {}",
+        style("✓").green().bold(),
+        report.applied,
+        report.skipped.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Re-encrypt every store under a freshly rotated key, or rewrite it as
+/// plaintext when `encrypt_at_rest` has been turned off. Old keys are only
+/// dropped once every store has been rewritten.
+async fn handle_rekey(config: &Config) -> Result<()> {
+    let Some(cipher) = encryption::runtime_cipher()? else {
+        println!("Memory encryption is off and no keyring exists. Nothing to rekey.");
+        return Ok(());
+    };
+    let encrypting = cipher.encrypts_writes();
+    if encrypting {
+        let id = cipher.rotate()?;
+        println!("Rotated memory key (active key: {id}).");
+    } else {
+        println!("encrypt_at_rest is off — decrypting stored data.");
+    }
+
+    let workspace = &config.workspace_dir;
+    let memory_db = workspace.join("memory").join("brain.db");
+    if memory_db.exists() {
+        let mem = super::SqliteMemory::new(workspace)?.with_cipher(Some(cipher.clone()));
+        println!("  Memories:         {}", mem.rekey().await?);
+    }
+
+    if workspace.join("sessions").join("sessions.db").exists() {
+        let store = crate::channels::sessions::SessionStore::open(workspace)?
+            .with_cipher(Some(cipher.clone()));
+        println!("  Session turns:    {}", store.rekey()?);
+    }
+
+    if workspace.join("memory").join("response_cache.db").exists() {
+        // Cached responses are disposable; drop them rather than rewrite them.
+        let cache = super::ResponseCache::new(workspace, 0, 0)?;
+        println!("  Cached responses: {} cleared", cache.clear()?);
+    }
+
+    if super::snapshot::rekey_snapshot(workspace, Some(&cipher))? {
+        println!("  Snapshot:         rewritten");
+    }
+    println!(
+        "  Cron outputs:     {}",
+        crate::cron::rekey_outputs(config, Some(&cipher))?
+    );
+    println!(
+        "  Cost records:     {}",
+        crate::cost::tracker::rekey_storage(workspace, Some(&cipher))?
+    );
+
+    if encrypting {
+        let retired = cipher.retire_inactive()?;
+        println!(
+            "{} Re-encrypted all stores; retired {retired} old key(s).",
+            style("✓").green().bold()
+        );
+    } else {
+        cipher.remove_keyring()?;
+        println!(
+            "{} Decrypted all stores and removed the memory keyring.",
+            style("✓").green().bold()
+        );
+    }
+
+    Ok(())
+}
+
 fn parse_category(s: &str) -> MemoryCategory {
     match s.trim().to_ascii_lowercase().as_str() {
         "core" => MemoryCategory::Core,
         "daily" => MemoryCategory::Daily,
         "conversation" => MemoryCategory::Conversation,
+        "knowledge" => MemoryCategory::Knowledge,
         other => MemoryCategory::Custom(other.to_string()),
     }
 }
@@ -325,6 +620,7 @@ mod tests {
         assert_eq!(parse_category("core"), MemoryCategory::Core);
         assert_eq!(parse_category("daily"), MemoryCategory::Daily);
         assert_eq!(parse_category("conversation"), MemoryCategory::Conversation);
+        assert_eq!(parse_category("knowledge"), MemoryCategory::Knowledge);
         assert_eq!(parse_category("CORE"), MemoryCategory::Core);
         assert_eq!(parse_category("  Daily  "), MemoryCategory::Daily);
     }
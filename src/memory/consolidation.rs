@@ -0,0 +1,1301 @@
+// Memory consolidation — `zeroclaw memory consolidate`.
+//
+// Auto-save stores raw user messages as `conversation` memories. The
+// consolidation engine turns them into durable `core` facts:
+//
+// 1. New conversation turns (since the last applied run) are grouped by
+//    namespace and sent to the model in batches with a JSON-schema request
+//    that returns atomic facts, an importance and the turns they came from.
+// 2. Each fact is embedded and compared with the namespace's `core` entries
+//    (and facts planned earlier in the run). Near-identical facts are merged
+//    into the existing entry; related ones are shown to the model, which
+//    decides whether the fact duplicates, updates or is distinct from it.
+// 3. The result is a `ConsolidationPlan` — adds, merges and supersessions
+//    with their source turns — rendered as a diff report. Nothing is written
+//    until the plan is applied; stale changes (the entry moved on since
+//    planning) are skipped.
+//
+// Without an embedding provider, keyword recall picks the candidates and
+// the model makes every call. Pending plans, reports and the run state live
+// in `memory/consolidation/` and are encrypted with `encrypt_at_rest`.
+
+use super::embeddings::EmbeddingProvider;
+use super::encryption::{self, MemoryCipher};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
+use super::vector;
+use crate::config::{Config, MemoryConsolidationConfig};
+use crate::providers::{self, structured, ChatMessage, ChatRequest, Provider, ResponseFormat};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Directory (under `memory/`) holding plans, reports and state.
+const CONSOLIDATION_DIR: &str = "consolidation";
+const PENDING_FILE: &str = "pending.json";
+const STATE_FILE: &str = "state.json";
+
+/// Conversation turns sent to the model per extraction call.
+const EXTRACTION_BATCH_TURNS: usize = 20;
+
+/// Longest turn or memory (in chars) shown to the model.
+const PROMPT_TEXT_MAX_CHARS: usize = 2000;
+
+/// Keyword-recall candidates per fact when no embeddings are available.
+const KEYWORD_CANDIDATES: usize = 3;
+
+/// Source keys listed in an entry's `source` metadata before eliding.
+const MAX_SOURCES_IN_METADATA: usize = 5;
+
+/// Tag added to every entry written by consolidation.
+pub const CONSOLIDATED_TAG: &str = "consolidated";
+
+const EXTRACTION_SYSTEM_PROMPT: &str = "You extract durable facts from a user's messages \
+to an assistant. Return atomic facts worth remembering long-term: preferences, personal \
+details, decisions, ongoing projects and standing instructions. Write one self-contained \
+fact per item in the third person (\"The user ...\"). Skip greetings, questions, one-off \
+requests and small talk. For each fact give a short snake_case key, an importance from 0 \
+(trivia) to 1 (critical) and the numbers of the messages it came from. Return an empty \
+list when nothing is worth keeping.";
+
+const RELATION_SYSTEM_PROMPT: &str = "You compare newly learned facts with stored memories. \
+For each numbered pair answer \"duplicate\" when the new fact says the same thing as the \
+stored memory, \"updates\" when it changes or contradicts it (the new fact is more recent \
+and should replace it), or \"distinct\" when they are about different things.";
+
+/// What applying a change does to memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    /// Store a new `core` entry.
+    Add,
+    /// Keep the existing entry; raise its importance and note the new sources.
+    Merge,
+    /// Replace the existing entry's content with the newer fact.
+    Supersede,
+}
+
+/// One planned change to a `core` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidationChange {
+    pub action: ChangeAction,
+    pub namespace: String,
+    pub key: String,
+    /// Content of the existing entry when planned (merge/supersede).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    /// Content after the change; unchanged for merges.
+    pub content: String,
+    /// Extracted facts folded into this change.
+    pub facts: Vec<String>,
+    pub importance: f64,
+    /// Embedding similarity to the existing entry, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+    /// Keys of the conversation turns the facts came from.
+    pub sources: Vec<String>,
+}
+
+/// The reviewable result of a consolidation run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidationPlan {
+    pub created_at: String,
+    /// Conversation turns examined.
+    pub turns: usize,
+    /// Timestamp of the newest examined turn; the next run starts after it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub through: Option<String>,
+    pub changes: Vec<ConsolidationChange>,
+}
+
+impl ConsolidationPlan {
+    /// Number of changes with `action`.
+    pub fn count(&self, action: ChangeAction) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+
+    /// One-line summary, e.g. "12 turns: 3 added, 1 merged, 0 superseded".
+    pub fn summary(&self) -> String {
+        format!(
+            "{} turns: {} added, {} merged, {} superseded",
+            self.turns,
+            self.count(ChangeAction::Add),
+            self.count(ChangeAction::Merge),
+            self.count(ChangeAction::Supersede),
+        )
+    }
+}
+
+/// Outcome of applying a plan.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ApplyReport {
+    pub applied: usize,
+    /// `(namespace, key, reason)` of changes that were not applied.
+    pub skipped: Vec<(String, String, String)>,
+}
+
+/// A fact as returned by the extraction call.
+#[derive(Debug, Clone, PartialEq)]
+struct ExtractedFact {
+    key: String,
+    content: String,
+    importance: f64,
+    sources: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ExtractionReply {
+    facts: Vec<ExtractionItem>,
+}
+
+#[derive(Deserialize)]
+struct ExtractionItem {
+    key: String,
+    fact: String,
+    importance: f64,
+    #[serde(default)]
+    turns: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Relation {
+    Duplicate,
+    Updates,
+    Distinct,
+}
+
+#[derive(Deserialize)]
+struct RelationReply {
+    relations: Vec<RelationItem>,
+}
+
+#[derive(Deserialize)]
+struct RelationItem {
+    pair: usize,
+    relation: Relation,
+}
+
+/// An existing `core` entry or a fact added earlier in the run.
+struct Candidate {
+    key: String,
+    content: String,
+    vector: Option<Vec<f32>>,
+    /// Index into the plan's changes when this is a planned add.
+    planned: Option<usize>,
+}
+
+/// A fact waiting for the model to judge its relation to a candidate.
+struct PendingPair {
+    fact: ExtractedFact,
+    vector: Option<Vec<f32>>,
+    candidate: usize,
+    similarity: Option<f32>,
+}
+
+/// Extracts facts from conversation turns and plans their consolidation.
+pub struct ConsolidationEngine {
+    provider: Box<dyn Provider>,
+    model: String,
+    embedder: Arc<dyn EmbeddingProvider>,
+    duplicate_threshold: f32,
+    related_threshold: f32,
+    max_turns: usize,
+}
+
+impl ConsolidationEngine {
+    pub fn new(
+        provider: Box<dyn Provider>,
+        model: &str,
+        embedder: Arc<dyn EmbeddingProvider>,
+        config: &MemoryConsolidationConfig,
+    ) -> Self {
+        #[allow(clippy::cast_possible_truncation)]
+        let threshold = |value: f64| value.clamp(0.0, 1.0) as f32;
+        let duplicate_threshold = threshold(config.duplicate_threshold);
+        Self {
+            provider,
+            model: model.to_string(),
+            embedder,
+            duplicate_threshold,
+            related_threshold: threshold(config.related_threshold).min(duplicate_threshold),
+            max_turns: config.max_turns.max(1),
+        }
+    }
+
+    /// Plan the consolidation of conversation turns newer than `since`
+    /// (RFC 3339). Reads memory but never writes it.
+    pub async fn plan(
+        &self,
+        memory: &dyn Memory,
+        since: Option<&str>,
+    ) -> Result<ConsolidationPlan> {
+        let since = since.and_then(parse_timestamp);
+        let mut turns: Vec<MemoryEntry> = memory
+            .export_entries()
+            .await?
+            .into_iter()
+            .filter(|entry| entry.category == MemoryCategory::Conversation)
+            .filter(|entry| match (since, parse_timestamp(&entry.timestamp)) {
+                (Some(since), Some(ts)) => ts > since,
+                _ => true,
+            })
+            .collect();
+        turns.sort_by_key(|entry| parse_timestamp(&entry.timestamp));
+        turns.truncate(self.max_turns);
+
+        let through = turns.last().map(|entry| entry.timestamp.clone());
+        let turn_count = turns.len();
+        let mut by_namespace: BTreeMap<String, Vec<MemoryEntry>> = BTreeMap::new();
+        for turn in turns {
+            by_namespace
+                .entry(turn.namespace.clone())
+                .or_default()
+                .push(turn);
+        }
+
+        let mut changes = Vec::new();
+        for (namespace, turns) in &by_namespace {
+            let mut facts = Vec::new();
+            for batch in turns.chunks(EXTRACTION_BATCH_TURNS) {
+                facts.extend(self.extract_facts(batch).await?);
+            }
+            if !facts.is_empty() {
+                changes.extend(self.reconcile(memory, namespace, facts).await?);
+            }
+        }
+
+        Ok(ConsolidationPlan {
+            created_at: Utc::now().to_rfc3339(),
+            turns: turn_count,
+            through,
+            changes,
+        })
+    }
+
+    async fn extract_facts(&self, turns: &[MemoryEntry]) -> Result<Vec<ExtractedFact>> {
+        let mut prompt = String::from("Messages from the user:\n");
+        for (index, turn) in turns.iter().enumerate() {
+            let _ = writeln!(
+                prompt,
+                "[{index}] ({}) {}",
+                turn.timestamp,
+                prompt_text(&turn.content)
+            );
+        }
+
+        let reply: ExtractionReply = self
+            .structured_call(
+                EXTRACTION_SYSTEM_PROMPT,
+                prompt,
+                ResponseFormat::json_schema("memory_facts", extraction_schema()),
+            )
+            .await
+            .context("fact extraction failed")?;
+
+        Ok(reply
+            .facts
+            .into_iter()
+            .filter(|item| !item.fact.trim().is_empty())
+            .map(|item| ExtractedFact {
+                key: slugify_key(&item.key, &item.fact),
+                content: item.fact.trim().to_string(),
+                importance: if item.importance.is_finite() {
+                    item.importance.clamp(0.0, 1.0)
+                } else {
+                    super::traits::DEFAULT_IMPORTANCE
+                },
+                sources: item
+                    .turns
+                    .iter()
+                    .filter_map(|&index| turns.get(index).map(|turn| turn.key.clone()))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Match facts against the namespace's `core` entries and plan changes.
+    async fn reconcile(
+        &self,
+        memory: &dyn Memory,
+        namespace: &str,
+        facts: Vec<ExtractedFact>,
+    ) -> Result<Vec<ConsolidationChange>> {
+        let namespaces = [namespace.to_string()];
+        let existing = memory
+            .list_in(&namespaces, Some(&MemoryCategory::Core), None)
+            .await?;
+        let use_vectors = self.embedder.dimensions() > 0;
+
+        let mut candidates = Vec::with_capacity(existing.len());
+        let mut to_embed = Vec::new();
+        for entry in existing {
+            let stored = if use_vectors {
+                memory
+                    .embedding(namespace, &entry.key)
+                    .await?
+                    .filter(|v| v.len() == self.embedder.dimensions())
+            } else {
+                None
+            };
+            if use_vectors && stored.is_none() {
+                to_embed.push(candidates.len());
+            }
+            candidates.push(Candidate {
+                key: entry.key,
+                content: entry.content,
+                vector: stored,
+                planned: None,
+            });
+        }
+        if !to_embed.is_empty() {
+            let texts: Vec<&str> = to_embed
+                .iter()
+                .map(|&i| candidates[i].content.as_str())
+                .collect();
+            let vectors = self.embedder.embed(&texts).await?;
+            for (&i, vector) in to_embed.iter().zip(vectors) {
+                candidates[i].vector = Some(vector);
+            }
+        }
+
+        let fact_vectors: Vec<Option<Vec<f32>>> = if use_vectors {
+            let texts: Vec<&str> = facts.iter().map(|f| f.content.as_str()).collect();
+            self.embedder
+                .embed(&texts)
+                .await?
+                .into_iter()
+                .map(Some)
+                .collect()
+        } else {
+            vec![None; facts.len()]
+        };
+
+        let mut plan = PlanBuilder::new(namespace, &candidates);
+        let mut pending = Vec::new();
+        for (fact, vector) in facts.into_iter().zip(fact_vectors) {
+            if let Some(vector) = &vector {
+                let best = candidates
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, c)| {
+                        c.vector
+                            .as_ref()
+                            .map(|v| (i, vector::cosine_similarity(vector, v)))
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+                match best {
+                    Some((i, sim)) if sim >= self.duplicate_threshold => {
+                        plan.merge(&candidates[i], fact, Some(sim));
+                    }
+                    Some((i, sim)) if sim >= self.related_threshold => pending.push(PendingPair {
+                        fact,
+                        vector: Some(vector.clone()),
+                        candidate: i,
+                        similarity: Some(sim),
+                    }),
+                    _ => plan.add(&mut candidates, fact, Some(vector.clone())),
+                }
+            } else {
+                let recalled = memory
+                    .recall_in(&namespaces, &fact.content, KEYWORD_CANDIDATES, None, &[])
+                    .await?;
+                let candidate = recalled
+                    .iter()
+                    .filter(|entry| entry.category == MemoryCategory::Core)
+                    .find_map(|entry| candidates.iter().position(|c| c.key == entry.key));
+                match candidate {
+                    Some(candidate) => pending.push(PendingPair {
+                        fact,
+                        vector: None,
+                        candidate,
+                        similarity: None,
+                    }),
+                    None => plan.add(&mut candidates, fact, None),
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            let relations = self.classify(&candidates, &pending).await?;
+            for (pair, relation) in pending.into_iter().zip(relations) {
+                match relation {
+                    Relation::Duplicate => {
+                        plan.merge(&candidates[pair.candidate], pair.fact, pair.similarity);
+                    }
+                    Relation::Updates => {
+                        plan.supersede(&candidates[pair.candidate], pair.fact, pair.similarity);
+                    }
+                    Relation::Distinct => plan.add(&mut candidates, pair.fact, pair.vector),
+                }
+            }
+        }
+
+        Ok(plan.changes)
+    }
+
+    /// Ask the model how each pending fact relates to its candidate. Pairs
+    /// the model leaves out are treated as distinct.
+    async fn classify(
+        &self,
+        candidates: &[Candidate],
+        pending: &[PendingPair],
+    ) -> Result<Vec<Relation>> {
+        let mut prompt = String::new();
+        for (index, pair) in pending.iter().enumerate() {
+            let _ = write!(
+                prompt,
+                "[{index}]\nStored memory: {}\nNew fact: {}\n\n",
+                prompt_text(&candidates[pair.candidate].content),
+                prompt_text(&pair.fact.content)
+            );
+        }
+
+        let reply: RelationReply = self
+            .structured_call(
+                RELATION_SYSTEM_PROMPT,
+                prompt,
+                ResponseFormat::json_schema("memory_relations", relation_schema()),
+            )
+            .await
+            .context("fact comparison failed")?;
+
+        let mut relations = vec![Relation::Distinct; pending.len()];
+        for item in reply.relations {
+            if let Some(slot) = relations.get_mut(item.pair) {
+                *slot = item.relation;
+            }
+        }
+        Ok(relations)
+    }
+
+    async fn structured_call<T: serde::de::DeserializeOwned>(
+        &self,
+        system: &str,
+        prompt: String,
+        format: ResponseFormat,
+    ) -> Result<T> {
+        let messages = [ChatMessage::system(system), ChatMessage::user(prompt)];
+        let response = structured::chat_with_response_format(
+            self.provider.as_ref(),
+            ChatRequest {
+                messages: &messages,
+                response_format: Some(&format),
+                ..ChatRequest::default()
+            },
+            &self.model,
+            0.0,
+        )
+        .await?;
+        serde_json::from_str(response.text_or_empty()).context("unexpected reply shape")
+    }
+}
+
+/// Accumulates changes for one namespace, folding facts that land on the
+/// same entry into a single change.
+struct PlanBuilder {
+    namespace: String,
+    changes: Vec<ConsolidationChange>,
+    by_key: HashMap<String, usize>,
+    taken_keys: HashSet<String>,
+}
+
+impl PlanBuilder {
+    fn new(namespace: &str, existing: &[Candidate]) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            changes: Vec::new(),
+            by_key: HashMap::new(),
+            taken_keys: existing.iter().map(|c| c.key.clone()).collect(),
+        }
+    }
+
+    fn add(
+        &mut self,
+        candidates: &mut Vec<Candidate>,
+        fact: ExtractedFact,
+        vector: Option<Vec<f32>>,
+    ) {
+        let mut key = fact.key.clone();
+        let mut suffix = 2;
+        while self.taken_keys.contains(&key) {
+            key = format!("{}_{suffix}", fact.key);
+            suffix += 1;
+        }
+        self.taken_keys.insert(key.clone());
+        self.by_key.insert(key.clone(), self.changes.len());
+        candidates.push(Candidate {
+            key: key.clone(),
+            content: fact.content.clone(),
+            vector,
+            planned: Some(self.changes.len()),
+        });
+        self.changes.push(ConsolidationChange {
+            action: ChangeAction::Add,
+            namespace: self.namespace.clone(),
+            key,
+            previous: None,
+            content: fact.content.clone(),
+            facts: vec![fact.content],
+            importance: fact.importance,
+            similarity: None,
+            sources: fact.sources,
+        });
+    }
+
+    fn merge(&mut self, candidate: &Candidate, fact: ExtractedFact, similarity: Option<f32>) {
+        let index = candidate
+            .planned
+            .or_else(|| self.by_key.get(&candidate.key).copied());
+        if let Some(index) = index {
+            fold_fact(&mut self.changes[index], fact);
+            return;
+        }
+        self.by_key
+            .insert(candidate.key.clone(), self.changes.len());
+        self.changes.push(ConsolidationChange {
+            action: ChangeAction::Merge,
+            namespace: self.namespace.clone(),
+            key: candidate.key.clone(),
+            previous: Some(candidate.content.clone()),
+            content: candidate.content.clone(),
+            facts: vec![fact.content],
+            importance: fact.importance,
+            similarity,
+            sources: fact.sources,
+        });
+    }
+
+    fn supersede(&mut self, candidate: &Candidate, fact: ExtractedFact, similarity: Option<f32>) {
+        let index = candidate
+            .planned
+            .or_else(|| self.by_key.get(&candidate.key).copied());
+        if let Some(index) = index {
+            // A later fact wins over an earlier one in the same run.
+            let change = &mut self.changes[index];
+            change.content.clone_from(&fact.content);
+            if change.action == ChangeAction::Merge {
+                change.action = ChangeAction::Supersede;
+                change.similarity = similarity;
+            }
+            fold_fact(change, fact);
+            return;
+        }
+        self.by_key
+            .insert(candidate.key.clone(), self.changes.len());
+        self.changes.push(ConsolidationChange {
+            action: ChangeAction::Supersede,
+            namespace: self.namespace.clone(),
+            key: candidate.key.clone(),
+            previous: Some(candidate.content.clone()),
+            content: fact.content.clone(),
+            facts: vec![fact.content],
+            importance: fact.importance,
+            similarity,
+            sources: fact.sources,
+        });
+    }
+}
+
+fn fold_fact(change: &mut ConsolidationChange, fact: ExtractedFact) {
+    if !change.facts.contains(&fact.content) {
+        change.facts.push(fact.content);
+    }
+    change.importance = change.importance.max(fact.importance);
+    for source in fact.sources {
+        if !change.sources.contains(&source) {
+            change.sources.push(source);
+        }
+    }
+}
+
+/// Write a plan to memory. Merges and supersessions whose entry changed
+/// since planning, and adds whose key has since been taken, are skipped.
+pub async fn apply_plan(memory: &dyn Memory, plan: &ConsolidationPlan) -> Result<ApplyReport> {
+    let mut report = ApplyReport::default();
+    for change in &plan.changes {
+        let current = memory.get_in(&change.namespace, &change.key).await?;
+        let stale = match (&change.action, &current) {
+            (ChangeAction::Add, Some(_)) => Some("key already exists"),
+            (ChangeAction::Merge | ChangeAction::Supersede, None) => Some("entry was deleted"),
+            (ChangeAction::Merge | ChangeAction::Supersede, Some(entry))
+                if Some(&entry.content) != change.previous.as_ref() =>
+            {
+                Some("entry changed since planning")
+            }
+            _ => None,
+        };
+        if let Some(reason) = stale {
+            report.skipped.push((
+                change.namespace.clone(),
+                change.key.clone(),
+                reason.to_string(),
+            ));
+            continue;
+        }
+
+        let previous = current.map(|entry| entry.metadata).unwrap_or_default();
+        let mut metadata = MemoryMetadata {
+            importance: Some(previous.importance.unwrap_or(0.0).max(change.importance)),
+            ..previous.without_access_count()
+        };
+        if !metadata.tags.iter().any(|tag| tag == CONSOLIDATED_TAG) {
+            metadata.tags.push(CONSOLIDATED_TAG.to_string());
+        }
+        if change.action != ChangeAction::Merge || metadata.source.is_none() {
+            metadata.source = Some(provenance(&change.sources));
+        }
+
+        memory
+            .store_in(
+                &change.namespace,
+                &change.key,
+                &change.content,
+                MemoryCategory::Core,
+                None,
+                &metadata,
+            )
+            .await?;
+        report.applied += 1;
+    }
+    Ok(report)
+}
+
+/// `source` metadata recording which turns a fact came from.
+fn provenance(sources: &[String]) -> String {
+    let mut listed: Vec<&str> = sources
+        .iter()
+        .take(MAX_SOURCES_IN_METADATA)
+        .map(String::as_str)
+        .collect();
+    let more = sources.len().saturating_sub(MAX_SOURCES_IN_METADATA);
+    let more_label = format!("+{more} more");
+    if more > 0 {
+        listed.push(&more_label);
+    }
+    if listed.is_empty() {
+        "consolidation".to_string()
+    } else {
+        format!("consolidation:{}", listed.join(","))
+    }
+}
+
+/// Render a plan as a markdown diff report. With `applied`, skipped changes
+/// are marked.
+pub fn render_report(plan: &ConsolidationPlan, applied: Option<&ApplyReport>) -> String {
+    let mut out = String::new();
+    let status = if applied.is_some() {
+        "applied"
+    } else {
+        "pending review"
+    };
+    let _ = writeln!(out, "# Memory consolidation ({status})\n");
+    let _ = writeln!(out, "- Planned: {}", plan.created_at);
+    if let Some(through) = &plan.through {
+        let _ = writeln!(out, "- Turns through: {through}");
+    }
+    let _ = writeln!(out, "- {}", plan.summary());
+    if let Some(applied) = applied {
+        let _ = writeln!(
+            out,
+            "- Applied: {}, skipped: {}",
+            applied.applied,
+            applied.skipped.len()
+        );
+    }
+
+    let mut namespace = None;
+    for change in &plan.changes {
+        if namespace != Some(&change.namespace) {
+            let _ = writeln!(out, "\n## Namespace `{}`", change.namespace);
+            namespace = Some(&change.namespace);
+        }
+        let similarity = change
+            .similarity
+            .map(|s| format!(", similarity {s:.2}"))
+            .unwrap_or_default();
+        let skipped = applied.and_then(|report| {
+            report
+                .skipped
+                .iter()
+                .find(|(ns, key, _)| *ns == change.namespace && *key == change.key)
+        });
+        let skipped = skipped
+            .map(|(_, _, reason)| format!(" — skipped: {reason}"))
+            .unwrap_or_default();
+        let (marker, label) = match change.action {
+            ChangeAction::Add => ('+', "add"),
+            ChangeAction::Merge => ('=', "merge"),
+            ChangeAction::Supersede => ('~', "supersede"),
+        };
+
+        let _ = writeln!(
+            out,
+            "\n{marker} `{}` ({label}, importance {:.2}{similarity}){skipped}\n```diff",
+            change.key, change.importance
+        );
+        match change.action {
+            ChangeAction::Add => {
+                let _ = writeln!(out, "+ {}", change.content);
+            }
+            ChangeAction::Merge => {
+                let _ = writeln!(out, "  {}", change.content);
+                for fact in &change.facts {
+                    let _ = writeln!(out, "# also stated: {fact}");
+                }
+            }
+            ChangeAction::Supersede => {
+                if let Some(previous) = &change.previous {
+                    let _ = writeln!(out, "- {previous}");
+                }
+                let _ = writeln!(out, "+ {}", change.content);
+            }
+        }
+        let _ = writeln!(out, "```");
+        if !change.sources.is_empty() {
+            let _ = writeln!(out, "Sources: {}", change.sources.join(", "));
+        }
+    }
+    if plan.changes.is_empty() {
+        let _ = writeln!(out, "\nNo changes.");
+    }
+    out
+}
+
+// ── Workspace files ──────────────────────────────────────────
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ConsolidationState {
+    /// Timestamp of the newest turn covered by an applied plan.
+    last_turn_at: Option<String>,
+}
+
+fn consolidation_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("memory").join(CONSOLIDATION_DIR)
+}
+
+/// Write `text`, encrypted when `encrypt_at_rest` is on.
+fn write_sealed(path: &Path, text: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let cipher = encryption::runtime_cipher()?;
+    let output = if cipher.as_deref().is_some_and(MemoryCipher::encrypts_writes) {
+        encryption::encrypt_field(cipher.as_deref(), text)? + "\n"
+    } else {
+        text.to_string()
+    };
+    fs::write(path, output).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn read_sealed(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if MemoryCipher::is_encrypted(raw.trim()) {
+        let cipher = encryption::runtime_cipher()?;
+        return encryption::decrypt_field(cipher.as_deref(), raw.trim()).map(Some);
+    }
+    Ok(Some(raw))
+}
+
+/// Timestamp of the newest turn already consolidated, if any.
+pub fn last_consolidated_turn(workspace_dir: &Path) -> Result<Option<String>> {
+    let path = consolidation_dir(workspace_dir).join(STATE_FILE);
+    let Some(raw) = read_sealed(&path)? else {
+        return Ok(None);
+    };
+    let state: ConsolidationState = serde_json::from_str(&raw)
+        .with_context(|| format!("Consolidation state {} is corrupt", path.display()))?;
+    Ok(state.last_turn_at)
+}
+
+fn record_consolidated_turn(workspace_dir: &Path, through: &str) -> Result<()> {
+    let state = ConsolidationState {
+        last_turn_at: Some(through.to_string()),
+    };
+    write_sealed(
+        &consolidation_dir(workspace_dir).join(STATE_FILE),
+        &serde_json::to_string_pretty(&state)?,
+    )
+}
+
+/// Save `plan` as the pending plan awaiting review.
+pub fn save_pending(workspace_dir: &Path, plan: &ConsolidationPlan) -> Result<()> {
+    write_sealed(
+        &consolidation_dir(workspace_dir).join(PENDING_FILE),
+        &serde_json::to_string_pretty(plan)?,
+    )
+}
+
+/// The pending plan, if one is awaiting review.
+pub fn load_pending(workspace_dir: &Path) -> Result<Option<ConsolidationPlan>> {
+    let path = consolidation_dir(workspace_dir).join(PENDING_FILE);
+    read_sealed(&path)?
+        .map(|raw| {
+            serde_json::from_str(&raw)
+                .with_context(|| format!("Pending plan {} is corrupt", path.display()))
+        })
+        .transpose()
+}
+
+/// Remove the pending plan. Returns whether one existed.
+pub fn discard_pending(workspace_dir: &Path) -> Result<bool> {
+    let path = consolidation_dir(workspace_dir).join(PENDING_FILE);
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(true)
+}
+
+/// Apply `plan`, write its report to `memory/consolidation/<time>.md`,
+/// advance the run state and drop the pending plan.
+pub async fn apply_and_record(
+    memory: &dyn Memory,
+    workspace_dir: &Path,
+    plan: &ConsolidationPlan,
+) -> Result<(ApplyReport, PathBuf)> {
+    let report = apply_plan(memory, plan).await?;
+    let path =
+        consolidation_dir(workspace_dir).join(format!("{}.md", Utc::now().format("%Y%m%d-%H%M%S")));
+    write_sealed(&path, &render_report(plan, Some(&report)))?;
+    if let Some(through) = &plan.through {
+        record_consolidated_turn(workspace_dir, through)?;
+    }
+    discard_pending(workspace_dir)?;
+    Ok((report, path))
+}
+
+// ── Construction ─────────────────────────────────────────────
+
+/// Build the engine from config: the extraction model (`model`, else
+/// `default_model`) through the routed provider, and the memory embedder.
+pub fn create_consolidation_engine(config: &Config) -> Result<ConsolidationEngine> {
+    let default_model = config
+        .default_model
+        .as_deref()
+        .unwrap_or("anthropic/claude-sonnet-4");
+    let model = config
+        .memory
+        .consolidation
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(default_model);
+    let provider = providers::create_routed_provider(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        default_model,
+    )?;
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::from(super::create_embedder(
+        &config.memory,
+        &config.embedding_routes,
+        config.api_key.as_deref(),
+        &config.reliability,
+    ));
+    Ok(ConsolidationEngine::new(
+        provider,
+        model,
+        embedder,
+        &config.memory.consolidation,
+    ))
+}
+
+/// Run for the nightly consolidation cron job: plan, then apply when
+/// `auto_apply` is set or leave the plan pending for review. Returns a
+/// summary for the job output.
+pub async fn run_scheduled(config: &Config) -> Result<String> {
+    let memory = super::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        &config.reliability,
+    )?;
+    let engine = create_consolidation_engine(config)?;
+    let since = last_consolidated_turn(&config.workspace_dir)?;
+    let plan = engine.plan(memory.as_ref(), since.as_deref()).await?;
+
+    if config.memory.consolidation.auto_apply {
+        let (report, path) =
+            apply_and_record(memory.as_ref(), &config.workspace_dir, &plan).await?;
+        return Ok(format!(
+            "memory consolidation applied ({}; {} skipped), report: {}",
+            plan.summary(),
+            report.skipped.len(),
+            path.display()
+        ));
+    }
+
+    save_pending(&config.workspace_dir, &plan)?;
+    Ok(format!(
+        "memory consolidation planned ({}); review with `zeroclaw memory consolidate` and apply with `--apply`",
+        plan.summary()
+    ))
+}
+
+// ── Helpers ──────────────────────────────────────────────────
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+fn prompt_text(text: &str) -> String {
+    text.chars()
+        .take(PROMPT_TEXT_MAX_CHARS)
+        .map(|c| if c == '\n' { ' ' } else { c })
+        .collect()
+}
+
+/// Lowercase `[a-z0-9_]` key, derived from the fact when the model's key is empty.
+fn slugify_key(key: &str, fact: &str) -> String {
+    let source = if key.trim().is_empty() { fact } else { key };
+    let mut slug = String::new();
+    for c in source.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('_') && !slug.is_empty() {
+            slug.push('_');
+        }
+        if slug.len() >= 48 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    if slug.is_empty() {
+        "fact".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn extraction_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "facts": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string" },
+                        "fact": { "type": "string" },
+                        "importance": { "type": "number", "minimum": 0, "maximum": 1 },
+                        "turns": { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+                    },
+                    "required": ["key", "fact", "importance", "turns"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["facts"],
+        "additionalProperties": false
+    })
+}
+
+fn relation_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "relations": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "pair": { "type": "integer", "minimum": 0 },
+                        "relation": { "type": "string", "enum": ["duplicate", "updates", "distinct"] }
+                    },
+                    "required": ["pair", "relation"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["relations"],
+        "additionalProperties": false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use async_trait::async_trait;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use tempfile::TempDir;
+
+    /// Replies to extraction and comparison calls with fixed JSON.
+    struct ScriptedProvider {
+        facts: &'static str,
+        relations: &'static str,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(if message.contains("Stored memory:") {
+                self.relations.to_string()
+            } else {
+                self.facts.to_string()
+            })
+        }
+    }
+
+    /// Bag-of-words embedding: texts sharing words are similar.
+    struct WordEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for WordEmbedding {
+        fn name(&self) -> &str {
+            "words"
+        }
+
+        fn dimensions(&self) -> usize {
+            512
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0; 512];
+                    for word in text.split(|c: char| !c.is_alphanumeric()) {
+                        if word.is_empty() {
+                            continue;
+                        }
+                        let mut hasher = DefaultHasher::new();
+                        word.to_lowercase().hash(&mut hasher);
+                        #[allow(clippy::cast_possible_truncation)]
+                        let slot = (hasher.finish() % 512) as usize;
+                        v[slot] = 1.0;
+                    }
+                    v
+                })
+                .collect())
+        }
+    }
+
+    const FACTS: &str = r#"{"facts": [
+        {"key": "editor", "fact": "The user prefers the Helix editor", "importance": 0.4, "turns": [0]},
+        {"key": "home city", "fact": "The user lives in Porto", "importance": 0.8, "turns": [1]},
+        {"key": "dog_name", "fact": "The user has a dog named Rex", "importance": 0.6, "turns": [2, 9]}
+    ]}"#;
+
+    fn engine(relations: &'static str) -> ConsolidationEngine {
+        ConsolidationEngine::new(
+            Box::new(ScriptedProvider {
+                facts: FACTS,
+                relations,
+            }),
+            "test-model",
+            Arc::new(WordEmbedding),
+            &MemoryConsolidationConfig::default(),
+        )
+    }
+
+    async fn seeded_memory(tmp: &TempDir) -> SqliteMemory {
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store(
+            "editor",
+            "The user prefers the Helix editor",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "home_city",
+            "The user lives in Lisbon",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        for (key, turn) in [
+            ("user_msg_1", "helix is still my favourite editor"),
+            ("user_msg_2", "I moved to Porto last month"),
+            ("user_msg_3", "walking Rex, my dog, brb"),
+        ] {
+            mem.store(key, turn, MemoryCategory::Conversation, None)
+                .await
+                .unwrap();
+        }
+        mem
+    }
+
+    #[tokio::test]
+    async fn plan_merges_duplicates_supersedes_updates_and_adds_new_facts() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let plan = engine(r#"{"relations": [{"pair": 0, "relation": "updates"}]}"#)
+            .plan(&mem, None)
+            .await
+            .unwrap();
+
+        assert_eq!(plan.turns, 3);
+        assert_eq!(plan.changes.len(), 3);
+
+        let merge = &plan.changes[0];
+        assert_eq!(merge.action, ChangeAction::Merge);
+        assert_eq!(merge.key, "editor");
+        assert_eq!(merge.sources, vec!["user_msg_1".to_string()]);
+
+        let add = &plan.changes[1];
+        assert_eq!(add.action, ChangeAction::Add);
+        assert_eq!(add.key, "dog_name");
+        assert_eq!(add.sources, vec!["user_msg_3".to_string()]);
+
+        let supersede = &plan.changes[2];
+        assert_eq!(supersede.action, ChangeAction::Supersede);
+        assert_eq!(supersede.key, "home_city");
+        assert_eq!(
+            supersede.previous.as_deref(),
+            Some("The user lives in Lisbon")
+        );
+        assert_eq!(supersede.content, "The user lives in Porto");
+
+        // Planning never writes.
+        let home = mem.get("home_city").await.unwrap().unwrap();
+        assert_eq!(home.content, "The user lives in Lisbon");
+        assert!(mem.get("dog_name").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn related_fact_judged_distinct_is_added_under_a_free_key() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let plan = engine(r#"{"relations": [{"pair": 0, "relation": "distinct"}]}"#)
+            .plan(&mem, None)
+            .await
+            .unwrap();
+
+        let porto = plan
+            .changes
+            .iter()
+            .find(|c| c.content == "The user lives in Porto")
+            .unwrap();
+        assert_eq!(porto.action, ChangeAction::Add);
+        assert_eq!(porto.key, "home_city_2");
+    }
+
+    #[tokio::test]
+    async fn apply_writes_changes_with_provenance_and_skips_stale_ones() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let plan = engine(r#"{"relations": [{"pair": 0, "relation": "updates"}]}"#)
+            .plan(&mem, None)
+            .await
+            .unwrap();
+
+        // The editor entry changes between planning and applying.
+        mem.store(
+            "editor",
+            "The user switched to Zed",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        let report = apply_plan(&mem, &plan).await.unwrap();
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].1, "editor");
+
+        let home = mem.get("home_city").await.unwrap().unwrap();
+        assert_eq!(home.content, "The user lives in Porto");
+        assert_eq!(home.category, MemoryCategory::Core);
+        assert_eq!(
+            home.metadata.source.as_deref(),
+            Some("consolidation:user_msg_2")
+        );
+        assert!(home.metadata.tags.contains(&CONSOLIDATED_TAG.to_string()));
+        assert_eq!(home.metadata.importance, Some(0.8));
+
+        let dog = mem.get("dog_name").await.unwrap().unwrap();
+        assert_eq!(dog.content, "The user has a dog named Rex");
+        assert_eq!(
+            mem.get("editor").await.unwrap().unwrap().content,
+            "The user switched to Zed"
+        );
+
+        let rendered = render_report(&plan, Some(&report));
+        assert!(rendered.contains("- The user lives in Lisbon"));
+        assert!(rendered.contains("+ The user lives in Porto"));
+        assert!(rendered.contains("skipped: entry changed since planning"));
+    }
+
+    #[tokio::test]
+    async fn keyword_candidates_are_used_without_embeddings() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let engine = ConsolidationEngine::new(
+            Box::new(ScriptedProvider {
+                facts: r#"{"facts": [{"key": "home", "fact": "The user lives in Porto", "importance": 0.7, "turns": [1]}]}"#,
+                relations: r#"{"relations": [{"pair": 0, "relation": "updates"}]}"#,
+            }),
+            "test-model",
+            Arc::new(super::super::embeddings::NoopEmbedding),
+            &MemoryConsolidationConfig::default(),
+        );
+
+        let plan = engine.plan(&mem, None).await.unwrap();
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].action, ChangeAction::Supersede);
+        assert!(plan.changes[0].similarity.is_none());
+    }
+
+    #[tokio::test]
+    async fn apply_and_record_advances_state_and_clears_pending() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_memory(&tmp).await;
+        let engine = engine(r#"{"relations": []}"#);
+        let plan = engine.plan(&mem, None).await.unwrap();
+
+        save_pending(tmp.path(), &plan).unwrap();
+        assert_eq!(load_pending(tmp.path()).unwrap().as_ref(), Some(&plan));
+
+        let (_, report_path) = apply_and_record(&mem, tmp.path(), &plan).await.unwrap();
+        assert!(report_path.exists());
+        assert!(load_pending(tmp.path()).unwrap().is_none());
+
+        let since = last_consolidated_turn(tmp.path()).unwrap();
+        assert_eq!(since, plan.through);
+        let next = engine.plan(&mem, since.as_deref()).await.unwrap();
+        assert_eq!(next.turns, 0);
+        assert!(next.changes.is_empty());
+    }
+
+    #[test]
+    fn slugify_key_normalizes_model_keys() {
+        assert_eq!(slugify_key("Home City", ""), "home_city");
+        assert_eq!(slugify_key("  ", "Likes: tea!"), "likes_tea");
+        assert_eq!(slugify_key("***", ""), "fact");
+    }
+
+    #[test]
+    fn provenance_elides_long_source_lists() {
+        let sources: Vec<String> = (0..7).map(|i| format!("m{i}")).collect();
+        assert_eq!(provenance(&sources), "consolidation:m0,m1,m2,m3,m4,+2 more");
+        assert_eq!(provenance(&[]), "consolidation");
+    }
+}
@@ -1,4 +1,13 @@
 use async_trait::async_trait;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::config::{AzureOpenAiAuthMode, AzureOpenAiConfig};
+use crate::providers::azure_openai::{self, AzureAuth};
+use crate::providers::bedrock::{self, AwsCredentials};
+use crate::providers::ollama::OllamaProvider;
+use crate::providers::reliable::is_non_retryable;
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -19,6 +28,221 @@ pub trait EmbeddingProvider: Send + Sync {
             .pop()
             .ok_or_else(|| anyhow::anyhow!("Empty embedding result"))
     }
+
+    /// Embed a search query. `embed` is for stored documents; providers that
+    /// embed the two differently (Cohere, Gemini, Voyage) override this.
+    async fn embed_query(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        self.embed_one(text).await
+    }
+}
+
+// ── Shared helpers ───────────────────────────────────────────
+
+/// Whether text is embedded for storage or as a search query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputKind {
+    Document,
+    Query,
+}
+
+/// Output size of well-known embedding models, used when
+/// `embedding_dimensions = 0`.
+fn known_dimensions(model: &str) -> Option<usize> {
+    let model = model.trim();
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    let dims = match model {
+        "text-embedding-3-small" | "text-embedding-ada-002" => 1536,
+        "text-embedding-3-large" => 3072,
+        "text-embedding-004" | "embedding-001" => 768,
+        m if m.starts_with("gemini-embedding") => 3072,
+        m if m.starts_with("nomic-embed-text") => 768,
+        m if m.starts_with("mxbai-embed-large") || m.starts_with("bge-m3") => 1024,
+        m if m.starts_with("all-minilm") => 384,
+        m if m.starts_with("embed-english-light") || m.starts_with("embed-multilingual-light") => {
+            384
+        }
+        m if m.starts_with("embed-english") || m.starts_with("embed-multilingual") => 1024,
+        m if m.starts_with("embed-v4") => 1536,
+        m if m.starts_with("amazon.titan-embed-text-v2") => 1024,
+        m if m.starts_with("amazon.titan-embed-text-v1")
+            || m.starts_with("amazon.titan-embed-g1-text") =>
+        {
+            1536
+        }
+        m if m.starts_with("voyage-3-lite") => 512,
+        m if m.starts_with("voyage-") => 1024,
+        _ => return None,
+    };
+    Some(dims)
+}
+
+/// Vector size that starts at the configured value (or the model's known size
+/// when configured as 0) and follows what the provider actually returns.
+struct DetectedDimensions(AtomicUsize);
+
+impl DetectedDimensions {
+    fn new(configured: usize, model: &str) -> Self {
+        let initial = if configured == 0 {
+            known_dimensions(model).unwrap_or_else(|| {
+                tracing::warn!(
+                    model,
+                    "Unknown embedding size for model; set [memory] embedding_dimensions"
+                );
+                0
+            })
+        } else {
+            configured
+        };
+        Self(AtomicUsize::new(initial))
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn observe(&self, provider: &str, vectors: &[Vec<f32>]) {
+        let Some(actual) = vectors.first().map(Vec::len).filter(|len| *len > 0) else {
+            return;
+        };
+        let previous = self.0.swap(actual, Ordering::Relaxed);
+        if previous != 0 && previous != actual {
+            tracing::warn!(
+                provider,
+                configured = previous,
+                actual,
+                "Embedding dimensions differ from configuration; using the provider's size"
+            );
+        }
+    }
+}
+
+/// First non-empty value among an explicit key and the given env vars.
+fn resolve_api_key(api_key: Option<&str>, env_vars: &[&str]) -> Option<String> {
+    api_key
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            env_vars.iter().find_map(|name| {
+                std::env::var(name)
+                    .ok()
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            })
+        })
+}
+
+/// Fail on a non-2xx status, otherwise decode the JSON body.
+async fn read_json(resp: reqwest::Response) -> anyhow::Result<serde_json::Value> {
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Embedding API error {status}: {text}");
+    }
+    Ok(resp.json().await?)
+}
+
+/// Decode a JSON number array into a vector.
+fn json_to_vector(value: &serde_json::Value) -> anyhow::Result<Vec<f32>> {
+    let values = value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(values
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect())
+}
+
+/// Decode `[[...], [...]]`, checking one vector came back per input.
+fn json_to_vectors(
+    value: Option<&serde_json::Value>,
+    expected: usize,
+) -> anyhow::Result<Vec<Vec<f32>>> {
+    let items = value
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Invalid embedding response: missing embeddings"))?;
+    let vectors = items
+        .iter()
+        .map(json_to_vector)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    check_batch_len(&vectors, expected)?;
+    Ok(vectors)
+}
+
+fn check_batch_len(vectors: &[Vec<f32>], expected: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        vectors.len() == expected,
+        "Embedding API returned {} vectors for {expected} inputs",
+        vectors.len()
+    );
+    Ok(())
+}
+
+// ── Retry decorator ──────────────────────────────────────────
+
+/// Retries transient embedding failures (network errors, 429, 5xx) with
+/// exponential backoff, using the `[reliability]` provider retry budget.
+pub struct RetryingEmbedding {
+    inner: Box<dyn EmbeddingProvider>,
+    max_retries: u32,
+    base_backoff_ms: u64,
+}
+
+impl RetryingEmbedding {
+    pub fn new(inner: Box<dyn EmbeddingProvider>, max_retries: u32, base_backoff_ms: u64) -> Self {
+        Self {
+            inner,
+            max_retries,
+            base_backoff_ms: base_backoff_ms.max(50),
+        }
+    }
+
+    async fn with_retries<T, F, Fut>(&self, mut attempt: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff_ms = self.base_backoff_ms;
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(e) if retries < self.max_retries && !is_non_retryable(&e) => {
+                    retries += 1;
+                    tracing::warn!(
+                        provider = self.inner.name(),
+                        retry = retries,
+                        backoff_ms,
+                        "Embedding request failed, retrying: {e}"
+                    );
+                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                    backoff_ms = backoff_ms.saturating_mul(2).min(10_000);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for RetryingEmbedding {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        self.with_retries(|| self.inner.embed(texts)).await
+    }
+
+    async fn embed_query(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        self.with_retries(|| self.inner.embed_query(text)).await
+    }
 }
 
 // ── Noop provider (keyword-only fallback) ────────────────────
@@ -42,11 +266,19 @@ impl EmbeddingProvider for NoopEmbedding {
 
 // ── OpenAI-compatible embedding provider ─────────────────────
 
+/// Requests per call accepted by the OpenAI embeddings endpoint.
+const OPENAI_MAX_BATCH: usize = 2048;
+/// Voyage accepts at most 128 inputs per request.
+const VOYAGE_MAX_BATCH: usize = 128;
+
 pub struct OpenAiEmbedding {
     base_url: String,
     api_key: String,
     model: String,
     dims: usize,
+    name: &'static str,
+    max_batch: usize,
+    input_type: bool,
 }
 
 impl OpenAiEmbedding {
@@ -56,6 +288,20 @@ impl OpenAiEmbedding {
             api_key: api_key.to_string(),
             model: model.to_string(),
             dims,
+            name: "openai",
+            max_batch: OPENAI_MAX_BATCH,
+            input_type: false,
+        }
+    }
+
+    /// Voyage-style API: same wire format, plus `input_type` ("document" or
+    /// "query") and a smaller batch limit.
+    pub fn voyage(base_url: &str, api_key: &str, model: &str, dims: usize) -> Self {
+        Self {
+            name: "voyage",
+            max_batch: VOYAGE_MAX_BATCH,
+            input_type: true,
+            ..Self::new(base_url, api_key, model, dims)
         }
     }
 
@@ -91,12 +337,145 @@ impl OpenAiEmbedding {
             format!("{}/v1/embeddings", self.base_url)
         }
     }
+
+    async fn embed_batch(&self, texts: &[&str], kind: InputKind) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+        if self.input_type {
+            body["input_type"] = serde_json::json!(match kind {
+                InputKind::Document => "document",
+                InputKind::Query => "query",
+            });
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.embeddings_url())
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+
+        let json = read_json(resp).await?;
+        parse_embeddings_response(&json)
+    }
 }
 
 #[async_trait]
 impl EmbeddingProvider for OpenAiEmbedding {
     fn name(&self) -> &str {
-        "openai"
+        self.name
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.max_batch) {
+            vectors.extend(self.embed_batch(batch, InputKind::Document).await?);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_query(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        self.embed_batch(&[text], InputKind::Query)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Empty embedding result"))
+    }
+}
+
+/// Extract vectors from an OpenAI-style `{"data": [{"embedding": [...]}]}` body.
+fn parse_embeddings_response(json: &serde_json::Value) -> anyhow::Result<Vec<Vec<f32>>> {
+    let data = json
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Invalid embedding response: missing 'data'"))?;
+
+    let mut embeddings = Vec::with_capacity(data.len());
+    for item in data {
+        let embedding = item
+            .get("embedding")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let vec: Vec<f32> = embedding
+            .iter()
+            .filter_map(|v| v.as_f64().map(|f| f as f32))
+            .collect();
+
+        embeddings.push(vec);
+    }
+
+    Ok(embeddings)
+}
+
+// ── Azure OpenAI embedding provider ──────────────────────────
+
+/// Embeddings served by an Azure OpenAI deployment. The configured model name
+/// is the deployment name; auth follows the chat provider (`api-key` header,
+/// or Entra ID from the environment when no key is given).
+pub struct AzureOpenAiEmbedding {
+    endpoint: Option<String>,
+    deployment: String,
+    api_version: String,
+    auth: Option<AzureAuth>,
+    dims: usize,
+}
+
+impl AzureOpenAiEmbedding {
+    pub fn new(
+        endpoint: Option<&str>,
+        api_key: Option<&str>,
+        deployment: &str,
+        dims: usize,
+    ) -> Self {
+        let endpoint = endpoint
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(azure_openai::normalize_endpoint)
+            .or_else(azure_openai::endpoint_from_env);
+        let api_version = std::env::var("AZURE_OPENAI_API_VERSION")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| AzureOpenAiConfig::default().api_version);
+
+        Self {
+            endpoint,
+            deployment: deployment.to_string(),
+            api_version,
+            auth: AzureAuth::resolve(AzureOpenAiAuthMode::ApiKey, api_key)
+                .or_else(|| AzureAuth::resolve(AzureOpenAiAuthMode::EntraId, None)),
+            dims,
+        }
+    }
+
+    fn embeddings_url(&self) -> anyhow::Result<String> {
+        let endpoint = self.endpoint.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Azure OpenAI endpoint not set. Use azure:<endpoint> or set AZURE_OPENAI_ENDPOINT."
+            )
+        })?;
+        Ok(azure_openai::deployment_url(
+            endpoint,
+            &self.deployment,
+            "embeddings",
+            &self.api_version,
+        ))
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for AzureOpenAiEmbedding {
+    fn name(&self) -> &str {
+        "azure"
     }
 
     fn dimensions(&self) -> usize {
@@ -108,19 +487,16 @@ impl EmbeddingProvider for OpenAiEmbedding {
             return Ok(Vec::new());
         }
 
-        let body = serde_json::json!({
-            "model": self.model,
-            "input": texts,
-        });
-
-        let resp = self
-            .http_client()
-            .post(self.embeddings_url())
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        let auth = self.auth.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Azure OpenAI credentials not set. Set AZURE_OPENAI_API_KEY or Entra ID env vars."
+            )
+        })?;
+        let client = crate::config::build_runtime_proxy_client("memory.embeddings");
+        let builder = client
+            .post(self.embeddings_url()?)
+            .json(&serde_json::json!({ "input": texts }));
+        let resp = auth.apply(builder, &client).await?.send().await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -129,28 +505,364 @@ impl EmbeddingProvider for OpenAiEmbedding {
         }
 
         let json: serde_json::Value = resp.json().await?;
-        let data = json
-            .get("data")
-            .and_then(|d| d.as_array())
-            .ok_or_else(|| anyhow::anyhow!("Invalid embedding response: missing 'data'"))?;
+        parse_embeddings_response(&json)
+    }
+}
 
-        let mut embeddings = Vec::with_capacity(data.len());
-        for item in data {
-            let embedding = item
-                .get("embedding")
-                .and_then(|e| e.as_array())
-                .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+// ── Ollama embedding provider ────────────────────────────────
 
-            #[allow(clippy::cast_possible_truncation)]
-            let vec: Vec<f32> = embedding
-                .iter()
-                .filter_map(|v| v.as_f64().map(|f| f as f32))
-                .collect();
+/// Inputs per `/api/embed` call; keeps request bodies small for local servers.
+const OLLAMA_MAX_BATCH: usize = 64;
 
-            embeddings.push(vec);
+/// Ollama's native batched `/api/embed` endpoint.
+pub struct OllamaEmbedding {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dims: DetectedDimensions,
+}
+
+impl OllamaEmbedding {
+    pub fn new(base_url: Option<&str>, api_key: Option<&str>, model: &str, dims: usize) -> Self {
+        Self {
+            base_url: OllamaProvider::normalize_base_url(
+                base_url
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .unwrap_or("http://localhost:11434"),
+            ),
+            api_key: resolve_api_key(api_key, &["OLLAMA_API_KEY"]),
+            model: model.to_string(),
+            dims: DetectedDimensions::new(dims, model),
         }
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let client = crate::config::build_runtime_proxy_client("memory.embeddings");
+        let mut request = client
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let json = read_json(request.send().await?).await?;
+        json_to_vectors(json.get("embeddings"), texts.len())
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbedding {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims.get()
+    }
 
-        Ok(embeddings)
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(OLLAMA_MAX_BATCH) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        self.dims.observe(self.name(), &vectors);
+        Ok(vectors)
+    }
+}
+
+// ── Gemini embedding provider ────────────────────────────────
+
+const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+/// Requests per `batchEmbedContents` call.
+const GEMINI_MAX_BATCH: usize = 100;
+
+/// Gemini `embedContent`, sent through `batchEmbedContents` so a batch is one
+/// request. Stored text uses `RETRIEVAL_DOCUMENT`, queries `RETRIEVAL_QUERY`.
+pub struct GeminiEmbedding {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dims: DetectedDimensions,
+}
+
+impl GeminiEmbedding {
+    pub fn new(base_url: Option<&str>, api_key: Option<&str>, model: &str, dims: usize) -> Self {
+        let model = model.trim();
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        Self {
+            base_url: base_url
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(GEMINI_API_BASE)
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: resolve_api_key(api_key, &["GEMINI_API_KEY", "GOOGLE_API_KEY"]),
+            model: model.to_string(),
+            dims: DetectedDimensions::new(dims, model),
+        }
+    }
+
+    async fn embed_batch(&self, texts: &[&str], kind: InputKind) -> anyhow::Result<Vec<Vec<f32>>> {
+        let api_key = self.api_key.as_deref().ok_or_else(|| {
+            anyhow::anyhow!("Gemini API key not set. Set GEMINI_API_KEY or GOOGLE_API_KEY.")
+        })?;
+        let task_type = match kind {
+            InputKind::Document => "RETRIEVAL_DOCUMENT",
+            InputKind::Query => "RETRIEVAL_QUERY",
+        };
+        let model_ref = format!("models/{}", self.model);
+        let requests: Vec<serde_json::Value> = texts
+            .iter()
+            .map(|text| {
+                serde_json::json!({
+                    "model": model_ref,
+                    "content": { "parts": [{ "text": text }] },
+                    "taskType": task_type,
+                })
+            })
+            .collect();
+
+        let resp = crate::config::build_runtime_proxy_client("memory.embeddings")
+            .post(format!("{}/{model_ref}:batchEmbedContents", self.base_url))
+            .header("x-goog-api-key", api_key)
+            .json(&serde_json::json!({ "requests": requests }))
+            .send()
+            .await?;
+
+        let json = read_json(resp).await?;
+        let items = json
+            .get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid embedding response: missing 'embeddings'"))?;
+        let vectors = items
+            .iter()
+            .map(|item| json_to_vector(item.get("values").unwrap_or(&serde_json::Value::Null)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        check_batch_len(&vectors, texts.len())?;
+        Ok(vectors)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for GeminiEmbedding {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims.get()
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(GEMINI_MAX_BATCH) {
+            vectors.extend(self.embed_batch(batch, InputKind::Document).await?);
+        }
+        self.dims.observe(self.name(), &vectors);
+        Ok(vectors)
+    }
+
+    async fn embed_query(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let vectors = self.embed_batch(&[text], InputKind::Query).await?;
+        self.dims.observe(self.name(), &vectors);
+        vectors
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty embedding result"))
+    }
+}
+
+// ── Cohere embedding provider ────────────────────────────────
+
+const COHERE_API_BASE: &str = "https://api.cohere.com";
+/// Texts per `/v2/embed` call.
+const COHERE_MAX_BATCH: usize = 96;
+
+/// Cohere `/v2/embed`. Stored text is sent as `search_document`, queries as
+/// `search_query`, which Cohere's v3+ models require to rank well.
+pub struct CohereEmbedding {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dims: DetectedDimensions,
+}
+
+impl CohereEmbedding {
+    pub fn new(base_url: Option<&str>, api_key: Option<&str>, model: &str, dims: usize) -> Self {
+        Self {
+            base_url: base_url
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(COHERE_API_BASE)
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: resolve_api_key(api_key, &["COHERE_API_KEY", "CO_API_KEY"]),
+            model: model.to_string(),
+            dims: DetectedDimensions::new(dims, model),
+        }
+    }
+
+    async fn embed_batch(&self, texts: &[&str], kind: InputKind) -> anyhow::Result<Vec<Vec<f32>>> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Cohere API key not set. Set COHERE_API_KEY."))?;
+        let input_type = match kind {
+            InputKind::Document => "search_document",
+            InputKind::Query => "search_query",
+        };
+
+        let resp = crate::config::build_runtime_proxy_client("memory.embeddings")
+            .post(format!("{}/v2/embed", self.base_url))
+            .bearer_auth(api_key)
+            .json(&serde_json::json!({
+                "model": self.model,
+                "texts": texts,
+                "input_type": input_type,
+                "embedding_types": ["float"],
+            }))
+            .send()
+            .await?;
+
+        let json = read_json(resp).await?;
+        json_to_vectors(
+            json.get("embeddings").and_then(|e| e.get("float")),
+            texts.len(),
+        )
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for CohereEmbedding {
+    fn name(&self) -> &str {
+        "cohere"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims.get()
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(COHERE_MAX_BATCH) {
+            vectors.extend(self.embed_batch(batch, InputKind::Document).await?);
+        }
+        self.dims.observe(self.name(), &vectors);
+        Ok(vectors)
+    }
+
+    async fn embed_query(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        let vectors = self.embed_batch(&[text], InputKind::Query).await?;
+        self.dims.observe(self.name(), &vectors);
+        vectors
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty embedding result"))
+    }
+}
+
+// ── Bedrock Titan embedding provider ─────────────────────────
+
+/// Output sizes Titan Text Embeddings V2 accepts for `dimensions`.
+const TITAN_V2_DIMENSIONS: [usize; 3] = [256, 512, 1024];
+
+/// Amazon Titan text embeddings via Bedrock `InvokeModel`. Titan takes one
+/// input per call, so batches are sent sequentially. Credentials and region
+/// come from the same AWS env vars (or EC2 instance role) as the chat provider.
+pub struct BedrockEmbedding {
+    endpoint: Option<String>,
+    credentials: Option<AwsCredentials>,
+    model: String,
+    requested_dims: Option<usize>,
+    dims: DetectedDimensions,
+}
+
+impl BedrockEmbedding {
+    pub fn new(endpoint: Option<&str>, model: &str, dims: usize) -> Self {
+        // Only V2 can shrink its output; V1 always returns 1536 floats.
+        let requested_dims = (model.contains("titan-embed-text-v2")
+            && TITAN_V2_DIMENSIONS.contains(&dims))
+        .then_some(dims);
+        Self {
+            endpoint: endpoint
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            credentials: AwsCredentials::from_env().ok(),
+            model: model.to_string(),
+            requested_dims,
+            dims: DetectedDimensions::new(dims, model),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    async fn embed_text(
+        &self,
+        client: &reqwest::Client,
+        credentials: &AwsCredentials,
+        text: &str,
+    ) -> anyhow::Result<Vec<f32>> {
+        let mut body = serde_json::json!({ "inputText": text });
+        if let Some(dims) = self.requested_dims {
+            body["dimensions"] = serde_json::json!(dims);
+            body["normalize"] = serde_json::json!(true);
+        }
+
+        let request = bedrock::signed_model_request(
+            client,
+            credentials,
+            self.endpoint.as_deref(),
+            &self.model,
+            "invoke",
+            serde_json::to_vec(&body)?,
+        )?;
+        let json = read_json(request.send().await?).await?;
+        json_to_vector(json.get("embedding").unwrap_or(&serde_json::Value::Null))
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for BedrockEmbedding {
+    fn name(&self) -> &str {
+        "bedrock"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims.get()
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let resolved;
+        let credentials = match &self.credentials {
+            Some(credentials) => credentials,
+            None => {
+                resolved = AwsCredentials::resolve().await.map_err(|_| {
+                    anyhow::anyhow!(
+                        "AWS Bedrock credentials not set. Set AWS_ACCESS_KEY_ID and \
+                         AWS_SECRET_ACCESS_KEY, or run with an attached IAM role."
+                    )
+                })?;
+                &resolved
+            }
+        };
+
+        let client = crate::config::build_runtime_proxy_client("memory.embeddings");
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            vectors.push(self.embed_text(&client, credentials, text).await?);
+        }
+        self.dims.observe(self.name(), &vectors);
+        Ok(vectors)
     }
 }
 
@@ -181,6 +893,32 @@ pub fn create_embedding_provider(
                 dims,
             ))
         }
+        "voyage" | "voyageai" => {
+            let key = resolve_api_key(api_key, &["VOYAGE_API_KEY"]).unwrap_or_default();
+            Box::new(OpenAiEmbedding::voyage(
+                "https://api.voyageai.com/v1",
+                &key,
+                model,
+                dims,
+            ))
+        }
+        "ollama" => Box::new(OllamaEmbedding::new(None, api_key, model, dims)),
+        name if name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:");
+            Box::new(OllamaEmbedding::new(base_url, api_key, model, dims))
+        }
+        "gemini" | "google" | "google-gemini" => {
+            Box::new(GeminiEmbedding::new(None, api_key, model, dims))
+        }
+        "cohere" => Box::new(CohereEmbedding::new(None, api_key, model, dims)),
+        "bedrock" | "aws-bedrock" => Box::new(BedrockEmbedding::new(None, model, dims)),
+        "azure" | "azure-openai" | "azure_openai" => {
+            Box::new(AzureOpenAiEmbedding::new(None, api_key, model, dims))
+        }
+        name if name.starts_with("azure:") => {
+            let endpoint = name.strip_prefix("azure:");
+            Box::new(AzureOpenAiEmbedding::new(endpoint, api_key, model, dims))
+        }
         name if name.starts_with("custom:") => {
             let base_url = name.strip_prefix("custom:").unwrap_or("");
             let key = api_key.unwrap_or("");
@@ -240,6 +978,312 @@ mod tests {
         assert_eq!(p.dimensions(), 768);
     }
 
+    #[test]
+    fn factory_azure_endpoint() {
+        let p = create_embedding_provider(
+            "azure:https://res.openai.azure.com/",
+            Some("key"),
+            "embed-deploy",
+            1536,
+        );
+        assert_eq!(p.name(), "azure");
+        assert_eq!(p.dimensions(), 1536);
+    }
+
+    #[test]
+    fn azure_embeddings_url_targets_deployment() {
+        let p = AzureOpenAiEmbedding::new(
+            Some("https://res.openai.azure.com/openai"),
+            Some("key"),
+            "embed-deploy",
+            1536,
+        );
+        let url = p.embeddings_url().unwrap();
+        assert!(url.starts_with(
+            "https://res.openai.azure.com/openai/deployments/embed-deploy/embeddings?api-version="
+        ));
+    }
+
+    #[tokio::test]
+    async fn azure_embed_sends_api_key_to_deployment() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/embed-deploy/embeddings"))
+            .and(header("api-key", "azure-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"embedding": [0.5, 0.25]}, {"embedding": [1.0, 0.0]}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let p =
+            AzureOpenAiEmbedding::new(Some(&server.uri()), Some("azure-key"), "embed-deploy", 2);
+        let vectors = p.embed(&["a", "b"]).await.unwrap();
+        assert_eq!(vectors, vec![vec![0.5, 0.25], vec![1.0, 0.0]]);
+    }
+
+    #[test]
+    fn factory_native_providers() {
+        for (provider, name) in [
+            ("ollama", "ollama"),
+            ("ollama:http://gpu-box:11434", "ollama"),
+            ("gemini", "gemini"),
+            ("cohere", "cohere"),
+            ("bedrock", "bedrock"),
+            ("voyage", "voyage"),
+        ] {
+            let p = create_embedding_provider(provider, Some("key"), "model", 768);
+            assert_eq!(p.name(), name, "{provider}");
+            assert_eq!(p.dimensions(), 768, "{provider}");
+        }
+    }
+
+    #[test]
+    fn zero_dimensions_fall_back_to_known_model_size() {
+        let p = create_embedding_provider("ollama", None, "nomic-embed-text:latest", 0);
+        assert_eq!(p.dimensions(), 768);
+        let p = create_embedding_provider("bedrock", None, "amazon.titan-embed-text-v2:0", 0);
+        assert_eq!(p.dimensions(), 1024);
+        let p = create_embedding_provider("gemini", Some("k"), "models/text-embedding-004", 0);
+        assert_eq!(p.dimensions(), 768);
+    }
+
+    /// Answer an embed request with one 3-dim vector per input text.
+    fn echo_vectors(
+        field: &'static str,
+    ) -> impl Fn(&wiremock::Request) -> wiremock::ResponseTemplate {
+        move |req: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            let count = body[field].as_array().map_or(0, Vec::len);
+            let vectors = vec![vec![0.1, 0.2, 0.3]; count];
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "embeddings": vectors }))
+        }
+    }
+
+    #[tokio::test]
+    async fn ollama_embed_batches_and_detects_dimensions() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/embed"))
+            .respond_with(echo_vectors("input"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let p = OllamaEmbedding::new(Some(&format!("{}/api", server.uri())), None, "m", 1536);
+        let texts = vec!["chunk"; OLLAMA_MAX_BATCH + 6];
+        let vectors = p.embed(&texts).await.unwrap();
+        assert_eq!(vectors.len(), OLLAMA_MAX_BATCH + 6);
+        assert_eq!(p.dimensions(), 3);
+    }
+
+    #[tokio::test]
+    async fn gemini_embed_uses_task_types_and_api_key_header() {
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/models/text-embedding-004:batchEmbedContents"))
+            .and(header("x-goog-api-key", "g-key"))
+            .and(body_partial_json(serde_json::json!({
+                "requests": [{"model": "models/text-embedding-004", "taskType": "RETRIEVAL_QUERY"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embeddings": [{"values": [0.5, 0.5]}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/models/text-embedding-004:batchEmbedContents"))
+            .and(body_partial_json(serde_json::json!({
+                "requests": [{"taskType": "RETRIEVAL_DOCUMENT"}, {"taskType": "RETRIEVAL_DOCUMENT"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embeddings": [{"values": [1.0, 0.0]}, {"values": [0.0, 1.0]}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let p = GeminiEmbedding::new(
+            Some(&server.uri()),
+            Some("g-key"),
+            "models/text-embedding-004",
+            768,
+        );
+        assert_eq!(p.embed_query("q").await.unwrap(), vec![0.5, 0.5]);
+        let docs = p.embed(&["a", "b"]).await.unwrap();
+        assert_eq!(docs, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(p.dimensions(), 2);
+    }
+
+    #[tokio::test]
+    async fn cohere_embed_splits_batches_and_marks_queries() {
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/embed"))
+            .and(body_partial_json(
+                serde_json::json!({"input_type": "search_query"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embeddings": {"float": [[0.25, 0.75]]}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/embed"))
+            .and(header("authorization", "Bearer co-key"))
+            .and(body_partial_json(serde_json::json!({
+                "input_type": "search_document",
+                "embedding_types": ["float"]
+            })))
+            .respond_with(move |req: &wiremock::Request| {
+                let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+                let count = body["texts"].as_array().map_or(0, Vec::len);
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "embeddings": {"float": vec![vec![0.1, 0.2]; count]}
+                }))
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let p = CohereEmbedding::new(Some(&server.uri()), Some("co-key"), "embed-english-v3.0", 0);
+        assert_eq!(p.dimensions(), 1024);
+        let texts = vec!["doc"; COHERE_MAX_BATCH + 4];
+        assert_eq!(p.embed(&texts).await.unwrap().len(), COHERE_MAX_BATCH + 4);
+        assert_eq!(p.embed_query("q").await.unwrap(), vec![0.25, 0.75]);
+        assert_eq!(p.dimensions(), 2);
+    }
+
+    #[tokio::test]
+    async fn cohere_embed_rejects_short_batch() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embeddings": {"float": [[0.1]]}
+            })))
+            .mount(&server)
+            .await;
+
+        let p = CohereEmbedding::new(Some(&server.uri()), Some("k"), "m", 1);
+        let err = p.embed(&["a", "b"]).await.unwrap_err();
+        assert!(err.to_string().contains("1 vectors for 2 inputs"));
+    }
+
+    #[tokio::test]
+    async fn bedrock_titan_signs_one_invoke_per_text() {
+        use wiremock::matchers::{body_partial_json, header_exists, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/model/amazon.titan-embed-text-v2:0/invoke"))
+            .and(header_exists("authorization"))
+            .and(header_exists("x-amz-date"))
+            .and(body_partial_json(
+                serde_json::json!({"dimensions": 256, "normalize": true}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embedding": [0.5, -0.5],
+                "inputTextTokenCount": 2
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let p = BedrockEmbedding::new(Some(&server.uri()), "amazon.titan-embed-text-v2:0", 256)
+            .with_credentials(AwsCredentials {
+                access_key_id: "AKIDTEST".into(),
+                secret_access_key: "secret".into(),
+                session_token: None,
+                region: "us-east-1".into(),
+            });
+        let vectors = p.embed(&["a", "b"]).await.unwrap();
+        assert_eq!(vectors, vec![vec![0.5, -0.5], vec![0.5, -0.5]]);
+        assert_eq!(p.dimensions(), 2);
+    }
+
+    #[tokio::test]
+    async fn voyage_sends_input_type() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(body_partial_json(
+                serde_json::json!({"input_type": "query"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"embedding": [1.0]}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let p = OpenAiEmbedding::voyage(&format!("{}/v1", server.uri()), "k", "voyage-3", 1);
+        assert_eq!(p.embed_query("q").await.unwrap(), vec![1.0]);
+    }
+
+    #[tokio::test]
+    async fn retrying_embedding_recovers_from_server_error() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(echo_vectors("input"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let inner = OllamaEmbedding::new(Some(&server.uri()), None, "m", 3);
+        let p = RetryingEmbedding::new(Box::new(inner), 2, 1);
+        assert_eq!(p.embed(&["a"]).await.unwrap(), vec![vec![0.1, 0.2, 0.3]]);
+        assert_eq!(p.name(), "ollama");
+    }
+
+    #[tokio::test]
+    async fn retrying_embedding_does_not_retry_client_errors() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad model"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let inner = OllamaEmbedding::new(Some(&server.uri()), None, "m", 3);
+        let p = RetryingEmbedding::new(Box::new(inner), 3, 1);
+        assert!(p.embed(&["a"]).await.is_err());
+    }
+
     // ── Edge cases ───────────────────────────────────────────────
 
     #[tokio::test]
@@ -272,7 +1316,7 @@ mod tests {
 
     #[test]
     fn factory_unknown_provider_returns_noop() {
-        let p = create_embedding_provider("cohere", None, "model", 1536);
+        let p = create_embedding_provider("not-a-provider", None, "model", 1536);
         assert_eq!(p.name(), "none");
     }
 
@@ -0,0 +1,606 @@
+// Encryption at rest for memory and session stores (`[memory] encrypt_at_rest`).
+//
+// Sensitive fields — memory content, snapshot files, channel session turns,
+// cron output and cost records — are sealed individually with
+// ChaCha20-Poly1305 as `menc:<key id>:<hex(nonce ‖ ciphertext ‖ tag)>`.
+// Keys, categories, timestamps and embeddings stay in the clear so lookups,
+// retention and vector recall keep working.
+//
+// Data keys live in `~/.zeroclaw/.memory_keys`, each wrapped by the secret
+// store (`.secret_key`), so the memory databases alone reveal nothing. The
+// keyring holds every key still referenced by stored data: `zeroclaw memory
+// rekey` adds a new active key, re-encrypts all stores with it, and only then
+// retires the old ones. Plaintext fields are always readable, so enabling
+// encryption on an existing workspace needs no migration.
+//
+// FTS5 cannot index ciphertext. Encrypted memories carry "blind" search terms
+// instead — a keyed HMAC of each lowercase token — and keyword queries are
+// blinded the same way, so BM25 recall still works without storing words.
+
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+/// Keyring filename, next to the secret store key.
+pub const KEYRING_FILENAME: &str = ".memory_keys";
+
+/// Prefix of encrypted field values.
+const FIELD_PREFIX: &str = "menc:";
+
+/// ChaCha20-Poly1305 nonce length in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Bytes of HMAC output kept per blind search term.
+const BLIND_TERM_BYTES: usize = 8;
+
+/// HMAC input deriving a data key's search-term key.
+const BLIND_KEY_CONTEXT: &[u8] = b"zeroclaw memory search terms";
+
+static RUNTIME_ENCRYPTION: OnceLock<RwLock<Option<RuntimeEncryption>>> = OnceLock::new();
+
+/// On-disk keyring: wrapped data keys by id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeyringFile {
+    active: Option<u32>,
+    keys: BTreeMap<u32, String>,
+}
+
+struct DataKey {
+    cipher: ChaCha20Poly1305,
+    blind_key: Vec<u8>,
+}
+
+impl DataKey {
+    fn new(key_bytes: &[u8]) -> Result<Self> {
+        anyhow::ensure!(key_bytes.len() == 32, "memory data key must be 32 bytes");
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key_bytes)
+            .map_err(|e| anyhow::anyhow!("invalid memory data key: {e}"))?;
+        mac.update(BLIND_KEY_CONTEXT);
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(key_bytes)),
+            blind_key: mac.finalize().into_bytes().to_vec(),
+        })
+    }
+
+    fn blind(&self, token: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.blind_key)
+            .expect("HMAC accepts any key length");
+        mac.update(token.as_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..BLIND_TERM_BYTES])
+    }
+
+    fn blind_text(&self, text: &str) -> String {
+        search_tokens(text)
+            .map(|token| self.blind(&token))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Default)]
+struct Keyring {
+    active: Option<u32>,
+    keys: BTreeMap<u32, DataKey>,
+    modified: Option<SystemTime>,
+}
+
+/// Field-level cipher for memory and session stores.
+pub struct MemoryCipher {
+    path: PathBuf,
+    secrets: SecretStore,
+    encrypt_writes: bool,
+    keyring: RwLock<Keyring>,
+}
+
+impl MemoryCipher {
+    /// Open the keyring in `key_dir` (the directory holding `.secret_key`).
+    ///
+    /// With `encrypt_writes`, a missing keyring is created and new values are
+    /// encrypted; otherwise the cipher only decrypts existing values.
+    pub fn open(key_dir: &Path, encrypt_writes: bool) -> Result<Self> {
+        let cipher = Self {
+            path: keyring_path(key_dir),
+            secrets: SecretStore::new(key_dir, true),
+            encrypt_writes,
+            keyring: RwLock::new(Keyring::default()),
+        };
+        if cipher.path.exists() {
+            *cipher.keyring.write() = cipher.load_keyring()?;
+        } else if encrypt_writes {
+            cipher.rotate()?;
+        }
+        Ok(cipher)
+    }
+
+    /// Whether `value` was produced by [`MemoryCipher::encrypt`].
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with(FIELD_PREFIX)
+    }
+
+    /// Whether new values are encrypted (as opposed to decrypt-only).
+    pub fn encrypts_writes(&self) -> bool {
+        self.encrypt_writes && self.keyring.read().active.is_some()
+    }
+
+    /// Id of the key used for new values, if any.
+    pub fn active_key_id(&self) -> Option<u32> {
+        self.keyring.read().active
+    }
+
+    /// Encrypt `plaintext` with the active key. Returns it unchanged when
+    /// the cipher is decrypt-only or `plaintext` is empty.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        if !self.encrypt_writes || plaintext.is_empty() {
+            return Ok(plaintext.to_string());
+        }
+        self.refresh_if_changed();
+
+        let keyring = self.keyring.read();
+        let Some(id) = keyring.active else {
+            return Ok(plaintext.to_string());
+        };
+        let key = keyring
+            .keys
+            .get(&id)
+            .context("active memory key missing from keyring")?;
+
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = key
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Memory encryption failed: {e}"))?;
+
+        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&ciphertext);
+        Ok(format!("{FIELD_PREFIX}{id}:{}", hex::encode(blob)))
+    }
+
+    /// Decrypt a value from any key in the keyring. Plaintext values are
+    /// returned as-is.
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(rest) = value.strip_prefix(FIELD_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let (id, hex_blob) = rest
+            .split_once(':')
+            .context("Encrypted memory value is malformed (missing key id)")?;
+        let id: u32 = id
+            .parse()
+            .context("Encrypted memory value has an invalid key id")?;
+        let blob = hex::decode(hex_blob).context("Encrypted memory value is corrupt (bad hex)")?;
+        anyhow::ensure!(
+            blob.len() > NONCE_LEN,
+            "Encrypted memory value too short (missing nonce)"
+        );
+
+        if !self.keyring.read().keys.contains_key(&id) {
+            // Another process may have rotated keys since we loaded them.
+            self.refresh_if_changed();
+        }
+        let keyring = self.keyring.read();
+        let key = keyring.keys.get(&id).with_context(|| {
+            format!(
+                "Memory key {id} is not in {} — was the keyring replaced?",
+                self.path.display()
+            )
+        })?;
+
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow::anyhow!("Memory decryption failed — wrong key or tampered data")
+            })?;
+        String::from_utf8(plaintext).context("Decrypted memory value is not valid UTF-8")
+    }
+
+    /// Blind search terms for `text` under the active key, or `None` when new
+    /// values are stored in plaintext.
+    pub fn search_terms(&self, text: &str) -> Option<String> {
+        if !self.encrypt_writes {
+            return None;
+        }
+        let keyring = self.keyring.read();
+        let key = keyring.keys.get(&keyring.active?)?;
+        Some(key.blind_text(text))
+    }
+
+    /// Blind forms of a query word, one per key, so encrypted rows written
+    /// under any key still match.
+    pub fn query_terms(&self, word: &str) -> Vec<String> {
+        let keyring = self.keyring.read();
+        keyring
+            .keys
+            .values()
+            .map(|key| key.blind_text(word))
+            .filter(|terms| !terms.is_empty())
+            .collect()
+    }
+
+    /// Add a fresh data key and make it active. Older keys stay in the
+    /// keyring until [`MemoryCipher::retire_inactive`]. Returns the new id.
+    pub fn rotate(&self) -> Result<u32> {
+        let mut keyring = self.keyring.write();
+        let id = keyring.keys.keys().next_back().map_or(1, |last| last + 1);
+        let key_bytes = ChaCha20Poly1305::generate_key(&mut OsRng);
+
+        let mut file = self.wrapped_keyring()?;
+        file.keys
+            .insert(id, self.secrets.encrypt(&hex::encode(key_bytes))?);
+        file.active = Some(id);
+        self.save_keyring(&file)?;
+
+        keyring.keys.insert(id, DataKey::new(&key_bytes)?);
+        keyring.active = Some(id);
+        keyring.modified = file_modified(&self.path);
+        Ok(id)
+    }
+
+    /// Drop every key except the active one. Only call this once all stores
+    /// have been re-encrypted. Returns the number of keys removed.
+    pub fn retire_inactive(&self) -> Result<usize> {
+        let mut keyring = self.keyring.write();
+        let active = keyring.active;
+        let mut file = self.wrapped_keyring()?;
+        let before = file.keys.len();
+        file.keys.retain(|id, _| Some(*id) == active);
+        let removed = before - file.keys.len();
+        if removed > 0 {
+            self.save_keyring(&file)?;
+            keyring.keys.retain(|id, _| Some(*id) == active);
+            keyring.modified = file_modified(&self.path);
+        }
+        Ok(removed)
+    }
+
+    /// Delete the keyring. Only call this once all stores are plaintext.
+    pub fn remove_keyring(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove {}", self.path.display()))?;
+        }
+        *self.keyring.write() = Keyring::default();
+        Ok(())
+    }
+
+    fn wrapped_keyring(&self) -> Result<KeyringFile> {
+        if !self.path.exists() {
+            return Ok(KeyringFile::default());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Memory keyring {} is corrupt", self.path.display()))
+    }
+
+    fn load_keyring(&self) -> Result<Keyring> {
+        let file = self.wrapped_keyring()?;
+        let mut keys = BTreeMap::new();
+        for (id, wrapped) in &file.keys {
+            let key_hex = self
+                .secrets
+                .decrypt(wrapped)
+                .with_context(|| format!("Failed to unwrap memory key {id}"))?;
+            let key_bytes = hex::decode(key_hex.trim())
+                .with_context(|| format!("Memory key {id} is corrupt"))?;
+            keys.insert(*id, DataKey::new(&key_bytes)?);
+        }
+        if let Some(active) = file.active {
+            anyhow::ensure!(
+                keys.contains_key(&active),
+                "Memory keyring names active key {active} but does not contain it"
+            );
+        }
+        Ok(Keyring {
+            active: file.active,
+            keys,
+            modified: file_modified(&self.path),
+        })
+    }
+
+    /// Reload the keyring if another process rewrote it.
+    fn refresh_if_changed(&self) {
+        let modified = file_modified(&self.path);
+        if modified.is_none() || modified == self.keyring.read().modified {
+            return;
+        }
+        match self.load_keyring() {
+            Ok(keyring) => *self.keyring.write() = keyring,
+            Err(e) => tracing::warn!("Failed to reload memory keyring: {e}"),
+        }
+    }
+
+    /// Write the keyring atomically with owner-only permissions.
+    fn save_keyring(&self, file: &KeyringFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        let json = serde_json::to_string_pretty(file)?;
+        {
+            let mut tmp = fs::File::create(&tmp_path)
+                .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                tmp.set_permissions(fs::Permissions::from_mode(0o600))
+                    .context("Failed to set memory keyring permissions")?;
+            }
+            tmp.write_all(json.as_bytes())?;
+            tmp.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Path of the keyring inside `key_dir`.
+pub fn keyring_path(key_dir: &Path) -> PathBuf {
+    key_dir.join(KEYRING_FILENAME)
+}
+
+/// Encrypt with `cipher` if present; plaintext otherwise.
+pub fn encrypt_field(cipher: Option<&MemoryCipher>, plaintext: &str) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(plaintext),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// Decrypt with `cipher` if present. Encrypted values without a cipher are
+/// an error rather than being passed through as ciphertext.
+pub fn decrypt_field(cipher: Option<&MemoryCipher>, value: &str) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(value),
+        None if MemoryCipher::is_encrypted(value) => anyhow::bail!(
+            "Stored value is encrypted but no memory keyring ({KEYRING_FILENAME}) is available"
+        ),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Lowercased alphanumeric tokens, roughly matching FTS5's `unicode61`.
+fn search_tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// ── Process-wide cipher ──────────────────────────────────────
+
+struct RuntimeEncryption {
+    enabled: bool,
+    key_dir: PathBuf,
+    cipher: Option<Arc<MemoryCipher>>,
+}
+
+fn runtime_state() -> &'static RwLock<Option<RuntimeEncryption>> {
+    RUNTIME_ENCRYPTION.get_or_init(|| RwLock::new(None))
+}
+
+/// Record the encryption setting and key directory of the loaded config.
+/// Called by config loading; stores opened afterwards pick it up through
+/// [`runtime_cipher`].
+pub fn set_runtime_encryption(enabled: bool, key_dir: &Path) {
+    let mut state = runtime_state().write();
+    if state
+        .as_ref()
+        .is_some_and(|s| s.enabled == enabled && s.key_dir == key_dir)
+    {
+        return;
+    }
+    *state = Some(RuntimeEncryption {
+        enabled,
+        key_dir: key_dir.to_path_buf(),
+        cipher: None,
+    });
+}
+
+/// Cipher for stores opened by this process: encrypting when
+/// `encrypt_at_rest` is on, decrypt-only when it is off but a keyring exists,
+/// `None` otherwise (or before any config was loaded).
+pub fn runtime_cipher() -> Result<Option<Arc<MemoryCipher>>> {
+    if let Some(state) = runtime_state().read().as_ref() {
+        if let Some(cipher) = &state.cipher {
+            return Ok(Some(cipher.clone()));
+        }
+        if !state.enabled && !keyring_path(&state.key_dir).exists() {
+            return Ok(None);
+        }
+    } else {
+        return Ok(None);
+    }
+
+    let mut guard = runtime_state().write();
+    let Some(state) = guard.as_mut() else {
+        return Ok(None);
+    };
+    if state.cipher.is_none() {
+        state.cipher = Some(Arc::new(MemoryCipher::open(&state.key_dir, state.enabled)?));
+    }
+    Ok(state.cipher.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+
+        let sealed = cipher.encrypt("User prefers dark mode 🌙").unwrap();
+        assert!(MemoryCipher::is_encrypted(&sealed));
+        assert!(sealed.starts_with("menc:1:"));
+        assert!(!sealed.contains("dark"));
+        assert_eq!(
+            cipher.decrypt(&sealed).unwrap(),
+            "User prefers dark mode 🌙"
+        );
+        assert_ne!(
+            cipher.encrypt("same").unwrap(),
+            cipher.encrypt("same").unwrap()
+        );
+    }
+
+    #[test]
+    fn plaintext_and_empty_values_pass_through() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+
+        assert_eq!(cipher.encrypt("").unwrap(), "");
+        assert_eq!(
+            cipher.decrypt("legacy plaintext").unwrap(),
+            "legacy plaintext"
+        );
+    }
+
+    #[test]
+    fn keyring_stores_wrapped_keys_only() {
+        let tmp = TempDir::new().unwrap();
+        let _cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+
+        let raw = fs::read_to_string(keyring_path(tmp.path())).unwrap();
+        let file: KeyringFile = serde_json::from_str(&raw).unwrap();
+        assert_eq!(file.active, Some(1));
+        assert!(SecretStore::is_secure_encrypted(&file.keys[&1]));
+        assert!(tmp.path().join(".secret_key").exists());
+    }
+
+    #[test]
+    fn decrypt_only_cipher_reads_but_writes_plaintext() {
+        let tmp = TempDir::new().unwrap();
+        let sealed = MemoryCipher::open(tmp.path(), true)
+            .unwrap()
+            .encrypt("secret")
+            .unwrap();
+
+        let reader = MemoryCipher::open(tmp.path(), false).unwrap();
+        assert!(!reader.encrypts_writes());
+        assert_eq!(reader.decrypt(&sealed).unwrap(), "secret");
+        assert_eq!(reader.encrypt("plain").unwrap(), "plain");
+        assert!(reader.search_terms("plain").is_none());
+    }
+
+    #[test]
+    fn decrypt_only_cipher_without_keyring_creates_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let reader = MemoryCipher::open(tmp.path(), false).unwrap();
+        assert!(!keyring_path(tmp.path()).exists());
+        assert!(reader.active_key_id().is_none());
+    }
+
+    #[test]
+    fn rotation_keeps_old_values_readable_until_retired() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+        let old = cipher.encrypt("before rotation").unwrap();
+
+        assert_eq!(cipher.rotate().unwrap(), 2);
+        let new = cipher.encrypt("after rotation").unwrap();
+        assert!(new.starts_with("menc:2:"));
+        assert_eq!(cipher.decrypt(&old).unwrap(), "before rotation");
+
+        let reopened = MemoryCipher::open(tmp.path(), true).unwrap();
+        assert_eq!(reopened.active_key_id(), Some(2));
+        assert_eq!(reopened.decrypt(&old).unwrap(), "before rotation");
+
+        assert_eq!(cipher.retire_inactive().unwrap(), 1);
+        assert!(cipher.decrypt(&old).is_err());
+        assert_eq!(cipher.decrypt(&new).unwrap(), "after rotation");
+    }
+
+    #[test]
+    fn other_process_rotation_is_picked_up() {
+        let tmp = TempDir::new().unwrap();
+        let daemon = MemoryCipher::open(tmp.path(), true).unwrap();
+        let cli = MemoryCipher::open(tmp.path(), true).unwrap();
+
+        cli.rotate().unwrap();
+        let sealed = cli.encrypt("from cli").unwrap();
+        assert_eq!(daemon.decrypt(&sealed).unwrap(), "from cli");
+    }
+
+    #[test]
+    fn tampered_or_foreign_values_are_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+        let sealed = cipher.encrypt("payload").unwrap();
+
+        let mut tampered = sealed.clone();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == '0' { '1' } else { '0' });
+        assert!(cipher.decrypt(&tampered).is_err());
+
+        let other_dir = TempDir::new().unwrap();
+        let other = MemoryCipher::open(other_dir.path(), true).unwrap();
+        assert!(other.decrypt(&sealed).is_err());
+        assert!(cipher.decrypt("menc:1:zz").is_err());
+        assert!(cipher.decrypt("menc:nope").is_err());
+    }
+
+    #[test]
+    fn decrypt_field_without_cipher_rejects_ciphertext() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+        let sealed = encrypt_field(Some(&cipher), "value").unwrap();
+
+        assert_eq!(decrypt_field(Some(&cipher), &sealed).unwrap(), "value");
+        assert!(decrypt_field(None, &sealed).is_err());
+        assert_eq!(decrypt_field(None, "plain").unwrap(), "plain");
+        assert_eq!(encrypt_field(None, "plain").unwrap(), "plain");
+    }
+
+    #[test]
+    fn search_terms_are_keyed_and_match_query_terms() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+
+        let terms = cipher.search_terms("Rust is FAST, rust-lang!").unwrap();
+        let tokens: Vec<&str> = terms.split(' ').collect();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(
+            tokens[0], tokens[3],
+            "case-insensitive tokens blind equally"
+        );
+        assert!(!terms.contains("rust"));
+        assert_eq!(cipher.query_terms("RUST"), vec![tokens[0].to_string()]);
+        assert!(cipher.query_terms("!!").is_empty());
+
+        let other_dir = TempDir::new().unwrap();
+        let other = MemoryCipher::open(other_dir.path(), true).unwrap();
+        assert_ne!(other.search_terms("rust").unwrap(), tokens[0]);
+
+        cipher.rotate().unwrap();
+        assert_eq!(cipher.query_terms("rust").len(), 2);
+    }
+
+    #[test]
+    fn remove_keyring_deletes_file() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::open(tmp.path(), true).unwrap();
+        cipher.remove_keyring().unwrap();
+        assert!(!keyring_path(tmp.path()).exists());
+        assert!(!cipher.encrypts_writes());
+    }
+}
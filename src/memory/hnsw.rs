@@ -0,0 +1,771 @@
+// HNSW approximate-nearest-neighbour index for embedding recall.
+//
+// Hierarchical Navigable Small World graph (Malkov & Yashunin) over
+// L2-normalized vectors, scored by cosine similarity. Supports incremental
+// insert/remove. Only the graph (ids + links) is persisted; vectors are
+// reloaded from the database on open, so the file stays small and can be
+// reconciled against the rows that actually exist.
+
+use anyhow::Context;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const FILE_MAGIC: &[u8; 8] = b"ZCHNSW01";
+
+/// Max links per node on layers above 0 (layer 0 allows twice as many).
+const DEFAULT_M: usize = 16;
+/// Candidate list size while inserting.
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+/// Minimum candidate list size while searching.
+const DEFAULT_EF_SEARCH: usize = 64;
+
+struct Node {
+    id: String,
+    vector: Vec<f32>,
+    /// `links[layer]` = neighbour slots on that layer; `links.len() - 1` is the node level.
+    links: Vec<Vec<u32>>,
+}
+
+/// Similarity-ordered slot for the search heaps.
+#[derive(Clone, Copy, PartialEq)]
+struct Scored {
+    sim: f32,
+    slot: u32,
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sim
+            .total_cmp(&other.sim)
+            .then_with(|| self.slot.cmp(&other.slot))
+    }
+}
+
+/// In-memory HNSW index keyed by memory id.
+pub struct HnswIndex {
+    dims: usize,
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    nodes: Vec<Option<Node>>,
+    free: Vec<u32>,
+    slots: HashMap<String, u32>,
+    entry: Option<u32>,
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    pub fn new(dims: usize) -> Self {
+        Self {
+            dims,
+            m: DEFAULT_M,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            ef_search: DEFAULT_EF_SEARCH,
+            nodes: Vec::new(),
+            free: Vec::new(),
+            slots: HashMap::new(),
+            entry: None,
+            rng_state: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Build an index over `vectors`, skipping any with the wrong dimensions.
+    pub fn build<'a>(dims: usize, vectors: impl IntoIterator<Item = (&'a str, &'a [f32])>) -> Self {
+        let mut index = Self::new(dims);
+        for (id, vector) in vectors {
+            index.insert(id, vector);
+        }
+        index
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.slots.contains_key(id)
+    }
+
+    /// Insert or replace `id`. Returns `false` if the vector has the wrong dimensions.
+    pub fn insert(&mut self, id: &str, vector: &[f32]) -> bool {
+        if vector.len() != self.dims || self.dims == 0 {
+            return false;
+        }
+        self.remove(id);
+
+        let level = self.random_level();
+        let node = Node {
+            id: id.to_string(),
+            vector: normalized(vector),
+            links: vec![Vec::new(); level + 1],
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot as usize] = Some(node);
+                slot
+            }
+            None => {
+                self.nodes.push(Some(node));
+                u32::try_from(self.nodes.len() - 1).expect("index exceeds u32::MAX nodes")
+            }
+        };
+        self.slots.insert(id.to_string(), slot);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(slot);
+            return true;
+        };
+
+        let query = self.node(slot).vector.clone();
+        let top_level = self.level(entry);
+        let mut entry_points = vec![Scored {
+            sim: self.sim_to(&query, entry),
+            slot: entry,
+        }];
+
+        for layer in (level + 1..=top_level).rev() {
+            entry_points = self.search_layer(&query, &entry_points, 1, layer);
+        }
+
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let neighbours = self.select_neighbours(&candidates, self.max_links(layer));
+            self.node_mut(slot).links[layer] = neighbours.clone();
+            for neighbour in neighbours {
+                self.connect(neighbour, slot, layer);
+            }
+            entry_points = candidates;
+        }
+
+        if level > top_level {
+            self.entry = Some(slot);
+        }
+        true
+    }
+
+    /// Remove `id`, repairing the links of nodes that pointed at it.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(slot) = self.slots.remove(id) else {
+            return false;
+        };
+        let removed = self.nodes[slot as usize]
+            .take()
+            .expect("slot map points at a live node");
+        self.free.push(slot);
+
+        for other in 0..self.nodes.len() {
+            let Some(node) = self.nodes[other].as_ref() else {
+                continue;
+            };
+            let other = u32::try_from(other).expect("slot fits u32");
+            let shared_layers = node.links.len().min(removed.links.len());
+            for layer in 0..shared_layers {
+                let links = &self.node(other).links[layer];
+                if !links.contains(&slot) {
+                    continue;
+                }
+                let base = self.node(other).vector.clone();
+                let mut pool: Vec<u32> = links
+                    .iter()
+                    .chain(removed.links[layer].iter())
+                    .copied()
+                    .filter(|&candidate| candidate != slot && candidate != other)
+                    .collect();
+                pool.sort_unstable();
+                pool.dedup();
+                let mut scored: Vec<Scored> = pool
+                    .into_iter()
+                    .map(|candidate| Scored {
+                        sim: self.sim_to(&base, candidate),
+                        slot: candidate,
+                    })
+                    .collect();
+                scored.sort_by(|a, b| b.cmp(a));
+                let kept = self.select_neighbours(&scored, self.max_links(layer));
+                self.node_mut(other).links[layer] = kept;
+            }
+        }
+
+        if self.entry == Some(slot) {
+            self.entry = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter_map(|(index, node)| node.as_ref().map(|n| (index, n.links.len())))
+                .max_by_key(|&(_, levels)| levels)
+                .map(|(index, _)| u32::try_from(index).expect("slot fits u32"));
+        }
+        true
+    }
+
+    /// Top-`k` ids by cosine similarity to `query`, best first.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 || query.len() != self.dims {
+            return Vec::new();
+        }
+
+        let query = normalized(query);
+        let mut entry_points = vec![Scored {
+            sim: self.sim_to(&query, entry),
+            slot: entry,
+        }];
+        for layer in (1..=self.level(entry)).rev() {
+            entry_points = self.search_layer(&query, &entry_points, 1, layer);
+        }
+        let found = self.search_layer(&query, &entry_points, self.ef_search.max(k), 0);
+
+        found
+            .into_iter()
+            .take(k)
+            .map(|scored| {
+                (
+                    self.node(scored.slot).id.clone(),
+                    scored.sim.clamp(0.0, 1.0),
+                )
+            })
+            .collect()
+    }
+
+    // ── Persistence ─────────────────────────────────────────────
+
+    /// Write the graph (not the vectors) to `path` atomically.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp = path.with_extension("hnsw.tmp");
+        {
+            let file = fs::File::create(&tmp)
+                .with_context(|| format!("failed to create {}", tmp.display()))?;
+            let mut out = BufWriter::new(file);
+            out.write_all(FILE_MAGIC)?;
+            write_u32(&mut out, to_u32(self.dims))?;
+            write_u32(&mut out, to_u32(self.m))?;
+            write_u32(&mut out, self.entry.unwrap_or(u32::MAX))?;
+            out.write_all(&self.rng_state.to_le_bytes())?;
+            write_u32(&mut out, to_u32(self.nodes.len()))?;
+            for node in &self.nodes {
+                let Some(node) = node else {
+                    out.write_all(&[0])?;
+                    continue;
+                };
+                out.write_all(&[1])?;
+                write_u32(&mut out, to_u32(node.id.len()))?;
+                out.write_all(node.id.as_bytes())?;
+                write_u32(&mut out, to_u32(node.links.len()))?;
+                for links in &node.links {
+                    write_u32(&mut out, to_u32(links.len()))?;
+                    for &link in links {
+                        write_u32(&mut out, link)?;
+                    }
+                }
+            }
+            out.flush()?;
+        }
+        fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load a graph saved by [`HnswIndex::save`] and reconcile it with
+    /// `vectors` (the current id → embedding rows). Nodes without a row are
+    /// removed and rows without a node are inserted. Returns the index and
+    /// whether reconciliation changed it.
+    pub fn load(
+        path: &Path,
+        dims: usize,
+        vectors: &HashMap<String, Vec<f32>>,
+    ) -> anyhow::Result<(Self, bool)> {
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let mut reader = Reader {
+            bytes: &bytes,
+            pos: 0,
+        };
+
+        anyhow::ensure!(reader.take(8)? == FILE_MAGIC, "not an HNSW index file");
+        let stored_dims = reader.u32()? as usize;
+        anyhow::ensure!(
+            stored_dims == dims,
+            "index dimensions {stored_dims} do not match embedder dimensions {dims}"
+        );
+
+        let mut index = Self::new(dims);
+        index.m = reader.u32()? as usize;
+        let entry = reader.u32()?;
+        index.rng_state = reader.u64()?;
+        let node_count = reader.u32()? as usize;
+
+        let mut stale = Vec::new();
+        for slot in 0..node_count {
+            if reader.take(1)?[0] == 0 {
+                index.nodes.push(None);
+                index.free.push(to_u32(slot));
+                continue;
+            }
+            let id_len = reader.u32()? as usize;
+            let id = String::from_utf8(reader.take(id_len)?.to_vec())
+                .context("index contains a non-UTF-8 id")?;
+            let levels = reader.u32()? as usize;
+            let mut links = Vec::with_capacity(levels);
+            for _ in 0..levels {
+                let count = reader.u32()? as usize;
+                let mut layer = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let link = reader.u32()?;
+                    anyhow::ensure!((link as usize) < node_count, "index link out of range");
+                    layer.push(link);
+                }
+                links.push(layer);
+            }
+            anyhow::ensure!(!links.is_empty(), "index node has no layers");
+
+            // Vectors come from the database; a missing or mismatched row
+            // means the node is stale and gets repaired after loading.
+            let vector = match vectors.get(&id) {
+                Some(vector) if vector.len() == dims => normalized(vector),
+                _ => {
+                    stale.push(id.clone());
+                    vec![0.0; dims]
+                }
+            };
+            index.slots.insert(id.clone(), to_u32(slot));
+            index.nodes.push(Some(Node { id, vector, links }));
+        }
+        anyhow::ensure!(reader.pos == bytes.len(), "trailing bytes in index file");
+
+        index.entry = (entry != u32::MAX).then_some(entry);
+        if let Some(entry) = index.entry {
+            anyhow::ensure!(
+                index.nodes.get(entry as usize).is_some_and(Option::is_some),
+                "index entry point is missing"
+            );
+        }
+        anyhow::ensure!(
+            index.entry.is_some() || index.slots.is_empty(),
+            "index has nodes but no entry point"
+        );
+        for (slot, node) in index.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            for (layer, links) in node.links.iter().enumerate() {
+                for &link in links {
+                    let target = index.nodes[link as usize].as_ref();
+                    anyhow::ensure!(
+                        target.is_some_and(|t| t.links.len() > layer) && link as usize != slot,
+                        "index link points at a missing node"
+                    );
+                }
+            }
+        }
+
+        for id in &stale {
+            index.remove(id);
+        }
+        let mut missing: Vec<(&String, &Vec<f32>)> = vectors
+            .iter()
+            .filter(|(id, vector)| vector.len() == dims && !index.contains(id))
+            .collect();
+        missing.sort_by(|a, b| a.0.cmp(b.0));
+        let changed = !stale.is_empty() || !missing.is_empty();
+        for (id, vector) in missing {
+            index.insert(id, vector);
+        }
+        Ok((index, changed))
+    }
+
+    // ── Graph internals ─────────────────────────────────────────
+
+    fn node(&self, slot: u32) -> &Node {
+        self.nodes[slot as usize]
+            .as_ref()
+            .expect("links only point at live nodes")
+    }
+
+    fn node_mut(&mut self, slot: u32) -> &mut Node {
+        self.nodes[slot as usize]
+            .as_mut()
+            .expect("links only point at live nodes")
+    }
+
+    fn level(&self, slot: u32) -> usize {
+        self.node(slot).links.len() - 1
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn sim_to(&self, query: &[f32], slot: u32) -> f32 {
+        dot(query, &self.node(slot).vector)
+    }
+
+    fn random_level(&mut self) -> usize {
+        // xorshift64*: deterministic, dependency-free level sampling.
+        self.rng_state ^= self.rng_state >> 12;
+        self.rng_state ^= self.rng_state << 25;
+        self.rng_state ^= self.rng_state >> 27;
+        let bits = self.rng_state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        #[allow(clippy::cast_precision_loss)]
+        let uniform = (bits as f64 + 1.0) / (1u64 << 53) as f64;
+        #[allow(clippy::cast_precision_loss)]
+        let level_mult = 1.0 / (self.m as f64).ln();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let level = (-uniform.ln() * level_mult).floor() as usize;
+        level.min(16)
+    }
+
+    /// Best-first search on one layer. Returns up to `ef` nodes, best first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[Scored],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Scored> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut candidates = BinaryHeap::new();
+        let mut found: BinaryHeap<std::cmp::Reverse<Scored>> = BinaryHeap::new();
+
+        for &point in entry_points {
+            if !visited[point.slot as usize] {
+                visited[point.slot as usize] = true;
+                candidates.push(point);
+                found.push(std::cmp::Reverse(point));
+            }
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = found.peek().map_or(f32::MIN, |w| w.0.sim);
+            if current.sim < worst && found.len() >= ef {
+                break;
+            }
+            let Some(links) = self.node(current.slot).links.get(layer) else {
+                continue;
+            };
+            for &neighbour in links {
+                if visited[neighbour as usize] {
+                    continue;
+                }
+                visited[neighbour as usize] = true;
+                let scored = Scored {
+                    sim: self.sim_to(query, neighbour),
+                    slot: neighbour,
+                };
+                let worst = found.peek().map_or(f32::MIN, |w| w.0.sim);
+                if found.len() < ef || scored.sim > worst {
+                    candidates.push(scored);
+                    found.push(std::cmp::Reverse(scored));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+        result.sort_by(|a, b| b.cmp(a));
+        result
+    }
+
+    /// Neighbour selection heuristic: prefer candidates that are closer to
+    /// the base node than to any already-selected neighbour, then top up
+    /// with the best remaining ones. `candidates` must be sorted best first.
+    fn select_neighbours(&self, candidates: &[Scored], limit: usize) -> Vec<u32> {
+        let mut selected: Vec<u32> = Vec::with_capacity(limit);
+        let mut skipped = Vec::new();
+        for candidate in candidates {
+            if selected.len() >= limit {
+                break;
+            }
+            let vector = &self.node(candidate.slot).vector;
+            let diverse = selected
+                .iter()
+                .all(|&s| dot(vector, &self.node(s).vector) < candidate.sim);
+            if diverse {
+                selected.push(candidate.slot);
+            } else {
+                skipped.push(candidate.slot);
+            }
+        }
+        for slot in skipped {
+            if selected.len() >= limit {
+                break;
+            }
+            selected.push(slot);
+        }
+        selected
+    }
+
+    /// Add a `from → to` link, pruning `from`'s links if over capacity.
+    fn connect(&mut self, from: u32, to: u32, layer: usize) {
+        let limit = self.max_links(layer);
+        let links = &mut self.node_mut(from).links[layer];
+        if links.contains(&to) {
+            return;
+        }
+        links.push(to);
+        if links.len() <= limit {
+            return;
+        }
+
+        let base = self.node(from).vector.clone();
+        let mut scored: Vec<Scored> = self.node(from).links[layer]
+            .iter()
+            .map(|&slot| Scored {
+                sim: self.sim_to(&base, slot),
+                slot,
+            })
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        let kept = self.select_neighbours(&scored, limit);
+        self.node_mut(from).links[layer] = kept;
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = dot(vector, vector).sqrt();
+    if !norm.is_finite() || norm <= f32::EPSILON {
+        return vec![0.0; vector.len()];
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).expect("index sizes fit in u32")
+}
+
+fn write_u32(out: &mut impl Write, value: u32) -> std::io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .context("index file is truncated")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> anyhow::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::vector::cosine_similarity;
+    use tempfile::TempDir;
+
+    /// Deterministic pseudo-random vectors.
+    fn vectors(count: usize, dims: usize, seed: u64) -> Vec<(String, Vec<f32>)> {
+        let mut state = seed;
+        (0..count)
+            .map(|i| {
+                let vector = (0..dims)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1_442_695_040_888_963_407);
+                        #[allow(clippy::cast_precision_loss)]
+                        let value = (state >> 40) as f32 / (1u64 << 24) as f32;
+                        value
+                    })
+                    .collect();
+                (format!("id-{i}"), vector)
+            })
+            .collect()
+    }
+
+    fn brute_force(data: &[(String, Vec<f32>)], query: &[f32], k: usize) -> Vec<String> {
+        let mut scored: Vec<(String, f32)> = data
+            .iter()
+            .map(|(id, v)| (id.clone(), cosine_similarity(query, v)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(id, _)| id).collect()
+    }
+
+    fn build(data: &[(String, Vec<f32>)], dims: usize) -> HnswIndex {
+        HnswIndex::build(dims, data.iter().map(|(id, v)| (id.as_str(), v.as_slice())))
+    }
+
+    #[test]
+    fn empty_index_returns_nothing() {
+        let index = HnswIndex::new(4);
+        assert!(index.is_empty());
+        assert!(index.search(&[1.0, 0.0, 0.0, 0.0], 5).is_empty());
+    }
+
+    #[test]
+    fn exact_match_ranks_first_with_cosine_score() {
+        let data = vectors(200, 16, 7);
+        let index = build(&data, 16);
+        assert_eq!(index.len(), 200);
+
+        let results = index.search(&data[42].1, 3);
+        assert_eq!(results[0].0, "id-42");
+        assert!((results[0].1 - 1.0).abs() < 1e-4);
+        let expected = cosine_similarity(&data[42].1, &data[43].1);
+        let score = |id: &str| results.iter().find(|(r, _)| r == id).map(|(_, s)| *s);
+        if let Some(found) = score("id-43") {
+            assert!((found - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn recall_matches_brute_force() {
+        let data = vectors(2000, 32, 11);
+        let index = build(&data, 32);
+        let queries = vectors(50, 32, 99);
+
+        let mut hits = 0;
+        for (_, query) in &queries {
+            let expected = brute_force(&data, query, 10);
+            let found: Vec<String> = index.search(query, 10).into_iter().map(|r| r.0).collect();
+            hits += expected.iter().filter(|id| found.contains(id)).count();
+        }
+        // ≥ 95% recall@10 against the exact scan.
+        assert!(hits >= 475, "recall too low: {hits}/500");
+    }
+
+    #[test]
+    fn wrong_dimensions_are_rejected() {
+        let mut index = HnswIndex::new(3);
+        assert!(!index.insert("a", &[1.0, 2.0]));
+        assert!(index.search(&[1.0, 2.0], 1).is_empty());
+    }
+
+    #[test]
+    fn replace_and_remove_update_results() {
+        let data = vectors(300, 8, 3);
+        let mut index = build(&data, 8);
+
+        assert!(index.remove("id-10"));
+        assert!(!index.remove("id-10"));
+        assert_eq!(index.len(), 299);
+        assert!(index
+            .search(&data[10].1, 5)
+            .iter()
+            .all(|(id, _)| id != "id-10"));
+
+        index.insert("id-11", &data[20].1);
+        assert_eq!(index.len(), 299);
+        let top: Vec<String> = index
+            .search(&data[20].1, 2)
+            .into_iter()
+            .map(|r| r.0)
+            .collect();
+        assert!(top.contains(&"id-11".to_string()));
+        assert!(top.contains(&"id-20".to_string()));
+
+        // Removing every node leaves a usable empty index.
+        for (id, _) in &data {
+            index.remove(id);
+        }
+        assert!(index.is_empty());
+        assert!(index.search(&data[0].1, 3).is_empty());
+        index.insert("fresh", &data[0].1);
+        assert_eq!(index.search(&data[0].1, 1)[0].0, "fresh");
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.hnsw");
+        let data = vectors(500, 16, 5);
+        let mut index = build(&data, 16);
+        index.remove("id-3");
+        index.save(&path).unwrap();
+
+        let rows: HashMap<String, Vec<f32>> = data
+            .iter()
+            .filter(|(id, _)| id != "id-3")
+            .cloned()
+            .collect();
+        let (loaded, changed) = HnswIndex::load(&path, 16, &rows).unwrap();
+        assert!(!changed);
+        assert_eq!(loaded.len(), 499);
+        for probe in [0, 100, 499] {
+            assert_eq!(
+                loaded.search(&data[probe].1, 1)[0].0,
+                index.search(&data[probe].1, 1)[0].0
+            );
+        }
+    }
+
+    #[test]
+    fn load_reconciles_with_current_rows() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.hnsw");
+        let data = vectors(100, 8, 21);
+        build(&data, 8).save(&path).unwrap();
+
+        // Row 0 deleted and one new row added since the graph was saved.
+        let mut rows: HashMap<String, Vec<f32>> = data[1..].iter().cloned().collect();
+        rows.insert("new".into(), data[0].1.clone());
+        let (loaded, changed) = HnswIndex::load(&path, 8, &rows).unwrap();
+        assert!(changed);
+        assert_eq!(loaded.len(), 100);
+        assert!(!loaded.contains("id-0"));
+        assert_eq!(loaded.search(&data[0].1, 1)[0].0, "new");
+    }
+
+    #[test]
+    fn load_rejects_corrupt_or_mismatched_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.hnsw");
+        let data = vectors(20, 8, 1);
+        build(&data, 8).save(&path).unwrap();
+
+        assert!(HnswIndex::load(&path, 16, &HashMap::new()).is_err());
+
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        assert!(HnswIndex::load(&path, 8, &HashMap::new()).is_err());
+
+        fs::write(&path, b"garbage").unwrap();
+        assert!(HnswIndex::load(&path, 8, &HashMap::new()).is_err());
+    }
+}
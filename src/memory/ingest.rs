@@ -0,0 +1,580 @@
+// Knowledge-base ingestion — `zeroclaw memory ingest <path>`.
+//
+// Walks a file or directory, extracts text from markdown/plain text, HTML
+// and (with the `rag-pdf` feature) PDF files, splits it with
+// `chunker::chunk_markdown` and stores every chunk in memory under
+// `MemoryCategory::Knowledge`. Each chunk starts with a `Source:` line
+// (path + line range) so recall results can cite the file they came from.
+//
+// Re-runs are incremental: a manifest of content hashes
+// (`memory/knowledge_manifest.json`) lets unchanged files be skipped,
+// changed files have their chunks replaced, and files deleted from an
+// ingested directory have their chunks removed.
+
+use super::chunker;
+use super::traits::{Memory, MemoryCategory};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Key prefix of every ingested chunk: `kb:<source path>#<chunk index>`.
+pub const KEY_PREFIX: &str = "kb:";
+
+const SOURCE_LINE_PREFIX: &str = "Source: ";
+const MANIFEST_FILE: &str = "knowledge_manifest.json";
+
+/// Options for a single ingestion run.
+#[derive(Debug, Clone, Copy)]
+pub struct IngestOptions {
+    /// Max approximate tokens per chunk.
+    pub max_tokens: usize,
+    /// Re-ingest files even when their content hash is unchanged.
+    pub force: bool,
+}
+
+/// What an ingestion run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestReport {
+    pub files_ingested: usize,
+    pub files_unchanged: usize,
+    /// Unsupported, unreadable or empty files.
+    pub files_skipped: usize,
+    /// Previously ingested files that no longer exist.
+    pub files_removed: usize,
+    pub chunks_stored: usize,
+    pub chunks_removed: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    sources: BTreeMap<String, SourceRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SourceRecord {
+    /// SHA-256 of the raw file bytes.
+    hash: String,
+    chunks: usize,
+}
+
+impl Manifest {
+    fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Memory key of chunk `index` of `source`.
+pub fn chunk_key(source: &str, index: usize) -> String {
+    format!("{KEY_PREFIX}{source}#{index}")
+}
+
+/// Split an ingested chunk into its citation (`path#Lstart-Lend`) and body.
+///
+/// Returns `None` for content that was not produced by ingestion.
+pub fn split_citation(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix(SOURCE_LINE_PREFIX)?;
+    let (citation, body) = rest.split_once('\n').unwrap_or((rest, ""));
+    Some((citation.trim(), body.trim_start_matches('\n')))
+}
+
+/// Ingest `target` (a file or directory) into `memory`.
+///
+/// The manifest lives in `<workspace>/memory/`, next to the other memory
+/// databases. It is saved even when a store fails part-way, so a re-run
+/// resumes with the files that were not ingested yet.
+pub async fn ingest_path(
+    memory: &dyn Memory,
+    workspace_dir: &Path,
+    target: &Path,
+    options: IngestOptions,
+) -> Result<IngestReport> {
+    let root = target
+        .canonicalize()
+        .with_context(|| format!("Cannot ingest {}: path not found", target.display()))?;
+
+    let files = if root.is_dir() {
+        let mut files = Vec::new();
+        collect_files(&root, &mut files);
+        files.sort();
+        files
+    } else if root.is_file() {
+        vec![root.clone()]
+    } else {
+        bail!("Cannot ingest {}: not a file or directory", root.display());
+    };
+
+    let manifest_path = workspace_dir.join("memory").join(MANIFEST_FILE);
+    let mut manifest = Manifest::load(&manifest_path)?;
+    let mut report = IngestReport::default();
+
+    let result = async {
+        let mut seen = BTreeSet::new();
+        for file in &files {
+            let source = file.display().to_string();
+            ingest_file(memory, file, &source, options, &mut manifest, &mut report).await?;
+            seen.insert(source);
+        }
+        if root.is_dir() {
+            prune_missing(memory, &root, &seen, &mut manifest, &mut report).await?;
+        }
+        anyhow::Ok(())
+    }
+    .await;
+
+    manifest.save(&manifest_path)?;
+    result.map(|()| report)
+}
+
+async fn ingest_file(
+    memory: &dyn Memory,
+    path: &Path,
+    source: &str,
+    options: IngestOptions,
+    manifest: &mut Manifest,
+    report: &mut IngestReport,
+) -> Result<()> {
+    let Ok(bytes) = std::fs::read(path) else {
+        tracing::warn!("Skipping unreadable file {source}");
+        report.files_skipped += 1;
+        return Ok(());
+    };
+    let hash = hex::encode(Sha256::digest(&bytes));
+    let previous = manifest.sources.get(source).cloned();
+
+    if let Some(record) = previous
+        .as_ref()
+        .filter(|r| r.hash == hash && !options.force)
+    {
+        // Only trust the manifest if the chunks are still in memory.
+        if record.chunks == 0 || memory.get(&chunk_key(source, 0)).await?.is_some() {
+            report.files_unchanged += 1;
+            return Ok(());
+        }
+    }
+
+    let text = extract_text(path, &bytes).unwrap_or_default();
+    let chunks = chunker::chunk_markdown(&text, options.max_tokens.max(1));
+
+    let mut cursor = 0;
+    for chunk in &chunks {
+        let citation = match locate_lines(&text, &chunk.content, &mut cursor) {
+            Some((start, end)) => format!("{source}#L{start}-L{end}"),
+            None => source.to_string(),
+        };
+        memory
+            .store(
+                &chunk_key(source, chunk.index),
+                &format!("{SOURCE_LINE_PREFIX}{citation}\n\n{}", chunk.content),
+                MemoryCategory::Knowledge,
+                None,
+            )
+            .await?;
+    }
+    report.chunks_stored += chunks.len();
+
+    if let Some(record) = &previous {
+        report.chunks_removed += forget_chunks(memory, source, chunks.len()..record.chunks).await?;
+    }
+
+    if chunks.is_empty() {
+        report.files_skipped += 1;
+        manifest.sources.remove(source);
+    } else {
+        report.files_ingested += 1;
+        manifest.sources.insert(
+            source.to_string(),
+            SourceRecord {
+                hash,
+                chunks: chunks.len(),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Drop chunks of files under `root` that were ingested before but are gone now.
+async fn prune_missing(
+    memory: &dyn Memory,
+    root: &Path,
+    seen: &BTreeSet<String>,
+    manifest: &mut Manifest,
+    report: &mut IngestReport,
+) -> Result<()> {
+    let stale: Vec<(String, usize)> = manifest
+        .sources
+        .iter()
+        .filter(|(source, _)| Path::new(source).starts_with(root) && !seen.contains(*source))
+        .map(|(source, record)| (source.clone(), record.chunks))
+        .collect();
+
+    for (source, chunks) in stale {
+        report.chunks_removed += forget_chunks(memory, &source, 0..chunks).await?;
+        report.files_removed += 1;
+        manifest.sources.remove(&source);
+    }
+    Ok(())
+}
+
+async fn forget_chunks(
+    memory: &dyn Memory,
+    source: &str,
+    indices: std::ops::Range<usize>,
+) -> Result<usize> {
+    let mut removed = 0;
+    for index in indices {
+        if memory.forget(&chunk_key(source, index)).await? {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn is_supported(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("md" | "markdown" | "txt" | "text" | "html" | "htm") => true,
+        Some("pdf") => cfg!(feature = "rag-pdf"),
+        _ => false,
+    }
+}
+
+/// Recursively collect supported files, skipping hidden entries.
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else if path.is_file() && is_supported(&path) {
+            out.push(path);
+        }
+    }
+}
+
+/// Plain text (markdown-flavoured where possible) of a supported file.
+fn extract_text(path: &Path, bytes: &[u8]) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "md" | "markdown" | "txt" | "text" => Some(String::from_utf8_lossy(bytes).into_owned()),
+        "html" | "htm" => Some(html_to_text(&String::from_utf8_lossy(bytes))),
+        #[cfg(feature = "rag-pdf")]
+        "pdf" => pdf_extract::extract_text_from_mem(bytes).ok(),
+        _ => {
+            tracing::warn!(
+                "Skipping {}: unsupported file type (PDF needs the 'rag-pdf' feature)",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+static HTML_HIDDEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<!--.*?-->|<script\b.*?</script\s*>|<style\b.*?</style\s*>|<head\b.*?</head\s*>",
+    )
+    .unwrap()
+});
+static HTML_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]\s*>").unwrap());
+static HTML_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)</?(p|div|br|li|tr|ul|ol|table|section|article|pre|blockquote|hr|h[1-6])\b[^>]*>",
+    )
+    .unwrap()
+});
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+/// Reduce HTML to text, keeping headings as markdown so chunks keep their
+/// section context.
+fn html_to_text(html: &str) -> String {
+    let text = HTML_HIDDEN.replace_all(html, "");
+    let text = HTML_HEADING.replace_all(&text, |caps: &regex::Captures<'_>| {
+        let level = caps[1].parse::<usize>().unwrap_or(1).min(3);
+        let title = HTML_TAG.replace_all(&caps[2], "");
+        format!(
+            "\n\n{} {}\n\n",
+            "#".repeat(level),
+            title.split_whitespace().collect::<Vec<_>>().join(" ")
+        )
+    });
+    let text = HTML_BLOCK.replace_all(&text, "\n");
+    let text = HTML_TAG.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    let mut out = String::with_capacity(text.len());
+    let mut blank = true;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            if !blank {
+                out.push('\n');
+            }
+            blank = true;
+        } else {
+            out.push_str(line);
+            out.push('\n');
+            blank = false;
+        }
+    }
+    out.trim().to_string()
+}
+
+/// 1-based line range of `chunk` in `text`, searching forward from `cursor`.
+///
+/// Chunks repeat their section heading, so lines that only occur before
+/// `cursor` are skipped rather than treated as the start.
+fn locate_lines(text: &str, chunk: &str, cursor: &mut usize) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = chunk
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let start = lines
+        .iter()
+        .find_map(|line| text[*cursor..].find(line).map(|pos| *cursor + pos))?;
+    let last = lines.last()?;
+    let end = text[start..]
+        .find(last)
+        .map_or(start, |pos| start + pos + last.len());
+
+    *cursor = end;
+    let line_at = |offset: usize| text[..offset].matches('\n').count() + 1;
+    Some((line_at(start), line_at(end.saturating_sub(1).max(start))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    const OPTIONS: IngestOptions = IngestOptions {
+        max_tokens: 512,
+        force: false,
+    };
+
+    fn setup() -> (TempDir, TempDir, SqliteMemory) {
+        let workspace = TempDir::new().unwrap();
+        let docs = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(workspace.path()).unwrap();
+        (workspace, docs, mem)
+    }
+
+    async fn knowledge_keys(mem: &SqliteMemory) -> Vec<String> {
+        let mut keys: Vec<String> = mem
+            .list(Some(&MemoryCategory::Knowledge), None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[tokio::test]
+    async fn ingests_markdown_with_citations() {
+        let (workspace, docs, mem) = setup();
+        std::fs::write(
+            docs.path().join("deploy.md"),
+            "# Deploy\n\nRun the blue-green rollout.\n\n## Rollback\n\nRevert the release tag.\n",
+        )
+        .unwrap();
+
+        let report = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(report.files_ingested, 1);
+        assert_eq!(report.chunks_stored, 2);
+
+        let hits = mem.recall("rollback release", 5, None).await.unwrap();
+        let hit = hits
+            .iter()
+            .find(|e| e.category == MemoryCategory::Knowledge)
+            .unwrap();
+        let (citation, body) = split_citation(&hit.content).unwrap();
+        assert!(citation.ends_with("deploy.md#L5-L7"), "{citation}");
+        assert!(body.starts_with("## Rollback"));
+        assert!(hit.key.starts_with(KEY_PREFIX));
+    }
+
+    #[tokio::test]
+    async fn reingest_skips_unchanged_and_replaces_changed() {
+        let (workspace, docs, mem) = setup();
+        let file = docs.path().join("runbook.md");
+        std::fs::write(&file, "# A\n\none\n\n# B\n\ntwo\n\n# C\n\nthree\n").unwrap();
+        ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+
+        let again = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(again.files_unchanged, 1);
+        assert_eq!(again.chunks_stored, 0);
+
+        std::fs::write(&file, "# A\n\nonly section left\n").unwrap();
+        let changed = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(changed.files_ingested, 1);
+        assert_eq!(changed.chunks_removed, 2);
+
+        let keys = knowledge_keys(&mem).await;
+        assert_eq!(keys.len(), 1);
+        let entry = mem.get(&keys[0]).await.unwrap().unwrap();
+        assert!(entry.content.contains("only section left"));
+    }
+
+    #[tokio::test]
+    async fn force_reingests_unchanged_files() {
+        let (workspace, docs, mem) = setup();
+        std::fs::write(docs.path().join("a.txt"), "plain notes").unwrap();
+        ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+
+        let forced = IngestOptions {
+            force: true,
+            ..OPTIONS
+        };
+        let report = ingest_path(&mem, workspace.path(), docs.path(), forced)
+            .await
+            .unwrap();
+        assert_eq!(report.files_ingested, 1);
+        assert_eq!(report.files_unchanged, 0);
+    }
+
+    #[tokio::test]
+    async fn reingests_when_chunks_were_cleared() {
+        let (workspace, docs, mem) = setup();
+        std::fs::write(docs.path().join("a.md"), "# Notes\n\nkeep me").unwrap();
+        ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        for key in knowledge_keys(&mem).await {
+            mem.forget(&key).await.unwrap();
+        }
+
+        let report = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(report.files_ingested, 1);
+        assert_eq!(knowledge_keys(&mem).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn deleted_files_are_pruned() {
+        let (workspace, docs, mem) = setup();
+        std::fs::write(docs.path().join("keep.md"), "# Keep\n\nstays").unwrap();
+        std::fs::write(docs.path().join("gone.md"), "# Gone\n\nleaves").unwrap();
+        ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+
+        std::fs::remove_file(docs.path().join("gone.md")).unwrap();
+        let report = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(report.chunks_removed, 1);
+
+        let keys = knowledge_keys(&mem).await;
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].contains("keep.md"));
+    }
+
+    #[tokio::test]
+    async fn skips_unsupported_and_hidden_files() {
+        let (workspace, docs, mem) = setup();
+        std::fs::write(docs.path().join("image.png"), [0u8, 1, 2]).unwrap();
+        std::fs::create_dir(docs.path().join(".git")).unwrap();
+        std::fs::write(docs.path().join(".git").join("notes.md"), "secret").unwrap();
+        std::fs::write(docs.path().join("empty.md"), "   \n").unwrap();
+
+        let report = ingest_path(&mem, workspace.path(), docs.path(), OPTIONS)
+            .await
+            .unwrap();
+        assert_eq!(report.files_ingested, 0);
+        assert_eq!(report.files_skipped, 1);
+        assert!(knowledge_keys(&mem).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_path_is_an_error() {
+        let (workspace, docs, mem) = setup();
+        let err = ingest_path(&mem, workspace.path(), &docs.path().join("nope"), OPTIONS)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("path not found"));
+    }
+
+    #[test]
+    fn html_to_text_keeps_headings_and_drops_scripts() {
+        let html = "<html><head><title>t</title></head><body>\
+            <h1>Runbook</h1><script>alert(1)</script>\
+            <p>Restart the <b>api</b> pod &amp; check logs.</p>\
+            <h2>Escalation</h2><ul><li>Page on-call</li></ul></body></html>";
+        let text = html_to_text(html);
+        assert_eq!(
+            text,
+            "# Runbook\n\nRestart the api pod & check logs.\n\n## Escalation\n\nPage on-call"
+        );
+    }
+
+    #[test]
+    fn split_citation_parses_ingested_content() {
+        let (citation, body) = split_citation("Source: /docs/a.md#L1-L3\n\n# A\nbody").unwrap();
+        assert_eq!(citation, "/docs/a.md#L1-L3");
+        assert_eq!(body, "# A\nbody");
+        assert!(split_citation("regular memory").is_none());
+    }
+
+    #[test]
+    fn locate_lines_skips_repeated_heading() {
+        let text = "# H\npara one\n\npara two\n";
+        let mut cursor = 0;
+        assert_eq!(
+            locate_lines(text, "# H\npara one", &mut cursor),
+            Some((1, 2))
+        );
+        assert_eq!(
+            locate_lines(text, "# H\npara two", &mut cursor),
+            Some((4, 4))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use super::sqlite::SqliteMemory;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE};
 use async_trait::async_trait;
 use chrono::Local;
 use parking_lot::Mutex;
@@ -137,6 +137,7 @@ impl LucidMemory {
             MemoryCategory::Core => "decision",
             MemoryCategory::Daily => "context",
             MemoryCategory::Conversation => "conversation",
+            MemoryCategory::Knowledge => "knowledge",
             MemoryCategory::Custom(_) => "learning",
         }
     }
@@ -151,6 +152,7 @@ impl LucidMemory {
             "decision" | "learning" | "solution" => MemoryCategory::Core,
             "context" | "conversation" => MemoryCategory::Conversation,
             "bug" => MemoryCategory::Daily,
+            "knowledge" => MemoryCategory::Knowledge,
             other => MemoryCategory::Custom(other.to_string()),
         }
     }
@@ -226,6 +228,8 @@ impl LucidMemory {
                 timestamp: now.clone(),
                 session_id: None,
                 score: Some((1.0 - rank as f64 * 0.05).max(0.1)),
+                metadata: MemoryMetadata::default(),
+                namespace: DEFAULT_NAMESPACE.to_string(),
             });
         }
 
@@ -306,6 +310,10 @@ impl Memory for LucidMemory {
         "lucid"
     }
 
+    fn supports_namespaces(&self) -> bool {
+        true
+    }
+
     async fn store(
         &self,
         key: &str,
@@ -320,6 +328,60 @@ impl Memory for LucidMemory {
         Ok(())
     }
 
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.local
+            .store_with_metadata(key, content, category.clone(), session_id, metadata)
+            .await?;
+        self.sync_to_lucid_async(key, content, &category).await;
+        Ok(())
+    }
+
+    async fn store_in(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.local
+            .store_in(
+                namespace,
+                key,
+                content,
+                category.clone(),
+                session_id,
+                metadata,
+            )
+            .await?;
+        // Lucid has no namespaces; only global memories are mirrored.
+        if namespace == DEFAULT_NAMESPACE {
+            self.sync_to_lucid_async(key, content, &category).await;
+        }
+        Ok(())
+    }
+
+    async fn recall_in(
+        &self,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.local
+            .recall_in(namespaces, query, limit, session_id, tags)
+            .await
+    }
+
     async fn recall(
         &self,
         query: &str,
@@ -363,6 +425,23 @@ impl Memory for LucidMemory {
         self.local.get(key).await
     }
 
+    async fn get_in(&self, namespace: &str, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.local.get_in(namespace, key).await
+    }
+
+    async fn list_in(
+        &self,
+        namespaces: &[String],
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.local.list_in(namespaces, category, session_id).await
+    }
+
+    async fn forget_in(&self, namespace: &str, key: &str) -> anyhow::Result<bool> {
+        self.local.forget_in(namespace, key).await
+    }
+
     async fn list(
         &self,
         category: Option<&MemoryCategory>,
@@ -382,6 +461,27 @@ impl Memory for LucidMemory {
     async fn health_check(&self) -> bool {
         self.local.health_check().await
     }
+
+    async fn export_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.local.export_entries().await
+    }
+
+    async fn embedding(&self, namespace: &str, key: &str) -> anyhow::Result<Option<Vec<f32>>> {
+        self.local.embedding(namespace, key).await
+    }
+
+    async fn import_entry(
+        &self,
+        entry: &MemoryEntry,
+        embedding: Option<&[f32]>,
+    ) -> anyhow::Result<()> {
+        self.local.import_entry(entry, embedding).await?;
+        if entry.namespace == DEFAULT_NAMESPACE {
+            self.sync_to_lucid_async(&entry.key, &entry.content, &entry.category)
+                .await;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(all(test, unix))]
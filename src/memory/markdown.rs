@@ -1,25 +1,66 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::scoring::{self, RecallWeights};
+use super::traits::{
+    ensure_default_namespace, Memory, MemoryCategory, MemoryEntry, MemoryMetadata,
+    DEFAULT_NAMESPACE,
+};
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, Utc};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// Marker that starts the metadata comment trailing an entry line.
+const META_PREFIX: &str = "<!-- meta: ";
+const META_SUFFIX: &str = " -->";
+
 /// Markdown-based memory — plain files as source of truth
 ///
 /// Layout:
 ///   workspace/MEMORY.md          — curated long-term memory (core)
 ///   workspace/memory/YYYY-MM-DD.md — daily logs (append-only)
+///
+/// Metadata is kept on the entry line as a trailing HTML comment so the
+/// files still render cleanly. Access counts are not tracked (append-only).
 pub struct MarkdownMemory {
     workspace_dir: PathBuf,
+    weights: RecallWeights,
 }
 
 impl MarkdownMemory {
     pub fn new(workspace_dir: &Path) -> Self {
         Self {
             workspace_dir: workspace_dir.to_path_buf(),
+            weights: RecallWeights::default(),
+        }
+    }
+
+    /// Set the importance/recency weighting applied to recall scores.
+    #[must_use]
+    pub fn with_recall_weights(mut self, weights: RecallWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    fn format_line(key: &str, content: &str, metadata: &MemoryMetadata) -> String {
+        let metadata = metadata.without_access_count();
+        match serde_json::to_string(&metadata) {
+            Ok(json) if !metadata.is_empty() => {
+                format!("- **{key}**: {content} {META_PREFIX}{json}{META_SUFFIX}")
+            }
+            _ => format!("- **{key}**: {content}"),
         }
     }
 
+    /// Split a trailing metadata comment off an entry line.
+    fn split_metadata(line: &str) -> (&str, MemoryMetadata) {
+        line.strip_suffix(META_SUFFIX)
+            .and_then(|rest| rest.rfind(META_PREFIX).map(|idx| (rest, idx)))
+            .and_then(|(rest, idx)| {
+                let metadata = serde_json::from_str(&rest[idx + META_PREFIX.len()..]).ok()?;
+                Some((rest[..idx].trim_end(), metadata))
+            })
+            .unwrap_or((line, MemoryMetadata::default()))
+    }
+
     fn memory_dir(&self) -> PathBuf {
         self.workspace_dir.join("memory")
     }
@@ -33,6 +74,13 @@ impl MarkdownMemory {
         self.memory_dir().join(format!("{date}.md"))
     }
 
+    /// Daily log for the date an exported entry was written, if it has one.
+    fn daily_path_for(&self, timestamp: &str) -> Option<PathBuf> {
+        let date = timestamp.get(..10)?;
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+        Some(self.memory_dir().join(format!("{date}.md")))
+    }
+
     async fn ensure_dirs(&self) -> anyhow::Result<()> {
         fs::create_dir_all(self.memory_dir()).await?;
         Ok(())
@@ -51,7 +99,7 @@ impl MarkdownMemory {
             let header = if path == self.core_path() {
                 "# Long-Term Memory\n\n"
             } else {
-                let date = Local::now().format("%Y-%m-%d").to_string();
+                let date = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
                 &format!("# Daily Log — {date}\n\n")
             };
             format!("{header}{content}\n")
@@ -83,6 +131,7 @@ impl MarkdownMemory {
             .map(|(i, line)| {
                 let trimmed = line.trim();
                 let clean = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+                let (clean, metadata) = Self::split_metadata(clean);
                 MemoryEntry {
                     id: format!("{filename}:{i}"),
                     key: format!("{filename}:{i}"),
@@ -91,6 +140,8 @@ impl MarkdownMemory {
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    metadata,
+                    namespace: DEFAULT_NAMESPACE.to_string(),
                 }
             })
             .collect()
@@ -139,13 +190,31 @@ impl Memory for MarkdownMemory {
     }
 
     async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            &MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         _session_id: Option<&str>,
+        metadata: &MemoryMetadata,
     ) -> anyhow::Result<()> {
-        let entry = format!("- **{key}**: {content}");
+        let entry = Self::format_line(key, content, metadata);
         let path = match category {
             MemoryCategory::Core => self.core_path(),
             _ => self.daily_path(),
@@ -182,11 +251,7 @@ impl Memory for MarkdownMemory {
             })
             .collect();
 
-        scored.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        scoring::rerank(&mut scored, &self.weights, Utc::now());
         scored.truncate(limit);
         Ok(scored)
     }
@@ -224,6 +289,22 @@ impl Memory for MarkdownMemory {
     async fn health_check(&self) -> bool {
         self.workspace_dir.exists()
     }
+
+    async fn import_entry(
+        &self,
+        entry: &MemoryEntry,
+        _embedding: Option<&[f32]>,
+    ) -> anyhow::Result<()> {
+        ensure_default_namespace(self.name(), &entry.namespace)?;
+        let line = Self::format_line(&entry.key, &entry.content, &entry.metadata);
+        let path = match entry.category {
+            MemoryCategory::Core => self.core_path(),
+            _ => self
+                .daily_path_for(&entry.timestamp)
+                .unwrap_or_else(|| self.daily_path()),
+        };
+        self.append_to_file(&path, &line).await
+    }
 }
 
 #[cfg(test)]
@@ -352,4 +433,65 @@ mod tests {
         let (_tmp, mem) = temp_workspace();
         assert_eq!(mem.count().await.unwrap(), 0);
     }
+
+    #[tokio::test]
+    async fn markdown_import_keeps_entry_date() {
+        let (tmp, mem) = temp_workspace();
+        let entry = MemoryEntry {
+            id: "x".into(),
+            key: "standup".into(),
+            content: "shipped the release".into(),
+            category: MemoryCategory::Daily,
+            timestamp: "2025-11-03T09:15:00+00:00".into(),
+            session_id: None,
+            score: None,
+            metadata: MemoryMetadata::default(),
+            namespace: DEFAULT_NAMESPACE.into(),
+        };
+        mem.import_entry(&entry, None).await.unwrap();
+
+        let log = tmp.path().join("memory").join("2025-11-03.md");
+        let written = fs::read_to_string(&log).await.unwrap();
+        assert!(written.starts_with("# Daily Log — 2025-11-03"));
+        assert!(written.contains("**standup**: shipped the release"));
+    }
+
+    #[tokio::test]
+    async fn markdown_metadata_roundtrips_through_file() {
+        let (_tmp, mem) = temp_workspace();
+        let metadata = MemoryMetadata {
+            tags: vec!["infra".into()],
+            importance: Some(0.9),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "db",
+            "Postgres runs on port 5433",
+            MemoryCategory::Core,
+            None,
+            &metadata,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "plain",
+            "Postgres is managed by ops",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("postgres", 10, None).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].content, "**db**: Postgres runs on port 5433");
+        assert_eq!(results[0].metadata, metadata);
+        assert!(results[1].metadata.is_empty());
+
+        let tagged = mem
+            .recall_tagged("postgres", 10, None, &["INFRA".into()])
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+    }
 }
@@ -1,18 +1,26 @@
 pub mod backend;
 pub mod chunker;
 pub mod cli;
+pub mod consolidation;
 pub mod embeddings;
+pub mod encryption;
+pub mod hnsw;
 pub mod hygiene;
+pub mod ingest;
 pub mod lucid;
 pub mod markdown;
+pub mod namespace;
 pub mod none;
 #[cfg(feature = "memory-postgres")]
 pub mod postgres;
 pub mod qdrant;
+pub mod reranker;
 pub mod response_cache;
+pub mod scoring;
 pub mod snapshot;
 pub mod sqlite;
 pub mod traits;
+pub mod transfer;
 pub mod vector;
 
 #[allow(unused_imports)]
@@ -22,6 +30,7 @@ pub use backend::{
 };
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
+pub use namespace::MemoryScope;
 pub use none::NoneMemory;
 #[cfg(feature = "memory-postgres")]
 pub use postgres::PostgresMemory;
@@ -30,9 +39,9 @@ pub use response_cache::ResponseCache;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE};
 
-use crate::config::{EmbeddingRouteConfig, MemoryConfig, StorageProviderConfig};
+use crate::config::{EmbeddingRouteConfig, MemoryConfig, ReliabilityConfig, StorageProviderConfig};
 use anyhow::Context;
 use std::path::Path;
 use std::sync::Arc;
@@ -42,6 +51,7 @@ fn create_memory_with_builders<F, G>(
     workspace_dir: &Path,
     mut sqlite_builder: F,
     mut postgres_builder: G,
+    recall_weights: scoring::RecallWeights,
     unknown_context: &str,
 ) -> anyhow::Result<Box<dyn Memory>>
 where
@@ -55,15 +65,17 @@ where
             Ok(Box::new(LucidMemory::new(workspace_dir, local)))
         }
         MemoryBackendKind::Postgres => postgres_builder(),
-        MemoryBackendKind::Qdrant | MemoryBackendKind::Markdown => {
-            Ok(Box::new(MarkdownMemory::new(workspace_dir)))
-        }
+        MemoryBackendKind::Qdrant | MemoryBackendKind::Markdown => Ok(Box::new(
+            MarkdownMemory::new(workspace_dir).with_recall_weights(recall_weights),
+        )),
         MemoryBackendKind::None => Ok(Box::new(NoneMemory::new())),
         MemoryBackendKind::Unknown => {
             tracing::warn!(
                 "Unknown memory backend '{backend_name}'{unknown_context}, falling back to markdown"
             );
-            Ok(Box::new(MarkdownMemory::new(workspace_dir)))
+            Ok(Box::new(
+                MarkdownMemory::new(workspace_dir).with_recall_weights(recall_weights),
+            ))
         }
     }
 }
@@ -169,13 +181,42 @@ fn resolve_embedding_config(
     }
 }
 
+/// Build the resolved embedding provider, retrying transient failures with the
+/// `[reliability]` provider retry budget.
+fn build_embedder(
+    resolved: &ResolvedEmbeddingConfig,
+    reliability: &ReliabilityConfig,
+) -> Box<dyn embeddings::EmbeddingProvider> {
+    let provider = embeddings::create_embedding_provider(
+        &resolved.provider,
+        resolved.api_key.as_deref(),
+        &resolved.model,
+        resolved.dimensions,
+    );
+    if reliability.provider_retries == 0 || provider.name() == "none" {
+        return provider;
+    }
+    Box::new(embeddings::RetryingEmbedding::new(
+        provider,
+        reliability.provider_retries,
+        reliability.provider_backoff_ms,
+    ))
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
     workspace_dir: &Path,
     api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    create_memory_with_storage_and_routes(config, &[], None, workspace_dir, api_key)
+    create_memory_with_storage_and_routes(
+        config,
+        &[],
+        None,
+        workspace_dir,
+        api_key,
+        &ReliabilityConfig::default(),
+    )
 }
 
 /// Factory: create memory with optional storage-provider override.
@@ -184,8 +225,16 @@ pub fn create_memory_with_storage(
     storage_provider: Option<&StorageProviderConfig>,
     workspace_dir: &Path,
     api_key: Option<&str>,
+    reliability: &ReliabilityConfig,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    create_memory_with_storage_and_routes(config, &[], storage_provider, workspace_dir, api_key)
+    create_memory_with_storage_and_routes(
+        config,
+        &[],
+        storage_provider,
+        workspace_dir,
+        api_key,
+        reliability,
+    )
 }
 
 /// Factory: create memory with optional storage-provider override and embedding routes.
@@ -195,10 +244,12 @@ pub fn create_memory_with_storage_and_routes(
     storage_provider: Option<&StorageProviderConfig>,
     workspace_dir: &Path,
     api_key: Option<&str>,
+    reliability: &ReliabilityConfig,
 ) -> anyhow::Result<Box<dyn Memory>> {
     let backend_name = effective_memory_backend_name(&config.backend, storage_provider);
     let backend_kind = classify_memory_backend(&backend_name);
     let resolved_embedding = resolve_embedding_config(config, embedding_routes, api_key);
+    let cipher = encryption::runtime_cipher()?;
 
     // Best-effort memory hygiene/retention pass (throttled by state file).
     if let Err(e) = hygiene::run_if_due(config, workspace_dir) {
@@ -213,7 +264,7 @@ pub fn create_memory_with_storage_and_routes(
             MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
         )
     {
-        if let Err(e) = snapshot::export_snapshot(workspace_dir) {
+        if let Err(e) = snapshot::export_snapshot(workspace_dir, cipher.as_deref()) {
             tracing::warn!("memory snapshot skipped: {e}");
         }
     }
@@ -228,7 +279,7 @@ pub fn create_memory_with_storage_and_routes(
         && snapshot::should_hydrate(workspace_dir)
     {
         tracing::info!("🧬 Cold boot detected — hydrating from MEMORY_SNAPSHOT.md");
-        match snapshot::hydrate_from_snapshot(workspace_dir) {
+        match snapshot::hydrate_from_snapshot(workspace_dir, cipher.as_deref()) {
             Ok(count) => {
                 if count > 0 {
                     tracing::info!("🧬 Hydrated {count} core memories from snapshot");
@@ -244,14 +295,11 @@ pub fn create_memory_with_storage_and_routes(
         config: &MemoryConfig,
         workspace_dir: &Path,
         resolved_embedding: &ResolvedEmbeddingConfig,
+        reliability: &ReliabilityConfig,
+        cipher: Option<Arc<encryption::MemoryCipher>>,
     ) -> anyhow::Result<SqliteMemory> {
         let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
+            Arc::from(build_embedder(resolved_embedding, reliability));
 
         #[allow(clippy::cast_possible_truncation)]
         let mem = SqliteMemory::with_embedder(
@@ -261,12 +309,16 @@ pub fn create_memory_with_storage_and_routes(
             config.keyword_weight as f32,
             config.embedding_cache_size,
             config.sqlite_open_timeout_secs,
-        )?;
+        )?
+        .with_ann_index(config.sqlite_ann_index)
+        .with_recall_weights(scoring::RecallWeights::from_config(config))
+        .with_cipher(cipher);
         Ok(mem)
     }
 
     #[cfg(feature = "memory-postgres")]
     fn build_postgres_memory(
+        config: &MemoryConfig,
         storage_provider: Option<&StorageProviderConfig>,
     ) -> anyhow::Result<Box<dyn Memory>> {
         let storage_provider = storage_provider
@@ -286,12 +338,14 @@ pub fn create_memory_with_storage_and_routes(
             &storage_provider.table,
             storage_provider.connect_timeout_secs,
             storage_provider.tls,
-        )?;
+        )?
+        .with_recall_weights(scoring::RecallWeights::from_config(config));
         Ok(Box::new(memory))
     }
 
     #[cfg(not(feature = "memory-postgres"))]
     fn build_postgres_memory(
+        _config: &MemoryConfig,
         _storage_provider: Option<&StorageProviderConfig>,
     ) -> anyhow::Result<Box<dyn Memory>> {
         anyhow::bail!(
@@ -321,30 +375,32 @@ pub fn create_memory_with_storage_and_routes(
             .or_else(|| std::env::var("QDRANT_API_KEY").ok())
             .filter(|s| !s.trim().is_empty());
         let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &resolved_embedding.provider,
-                resolved_embedding.api_key.as_deref(),
-                &resolved_embedding.model,
-                resolved_embedding.dimensions,
-            ));
+            Arc::from(build_embedder(&resolved_embedding, reliability));
         tracing::info!(
             "📦 Qdrant memory backend configured (url: {}, collection: {})",
             url,
             collection
         );
-        return Ok(Box::new(QdrantMemory::new_lazy(
-            &url,
-            &collection,
-            qdrant_api_key,
-            embedder,
-        )));
+        return Ok(Box::new(
+            QdrantMemory::new_lazy(&url, &collection, qdrant_api_key, embedder)
+                .with_recall_weights(scoring::RecallWeights::from_config(config)),
+        ));
     }
 
     create_memory_with_builders(
         &backend_name,
         workspace_dir,
-        || build_sqlite_memory(config, workspace_dir, &resolved_embedding),
-        || build_postgres_memory(storage_provider),
+        || {
+            build_sqlite_memory(
+                config,
+                workspace_dir,
+                &resolved_embedding,
+                reliability,
+                cipher.clone(),
+            )
+        },
+        || build_postgres_memory(config, storage_provider),
+        scoring::RecallWeights::from_config(config),
         "",
     )
 }
@@ -371,23 +427,39 @@ pub fn create_memory_for_migration(
     create_memory_with_builders(
         backend,
         workspace_dir,
-        || SqliteMemory::new(workspace_dir),
+        || Ok(SqliteMemory::new(workspace_dir)?.with_cipher(encryption::runtime_cipher()?)),
         || anyhow::bail!("postgres backend is not available in migration context"),
+        scoring::RecallWeights::default(),
         " during migration",
     )
 }
 
+/// Factory: create the configured embedding provider, resolving `hint:` routes.
+pub fn create_embedder(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    api_key: Option<&str>,
+    reliability: &ReliabilityConfig,
+) -> Box<dyn embeddings::EmbeddingProvider> {
+    let resolved = resolve_embedding_config(config, embedding_routes, api_key);
+    build_embedder(&resolved, reliability)
+}
+
 /// Factory: create an optional response cache from config.
 pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Option<ResponseCache> {
     if !config.response_cache_enabled {
         return None;
     }
 
-    match ResponseCache::new(
-        workspace_dir,
-        config.response_cache_ttl_minutes,
-        config.response_cache_max_entries,
-    ) {
+    let cache = encryption::runtime_cipher().and_then(|cipher| {
+        Ok(ResponseCache::new(
+            workspace_dir,
+            config.response_cache_ttl_minutes,
+            config.response_cache_max_entries,
+        )?
+        .with_cipher(cipher))
+    });
+    match cache {
         Ok(cache) => {
             tracing::info!(
                 "💾 Response cache enabled (TTL: {}min, max: {} entries)",
@@ -516,9 +588,15 @@ mod tests {
             ..StorageProviderConfig::default()
         };
 
-        let error = create_memory_with_storage(&cfg, Some(&storage), tmp.path(), None)
-            .err()
-            .expect("postgres without db_url should be rejected");
+        let error = create_memory_with_storage(
+            &cfg,
+            Some(&storage),
+            tmp.path(),
+            None,
+            &ReliabilityConfig::default(),
+        )
+        .err()
+        .expect("postgres without db_url should be rejected");
         if cfg!(feature = "memory-postgres") {
             assert!(error.to_string().contains("db_url"));
         } else {
@@ -0,0 +1,187 @@
+// Per-user memory namespaces for multi-user channels.
+//
+// A `MemoryScope` names the namespaces a single channel message may touch:
+// the sender's private namespace, the chat's shared namespace and the global
+// `default` namespace. Channel and webhook handlers install the scope for the
+// duration of one message (`with_scope`), so memory tools and context
+// builders pick it up via `current()` without extra plumbing.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry, DEFAULT_NAMESPACE};
+use crate::config::{MemoryNamespaceConfig, MemorySharing};
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT_SCOPE: MemoryScope;
+}
+
+/// Namespaces visible to one sender in one chat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryScope {
+    private: String,
+    chat: String,
+    rules: MemoryNamespaceConfig,
+}
+
+impl MemoryScope {
+    /// Scope for `sender` in `chat` on `channel`; `None` when namespaces are
+    /// disabled or `memory` cannot keep them apart.
+    pub fn for_sender(
+        memory: &dyn Memory,
+        config: &MemoryNamespaceConfig,
+        channel: &str,
+        chat: &str,
+        sender: &str,
+    ) -> Option<Self> {
+        (config.enabled && memory.supports_namespaces())
+            .then(|| Self::new(config, channel, chat, sender))
+    }
+
+    fn new(config: &MemoryNamespaceConfig, channel: &str, chat: &str, sender: &str) -> Self {
+        Self {
+            private: private_namespace(channel, sender),
+            chat: chat_namespace(channel, chat),
+            rules: config.clone(),
+        }
+    }
+
+    /// The sender's private namespace.
+    pub fn private(&self) -> &str {
+        &self.private
+    }
+
+    /// The namespace shared by everyone in the chat.
+    pub fn chat(&self) -> &str {
+        &self.chat
+    }
+
+    /// Namespace a new entry of `category` is written to.
+    pub fn write_namespace(&self, category: &MemoryCategory) -> &str {
+        let sharing = match category {
+            MemoryCategory::Core => self.rules.core,
+            MemoryCategory::Daily => self.rules.daily,
+            MemoryCategory::Conversation => self.rules.conversation,
+            MemoryCategory::Knowledge => self.rules.knowledge,
+            MemoryCategory::Custom(_) => self.rules.custom,
+        };
+        match sharing {
+            MemorySharing::Global => DEFAULT_NAMESPACE,
+            MemorySharing::Chat => &self.chat,
+            MemorySharing::Private => &self.private,
+        }
+    }
+
+    /// Namespaces recall may read: private, chat and global.
+    pub fn read_namespaces(&self) -> Vec<String> {
+        let mut namespaces = vec![self.private.clone()];
+        if self.chat != self.private {
+            namespaces.push(self.chat.clone());
+        }
+        namespaces.push(DEFAULT_NAMESPACE.to_string());
+        namespaces
+    }
+
+    /// Namespaces the sender may delete from. The global namespace is
+    /// read-only from channels.
+    pub fn writable_namespaces(&self) -> [&str; 2] {
+        [&self.private, &self.chat]
+    }
+}
+
+/// `{channel}:user:{sender}`
+pub fn private_namespace(channel: &str, sender: &str) -> String {
+    format!("{channel}:user:{sender}")
+}
+
+/// `{channel}:chat:{chat}`
+pub fn chat_namespace(channel: &str, chat: &str) -> String {
+    format!("{channel}:chat:{chat}")
+}
+
+/// Run `fut` with `scope` as the current memory scope (no-op for `None`).
+pub async fn with_scope<F: Future>(scope: Option<MemoryScope>, fut: F) -> F::Output {
+    match scope {
+        Some(scope) => CURRENT_SCOPE.scope(scope, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Scope installed by the enclosing `with_scope`, if any.
+pub fn current() -> Option<MemoryScope> {
+    CURRENT_SCOPE.try_with(Clone::clone).ok()
+}
+
+/// Recall through `scope`'s readable namespaces, or the default namespace.
+pub async fn recall(
+    memory: &dyn Memory,
+    scope: Option<&MemoryScope>,
+    query: &str,
+    limit: usize,
+    tags: &[String],
+) -> anyhow::Result<Vec<MemoryEntry>> {
+    match scope {
+        Some(scope) => {
+            memory
+                .recall_in(&scope.read_namespaces(), query, limit, None, tags)
+                .await
+        }
+        None => memory.recall_tagged(query, limit, None, tags).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::memory::{NoneMemory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn scope() -> MemoryScope {
+        MemoryScope::new(&MemoryNamespaceConfig::default(), "slack", "C1", "U1")
+    }
+
+    #[test]
+    fn scope_requires_enabled_config_and_namespaced_backend() {
+        let tmp = TempDir::new().unwrap();
+        let sqlite = SqliteMemory::new(tmp.path()).unwrap();
+        let config = MemoryNamespaceConfig::default();
+        assert!(MemoryScope::for_sender(&sqlite, &config, "slack", "C1", "U1").is_some());
+        assert!(
+            MemoryScope::for_sender(&NoneMemory::new(), &config, "slack", "C1", "U1").is_none()
+        );
+
+        let disabled = MemoryNamespaceConfig {
+            enabled: false,
+            ..MemoryNamespaceConfig::default()
+        };
+        assert!(MemoryScope::for_sender(&sqlite, &disabled, "slack", "C1", "U1").is_none());
+    }
+
+    #[test]
+    fn default_rules_route_categories() {
+        let scope = scope();
+        assert_eq!(
+            scope.write_namespace(&MemoryCategory::Core),
+            "slack:chat:C1"
+        );
+        assert_eq!(
+            scope.write_namespace(&MemoryCategory::Conversation),
+            "slack:user:U1"
+        );
+        assert_eq!(
+            scope.write_namespace(&MemoryCategory::Knowledge),
+            DEFAULT_NAMESPACE
+        );
+        assert_eq!(
+            scope.read_namespaces(),
+            vec!["slack:user:U1", "slack:chat:C1", DEFAULT_NAMESPACE]
+        );
+    }
+
+    #[tokio::test]
+    async fn current_follows_with_scope() {
+        assert!(current().is_none());
+        let inside = with_scope(Some(scope()), async { current() }).await;
+        assert_eq!(inside, Some(scope()));
+        assert!(current().is_none());
+    }
+}
@@ -1,4 +1,7 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::scoring::{self, RecallWeights};
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE, TAG_RECALL_OVERSAMPLE,
+};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -11,6 +14,10 @@ use uuid::Uuid;
 /// Maximum allowed connect timeout (seconds) to avoid unreasonable waits.
 const POSTGRES_CONNECT_TIMEOUT_CAP_SECS: u64 = 300;
 
+/// Columns read into a `MemoryEntry` by [`PostgresMemory::row_to_entry`].
+const ENTRY_COLUMNS: &str =
+    "id, key, content, category, created_at, session_id, metadata, access_count, namespace";
+
 /// A no-op TLS certificate verifier used for `tls = "require"` mode.
 ///
 /// This accepts any server certificate without verification — equivalent to
@@ -73,6 +80,7 @@ impl rustls::client::danger::ServerCertVerifier for NoCertVerifier {
 pub struct PostgresMemory {
     client: Arc<Mutex<Client>>,
     qualified_table: String,
+    weights: RecallWeights,
 }
 
 impl PostgresMemory {
@@ -101,9 +109,17 @@ impl PostgresMemory {
         Ok(Self {
             client: Arc::new(Mutex::new(client)),
             qualified_table,
+            weights: RecallWeights::default(),
         })
     }
 
+    /// Set the importance/recency weighting applied to recall scores.
+    #[must_use]
+    pub fn with_recall_weights(mut self, weights: RecallWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
     fn initialize_client(
         db_url: String,
         connect_timeout_secs: Option<u64>,
@@ -165,7 +181,7 @@ impl PostgresMemory {
 
             CREATE TABLE IF NOT EXISTS {qualified_table} (
                 id TEXT PRIMARY KEY,
-                key TEXT UNIQUE NOT NULL,
+                key TEXT NOT NULL,
                 content TEXT NOT NULL,
                 category TEXT NOT NULL,
                 created_at TIMESTAMPTZ NOT NULL,
@@ -176,6 +192,28 @@ impl PostgresMemory {
             CREATE INDEX IF NOT EXISTS idx_memories_category ON {qualified_table}(category);
             CREATE INDEX IF NOT EXISTS idx_memories_session_id ON {qualified_table}(session_id);
             CREATE INDEX IF NOT EXISTS idx_memories_updated_at ON {qualified_table}(updated_at DESC);
+
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS metadata TEXT;
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS access_count BIGINT NOT NULL DEFAULT 0;
+            ALTER TABLE {qualified_table} ADD COLUMN IF NOT EXISTS namespace TEXT NOT NULL DEFAULT '{DEFAULT_NAMESPACE}';
+
+            -- Keys were globally unique before namespaces; drop that constraint.
+            DO $$
+            DECLARE legacy TEXT;
+            BEGIN
+                SELECT conname INTO legacy FROM pg_constraint
+                WHERE conrelid = '{qualified_table}'::regclass
+                  AND contype = 'u'
+                  AND conkey = ARRAY[(
+                      SELECT attnum FROM pg_attribute
+                      WHERE attrelid = '{qualified_table}'::regclass AND attname = 'key'
+                  )];
+                IF legacy IS NOT NULL THEN
+                    EXECUTE format('ALTER TABLE {qualified_table} DROP CONSTRAINT %I', legacy);
+                END IF;
+            END $$;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_memories_namespace_key ON {qualified_table}(namespace, key);
             "
         ))?;
 
@@ -187,6 +225,7 @@ impl PostgresMemory {
             MemoryCategory::Core => "core".to_string(),
             MemoryCategory::Daily => "daily".to_string(),
             MemoryCategory::Conversation => "conversation".to_string(),
+            MemoryCategory::Knowledge => "knowledge".to_string(),
             MemoryCategory::Custom(name) => name.clone(),
         }
     }
@@ -196,12 +235,19 @@ impl PostgresMemory {
             "core" => MemoryCategory::Core,
             "daily" => MemoryCategory::Daily,
             "conversation" => MemoryCategory::Conversation,
+            "knowledge" => MemoryCategory::Knowledge,
             other => MemoryCategory::Custom(other.to_string()),
         }
     }
 
+    /// Map a row selected with [`ENTRY_COLUMNS`] (plus an optional `score`).
     fn row_to_entry(row: &Row) -> Result<MemoryEntry> {
         let timestamp: DateTime<Utc> = row.get(4);
+        let mut metadata: MemoryMetadata = row
+            .get::<_, Option<String>>(6)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        metadata.access_count = u64::try_from(row.get::<_, i64>(7)).unwrap_or(0);
 
         Ok(MemoryEntry {
             id: row.get(0),
@@ -210,8 +256,97 @@ impl PostgresMemory {
             category: Self::parse_category(&row.get::<_, String>(3)),
             timestamp: timestamp.to_rfc3339(),
             session_id: row.get(5),
-            score: row.try_get(6).ok(),
+            score: row.try_get("score").ok(),
+            metadata,
+            namespace: row.get(8),
+        })
+    }
+
+    /// Insert or update a row. With `timestamp` (imports) both `created_at`
+    /// and `updated_at` take that value and `access_count` is restored from
+    /// `metadata`; otherwise `created_at` and `access_count` of an existing
+    /// row are preserved. `metadata: None` keeps the existing metadata.
+    #[allow(clippy::too_many_arguments)]
+    async fn upsert(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: &MemoryCategory,
+        session_id: Option<&str>,
+        metadata: Option<&MemoryMetadata>,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let client = self.client.clone();
+        let qualified_table = self.qualified_table.clone();
+        let namespace = namespace.to_string();
+        let key = key.to_string();
+        let content = content.to_string();
+        let category = Self::category_to_str(category);
+        let sid = session_id.map(str::to_string);
+        let replace_metadata = metadata.is_some();
+        // Only imports carry a meaningful access count; new rows start at 0.
+        #[allow(clippy::cast_possible_wrap)]
+        let access_count = metadata
+            .filter(|_| timestamp.is_some())
+            .map_or(0, |m| m.access_count as i64);
+        let metadata_json = metadata
+            .map(MemoryMetadata::without_access_count)
+            .filter(|m| !m.is_empty())
+            .map(|m| serde_json::to_string(&m))
+            .transpose()?;
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let now = timestamp.unwrap_or_else(Utc::now);
+            let mut client = client.lock();
+            let stmt = format!(
+                "
+                INSERT INTO {qualified_table}
+                    (id, key, content, category, created_at, updated_at, session_id,
+                     metadata, access_count, namespace)
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $12)
+                ON CONFLICT (namespace, key) DO UPDATE SET
+                    content = EXCLUDED.content,
+                    category = EXCLUDED.category,
+                    created_at = COALESCE($10, {qualified_table}.created_at),
+                    updated_at = EXCLUDED.updated_at,
+                    session_id = EXCLUDED.session_id,
+                    metadata = CASE WHEN $11 THEN EXCLUDED.metadata
+                                    ELSE {qualified_table}.metadata END,
+                    access_count = CASE WHEN $10::TIMESTAMPTZ IS NULL
+                                        THEN {qualified_table}.access_count
+                                        ELSE EXCLUDED.access_count END
+                "
+            );
+
+            let id = Uuid::new_v4().to_string();
+            client.execute(
+                &stmt,
+                &[
+                    &id,
+                    &key,
+                    &content,
+                    &category,
+                    &now,
+                    &now,
+                    &sid,
+                    &metadata_json,
+                    &access_count,
+                    &timestamp,
+                    &replace_metadata,
+                    &namespace,
+                ],
+            )?;
+            Ok(())
         })
+        .await?
+    }
+
+    /// Parse an exported timestamp: RFC 3339, or a bare `YYYY-MM-DD` date
+    /// (markdown exports). Anything else becomes "now".
+    fn parse_timestamp(value: &str) -> DateTime<Utc> {
+        scoring::parse_timestamp(value).unwrap_or_else(Utc::now)
     }
 }
 
@@ -248,6 +383,10 @@ impl Memory for PostgresMemory {
         "postgres"
     }
 
+    fn supports_namespaces(&self) -> bool {
+        true
+    }
+
     async fn store(
         &self,
         key: &str,
@@ -255,35 +394,56 @@ impl Memory for PostgresMemory {
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> Result<()> {
-        let client = self.client.clone();
-        let qualified_table = self.qualified_table.clone();
-        let key = key.to_string();
-        let content = content.to_string();
-        let category = Self::category_to_str(&category);
-        let sid = session_id.map(str::to_string);
+        self.upsert(
+            DEFAULT_NAMESPACE,
+            key,
+            content,
+            &category,
+            session_id,
+            None,
+            None,
+        )
+        .await
+    }
 
-        tokio::task::spawn_blocking(move || -> Result<()> {
-            let now = Utc::now();
-            let mut client = client.lock();
-            let stmt = format!(
-                "
-                INSERT INTO {qualified_table}
-                    (id, key, content, category, created_at, updated_at, session_id)
-                VALUES
-                    ($1, $2, $3, $4, $5, $6, $7)
-                ON CONFLICT (key) DO UPDATE SET
-                    content = EXCLUDED.content,
-                    category = EXCLUDED.category,
-                    updated_at = EXCLUDED.updated_at,
-                    session_id = EXCLUDED.session_id
-                "
-            );
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> Result<()> {
+        self.store_in(
+            DEFAULT_NAMESPACE,
+            key,
+            content,
+            category,
+            session_id,
+            metadata,
+        )
+        .await
+    }
 
-            let id = Uuid::new_v4().to_string();
-            client.execute(&stmt, &[&id, &key, &content, &category, &now, &now, &sid])?;
-            Ok(())
-        })
-        .await?
+    async fn store_in(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> Result<()> {
+        self.upsert(
+            namespace,
+            key,
+            content,
+            &category,
+            session_id,
+            Some(metadata),
+            None,
+        )
+        .await
     }
 
     async fn recall(
@@ -292,56 +452,111 @@ impl Memory for PostgresMemory {
         limit: usize,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
+        self.recall_in(
+            &[DEFAULT_NAMESPACE.to_string()],
+            query,
+            limit,
+            session_id,
+            &[],
+        )
+        .await
+    }
+
+    async fn recall_in(
+        &self,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        if namespaces.is_empty() {
+            return Ok(Vec::new());
+        }
+
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
         let query = query.trim().to_string();
         let sid = session_id.map(str::to_string);
+        let namespaces = namespaces.to_vec();
+        let tags = tags.to_vec();
+        let weights = self.weights;
 
         tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id,
+                SELECT {ENTRY_COLUMNS},
                        (
                          CASE WHEN key ILIKE '%' || $1 || '%' THEN 2.0 ELSE 0.0 END +
                          CASE WHEN content ILIKE '%' || $1 || '%' THEN 1.0 ELSE 0.0 END
                        ) AS score
                 FROM {qualified_table}
                 WHERE ($2::TEXT IS NULL OR session_id = $2)
+                  AND namespace = ANY($4)
                   AND ($1 = '' OR key ILIKE '%' || $1 || '%' OR content ILIKE '%' || $1 || '%')
                 ORDER BY score DESC, updated_at DESC
                 LIMIT $3
                 "
             );
 
+            // Keep spare candidates for expiry, tag filtering and re-ranking.
+            let oversample = if tags.is_empty() {
+                2
+            } else {
+                TAG_RECALL_OVERSAMPLE
+            };
             #[allow(clippy::cast_possible_wrap)]
-            let limit_i64 = limit as i64;
+            let limit_i64 = limit.saturating_mul(oversample) as i64;
 
-            let rows = client.query(&stmt, &[&query, &sid, &limit_i64])?;
-            rows.iter()
+            let rows = client.query(&stmt, &[&query, &sid, &limit_i64, &namespaces])?;
+            let mut entries = rows
+                .iter()
                 .map(Self::row_to_entry)
-                .collect::<Result<Vec<MemoryEntry>>>()
+                .collect::<Result<Vec<MemoryEntry>>>()?;
+            entries.retain(|entry| entry.metadata.has_tags(&tags));
+            scoring::rerank(&mut entries, &weights, Utc::now());
+            entries.truncate(limit);
+
+            if !entries.is_empty() {
+                let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+                client.execute(
+                    &format!(
+                        "UPDATE {qualified_table} SET access_count = access_count + 1 WHERE id = ANY($1)"
+                    ),
+                    &[&ids],
+                )?;
+                for entry in &mut entries {
+                    entry.metadata.access_count += 1;
+                }
+            }
+            Ok(entries)
         })
         .await?
     }
 
     async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
+        self.get_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn get_in(&self, namespace: &str, key: &str) -> Result<Option<MemoryEntry>> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
+        let namespace = namespace.to_string();
         let key = key.to_string();
 
         tokio::task::spawn_blocking(move || -> Result<Option<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id
+                SELECT {ENTRY_COLUMNS}
                 FROM {qualified_table}
-                WHERE key = $1
+                WHERE namespace = $1 AND key = $2
                 LIMIT 1
                 "
             );
 
-            let row = client.query_opt(&stmt, &[&key])?;
+            let row = client.query_opt(&stmt, &[&namespace, &key])?;
             row.as_ref().map(Self::row_to_entry).transpose()
         })
         .await?
@@ -351,27 +566,39 @@ impl Memory for PostgresMemory {
         &self,
         category: Option<&MemoryCategory>,
         session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.list_in(&[DEFAULT_NAMESPACE.to_string()], category, session_id)
+            .await
+    }
+
+    async fn list_in(
+        &self,
+        namespaces: &[String],
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
         let category = category.map(Self::category_to_str);
         let sid = session_id.map(str::to_string);
+        let namespaces = namespaces.to_vec();
 
         tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
             let mut client = client.lock();
             let stmt = format!(
                 "
-                SELECT id, key, content, category, created_at, session_id
+                SELECT {ENTRY_COLUMNS}
                 FROM {qualified_table}
                 WHERE ($1::TEXT IS NULL OR category = $1)
                   AND ($2::TEXT IS NULL OR session_id = $2)
+                  AND namespace = ANY($3)
                 ORDER BY updated_at DESC
                 "
             );
 
             let category_ref = category.as_deref();
             let session_ref = sid.as_deref();
-            let rows = client.query(&stmt, &[&category_ref, &session_ref])?;
+            let rows = client.query(&stmt, &[&category_ref, &session_ref, &namespaces])?;
             rows.iter()
                 .map(Self::row_to_entry)
                 .collect::<Result<Vec<MemoryEntry>>>()
@@ -380,14 +607,19 @@ impl Memory for PostgresMemory {
     }
 
     async fn forget(&self, key: &str) -> Result<bool> {
+        self.forget_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn forget_in(&self, namespace: &str, key: &str) -> Result<bool> {
         let client = self.client.clone();
         let qualified_table = self.qualified_table.clone();
+        let namespace = namespace.to_string();
         let key = key.to_string();
 
         tokio::task::spawn_blocking(move || -> Result<bool> {
             let mut client = client.lock();
-            let stmt = format!("DELETE FROM {qualified_table} WHERE key = $1");
-            let deleted = client.execute(&stmt, &[&key])?;
+            let stmt = format!("DELETE FROM {qualified_table} WHERE namespace = $1 AND key = $2");
+            let deleted = client.execute(&stmt, &[&namespace, &key])?;
             Ok(deleted > 0)
         })
         .await?
@@ -414,6 +646,35 @@ impl Memory for PostgresMemory {
             .await
             .unwrap_or(false)
     }
+
+    async fn export_entries(&self) -> Result<Vec<MemoryEntry>> {
+        let client = self.client.clone();
+        let qualified_table = self.qualified_table.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<MemoryEntry>> {
+            let mut client = client.lock();
+            let stmt =
+                format!("SELECT {ENTRY_COLUMNS} FROM {qualified_table} ORDER BY created_at, key");
+            let rows = client.query(&stmt, &[])?;
+            rows.iter()
+                .map(Self::row_to_entry)
+                .collect::<Result<Vec<MemoryEntry>>>()
+        })
+        .await?
+    }
+
+    async fn import_entry(&self, entry: &MemoryEntry, _embedding: Option<&[f32]>) -> Result<()> {
+        self.upsert(
+            &entry.namespace,
+            &entry.key,
+            &entry.content,
+            &entry.category,
+            entry.session_id.as_deref(),
+            Some(&entry.metadata),
+            Some(Self::parse_timestamp(&entry.timestamp)),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -450,6 +711,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_timestamp_accepts_rfc3339_and_dates() {
+        assert_eq!(
+            PostgresMemory::parse_timestamp("2026-03-01T10:00:00+02:00").to_rfc3339(),
+            "2026-03-01T08:00:00+00:00"
+        );
+        assert_eq!(
+            PostgresMemory::parse_timestamp("2026-03-01").to_rfc3339(),
+            "2026-03-01T00:00:00+00:00"
+        );
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn new_does_not_panic_inside_tokio_runtime() {
         let outcome = std::panic::catch_unwind(|| {
@@ -1,5 +1,8 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::scoring::{self, RecallWeights};
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE, TAG_RECALL_OVERSAMPLE,
+};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
@@ -18,6 +21,7 @@ pub struct QdrantMemory {
     collection: String,
     api_key: Option<String>,
     embedder: Arc<dyn EmbeddingProvider>,
+    weights: RecallWeights,
     /// Tracks whether collection has been initialized (lazy init for sync factory).
     initialized: OnceCell<()>,
 }
@@ -64,10 +68,18 @@ impl QdrantMemory {
             collection: collection.to_string(),
             api_key,
             embedder,
+            weights: RecallWeights::default(),
             initialized: OnceCell::new(),
         }
     }
 
+    /// Set the importance/recency weighting applied to recall scores.
+    #[must_use]
+    pub fn with_recall_weights(mut self, weights: RecallWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
     /// Ensure the collection is initialized (called lazily on first operation).
     async fn ensure_initialized(&self) -> Result<()> {
         self.initialized
@@ -165,6 +177,7 @@ impl QdrantMemory {
             MemoryCategory::Core => "core".to_string(),
             MemoryCategory::Daily => "daily".to_string(),
             MemoryCategory::Conversation => "conversation".to_string(),
+            MemoryCategory::Knowledge => "knowledge".to_string(),
             MemoryCategory::Custom(name) => name.clone(),
         }
     }
@@ -174,20 +187,175 @@ impl QdrantMemory {
             "core" => MemoryCategory::Core,
             "daily" => MemoryCategory::Daily,
             "conversation" => MemoryCategory::Conversation,
+            "knowledge" => MemoryCategory::Knowledge,
             other => MemoryCategory::Custom(other.to_string()),
         }
     }
+
+    /// Filter condition matching points in any of `namespaces`.
+    ///
+    /// Points written before namespaces existed carry no `namespace` field and
+    /// belong to the default namespace.
+    fn namespace_condition(namespaces: &[String]) -> serde_json::Value {
+        let mut should = vec![serde_json::json!({
+            "key": "namespace",
+            "match": { "any": namespaces }
+        })];
+        if namespaces.iter().any(|ns| ns == DEFAULT_NAMESPACE) {
+            should.push(serde_json::json!({ "is_empty": { "key": "namespace" } }));
+        }
+        serde_json::json!({ "should": should })
+    }
+
+    /// Filter matching `key` within `namespace`.
+    fn key_filter(namespace: &str, key: &str) -> serde_json::Value {
+        serde_json::json!({
+            "must": [
+                { "key": "key", "match": { "value": key } },
+                Self::namespace_condition(&[namespace.to_string()])
+            ]
+        })
+    }
+
+    /// Replace any point stored under `payload.key` with a new one.
+    async fn upsert_point(&self, payload: MemoryPayload, embedding: Vec<f32>) -> Result<()> {
+        if embedding.is_empty() {
+            anyhow::bail!("Qdrant requires non-zero dimensional embeddings");
+        }
+
+        let id = Uuid::new_v4().to_string();
+
+        // Delete any existing point with the same key first
+        let _ = self.forget_in(&payload.namespace, &payload.key).await;
+
+        // Upsert point
+        let upsert_body = serde_json::json!({
+            "points": [{
+                "id": id,
+                "vector": embedding,
+                "payload": payload
+            }]
+        });
+
+        let resp = self
+            .request(
+                reqwest::Method::PUT,
+                &format!("/collections/{}/points", self.collection),
+            )
+            .query(&[("wait", "true")])
+            .json(&upsert_body)
+            .send()
+            .await
+            .context("failed to upsert point to Qdrant")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant upsert failed ({status}): {text}");
+        }
+
+        Ok(())
+    }
+
+    /// Run one `points/scroll` request.
+    async fn scroll(&self, body: &serde_json::Value) -> Result<QdrantScrollPoints> {
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/scroll", self.collection),
+            )
+            .json(body)
+            .send()
+            .await
+            .context("failed to scroll Qdrant")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant scroll failed ({status}): {text}");
+        }
+
+        let result: QdrantScrollResult = resp.json().await?;
+        Ok(result.result)
+    }
+
+    /// Persist incremented access counts for recalled points.
+    async fn record_access(&self, entries: &mut [MemoryEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let operations: Vec<serde_json::Value> = entries
+            .iter_mut()
+            .map(|entry| {
+                entry.metadata.access_count += 1;
+                serde_json::json!({
+                    "set_payload": {
+                        "payload": { "access_count": entry.metadata.access_count },
+                        "points": [entry.id]
+                    }
+                })
+            })
+            .collect();
+
+        let resp = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/collections/{}/points/batch", self.collection),
+            )
+            .json(&serde_json::json!({ "operations": operations }))
+            .send()
+            .await
+            .context("failed to update Qdrant access counts")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant payload update failed ({status}): {text}");
+        }
+
+        Ok(())
+    }
+
+    fn point_to_entry(point: QdrantPoint) -> Option<MemoryEntry> {
+        let payload = point.payload?;
+        let id = match &point.id {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+
+        Some(MemoryEntry {
+            id,
+            namespace: payload.namespace,
+            key: payload.key,
+            content: payload.content,
+            category: Self::parse_category(&payload.category),
+            timestamp: payload.timestamp,
+            session_id: payload.session_id,
+            score: None,
+            metadata: payload.metadata,
+        })
+    }
 }
 
 /// Qdrant point payload structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct MemoryPayload {
+    #[serde(default = "default_namespace")]
+    namespace: String,
     key: String,
     content: String,
     category: String,
     timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     session_id: Option<String>,
+    #[serde(flatten)]
+    metadata: MemoryMetadata,
+}
+
+fn default_namespace() -> String {
+    DEFAULT_NAMESPACE.to_string()
 }
 
 /// Qdrant search result
@@ -212,12 +380,16 @@ struct QdrantScrollResult {
 #[derive(Debug, Deserialize)]
 struct QdrantScrollPoints {
     points: Vec<QdrantPoint>,
+    #[serde(default)]
+    next_page_offset: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 struct QdrantPoint {
     id: serde_json::Value,
     payload: Option<MemoryPayload>,
+    #[serde(default)]
+    vector: Option<Vec<f32>>,
 }
 
 #[async_trait]
@@ -226,12 +398,54 @@ impl Memory for QdrantMemory {
         "qdrant"
     }
 
+    fn supports_namespaces(&self) -> bool {
+        true
+    }
+
     async fn store(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         session_id: Option<&str>,
+    ) -> Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            &MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> Result<()> {
+        self.store_in(
+            DEFAULT_NAMESPACE,
+            key,
+            content,
+            category,
+            session_id,
+            metadata,
+        )
+        .await
+    }
+
+    async fn store_in(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
     ) -> Result<()> {
         self.ensure_initialized().await?;
 
@@ -239,51 +453,16 @@ impl Memory for QdrantMemory {
         let combined_text = format!("{}\n{}", key, content);
         let embedding = self.embedder.embed_one(&combined_text).await?;
 
-        if embedding.is_empty() {
-            anyhow::bail!("Qdrant requires non-zero dimensional embeddings");
-        }
-
-        let id = Uuid::new_v4().to_string();
-        let timestamp = Utc::now().to_rfc3339();
-
         let payload = MemoryPayload {
+            namespace: namespace.to_string(),
             key: key.to_string(),
             content: content.to_string(),
             category: Self::category_to_str(&category),
-            timestamp,
+            timestamp: Utc::now().to_rfc3339(),
             session_id: session_id.map(str::to_string),
+            metadata: metadata.without_access_count(),
         };
-
-        // Delete any existing point with the same key first
-        let _ = self.forget(key).await;
-
-        // Upsert point
-        let upsert_body = serde_json::json!({
-            "points": [{
-                "id": id,
-                "vector": embedding,
-                "payload": payload
-            }]
-        });
-
-        let resp = self
-            .request(
-                reqwest::Method::PUT,
-                &format!("/collections/{}/points", self.collection),
-            )
-            .query(&[("wait", "true")])
-            .json(&upsert_body)
-            .send()
-            .await
-            .context("failed to upsert point to Qdrant")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Qdrant upsert failed ({status}): {text}");
-        }
-
-        Ok(())
+        self.upsert_point(payload, embedding).await
     }
 
     async fn recall(
@@ -292,40 +471,62 @@ impl Memory for QdrantMemory {
         limit: usize,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
+        self.recall_in(
+            &[DEFAULT_NAMESPACE.to_string()],
+            query,
+            limit,
+            session_id,
+            &[],
+        )
+        .await
+    }
+
+    async fn recall_in(
+        &self,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<Vec<MemoryEntry>> {
+        if namespaces.is_empty() {
+            return Ok(Vec::new());
+        }
         if query.trim().is_empty() {
-            return self.list(None, session_id).await;
+            return self.list_in(namespaces, None, session_id).await;
         }
 
         self.ensure_initialized().await?;
 
         // Generate embedding for the query
-        let embedding = self.embedder.embed_one(query).await?;
+        let embedding = self.embedder.embed_query(query).await?;
 
         if embedding.is_empty() {
             // Fallback to listing if embeddings aren't available
-            return self.list(None, session_id).await;
+            return self.list_in(namespaces, None, session_id).await;
         }
 
-        // Build filter for session_id if provided
-        let filter = session_id.map(|sid| {
-            serde_json::json!({
-                "must": [{
-                    "key": "session_id",
-                    "match": { "value": sid }
-                }]
-            })
-        });
+        let mut must_conditions = vec![Self::namespace_condition(namespaces)];
+        if let Some(sid) = session_id {
+            must_conditions.push(serde_json::json!({
+                "key": "session_id",
+                "match": { "value": sid }
+            }));
+        }
 
-        let mut search_body = serde_json::json!({
+        // Keep spare candidates for expiry, tag filtering and re-ranking.
+        let oversample = if tags.is_empty() {
+            2
+        } else {
+            TAG_RECALL_OVERSAMPLE
+        };
+        let search_body = serde_json::json!({
             "vector": embedding,
-            "limit": limit,
-            "with_payload": true
+            "limit": limit.saturating_mul(oversample),
+            "with_payload": true,
+            "filter": { "must": must_conditions }
         });
 
-        if let Some(f) = filter {
-            search_body["filter"] = f;
-        }
-
         let resp = self
             .request(
                 reqwest::Method::POST,
@@ -344,83 +545,53 @@ impl Memory for QdrantMemory {
 
         let result: QdrantSearchResult = resp.json().await?;
 
-        let entries = result
+        let mut entries: Vec<MemoryEntry> = result
             .result
             .into_iter()
             .filter_map(|point| {
-                let payload = point.payload?;
-                let id = match &point.id {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => return None,
-                };
-
-                Some(MemoryEntry {
-                    id,
-                    key: payload.key,
-                    content: payload.content,
-                    category: Self::parse_category(&payload.category),
-                    timestamp: payload.timestamp,
-                    session_id: payload.session_id,
-                    score: Some(point.score),
-                })
+                let score = point.score;
+                let mut entry = Self::point_to_entry(QdrantPoint {
+                    id: point.id,
+                    payload: point.payload,
+                    vector: None,
+                })?;
+                entry.score = Some(score);
+                Some(entry)
             })
             .collect();
 
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        scoring::rerank(&mut entries, &self.weights, Utc::now());
+        entries.truncate(limit);
+
+        // Access counts are advisory; a failed update must not fail recall.
+        if let Err(err) = self.record_access(&mut entries).await {
+            tracing::debug!("Qdrant access count update skipped: {err}");
+        }
+
         Ok(entries)
     }
 
     async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
+        self.get_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn get_in(&self, namespace: &str, key: &str) -> Result<Option<MemoryEntry>> {
         self.ensure_initialized().await?;
 
         // Scroll with filter for exact key match
         let scroll_body = serde_json::json!({
-            "filter": {
-                "must": [{
-                    "key": "key",
-                    "match": { "value": key }
-                }]
-            },
+            "filter": Self::key_filter(namespace, key),
             "limit": 1,
             "with_payload": true
         });
 
-        let resp = self
-            .request(
-                reqwest::Method::POST,
-                &format!("/collections/{}/points/scroll", self.collection),
-            )
-            .json(&scroll_body)
-            .send()
-            .await
-            .context("failed to scroll Qdrant")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Qdrant scroll failed ({status}): {text}");
-        }
-
-        let result: QdrantScrollResult = resp.json().await?;
-
-        let entry = result.result.points.into_iter().next().and_then(|point| {
-            let payload = point.payload?;
-            let id = match &point.id {
-                serde_json::Value::String(s) => s.clone(),
-                serde_json::Value::Number(n) => n.to_string(),
-                _ => return None,
-            };
-
-            Some(MemoryEntry {
-                id,
-                key: payload.key,
-                content: payload.content,
-                category: Self::parse_category(&payload.category),
-                timestamp: payload.timestamp,
-                session_id: payload.session_id,
-                score: None,
-            })
-        });
+        let page = self.scroll(&scroll_body).await?;
+        let entry = page
+            .points
+            .into_iter()
+            .next()
+            .and_then(Self::point_to_entry);
 
         Ok(entry)
     }
@@ -430,10 +601,23 @@ impl Memory for QdrantMemory {
         category: Option<&MemoryCategory>,
         session_id: Option<&str>,
     ) -> Result<Vec<MemoryEntry>> {
+        self.list_in(&[DEFAULT_NAMESPACE.to_string()], category, session_id)
+            .await
+    }
+
+    async fn list_in(
+        &self,
+        namespaces: &[String],
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        if namespaces.is_empty() {
+            return Ok(Vec::new());
+        }
         self.ensure_initialized().await?;
 
         // Build filter conditions
-        let mut must_conditions = Vec::new();
+        let mut must_conditions = vec![Self::namespace_condition(namespaces)];
 
         if let Some(cat) = category {
             must_conditions.push(serde_json::json!({
@@ -449,71 +633,32 @@ impl Memory for QdrantMemory {
             }));
         }
 
-        let mut scroll_body = serde_json::json!({
+        let scroll_body = serde_json::json!({
             "limit": 1000,
-            "with_payload": true
+            "with_payload": true,
+            "filter": { "must": must_conditions }
         });
 
-        if !must_conditions.is_empty() {
-            scroll_body["filter"] = serde_json::json!({ "must": must_conditions });
-        }
-
-        let resp = self
-            .request(
-                reqwest::Method::POST,
-                &format!("/collections/{}/points/scroll", self.collection),
-            )
-            .json(&scroll_body)
-            .send()
-            .await
-            .context("failed to scroll Qdrant")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            anyhow::bail!("Qdrant scroll failed ({status}): {text}");
-        }
-
-        let result: QdrantScrollResult = resp.json().await?;
-
-        let entries = result
-            .result
+        let page = self.scroll(&scroll_body).await?;
+        let entries = page
             .points
             .into_iter()
-            .filter_map(|point| {
-                let payload = point.payload?;
-                let id = match &point.id {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    _ => return None,
-                };
-
-                Some(MemoryEntry {
-                    id,
-                    key: payload.key,
-                    content: payload.content,
-                    category: Self::parse_category(&payload.category),
-                    timestamp: payload.timestamp,
-                    session_id: payload.session_id,
-                    score: None,
-                })
-            })
+            .filter_map(Self::point_to_entry)
             .collect();
 
         Ok(entries)
     }
 
     async fn forget(&self, key: &str) -> Result<bool> {
+        self.forget_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn forget_in(&self, namespace: &str, key: &str) -> Result<bool> {
         self.ensure_initialized().await?;
 
         // Delete points matching the key
         let delete_body = serde_json::json!({
-            "filter": {
-                "must": [{
-                    "key": "key",
-                    "match": { "value": key }
-                }]
-            }
+            "filter": Self::key_filter(namespace, key)
         });
 
         let resp = self
@@ -573,6 +718,73 @@ impl Memory for QdrantMemory {
 
         matches!(resp, Ok(r) if r.status().is_success())
     }
+    async fn export_entries(&self) -> Result<Vec<MemoryEntry>> {
+        self.ensure_initialized().await?;
+
+        // `list` reads a single page; follow `next_page_offset` to the end.
+        let mut entries = Vec::new();
+        let mut offset = None;
+        loop {
+            let mut scroll_body = serde_json::json!({
+                "limit": 256,
+                "with_payload": true
+            });
+            if let Some(offset) = offset.take() {
+                scroll_body["offset"] = offset;
+            }
+
+            let page = self.scroll(&scroll_body).await?;
+            entries.extend(page.points.into_iter().filter_map(Self::point_to_entry));
+            match page.next_page_offset {
+                Some(next) if !next.is_null() => offset = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn embedding(&self, namespace: &str, key: &str) -> Result<Option<Vec<f32>>> {
+        self.ensure_initialized().await?;
+
+        let scroll_body = serde_json::json!({
+            "filter": Self::key_filter(namespace, key),
+            "limit": 1,
+            "with_payload": false,
+            "with_vector": true
+        });
+
+        let page = self.scroll(&scroll_body).await?;
+        Ok(page
+            .points
+            .into_iter()
+            .next()
+            .and_then(|point| point.vector))
+    }
+
+    async fn import_entry(&self, entry: &MemoryEntry, embedding: Option<&[f32]>) -> Result<()> {
+        self.ensure_initialized().await?;
+
+        let dims = self.embedder.dimensions();
+        let embedding = match embedding {
+            Some(emb) if dims > 0 && emb.len() == dims => emb.to_vec(),
+            _ => {
+                let combined_text = format!("{}\n{}", entry.key, entry.content);
+                self.embedder.embed_one(&combined_text).await?
+            }
+        };
+
+        let payload = MemoryPayload {
+            namespace: entry.namespace.clone(),
+            key: entry.key.clone(),
+            content: entry.content.clone(),
+            category: Self::category_to_str(&entry.category),
+            timestamp: entry.timestamp.clone(),
+            session_id: entry.session_id.clone(),
+            metadata: entry.metadata.clone(),
+        };
+        self.upsert_point(payload, embedding).await
+    }
 }
 
 #[cfg(test)]
@@ -613,11 +825,13 @@ mod tests {
     #[test]
     fn memory_payload_serializes_correctly() {
         let payload = MemoryPayload {
+            namespace: DEFAULT_NAMESPACE.into(),
             key: "test_key".into(),
             content: "test content".into(),
             category: "core".into(),
             timestamp: "2026-02-20T00:00:00Z".into(),
             session_id: Some("session-1".into()),
+            metadata: MemoryMetadata::default(),
         };
 
         let json = serde_json::to_string(&payload).unwrap();
@@ -629,14 +843,67 @@ mod tests {
     #[test]
     fn memory_payload_skips_none_session_id() {
         let payload = MemoryPayload {
+            namespace: DEFAULT_NAMESPACE.into(),
             key: "test_key".into(),
             content: "test content".into(),
             category: "core".into(),
             timestamp: "2026-02-20T00:00:00Z".into(),
             session_id: None,
+            metadata: MemoryMetadata::default(),
         };
 
         let json = serde_json::to_string(&payload).unwrap();
         assert!(!json.contains("session_id"));
     }
+
+    #[test]
+    fn memory_payload_flattens_metadata() {
+        let payload = MemoryPayload {
+            namespace: DEFAULT_NAMESPACE.into(),
+            key: "test_key".into(),
+            content: "test content".into(),
+            category: "core".into(),
+            timestamp: "2026-02-20T00:00:00Z".into(),
+            session_id: None,
+            metadata: MemoryMetadata {
+                tags: vec!["infra".into()],
+                importance: Some(0.8),
+                access_count: 3,
+                ..MemoryMetadata::default()
+            },
+        };
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["tags"], serde_json::json!(["infra"]));
+        assert_eq!(json["access_count"], serde_json::json!(3));
+
+        let parsed: MemoryPayload = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.metadata, payload.metadata);
+    }
+
+    #[test]
+    fn scroll_page_reads_next_offset_and_vectors() {
+        let json = r#"{"result":{"points":[{"id":"a1","payload":{"key":"k","content":"c","category":"core","timestamp":"t"},"vector":[0.5,1.0]}],"next_page_offset":"a2"}}"#;
+        let page: QdrantScrollResult = serde_json::from_str(json).unwrap();
+        let page = page.result;
+        assert_eq!(page.next_page_offset, Some(serde_json::json!("a2")));
+        assert_eq!(page.points[0].vector.as_deref(), Some(&[0.5, 1.0][..]));
+
+        let entry = QdrantMemory::point_to_entry(page.points.into_iter().next().unwrap()).unwrap();
+        assert_eq!(entry.id, "a1");
+        assert_eq!(entry.key, "k");
+        assert_eq!(entry.namespace, DEFAULT_NAMESPACE);
+    }
+
+    #[test]
+    fn namespace_condition_matches_legacy_points_only_for_default() {
+        let scoped = QdrantMemory::namespace_condition(&["slack:user:U1".into()]);
+        assert_eq!(scoped["should"].as_array().unwrap().len(), 1);
+
+        let with_default =
+            QdrantMemory::namespace_condition(&["slack:user:U1".into(), DEFAULT_NAMESPACE.into()]);
+        let should = with_default["should"].as_array().unwrap();
+        assert_eq!(should.len(), 2);
+        assert_eq!(should[1]["is_empty"]["key"], "namespace");
+    }
 }
@@ -0,0 +1,590 @@
+// Rerank stage for memory recall (`[memory.rerank]`).
+//
+// Hybrid FTS5 + vector recall happily ranks keyword matches that have nothing
+// to do with the question. When enabled, context builders recall a larger
+// candidate set, hand it to a `Reranker` — a Cohere/Jina-style `/rerank`
+// endpoint or a cheap chat model behind a `hint:rerank` route — and keep only
+// the top entries. Orderings are cached per query and candidate set; any
+// failure or timeout falls back to the original recall order.
+
+use super::traits::MemoryEntry;
+use crate::config::{Config, MemoryRerankConfig, RerankBackend};
+use crate::providers::{self, Provider};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Longest snippet (in chars) shown to the LLM reranker per candidate.
+const LLM_SNIPPET_MAX_CHARS: usize = 500;
+
+const LLM_RERANK_SYSTEM_PROMPT: &str = "You rank memory snippets by how relevant they are \
+to a user message. Reply with only a JSON array of snippet numbers, most relevant first. \
+Leave out snippets that are not relevant.";
+
+/// Orders candidate documents by relevance to a query.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Reranker name, for logs.
+    fn name(&self) -> &str;
+
+    /// Indices into `documents`, most relevant first. Indices left out are
+    /// treated as irrelevant.
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<usize>>;
+}
+
+// ── HTTP reranker (Cohere / Jina `/rerank`) ──────────────────
+
+/// Calls a `/rerank` endpoint that takes `{query, documents, top_n, model}`
+/// and answers `{"results": [{"index", "relevance_score"}]}` — the shape
+/// shared by Cohere, Jina, Voyage and most self-hosted rerank servers.
+pub struct HttpReranker {
+    endpoint: String,
+    api_key: Option<String>,
+    model: Option<String>,
+}
+
+impl HttpReranker {
+    pub fn new(endpoint: &str, api_key: Option<&str>, model: Option<&str>) -> Self {
+        let non_empty = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            endpoint: endpoint.trim().to_string(),
+            api_key: non_empty(api_key),
+            model: non_empty(model),
+        }
+    }
+}
+
+#[async_trait]
+impl Reranker for HttpReranker {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<usize>> {
+        let mut body = serde_json::json!({
+            "query": query,
+            "documents": documents,
+            "top_n": documents.len(),
+        });
+        if let Some(model) = &self.model {
+            body["model"] = serde_json::json!(model);
+        }
+
+        let mut request = crate::config::build_runtime_proxy_client("memory.rerank")
+            .post(&self.endpoint)
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Rerank API error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        let results = json
+            .get("results")
+            .and_then(|r| r.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid rerank response: missing 'results'"))?;
+
+        let mut scored: Vec<(usize, f64)> = results
+            .iter()
+            .filter_map(|item| {
+                let index = usize::try_from(item.get("index")?.as_u64()?).ok()?;
+                let score = item
+                    .get("relevance_score")
+                    .and_then(serde_json::Value::as_f64)
+                    .unwrap_or(0.0);
+                (index < documents.len()).then_some((index, score))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored.into_iter().map(|(index, _)| index).collect())
+    }
+}
+
+// ── LLM reranker ─────────────────────────────────────────────
+
+/// Asks a chat model to order the candidates. Intended for a cheap model
+/// behind a `hint:rerank` route, resolved by `RouterProvider`.
+pub struct LlmReranker {
+    provider: Box<dyn Provider>,
+    model: String,
+}
+
+impl LlmReranker {
+    pub fn new(provider: Box<dyn Provider>, model: &str) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+        }
+    }
+
+    fn build_prompt(query: &str, documents: &[&str]) -> String {
+        let mut prompt = format!("User message: {query}\n\nSnippets:\n");
+        for (index, document) in documents.iter().enumerate() {
+            let snippet: String = document
+                .chars()
+                .take(LLM_SNIPPET_MAX_CHARS)
+                .map(|c| if c == '\n' { ' ' } else { c })
+                .collect();
+            let _ = writeln!(prompt, "[{index}] {snippet}");
+        }
+        prompt
+    }
+}
+
+/// Parse the first JSON array of indices in a model reply, dropping
+/// duplicates and out-of-range entries.
+fn parse_llm_ranking(reply: &str, count: usize) -> anyhow::Result<Vec<usize>> {
+    let start = reply
+        .find('[')
+        .ok_or_else(|| anyhow::anyhow!("rerank reply has no JSON array"))?;
+    let end = reply[start..]
+        .find(']')
+        .map(|offset| start + offset)
+        .ok_or_else(|| anyhow::anyhow!("rerank reply has an unterminated JSON array"))?;
+    let indices: Vec<serde_json::Value> = serde_json::from_str(&reply[start..=end])?;
+
+    let mut seen = vec![false; count];
+    let mut order = Vec::with_capacity(count);
+    for value in indices {
+        let Some(index) = value
+            .as_u64()
+            .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+            .and_then(|index| usize::try_from(index).ok())
+        else {
+            continue;
+        };
+        if index < count && !seen[index] {
+            seen[index] = true;
+            order.push(index);
+        }
+    }
+    Ok(order)
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<usize>> {
+        let reply = self
+            .provider
+            .chat_with_system(
+                Some(LLM_RERANK_SYSTEM_PROMPT),
+                &Self::build_prompt(query, documents),
+                &self.model,
+                0.0,
+            )
+            .await?;
+        parse_llm_ranking(&reply, documents.len())
+    }
+}
+
+// ── Recall integration ───────────────────────────────────────
+
+/// Bounded cache of orderings, evicting the oldest insert first.
+struct OrderCache {
+    capacity: usize,
+    entries: HashMap<String, Vec<usize>>,
+    order: VecDeque<String>,
+}
+
+impl OrderCache {
+    fn get(&self, key: &str) -> Option<Vec<usize>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: String, value: Vec<usize>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key.clone(), value).is_none() {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Applies a `Reranker` to recalled memory entries, with caching, a timeout
+/// and fallback to the recall order.
+pub struct MemoryReranker {
+    reranker: Box<dyn Reranker>,
+    candidates: usize,
+    timeout: Duration,
+    cache: Mutex<OrderCache>,
+}
+
+impl MemoryReranker {
+    pub fn new(
+        reranker: Box<dyn Reranker>,
+        candidates: usize,
+        cache_size: usize,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            reranker,
+            candidates,
+            timeout,
+            cache: Mutex::new(OrderCache {
+                capacity: cache_size,
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// How many entries to recall so the reranker has `limit` or more to choose from.
+    pub fn candidate_limit(&self, limit: usize) -> usize {
+        self.candidates.max(limit)
+    }
+
+    fn cache_key(query: &str, entries: &[MemoryEntry]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(query.as_bytes());
+        for entry in entries {
+            hasher.update([0]);
+            hasher.update(entry.id.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Reorder `entries` by relevance to `query`. Entries the reranker leaves
+    /// out are dropped; on error or timeout the input order is returned.
+    pub async fn rerank(&self, query: &str, entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        if entries.len() < 2 {
+            return entries;
+        }
+
+        let key = Self::cache_key(query, &entries);
+        let cached = self.cache.lock().get(&key);
+        let order = match cached {
+            Some(order) => order,
+            None => {
+                let documents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+                let result =
+                    tokio::time::timeout(self.timeout, self.reranker.rerank(query, &documents))
+                        .await;
+                match result {
+                    Ok(Ok(order)) => {
+                        self.cache.lock().insert(key, order.clone());
+                        order
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(
+                            reranker = self.reranker.name(),
+                            "Memory rerank failed, keeping recall order: {e}"
+                        );
+                        return entries;
+                    }
+                    Err(_) => {
+                        tracing::warn!(
+                            reranker = self.reranker.name(),
+                            timeout_secs = self.timeout.as_secs(),
+                            "Memory rerank timed out, keeping recall order"
+                        );
+                        return entries;
+                    }
+                }
+            }
+        };
+
+        let mut slots: Vec<Option<MemoryEntry>> = entries.into_iter().map(Some).collect();
+        order
+            .into_iter()
+            .filter_map(|index| slots.get_mut(index).and_then(Option::take))
+            .collect()
+    }
+}
+
+/// Build the configured memory reranker, or `None` when disabled or
+/// misconfigured (a warning explains why).
+pub fn create_memory_reranker(config: &Config) -> Option<MemoryReranker> {
+    let rerank: &MemoryRerankConfig = &config.memory.rerank;
+    if !rerank.enabled {
+        return None;
+    }
+
+    let reranker: Box<dyn Reranker> = match rerank.backend {
+        RerankBackend::Http => {
+            let Some(endpoint) = rerank
+                .endpoint
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+            else {
+                tracing::warn!(
+                    "[memory.rerank] backend = \"http\" needs an endpoint; rerank disabled"
+                );
+                return None;
+            };
+            Box::new(HttpReranker::new(
+                endpoint,
+                rerank.api_key.as_deref(),
+                rerank.model.as_deref(),
+            ))
+        }
+        RerankBackend::Llm => {
+            let model = rerank
+                .model
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or("hint:rerank");
+            if let Some(hint) = model.strip_prefix("hint:") {
+                if !config.model_routes.iter().any(|route| route.hint == hint) {
+                    tracing::warn!(
+                        hint,
+                        "[memory.rerank] uses a model route hint with no matching [[model_routes]] entry; rerank disabled"
+                    );
+                    return None;
+                }
+            }
+
+            let default_model = config
+                .default_model
+                .as_deref()
+                .unwrap_or("anthropic/claude-sonnet-4");
+            let provider = match providers::create_routed_provider(
+                config.default_provider.as_deref().unwrap_or("openrouter"),
+                config.api_key.as_deref(),
+                config.api_url.as_deref(),
+                &config.reliability,
+                &config.model_routes,
+                default_model,
+            ) {
+                Ok(provider) => provider,
+                Err(e) => {
+                    tracing::warn!("[memory.rerank] provider unavailable, rerank disabled: {e}");
+                    return None;
+                }
+            };
+            Box::new(LlmReranker::new(provider, model))
+        }
+    };
+
+    Some(MemoryReranker::new(
+        reranker,
+        rerank.candidates,
+        rerank.cache_size,
+        Duration::from_secs(rerank.timeout_secs.max(1)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, MemoryMetadata, DEFAULT_NAMESPACE};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn entry(id: &str, content: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: id.into(),
+            key: id.into(),
+            content: content.into(),
+            category: MemoryCategory::Core,
+            timestamp: "now".into(),
+            session_id: None,
+            score: Some(0.5),
+            metadata: MemoryMetadata::default(),
+            namespace: DEFAULT_NAMESPACE.into(),
+        }
+    }
+
+    fn ids(entries: &[MemoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    struct FixedReranker {
+        order: Option<Vec<usize>>,
+        calls: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Reranker for FixedReranker {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn rerank(&self, _query: &str, _documents: &[&str]) -> anyhow::Result<Vec<usize>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.order
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("rerank backend down"))
+        }
+    }
+
+    fn fixed(order: Option<Vec<usize>>, delay: Duration) -> (MemoryReranker, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let reranker = FixedReranker {
+            order,
+            calls: Arc::clone(&calls),
+            delay,
+        };
+        (
+            MemoryReranker::new(Box::new(reranker), 20, 8, Duration::from_millis(200)),
+            calls,
+        )
+    }
+
+    #[tokio::test]
+    async fn rerank_reorders_drops_omitted_and_caches_per_query() {
+        let (reranker, calls) = fixed(Some(vec![2, 0]), Duration::ZERO);
+        let entries = vec![entry("a", "x"), entry("b", "y"), entry("c", "z")];
+
+        let first = reranker.rerank("q", entries.clone()).await;
+        assert_eq!(ids(&first), vec!["c", "a"]);
+        let second = reranker.rerank("q", entries.clone()).await;
+        assert_eq!(ids(&second), vec!["c", "a"]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        reranker.rerank("other", entries).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rerank_failure_keeps_recall_order() {
+        let (reranker, _) = fixed(None, Duration::ZERO);
+        let entries = vec![entry("a", "x"), entry("b", "y")];
+        assert_eq!(ids(&reranker.rerank("q", entries).await), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn rerank_timeout_keeps_recall_order() {
+        let (reranker, _) = fixed(Some(vec![1, 0]), Duration::from_secs(5));
+        let entries = vec![entry("a", "x"), entry("b", "y")];
+        assert_eq!(ids(&reranker.rerank("q", entries).await), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn order_cache_evicts_oldest() {
+        let mut cache = OrderCache {
+            capacity: 2,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        };
+        cache.insert("a".into(), vec![0]);
+        cache.insert("b".into(), vec![1]);
+        cache.insert("c".into(), vec![2]);
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("c"), Some(vec![2]));
+    }
+
+    #[test]
+    fn parse_llm_ranking_tolerates_prose_and_bad_indices() {
+        let order = parse_llm_ranking("Ranking: [3, \"1\", 1, 9, -2, 0] done", 4).unwrap();
+        assert_eq!(order, vec![3, 1, 0]);
+        assert!(parse_llm_ranking("no idea", 4).is_err());
+    }
+
+    struct ScriptedProvider {
+        reply: String,
+        seen_model: Arc<Mutex<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            *self.seen_model.lock() = model.to_string();
+            Ok(self.reply.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn llm_reranker_uses_configured_route() {
+        let seen_model = Arc::new(Mutex::new(String::new()));
+        let reranker = LlmReranker::new(
+            Box::new(ScriptedProvider {
+                reply: "[1, 0]".into(),
+                seen_model: Arc::clone(&seen_model),
+            }),
+            "hint:rerank",
+        );
+        let order = reranker.rerank("q", &["a", "b"]).await.unwrap();
+        assert_eq!(order, vec![1, 0]);
+        assert_eq!(*seen_model.lock(), "hint:rerank");
+    }
+
+    #[test]
+    fn llm_prompt_numbers_and_flattens_snippets() {
+        let prompt = LlmReranker::build_prompt("where?", &["line one\nline two", "other"]);
+        assert!(prompt.contains("[0] line one line two"));
+        assert!(prompt.contains("[1] other"));
+    }
+
+    #[tokio::test]
+    async fn http_reranker_orders_by_relevance_score() {
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/rerank"))
+            .and(header("authorization", "Bearer rr-key"))
+            .and(body_partial_json(serde_json::json!({
+                "query": "q",
+                "documents": ["a", "b", "c"],
+                "top_n": 3,
+                "model": "rerank-v3.5"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {"index": 0, "relevance_score": 0.2},
+                    {"index": 2, "relevance_score": 0.9},
+                    {"index": 7, "relevance_score": 0.8}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let reranker = HttpReranker::new(
+            &format!("{}/v2/rerank", server.uri()),
+            Some("rr-key"),
+            Some("rerank-v3.5"),
+        );
+        let order = reranker.rerank("q", &["a", "b", "c"]).await.unwrap();
+        assert_eq!(order, vec![2, 0]);
+    }
+
+    #[test]
+    fn factory_requires_route_or_endpoint() {
+        let mut config = Config::default();
+        assert!(create_memory_reranker(&config).is_none());
+
+        config.memory.rerank.enabled = true;
+        assert!(create_memory_reranker(&config).is_none(), "no rerank route");
+
+        config.memory.rerank.backend = RerankBackend::Http;
+        assert!(create_memory_reranker(&config).is_none(), "no endpoint");
+
+        config.memory.rerank.endpoint = Some("http://localhost:8080/rerank".into());
+        assert!(create_memory_reranker(&config).is_some());
+    }
+}
@@ -4,13 +4,41 @@
 //! `(model, system_prompt_hash, user_prompt)`. Entries expire after a
 //! configurable TTL (default: 1 hour). The cache is optional and disabled by
 //! default — users opt in via `[memory] response_cache_enabled = true`.
+//!
+//! Entries may also carry a scope hash (everything in the request except the
+//! final user message) and an embedding of that message, so near-duplicate
+//! prompts in the same scope can be served by similarity instead of exact key.
 
+use super::encryption::{self, MemoryCipher};
+use super::vector;
 use anyhow::Result;
 use chrono::{Duration, Local};
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A cached response and the tokens it originally cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub response: String,
+    pub token_count: u32,
+}
+
+/// Lookup counters since the cache was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseCacheCounters {
+    /// Lookups served from the cache (exact + semantic).
+    pub hits: u64,
+    /// Subset of `hits` matched by embedding similarity.
+    pub semantic_hits: u64,
+    pub misses: u64,
+    /// Tokens the cached responses originally cost.
+    pub tokens_saved: u64,
+}
 
 /// Response cache backed by a dedicated SQLite database.
 ///
@@ -22,6 +50,11 @@ pub struct ResponseCache {
     db_path: PathBuf,
     ttl_minutes: i64,
     max_entries: usize,
+    hits: AtomicU64,
+    semantic_hits: AtomicU64,
+    misses: AtomicU64,
+    tokens_saved: AtomicU64,
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
 impl ResponseCache {
@@ -53,14 +86,41 @@ impl ResponseCache {
             CREATE INDEX IF NOT EXISTS idx_rc_created ON response_cache(created_at);",
         )?;
 
+        // Migration: add semantic-match columns if not present (safe to run repeatedly)
+        let has_scope_hash: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='response_cache'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("scope_hash");
+        if !has_scope_hash {
+            conn.execute_batch(
+                "ALTER TABLE response_cache ADD COLUMN scope_hash TEXT;
+                 ALTER TABLE response_cache ADD COLUMN embedding BLOB;",
+            )?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_rc_scope ON response_cache(scope_hash);",
+        )?;
+
         Ok(Self {
             conn: Mutex::new(conn),
             db_path,
             ttl_minutes: i64::from(ttl_minutes),
             max_entries,
+            hits: AtomicU64::new(0),
+            semantic_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            tokens_saved: AtomicU64::new(0),
+            cipher: None,
         })
     }
 
+    /// Encrypt cached responses with `cipher` (see [`MemoryCipher`]).
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Arc<MemoryCipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Build a deterministic cache key from model + system prompt + user prompt.
     pub fn cache_key(model: &str, system_prompt: Option<&str>, user_prompt: &str) -> String {
         let mut hasher = Sha256::new();
@@ -77,43 +137,137 @@ impl ResponseCache {
 
     /// Look up a cached response. Returns `None` on miss or expired entry.
     pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.get_entry(key)?.map(|entry| entry.response))
+    }
+
+    /// Like [`ResponseCache::get`], but also returns the stored token count.
+    pub fn get_entry(&self, key: &str) -> Result<Option<CachedResponse>> {
         let conn = self.conn.lock();
 
         let now = Local::now();
         let cutoff = (now - Duration::minutes(self.ttl_minutes)).to_rfc3339();
 
         let mut stmt = conn.prepare(
-            "SELECT response FROM response_cache
+            "SELECT response, token_count FROM response_cache
              WHERE prompt_hash = ?1 AND created_at > ?2",
         )?;
 
-        let result: Option<String> = stmt.query_row(params![key, cutoff], |row| row.get(0)).ok();
+        let result: Option<CachedResponse> = stmt
+            .query_row(params![key, cutoff], |row| {
+                Ok(CachedResponse {
+                    response: row.get(0)?,
+                    token_count: row.get(1)?,
+                })
+            })
+            .optional()?;
+
+        let Some(mut entry) = result else {
+            return Ok(None);
+        };
+        entry.response = encryption::decrypt_field(self.cipher.as_deref(), &entry.response)?;
+        Self::touch(&conn, key, &now.to_rfc3339())?;
+        Ok(Some(entry))
+    }
 
-        if result.is_some() {
-            // Bump hit count and accessed_at
-            let now_str = now.to_rfc3339();
-            conn.execute(
-                "UPDATE response_cache
-                 SET accessed_at = ?1, hit_count = hit_count + 1
-                 WHERE prompt_hash = ?2",
-                params![now_str, key],
-            )?;
+    /// Find the freshest-best entry in `scope_hash` whose prompt embedding has
+    /// cosine similarity of at least `min_similarity` with `embedding`.
+    pub fn get_similar(
+        &self,
+        scope_hash: &str,
+        embedding: &[f32],
+        min_similarity: f32,
+    ) -> Result<Option<CachedResponse>> {
+        let conn = self.conn.lock();
+
+        let now = Local::now();
+        let cutoff = (now - Duration::minutes(self.ttl_minutes)).to_rfc3339();
+
+        let mut stmt = conn.prepare(
+            "SELECT prompt_hash, response, token_count, embedding FROM response_cache
+             WHERE scope_hash = ?1 AND created_at > ?2 AND embedding IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![scope_hash, cutoff], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+            ))
+        })?;
+
+        let mut best: Option<(f32, String, CachedResponse)> = None;
+        for row in rows {
+            let (key, response, token_count, blob) = row?;
+            let sim = vector::cosine_similarity(embedding, &vector::bytes_to_vec(&blob));
+            if sim >= min_similarity && best.as_ref().is_none_or(|(b, _, _)| sim > *b) {
+                best = Some((
+                    sim,
+                    key,
+                    CachedResponse {
+                        response,
+                        token_count,
+                    },
+                ));
+            }
         }
+        drop(stmt);
+
+        let Some((_, key, mut entry)) = best else {
+            return Ok(None);
+        };
+        entry.response = encryption::decrypt_field(self.cipher.as_deref(), &entry.response)?;
+        Self::touch(&conn, &key, &now.to_rfc3339())?;
+        Ok(Some(entry))
+    }
 
-        Ok(result)
+    /// Bump hit count and accessed_at.
+    fn touch(conn: &Connection, key: &str, now: &str) -> Result<()> {
+        conn.execute(
+            "UPDATE response_cache
+             SET accessed_at = ?1, hit_count = hit_count + 1
+             WHERE prompt_hash = ?2",
+            params![now, key],
+        )?;
+        Ok(())
     }
 
     /// Store a response in the cache.
     pub fn put(&self, key: &str, model: &str, response: &str, token_count: u32) -> Result<()> {
+        self.put_scoped(key, None, model, response, token_count, None)
+    }
+
+    /// Store a response with an optional scope hash and prompt embedding,
+    /// making it eligible for [`ResponseCache::get_similar`].
+    pub fn put_scoped(
+        &self,
+        key: &str,
+        scope_hash: Option<&str>,
+        model: &str,
+        response: &str,
+        token_count: u32,
+        embedding: Option<&[f32]>,
+    ) -> Result<()> {
+        let response = encryption::encrypt_field(self.cipher.as_deref(), response)?;
         let conn = self.conn.lock();
 
         let now = Local::now().to_rfc3339();
+        let embedding = embedding.map(vector::vec_to_bytes);
 
         conn.execute(
             "INSERT OR REPLACE INTO response_cache
-             (prompt_hash, model, response, token_count, created_at, accessed_at, hit_count)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)",
-            params![key, model, response, token_count, now, now],
+             (prompt_hash, model, response, token_count, created_at, accessed_at, hit_count,
+              scope_hash, embedding)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?8)",
+            params![
+                key,
+                model,
+                response,
+                token_count,
+                now,
+                now,
+                scope_hash,
+                embedding
+            ],
         )?;
 
         // Evict expired entries
@@ -161,6 +315,31 @@ impl ResponseCache {
         Ok((count as usize, hits as u64, tokens_saved as u64))
     }
 
+    /// Record a lookup served from the cache.
+    pub fn record_hit(&self, entry: &CachedResponse, semantic: bool) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        if semantic {
+            self.semantic_hits.fetch_add(1, Ordering::Relaxed);
+        }
+        self.tokens_saved
+            .fetch_add(u64::from(entry.token_count), Ordering::Relaxed);
+    }
+
+    /// Record a lookup that had to go to the provider.
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Lookup counters since the cache was opened.
+    pub fn counters(&self) -> ResponseCacheCounters {
+        ResponseCacheCounters {
+            hits: self.hits.load(Ordering::Relaxed),
+            semantic_hits: self.semantic_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            tokens_saved: self.tokens_saved.load(Ordering::Relaxed),
+        }
+    }
+
     /// Wipe the entire cache (useful for `zeroclaw cache clear`).
     pub fn clear(&self) -> Result<usize> {
         let conn = self.conn.lock();
@@ -383,6 +562,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encrypted_responses_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = Arc::new(MemoryCipher::open(tmp.path(), true).unwrap());
+        let cache = ResponseCache::new(tmp.path(), 60, 100)
+            .unwrap()
+            .with_cipher(Some(cipher));
+
+        let key = ResponseCache::cache_key("gpt-4", None, "where do I live?");
+        cache.put(&key, "gpt-4", "You live in Lisbon.", 12).unwrap();
+        assert_eq!(
+            cache.get(&key).unwrap().as_deref(),
+            Some("You live in Lisbon.")
+        );
+
+        let stored: String = cache
+            .conn
+            .lock()
+            .query_row(
+                "SELECT response FROM response_cache WHERE prompt_hash = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(MemoryCipher::is_encrypted(&stored));
+        assert!(!stored.contains("Lisbon"));
+    }
+
     #[test]
     fn cache_handles_zero_max_entries() {
         let tmp = TempDir::new().unwrap();
@@ -396,6 +603,124 @@ mod tests {
         assert_eq!(count, 0, "cache with max_entries=0 should evict everything");
     }
 
+    // ── Semantic match ───────────────────────────────────────
+
+    #[test]
+    fn get_similar_matches_within_scope_above_threshold() {
+        let (_tmp, cache) = temp_cache(60);
+        cache
+            .put_scoped(
+                "k1",
+                Some("scope-a"),
+                "gpt-4",
+                "answer",
+                40,
+                Some(&[1.0, 0.0, 0.0]),
+            )
+            .unwrap();
+
+        let hit = cache
+            .get_similar("scope-a", &[0.99, 0.1, 0.0], 0.95)
+            .unwrap();
+        assert_eq!(
+            hit,
+            Some(CachedResponse {
+                response: "answer".into(),
+                token_count: 40
+            })
+        );
+
+        // Different scope or dissimilar prompt → miss
+        assert!(cache
+            .get_similar("scope-b", &[1.0, 0.0, 0.0], 0.95)
+            .unwrap()
+            .is_none());
+        assert!(cache
+            .get_similar("scope-a", &[0.0, 1.0, 0.0], 0.95)
+            .unwrap()
+            .is_none());
+
+        let (_, hits, _) = cache.stats().unwrap();
+        assert_eq!(hits, 1);
+    }
+
+    #[test]
+    fn get_similar_picks_closest_entry() {
+        let (_tmp, cache) = temp_cache(60);
+        cache
+            .put_scoped("k1", Some("s"), "m", "far", 1, Some(&[1.0, 0.3]))
+            .unwrap();
+        cache
+            .put_scoped("k2", Some("s"), "m", "near", 1, Some(&[1.0, 0.05]))
+            .unwrap();
+
+        let hit = cache.get_similar("s", &[1.0, 0.0], 0.9).unwrap().unwrap();
+        assert_eq!(hit.response, "near");
+    }
+
+    #[test]
+    fn get_similar_ignores_expired_entries() {
+        let (_tmp, cache) = temp_cache(0);
+        cache
+            .put_scoped("k1", Some("s"), "m", "stale", 1, Some(&[1.0, 0.0]))
+            .unwrap();
+        assert!(cache.get_similar("s", &[1.0, 0.0], 0.5).unwrap().is_none());
+    }
+
+    #[test]
+    fn counters_track_hits_and_misses() {
+        let (_tmp, cache) = temp_cache(60);
+        let entry = CachedResponse {
+            response: "r".into(),
+            token_count: 30,
+        };
+        cache.record_hit(&entry, false);
+        cache.record_hit(&entry, true);
+        cache.record_miss();
+
+        assert_eq!(
+            cache.counters(),
+            ResponseCacheCounters {
+                hits: 2,
+                semantic_hits: 1,
+                misses: 1,
+                tokens_saved: 60,
+            }
+        );
+    }
+
+    #[test]
+    fn legacy_schema_is_migrated() {
+        let tmp = TempDir::new().unwrap();
+        let db_dir = tmp.path().join("memory");
+        std::fs::create_dir_all(&db_dir).unwrap();
+        {
+            let conn = Connection::open(db_dir.join("response_cache.db")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE response_cache (
+                    prompt_hash TEXT PRIMARY KEY,
+                    model       TEXT NOT NULL,
+                    response    TEXT NOT NULL,
+                    token_count INTEGER NOT NULL DEFAULT 0,
+                    created_at  TEXT NOT NULL,
+                    accessed_at TEXT NOT NULL,
+                    hit_count   INTEGER NOT NULL DEFAULT 0
+                );",
+            )
+            .unwrap();
+        }
+
+        let cache = ResponseCache::new(tmp.path(), 60, 100).unwrap();
+        cache
+            .put_scoped("k", Some("s"), "m", "r", 1, Some(&[1.0]))
+            .unwrap();
+        assert_eq!(cache.get("k").unwrap().as_deref(), Some("r"));
+
+        // Reopening an already-migrated database is a no-op
+        drop(cache);
+        ResponseCache::new(tmp.path(), 60, 100).unwrap();
+    }
+
     #[test]
     fn cache_concurrent_reads_no_panic() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,184 @@
+// Recall re-ranking by importance and recency.
+//
+// Backends compute a relevance score (BM25, cosine or hybrid). `rerank`
+// scales it by the entry's importance and an exponential age decay, drops
+// expired entries and re-sorts, so every backend ranks metadata the same way.
+
+use super::traits::MemoryEntry;
+use crate::config::MemoryConfig;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Weights applied on top of a backend's relevance score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecallWeights {
+    /// Scores range over 1 ± this weight from importance 0.0 to 1.0.
+    pub importance_weight: f64,
+    /// Share of the score that decays with age.
+    pub recency_weight: f64,
+    /// Age at which the decaying share is halved; 0 disables decay.
+    pub recency_half_life_days: f64,
+}
+
+impl Default for RecallWeights {
+    fn default() -> Self {
+        Self::from_config(&MemoryConfig::default())
+    }
+}
+
+impl RecallWeights {
+    pub fn from_config(config: &MemoryConfig) -> Self {
+        Self {
+            importance_weight: config.importance_weight.clamp(0.0, 1.0),
+            recency_weight: config.recency_weight.clamp(0.0, 1.0),
+            recency_half_life_days: config.recency_half_life_days.max(0.0),
+        }
+    }
+
+    /// Multiplier for `entry`'s relevance score at time `now`.
+    pub fn factor(&self, entry: &MemoryEntry, now: DateTime<Utc>) -> f64 {
+        let importance = 1.0 + self.importance_weight * (2.0 * entry.metadata.importance() - 1.0);
+
+        let recency = match parse_timestamp(&entry.timestamp) {
+            Some(ts) if self.recency_half_life_days > 0.0 => {
+                #[allow(clippy::cast_precision_loss)]
+                let age_days = (now - ts).num_seconds().max(0) as f64 / 86_400.0;
+                let decay = 0.5_f64.powf(age_days / self.recency_half_life_days);
+                (1.0 - self.recency_weight) + self.recency_weight * decay
+            }
+            _ => 1.0,
+        };
+
+        importance * recency
+    }
+}
+
+/// Drop expired entries, weight scores and sort best-first.
+///
+/// Entries without a score keep their relative order after scored ones.
+pub fn rerank(entries: &mut Vec<MemoryEntry>, weights: &RecallWeights, now: DateTime<Utc>) {
+    entries.retain(|entry| !entry.metadata.is_expired(now));
+    for entry in entries.iter_mut() {
+        if let Some(score) = entry.score {
+            entry.score = Some(score * weights.factor(entry, now));
+        }
+    }
+    entries.sort_by(|a, b| {
+        let a = a.score.unwrap_or(f64::NEG_INFINITY);
+        let b = b.score.unwrap_or(f64::NEG_INFINITY);
+        b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// RFC 3339 timestamps, or bare `YYYY-MM-DD` dates (markdown daily logs).
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+                .map(|dt| dt.and_utc())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::traits::{MemoryCategory, MemoryMetadata};
+
+    fn entry(key: &str, score: f64, timestamp: &str, metadata: MemoryMetadata) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: String::new(),
+            category: MemoryCategory::Core,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: Some(score),
+            metadata,
+            namespace: crate::memory::DEFAULT_NAMESPACE.into(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2026-06-01T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn default_entry_scored_now_is_unchanged() {
+        let weights = RecallWeights::default();
+        let e = entry("a", 0.8, "2026-06-01T00:00:00Z", MemoryMetadata::default());
+        assert!((weights.factor(&e, now()) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn importance_scales_score() {
+        let weights = RecallWeights::default();
+        let high = MemoryMetadata {
+            importance: Some(1.0),
+            ..MemoryMetadata::default()
+        };
+        let low = MemoryMetadata {
+            importance: Some(0.0),
+            ..MemoryMetadata::default()
+        };
+        let ts = "2026-06-01T00:00:00Z";
+        assert!((weights.factor(&entry("h", 1.0, ts, high), now()) - 1.3).abs() < 1e-9);
+        assert!((weights.factor(&entry("l", 1.0, ts, low), now()) - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recency_halves_decaying_share_per_half_life() {
+        let weights = RecallWeights::default();
+        let month_old = entry("o", 1.0, "2026-05-02T00:00:00Z", MemoryMetadata::default());
+        // 30 days old: 0.8 + 0.2 * 0.5
+        assert!((weights.factor(&month_old, now()) - 0.9).abs() < 1e-9);
+
+        let dated = entry("d", 1.0, "2026-05-02", MemoryMetadata::default());
+        assert!((weights.factor(&dated, now()) - 0.9).abs() < 1e-9);
+
+        let undated = entry("u", 1.0, "MEMORY", MemoryMetadata::default());
+        assert!((weights.factor(&undated, now()) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rerank_drops_expired_and_reorders() {
+        let weights = RecallWeights::default();
+        let expired = MemoryMetadata {
+            expires_at: Some("2026-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        let important = MemoryMetadata {
+            importance: Some(1.0),
+            ..MemoryMetadata::default()
+        };
+        let mut entries = vec![
+            entry(
+                "plain",
+                0.9,
+                "2026-06-01T00:00:00Z",
+                MemoryMetadata::default(),
+            ),
+            entry("gone", 1.0, "2026-06-01T00:00:00Z", expired),
+            entry("vip", 0.8, "2026-06-01T00:00:00Z", important),
+        ];
+        rerank(&mut entries, &weights, now());
+        let keys: Vec<_> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["vip", "plain"]);
+    }
+
+    #[test]
+    fn from_config_clamps_weights() {
+        let config = MemoryConfig {
+            importance_weight: 4.0,
+            recency_weight: -1.0,
+            recency_half_life_days: -5.0,
+            ..MemoryConfig::default()
+        };
+        let weights = RecallWeights::from_config(&config);
+        assert!((weights.importance_weight - 1.0).abs() < f64::EPSILON);
+        assert!(weights.recency_weight.abs() < f64::EPSILON);
+        assert!(weights.recency_half_life_days.abs() < f64::EPSILON);
+    }
+}
@@ -5,7 +5,11 @@
 //!
 //! **Auto-Hydration**: if `brain.db` is missing but `MEMORY_SNAPSHOT.md` exists,
 //! re-indexes all entries back into a fresh SQLite database.
+//!
+//! With `[memory] encrypt_at_rest`, the whole snapshot is written as a single
+//! encrypted value and hydrated rows are encrypted again on insert.
 
+use super::encryption::{self, MemoryCipher};
 use anyhow::Result;
 use chrono::Local;
 use rusqlite::{params, Connection};
@@ -25,7 +29,7 @@ const SNAPSHOT_HEADER: &str = "# 🧠 ZeroClaw Memory Snapshot\n\n\
 /// Export all `Core` memories from SQLite → `MEMORY_SNAPSHOT.md`.
 ///
 /// Returns the number of entries exported.
-pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
+pub fn export_snapshot(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<usize> {
     let db_path = workspace_dir.join("memory").join("brain.db");
     if !db_path.exists() {
         tracing::debug!("snapshot export skipped: brain.db does not exist");
@@ -68,6 +72,7 @@ pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
     write!(output, "**Total core memories:** {}\n\n---\n\n", rows.len()).unwrap();
 
     for (key, content, _category, created_at, updated_at) in &rows {
+        let content = encryption::decrypt_field(cipher, content)?;
         write!(output, "### 🔑 `{key}`\n\n").unwrap();
         write!(output, "{content}\n\n").unwrap();
         write!(
@@ -78,6 +83,9 @@ pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
     }
 
     let snapshot_path = snapshot_path(workspace_dir);
+    if cipher.is_some_and(MemoryCipher::encrypts_writes) {
+        output = encryption::encrypt_field(cipher, &output)? + "\n";
+    }
     fs::write(&snapshot_path, output)?;
 
     tracing::info!(
@@ -93,13 +101,13 @@ pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
 ///
 /// Called during cold-boot when `brain.db` doesn't exist but the snapshot does.
 /// Returns the number of entries hydrated.
-pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
+pub fn hydrate_from_snapshot(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<usize> {
     let snapshot = snapshot_path(workspace_dir);
     if !snapshot.exists() {
         return Ok(0);
     }
 
-    let content = fs::read_to_string(&snapshot)?;
+    let content = read_snapshot(&snapshot, cipher)?;
     let entries = parse_snapshot(&content);
 
     if entries.is_empty() {
@@ -123,7 +131,8 @@ pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
             category   TEXT NOT NULL DEFAULT 'core',
             embedding  BLOB,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            search_terms TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_mem_key ON memories(key);
         CREATE INDEX IF NOT EXISTS idx_mem_cat ON memories(category);
@@ -135,7 +144,8 @@ pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
         CREATE TABLE IF NOT EXISTS embedding_cache (
             content_hash TEXT PRIMARY KEY,
             embedding    BLOB NOT NULL,
-            created_at   TEXT NOT NULL
+            created_at   TEXT NOT NULL,
+            accessed_at  TEXT NOT NULL
         );",
     )?;
 
@@ -144,18 +154,20 @@ pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
 
     for (key, content) in &entries {
         let id = uuid::Uuid::new_v4().to_string();
+        let search_terms = cipher.and_then(|c| c.search_terms(content));
+        let stored = encryption::encrypt_field(cipher, content)?;
         let result = conn.execute(
-            "INSERT OR IGNORE INTO memories (id, key, content, category, created_at, updated_at)
-             VALUES (?1, ?2, ?3, 'core', ?4, ?5)",
-            params![id, key, content, now, now],
+            "INSERT OR IGNORE INTO memories (id, key, content, category, created_at, updated_at, search_terms)
+             VALUES (?1, ?2, ?3, 'core', ?4, ?5, ?6)",
+            params![id, key, stored, now, now, search_terms],
         );
 
         match result {
             Ok(changed) if changed > 0 => {
-                // Populate FTS5
+                // Populate FTS5 (blind terms stand in for encrypted content)
                 let _ = conn.execute(
                     "INSERT INTO memories_fts(key, content) VALUES (?1, ?2)",
-                    params![key, content],
+                    params![key, search_terms.as_deref().unwrap_or(content)],
                 );
                 hydrated += 1;
             }
@@ -203,6 +215,35 @@ fn snapshot_path(workspace_dir: &Path) -> PathBuf {
     workspace_dir.join(SNAPSHOT_FILENAME)
 }
 
+/// Read the snapshot, decrypting it if it was written encrypted.
+fn read_snapshot(path: &Path, cipher: Option<&MemoryCipher>) -> Result<String> {
+    let raw = fs::read_to_string(path)?;
+    let trimmed = raw.trim();
+    if MemoryCipher::is_encrypted(trimmed) {
+        encryption::decrypt_field(cipher, trimmed)
+    } else {
+        Ok(raw)
+    }
+}
+
+/// Rewrite an existing snapshot under the cipher's active key, or as
+/// plaintext when the cipher is decrypt-only. Returns whether a snapshot
+/// existed.
+pub fn rekey_snapshot(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<bool> {
+    let path = snapshot_path(workspace_dir);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = read_snapshot(&path, cipher)?;
+    let output = if cipher.is_some_and(MemoryCipher::encrypts_writes) {
+        encryption::encrypt_field(cipher, &content)? + "\n"
+    } else {
+        content
+    };
+    fs::write(&path, output)?;
+    Ok(true)
+}
+
 /// Parse the structured markdown snapshot back into (key, content) pairs.
 fn parse_snapshot(input: &str) -> Vec<(String, String)> {
     let mut entries = Vec::new();
@@ -330,7 +371,7 @@ Rule 3: Protect the user.
     #[test]
     fn export_no_db_returns_zero() {
         let tmp = TempDir::new().unwrap();
-        let count = export_snapshot(tmp.path()).unwrap();
+        let count = export_snapshot(tmp.path(), None).unwrap();
         assert_eq!(count, 0);
     }
 
@@ -383,7 +424,7 @@ Rule 3: Protect the user.
         drop(conn);
 
         // Export snapshot
-        let exported = export_snapshot(workspace).unwrap();
+        let exported = export_snapshot(workspace, None).unwrap();
         assert_eq!(exported, 2, "Should export only core memories");
 
         // Verify the file exists and is readable
@@ -403,7 +444,7 @@ Rule 3: Protect the user.
         assert!(should_hydrate(workspace));
 
         // Hydrate from snapshot
-        let hydrated = hydrate_from_snapshot(workspace).unwrap();
+        let hydrated = hydrate_from_snapshot(workspace, None).unwrap();
         assert_eq!(hydrated, 2, "Should hydrate both core memories");
 
         // Verify brain.db was recreated
@@ -461,10 +502,49 @@ Rule 3: Protect the user.
         assert!(!should_hydrate(workspace));
     }
 
+    #[tokio::test]
+    async fn encrypted_snapshot_roundtrip() {
+        use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+        use std::sync::Arc;
+
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let cipher = Arc::new(MemoryCipher::open(tmp.path(), true).unwrap());
+
+        let mem = SqliteMemory::new(&workspace)
+            .unwrap()
+            .with_cipher(Some(cipher.clone()));
+        mem.store(
+            "identity",
+            "Agent codename bluefin",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        drop(mem);
+
+        assert_eq!(export_snapshot(&workspace, Some(&cipher)).unwrap(), 1);
+        let raw = fs::read_to_string(workspace.join(SNAPSHOT_FILENAME)).unwrap();
+        assert!(MemoryCipher::is_encrypted(raw.trim()));
+        assert!(!raw.contains("bluefin"));
+
+        fs::remove_dir_all(workspace.join("memory")).unwrap();
+        assert_eq!(hydrate_from_snapshot(&workspace, Some(&cipher)).unwrap(), 1);
+
+        let mem = SqliteMemory::new(&workspace)
+            .unwrap()
+            .with_cipher(Some(cipher));
+        let entry = mem.get("identity").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Agent codename bluefin");
+        let recalled = mem.recall("bluefin", 5, None).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+    }
+
     #[test]
     fn hydrate_no_snapshot_returns_zero() {
         let tmp = TempDir::new().unwrap();
-        let count = hydrate_from_snapshot(tmp.path()).unwrap();
+        let count = hydrate_from_snapshot(tmp.path(), None).unwrap();
         assert_eq!(count, 0);
     }
 }
@@ -1,11 +1,16 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::encryption::{self, MemoryCipher};
+use super::hnsw::HnswIndex;
+use super::scoring::{self, RecallWeights};
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE, TAG_RECALL_OVERSAMPLE,
+};
 use super::vector;
 use anyhow::Context;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, Utc};
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
@@ -17,14 +22,64 @@ use uuid::Uuid;
 /// Maximum allowed open timeout (seconds) to avoid unreasonable waits.
 const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 
+/// Persist the ANN index after this many unsaved inserts/removals.
+const ANN_SAVE_EVERY: usize = 32;
+
+/// ANN queries fetch this many times `limit` candidates before filtering by
+/// namespace and session.
+const ANN_SCOPE_OVERSAMPLE: usize = 8;
+
+/// Columns read into a `MemoryEntry` by [`SqliteMemory::row_to_entry`].
+const ENTRY_COLUMNS: &str =
+    "id, key, content, category, created_at, session_id, metadata, access_count, namespace";
+
+/// FTS5 sync triggers, recreated when the `memories` table is rebuilt.
+/// Encrypted rows index their blind `search_terms` instead of the content.
+const FTS_TRIGGERS: &str = "
+    CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
+        INSERT INTO memories_fts(rowid, key, content)
+        VALUES (new.rowid, new.key, COALESCE(new.search_terms, new.content));
+    END;
+    CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, key, content)
+        VALUES ('delete', old.rowid, old.key, COALESCE(old.search_terms, old.content));
+    END;
+    CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, key, content)
+        VALUES ('delete', old.rowid, old.key, COALESCE(old.search_terms, old.content));
+        INSERT INTO memories_fts(rowid, key, content)
+        VALUES (new.rowid, new.key, COALESCE(new.search_terms, new.content));
+    END;";
+
+/// Rebuild the FTS index from the `memories` table. FTS5's own `rebuild`
+/// would read the (possibly encrypted) `content` column directly.
+const FTS_REBUILD: &str = "
+    INSERT INTO memories_fts(memories_fts) VALUES('delete-all');
+    INSERT INTO memories_fts(rowid, key, content)
+    SELECT rowid, key, COALESCE(search_terms, content) FROM memories;";
+
+/// Lifecycle of the HNSW index over stored embeddings.
+enum AnnState {
+    /// Disabled by config, or the embedder produces no vectors.
+    Disabled,
+    /// Loaded from `brain.hnsw` (or rebuilt) on first use.
+    Unloaded,
+    Ready {
+        index: HnswIndex,
+        unsaved: usize,
+    },
+}
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
 /// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
+/// - **ANN Index**: HNSW graph persisted as `brain.hnsw`, brute-force fallback
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
+/// - **Encryption at rest**: optional field-level encryption of content
 pub struct SqliteMemory {
     conn: Arc<Mutex<Connection>>,
     db_path: PathBuf,
@@ -32,6 +87,10 @@ pub struct SqliteMemory {
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    ann: Arc<Mutex<AnnState>>,
+    ann_path: PathBuf,
+    weights: RecallWeights,
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
 impl SqliteMemory {
@@ -83,16 +142,52 @@ impl SqliteMemory {
 
         Self::init_schema(&conn)?;
 
+        let ann = if embedder.dimensions() == 0 {
+            AnnState::Disabled
+        } else {
+            AnnState::Unloaded
+        };
+
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            ann_path: db_path.with_extension("hnsw"),
             db_path,
             embedder,
             vector_weight,
             keyword_weight,
             cache_max,
+            ann: Arc::new(Mutex::new(ann)),
+            weights: RecallWeights::default(),
+            cipher: None,
         })
     }
 
+    /// Enable or disable the HNSW index for vector recall (enabled by default).
+    ///
+    /// When disabled, recall scans every stored embedding.
+    #[must_use]
+    pub fn with_ann_index(self, enabled: bool) -> Self {
+        if !enabled {
+            *self.ann.lock() = AnnState::Disabled;
+        }
+        self
+    }
+
+    /// Set the importance/recency weighting applied to recall scores.
+    #[must_use]
+    pub fn with_recall_weights(mut self, weights: RecallWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Encrypt stored content with `cipher` (see [`MemoryCipher`]). Without a
+    /// cipher, content is stored in plaintext and encrypted rows are unreadable.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Arc<MemoryCipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Open SQLite connection, optionally with a timeout (for locked/slow storage).
     fn open_connection(
         db_path: &Path,
@@ -135,7 +230,8 @@ impl SqliteMemory {
                 category    TEXT NOT NULL DEFAULT 'core',
                 embedding   BLOB,
                 created_at  TEXT NOT NULL,
-                updated_at  TEXT NOT NULL
+                updated_at  TEXT NOT NULL,
+                search_terms TEXT
             );
             CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
             CREATE INDEX IF NOT EXISTS idx_memories_key ON memories(key);
@@ -145,22 +241,6 @@ impl SqliteMemory {
                 key, content, content=memories, content_rowid=rowid
             );
 
-            -- FTS5 triggers: keep in sync with memories table
-            CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-
             -- Embedding cache with LRU eviction
             CREATE TABLE IF NOT EXISTS embedding_cache (
                 content_hash TEXT PRIMARY KEY,
@@ -170,6 +250,7 @@ impl SqliteMemory {
             );
             CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
         )?;
+        conn.execute_batch(FTS_TRIGGERS)?;
 
         // Migration: add session_id column if not present (safe to run repeatedly)
         let has_session_id: bool = conn
@@ -183,6 +264,142 @@ impl SqliteMemory {
             )?;
         }
 
+        // Migration: add metadata columns (tags/source/importance/expiry JSON + access count)
+        let has_metadata: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("access_count");
+        if !has_metadata {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN metadata TEXT;
+                 ALTER TABLE memories ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+
+        // Migration: blind search terms for encrypted rows. The FTS triggers
+        // index them in place of the content, so they are recreated too.
+        let has_search_terms: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("search_terms");
+        if !has_search_terms {
+            conn.execute_batch(&format!(
+                "ALTER TABLE memories ADD COLUMN search_terms TEXT;
+                 DROP TRIGGER IF EXISTS memories_ai;
+                 DROP TRIGGER IF EXISTS memories_ad;
+                 DROP TRIGGER IF EXISTS memories_au;
+                 {FTS_TRIGGERS}"
+            ))?;
+        }
+
+        // Migration: namespaces. Keys become unique per namespace, which needs a
+        // table rebuild; rowids are kept so the FTS index stays valid. Existing
+        // rows land in the default namespace.
+        let has_namespace: bool = conn
+            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+            .query_row([], |row| row.get::<_, String>(0))?
+            .contains("namespace");
+        if !has_namespace {
+            conn.execute_batch(&format!(
+                "BEGIN;
+                 CREATE TABLE memories_migrated (
+                     id           TEXT PRIMARY KEY,
+                     key          TEXT NOT NULL,
+                     content      TEXT NOT NULL,
+                     category     TEXT NOT NULL DEFAULT 'core',
+                     embedding    BLOB,
+                     created_at   TEXT NOT NULL,
+                     updated_at   TEXT NOT NULL,
+                     session_id   TEXT,
+                     metadata     TEXT,
+                     access_count INTEGER NOT NULL DEFAULT 0,
+                     namespace    TEXT NOT NULL DEFAULT '{DEFAULT_NAMESPACE}',
+                     search_terms TEXT,
+                     UNIQUE(namespace, key)
+                 );
+                 INSERT INTO memories_migrated
+                     (rowid, id, key, content, category, embedding, created_at, updated_at,
+                      session_id, metadata, access_count, search_terms)
+                 SELECT rowid, id, key, content, category, embedding, created_at, updated_at,
+                        session_id, metadata, access_count, search_terms
+                 FROM memories;
+                 DROP TABLE memories;
+                 ALTER TABLE memories_migrated RENAME TO memories;
+                 CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
+                 CREATE INDEX IF NOT EXISTS idx_memories_key ON memories(key);
+                 CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id);
+                 CREATE INDEX IF NOT EXISTS idx_memories_namespace ON memories(namespace);
+                 {FTS_TRIGGERS}
+                 COMMIT;"
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// `namespace IN (?n, ...)` starting at placeholder `first`.
+    fn namespace_clause(column: &str, namespaces: &[String], first: usize) -> String {
+        let placeholders: Vec<String> = (first..first + namespaces.len())
+            .map(|i| format!("?{i}"))
+            .collect();
+        format!("{column} IN ({})", placeholders.join(", "))
+    }
+
+    /// Map a row selected with [`ENTRY_COLUMNS`].
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<MemoryEntry> {
+        let metadata_json: Option<String> = row.get(6)?;
+        let mut metadata: MemoryMetadata = metadata_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        metadata.access_count = u64::try_from(row.get::<_, i64>(7)?).unwrap_or(0);
+
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: row.get(2)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: row.get(5)?,
+            score: None,
+            metadata,
+            namespace: row.get(8)?,
+        })
+    }
+
+    /// Decrypt the content of entries read with [`ENTRY_COLUMNS`].
+    fn decrypt_entries(
+        cipher: Option<&MemoryCipher>,
+        entries: &mut [MemoryEntry],
+    ) -> anyhow::Result<()> {
+        for entry in entries {
+            if MemoryCipher::is_encrypted(&entry.content) {
+                entry.content = encryption::decrypt_field(cipher, &entry.content)
+                    .with_context(|| format!("failed to decrypt memory '{}'", entry.key))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Increment `access_count` for recalled entries.
+    fn record_access(conn: &Connection, entries: &mut [MemoryEntry]) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let placeholders: String = (1..=entries.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "UPDATE memories SET access_count = access_count + 1 WHERE id IN ({placeholders})"
+        );
+        let ids: Vec<&dyn rusqlite::types::ToSql> = entries
+            .iter()
+            .map(|e| &e.id as &dyn rusqlite::types::ToSql)
+            .collect();
+        conn.execute(&sql, ids.as_slice())?;
+        for entry in entries {
+            entry.metadata.access_count += 1;
+        }
         Ok(())
     }
 
@@ -191,6 +408,7 @@ impl SqliteMemory {
             MemoryCategory::Core => "core".into(),
             MemoryCategory::Daily => "daily".into(),
             MemoryCategory::Conversation => "conversation".into(),
+            MemoryCategory::Knowledge => "knowledge".into(),
             MemoryCategory::Custom(name) => name.clone(),
         }
     }
@@ -200,6 +418,7 @@ impl SqliteMemory {
             "core" => MemoryCategory::Core,
             "daily" => MemoryCategory::Daily,
             "conversation" => MemoryCategory::Conversation,
+            "knowledge" => MemoryCategory::Knowledge,
             other => MemoryCategory::Custom(other.to_string()),
         }
     }
@@ -223,11 +442,29 @@ impl SqliteMemory {
 
     /// Get embedding from cache, or compute + cache it
     async fn get_or_compute_embedding(&self, text: &str) -> anyhow::Result<Option<Vec<f32>>> {
+        self.cached_embedding(text, false).await
+    }
+
+    /// Like `get_or_compute_embedding`, but embeds `query` as a search query.
+    /// Query vectors are cached under a separate key, since some providers
+    /// embed queries and documents differently.
+    async fn get_or_compute_query_embedding(
+        &self,
+        query: &str,
+    ) -> anyhow::Result<Option<Vec<f32>>> {
+        self.cached_embedding(query, true).await
+    }
+
+    async fn cached_embedding(&self, text: &str, query: bool) -> anyhow::Result<Option<Vec<f32>>> {
         if self.embedder.dimensions() == 0 {
             return Ok(None); // Noop embedder
         }
 
-        let hash = Self::content_hash(text);
+        let hash = if query {
+            Self::content_hash(&format!("query\n{text}"))
+        } else {
+            Self::content_hash(text)
+        };
         let now = Local::now().to_rfc3339();
 
         // Check cache (offloaded to blocking thread)
@@ -255,7 +492,11 @@ impl SqliteMemory {
         }
 
         // Compute embedding (async I/O)
-        let embedding = self.embedder.embed_one(text).await?;
+        let embedding = if query {
+            self.embedder.embed_query(text).await?
+        } else {
+            self.embedder.embed_one(text).await?
+        };
         let bytes = vector::vec_to_bytes(&embedding);
 
         // Store in cache + LRU eviction (offloaded to blocking thread)
@@ -289,11 +530,18 @@ impl SqliteMemory {
         conn: &Connection,
         query: &str,
         limit: usize,
+        namespaces: &[String],
+        cipher: Option<&MemoryCipher>,
     ) -> anyhow::Result<Vec<(String, f32)>> {
-        // Escape FTS5 special chars and build query
+        // Escape FTS5 special chars and build query. Encrypted rows are
+        // matched through the blind form of each word.
         let fts_query: String = query
             .split_whitespace()
-            .map(|w| format!("\"{w}\""))
+            .flat_map(|w| {
+                let blind = cipher.map(|c| c.query_terms(w)).unwrap_or_default();
+                std::iter::once(format!("\"{w}\""))
+                    .chain(blind.into_iter().map(|terms| format!("\"{terms}\"")))
+            })
             .collect::<Vec<_>>()
             .join(" OR ");
 
@@ -301,18 +549,27 @@ impl SqliteMemory {
             return Ok(Vec::new());
         }
 
-        let sql = "SELECT m.id, bm25(memories_fts) as score
-                   FROM memories_fts f
-                   JOIN memories m ON m.rowid = f.rowid
-                   WHERE memories_fts MATCH ?1
-                   ORDER BY score
-                   LIMIT ?2";
+        let sql = format!(
+            "SELECT m.id, bm25(memories_fts) as score
+             FROM memories_fts f
+             JOIN memories m ON m.rowid = f.rowid
+             WHERE memories_fts MATCH ?1 AND {}
+             ORDER BY score
+             LIMIT ?2",
+            Self::namespace_clause("m.namespace", namespaces, 3)
+        );
 
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
         #[allow(clippy::cast_possible_wrap)]
         let limit_i64 = limit as i64;
+        let mut param_values: Vec<&dyn rusqlite::types::ToSql> = vec![&fts_query, &limit_i64];
+        param_values.extend(
+            namespaces
+                .iter()
+                .map(|ns| ns as &dyn rusqlite::types::ToSql),
+        );
 
-        let rows = stmt.query_map(params![fts_query, limit_i64], |row| {
+        let rows = stmt.query_map(param_values.as_slice(), |row| {
             let id: String = row.get(0)?;
             let score: f64 = row.get(1)?;
             // BM25 returns negative scores (lower = better), negate for ranking
@@ -337,10 +594,17 @@ impl SqliteMemory {
         limit: usize,
         category: Option<&str>,
         session_id: Option<&str>,
+        namespaces: &[String],
     ) -> anyhow::Result<Vec<(String, f32)>> {
-        let mut sql = "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL".to_string();
-        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-        let mut idx = 1;
+        let mut sql = format!(
+            "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL AND {}",
+            Self::namespace_clause("namespace", namespaces, 1)
+        );
+        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = namespaces
+            .iter()
+            .map(|ns| Box::new(ns.clone()) as Box<dyn rusqlite::types::ToSql>)
+            .collect();
+        let mut idx = namespaces.len() + 1;
 
         if let Some(cat) = category {
             let _ = write!(sql, " AND category = ?{idx}");
@@ -376,108 +640,273 @@ impl SqliteMemory {
         Ok(scored)
     }
 
-    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
-    pub async fn reindex(&self) -> anyhow::Result<usize> {
-        // Step 1: Rebuild FTS5
-        {
-            let conn = self.conn.clone();
-            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                let conn = conn.lock();
-                conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
-                Ok(())
-            })
-            .await??;
+    /// Vector search through the HNSW index.
+    ///
+    /// The index spans every namespace and session, so queries oversample and
+    /// filter by scope; if too few candidates survive, fall back to the exact
+    /// scan.
+    fn ann_search(
+        conn: &Connection,
+        index: &HnswIndex,
+        query_embedding: &[f32],
+        limit: usize,
+        session_id: Option<&str>,
+        namespaces: &[String],
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let fetch = limit.saturating_mul(ANN_SCOPE_OVERSAMPLE);
+        let hits = index.search(query_embedding, fetch);
+        let in_scope = Self::ids_in_scope(conn, &hits, session_id, namespaces)?;
+        let scoped: Vec<(String, f32)> = hits
+            .into_iter()
+            .filter(|(id, sim)| *sim > 0.0 && in_scope.contains(id))
+            .take(limit)
+            .collect();
+
+        if scoped.len() < limit && index.len() > fetch {
+            return Self::vector_search(conn, query_embedding, limit, None, session_id, namespaces);
         }
+        Ok(scoped)
+    }
 
-        // Step 2: Re-embed all memories that lack embeddings
-        if self.embedder.dimensions() == 0 {
-            return Ok(0);
+    /// Subset of `hits` whose memory lies in `namespaces` (and `session_id`, if set).
+    fn ids_in_scope(
+        conn: &Connection,
+        hits: &[(String, f32)],
+        session_id: Option<&str>,
+        namespaces: &[String],
+    ) -> anyhow::Result<std::collections::HashSet<String>> {
+        if hits.is_empty() {
+            return Ok(std::collections::HashSet::new());
         }
+        let first_id = namespaces.len() + 2;
+        let placeholders: String = (first_id..first_id + hits.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT id FROM memories
+             WHERE (?1 IS NULL OR session_id = ?1) AND {} AND id IN ({placeholders})",
+            Self::namespace_clause("namespace", namespaces, 2)
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let mut param_values: Vec<&dyn rusqlite::types::ToSql> = vec![&session_id];
+        param_values.extend(
+            namespaces
+                .iter()
+                .map(|ns| ns as &dyn rusqlite::types::ToSql),
+        );
+        param_values.extend(hits.iter().map(|(id, _)| id as &dyn rusqlite::types::ToSql));
+        let rows = stmt.query_map(param_values.as_slice(), |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<_, _>>().map_err(Into::into)
+    }
 
-        let conn = self.conn.clone();
-        let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
-            let conn = conn.lock();
-            let mut stmt =
-                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
-            let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?;
-            Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
-        })
-        .await??;
+    /// All stored embeddings with the expected dimensions, keyed by memory id.
+    fn embedding_rows(
+        conn: &Connection,
+        dims: usize,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<f32>>> {
+        let mut stmt =
+            conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
 
-        let mut count = 0;
-        for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
-                let bytes = vector::vec_to_bytes(&emb);
-                let conn = self.conn.clone();
-                let id = id.clone();
-                tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                    let conn = conn.lock();
-                    conn.execute(
-                        "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-                        params![bytes, id],
-                    )?;
-                    Ok(())
-                })
-                .await??;
-                count += 1;
+        let mut vectors = std::collections::HashMap::new();
+        for row in rows {
+            let (id, blob) = row?;
+            let emb = vector::bytes_to_vec(&blob);
+            if emb.len() == dims {
+                vectors.insert(id, emb);
             }
         }
+        Ok(vectors)
+    }
 
-        Ok(count)
+    /// Build a fresh index over the current embeddings and persist it.
+    fn build_ann_index(conn: &Connection, path: &Path, dims: usize) -> anyhow::Result<HnswIndex> {
+        let vectors = Self::embedding_rows(conn, dims)?;
+        let index = HnswIndex::build(
+            dims,
+            vectors
+                .iter()
+                .map(|(id, emb)| (id.as_str(), emb.as_slice())),
+        );
+        if let Err(e) = index.save(path) {
+            tracing::warn!("failed to save memory ANN index: {e}");
+        }
+        Ok(index)
     }
-}
 
-#[async_trait]
-impl Memory for SqliteMemory {
-    fn name(&self) -> &str {
-        "sqlite"
+    /// Load `brain.hnsw` and reconcile it with the database, rebuilding it
+    /// when the file is missing or unusable.
+    fn load_ann_index(conn: &Connection, path: &Path, dims: usize) -> anyhow::Result<HnswIndex> {
+        if !path.exists() {
+            return Self::build_ann_index(conn, path, dims);
+        }
+        let vectors = Self::embedding_rows(conn, dims)?;
+        match HnswIndex::load(path, dims, &vectors) {
+            Ok((index, changed)) => {
+                if changed {
+                    if let Err(e) = index.save(path) {
+                        tracing::warn!("failed to save memory ANN index: {e}");
+                    }
+                }
+                Ok(index)
+            }
+            Err(e) => {
+                tracing::warn!("rebuilding memory ANN index: {e}");
+                Self::build_ann_index(conn, path, dims)
+            }
+        }
     }
 
-    async fn store(
+    /// The ANN index, loading it on first use. `None` when disabled or
+    /// when it cannot be built (recall then uses the brute-force scan).
+    fn ann_index<'a>(
+        conn: &Connection,
+        state: &'a mut AnnState,
+        path: &Path,
+        dims: usize,
+    ) -> Option<&'a HnswIndex> {
+        if matches!(state, AnnState::Unloaded) {
+            *state = match Self::load_ann_index(conn, path, dims) {
+                Ok(index) => AnnState::Ready { index, unsaved: 0 },
+                Err(e) => {
+                    tracing::warn!("memory ANN index unavailable, using brute-force scan: {e}");
+                    AnnState::Disabled
+                }
+            };
+        }
+        match state {
+            AnnState::Ready { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Apply an incremental change to the ANN index, persisting it every
+    /// `ANN_SAVE_EVERY` changes. `update` returns whether anything changed.
+    fn ann_update(
+        conn: &Connection,
+        state: &mut AnnState,
+        path: &Path,
+        dims: usize,
+        update: impl FnOnce(&mut HnswIndex) -> bool,
+    ) {
+        Self::ann_index(conn, state, path, dims);
+        let AnnState::Ready { index, unsaved } = state else {
+            return;
+        };
+        if !update(index) {
+            return;
+        }
+        *unsaved += 1;
+        if *unsaved >= ANN_SAVE_EVERY {
+            match index.save(path) {
+                Ok(()) => *unsaved = 0,
+                Err(e) => tracing::warn!("failed to save memory ANN index: {e}"),
+            }
+        }
+    }
+
+    /// Insert or update a row and keep the ANN index in sync.
+    ///
+    /// With `timestamp` (imports) both `created_at` and `updated_at` take that
+    /// value and `access_count` is restored from `metadata`; otherwise
+    /// `created_at` and `access_count` of an existing row are preserved.
+    /// `metadata: None` keeps the existing row's metadata.
+    #[allow(clippy::too_many_arguments)]
+    async fn upsert(
         &self,
+        namespace: &str,
         key: &str,
         content: &str,
         category: MemoryCategory,
         session_id: Option<&str>,
+        embedding: Option<Vec<f32>>,
+        metadata: Option<&MemoryMetadata>,
+        timestamp: Option<String>,
     ) -> anyhow::Result<()> {
-        // Compute embedding (async, before blocking work)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
+        let replace_metadata = metadata.is_some();
+        #[allow(clippy::cast_possible_wrap)]
+        let access_count = metadata.map_or(0, |m| m.access_count as i64);
+        let metadata_json = metadata
+            .map(MemoryMetadata::without_access_count)
+            .filter(|m| !m.is_empty())
+            .map(|m| serde_json::to_string(&m))
+            .transpose()?;
 
         let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        let ann_path = self.ann_path.clone();
+        let dims = self.embedder.dimensions();
+        let namespace = namespace.to_string();
         let key = key.to_string();
-        let content = content.to_string();
+        let cipher = self.cipher.as_deref();
+        let search_terms = cipher.and_then(|c| c.search_terms(content));
+        let content = encryption::encrypt_field(cipher, content)?;
         let sid = session_id.map(String::from);
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
             let conn = conn.lock();
             let now = Local::now().to_rfc3339();
+            let created_at = timestamp.as_deref().unwrap_or(&now);
             let cat = Self::category_to_str(&category);
             let id = Uuid::new_v4().to_string();
 
             conn.execute(
-                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                 ON CONFLICT(key) DO UPDATE SET
+                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id, metadata, access_count, namespace, search_terms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?13, ?14)
+                 ON CONFLICT(namespace, key) DO UPDATE SET
                     content = excluded.content,
+                    search_terms = excluded.search_terms,
                     category = excluded.category,
                     embedding = excluded.embedding,
+                    created_at = COALESCE(?11, created_at),
                     updated_at = excluded.updated_at,
-                    session_id = excluded.session_id",
-                params![id, key, content, cat, embedding_bytes, now, now, sid],
+                    session_id = excluded.session_id,
+                    metadata = CASE WHEN ?12 THEN excluded.metadata ELSE metadata END,
+                    access_count = CASE WHEN ?11 IS NULL THEN access_count ELSE excluded.access_count END",
+                params![
+                    id,
+                    key,
+                    content,
+                    cat,
+                    embedding_bytes,
+                    created_at,
+                    created_at,
+                    sid,
+                    metadata_json,
+                    access_count,
+                    timestamp,
+                    replace_metadata,
+                    namespace,
+                    search_terms
+                ],
+            )?;
+
+            // Upserts keep the original id, so look up the row that was written.
+            let id: String = conn.query_row(
+                "SELECT id FROM memories WHERE namespace = ?1 AND key = ?2",
+                params![namespace, key],
+                |row| row.get(0),
             )?;
+            Self::ann_update(&conn, &mut ann.lock(), &ann_path, dims, |index| {
+                let removed = index.remove(&id);
+                let inserted = embedding
+                    .as_deref()
+                    .is_some_and(|emb| index.insert(&id, emb));
+                removed || inserted
+            });
             Ok(())
         })
         .await?
     }
 
-    async fn recall(
+    /// Hybrid recall restricted to `namespaces`.
+    async fn recall_scoped(
         &self,
+        namespaces: Vec<String>,
         query: &str,
         limit: usize,
         session_id: Option<&str>,
@@ -487,24 +916,41 @@ impl Memory for SqliteMemory {
         }
 
         // Compute query embedding (async, before blocking work)
-        let query_embedding = self.get_or_compute_embedding(query).await?;
+        let query_embedding = self.get_or_compute_query_embedding(query).await?;
 
         let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        let ann_path = self.ann_path.clone();
+        let dims = self.embedder.dimensions();
         let query = query.to_string();
         let sid = session_id.map(String::from);
         let vector_weight = self.vector_weight;
         let keyword_weight = self.keyword_weight;
+        let weights = self.weights;
+        let cipher = self.cipher.clone();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
             let conn = conn.lock();
             let session_ref = sid.as_deref();
 
             // FTS5 BM25 keyword search
-            let keyword_results = Self::fts5_search(&conn, &query, limit * 2).unwrap_or_default();
+            let keyword_results =
+                Self::fts5_search(&conn, &query, limit * 2, &namespaces, cipher.as_deref())
+                    .unwrap_or_default();
 
-            // Vector similarity search (if embeddings available)
+            // Vector similarity search (if embeddings available):
+            // HNSW index when enabled, exact scan otherwise
             let vector_results = if let Some(ref qe) = query_embedding {
-                Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                let mut ann = ann.lock();
+                match Self::ann_index(&conn, &mut ann, &ann_path, dims) {
+                    Some(index) => {
+                        Self::ann_search(&conn, index, qe, limit * 2, session_ref, &namespaces)
+                    }
+                    None => {
+                        Self::vector_search(&conn, qe, limit * 2, None, session_ref, &namespaces)
+                    }
+                }
+                .unwrap_or_default()
             } else {
                 Vec::new()
             };
@@ -521,12 +967,13 @@ impl Memory for SqliteMemory {
                     })
                     .collect::<Vec<_>>()
             } else {
+                // Keep spare candidates for expiry filtering and re-ranking.
                 vector::hybrid_merge(
                     &vector_results,
                     &keyword_results,
                     vector_weight,
                     keyword_weight,
-                    limit,
+                    limit * 2,
                 )
             };
 
@@ -538,10 +985,8 @@ impl Memory for SqliteMemory {
                     .map(|i| format!("?{i}"))
                     .collect::<Vec<_>>()
                     .join(", ");
-                let sql = format!(
-                    "SELECT id, key, content, category, created_at, session_id \
-                     FROM memories WHERE id IN ({placeholders})"
-                );
+                let sql =
+                    format!("SELECT {ENTRY_COLUMNS} FROM memories WHERE id IN ({placeholders})");
                 let mut stmt = conn.prepare(&sql)?;
                 let id_params: Vec<Box<dyn rusqlite::types::ToSql>> = merged
                     .iter()
@@ -549,34 +994,20 @@ impl Memory for SqliteMemory {
                     .collect();
                 let params_ref: Vec<&dyn rusqlite::types::ToSql> =
                     id_params.iter().map(AsRef::as_ref).collect();
-                let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, String>(1)?,
-                        row.get::<_, String>(2)?,
-                        row.get::<_, String>(3)?,
-                        row.get::<_, String>(4)?,
-                        row.get::<_, Option<String>>(5)?,
-                    ))
-                })?;
+                let rows = stmt.query_map(params_ref.as_slice(), Self::row_to_entry)?;
 
                 let mut entry_map = std::collections::HashMap::new();
                 for row in rows {
-                    let (id, key, content, cat, ts, sid) = row?;
-                    entry_map.insert(id, (key, content, cat, ts, sid));
+                    let entry = row?;
+                    entry_map.insert(entry.id.clone(), entry);
                 }
 
                 for scored in &merged {
-                    if let Some((key, content, cat, ts, sid)) = entry_map.remove(&scored.id) {
-                        let entry = MemoryEntry {
-                            id: scored.id.clone(),
-                            key,
-                            content,
-                            category: Self::str_to_category(&cat),
-                            timestamp: ts,
-                            session_id: sid,
-                            score: Some(f64::from(scored.final_score)),
-                        };
+                    if let Some(mut entry) = entry_map.remove(&scored.id) {
+                        entry.score = Some(f64::from(scored.final_score));
+                        if !namespaces.contains(&entry.namespace) {
+                            continue;
+                        }
                         if let Some(filter_sid) = session_ref {
                             if entry.session_id.as_deref() != Some(filter_sid) {
                                 continue;
@@ -607,10 +1038,11 @@ impl Memory for SqliteMemory {
                         .collect();
                     let where_clause = conditions.join(" OR ");
                     let sql = format!(
-                        "SELECT id, key, content, category, created_at, session_id FROM memories
-                         WHERE {where_clause}
+                        "SELECT {ENTRY_COLUMNS} FROM memories
+                         WHERE ({where_clause}) AND {}
                          ORDER BY updated_at DESC
                          LIMIT ?{}",
+                        Self::namespace_clause("namespace", &namespaces, keywords.len() * 2 + 2),
                         keywords.len() * 2 + 1
                     );
                     let mut stmt = conn.prepare(&sql)?;
@@ -621,18 +1053,15 @@ impl Memory for SqliteMemory {
                     }
                     #[allow(clippy::cast_possible_wrap)]
                     param_values.push(Box::new(limit as i64));
+                    for ns in &namespaces {
+                        param_values.push(Box::new(ns.clone()));
+                    }
                     let params_ref: Vec<&dyn rusqlite::types::ToSql> =
                         param_values.iter().map(AsRef::as_ref).collect();
                     let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                        Ok(MemoryEntry {
-                            id: row.get(0)?,
-                            key: row.get(1)?,
-                            content: row.get(2)?,
-                            category: Self::str_to_category(&row.get::<_, String>(3)?),
-                            timestamp: row.get(4)?,
-                            session_id: row.get(5)?,
-                            score: Some(1.0),
-                        })
+                        let mut entry = Self::row_to_entry(row)?;
+                        entry.score = Some(1.0);
+                        Ok(entry)
                     })?;
                     for row in rows {
                         let entry = row?;
@@ -646,36 +1075,271 @@ impl Memory for SqliteMemory {
                 }
             }
 
+            scoring::rerank(&mut results, &weights, Utc::now());
             results.truncate(limit);
+            Self::decrypt_entries(cipher.as_deref(), &mut results)?;
+            Self::record_access(&conn, &mut results)?;
             Ok(results)
         })
         .await?
     }
 
+    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
+    #[allow(dead_code)]
+    pub async fn reindex(&self) -> anyhow::Result<usize> {
+        // Step 1: Rebuild FTS5
+        {
+            let conn = self.conn.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                let conn = conn.lock();
+                conn.execute_batch(FTS_REBUILD)?;
+                Ok(())
+            })
+            .await??;
+        }
+
+        // Step 2: Re-embed all memories that lack embeddings
+        if self.embedder.dimensions() == 0 {
+            return Ok(0);
+        }
+
+        let conn = self.conn.clone();
+        let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
+            let conn = conn.lock();
+            let mut stmt =
+                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
+        })
+        .await??;
+
+        let mut count = 0;
+        for (id, content) in &entries {
+            let content = encryption::decrypt_field(self.cipher.as_deref(), content)?;
+            if let Ok(Some(emb)) = self.get_or_compute_embedding(&content).await {
+                let bytes = vector::vec_to_bytes(&emb);
+                let conn = self.conn.clone();
+                let id = id.clone();
+                tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+                    let conn = conn.lock();
+                    conn.execute(
+                        "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                        params![bytes, id],
+                    )?;
+                    Ok(())
+                })
+                .await??;
+                count += 1;
+            }
+        }
+
+        // Step 3: Rebuild the ANN index from scratch
+        let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        let ann_path = self.ann_path.clone();
+        let dims = self.embedder.dimensions();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let conn = conn.lock();
+            let mut ann = ann.lock();
+            if !matches!(*ann, AnnState::Disabled) {
+                let index = Self::build_ann_index(&conn, &ann_path, dims)?;
+                *ann = AnnState::Ready { index, unsaved: 0 };
+            }
+            Ok(())
+        })
+        .await??;
+
+        Ok(count)
+    }
+
+    /// Rewrite every row's content under the cipher's active key, or as
+    /// plaintext when the cipher is decrypt-only or absent. Runs in a single
+    /// transaction; returns the number of rows rewritten.
+    pub async fn rekey(&self) -> anyhow::Result<usize> {
+        let conn = self.conn.clone();
+        let cipher = self.cipher.clone();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+            let mut conn = conn.lock();
+            let cipher = cipher.as_deref();
+            let tx = conn.transaction()?;
+            let rows: Vec<(String, String, String)> = {
+                let mut stmt = tx.prepare("SELECT id, key, content FROM memories")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+            {
+                let mut update = tx
+                    .prepare("UPDATE memories SET content = ?1, search_terms = ?2 WHERE id = ?3")?;
+                for (id, key, stored) in &rows {
+                    let plaintext = encryption::decrypt_field(cipher, stored)
+                        .with_context(|| format!("failed to decrypt memory '{key}'"))?;
+                    let search_terms = cipher.and_then(|c| c.search_terms(&plaintext));
+                    let content = encryption::encrypt_field(cipher, &plaintext)?;
+                    update.execute(params![content, search_terms, id])?;
+                }
+            }
+            tx.commit()?;
+            Ok(rows.len())
+        })
+        .await?
+    }
+}
+
+impl Drop for SqliteMemory {
+    fn drop(&mut self) {
+        if let AnnState::Ready { index, unsaved } = &*self.ann.lock() {
+            if *unsaved > 0 {
+                if let Err(e) = index.save(&self.ann_path) {
+                    tracing::warn!("failed to save memory ANN index: {e}");
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Memory for SqliteMemory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn supports_namespaces(&self) -> bool {
+        true
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        // Compute embedding (async, before blocking work)
+        let embedding = self.get_or_compute_embedding(content).await?;
+        self.upsert(
+            DEFAULT_NAMESPACE,
+            key,
+            content,
+            category,
+            session_id,
+            embedding,
+            None,
+            None,
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.store_in(
+            DEFAULT_NAMESPACE,
+            key,
+            content,
+            category,
+            session_id,
+            metadata,
+        )
+        .await
+    }
+
+    async fn store_in(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        let embedding = self.get_or_compute_embedding(content).await?;
+        self.upsert(
+            namespace,
+            key,
+            content,
+            category,
+            session_id,
+            embedding,
+            Some(metadata),
+            None,
+        )
+        .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_scoped(
+            vec![DEFAULT_NAMESPACE.to_string()],
+            query,
+            limit,
+            session_id,
+        )
+        .await
+    }
+
+    async fn recall_in(
+        &self,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if namespaces.is_empty() {
+            return Ok(Vec::new());
+        }
+        if tags.is_empty() {
+            return self
+                .recall_scoped(namespaces.to_vec(), query, limit, session_id)
+                .await;
+        }
+        let mut entries = self
+            .recall_scoped(
+                namespaces.to_vec(),
+                query,
+                limit.saturating_mul(TAG_RECALL_OVERSAMPLE),
+                session_id,
+            )
+            .await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.get_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn get_in(&self, namespace: &str, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let conn = self.conn.clone();
+        let namespace = namespace.to_string();
         let key = key.to_string();
+        let cipher = self.cipher.clone();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<Option<MemoryEntry>> {
             let conn = conn.lock();
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories WHERE key = ?1",
-            )?;
-
-            let mut rows = stmt.query_map(params![key], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: None,
-                })
-            })?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories WHERE namespace = ?1 AND key = ?2"
+            ))?;
+            let mut rows = stmt.query_map(params![namespace, key], Self::row_to_entry)?;
 
             match rows.next() {
-                Some(Ok(entry)) => Ok(Some(entry)),
+                Some(Ok(mut entry)) => {
+                    Self::decrypt_entries(cipher.as_deref(), std::slice::from_mut(&mut entry))?;
+                    Ok(Some(entry))
+                }
                 _ => Ok(None),
             }
         })
@@ -686,75 +1350,84 @@ impl Memory for SqliteMemory {
         &self,
         category: Option<&MemoryCategory>,
         session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.list_in(&[DEFAULT_NAMESPACE.to_string()], category, session_id)
+            .await
+    }
+
+    async fn list_in(
+        &self,
+        namespaces: &[String],
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
         const DEFAULT_LIST_LIMIT: i64 = 1000;
 
+        if namespaces.is_empty() {
+            return Ok(Vec::new());
+        }
+
         let conn = self.conn.clone();
-        let category = category.cloned();
+        let category = category.map(Self::category_to_str);
         let sid = session_id.map(String::from);
+        let namespaces = namespaces.to_vec();
+        let cipher = self.cipher.clone();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
             let conn = conn.lock();
-            let session_ref = sid.as_deref();
-            let mut results = Vec::new();
-
-            let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: None,
-                })
-            };
-
-            if let Some(ref cat) = category {
-                let cat_str = Self::category_to_str(cat);
-                let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
-                     WHERE category = ?1 ORDER BY updated_at DESC LIMIT ?2",
-                )?;
-                let rows = stmt.query_map(params![cat_str, DEFAULT_LIST_LIMIT], row_mapper)?;
-                for row in rows {
-                    let entry = row?;
-                    if let Some(sid) = session_ref {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
-                        }
-                    }
-                    results.push(entry);
-                }
-            } else {
-                let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
-                     ORDER BY updated_at DESC LIMIT ?1",
-                )?;
-                let rows = stmt.query_map(params![DEFAULT_LIST_LIMIT], row_mapper)?;
-                for row in rows {
-                    let entry = row?;
-                    if let Some(sid) = session_ref {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
-                        }
-                    }
-                    results.push(entry);
-                }
-            }
-
-            Ok(results)
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE (?1 IS NULL OR category = ?1)
+                   AND (?2 IS NULL OR session_id = ?2)
+                   AND {}
+                 ORDER BY updated_at DESC LIMIT ?3",
+                Self::namespace_clause("namespace", &namespaces, 4)
+            ))?;
+            let mut param_values: Vec<&dyn rusqlite::types::ToSql> =
+                vec![&category, &sid, &DEFAULT_LIST_LIMIT];
+            param_values.extend(
+                namespaces
+                    .iter()
+                    .map(|ns| ns as &dyn rusqlite::types::ToSql),
+            );
+            let rows = stmt.query_map(param_values.as_slice(), Self::row_to_entry)?;
+            let mut entries = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+            Self::decrypt_entries(cipher.as_deref(), &mut entries)?;
+            Ok(entries)
         })
         .await?
     }
 
     async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        self.forget_in(DEFAULT_NAMESPACE, key).await
+    }
+
+    async fn forget_in(&self, namespace: &str, key: &str) -> anyhow::Result<bool> {
         let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        let ann_path = self.ann_path.clone();
+        let dims = self.embedder.dimensions();
+        let namespace = namespace.to_string();
         let key = key.to_string();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
             let conn = conn.lock();
-            let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
+            let id: Option<String> = conn
+                .query_row(
+                    "SELECT id FROM memories WHERE namespace = ?1 AND key = ?2",
+                    params![namespace, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let affected = conn.execute(
+                "DELETE FROM memories WHERE namespace = ?1 AND key = ?2",
+                params![namespace, key],
+            )?;
+            if let Some(id) = id {
+                Self::ann_update(&conn, &mut ann.lock(), &ann_path, dims, |index| {
+                    index.remove(&id)
+                });
+            }
             Ok(affected > 0)
         })
         .await?
@@ -779,6 +1452,68 @@ impl Memory for SqliteMemory {
             .await
             .unwrap_or(false)
     }
+
+    async fn export_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        let conn = self.conn.clone();
+        let cipher = self.cipher.clone();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
+            let conn = conn.lock();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories ORDER BY created_at, key"
+            ))?;
+            let rows = stmt.query_map([], Self::row_to_entry)?;
+            let mut entries = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+            Self::decrypt_entries(cipher.as_deref(), &mut entries)?;
+            Ok(entries)
+        })
+        .await?
+    }
+
+    async fn embedding(&self, namespace: &str, key: &str) -> anyhow::Result<Option<Vec<f32>>> {
+        let conn = self.conn.clone();
+        let namespace = namespace.to_string();
+        let key = key.to_string();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<Option<Vec<f32>>> {
+            let conn = conn.lock();
+            let bytes: Option<Option<Vec<u8>>> = conn
+                .query_row(
+                    "SELECT embedding FROM memories WHERE namespace = ?1 AND key = ?2",
+                    params![namespace, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(bytes
+                .flatten()
+                .map(|bytes| vector::bytes_to_vec(&bytes))
+                .filter(|emb| !emb.is_empty()))
+        })
+        .await?
+    }
+
+    async fn import_entry(
+        &self,
+        entry: &MemoryEntry,
+        embedding: Option<&[f32]>,
+    ) -> anyhow::Result<()> {
+        let dims = self.embedder.dimensions();
+        let embedding = match embedding {
+            Some(emb) if dims > 0 && emb.len() == dims => Some(emb.to_vec()),
+            _ => self.get_or_compute_embedding(&entry.content).await?,
+        };
+        self.upsert(
+            &entry.namespace,
+            &entry.key,
+            &entry.content,
+            entry.category.clone(),
+            entry.session_id.as_deref(),
+            embedding,
+            Some(&entry.metadata),
+            Some(entry.timestamp.clone()),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -1785,6 +2520,105 @@ mod tests {
         }
     }
 
+    // ── Namespaces ────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn namespaces_keep_same_key_apart() {
+        let (_tmp, mem) = temp_sqlite();
+        let meta = MemoryMetadata::default();
+        mem.store("lang", "Rust is the default", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store_in(
+            "slack:user:U1",
+            "lang",
+            "U1 prefers Rust",
+            MemoryCategory::Core,
+            None,
+            &meta,
+        )
+        .await
+        .unwrap();
+        mem.store_in(
+            "slack:user:U2",
+            "lang",
+            "U2 prefers Go",
+            MemoryCategory::Core,
+            None,
+            &meta,
+        )
+        .await
+        .unwrap();
+        assert_eq!(mem.count().await.unwrap(), 3);
+
+        let u1 = vec!["slack:user:U1".to_string(), DEFAULT_NAMESPACE.to_string()];
+        let results = mem.recall_in(&u1, "prefers", 10, None, &[]).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "U1 prefers Rust");
+        assert_eq!(results[0].namespace, "slack:user:U1");
+
+        let global = mem.recall("prefers", 10, None).await.unwrap();
+        assert!(global.is_empty());
+        assert_eq!(
+            mem.get("lang").await.unwrap().unwrap().content,
+            "Rust is the default"
+        );
+        assert_eq!(mem.list_in(&u1, None, None).await.unwrap().len(), 2);
+
+        assert!(!mem.forget_in("slack:user:U1", "missing").await.unwrap());
+        assert!(mem.forget_in("slack:user:U2", "lang").await.unwrap());
+        assert!(mem.get_in("slack:user:U2", "lang").await.unwrap().is_none());
+        assert!(mem.get_in("slack:user:U1", "lang").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn namespace_migration_keeps_legacy_rows_searchable() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("memory").join("brain.db");
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE memories (
+                    id TEXT PRIMARY KEY,
+                    key TEXT NOT NULL UNIQUE,
+                    content TEXT NOT NULL,
+                    category TEXT NOT NULL DEFAULT 'core',
+                    embedding BLOB,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                CREATE VIRTUAL TABLE memories_fts USING fts5(
+                    key, content, content=memories, content_rowid=rowid
+                );
+                INSERT INTO memories VALUES
+                    ('id-1', 'legacy', 'pre-namespace fact', 'core', NULL, '2026-01-01', '2026-01-01');
+                INSERT INTO memories_fts(rowid, key, content)
+                    SELECT rowid, key, content FROM memories;",
+            )
+            .unwrap();
+        }
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let entry = mem.get("legacy").await.unwrap().unwrap();
+        assert_eq!(entry.namespace, DEFAULT_NAMESPACE);
+        let results = mem.recall("namespace", 5, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "legacy");
+
+        mem.store_in(
+            "cli:user:me",
+            "legacy",
+            "scoped copy",
+            MemoryCategory::Core,
+            None,
+            &MemoryMetadata::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(mem.count().await.unwrap(), 2);
+    }
+
     // ── §4.1 Concurrent write contention tests ──────────────
 
     #[tokio::test]
@@ -1897,4 +2731,304 @@ mod tests {
 
         assert_eq!(mem.count().await.unwrap(), 1);
     }
+
+    // ── ANN index ────────────────────────────────────────────────
+
+    /// Deterministic bag-of-words embedder so vector recall is testable offline.
+    struct HashEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for HashEmbedding {
+        fn name(&self) -> &str {
+            "hash"
+        }
+
+        fn dimensions(&self) -> usize {
+            32
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut emb = vec![0.0; 32];
+                    for word in text.split_whitespace() {
+                        let bucket = word
+                            .bytes()
+                            .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b.into()));
+                        emb[bucket % 32] += 1.0;
+                    }
+                    emb
+                })
+                .collect())
+        }
+    }
+
+    fn embedded_sqlite(dir: &Path, ann_enabled: bool) -> SqliteMemory {
+        SqliteMemory::with_embedder(dir, Arc::new(HashEmbedding), 0.7, 0.3, 100, None)
+            .unwrap()
+            .with_ann_index(ann_enabled)
+    }
+
+    fn ann_len(mem: &SqliteMemory) -> Option<usize> {
+        match &*mem.ann.lock() {
+            AnnState::Ready { index, .. } => Some(index.len()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn ann_recall_matches_brute_force() {
+        let tmp = TempDir::new().unwrap();
+        let ann = embedded_sqlite(&tmp.path().join("ann"), true);
+        let exact = embedded_sqlite(&tmp.path().join("exact"), false);
+        for (key, content) in [
+            ("lang", "user prefers rust for systems work"),
+            ("pet", "user has a cat named miso"),
+            ("tz", "user lives in berlin timezone"),
+            ("editor", "user edits rust in helix"),
+        ] {
+            ann.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            exact
+                .store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let from_ann = ann.recall("rust systems", 3, None).await.unwrap();
+        let from_exact = exact.recall("rust systems", 3, None).await.unwrap();
+        let keys =
+            |entries: &[MemoryEntry]| entries.iter().map(|e| e.key.clone()).collect::<Vec<_>>();
+        assert_eq!(keys(&from_ann), keys(&from_exact));
+        assert_eq!(from_ann[0].key, "lang");
+        assert_eq!(ann_len(&ann), Some(4));
+        assert_eq!(ann_len(&exact), None);
+    }
+
+    #[tokio::test]
+    async fn ann_index_tracks_store_and_forget() {
+        let tmp = TempDir::new().unwrap();
+        let mem = embedded_sqlite(tmp.path(), true);
+        mem.store("a", "alpha bravo", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "charlie delta", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(ann_len(&mem), Some(2));
+
+        // Upsert keeps a single node for the key
+        mem.store("a", "echo foxtrot", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(ann_len(&mem), Some(2));
+        let results = mem.recall("echo", 5, None).await.unwrap();
+        assert_eq!(results[0].key, "a");
+
+        assert!(mem.forget("a").await.unwrap());
+        assert_eq!(ann_len(&mem), Some(1));
+        let results = mem.recall("echo foxtrot", 5, None).await.unwrap();
+        assert!(results.iter().all(|e| e.key != "a"));
+    }
+
+    #[tokio::test]
+    async fn ann_recall_respects_session_filter() {
+        let tmp = TempDir::new().unwrap();
+        let mem = embedded_sqlite(tmp.path(), true);
+        mem.store(
+            "s1",
+            "shared topic one",
+            MemoryCategory::Core,
+            Some("sess-a"),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "s2",
+            "shared topic two",
+            MemoryCategory::Core,
+            Some("sess-b"),
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("shared topic", 5, Some("sess-b")).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "s2");
+    }
+
+    #[tokio::test]
+    async fn ann_index_persists_and_reconciles_on_reopen() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let mem = embedded_sqlite(tmp.path(), true);
+            mem.store("a", "alpha bravo", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            mem.store("b", "charlie delta", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let index_path = tmp.path().join("memory").join("brain.hnsw");
+        assert!(index_path.exists(), "index saved on drop");
+
+        // A row deleted behind the index's back is dropped on load
+        {
+            let conn = Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+            conn.execute("DELETE FROM memories WHERE key = 'b'", [])
+                .unwrap();
+        }
+
+        let mem = embedded_sqlite(tmp.path(), true);
+        let results = mem.recall("alpha", 5, None).await.unwrap();
+        assert_eq!(results[0].key, "a");
+        assert_eq!(ann_len(&mem), Some(1));
+    }
+
+    #[tokio::test]
+    async fn ann_index_rebuilt_when_file_corrupt() {
+        let tmp = TempDir::new().unwrap();
+        let index_path = tmp.path().join("memory").join("brain.hnsw");
+        {
+            let mem = embedded_sqlite(tmp.path(), true);
+            mem.store("a", "alpha bravo", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        std::fs::write(&index_path, b"not an index").unwrap();
+
+        let mem = embedded_sqlite(tmp.path(), true);
+        let results = mem.recall("alpha", 5, None).await.unwrap();
+        assert_eq!(results[0].key, "a");
+        assert_eq!(ann_len(&mem), Some(1));
+    }
+
+    #[tokio::test]
+    async fn reindex_rebuilds_ann_index() {
+        let tmp = TempDir::new().unwrap();
+        let mem = embedded_sqlite(tmp.path(), true);
+        mem.store("a", "alpha bravo", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "charlie delta", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let index_path = tmp.path().join("memory").join("brain.hnsw");
+        let _ = std::fs::remove_file(&index_path);
+
+        mem.reindex().await.unwrap();
+        assert!(index_path.exists());
+        assert_eq!(ann_len(&mem), Some(2));
+    }
+
+    #[tokio::test]
+    async fn ann_disabled_skips_index_file() {
+        let tmp = TempDir::new().unwrap();
+        let mem = embedded_sqlite(tmp.path(), false);
+        mem.store("a", "alpha bravo", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let results = mem.recall("alpha", 5, None).await.unwrap();
+        assert_eq!(results[0].key, "a");
+        drop(mem);
+        assert!(!tmp.path().join("memory").join("brain.hnsw").exists());
+    }
+
+    #[tokio::test]
+    async fn metadata_persists_and_plain_store_keeps_it() {
+        let (_tmp, mem) = temp_sqlite();
+        let metadata = MemoryMetadata {
+            tags: vec!["infra".into()],
+            source: Some("runbook".into()),
+            importance: Some(0.8),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "db",
+            "Postgres on 5433",
+            MemoryCategory::Core,
+            None,
+            &metadata,
+        )
+        .await
+        .unwrap();
+        mem.store("db", "Postgres on 5434", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let entry = mem.get("db").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Postgres on 5434");
+        assert_eq!(entry.metadata, metadata);
+    }
+
+    #[tokio::test]
+    async fn recall_counts_access_and_skips_expired() {
+        let (_tmp, mem) = temp_sqlite();
+        let expired = MemoryMetadata {
+            expires_at: Some("2000-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store(
+            "live",
+            "deploy window is friday",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "old",
+            "deploy window is monday",
+            MemoryCategory::Core,
+            None,
+            &expired,
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("deploy window", 10, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "live");
+        assert_eq!(results[0].metadata.access_count, 1);
+
+        mem.recall("deploy window", 10, None).await.unwrap();
+        let entry = mem.get("live").await.unwrap().unwrap();
+        assert_eq!(entry.metadata.access_count, 2);
+    }
+
+    #[tokio::test]
+    async fn recall_prefers_important_entries() {
+        let (_tmp, mem) = temp_sqlite();
+        let important = MemoryMetadata {
+            importance: Some(1.0),
+            ..MemoryMetadata::default()
+        };
+        let trivial = MemoryMetadata {
+            importance: Some(0.0),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "a",
+            "coffee order oat latte",
+            MemoryCategory::Core,
+            None,
+            &trivial,
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "b",
+            "coffee order flat white",
+            MemoryCategory::Core,
+            None,
+            &important,
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("coffee order", 10, None).await.unwrap();
+        assert_eq!(results[0].key, "b");
+    }
 }
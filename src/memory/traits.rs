@@ -1,6 +1,17 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Importance assumed for entries that do not set one.
+pub const DEFAULT_IMPORTANCE: f64 = 0.5;
+
+/// Namespace of entries stored without one (CLI, gateway, pre-namespace rows).
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+fn default_namespace() -> String {
+    DEFAULT_NAMESPACE.to_string()
+}
+
 /// A single memory entry
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -11,6 +22,73 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    #[serde(default)]
+    pub metadata: MemoryMetadata,
+    /// Isolation boundary, e.g. one chat user; see `memory::namespace`
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+}
+
+/// Optional per-entry metadata used for filtering and recall weighting
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MemoryMetadata {
+    /// Free-form labels (project, topic, ...) usable as recall filters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Where the fact came from (channel, file, URL, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 0.0–1.0; unset entries count as [`DEFAULT_IMPORTANCE`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<f64>,
+    /// RFC 3339 time after which the entry is no longer recalled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// How often the entry has been returned by `recall` (maintained by the backend)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub access_count: u64,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl MemoryMetadata {
+    /// True when nothing beyond the defaults is set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Importance clamped to 0.0–1.0, or [`DEFAULT_IMPORTANCE`].
+    pub fn importance(&self) -> f64 {
+        self.importance
+            .filter(|v| v.is_finite())
+            .map_or(DEFAULT_IMPORTANCE, |v| v.clamp(0.0, 1.0))
+    }
+
+    /// Whether `expires_at` lies before `now`. Unparseable values never expire.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .is_some_and(|ts| ts < now)
+    }
+
+    /// Whether the entry carries every tag in `tags` (case-insensitive).
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .all(|wanted| self.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted)))
+    }
+
+    /// Copy without the backend-maintained access counter, for persisting
+    /// the caller-provided part.
+    pub fn without_access_count(&self) -> Self {
+        Self {
+            access_count: 0,
+            ..self.clone()
+        }
+    }
 }
 
 impl std::fmt::Debug for MemoryEntry {
@@ -22,6 +100,8 @@ impl std::fmt::Debug for MemoryEntry {
             .field("category", &self.category)
             .field("timestamp", &self.timestamp)
             .field("score", &self.score)
+            .field("metadata", &self.metadata)
+            .field("namespace", &self.namespace)
             .finish_non_exhaustive()
     }
 }
@@ -36,6 +116,8 @@ pub enum MemoryCategory {
     Daily,
     /// Conversation context
     Conversation,
+    /// Document chunks ingested from files (`zeroclaw memory ingest`)
+    Knowledge,
     /// User-defined custom category
     Custom(String),
 }
@@ -46,12 +128,27 @@ impl std::fmt::Display for MemoryCategory {
             Self::Core => write!(f, "core"),
             Self::Daily => write!(f, "daily"),
             Self::Conversation => write!(f, "conversation"),
+            Self::Knowledge => write!(f, "knowledge"),
             Self::Custom(name) => write!(f, "{name}"),
         }
     }
 }
 
+/// `recall_tagged` fetches this many times `limit` candidates before filtering.
+pub(crate) const TAG_RECALL_OVERSAMPLE: usize = 4;
+
+pub(crate) fn ensure_default_namespace(backend: &str, namespace: &str) -> anyhow::Result<()> {
+    if namespace != DEFAULT_NAMESPACE {
+        anyhow::bail!("memory backend '{backend}' does not support namespaces (got '{namespace}')");
+    }
+    Ok(())
+}
+
 /// Core memory trait — implement for any persistence backend
+///
+/// The un-suffixed methods (`store`, `recall`, `get`, `list`, `forget`) act on
+/// [`DEFAULT_NAMESPACE`]; the `*_in` variants take explicit namespaces.
+/// Backends without namespace support only accept the default namespace.
 #[async_trait]
 pub trait Memory: Send + Sync {
     /// Backend name
@@ -66,6 +163,20 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<()>;
 
+    /// Store a memory entry with metadata (tags, source, importance, expiry).
+    ///
+    /// Backends that cannot persist metadata fall back to `store`.
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        _metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.store(key, content, category, session_id).await
+    }
+
     /// Recall memories matching a query (keyword search), optionally scoped to a session
     async fn recall(
         &self,
@@ -74,9 +185,98 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall restricted to entries carrying all of `tags`.
+    ///
+    /// The default oversamples `recall` and filters the results.
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        let mut entries = self
+            .recall(
+                query,
+                limit.saturating_mul(TAG_RECALL_OVERSAMPLE),
+                session_id,
+            )
+            .await?;
+        entries.retain(|entry| entry.metadata.has_tags(tags));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
+    /// Whether the `*_in` methods honour namespaces other than the default.
+    fn supports_namespaces(&self) -> bool {
+        false
+    }
+
+    /// Store into `namespace`.
+    async fn store_in(
+        &self,
+        namespace: &str,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        ensure_default_namespace(self.name(), namespace)?;
+        self.store_with_metadata(key, content, category, session_id, metadata)
+            .await
+    }
+
+    /// Tag-filtered recall across `namespaces`.
+    async fn recall_in(
+        &self,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if !namespaces.iter().any(|ns| ns == DEFAULT_NAMESPACE) {
+            return Ok(Vec::new());
+        }
+        self.recall_tagged(query, limit, session_id, tags).await
+    }
+
+    /// Get a memory by key within `namespace`.
+    async fn get_in(&self, namespace: &str, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        if namespace != DEFAULT_NAMESPACE {
+            return Ok(None);
+        }
+        self.get(key).await
+    }
+
+    /// List memories across `namespaces`.
+    async fn list_in(
+        &self,
+        namespaces: &[String],
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if !namespaces.iter().any(|ns| ns == DEFAULT_NAMESPACE) {
+            return Ok(Vec::new());
+        }
+        self.list(category, session_id).await
+    }
+
+    /// Remove a memory by key within `namespace`.
+    async fn forget_in(&self, namespace: &str, key: &str) -> anyhow::Result<bool> {
+        if namespace != DEFAULT_NAMESPACE {
+            return Ok(false);
+        }
+        self.forget(key).await
+    }
+
     /// List all memory keys, optionally filtered by category and/or session
     async fn list(
         &self,
@@ -87,11 +287,42 @@ pub trait Memory: Send + Sync {
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
-    /// Count total memories
+    /// Count total memories (all namespaces)
     async fn count(&self) -> anyhow::Result<usize>;
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Every stored entry in every namespace, for export. Backends that cap
+    /// `list` or support namespaces override this.
+    async fn export_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.list(None, None).await
+    }
+
+    /// Stored embedding vector for a key in `namespace`, if the backend keeps one.
+    async fn embedding(&self, _namespace: &str, _key: &str) -> anyhow::Result<Option<Vec<f32>>> {
+        Ok(None)
+    }
+
+    /// Store an exported entry, keeping its timestamp where the backend can.
+    ///
+    /// `embedding` is reused when it matches the backend's dimensions; it is
+    /// recomputed otherwise. The default falls back to `store_in`.
+    async fn import_entry(
+        &self,
+        entry: &MemoryEntry,
+        _embedding: Option<&[f32]>,
+    ) -> anyhow::Result<()> {
+        self.store_in(
+            &entry.namespace,
+            &entry.key,
+            &entry.content,
+            entry.category.clone(),
+            entry.session_id.as_deref(),
+            &entry.metadata,
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -103,6 +334,7 @@ mod tests {
         assert_eq!(MemoryCategory::Core.to_string(), "core");
         assert_eq!(MemoryCategory::Daily.to_string(), "daily");
         assert_eq!(MemoryCategory::Conversation.to_string(), "conversation");
+        assert_eq!(MemoryCategory::Knowledge.to_string(), "knowledge");
         assert_eq!(
             MemoryCategory::Custom("project_notes".into()).to_string(),
             "project_notes"
@@ -130,6 +362,12 @@ mod tests {
             timestamp: "2026-02-16T00:00:00Z".into(),
             session_id: Some("session-abc".into()),
             score: Some(0.98),
+            metadata: MemoryMetadata {
+                tags: vec!["rust".into()],
+                importance: Some(0.9),
+                ..MemoryMetadata::default()
+            },
+            namespace: "slack:user:U1".into(),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -141,5 +379,35 @@ mod tests {
         assert_eq!(parsed.category, MemoryCategory::Core);
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
+        assert_eq!(parsed.metadata.tags, vec!["rust".to_string()]);
+        assert_eq!(parsed.metadata.importance, Some(0.9));
+        assert_eq!(parsed.namespace, "slack:user:U1");
+    }
+
+    #[test]
+    fn memory_entry_without_metadata_deserializes() {
+        let json = r#"{"id":"1","key":"k","content":"c","category":"core","timestamp":"t","session_id":null,"score":null}"#;
+        let parsed: MemoryEntry = serde_json::from_str(json).unwrap();
+        assert!(parsed.metadata.is_empty());
+        assert_eq!(parsed.namespace, DEFAULT_NAMESPACE);
+    }
+
+    #[test]
+    fn metadata_helpers() {
+        let meta = MemoryMetadata {
+            tags: vec!["Infra".into(), "oncall".into()],
+            importance: Some(3.0),
+            expires_at: Some("2026-01-01T00:00:00Z".into()),
+            ..MemoryMetadata::default()
+        };
+        assert!(meta.has_tags(&["infra".into()]));
+        assert!(!meta.has_tags(&["infra".into(), "billing".into()]));
+        assert!((meta.importance() - 1.0).abs() < f64::EPSILON);
+        assert!((MemoryMetadata::default().importance() - DEFAULT_IMPORTANCE).abs() < f64::EPSILON);
+
+        let before = DateTime::parse_from_rfc3339("2025-12-31T00:00:00Z").unwrap();
+        let after = DateTime::parse_from_rfc3339("2026-01-02T00:00:00Z").unwrap();
+        assert!(!meta.is_expired(before.with_timezone(&Utc)));
+        assert!(meta.is_expired(after.with_timezone(&Utc)));
     }
 }
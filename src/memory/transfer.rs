@@ -0,0 +1,504 @@
+// Backend-neutral memory export/import — `zeroclaw memory export|import`.
+//
+// Entries are written as JSON Lines: a header line identifying the format
+// and version, then one record per entry with its namespace, key, content,
+// category, session id, timestamp, metadata and (optionally) embedding. Export goes through
+// `Memory::export_entries`/`embedding` and import through
+// `Memory::import_entry`, so any pair of backends can exchange data, e.g.
+// export from SQLite and import into Postgres or Qdrant.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, DEFAULT_NAMESPACE};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// Value of the header's `format` field.
+pub const FORMAT_NAME: &str = "zeroclaw-memory";
+
+/// Current export format version. Importers reject newer versions.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// First line of every export.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportHeader {
+    pub format: String,
+    pub version: u32,
+    /// Backend the entries were exported from.
+    pub backend: String,
+    pub exported_at: String,
+    pub entries: usize,
+}
+
+/// One exported memory entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportRecord {
+    /// Omitted for the default namespace.
+    #[serde(
+        default = "default_namespace",
+        skip_serializing_if = "is_default_namespace"
+    )]
+    pub namespace: String,
+    pub key: String,
+    pub content: String,
+    pub category: String,
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "MemoryMetadata::is_empty")]
+    pub metadata: MemoryMetadata,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+impl ExportRecord {
+    fn from_entry(entry: MemoryEntry, embedding: Option<Vec<f32>>) -> Self {
+        Self {
+            namespace: entry.namespace,
+            key: entry.key,
+            content: entry.content,
+            category: entry.category.to_string(),
+            timestamp: entry.timestamp,
+            session_id: entry.session_id,
+            metadata: entry.metadata,
+            embedding,
+        }
+    }
+
+    fn to_entry(&self) -> MemoryEntry {
+        MemoryEntry {
+            id: String::new(),
+            key: self.key.clone(),
+            content: self.content.clone(),
+            category: parse_category(&self.category),
+            timestamp: self.timestamp.clone(),
+            session_id: self.session_id.clone(),
+            score: None,
+            metadata: self.metadata.clone(),
+            namespace: self.namespace.clone(),
+        }
+    }
+}
+
+fn default_namespace() -> String {
+    DEFAULT_NAMESPACE.to_string()
+}
+
+fn is_default_namespace(namespace: &str) -> bool {
+    namespace == DEFAULT_NAMESPACE
+}
+
+/// Options for `export_jsonl`.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only export this category.
+    pub category: Option<MemoryCategory>,
+    /// Include stored embedding vectors (backends without vectors write none).
+    pub embeddings: bool,
+}
+
+/// Options for `import_jsonl`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Leave entries whose key already exists untouched.
+    pub skip_existing: bool,
+    /// Validate and count without writing.
+    pub dry_run: bool,
+}
+
+/// What an import did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Backend named in the export header.
+    pub source_backend: String,
+    pub imported: usize,
+    pub skipped_existing: usize,
+}
+
+/// Write every entry of `memory` to `out`. Returns the number of entries.
+pub async fn export_jsonl(
+    memory: &dyn Memory,
+    out: &mut dyn Write,
+    options: &ExportOptions,
+) -> Result<usize> {
+    let mut entries = memory.export_entries().await?;
+    if let Some(category) = &options.category {
+        entries.retain(|entry| &entry.category == category);
+    }
+
+    let header = ExportHeader {
+        format: FORMAT_NAME.to_string(),
+        version: FORMAT_VERSION,
+        backend: memory.name().to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        entries: entries.len(),
+    };
+    serde_json::to_writer(&mut *out, &header)?;
+    out.write_all(b"\n")?;
+
+    let count = entries.len();
+    for entry in entries {
+        let embedding = if options.embeddings {
+            memory.embedding(&entry.namespace, &entry.key).await?
+        } else {
+            None
+        };
+        serde_json::to_writer(&mut *out, &ExportRecord::from_entry(entry, embedding))?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+
+    Ok(count)
+}
+
+/// Read an export from `input` and store its entries in `memory`.
+///
+/// The whole file is parsed before anything is written, so a malformed or
+/// newer-version export leaves the target untouched.
+pub async fn import_jsonl(
+    memory: &dyn Memory,
+    input: impl BufRead,
+    options: ImportOptions,
+) -> Result<ImportReport> {
+    let (header, records) = parse_export(input)?;
+    let mut report = ImportReport {
+        source_backend: header.backend,
+        ..ImportReport::default()
+    };
+
+    for record in &records {
+        if options.skip_existing
+            && memory
+                .get_in(&record.namespace, &record.key)
+                .await?
+                .is_some()
+        {
+            report.skipped_existing += 1;
+            continue;
+        }
+        if !options.dry_run {
+            memory
+                .import_entry(&record.to_entry(), record.embedding.as_deref())
+                .await
+                .with_context(|| format!("Failed to import memory '{}'", record.key))?;
+        }
+        report.imported += 1;
+    }
+
+    Ok(report)
+}
+
+fn parse_export(input: impl BufRead) -> Result<(ExportHeader, Vec<ExportRecord>)> {
+    let mut header: Option<ExportHeader> = None;
+    let mut records = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.with_context(|| format!("Failed to read line {line_no}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if header.is_none() {
+            let parsed: ExportHeader = serde_json::from_str(&line)
+                .with_context(|| format!("line {line_no}: not a ZeroClaw memory export header"))?;
+            if parsed.format != FORMAT_NAME {
+                bail!(
+                    "line {line_no}: unknown export format '{}' (expected '{FORMAT_NAME}')",
+                    parsed.format
+                );
+            }
+            if parsed.version > FORMAT_VERSION {
+                bail!(
+                    "export format version {} is newer than supported version {FORMAT_VERSION}; upgrade zeroclaw",
+                    parsed.version
+                );
+            }
+            header = Some(parsed);
+            continue;
+        }
+
+        let record: ExportRecord = serde_json::from_str(&line)
+            .with_context(|| format!("line {line_no}: invalid memory record"))?;
+        if record.key.trim().is_empty() {
+            bail!("line {line_no}: memory record has an empty key");
+        }
+        records.push(record);
+    }
+
+    let header = header.context("export is empty (missing header line)")?;
+    Ok((header, records))
+}
+
+fn parse_category(raw: &str) -> MemoryCategory {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "core" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        "knowledge" => MemoryCategory::Knowledge,
+        _ => MemoryCategory::Custom(raw.trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::EmbeddingProvider;
+    use crate::memory::{MarkdownMemory, SqliteMemory};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Deterministic 3-dimensional embedder so vectors survive a round trip.
+    struct FixedEmbedding;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for FixedEmbedding {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            #[allow(clippy::cast_precision_loss)]
+            Ok(texts
+                .iter()
+                .map(|t| vec![t.len() as f32, 1.0, 0.5])
+                .collect())
+        }
+    }
+
+    fn sqlite_with_embedder(dir: &TempDir) -> SqliteMemory {
+        SqliteMemory::with_embedder(dir.path(), Arc::new(FixedEmbedding), 0.7, 0.3, 100, None)
+            .unwrap()
+    }
+
+    async fn seed(mem: &dyn Memory) {
+        mem.store("pref", "likes rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "chat_1",
+            "asked about deploys",
+            MemoryCategory::Conversation,
+            Some("sess-a"),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "note",
+            "custom note",
+            MemoryCategory::Custom("project_x".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn export_to_string(mem: &dyn Memory, options: &ExportOptions) -> String {
+        let mut buf = Vec::new();
+        export_jsonl(mem, &mut buf, options).await.unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_writes_header_and_records() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        seed(&mem).await;
+
+        let out = export_to_string(&mem, &ExportOptions::default()).await;
+        let mut lines = out.lines();
+        let header: ExportHeader = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(header.format, FORMAT_NAME);
+        assert_eq!(header.version, FORMAT_VERSION);
+        assert_eq!(header.backend, "sqlite");
+        assert_eq!(header.entries, 3);
+
+        let records: Vec<ExportRecord> = lines.map(|l| serde_json::from_str(l).unwrap()).collect();
+        let chat = records.iter().find(|r| r.key == "chat_1").unwrap();
+        assert_eq!(chat.category, "conversation");
+        assert_eq!(chat.session_id.as_deref(), Some("sess-a"));
+        assert!(records.iter().all(|r| r.embedding.is_none()));
+    }
+
+    #[tokio::test]
+    async fn export_filters_by_category() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        seed(&mem).await;
+
+        let options = ExportOptions {
+            category: Some(MemoryCategory::Core),
+            embeddings: false,
+        };
+        let out = export_to_string(&mem, &options).await;
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.contains("likes rust"));
+    }
+
+    #[tokio::test]
+    async fn sqlite_round_trip_preserves_everything() {
+        let src_dir = TempDir::new().unwrap();
+        let src = sqlite_with_embedder(&src_dir);
+        seed(&src).await;
+        let options = ExportOptions {
+            category: None,
+            embeddings: true,
+        };
+        let out = export_to_string(&src, &options).await;
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = sqlite_with_embedder(&dst_dir);
+        let report = import_jsonl(&dst, out.as_bytes(), ImportOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.source_backend, "sqlite");
+
+        for original in src.export_entries().await.unwrap() {
+            let copy = dst.get(&original.key).await.unwrap().unwrap();
+            assert_eq!(copy.content, original.content);
+            assert_eq!(copy.category, original.category);
+            assert_eq!(copy.timestamp, original.timestamp);
+            assert_eq!(copy.session_id, original.session_id);
+            assert_eq!(
+                dst.embedding(&original.namespace, &original.key)
+                    .await
+                    .unwrap(),
+                src.embedding(&original.namespace, &original.key)
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn namespaces_survive_round_trip() {
+        let src_dir = TempDir::new().unwrap();
+        let src = SqliteMemory::new(src_dir.path()).unwrap();
+        seed(&src).await;
+        src.store_in(
+            "slack:user:U1",
+            "pref",
+            "likes zig",
+            MemoryCategory::Core,
+            None,
+            &MemoryMetadata::default(),
+        )
+        .await
+        .unwrap();
+        let out = export_to_string(&src, &ExportOptions::default()).await;
+        assert_eq!(out.matches("\"namespace\"").count(), 1);
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = SqliteMemory::new(dst_dir.path()).unwrap();
+        let report = import_jsonl(&dst, out.as_bytes(), ImportOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.imported, 4);
+        assert_eq!(
+            dst.get("pref").await.unwrap().unwrap().content,
+            "likes rust"
+        );
+        let scoped = dst.get_in("slack:user:U1", "pref").await.unwrap().unwrap();
+        assert_eq!(scoped.content, "likes zig");
+    }
+
+    #[tokio::test]
+    async fn import_skip_existing_and_dry_run() {
+        let src_dir = TempDir::new().unwrap();
+        let src = SqliteMemory::new(src_dir.path()).unwrap();
+        seed(&src).await;
+        let out = export_to_string(&src, &ExportOptions::default()).await;
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = SqliteMemory::new(dst_dir.path()).unwrap();
+        dst.store("pref", "likes go", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let dry = ImportOptions {
+            skip_existing: true,
+            dry_run: true,
+        };
+        let report = import_jsonl(&dst, out.as_bytes(), dry).await.unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.skipped_existing, 1);
+        assert_eq!(dst.count().await.unwrap(), 1);
+
+        let skip = ImportOptions {
+            skip_existing: true,
+            dry_run: false,
+        };
+        import_jsonl(&dst, out.as_bytes(), skip).await.unwrap();
+        assert_eq!(dst.count().await.unwrap(), 3);
+        let pref = dst.get("pref").await.unwrap().unwrap();
+        assert_eq!(pref.content, "likes go");
+    }
+
+    #[tokio::test]
+    async fn import_into_markdown() {
+        let src_dir = TempDir::new().unwrap();
+        let src = SqliteMemory::new(src_dir.path()).unwrap();
+        seed(&src).await;
+        let out = export_to_string(&src, &ExportOptions::default()).await;
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = MarkdownMemory::new(dst_dir.path());
+        let report = import_jsonl(&dst, out.as_bytes(), ImportOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.imported, 3);
+        let core = dst.list(Some(&MemoryCategory::Core), None).await.unwrap();
+        assert!(core.iter().any(|e| e.content.contains("likes rust")));
+    }
+
+    #[tokio::test]
+    async fn import_rejects_newer_version_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let input = format!(
+            "{{\"format\":\"{FORMAT_NAME}\",\"version\":{},\"backend\":\"sqlite\",\"exported_at\":\"x\",\"entries\":1}}\n\
+             {{\"key\":\"a\",\"content\":\"b\",\"category\":\"core\",\"timestamp\":\"t\"}}\n",
+            FORMAT_VERSION + 1
+        );
+        let err = import_jsonl(&mem, input.as_bytes(), ImportOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+        assert_eq!(mem.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn import_reports_bad_line_numbers() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let input = format!(
+            "{{\"format\":\"{FORMAT_NAME}\",\"version\":1,\"backend\":\"sqlite\",\"exported_at\":\"x\",\"entries\":1}}\n\
+             {{\"key\":\"a\",\"content\":\"b\",\"category\":\"core\",\"timestamp\":\"t\"}}\n\
+             not json\n"
+        );
+        let err = import_jsonl(&mem, input.as_bytes(), ImportOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{err}");
+        assert_eq!(mem.count().await.unwrap(), 0);
+
+        let err = import_jsonl(&mem, &b""[..], ImportOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing header"));
+    }
+
+    #[test]
+    fn parse_category_handles_builtin_and_custom() {
+        assert_eq!(parse_category("core"), MemoryCategory::Core);
+        assert_eq!(parse_category("knowledge"), MemoryCategory::Knowledge);
+        assert_eq!(
+            parse_category("project_x"),
+            MemoryCategory::Custom("project_x".into())
+        );
+    }
+}
@@ -303,6 +303,7 @@ fn parse_category(raw: &str) -> MemoryCategory {
         "core" | "" => MemoryCategory::Core,
         "daily" => MemoryCategory::Daily,
         "conversation" => MemoryCategory::Conversation,
+        "knowledge" => MemoryCategory::Knowledge,
         other => MemoryCategory::Custom(other.to_string()),
     }
 }
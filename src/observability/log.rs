@@ -83,6 +83,13 @@ impl Observer for LogObserver {
                     "llm.response"
                 );
             }
+            ObserverEvent::ResponseCacheLookup {
+                model,
+                hit,
+                semantic,
+            } => {
+                info!(model = %model, hit = hit, semantic = semantic, "response_cache.lookup");
+            }
         }
     }
 
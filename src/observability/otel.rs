@@ -23,6 +23,7 @@ pub struct OtelObserver {
     channel_messages: Counter<u64>,
     heartbeat_ticks: Counter<u64>,
     errors: Counter<u64>,
+    response_cache_lookups: Counter<u64>,
     request_latency: Histogram<f64>,
     tokens_used: Counter<u64>,
     active_sessions: Gauge<u64>,
@@ -126,6 +127,11 @@ impl OtelObserver {
             .with_description("Total heartbeat ticks")
             .build();
 
+        let response_cache_lookups = meter
+            .u64_counter("zeroclaw.response_cache.lookups")
+            .with_description("Total response cache lookups")
+            .build();
+
         let errors = meter
             .u64_counter("zeroclaw.errors")
             .with_description("Total errors by component")
@@ -164,6 +170,7 @@ impl OtelObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            response_cache_lookups,
             request_latency,
             tokens_used,
             active_sessions,
@@ -319,6 +326,20 @@ impl Observer for OtelObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.add(1, &[]);
             }
+            ObserverEvent::ResponseCacheLookup {
+                model,
+                hit,
+                semantic,
+            } => {
+                self.response_cache_lookups.add(
+                    1,
+                    &[
+                        KeyValue::new("model", model.clone()),
+                        KeyValue::new("hit", hit.to_string()),
+                        KeyValue::new("semantic", semantic.to_string()),
+                    ],
+                );
+            }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
                 let mut span = tracer.build(
@@ -16,6 +16,7 @@ pub struct PrometheusObserver {
     channel_messages: IntCounterVec,
     heartbeat_ticks: prometheus::IntCounter,
     errors: IntCounterVec,
+    response_cache_lookups: IntCounterVec,
 
     // Histograms
     agent_duration: HistogramVec,
@@ -81,6 +82,15 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let response_cache_lookups = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_response_cache_lookups_total",
+                "Total response cache lookups by result (hit, semantic_hit, miss)",
+            ),
+            &["result"],
+        )
+        .expect("valid metric");
+
         let agent_duration = HistogramVec::new(
             HistogramOpts::new(
                 "zeroclaw_agent_duration_seconds",
@@ -139,6 +149,9 @@ impl PrometheusObserver {
         registry.register(Box::new(channel_messages.clone())).ok();
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
+        registry
+            .register(Box::new(response_cache_lookups.clone()))
+            .ok();
         registry.register(Box::new(agent_duration.clone())).ok();
         registry.register(Box::new(tool_duration.clone())).ok();
         registry.register(Box::new(request_latency.clone())).ok();
@@ -156,6 +169,7 @@ impl PrometheusObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            response_cache_lookups,
             agent_duration,
             tool_duration,
             request_latency,
@@ -251,6 +265,16 @@ impl Observer for PrometheusObserver {
             } => {
                 self.errors.with_label_values(&[component]).inc();
             }
+            ObserverEvent::ResponseCacheLookup { hit, semantic, .. } => {
+                let result = match (hit, semantic) {
+                    (true, true) => "semantic_hit",
+                    (true, false) => "hit",
+                    (false, _) => "miss",
+                };
+                self.response_cache_lookups
+                    .with_label_values(&[result])
+                    .inc();
+            }
         }
     }
 
@@ -334,6 +358,11 @@ mod tests {
             component: "provider".into(),
             message: "timeout".into(),
         });
+        obs.record_event(&ObserverEvent::ResponseCacheLookup {
+            model: "gpt-4".into(),
+            hit: true,
+            semantic: true,
+        });
     }
 
     #[test]
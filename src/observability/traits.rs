@@ -29,6 +29,15 @@ pub enum ObserverEvent {
         input_tokens: Option<u64>,
         output_tokens: Option<u64>,
     },
+    /// A cacheable provider request was looked up in the response cache.
+    ///
+    /// `semantic` is true when a hit came from embedding similarity rather
+    /// than an exact prompt match.
+    ResponseCacheLookup {
+        model: String,
+        hit: bool,
+        semantic: bool,
+    },
     /// The agent session has finished.
     ///
     /// Carries aggregate usage data (tokens, cost) when the provider reports it.
@@ -164,6 +164,7 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         tunnel: tunnel_config,
         gateway: crate::config::GatewayConfig::default(),
         composio: composio_config,
+        mcp: crate::config::McpConfig::default(),
         secrets: secrets_config,
         browser: BrowserConfig::default(),
         http_request: http_request_config,
@@ -180,6 +181,7 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
         agents_ipc: crate::config::AgentsIpcConfig::default(),
+        sop: crate::config::SopConfig::default(),
         model_support_vision: None,
     };
 
@@ -387,6 +389,9 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         vector_weight: 0.7,
         keyword_weight: 0.3,
         min_relevance_score: 0.4,
+        importance_weight: 0.3,
+        recency_weight: 0.2,
+        recency_half_life_days: 30.0,
         embedding_cache_size: if profile.uses_sqlite_hygiene {
             10000
         } else {
@@ -396,11 +401,18 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         response_cache_enabled: false,
         response_cache_ttl_minutes: 60,
         response_cache_max_entries: 5_000,
+        response_cache_semantic: false,
+        response_cache_similarity_threshold: 0.95,
         snapshot_enabled: false,
         snapshot_on_hygiene: false,
         auto_hydrate: true,
         sqlite_open_timeout_secs: None,
+        sqlite_ann_index: true,
+        encrypt_at_rest: false,
         qdrant: crate::config::QdrantConfig::default(),
+        namespaces: crate::config::MemoryNamespaceConfig::default(),
+        rerank: crate::config::MemoryRerankConfig::default(),
+        consolidation: crate::config::MemoryConsolidationConfig::default(),
     }
 }
 
@@ -522,6 +534,7 @@ async fn run_quick_setup_with_home(
         tunnel: crate::config::TunnelConfig::default(),
         gateway: crate::config::GatewayConfig::default(),
         composio: ComposioConfig::default(),
+        mcp: crate::config::McpConfig::default(),
         secrets: SecretsConfig::default(),
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
@@ -538,6 +551,7 @@ async fn run_quick_setup_with_home(
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
         agents_ipc: crate::config::AgentsIpcConfig::default(),
+        sop: crate::config::SopConfig::default(),
         model_support_vision: None,
     };
 
@@ -5274,6 +5288,16 @@ fn setup_channels() -> Result<ChannelsConfig> {
         println!();
     }
 
+    if has_launchable_channels(&config) {
+        config.persist_sessions = Confirm::new()
+            .with_prompt(
+                "  Keep channel conversations across restarts? \
+                 (stored in the workspace, plaintext unless memory encryption is on)",
+            )
+            .default(false)
+            .interact()?;
+    }
+
     // Summary line
     let channels = config.channels();
     let channels = channels
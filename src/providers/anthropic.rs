@@ -1,12 +1,18 @@
+use crate::providers::compatible::{response_lines_to_chunks, send_streaming_request};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, StreamChunk, StreamError, StreamOptions, StreamResult,
+    TokenUsage, ToolCall as ProviderToolCall, ToolCallDelta, ToolChoice,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+/// Output cap used when the request does not set `max_tokens`.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
 pub struct AnthropicProvider {
     credential: Option<String>,
     base_url: String,
@@ -51,6 +57,12 @@ struct NativeChatRequest<'a> {
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -164,6 +176,109 @@ struct NativeContentIn {
     input: Option<serde_json::Value>,
 }
 
+/// Server-sent events of the Messages streaming API.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: StreamMessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: NativeContentIn,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: StreamBlockDelta,
+    },
+    MessageDelta {
+        #[serde(default)]
+        usage: Option<AnthropicUsage>,
+    },
+    Error {
+        error: StreamErrorBody,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessageStart {
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamBlockDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamErrorBody {
+    #[serde(default)]
+    message: String,
+}
+
+/// Parse one line of a Messages API event stream. Content block indices are
+/// used as tool-call indices, so fragments of one `tool_use` block merge.
+fn parse_stream_line(line: &str) -> StreamResult<Option<StreamChunk>> {
+    let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+        return Ok(None);
+    };
+    if data.is_empty() {
+        return Ok(None);
+    }
+
+    let usage = |u: AnthropicUsage| TokenUsage {
+        input_tokens: u.input_tokens,
+        output_tokens: u.output_tokens,
+    };
+    let chunk = match serde_json::from_str(data).map_err(StreamError::Json)? {
+        StreamEvent::MessageStart { message } => message.usage.map(usage).map(StreamChunk::usage),
+        StreamEvent::ContentBlockStart {
+            index,
+            content_block,
+        } if content_block.kind == "tool_use" => Some(StreamChunk::tool_call(ToolCallDelta {
+            index,
+            id: content_block.id,
+            name: content_block.name,
+            arguments: String::new(),
+        })),
+        StreamEvent::ContentBlockDelta { index, delta } => match delta {
+            StreamBlockDelta::TextDelta { text } => Some(StreamChunk::delta(text)),
+            StreamBlockDelta::InputJsonDelta { partial_json } => {
+                Some(StreamChunk::tool_call(ToolCallDelta {
+                    index,
+                    arguments: partial_json,
+                    ..ToolCallDelta::default()
+                }))
+            }
+            StreamBlockDelta::ThinkingDelta { thinking } => Some(StreamChunk::reasoning(thinking)),
+            StreamBlockDelta::Other => None,
+        },
+        StreamEvent::MessageDelta { usage: Some(u) } => Some(StreamChunk::usage(usage(u))),
+        StreamEvent::Error { error } => {
+            return Err(StreamError::Provider(format!(
+                "Anthropic stream error: {}",
+                error.message
+            )))
+        }
+        _ => None,
+    };
+    Ok(chunk.filter(|chunk| !chunk.is_empty()))
+}
+
 impl AnthropicProvider {
     pub fn new(credential: Option<&str>) -> Self {
         Self::with_base_url(credential, None)
@@ -249,6 +364,50 @@ impl AnthropicProvider {
         Some(native_tools)
     }
 
+    /// Anthropic `tool_choice`; omitted when no tools are sent.
+    fn convert_tool_choice(
+        choice: Option<ToolChoice<'_>>,
+        has_tools: bool,
+    ) -> Option<serde_json::Value> {
+        if !has_tools {
+            return None;
+        }
+        Some(match choice? {
+            ToolChoice::None => serde_json::json!({ "type": "none" }),
+            ToolChoice::Auto => serde_json::json!({ "type": "auto" }),
+            ToolChoice::Required => serde_json::json!({ "type": "any" }),
+            ToolChoice::Tool(name) => serde_json::json!({ "type": "tool", "name": name }),
+        })
+    }
+
+    /// Build the Messages API payload shared by `chat` and `stream_chat`.
+    fn build_native_request<'a>(
+        request: &ProviderChatRequest<'a>,
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> NativeChatRequest<'a> {
+        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
+
+        // Auto-cache last message if conversation is long
+        if Self::should_cache_conversation(request.messages) {
+            Self::apply_cache_to_last_message(&mut messages);
+        }
+
+        let tools = Self::convert_tools(request.tools);
+        NativeChatRequest {
+            model: model.to_string(),
+            max_tokens: request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system: system_prompt,
+            messages,
+            temperature,
+            tool_choice: Self::convert_tool_choice(request.tool_choice, tools.is_some()),
+            tools,
+            stop_sequences: (!request.stop.is_empty()).then(|| request.stop.to_vec()),
+            stream: stream.then_some(true),
+        }
+    }
+
     fn parse_assistant_tool_call_message(content: &str) -> Option<Vec<NativeContentOut>> {
         let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
         let tool_calls = value
@@ -523,21 +682,7 @@ impl Provider for AnthropicProvider {
             )
         })?;
 
-        let (system_prompt, mut messages) = Self::convert_messages(request.messages);
-
-        // Auto-cache last message if conversation is long
-        if Self::should_cache_conversation(request.messages) {
-            Self::apply_cache_to_last_message(&mut messages);
-        }
-
-        let native_request = NativeChatRequest {
-            model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt,
-            messages,
-            temperature,
-            tools: Self::convert_tools(request.tools),
-        };
+        let native_request = Self::build_native_request(&request, model, temperature, false);
 
         let req = self
             .http_client()
@@ -559,6 +704,69 @@ impl Provider for AnthropicProvider {
         true
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_chat(
+            ProviderChatRequest {
+                messages,
+                tools: None,
+                ..Default::default()
+            },
+            model,
+            temperature,
+            options,
+        )
+    }
+
+    fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.as_ref() else {
+            return stream::once(async {
+                Err(StreamError::Provider(
+                    "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token).".into(),
+                ))
+            })
+            .boxed();
+        };
+
+        let native_request = Self::build_native_request(&request, model, temperature, true);
+
+        let req = self
+            .http_client()
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .header("accept", "text/event-stream")
+            .json(&native_request);
+
+        send_streaming_request(
+            self.apply_auth(req, credential),
+            "Anthropic".to_string(),
+            options.count_tokens,
+            |response, count_tokens| {
+                response_lines_to_chunks(response, count_tokens, parse_stream_line)
+            },
+        )
+    }
+
     fn capabilities(&self) -> ProviderCapabilities {
         ProviderCapabilities {
             native_tool_calling: true,
@@ -609,6 +817,7 @@ impl Provider for AnthropicProvider {
             } else {
                 Some(&tool_specs)
             },
+            ..Default::default()
         };
         self.chat(request, model, temperature).await
     }
@@ -1208,6 +1417,9 @@ mod tests {
             }],
             temperature: 0.7,
             tools: None,
+            tool_choice: None,
+            stop_sequences: None,
+            stream: None,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -1413,4 +1625,90 @@ mod tests {
         assert!(caps.vision);
         assert!(caps.native_tool_calling);
     }
+
+    #[test]
+    fn stream_events_fold_into_tool_calls_reasoning_and_usage() {
+        use crate::providers::traits::StreamAccumulator;
+
+        let lines = [
+            "event: message_start",
+            r#"data: {"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":25,"output_tokens":1}}}"#,
+            r#"data: {"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Need ls."}}"#,
+            r#"data: {"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"Checking."}}"#,
+            r#"data: {"type":"content_block_start","index":2,"content_block":{"type":"tool_use","id":"toolu_1","name":"shell","input":{}}}"#,
+            r#"data: {"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"{\"command\":"}}"#,
+            r#"data: {"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"\"ls\"}"}}"#,
+            r#"data: {"type":"content_block_stop","index":2}"#,
+            r#"data: {"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":40}}"#,
+            r#"data: {"type":"message_stop"}"#,
+        ];
+
+        let mut acc = StreamAccumulator::new();
+        for line in lines {
+            if let Some(chunk) = parse_stream_line(line).unwrap() {
+                acc.push(&chunk);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Checking."));
+        assert_eq!(response.reasoning_content.as_deref(), Some("Need ls."));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "toolu_1");
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(25));
+        assert_eq!(usage.output_tokens, Some(40));
+    }
+
+    #[test]
+    fn stream_error_event_surfaces_as_provider_error() {
+        let err = parse_stream_line(
+            r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+
+    #[test]
+    fn native_request_maps_generation_options() {
+        let messages = vec![ChatMessage::user("hi")];
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let stop = vec!["END".to_string()];
+        let request = ProviderChatRequest {
+            messages: &messages,
+            tools: Some(&tools),
+            max_tokens: Some(512),
+            stop: &stop,
+            tool_choice: Some(ToolChoice::Tool("shell")),
+            ..Default::default()
+        };
+
+        let native = AnthropicProvider::build_native_request(&request, "claude", 0.2, false);
+        let json = serde_json::to_value(&native).unwrap();
+        assert_eq!(json["max_tokens"], 512);
+        assert_eq!(json["stop_sequences"], serde_json::json!(["END"]));
+        assert_eq!(
+            json["tool_choice"],
+            serde_json::json!({"type": "tool", "name": "shell"})
+        );
+        assert!(json.get("stream").is_none());
+
+        let request = ProviderChatRequest {
+            messages: &messages,
+            tool_choice: Some(ToolChoice::Required),
+            ..Default::default()
+        };
+        let native = AnthropicProvider::build_native_request(&request, "claude", 0.2, true);
+        let json = serde_json::to_value(&native).unwrap();
+        assert_eq!(json["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert!(json.get("tool_choice").is_none());
+        assert!(json.get("stop_sequences").is_none());
+        assert_eq!(json["stream"], true);
+    }
 }
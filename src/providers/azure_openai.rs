@@ -0,0 +1,940 @@
+//! Azure OpenAI provider.
+//!
+//! Azure hosts OpenAI models behind per-deployment URLs
+//! (`{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=…`)
+//! and authenticates with either an `api-key` header or a Microsoft Entra ID
+//! bearer token. Model ids are mapped to deployments through
+//! `[provider.azure_openai].deployments`.
+
+use crate::config::{AzureOpenAiAuthMode, AzureOpenAiConfig, AzureOpenAiDeploymentConfig};
+use crate::multimodal;
+use crate::providers::compatible::{
+    openai_response_format, openai_sse_to_structured_chunks, openai_stop, openai_tool_choice,
+    send_streaming_request,
+};
+use crate::providers::traits::{
+    ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
+    Provider, ProviderCapabilities, StreamChunk, StreamError, StreamOptions, StreamResult,
+    TokenUsage, ToolCall as ProviderToolCall,
+};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const ENDPOINT_ENV: &str = "AZURE_OPENAI_ENDPOINT";
+const AD_TOKEN_ENV: &str = "AZURE_OPENAI_AD_TOKEN";
+const TENANT_ID_ENV: &str = "AZURE_TENANT_ID";
+const CLIENT_ID_ENV: &str = "AZURE_CLIENT_ID";
+const CLIENT_SECRET_ENV: &str = "AZURE_CLIENT_SECRET";
+const AUTHORITY_HOST_ENV: &str = "AZURE_AUTHORITY_HOST";
+const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+const COGNITIVE_SERVICES_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+/// Refresh Entra tokens this long before they expire.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+// ── Authentication ──────────────────────────────────────────────
+
+/// How requests to an Azure OpenAI resource authenticate.
+pub(crate) enum AzureAuth {
+    /// `api-key: <key>`
+    ApiKey(String),
+    /// `Authorization: Bearer <token>` with a pre-issued Entra ID token.
+    Bearer(String),
+    /// Entra ID client-credentials flow; tokens are fetched and cached.
+    ClientCredentials(EntraClientCredentials),
+}
+
+impl AzureAuth {
+    /// Resolve credentials for `mode`.
+    ///
+    /// `credential` is the configured key (api-key mode) or a pre-issued token
+    /// (Entra ID mode). Entra ID falls back to `AZURE_OPENAI_AD_TOKEN`, then to
+    /// client credentials from `AZURE_TENANT_ID` / `AZURE_CLIENT_ID` /
+    /// `AZURE_CLIENT_SECRET`.
+    pub(crate) fn resolve(mode: AzureOpenAiAuthMode, credential: Option<&str>) -> Option<Self> {
+        let credential = credential
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string);
+
+        match mode {
+            AzureOpenAiAuthMode::ApiKey => credential.map(Self::ApiKey),
+            AzureOpenAiAuthMode::EntraId => {
+                if let Some(token) = credential.or_else(|| read_env(AD_TOKEN_ENV)) {
+                    return Some(Self::Bearer(token));
+                }
+                let tenant_id = read_env(TENANT_ID_ENV)?;
+                let client_id = read_env(CLIENT_ID_ENV)?;
+                let client_secret = read_env(CLIENT_SECRET_ENV)?;
+                let authority =
+                    read_env(AUTHORITY_HOST_ENV).unwrap_or_else(|| DEFAULT_AUTHORITY_HOST.into());
+                Some(Self::ClientCredentials(EntraClientCredentials::new(
+                    &format!(
+                        "{}/{tenant_id}/oauth2/v2.0/token",
+                        authority.trim_end_matches('/')
+                    ),
+                    &client_id,
+                    &client_secret,
+                )))
+            }
+        }
+    }
+
+    /// Attach the auth header to `builder`, fetching an Entra token if needed.
+    pub(crate) async fn apply(
+        &self,
+        builder: RequestBuilder,
+        client: &Client,
+    ) -> anyhow::Result<RequestBuilder> {
+        Ok(match self {
+            Self::ApiKey(key) => builder.header("api-key", key),
+            Self::Bearer(token) => builder.bearer_auth(token),
+            Self::ClientCredentials(credentials) => {
+                builder.bearer_auth(credentials.access_token(client).await?)
+            }
+        })
+    }
+}
+
+/// Entra ID client-credentials token source for the Cognitive Services scope.
+pub(crate) struct EntraClientCredentials {
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    cached: tokio::sync::Mutex<Option<(String, Instant)>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntraTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+impl EntraClientCredentials {
+    pub(crate) fn new(token_url: &str, client_id: &str, client_secret: &str) -> Self {
+        Self {
+            token_url: token_url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    async fn access_token(&self, client: &Client) -> anyhow::Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some((token, refresh_at)) = cached.as_ref() {
+            if Instant::now() < *refresh_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = client
+            .post(&self.token_url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("scope", COGNITIVE_SERVICES_SCOPE),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Entra ID token", response).await);
+        }
+
+        let token: EntraTokenResponse = response.json().await?;
+        let lifetime = Duration::from_secs(token.expires_in.unwrap_or(3600));
+        let refresh_at = Instant::now() + lifetime.saturating_sub(TOKEN_REFRESH_MARGIN);
+        *cached = Some((token.access_token.clone(), refresh_at));
+        Ok(token.access_token)
+    }
+}
+
+fn read_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Normalize a resource endpoint: no trailing slash or `/openai` suffix.
+pub(crate) fn normalize_endpoint(endpoint: &str) -> String {
+    let trimmed = endpoint.trim().trim_end_matches('/');
+    trimmed
+        .strip_suffix("/openai")
+        .unwrap_or(trimmed)
+        .to_string()
+}
+
+/// URL of `operation` (e.g. `chat/completions`, `embeddings`) on a deployment.
+pub(crate) fn deployment_url(
+    endpoint: &str,
+    deployment: &str,
+    operation: &str,
+    api_version: &str,
+) -> String {
+    format!(
+        "{endpoint}/openai/deployments/{deployment}/{operation}?api-version={api_version}",
+        deployment = urlencoding::encode(deployment),
+        api_version = urlencoding::encode(api_version),
+    )
+}
+
+/// Endpoint from `AZURE_OPENAI_ENDPOINT`, if set.
+pub(crate) fn endpoint_from_env() -> Option<String> {
+    read_env(ENDPOINT_ENV).map(|endpoint| normalize_endpoint(&endpoint))
+}
+
+// ── Wire format ─────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+struct NativeChatRequest {
+    messages: Vec<NativeMessage>,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeMessage {
+    role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<MessageContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<NativeToolCall>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<MessagePart>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessagePart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrlPart },
+}
+
+#[derive(Debug, Serialize)]
+struct ImageUrlPart {
+    url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NativeToolCall {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    function: NativeFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NativeFunctionCall {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeChatResponse {
+    choices: Vec<NativeChoice>,
+    #[serde(default)]
+    usage: Option<UsageInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageInfo {
+    #[serde(default)]
+    prompt_tokens: Option<u64>,
+    #[serde(default)]
+    completion_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeChoice {
+    message: NativeResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<NativeToolCall>>,
+}
+
+// ── Provider ────────────────────────────────────────────────────
+
+pub struct AzureOpenAiProvider {
+    endpoint: Option<String>,
+    api_version: String,
+    deployments: Vec<AzureOpenAiDeploymentConfig>,
+    auth: Option<Arc<AzureAuth>>,
+    max_tokens_override: Option<u32>,
+}
+
+/// Where a model's requests go.
+#[derive(Debug, PartialEq, Eq)]
+struct DeploymentTarget {
+    endpoint: String,
+    deployment: String,
+    api_version: String,
+}
+
+impl AzureOpenAiProvider {
+    /// Create a provider from `[provider.azure_openai]`.
+    ///
+    /// The endpoint resolves from the config, then `api_url`, then
+    /// `AZURE_OPENAI_ENDPOINT`; `credential` is interpreted per the auth mode.
+    pub fn new(
+        config: &AzureOpenAiConfig,
+        api_url: Option<&str>,
+        credential: Option<&str>,
+        max_tokens_override: Option<u32>,
+    ) -> Self {
+        let endpoint = config
+            .endpoint
+            .as_deref()
+            .or(api_url)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(normalize_endpoint)
+            .or_else(endpoint_from_env);
+
+        Self {
+            endpoint,
+            api_version: config.api_version.trim().to_string(),
+            deployments: config.deployments.clone(),
+            auth: AzureAuth::resolve(config.auth, credential).map(Arc::new),
+            max_tokens_override: max_tokens_override.filter(|value| *value > 0),
+        }
+    }
+
+    fn http_client(&self) -> Client {
+        crate::config::build_runtime_proxy_client_with_timeouts("provider.azure_openai", 120, 10)
+    }
+
+    fn auth(&self) -> anyhow::Result<Arc<AzureAuth>> {
+        self.auth.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Azure OpenAI credentials not set. Set AZURE_OPENAI_API_KEY (or configure \
+                 Entra ID with [provider.azure_openai].auth = \"entra_id\") or edit config.toml."
+            )
+        })
+    }
+
+    fn target(&self, model: &str) -> anyhow::Result<DeploymentTarget> {
+        let mapping = self
+            .deployments
+            .iter()
+            .find(|d| d.model.trim() == model || d.deployment.trim() == model);
+
+        let endpoint = mapping
+            .and_then(|d| d.endpoint.as_deref())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(normalize_endpoint)
+            .or_else(|| self.endpoint.clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Azure OpenAI endpoint not set. Set [provider.azure_openai].endpoint, \
+                     api_url or AZURE_OPENAI_ENDPOINT."
+                )
+            })?;
+
+        Ok(DeploymentTarget {
+            endpoint,
+            deployment: mapping.map_or(model, |d| d.deployment.trim()).to_string(),
+            api_version: mapping
+                .and_then(|d| d.api_version.as_deref())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&self.api_version)
+                .to_string(),
+        })
+    }
+
+    fn chat_url(&self, model: &str) -> anyhow::Result<String> {
+        let target = self.target(model)?;
+        Ok(deployment_url(
+            &target.endpoint,
+            &target.deployment,
+            "chat/completions",
+            &target.api_version,
+        ))
+    }
+
+    fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<serde_json::Value>> {
+        tools.map(|items| {
+            items
+                .iter()
+                .map(|tool| {
+                    serde_json::json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.parameters,
+                        }
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn to_message_content(role: &str, content: &str) -> MessageContent {
+        if role != "user" {
+            return MessageContent::Text(content.to_string());
+        }
+
+        let (cleaned_text, image_refs) = multimodal::parse_image_markers(content);
+        if image_refs.is_empty() {
+            return MessageContent::Text(content.to_string());
+        }
+
+        let mut parts = Vec::with_capacity(image_refs.len() + 1);
+        let trimmed_text = cleaned_text.trim();
+        if !trimmed_text.is_empty() {
+            parts.push(MessagePart::Text {
+                text: trimmed_text.to_string(),
+            });
+        }
+        for image_ref in image_refs {
+            parts.push(MessagePart::ImageUrl {
+                image_url: ImageUrlPart { url: image_ref },
+            });
+        }
+        MessageContent::Parts(parts)
+    }
+
+    fn convert_messages(messages: &[ChatMessage]) -> Vec<NativeMessage> {
+        messages
+            .iter()
+            .map(|m| {
+                if m.role == "assistant" {
+                    if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
+                        if let Some(parsed_calls) =
+                            value.get("tool_calls").cloned().and_then(|calls| {
+                                serde_json::from_value::<Vec<ProviderToolCall>>(calls).ok()
+                            })
+                        {
+                            let tool_calls = parsed_calls
+                                .into_iter()
+                                .map(|tc| NativeToolCall {
+                                    id: Some(tc.id),
+                                    kind: Some("function".to_string()),
+                                    function: NativeFunctionCall {
+                                        name: tc.name,
+                                        arguments: tc.arguments,
+                                    },
+                                })
+                                .collect();
+                            return NativeMessage {
+                                role: "assistant".to_string(),
+                                content: value
+                                    .get("content")
+                                    .and_then(serde_json::Value::as_str)
+                                    .map(|text| MessageContent::Text(text.to_string())),
+                                tool_call_id: None,
+                                tool_calls: Some(tool_calls),
+                            };
+                        }
+                    }
+                }
+
+                if m.role == "tool" {
+                    if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
+                        return NativeMessage {
+                            role: "tool".to_string(),
+                            content: Some(MessageContent::Text(
+                                value
+                                    .get("content")
+                                    .and_then(serde_json::Value::as_str)
+                                    .map_or_else(|| m.content.clone(), ToString::to_string),
+                            )),
+                            tool_call_id: value
+                                .get("tool_call_id")
+                                .and_then(serde_json::Value::as_str)
+                                .map(ToString::to_string),
+                            tool_calls: None,
+                        };
+                    }
+                }
+
+                NativeMessage {
+                    role: m.role.clone(),
+                    content: Some(Self::to_message_content(&m.role, &m.content)),
+                    tool_call_id: None,
+                    tool_calls: None,
+                }
+            })
+            .collect()
+    }
+
+    fn build_request(
+        &self,
+        request: &ProviderChatRequest<'_>,
+        tools: Option<Vec<serde_json::Value>>,
+        temperature: f64,
+        stream: bool,
+    ) -> NativeChatRequest {
+        NativeChatRequest {
+            messages: Self::convert_messages(request.messages),
+            temperature,
+            max_tokens: request.max_tokens.or(self.max_tokens_override),
+            tool_choice: openai_tool_choice(request.tool_choice, tools.is_some()),
+            tools,
+            stop: openai_stop(request.stop),
+            response_format: openai_response_format(request.response_format),
+            stream: stream.then_some(true),
+            stream_options: stream.then(|| serde_json::json!({ "include_usage": true })),
+        }
+    }
+
+    async fn send(
+        &self,
+        model: &str,
+        body: &NativeChatRequest,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let auth = self.auth()?;
+        let client = self.http_client();
+        let builder = client.post(self.chat_url(model)?).json(body);
+        let response = auth.apply(builder, &client).await?.send().await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("Azure OpenAI", response).await);
+        }
+
+        let native_response: NativeChatResponse = response.json().await?;
+        let usage = native_response.usage.map(|u| TokenUsage {
+            input_tokens: u.prompt_tokens,
+            output_tokens: u.completion_tokens,
+        });
+        let message = native_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .ok_or_else(|| anyhow::anyhow!("No response from Azure OpenAI"))?;
+
+        Ok(ProviderChatResponse {
+            text: message.content.filter(|text| !text.is_empty()),
+            tool_calls: message
+                .tool_calls
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tc| ProviderToolCall {
+                    id: tc.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                    name: tc.function.name,
+                    arguments: tc.function.arguments,
+                })
+                .collect(),
+            usage,
+            reasoning_content: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Provider for AzureOpenAiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
+        }
+        messages.push(ChatMessage::user(message));
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = ProviderChatRequest {
+            messages,
+            ..Default::default()
+        };
+        let body = self.build_request(&request, None, temperature, false);
+        Ok(self.send(model, &body).await?.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let tools = Self::convert_tools(request.tools);
+        let body = self.build_request(&request, tools, temperature, false);
+        self.send(model, &body).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let request = ProviderChatRequest {
+            messages,
+            ..Default::default()
+        };
+        let tools = (!tools.is_empty()).then(|| tools.to_vec());
+        let body = self.build_request(&request, tools, temperature, false);
+        self.send(model, &body).await
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_chat(
+            ProviderChatRequest {
+                messages,
+                ..Default::default()
+            },
+            model,
+            temperature,
+            options,
+        )
+    }
+
+    fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let prepared = self
+            .auth()
+            .and_then(|auth| Ok((auth, self.chat_url(model)?)));
+        let (auth, url) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return stream::once(async move { Err(StreamError::Provider(e.to_string())) })
+                    .boxed();
+            }
+        };
+
+        let tools = Self::convert_tools(request.tools);
+        let body = self.build_request(&request, tools, temperature, true);
+        let client = self.http_client();
+        let builder = client
+            .post(url)
+            .header("Accept", "text/event-stream")
+            .json(&body);
+
+        // Entra tokens may need fetching, so authenticate inside the stream.
+        stream::once(async move {
+            match auth.apply(builder, &client).await {
+                Ok(builder) => send_streaming_request(
+                    builder,
+                    "Azure OpenAI".to_string(),
+                    options.count_tokens,
+                    openai_sse_to_structured_chunks,
+                ),
+                Err(e) => {
+                    stream::once(async move { Err(StreamError::Provider(e.to_string())) }).boxed()
+                }
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config(endpoint: &str) -> AzureOpenAiConfig {
+        AzureOpenAiConfig {
+            endpoint: Some(endpoint.to_string()),
+            deployments: vec![AzureOpenAiDeploymentConfig {
+                model: "gpt-4o".into(),
+                deployment: "prod-gpt4o".into(),
+                endpoint: None,
+                api_version: None,
+            }],
+            ..AzureOpenAiConfig::default()
+        }
+    }
+
+    fn completion_body(content: &str) -> serde_json::Value {
+        serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": content}}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 3}
+        })
+    }
+
+    #[test]
+    fn maps_models_to_deployments() {
+        let mut cfg = config("https://res.openai.azure.com/openai/");
+        cfg.deployments.push(AzureOpenAiDeploymentConfig {
+            model: "text-embedding-3-small".into(),
+            deployment: "embed".into(),
+            endpoint: Some("https://other.openai.azure.com".into()),
+            api_version: Some("2025-01-01-preview".into()),
+        });
+        let provider = AzureOpenAiProvider::new(&cfg, None, Some("key"), None);
+
+        assert_eq!(
+            provider.chat_url("gpt-4o").unwrap(),
+            "https://res.openai.azure.com/openai/deployments/prod-gpt4o/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(
+            provider.target("text-embedding-3-small").unwrap(),
+            DeploymentTarget {
+                endpoint: "https://other.openai.azure.com".into(),
+                deployment: "embed".into(),
+                api_version: "2025-01-01-preview".into(),
+            }
+        );
+        // Unmapped models are used as deployment names.
+        assert_eq!(
+            provider.target("my-deploy").unwrap().deployment,
+            "my-deploy"
+        );
+    }
+
+    #[test]
+    fn api_url_is_endpoint_fallback() {
+        let cfg = AzureOpenAiConfig::default();
+        let provider =
+            AzureOpenAiProvider::new(&cfg, Some("https://res.openai.azure.com"), None, None);
+        assert_eq!(
+            provider.target("gpt-4o").unwrap().endpoint,
+            "https://res.openai.azure.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn chat_fails_without_credentials() {
+        let provider = AzureOpenAiProvider::new(&config("https://res"), None, None, None);
+        let err = provider
+            .chat_with_system(None, "hello", "gpt-4o", 0.7)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("credentials not set"));
+    }
+
+    #[tokio::test]
+    async fn chat_uses_deployment_url_and_api_key_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/prod-gpt4o/chat/completions"))
+            .and(query_param("api-version", "2024-10-21"))
+            .and(header("api-key", "azure-test-key"))
+            .and(body_partial_json(serde_json::json!({
+                "tools": [{"type": "function", "function": {"name": "shell"}}],
+                "tool_choice": "auto",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "shell", "arguments": "{\"command\":\"ls\"}"}
+                    }]
+                }}],
+                "usage": {"prompt_tokens": 20, "completion_tokens": 5}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider =
+            AzureOpenAiProvider::new(&config(&server.uri()), None, Some("azure-test-key"), None);
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let messages = vec![ChatMessage::user("list files")];
+        let response = provider
+            .chat(
+                ProviderChatRequest {
+                    messages: &messages,
+                    tools: Some(&tools),
+                    ..Default::default()
+                },
+                "gpt-4o",
+                0.0,
+            )
+            .await
+            .unwrap();
+
+        assert!(response.text.is_none());
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.usage.unwrap().input_tokens, Some(20));
+    }
+
+    #[tokio::test]
+    async fn chat_sends_images_as_content_parts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{"role": "user", "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="}}
+                ]}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion_body("A cat")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = AzureOpenAiProvider::new(&config(&server.uri()), None, Some("k"), None);
+        assert!(provider.supports_vision());
+        let reply = provider
+            .chat_with_history(
+                &[ChatMessage::user(
+                    "What is this? [IMAGE:data:image/png;base64,iVBORw0KGgo=]",
+                )],
+                "gpt-4o",
+                0.0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply, "A cat");
+    }
+
+    #[tokio::test]
+    async fn entra_client_credentials_fetch_and_cache_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tenant/oauth2/v2.0/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "entra-token",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/prod-gpt4o/chat/completions"))
+            .and(header("authorization", "Bearer entra-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion_body("ok")))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut provider = AzureOpenAiProvider::new(&config(&server.uri()), None, None, None);
+        provider.auth = Some(Arc::new(AzureAuth::ClientCredentials(
+            EntraClientCredentials::new(
+                &format!("{}/tenant/oauth2/v2.0/token", server.uri()),
+                "client",
+                "secret",
+            ),
+        )));
+
+        for _ in 0..2 {
+            let reply = provider
+                .chat_with_system(None, "hi", "gpt-4o", 0.0)
+                .await
+                .unwrap();
+            assert_eq!(reply, "ok");
+        }
+    }
+
+    #[test]
+    fn entra_mode_uses_configured_credential_as_bearer_token() {
+        let auth = AzureAuth::resolve(AzureOpenAiAuthMode::EntraId, Some(" token ")).unwrap();
+        assert!(matches!(auth, AzureAuth::Bearer(ref t) if t == "token"));
+        let auth = AzureAuth::resolve(AzureOpenAiAuthMode::ApiKey, Some("key")).unwrap();
+        assert!(matches!(auth, AzureAuth::ApiKey(ref k) if k == "key"));
+        assert!(AzureAuth::resolve(AzureOpenAiAuthMode::ApiKey, Some("  ")).is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_chat_streams_text_and_usage() {
+        use crate::providers::traits::StreamAccumulator;
+
+        let server = MockServer::start().await;
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":4,\"completion_tokens\":2}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/prod-gpt4o/chat/completions"))
+            .and(header("api-key", "k"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let provider = AzureOpenAiProvider::new(&config(&server.uri()), None, Some("k"), None);
+        let messages = vec![ChatMessage::user("hi")];
+        let mut stream =
+            provider.stream_chat_with_history(&messages, "gpt-4o", 0.0, StreamOptions::new(true));
+
+        let mut acc = StreamAccumulator::new();
+        while let Some(chunk) = stream.next().await {
+            acc.push(&chunk.unwrap());
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Hello"));
+        assert_eq!(response.usage.unwrap().output_tokens, Some(2));
+    }
+}
@@ -7,7 +7,7 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ProviderCapabilities, StreamChunk, StreamError, StreamOptions, StreamResult,
-    TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
+    TokenUsage, ToolCall as ProviderToolCall, ToolChoice, ToolsPayload,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -27,16 +27,16 @@ const DEFAULT_MAX_TOKENS: u32 = 4096;
 // ── AWS Credentials ─────────────────────────────────────────────
 
 /// Resolved AWS credentials for SigV4 signing.
-struct AwsCredentials {
-    access_key_id: String,
-    secret_access_key: String,
-    session_token: Option<String>,
-    region: String,
+pub(crate) struct AwsCredentials {
+    pub(crate) access_key_id: String,
+    pub(crate) secret_access_key: String,
+    pub(crate) session_token: Option<String>,
+    pub(crate) region: String,
 }
 
 impl AwsCredentials {
     /// Resolve credentials: first try environment variables, then EC2 IMDSv2.
-    fn from_env() -> anyhow::Result<Self> {
+    pub(crate) fn from_env() -> anyhow::Result<Self> {
         let access_key_id = env_required("AWS_ACCESS_KEY_ID")?;
         let secret_access_key = env_required("AWS_SECRET_ACCESS_KEY")?;
 
@@ -135,7 +135,7 @@ impl AwsCredentials {
     }
 
     /// Resolve credentials: env vars first, then EC2 IMDS.
-    async fn resolve() -> anyhow::Result<Self> {
+    pub(crate) async fn resolve() -> anyhow::Result<Self> {
         if let Ok(creds) = Self::from_env() {
             return Ok(creds);
         }
@@ -244,6 +244,70 @@ fn build_authorization_header(
     )
 }
 
+/// Build a signed JSON `POST` to `/model/{model_id}/{action}` on Bedrock Runtime.
+///
+/// `endpoint` overrides the regional `https://bedrock-runtime.{region}.amazonaws.com`
+/// base (VPC endpoints, local stand-ins); the signed `host` follows it.
+pub(crate) fn signed_model_request(
+    client: &Client,
+    credentials: &AwsCredentials,
+    endpoint: Option<&str>,
+    model_id: &str,
+    action: &str,
+    payload: Vec<u8>,
+) -> anyhow::Result<reqwest::RequestBuilder> {
+    let base = endpoint.map_or_else(
+        || format!("https://{}", credentials.host()),
+        |value| value.trim_end_matches('/').to_string(),
+    );
+    let parsed = reqwest::Url::parse(&base)?;
+    let host = match (parsed.host_str(), parsed.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => anyhow::bail!("Bedrock endpoint has no host: {base}"),
+    };
+
+    let url = format!("{base}/model/{model_id}/{action}");
+    let canonical_uri = format!(
+        "{}/model/{}/{action}",
+        parsed.path().trim_end_matches('/'),
+        BedrockProvider::encode_model_path(model_id)
+    );
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut headers_to_sign = vec![
+        ("content-type".to_string(), "application/json".to_string()),
+        ("host".to_string(), host),
+        ("x-amz-date".to_string(), amz_date.clone()),
+    ];
+    if let Some(ref token) = credentials.session_token {
+        headers_to_sign.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    headers_to_sign.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let authorization = build_authorization_header(
+        credentials,
+        "POST",
+        &canonical_uri,
+        "",
+        &headers_to_sign,
+        &payload,
+        &now,
+    );
+
+    let mut request = client
+        .post(url)
+        .header("content-type", "application/json")
+        .header("x-amz-date", &amz_date)
+        .header("authorization", &authorization);
+    if let Some(ref token) = credentials.session_token {
+        request = request.header("x-amz-security-token", token);
+    }
+
+    Ok(request.body(payload))
+}
+
 // ── Converse API Types (Request) ────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -368,12 +432,16 @@ enum SystemBlock {
 struct InferenceConfig {
     max_tokens: u32,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ToolConfig {
     tools: Vec<ToolDefinition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -834,7 +902,27 @@ impl BedrockProvider {
                 },
             })
             .collect();
-        Some(ToolConfig { tools: tool_defs })
+        Some(ToolConfig {
+            tools: tool_defs,
+            tool_choice: None,
+        })
+    }
+
+    /// Apply a [`ToolChoice`] to the Converse tool configuration.
+    /// `ToolChoice::None` drops the tools entirely since Converse has no
+    /// "none" mode.
+    fn apply_tool_choice(
+        tool_config: Option<ToolConfig>,
+        choice: Option<ToolChoice<'_>>,
+    ) -> Option<ToolConfig> {
+        let mut config = tool_config?;
+        config.tool_choice = match choice {
+            None | Some(ToolChoice::Auto) => None,
+            Some(ToolChoice::None) => return None,
+            Some(ToolChoice::Required) => Some(serde_json::json!({ "any": {} })),
+            Some(ToolChoice::Tool(name)) => Some(serde_json::json!({ "tool": { "name": name } })),
+        };
+        Some(config)
     }
 
     // ── Response parsing ────────────────────────────────────────
@@ -1265,6 +1353,7 @@ impl Provider for BedrockProvider {
             inference_config: Some(InferenceConfig {
                 max_tokens: DEFAULT_MAX_TOKENS,
                 temperature,
+                stop_sequences: None,
             }),
             tool_config: None,
         };
@@ -1312,14 +1401,18 @@ impl Provider for BedrockProvider {
             }
         }
 
-        let tool_config = Self::convert_tools_to_converse(request.tools);
+        let tool_config = Self::apply_tool_choice(
+            Self::convert_tools_to_converse(request.tools),
+            request.tool_choice,
+        );
 
         let converse_request = ConverseRequest {
             system,
             messages: converse_messages,
             inference_config: Some(InferenceConfig {
-                max_tokens: DEFAULT_MAX_TOKENS,
+                max_tokens: request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
                 temperature,
+                stop_sequences: (!request.stop.is_empty()).then(|| request.stop.to_vec()),
             }),
             tool_config,
         };
@@ -1376,6 +1469,7 @@ impl Provider for BedrockProvider {
             inference_config: Some(InferenceConfig {
                 max_tokens: DEFAULT_MAX_TOKENS,
                 temperature,
+                stop_sequences: None,
             }),
             tool_config: None,
         };
@@ -1883,6 +1977,7 @@ mod tests {
             inference_config: Some(InferenceConfig {
                 max_tokens: 4096,
                 temperature: 0.7,
+                stop_sequences: None,
             }),
             tool_config: None,
         };
@@ -2274,4 +2369,30 @@ mod tests {
         let delta: ContentBlockDelta = serde_json::from_str(json).unwrap();
         assert!(delta.delta.text.is_none());
     }
+
+    #[test]
+    fn tool_choice_maps_to_converse_tool_config() {
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let config = |choice| {
+            BedrockProvider::apply_tool_choice(
+                BedrockProvider::convert_tools_to_converse(Some(&tools)),
+                choice,
+            )
+        };
+
+        let auto = serde_json::to_value(config(Some(ToolChoice::Auto)).unwrap()).unwrap();
+        assert!(auto.get("toolChoice").is_none());
+        let any = serde_json::to_value(config(Some(ToolChoice::Required)).unwrap()).unwrap();
+        assert_eq!(any["toolChoice"], serde_json::json!({"any": {}}));
+        let tool = serde_json::to_value(config(Some(ToolChoice::Tool("shell"))).unwrap()).unwrap();
+        assert_eq!(
+            tool["toolChoice"],
+            serde_json::json!({"tool": {"name": "shell"}})
+        );
+        assert!(config(Some(ToolChoice::None)).is_none());
+    }
 }
@@ -0,0 +1,679 @@
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, ResponseFormat, StreamChunk,
+    StreamOptions, StreamResult, ToolsPayload,
+};
+use super::Provider;
+use crate::config::Config;
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::memory::response_cache::{CachedResponse, ResponseCache};
+use crate::observability::{Observer, ObserverEvent};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::stream;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Embedding-similarity matching for near-duplicate prompts.
+struct SemanticMatch {
+    embedder: Arc<dyn EmbeddingProvider>,
+    min_similarity: f32,
+}
+
+/// Response-caching decorator — serves repeated prompts from [`ResponseCache`].
+///
+/// Only tool-free, deterministic turns are cached: no tools offered, no tool
+/// results in the history, and `temperature == 0`. Everything else, including
+/// streaming, passes straight through to the wrapped provider.
+///
+/// Hits and misses are reported as [`ObserverEvent::ResponseCacheLookup`] and
+/// counted on the cache itself (surfaced by `/api/cost`).
+pub struct CachingProvider {
+    inner: Box<dyn Provider>,
+    cache: Arc<ResponseCache>,
+    observer: Arc<dyn Observer>,
+    semantic: Option<SemanticMatch>,
+}
+
+/// Cache identity of a request.
+struct CacheKeys<'a> {
+    /// Hash of the whole request.
+    exact: String,
+    /// Hash of the request minus the final user message; semantic matches
+    /// must agree on everything but that message.
+    scope: String,
+    /// The final user message, compared by embedding in semantic mode.
+    last_user: Option<&'a str>,
+}
+
+impl CachingProvider {
+    pub fn new(
+        inner: Box<dyn Provider>,
+        cache: Arc<ResponseCache>,
+        observer: Arc<dyn Observer>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            observer,
+            semantic: None,
+        }
+    }
+
+    /// Also serve prompts whose final user message embeds within
+    /// `min_similarity` (cosine) of a cached one.
+    pub fn with_semantic_match(
+        mut self,
+        embedder: Arc<dyn EmbeddingProvider>,
+        min_similarity: f32,
+    ) -> Self {
+        self.semantic = Some(SemanticMatch {
+            embedder,
+            min_similarity,
+        });
+        self
+    }
+
+    fn is_cacheable(
+        messages: &[ChatMessage],
+        tools: Option<&[ToolSpec]>,
+        temperature: f64,
+    ) -> bool {
+        temperature == 0.0
+            && tools.is_none_or(<[ToolSpec]>::is_empty)
+            && !messages.iter().any(|m| m.role == "tool")
+    }
+
+    fn cache_keys<'a>(
+        model: &str,
+        messages: &'a [ChatMessage],
+        max_tokens: Option<u32>,
+        stop: &[String],
+        response_format: Option<&ResponseFormat>,
+    ) -> CacheKeys<'a> {
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        let system = system.join("\n\n");
+        let turns: Vec<&ChatMessage> = messages.iter().filter(|m| m.role != "system").collect();
+
+        let mut prefix =
+            format!("max_tokens={max_tokens:?};stop={stop:?};format={response_format:?}");
+        let last_user = match turns.last() {
+            Some(last) if last.role == "user" => Some(last.content.as_str()),
+            _ => None,
+        };
+        let scoped_turns = if last_user.is_some() {
+            &turns[..turns.len() - 1]
+        } else {
+            &turns[..]
+        };
+        for turn in scoped_turns {
+            let _ = write!(prefix, "\u{1e}{}\u{1f}{}", turn.role, turn.content);
+        }
+
+        let scope = ResponseCache::cache_key(model, Some(&system), &format!("scope|{prefix}"));
+        let exact = match last_user {
+            Some(text) => format!("{prefix}\u{1e}user\u{1f}{text}"),
+            None => prefix,
+        };
+        CacheKeys {
+            exact: ResponseCache::cache_key(model, Some(&system), &exact),
+            scope,
+            last_user,
+        }
+    }
+
+    fn record_lookup(&self, model: &str, hit: Option<(&CachedResponse, bool)>) {
+        match hit {
+            Some((entry, semantic)) => self.cache.record_hit(entry, semantic),
+            None => self.cache.record_miss(),
+        }
+        self.observer
+            .record_event(&ObserverEvent::ResponseCacheLookup {
+                model: model.to_string(),
+                hit: hit.is_some(),
+                semantic: hit.is_some_and(|(_, semantic)| semantic),
+            });
+    }
+
+    async fn embed_prompt(&self, text: &str) -> Option<Vec<f32>> {
+        let semantic = self.semantic.as_ref()?;
+        match semantic.embedder.embed_one(text).await {
+            Ok(embedding) if !embedding.is_empty() => Some(embedding),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Response cache: prompt embedding failed, exact match only: {e}");
+                None
+            }
+        }
+    }
+
+    /// Serve `keys` from the cache, or run `call` and cache its text answer.
+    async fn through_cache(
+        &self,
+        model: &str,
+        keys: CacheKeys<'_>,
+        call: impl Future<Output = anyhow::Result<ChatResponse>>,
+    ) -> anyhow::Result<ChatResponse> {
+        let exact = self.cache.get_entry(&keys.exact).unwrap_or_else(|e| {
+            tracing::warn!("Response cache lookup failed: {e}");
+            None
+        });
+        if let Some(entry) = exact {
+            self.record_lookup(model, Some((&entry, false)));
+            return Ok(cached_response(entry));
+        }
+
+        let embedding = match keys.last_user {
+            Some(text) => self.embed_prompt(text).await,
+            None => None,
+        };
+        if let (Some(semantic), Some(embedding)) = (&self.semantic, &embedding) {
+            let similar = self
+                .cache
+                .get_similar(&keys.scope, embedding, semantic.min_similarity)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Response cache semantic lookup failed: {e}");
+                    None
+                });
+            if let Some(entry) = similar {
+                self.record_lookup(model, Some((&entry, true)));
+                return Ok(cached_response(entry));
+            }
+        }
+
+        self.record_lookup(model, None);
+        let response = call.await?;
+
+        if let Some(text) = response.text.as_deref().filter(|t| !t.trim().is_empty()) {
+            if !response.has_tool_calls() {
+                let tokens = response.usage.as_ref().map_or(0, |usage| {
+                    usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0)
+                });
+                if let Err(e) = self.cache.put_scoped(
+                    &keys.exact,
+                    Some(&keys.scope),
+                    model,
+                    text,
+                    u32::try_from(tokens).unwrap_or(u32::MAX),
+                    embedding.as_deref(),
+                ) {
+                    tracing::warn!("Response cache store failed: {e}");
+                }
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// A cache hit as a provider response. No tokens were spent, so no usage.
+fn cached_response(entry: CachedResponse) -> ChatResponse {
+    ChatResponse {
+        text: Some(entry.response),
+        tool_calls: Vec::new(),
+        usage: None,
+        reasoning_content: None,
+    }
+}
+
+fn text_response(text: String) -> ChatResponse {
+    ChatResponse {
+        text: Some(text),
+        tool_calls: Vec::new(),
+        usage: None,
+        reasoning_content: None,
+    }
+}
+
+#[async_trait]
+impl Provider for CachingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let call = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature);
+        if temperature != 0.0 {
+            return call.await;
+        }
+
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system) = system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(message));
+        let keys = Self::cache_keys(model, &messages, None, &[], None);
+        let response = self
+            .through_cache(model, keys, async { call.await.map(text_response) })
+            .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let call = self.inner.chat_with_history(messages, model, temperature);
+        if !Self::is_cacheable(messages, None, temperature) {
+            return call.await;
+        }
+
+        let keys = Self::cache_keys(model, messages, None, &[], None);
+        let response = self
+            .through_cache(model, keys, async { call.await.map(text_response) })
+            .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        if !Self::is_cacheable(request.messages, request.tools, temperature) {
+            return self.inner.chat(request, model, temperature).await;
+        }
+
+        let keys = Self::cache_keys(
+            model,
+            request.messages,
+            request.max_tokens,
+            request.stop,
+            request.response_format,
+        );
+        self.through_cache(model, keys, self.inner.chat(request, model, temperature))
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        self.inner.supports_structured_output()
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        self.inner.supports_streaming_tool_calls()
+    }
+
+    fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner.stream_chat(request, model, temperature, options)
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+/// Wrap `provider` in a [`CachingProvider`] when a response cache is
+/// configured (`[memory] response_cache_enabled`), adding semantic matching
+/// when `response_cache_semantic` is set and an embedding provider exists.
+pub fn wrap_with_response_cache(
+    provider: Box<dyn Provider>,
+    cache: Option<&Arc<ResponseCache>>,
+    config: &Config,
+    observer: Arc<dyn Observer>,
+) -> Box<dyn Provider> {
+    let Some(cache) = cache else {
+        return provider;
+    };
+    let mut caching = CachingProvider::new(provider, Arc::clone(cache), observer);
+
+    if config.memory.response_cache_semantic {
+        let embedder: Arc<dyn EmbeddingProvider> = Arc::from(crate::memory::create_embedder(
+            &config.memory,
+            &config.embedding_routes,
+            config.api_key.as_deref(),
+            &config.reliability,
+        ));
+        if embedder.dimensions() == 0 {
+            tracing::warn!(
+                "response_cache_semantic needs [memory] embedding_provider; using exact matching only"
+            );
+        } else {
+            #[allow(clippy::cast_possible_truncation)]
+            let threshold = config.memory.response_cache_similarity_threshold as f32;
+            caching = caching.with_semantic_match(embedder, threshold);
+        }
+    }
+
+    Box::new(caching)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+    use crate::providers::traits::TokenUsage;
+    use parking_lot::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(format!("answer {n} to {message}"))
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let last = request.messages.last().map_or("", |m| m.content.as_str());
+            Ok(ChatResponse {
+                text: Some(format!("answer {n} to {last}")),
+                tool_calls: Vec::new(),
+                usage: Some(TokenUsage {
+                    input_tokens: Some(12),
+                    output_tokens: Some(8),
+                }),
+                reasoning_content: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        lookups: Mutex<Vec<(bool, bool)>>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::ResponseCacheLookup { hit, semantic, .. } = event {
+                self.lookups.lock().push((*hit, *semantic));
+            }
+        }
+
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    /// Embeds texts by their words' first letters, so "hi there" and
+    /// "hello there" collide while unrelated prompts do not.
+    struct InitialsEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for InitialsEmbedding {
+        fn name(&self) -> &str {
+            "initials"
+        }
+
+        fn dimensions(&self) -> usize {
+            26
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut emb = vec![0.0; 26];
+                    for word in text.split_whitespace() {
+                        if let Some(c) = word.bytes().next().filter(u8::is_ascii_lowercase) {
+                            emb[usize::from(c - b'a')] += 1.0;
+                        }
+                    }
+                    emb
+                })
+                .collect())
+        }
+    }
+
+    struct Fixture {
+        _tmp: TempDir,
+        cache: Arc<ResponseCache>,
+        calls: Arc<AtomicUsize>,
+        observer: Arc<RecordingObserver>,
+        provider: CachingProvider,
+    }
+
+    fn fixture() -> Fixture {
+        let tmp = TempDir::new().unwrap();
+        let cache = Arc::new(ResponseCache::new(tmp.path(), 60, 100).unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let observer = Arc::new(RecordingObserver::default());
+        let provider = CachingProvider::new(
+            Box::new(CountingProvider {
+                calls: Arc::clone(&calls),
+            }),
+            Arc::clone(&cache),
+            observer.clone(),
+        );
+        Fixture {
+            _tmp: tmp,
+            cache,
+            calls,
+            observer,
+            provider,
+        }
+    }
+
+    fn request(messages: &[ChatMessage]) -> ChatRequest<'_> {
+        ChatRequest {
+            messages,
+            ..ChatRequest::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_deterministic_chat_is_served_from_cache() {
+        let f = fixture();
+        let messages = [ChatMessage::system("be brief"), ChatMessage::user("hello")];
+
+        let first = f.provider.chat(request(&messages), "m", 0.0).await.unwrap();
+        let second = f.provider.chat(request(&messages), "m", 0.0).await.unwrap();
+
+        assert_eq!(f.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.text, second.text);
+        assert!(second.usage.is_none(), "cache hits spend no tokens");
+        assert_eq!(
+            *f.observer.lookups.lock(),
+            vec![(false, false), (true, false)]
+        );
+
+        let counters = f.cache.counters();
+        assert_eq!((counters.hits, counters.misses), (1, 1));
+        assert_eq!(counters.tokens_saved, 20);
+    }
+
+    #[tokio::test]
+    async fn non_deterministic_and_tool_turns_bypass_cache() {
+        let f = fixture();
+        let messages = [ChatMessage::user("hello")];
+        f.provider.chat(request(&messages), "m", 0.7).await.unwrap();
+        f.provider.chat(request(&messages), "m", 0.7).await.unwrap();
+
+        let tools = [ToolSpec {
+            name: "shell".into(),
+            description: "run".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let with_tools = ChatRequest {
+            messages: &messages,
+            tools: Some(&tools),
+            ..ChatRequest::default()
+        };
+        f.provider.chat(with_tools, "m", 0.0).await.unwrap();
+        f.provider.chat(with_tools, "m", 0.0).await.unwrap();
+
+        let with_results = [
+            ChatMessage::user("hello"),
+            ChatMessage::tool(r#"{"tool_call_id":"1","content":"ok"}"#),
+        ];
+        f.provider
+            .chat(request(&with_results), "m", 0.0)
+            .await
+            .unwrap();
+        f.provider
+            .chat(request(&with_results), "m", 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(f.calls.load(Ordering::SeqCst), 6);
+        assert!(f.observer.lookups.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn key_covers_model_history_and_generation_controls() {
+        let f = fixture();
+        let messages = [ChatMessage::user("hello")];
+        let history = [
+            ChatMessage::user("earlier"),
+            ChatMessage::assistant("reply"),
+            ChatMessage::user("hello"),
+        ];
+        let capped = ChatRequest {
+            messages: &messages,
+            max_tokens: Some(5),
+            ..ChatRequest::default()
+        };
+
+        f.provider.chat(request(&messages), "m", 0.0).await.unwrap();
+        f.provider
+            .chat(request(&messages), "other", 0.0)
+            .await
+            .unwrap();
+        f.provider.chat(request(&history), "m", 0.0).await.unwrap();
+        f.provider.chat(capped, "m", 0.0).await.unwrap();
+
+        assert_eq!(f.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn simple_chat_paths_are_cached() {
+        let f = fixture();
+        let a = f.provider.simple_chat("ping", "m", 0.0).await.unwrap();
+        let b = f.provider.simple_chat("ping", "m", 0.0).await.unwrap();
+        assert_eq!(a, b);
+        assert_eq!(f.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn semantic_mode_serves_near_duplicate_prompts() {
+        let tmp = TempDir::new().unwrap();
+        let cache = Arc::new(ResponseCache::new(tmp.path(), 60, 100).unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let observer = Arc::new(RecordingObserver::default());
+        let provider = CachingProvider::new(
+            Box::new(CountingProvider {
+                calls: Arc::clone(&calls),
+            }),
+            Arc::clone(&cache),
+            observer.clone(),
+        )
+        .with_semantic_match(Arc::new(InitialsEmbedding), 0.99);
+
+        let first = [ChatMessage::user("hi there")];
+        let near = [ChatMessage::user("hello there")];
+        let far = [ChatMessage::user("what time")];
+        let a = provider.chat(request(&first), "m", 0.0).await.unwrap();
+        let b = provider.chat(request(&near), "m", 0.0).await.unwrap();
+        provider.chat(request(&far), "m", 0.0).await.unwrap();
+
+        assert_eq!(a.text, b.text);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *observer.lookups.lock(),
+            vec![(false, false), (true, true), (false, false)]
+        );
+        assert_eq!(cache.counters().semantic_hits, 1);
+    }
+
+    #[test]
+    fn wrap_is_noop_without_cache() {
+        let provider: Box<dyn Provider> = Box::new(CountingProvider {
+            calls: Arc::new(AtomicUsize::new(0)),
+        });
+        let wrapped =
+            wrap_with_response_cache(provider, None, &Config::default(), Arc::new(NoopObserver));
+        assert!(!wrapped.supports_native_tools());
+    }
+}
@@ -5,8 +5,8 @@
 use crate::multimodal;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
-    ToolCall as ProviderToolCall,
+    Provider, ResponseFormat, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
+    ToolCall as ProviderToolCall, ToolCallDelta, ToolChoice,
 };
 use async_trait::async_trait;
 use futures_util::{stream, SinkExt, StreamExt};
@@ -596,7 +596,50 @@ struct NativeChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tool_choice: Option<String>,
+    tool_choice: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+    /// Ask for a trailing usage chunk when streaming.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<Value>,
+}
+
+/// OpenAI `tool_choice` for a request; omitted when no tools are sent.
+pub(crate) fn openai_tool_choice(choice: Option<ToolChoice<'_>>, has_tools: bool) -> Option<Value> {
+    if !has_tools {
+        return None;
+    }
+    Some(match choice.unwrap_or(ToolChoice::Auto) {
+        ToolChoice::None => Value::from("none"),
+        ToolChoice::Auto => Value::from("auto"),
+        ToolChoice::Required => Value::from("required"),
+        ToolChoice::Tool(name) => serde_json::json!({
+            "type": "function",
+            "function": { "name": name }
+        }),
+    })
+}
+
+/// OpenAI `response_format` payload.
+pub(crate) fn openai_response_format(format: Option<&ResponseFormat>) -> Option<Value> {
+    format.map(|format| match format {
+        ResponseFormat::JsonObject => serde_json::json!({ "type": "json_object" }),
+        ResponseFormat::JsonSchema {
+            name,
+            schema,
+            strict,
+        } => serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": name, "schema": schema, "strict": strict }
+        }),
+    })
+}
+
+/// OpenAI `stop` payload; omitted when empty.
+pub(crate) fn openai_stop(stop: &[String]) -> Option<Vec<String>> {
+    (!stop.is_empty()).then(|| stop.to_vec())
 }
 
 #[derive(Debug, Serialize)]
@@ -695,61 +738,120 @@ struct ResponsesWebSocketCreateEvent {
 /// Server-Sent Event stream chunk for OpenAI-compatible streaming.
 #[derive(Debug, Deserialize)]
 struct StreamChunkResponse {
+    #[serde(default)]
     choices: Vec<StreamChoice>,
+    /// Present on the trailing chunk when `stream_options.include_usage` is set.
+    #[serde(default)]
+    usage: Option<UsageInfo>,
 }
 
 #[derive(Debug, Deserialize)]
 struct StreamChoice {
+    #[serde(default)]
     delta: StreamDelta,
+    #[serde(default)]
     finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct StreamDelta {
     #[serde(default)]
     content: Option<String>,
     /// Reasoning/thinking models may stream output via `reasoning_content`.
     #[serde(default)]
     reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<StreamToolCallDelta>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamToolCallDelta {
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<StreamFunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamFunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+/// Extract the JSON payload of an SSE `data:` line, skipping blanks,
+/// comments and the `[DONE]` sentinel.
+fn sse_data(line: &str) -> Option<&str> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    (data != "[DONE]" && !data.is_empty()).then_some(data)
 }
 
 /// Parse SSE (Server-Sent Events) stream from OpenAI-compatible providers.
 /// Handles the `data: {...}` format and `[DONE]` sentinel.
 fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
-    let line = line.trim();
-
-    // Skip empty lines and comments
-    if line.is_empty() || line.starts_with(':') {
+    let Some(data) = sse_data(line) else {
         return Ok(None);
-    }
+    };
 
-    // SSE format: "data: {...}"
-    if let Some(data) = line.strip_prefix("data:") {
-        let data = data.trim();
+    // Parse JSON delta
+    let chunk: StreamChunkResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
 
-        // Check for [DONE] sentinel
-        if data == "[DONE]" {
-            return Ok(None);
+    // Extract content from delta
+    if let Some(choice) = chunk.choices.first() {
+        if let Some(content) = &choice.delta.content {
+            if !content.is_empty() {
+                return Ok(Some(content.clone()));
+            }
         }
+        // Fallback to reasoning_content for thinking models
+        if let Some(reasoning) = &choice.delta.reasoning_content {
+            return Ok(Some(reasoning.clone()));
+        }
+    }
 
-        // Parse JSON delta
-        let chunk: StreamChunkResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
+    Ok(None)
+}
 
-        // Extract content from delta
-        if let Some(choice) = chunk.choices.first() {
-            if let Some(content) = &choice.delta.content {
-                if !content.is_empty() {
-                    return Ok(Some(content.clone()));
-                }
-            }
-            // Fallback to reasoning_content for thinking models
-            if let Some(reasoning) = &choice.delta.reasoning_content {
-                return Ok(Some(reasoning.clone()));
-            }
+/// Parse one SSE line into a structured chunk: content, reasoning and
+/// tool-call fragments stay separate, and the trailing usage chunk is kept.
+fn parse_sse_line_structured(line: &str) -> StreamResult<Option<StreamChunk>> {
+    let Some(data) = sse_data(line) else {
+        return Ok(None);
+    };
+    let parsed: StreamChunkResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
+
+    let mut chunk = StreamChunk::default();
+    if let Some(choice) = parsed.choices.into_iter().next() {
+        chunk.delta = choice.delta.content.unwrap_or_default();
+        chunk.reasoning_delta = choice.delta.reasoning_content.unwrap_or_default();
+        for (position, call) in choice
+            .delta
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let (name, arguments) = call
+                .function
+                .map(|function| (function.name, function.arguments.unwrap_or_default()))
+                .unwrap_or_default();
+            chunk.tool_call_deltas.push(ToolCallDelta {
+                index: call.index.unwrap_or(position),
+                id: call.id,
+                name,
+                arguments,
+            });
         }
     }
+    chunk.usage = parsed.usage.map(|u| TokenUsage {
+        input_tokens: u.prompt_tokens,
+        output_tokens: u.completion_tokens,
+    });
 
-    Ok(None)
+    Ok((!chunk.is_empty()).then_some(chunk))
 }
 
 /// Convert SSE byte stream to text chunks.
@@ -757,6 +859,78 @@ fn sse_bytes_to_chunks(
     response: reqwest::Response,
     count_tokens: bool,
 ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+    response_lines_to_chunks(response, count_tokens, |line| {
+        parse_sse_line(line).map(|content| content.map(StreamChunk::delta))
+    })
+}
+
+/// Convert an OpenAI-style chat completions SSE response into structured
+/// chunks (text, reasoning, tool-call fragments, usage).
+pub(crate) fn openai_sse_to_structured_chunks(
+    response: reqwest::Response,
+    count_tokens: bool,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+    response_lines_to_chunks(response, count_tokens, parse_sse_line_structured)
+}
+
+/// Send a streaming request and forward the chunks produced by `to_chunks`.
+/// Non-2xx responses surface as a single sanitized [`StreamError::Provider`].
+pub(crate) fn send_streaming_request<F>(
+    request_builder: reqwest::RequestBuilder,
+    provider_name: String,
+    count_tokens: bool,
+    to_chunks: F,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>>
+where
+    F: FnOnce(reqwest::Response, bool) -> stream::BoxStream<'static, StreamResult<StreamChunk>>
+        + Send
+        + 'static,
+{
+    let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
+    tokio::spawn(async move {
+        let response = match request_builder.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = tx.send(Err(StreamError::Http(e))).await;
+                return;
+            }
+        };
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let sanitized = super::sanitize_api_error(&body);
+            let _ = tx
+                .send(Err(StreamError::Provider(format!(
+                    "{provider_name} API error ({status}): {sanitized}"
+                ))))
+                .await;
+            return;
+        }
+
+        let mut chunks = to_chunks(response, count_tokens);
+        while let Some(chunk) = chunks.next().await {
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    })
+    .boxed()
+}
+
+/// Split a streaming response body into lines (SSE or NDJSON) and turn each
+/// line into chunks with `parse_line`, which may keep state across lines.
+pub(crate) fn response_lines_to_chunks<F>(
+    response: reqwest::Response,
+    count_tokens: bool,
+    mut parse_line: F,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>>
+where
+    F: FnMut(&str) -> StreamResult<Option<StreamChunk>> + Send + 'static,
+{
     // Create a channel to send chunks
     let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
 
@@ -797,11 +971,9 @@ fn sse_bytes_to_chunks(
                     // Process complete lines
                     while let Some(pos) = buffer.find('\n') {
                         let line = buffer.drain(..=pos).collect::<String>();
-                        buffer = buffer[pos + 1..].to_string();
 
-                        match parse_sse_line(&line) {
-                            Ok(Some(content)) => {
-                                let mut chunk = StreamChunk::delta(content);
+                        match parse_line(&line) {
+                            Ok(Some(mut chunk)) => {
                                 if count_tokens {
                                     chunk = chunk.with_token_estimate();
                                 }
@@ -2031,10 +2203,13 @@ impl Provider for OpenAiCompatibleProvider {
                 !self.merge_system_into_user,
             ),
             temperature,
-            max_tokens: self.effective_max_tokens(),
+            max_tokens: request.max_tokens.or_else(|| self.effective_max_tokens()),
             stream: Some(false),
-            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tool_choice: openai_tool_choice(request.tool_choice, tools.is_some()),
             tools,
+            stop: openai_stop(request.stop),
+            response_format: openai_response_format(request.response_format),
+            stream_options: None,
         };
 
         if self.should_use_responses_mode() {
@@ -2250,6 +2425,69 @@ impl Provider for OpenAiCompatibleProvider {
         .boxed()
     }
 
+    fn supports_structured_output(&self) -> bool {
+        !self.should_use_responses_mode()
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        self.native_tool_calling && !self.should_use_responses_mode()
+    }
+
+    fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.clone() else {
+            let provider_name = self.name.clone();
+            return stream::once(async move {
+                Err(StreamError::Provider(format!(
+                    "{provider_name} API key not set"
+                )))
+            })
+            .boxed();
+        };
+
+        let tools = Self::convert_tool_specs(request.tools);
+        let effective_messages = if self.merge_system_into_user {
+            Self::flatten_system_messages(request.messages)
+        } else {
+            request.messages.to_vec()
+        };
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages_for_native(
+                &effective_messages,
+                !self.merge_system_into_user,
+            ),
+            temperature,
+            max_tokens: request.max_tokens.or_else(|| self.effective_max_tokens()),
+            stream: Some(true),
+            tool_choice: openai_tool_choice(request.tool_choice, tools.is_some()),
+            tools,
+            stop: openai_stop(request.stop),
+            response_format: openai_response_format(request.response_format),
+            stream_options: Some(serde_json::json!({ "include_usage": true })),
+        };
+
+        let request_builder = self
+            .apply_auth_header(
+                self.http_client()
+                    .post(self.chat_completions_url())
+                    .json(&native_request),
+                &credential,
+            )
+            .header("Accept", "text/event-stream");
+        send_streaming_request(
+            request_builder,
+            self.name.clone(),
+            options.count_tokens,
+            openai_sse_to_structured_chunks,
+        )
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(credential) = self.credential.as_ref() {
             // Hit the chat completions URL with a GET to establish the connection pool.
@@ -3589,4 +3827,131 @@ mod tests {
         );
         assert!(json.contains("thinking..."));
     }
+
+    #[test]
+    fn structured_sse_parser_separates_content_reasoning_and_tool_calls() {
+        let reasoning = parse_sse_line_structured(
+            r#"data: {"choices":[{"delta":{"reasoning_content":"hmm"}}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(reasoning.reasoning_delta, "hmm");
+        assert!(reasoning.delta.is_empty());
+
+        let call = parse_sse_line_structured(
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_9","type":"function","function":{"name":"shell","arguments":""}}]}}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(call.tool_call_deltas.len(), 1);
+        assert_eq!(call.tool_call_deltas[0].index, 1);
+        assert_eq!(call.tool_call_deltas[0].id.as_deref(), Some("call_9"));
+        assert_eq!(call.tool_call_deltas[0].name.as_deref(), Some("shell"));
+
+        let usage = parse_sse_line_structured(
+            r#"data: {"choices":[],"usage":{"prompt_tokens":10,"completion_tokens":4}}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(usage.usage.unwrap().output_tokens, Some(4));
+
+        assert!(parse_sse_line_structured("data: [DONE]").unwrap().is_none());
+        assert!(parse_sse_line_structured(": keep-alive").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_chat_streams_native_tool_calls() {
+        use crate::providers::traits::StreamAccumulator;
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Checking\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"shell\",\"arguments\":\"{\\\"command\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"ls\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":21,\"completion_tokens\":9}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "stream": true,
+                "stream_options": {"include_usage": true},
+                "tool_choice": "auto",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let provider = make_provider("test", &server.uri(), Some("key"));
+        assert!(provider.supports_streaming_tool_calls());
+        let tools = vec![crate::tools::ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let messages = vec![ChatMessage::user("list files")];
+        let mut stream = provider.stream_chat(
+            ProviderChatRequest {
+                messages: &messages,
+                tools: Some(&tools),
+                ..Default::default()
+            },
+            "model",
+            0.0,
+            StreamOptions::new(true),
+        );
+
+        let mut acc = StreamAccumulator::new();
+        let mut saw_final = false;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap();
+            saw_final |= chunk.is_final;
+            acc.push(&chunk);
+        }
+        assert!(saw_final);
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Checking"));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.usage.unwrap().input_tokens, Some(21));
+    }
+
+    #[test]
+    fn openai_generation_options_map_to_wire_format() {
+        assert!(openai_tool_choice(Some(ToolChoice::Required), false).is_none());
+        assert_eq!(
+            openai_tool_choice(None, true),
+            Some(serde_json::json!("auto"))
+        );
+        assert_eq!(
+            openai_tool_choice(Some(ToolChoice::None), true),
+            Some(serde_json::json!("none"))
+        );
+        assert_eq!(
+            openai_tool_choice(Some(ToolChoice::Required), true),
+            Some(serde_json::json!("required"))
+        );
+        assert_eq!(
+            openai_tool_choice(Some(ToolChoice::Tool("shell")), true),
+            Some(serde_json::json!({"type": "function", "function": {"name": "shell"}}))
+        );
+
+        let schema = serde_json::json!({"type": "object"});
+        let format = ResponseFormat::json_schema("person", schema.clone());
+        assert_eq!(
+            openai_response_format(Some(&format)),
+            Some(serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {"name": "person", "schema": schema, "strict": true}
+            }))
+        );
+        assert_eq!(
+            openai_response_format(Some(&ResponseFormat::JsonObject)),
+            Some(serde_json::json!({"type": "json_object"}))
+        );
+        assert!(openai_stop(&[]).is_none());
+    }
 }
@@ -5,10 +5,15 @@
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
 use crate::auth::AuthService;
-use crate::providers::traits::{ChatMessage, ChatResponse, Provider, TokenUsage};
+use crate::providers::compatible::{response_lines_to_chunks, send_streaming_request};
+use crate::providers::traits::{
+    ChatMessage, ChatResponse, Provider, StreamChunk, StreamError, StreamOptions, StreamResult,
+    TokenUsage,
+};
 use async_trait::async_trait;
 use base64::Engine;
 use directories::UserDirs;
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -139,11 +144,46 @@ struct Part {
     text: String,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, Default)]
 struct GenerationConfig {
     temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseJsonSchema", skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<serde_json::Value>,
+}
+
+/// Output cap used when the request does not set `max_tokens`.
+const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 8192;
+
+impl GenerationConfig {
+    fn new(temperature: f64) -> Self {
+        Self {
+            temperature,
+            max_output_tokens: DEFAULT_MAX_OUTPUT_TOKENS,
+            ..Self::default()
+        }
+    }
+
+    /// Map the generation options of a [`ChatRequest`](crate::providers::traits::ChatRequest).
+    fn from_request(request: &crate::providers::traits::ChatRequest<'_>, temperature: f64) -> Self {
+        let mut config = Self::new(temperature);
+        if let Some(max_tokens) = request.max_tokens {
+            config.max_output_tokens = max_tokens;
+        }
+        if !request.stop.is_empty() {
+            config.stop_sequences = Some(request.stop.to_vec());
+        }
+        if let Some(format) = request.response_format {
+            config.response_mime_type = Some("application/json".to_string());
+            config.response_json_schema = format.schema().cloned();
+        }
+        config
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -231,6 +271,48 @@ struct ApiError {
     message: String,
 }
 
+/// Parse one `streamGenerateContent?alt=sse` line. Thought parts become
+/// reasoning deltas; `usageMetadata` is cumulative, so the last one wins.
+fn parse_stream_line(line: &str) -> StreamResult<Option<StreamChunk>> {
+    let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+        return Ok(None);
+    };
+    if data.is_empty() {
+        return Ok(None);
+    }
+
+    let parsed: GenerateContentResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
+    let parsed = parsed.into_effective_response();
+    if let Some(err) = parsed.error {
+        return Err(StreamError::Provider(format!(
+            "Gemini API error: {}",
+            err.message
+        )));
+    }
+
+    let mut chunk = StreamChunk::default();
+    let parts = parsed
+        .candidates
+        .and_then(|c| c.into_iter().next())
+        .and_then(|c| c.content)
+        .map(|c| c.parts)
+        .unwrap_or_default();
+    for part in parts {
+        let Some(text) = part.text else { continue };
+        if part.thought {
+            chunk.reasoning_delta.push_str(&text);
+        } else {
+            chunk.delta.push_str(&text);
+        }
+    }
+    chunk.usage = parsed.usage_metadata.map(|u| TokenUsage {
+        input_tokens: u.prompt_token_count,
+        output_tokens: u.candidates_token_count,
+    });
+
+    Ok((!chunk.is_empty()).then_some(chunk))
+}
+
 impl GenerateContentResponse {
     /// cloudcode-pa wraps the actual response under `response`.
     fn into_effective_response(self) -> Self {
@@ -796,6 +878,44 @@ impl GeminiProvider {
         }
     }
 
+    /// Streaming URL for API-key auth (public endpoint, SSE framing).
+    fn build_stream_generate_content_url(model: &str, api_key: &str) -> String {
+        let model_name = Self::format_model_name(model);
+        format!("{PUBLIC_API_ENDPOINT}/{model_name}:streamGenerateContent?alt=sse&key={api_key}")
+    }
+
+    /// Split history into Gemini `contents` plus a joined system instruction.
+    fn convert_history(messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+        let mut system_parts: Vec<&str> = Vec::new();
+        let mut contents: Vec<Content> = Vec::new();
+
+        for msg in messages {
+            let role = match msg.role.as_str() {
+                "system" => {
+                    system_parts.push(&msg.content);
+                    continue;
+                }
+                "user" => "user",
+                "assistant" => "model",
+                _ => continue,
+            };
+            contents.push(Content {
+                role: Some(role.to_string()),
+                parts: vec![Part {
+                    text: msg.content.clone(),
+                }],
+            });
+        }
+
+        let system_instruction = (!system_parts.is_empty()).then(|| Content {
+            role: None,
+            parts: vec![Part {
+                text: system_parts.join("\n\n"),
+            }],
+        });
+        (system_instruction, contents)
+    }
+
     fn http_client(&self) -> Client {
         crate::config::build_runtime_proxy_client_with_timeouts("provider.gemini", 120, 10)
     }
@@ -938,7 +1058,7 @@ impl GeminiProvider {
         contents: Vec<Content>,
         system_instruction: Option<Content>,
         model: &str,
-        temperature: f64,
+        generation_config: GenerationConfig,
     ) -> anyhow::Result<(String, Option<TokenUsage>)> {
         let auth = self.auth.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
@@ -985,10 +1105,7 @@ impl GeminiProvider {
         let request = GenerateContentRequest {
             contents,
             system_instruction,
-            generation_config: GenerationConfig {
-                temperature,
-                max_output_tokens: 8192,
-            },
+            generation_config,
         };
 
         let url = Self::build_generate_content_url(model, auth);
@@ -1167,7 +1284,12 @@ impl Provider for GeminiProvider {
         }];
 
         let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(
+                contents,
+                system_instruction,
+                model,
+                GenerationConfig::new(temperature),
+            )
             .await?;
         Ok(text)
     }
@@ -1219,7 +1341,12 @@ impl Provider for GeminiProvider {
         };
 
         let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(
+                contents,
+                system_instruction,
+                model,
+                GenerationConfig::new(temperature),
+            )
             .await?;
         Ok(text)
     }
@@ -1263,8 +1390,9 @@ impl Provider for GeminiProvider {
             })
         };
 
+        let generation_config = GenerationConfig::from_request(&request, temperature);
         let (text, usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(contents, system_instruction, model, generation_config)
             .await?;
 
         Ok(ChatResponse {
@@ -1275,6 +1403,56 @@ impl Provider for GeminiProvider {
         })
     }
 
+    /// Streaming is offered for API-key auth; the OAuth code-assist endpoint
+    /// keeps using the request/response path.
+    fn supports_streaming(&self) -> bool {
+        self.auth.as_ref().is_some_and(GeminiAuth::is_api_key)
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    /// Tools are prompt-guided here, so a streamed turn never carries native
+    /// tool definitions; the full history is still sent.
+    fn supports_streaming_tool_calls(&self) -> bool {
+        self.supports_streaming()
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(auth) = self.auth.as_ref().filter(|auth| auth.is_api_key()) else {
+            return stream::once(async {
+                Err(StreamError::Provider(
+                    "Gemini streaming requires API key authentication".into(),
+                ))
+            })
+            .boxed();
+        };
+
+        let (system_instruction, contents) = Self::convert_history(messages);
+        let request = GenerateContentRequest {
+            contents,
+            system_instruction,
+            generation_config: GenerationConfig::new(temperature),
+        };
+        let url = Self::build_stream_generate_content_url(model, auth.api_key_credential());
+
+        send_streaming_request(
+            self.http_client().post(url).json(&request),
+            "Gemini".to_string(),
+            options.count_tokens,
+            |response, count_tokens| {
+                response_lines_to_chunks(response, count_tokens, parse_stream_line)
+            },
+        )
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         if let Some(auth) = self.auth.as_ref() {
             match auth {
@@ -1528,6 +1706,31 @@ mod tests {
         assert!(!url.contains("?key="));
     }
 
+    #[test]
+    fn stream_url_uses_sse_framing_with_key() {
+        let url =
+            GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", "api-key-123");
+        assert!(url.contains("models/gemini-2.0-flash:streamGenerateContent?alt=sse"));
+        assert!(url.ends_with("&key=api-key-123"));
+    }
+
+    #[test]
+    fn stream_line_separates_thoughts_and_reports_usage() {
+        let chunk = parse_stream_line(
+            r#"data: {"candidates":[{"content":{"parts":[{"text":"Plan","thought":true},{"text":"Hi"}]}}],"usageMetadata":{"promptTokenCount":9,"candidatesTokenCount":3}}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(chunk.delta, "Hi");
+        assert_eq!(chunk.reasoning_delta, "Plan");
+        let usage = chunk.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(9));
+        assert_eq!(usage.output_tokens, Some(3));
+
+        assert!(parse_stream_line("").unwrap().is_none());
+        assert!(parse_stream_line(r#"data: {"error":{"message":"quota"}}"#).is_err());
+    }
+
     #[test]
     fn api_key_url_uses_public_endpoint() {
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
@@ -1549,10 +1752,7 @@ mod tests {
                 }],
             }],
             system_instruction: None,
-            generation_config: GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::new(0.7),
         };
 
         let request = provider
@@ -1590,10 +1790,7 @@ mod tests {
                 }],
             }],
             system_instruction: None,
-            generation_config: GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::new(0.7),
         };
 
         let request = provider
@@ -1634,10 +1831,7 @@ mod tests {
                 }],
             }],
             system_instruction: None,
-            generation_config: GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::new(0.7),
         };
 
         let request = provider
@@ -1671,10 +1865,7 @@ mod tests {
                     text: "You are helpful".to_string(),
                 }],
             }),
-            generation_config: GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::new(0.7),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1700,10 +1891,7 @@ mod tests {
                     }],
                 }],
                 system_instruction: None,
-                generation_config: Some(GenerationConfig {
-                    temperature: 0.7,
-                    max_output_tokens: 8192,
-                }),
+                generation_config: Some(GenerationConfig::new(0.7)),
             },
         };
 
@@ -2141,4 +2329,30 @@ mod tests {
         // Should succeed without making HTTP requests
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn generation_config_maps_request_options() {
+        use crate::providers::traits::{ChatRequest, ResponseFormat};
+
+        let schema = serde_json::json!({"type": "object", "required": ["name"]});
+        let format = ResponseFormat::json_schema("person", schema.clone());
+        let stop = vec!["END".to_string()];
+        let request = ChatRequest {
+            max_tokens: Some(256),
+            stop: &stop,
+            response_format: Some(&format),
+            ..ChatRequest::default()
+        };
+
+        let json = serde_json::to_value(GenerationConfig::from_request(&request, 0.3)).unwrap();
+        assert_eq!(json["maxOutputTokens"], 256);
+        assert_eq!(json["stopSequences"], serde_json::json!(["END"]));
+        assert_eq!(json["responseMimeType"], "application/json");
+        assert_eq!(json["responseJsonSchema"], schema);
+
+        let json = serde_json::to_value(GenerationConfig::new(0.3)).unwrap();
+        assert_eq!(json["maxOutputTokens"], 8192);
+        assert!(json.get("stopSequences").is_none());
+        assert!(json.get("responseMimeType").is_none());
+    }
 }
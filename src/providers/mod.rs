@@ -17,7 +17,9 @@
 //! in [`create_provider_with_url`]. See `AGENTS.md` §7.1 for the full change playbook.
 
 pub mod anthropic;
+pub mod azure_openai;
 pub mod bedrock;
+pub mod caching;
 pub mod compatible;
 pub mod copilot;
 pub mod gemini;
@@ -27,13 +29,14 @@ pub mod openai_codex;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+pub mod structured;
 pub mod telnyx;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
     ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ResponseFormat, ToolCall, ToolChoice, ToolResultMessage,
 };
 
 use crate::auth::AuthService;
@@ -682,6 +685,7 @@ pub struct ProviderRuntimeOptions {
     pub custom_provider_api_mode: Option<CompatibleApiMode>,
     pub max_tokens_override: Option<u32>,
     pub model_support_vision: Option<bool>,
+    pub azure_openai: crate::config::AzureOpenAiConfig,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -696,6 +700,7 @@ impl Default for ProviderRuntimeOptions {
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            azure_openai: crate::config::AzureOpenAiConfig::default(),
         }
     }
 }
@@ -842,6 +847,7 @@ fn resolve_provider_credential(name: &str, credential_override: Option<&str>) ->
         "anthropic" => vec!["ANTHROPIC_OAUTH_TOKEN", "ANTHROPIC_API_KEY"],
         "openrouter" => vec!["OPENROUTER_API_KEY"],
         "openai" => vec!["OPENAI_API_KEY"],
+        "azure" | "azure-openai" | "azure_openai" => vec!["AZURE_OPENAI_API_KEY"],
         "ollama" => vec!["OLLAMA_API_KEY"],
         "venice" => vec!["VENICE_API_KEY"],
         "groq" => vec!["GROQ_API_KEY"],
@@ -1047,6 +1053,20 @@ fn create_provider_with_url_and_options(
             key,
             options.max_tokens_override,
         ))),
+        "azure" | "azure-openai" | "azure_openai" => {
+            // In Entra ID mode an explicit credential is a bearer token; the
+            // AZURE_OPENAI_API_KEY fallback only applies to api-key auth.
+            let credential = match options.azure_openai.auth {
+                crate::config::AzureOpenAiAuthMode::ApiKey => key,
+                crate::config::AzureOpenAiAuthMode::EntraId => api_key,
+            };
+            Ok(Box::new(azure_openai::AzureOpenAiProvider::new(
+                &options.azure_openai,
+                api_url,
+                credential,
+                options.max_tokens_override,
+            )))
+        }
         // Ollama uses api_url for custom base URL (e.g. remote Ollama instance)
         "ollama" => Ok(Box::new(ollama::OllamaProvider::new_with_reasoning(
             api_url,
@@ -1672,6 +1692,12 @@ pub fn list_providers() -> Vec<ProviderInfo> {
             ],
             local: false,
         },
+        ProviderInfo {
+            name: "azure",
+            display_name: "Azure OpenAI",
+            aliases: &["azure-openai", "azure_openai"],
+            local: false,
+        },
         ProviderInfo {
             name: "bedrock",
             display_name: "Amazon Bedrock",
@@ -2270,6 +2296,16 @@ mod tests {
         assert!(create_provider("bedrock", Some("ignored")).is_ok());
     }
 
+    #[test]
+    fn factory_azure_openai() {
+        assert!(create_provider("azure", Some("key")).is_ok());
+        assert!(create_provider("azure-openai", Some("key")).is_ok());
+        assert!(create_provider("azure_openai", Some("key")).is_ok());
+        let azure = create_provider("azure", Some("key")).expect("provider should resolve");
+        assert!(azure.supports_native_tools());
+        assert!(azure.supports_vision());
+    }
+
     #[test]
     fn factory_hunyuan() {
         assert!(create_provider("hunyuan", Some("key")).is_ok());
@@ -2747,6 +2783,7 @@ mod tests {
             "minimax",
             "minimax-cn",
             "bedrock",
+            "azure",
             "qianfan",
             "doubao",
             "qwen",
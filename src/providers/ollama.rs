@@ -1,8 +1,11 @@
 use crate::multimodal;
+use crate::providers::compatible::{response_lines_to_chunks, send_streaming_request};
 use crate::providers::traits::{
-    ChatMessage, ChatResponse, Provider, ProviderCapabilities, TokenUsage, ToolCall,
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, StreamChunk, StreamError,
+    StreamOptions, StreamResult, TokenUsage, ToolCall, ToolCallDelta,
 };
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -80,6 +83,19 @@ struct ResponseMessage {
     thinking: Option<String>,
 }
 
+/// One NDJSON line of a streaming `/api/chat` response.
+#[derive(Debug, Deserialize)]
+struct ApiStreamLine {
+    #[serde(default)]
+    message: Option<ResponseMessage>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaToolCall {
     id: Option<String>,
@@ -95,8 +111,49 @@ struct OllamaFunction {
 
 // ─── Implementation ───────────────────────────────────────────────────────────
 
+/// Build a stateful NDJSON line parser for streaming `/api/chat`.
+///
+/// Ollama sends each tool call complete in a single line, so every call is
+/// emitted as one [`ToolCallDelta`] with a running index.
+fn stream_line_parser() -> impl FnMut(&str) -> StreamResult<Option<StreamChunk>> + Send + 'static {
+    let mut next_tool_index = 0usize;
+    move |line: &str| {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let parsed: ApiStreamLine = serde_json::from_str(line).map_err(StreamError::Json)?;
+        if let Some(error) = parsed.error {
+            return Err(StreamError::Provider(format!("Ollama API error: {error}")));
+        }
+
+        let mut chunk = StreamChunk::default();
+        if let Some(message) = parsed.message {
+            chunk.delta = message.content;
+            chunk.reasoning_delta = message.thinking.unwrap_or_default();
+            for tc in &message.tool_calls {
+                let (name, args) = OllamaProvider::unwrap_tool_call(tc);
+                chunk.tool_call_deltas.push(ToolCallDelta {
+                    index: next_tool_index,
+                    id: tc.id.clone(),
+                    name: Some(name),
+                    arguments: serde_json::to_string(&args).unwrap_or_else(|_| "{}".into()),
+                });
+                next_tool_index += 1;
+            }
+        }
+        if parsed.prompt_eval_count.is_some() || parsed.eval_count.is_some() {
+            chunk.usage = Some(TokenUsage {
+                input_tokens: parsed.prompt_eval_count,
+                output_tokens: parsed.eval_count,
+            });
+        }
+        Ok((!chunk.is_empty()).then_some(chunk))
+    }
+}
+
 impl OllamaProvider {
-    fn normalize_base_url(raw_url: &str) -> String {
+    pub(crate) fn normalize_base_url(raw_url: &str) -> String {
         let trimmed = raw_url.trim().trim_end_matches('/');
         if trimmed.is_empty() {
             return String::new();
@@ -448,6 +505,10 @@ impl OllamaProvider {
 
     /// Extract the actual tool name and arguments from potentially nested structures
     fn extract_tool_name_and_args(&self, tc: &OllamaToolCall) -> (String, serde_json::Value) {
+        Self::unwrap_tool_call(tc)
+    }
+
+    fn unwrap_tool_call(tc: &OllamaToolCall) -> (String, serde_json::Value) {
         let name = &tc.function.name;
         let args = &tc.function.arguments;
 
@@ -677,6 +738,94 @@ impl Provider for OllamaProvider {
         true
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_chat(
+            crate::providers::traits::ChatRequest {
+                messages,
+                tools: None,
+                ..Default::default()
+            },
+            model,
+            temperature,
+            options,
+        )
+    }
+
+    fn stream_chat(
+        &self,
+        request: crate::providers::traits::ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let (normalized_model, should_auth) = match self.resolve_request_details(model) {
+            Ok(details) => details,
+            Err(e) => {
+                let message = e.to_string();
+                return stream::once(async move { Err(StreamError::Provider(message)) }).boxed();
+            }
+        };
+
+        let tools: Option<Vec<serde_json::Value>> = request
+            .tools
+            .filter(|specs| !specs.is_empty())
+            .map(|specs| {
+                specs
+                    .iter()
+                    .map(|s| {
+                        serde_json::json!({
+                            "type": "function",
+                            "function": {
+                                "name": s.name,
+                                "description": s.description,
+                                "parameters": s.parameters
+                            }
+                        })
+                    })
+                    .collect()
+            });
+        let mut chat_request = self.build_chat_request(
+            self.convert_messages(request.messages),
+            &normalized_model,
+            temperature,
+            tools.as_deref(),
+        );
+        chat_request.stream = true;
+
+        let mut request_builder = self
+            .http_client()
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&chat_request);
+        if should_auth {
+            if let Some(key) = self.api_key.as_ref() {
+                request_builder = request_builder.bearer_auth(key);
+            }
+        }
+
+        send_streaming_request(
+            request_builder,
+            "Ollama".to_string(),
+            options.count_tokens,
+            |response, count_tokens| {
+                response_lines_to_chunks(response, count_tokens, stream_line_parser())
+            },
+        )
+    }
+
     async fn chat(
         &self,
         request: crate::providers::traits::ChatRequest<'_>,
@@ -1069,4 +1218,35 @@ mod tests {
         assert!(resp.prompt_eval_count.is_none());
         assert!(resp.eval_count.is_none());
     }
+
+    #[test]
+    fn stream_lines_emit_text_thinking_tool_calls_and_usage() {
+        use crate::providers::traits::StreamAccumulator;
+
+        let mut parse = stream_line_parser();
+        let lines = [
+            r#"{"message":{"role":"assistant","content":"","thinking":"Look up"},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":"Sure."},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"tool.shell","arguments":{"command":"date"}}}]},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":""},"done":true,"prompt_eval_count":30,"eval_count":12}"#,
+        ];
+
+        let mut acc = StreamAccumulator::new();
+        for line in lines {
+            if let Some(chunk) = parse(line).unwrap() {
+                acc.push(&chunk);
+            }
+        }
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Sure."));
+        assert_eq!(response.reasoning_content.as_deref(), Some("Look up"));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"date"}"#);
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(30));
+        assert_eq!(usage.output_tokens, Some(12));
+
+        assert!(parse(r#"{"error":"model not found"}"#).is_err());
+    }
 }
@@ -1,9 +1,15 @@
+use crate::providers::compatible::{
+    openai_response_format, openai_sse_to_structured_chunks, openai_stop, openai_tool_choice,
+    send_streaming_request,
+};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
+    ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -66,7 +72,15 @@ struct NativeChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tool_choice: Option<String>,
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -380,9 +394,13 @@ impl Provider for OpenAiProvider {
             model: model.to_string(),
             messages: Self::convert_messages(request.messages),
             temperature,
-            max_tokens: self.max_tokens_override,
-            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            max_tokens: request.max_tokens.or(self.max_tokens_override),
+            tool_choice: openai_tool_choice(request.tool_choice, tools.is_some()),
             tools,
+            stop: openai_stop(request.stop),
+            response_format: openai_response_format(request.response_format),
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
@@ -417,6 +435,82 @@ impl Provider for OpenAiProvider {
         true
     }
 
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_chat(
+            ProviderChatRequest {
+                messages,
+                tools: None,
+                ..Default::default()
+            },
+            model,
+            temperature,
+            options,
+        )
+    }
+
+    fn stream_chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.clone() else {
+            return stream::once(async {
+                Err(StreamError::Provider(
+                    "OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.".into(),
+                ))
+            })
+            .boxed();
+        };
+
+        let tools = Self::convert_tools(request.tools);
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages(request.messages),
+            temperature,
+            max_tokens: request.max_tokens.or(self.max_tokens_override),
+            tool_choice: openai_tool_choice(request.tool_choice, tools.is_some()),
+            tools,
+            stop: openai_stop(request.stop),
+            response_format: openai_response_format(request.response_format),
+            stream: Some(true),
+            stream_options: Some(serde_json::json!({ "include_usage": true })),
+        };
+
+        let request_builder = self
+            .http_client()
+            .post(format!("{}/chat/completions", self.base_url))
+            .header("Authorization", format!("Bearer {credential}"))
+            .header("Accept", "text/event-stream")
+            .json(&native_request);
+
+        send_streaming_request(
+            request_builder,
+            "OpenAI".to_string(),
+            options.count_tokens,
+            openai_sse_to_structured_chunks,
+        )
+    }
+
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -445,8 +539,12 @@ impl Provider for OpenAiProvider {
             messages: Self::convert_messages(messages),
             temperature,
             max_tokens: self.max_tokens_override,
-            tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
+            tool_choice: openai_tool_choice(None, native_tools.is_some()),
             tools: native_tools,
+            stop: None,
+            response_format: None,
+            stream: None,
+            stream_options: None,
         };
 
         let response = self
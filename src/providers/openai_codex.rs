@@ -1085,6 +1085,7 @@ data: [DONE]
             custom_provider_api_mode: None,
             max_tokens_override: None,
             model_support_vision: None,
+            azure_openai: crate::config::AzureOpenAiConfig::default(),
         };
         let provider =
             OpenAiCodexProvider::new(&options, None).expect("provider should initialize");
@@ -15,7 +15,7 @@ use std::time::Duration;
 // immediately — avoiding wasted latency on errors that cannot self-heal.
 
 /// Check if an error is non-retryable (client errors that won't resolve with retries).
-fn is_non_retryable(err: &anyhow::Error) -> bool {
+pub(crate) fn is_non_retryable(err: &anyhow::Error) -> bool {
     if is_context_window_exceeded(err) {
         return true;
     }
@@ -754,11 +754,7 @@ impl Provider for ReliableProvider {
                     let mut backoff_ms = self.base_backoff_ms;
 
                     for attempt in 0..=self.max_retries {
-                        let req = ChatRequest {
-                            messages: request.messages,
-                            tools: request.tools,
-                        };
-                        match provider.chat(req, sent_model, temperature).await {
+                        match provider.chat(request, sent_model, temperature).await {
                             Ok(resp) => {
                                 if attempt > 0 || sent_model != model {
                                     tracing::info!(
@@ -865,6 +861,65 @@ impl Provider for ReliableProvider {
         self.providers.iter().any(|(_, p)| p.supports_streaming())
     }
 
+    fn supports_structured_output(&self) -> bool {
+        self.providers
+            .first()
+            .map(|(_, p)| p.supports_structured_output())
+            .unwrap_or(false)
+    }
+
+    fn supports_streaming_tool_calls(&self) -> bool {
+        self.providers
+            .first()
+            .map(|(_, p)| p.supports_streaming_tool_calls())
+            .unwrap_or(false)
+    }
+
+    fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        // Only the primary provider is used: tool-call formats and the native
+        // tool decision made by the caller are tied to it.
+        let Some((provider_name, provider)) = self
+            .providers
+            .first()
+            .filter(|(_, p)| options.enabled && p.supports_streaming_tool_calls())
+        else {
+            return stream::once(async move {
+                Err(super::traits::StreamError::Provider(
+                    "Primary provider does not support structured streaming".to_string(),
+                ))
+            })
+            .boxed();
+        };
+
+        let base_model = self.model_chain(model).first().copied().unwrap_or(model);
+        let current_model = self
+            .provider_model_chain(base_model, provider_name, true)
+            .first()
+            .copied()
+            .unwrap_or(base_model)
+            .to_string();
+
+        let stream = provider.stream_chat(request, &current_model, temperature, options);
+        let provider_name = provider_name.clone();
+        stream
+            .inspect(move |chunk| {
+                if let Err(e) = chunk {
+                    tracing::warn!(
+                        provider = provider_name,
+                        model = current_model,
+                        "Streaming error: {e}"
+                    );
+                }
+            })
+            .boxed()
+    }
+
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -1838,6 +1893,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            ..Default::default()
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1847,6 +1903,87 @@ mod tests {
         assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 
+    struct StructuredMock {
+        native: bool,
+        prompts: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Provider for StructuredMock {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat() is overridden")
+        }
+
+        fn supports_structured_output(&self) -> bool {
+            self.native
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.prompts.lock().unwrap().push(
+                request
+                    .messages
+                    .iter()
+                    .map(|m| m.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            Ok(ChatResponse {
+                text: Some(r#"{"ok":true}"#.to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn structured_output_support_is_forwarded_from_primary() {
+        let prompts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(StructuredMock {
+                    native: true,
+                    prompts: Arc::clone(&prompts),
+                }) as Box<dyn Provider>,
+            )],
+            1,
+            1,
+        );
+        assert!(provider.supports_structured_output());
+
+        let format = super::super::traits::ResponseFormat::JsonObject;
+        let messages = vec![ChatMessage::user("status as json")];
+        let request = ChatRequest {
+            messages: &messages,
+            response_format: Some(&format),
+            ..Default::default()
+        };
+        let response = crate::providers::structured::chat_with_response_format(
+            &provider,
+            request,
+            "test-model",
+            0.0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.text.as_deref(), Some(r#"{"ok":true}"#));
+        // Native support means no schema instructions are injected into the prompt.
+        assert_eq!(*prompts.lock().unwrap(), vec!["status as json".to_string()]);
+    }
+
     #[tokio::test]
     async fn chat_retries_and_recovers() {
         let calls = Arc::new(AtomicUsize::new(0));
@@ -1874,6 +2011,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            ..Default::default()
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -1945,6 +2083,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            ..Default::default()
         };
         let err = provider
             .chat(request, "test", 0.0)
@@ -2061,6 +2200,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            ..Default::default()
         };
         let result = provider.chat(request, "claude-opus", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("ok from sonnet"));
@@ -2109,6 +2249,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            ..Default::default()
         };
         let result = provider.chat(request, "test", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("from fallback"));
@@ -1,6 +1,9 @@
-use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
+};
 use super::Provider;
 use async_trait::async_trait;
+use futures_util::stream;
 use std::collections::HashMap;
 
 /// A single route: maps a task hint to a provider + model combo.
@@ -175,6 +178,25 @@ impl Provider for RouterProvider {
         })
     }
 
+    fn supports_streaming_tool_calls(&self) -> bool {
+        self.providers
+            .get(self.default_index)
+            .map(|(_, p)| p.supports_streaming_tool_calls())
+            .unwrap_or(false)
+    }
+
+    fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        let (_, provider) = &self.providers[provider_idx];
+        provider.stream_chat(request, &resolved_model, temperature, options)
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
@@ -0,0 +1,605 @@
+//! Structured (JSON) output for [`Provider::chat`].
+//!
+//! [`chat_with_response_format`] runs a request that carries a
+//! [`ResponseFormat`] and guarantees the returned text is JSON matching it:
+//!
+//! 1. Providers without native enforcement get the schema as a system
+//!    instruction.
+//! 2. The reply is extracted (code fences and surrounding prose are tolerated)
+//!    and validated against the schema.
+//! 3. On failure the model is shown the validation errors and asked to repair
+//!    its answer, up to [`MAX_REPAIR_ATTEMPTS`] times.
+//!
+//! Validation covers the JSON Schema keywords models are commonly given:
+//! `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, length/size/range bounds, `pattern`, `anyOf`/`oneOf`/`allOf` and
+//! local `$ref`s. Unknown keywords are ignored.
+
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, Provider, ResponseFormat};
+use serde_json::Value;
+
+/// Repair turns allowed after the first invalid reply.
+pub const MAX_REPAIR_ATTEMPTS: usize = 1;
+
+/// Run `request` and, when it carries a `response_format`, return a response
+/// whose text is compact JSON satisfying it. Tool-call responses are returned
+/// unchecked.
+pub async fn chat_with_response_format(
+    provider: &dyn Provider,
+    request: ChatRequest<'_>,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<ChatResponse> {
+    let Some(format) = request.response_format else {
+        return provider.chat(request, model, temperature).await;
+    };
+
+    let mut messages = request.messages.to_vec();
+    if !provider.supports_structured_output() {
+        add_format_instruction(&mut messages, format);
+    }
+
+    let mut attempt = 0;
+    loop {
+        let mut response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    ..request
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        if !response.tool_calls.is_empty() {
+            return Ok(response);
+        }
+
+        let text = response.text_or_empty().to_string();
+        match check_structured_output(&text, format) {
+            Ok(value) => {
+                response.text = Some(value.to_string());
+                return Ok(response);
+            }
+            Err(problems) if attempt < MAX_REPAIR_ATTEMPTS => {
+                attempt += 1;
+                tracing::debug!(
+                    attempt,
+                    problems = problems.len(),
+                    "Structured output invalid; asking model to repair"
+                );
+                messages.push(ChatMessage::assistant(text));
+                messages.push(ChatMessage::user(repair_prompt(&problems, format)));
+            }
+            Err(problems) => {
+                anyhow::bail!(
+                    "Model output did not match the requested response format after {} attempt(s): {}",
+                    attempt + 1,
+                    problems.join("; ")
+                );
+            }
+        }
+    }
+}
+
+/// Extract the JSON value from `text` and validate it against `format`.
+pub fn check_structured_output(text: &str, format: &ResponseFormat) -> Result<Value, Vec<String>> {
+    let value = extract_json(text).ok_or_else(|| vec!["reply is not valid JSON".to_string()])?;
+    let problems = match format {
+        ResponseFormat::JsonObject => {
+            if value.is_object() {
+                Vec::new()
+            } else {
+                vec![format!(
+                    "$: expected a JSON object, got {}",
+                    type_name(&value)
+                )]
+            }
+        }
+        ResponseFormat::JsonSchema { schema, .. } => validate_json(&value, schema),
+    };
+    if problems.is_empty() {
+        Ok(value)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Parse a JSON value out of a model reply, tolerating code fences and prose
+/// around a single top-level object or array.
+pub fn extract_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"));
+    if let Some(inner) = unfenced {
+        if let Ok(value) = serde_json::from_str(inner.trim()) {
+            return Some(value);
+        }
+    }
+
+    let start = trimmed.find(['{', '['])?;
+    let close = if trimmed[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = trimmed.rfind(close)?;
+    (end > start)
+        .then(|| serde_json::from_str(&trimmed[start..=end]).ok())
+        .flatten()
+}
+
+/// Validate `instance` against `schema`, returning one message per problem
+/// (prefixed with a `$`-rooted path).
+pub fn validate_json(instance: &Value, schema: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    validate_at(
+        instance,
+        schema,
+        schema,
+        &mut Vec::new(),
+        "$",
+        &mut problems,
+    );
+    problems
+}
+
+/// `active_refs` holds the `($ref, path)` pairs currently being followed, so a
+/// reference cycle that never consumes input (`{"$ref": "#"}`) is reported
+/// instead of recursing forever.
+fn validate_at(
+    instance: &Value,
+    schema: &Value,
+    root: &Value,
+    active_refs: &mut Vec<(String, String)>,
+    path: &str,
+    out: &mut Vec<String>,
+) {
+    let obj = match schema {
+        Value::Bool(false) => {
+            out.push(format!("{path}: no value is allowed here"));
+            return;
+        }
+        Value::Object(obj) => obj,
+        // `true` and non-schema values accept anything.
+        _ => return,
+    };
+
+    if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+        let key = (reference.to_string(), path.to_string());
+        if active_refs.contains(&key) {
+            out.push(format!("{path}: $ref '{reference}' is circular"));
+            return;
+        }
+        match resolve_ref(root, reference) {
+            Some(target) => {
+                active_refs.push(key);
+                validate_at(instance, target, root, active_refs, path, out);
+                active_refs.pop();
+            }
+            None => out.push(format!("{path}: unresolvable $ref '{reference}'")),
+        }
+    }
+
+    if let Some(expected) = obj.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(instance, t)) {
+            out.push(format!(
+                "{path}: expected type {}, got {}",
+                allowed.join(" or "),
+                type_name(instance)
+            ));
+            return;
+        }
+    }
+
+    if let Some(options) = obj.get("enum").and_then(Value::as_array) {
+        if !options.contains(instance) {
+            out.push(format!(
+                "{path}: value {instance} is not one of {}",
+                Value::Array(options.clone())
+            ));
+        }
+    }
+    if let Some(constant) = obj.get("const") {
+        if constant != instance {
+            out.push(format!("{path}: value must be {constant}"));
+        }
+    }
+
+    match instance {
+        Value::Object(map) => {
+            let properties = obj.get("properties").and_then(Value::as_object);
+            if let Some(required) = obj.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        out.push(format!("{path}: missing required property '{key}'"));
+                    }
+                }
+            }
+            for (key, value) in map {
+                let child = format!("{path}.{key}");
+                match properties.and_then(|props| props.get(key)) {
+                    Some(prop_schema) => {
+                        validate_at(value, prop_schema, root, active_refs, &child, out);
+                    }
+                    None => match obj.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            out.push(format!("{path}: unexpected property '{key}'"));
+                        }
+                        Some(extra) => validate_at(value, extra, root, active_refs, &child, out),
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(obj, "minItems", items.len(), path, "item(s)", out);
+            check_bound(obj, "maxItems", items.len(), path, "item(s)", out);
+            if let Some(item_schema) = obj.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(
+                        item,
+                        item_schema,
+                        root,
+                        active_refs,
+                        &format!("{path}[{index}]"),
+                        out,
+                    );
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            check_bound(obj, "minLength", len, path, "character(s)", out);
+            check_bound(obj, "maxLength", len, path, "character(s)", out);
+            if let Some(pattern) = obj.get("pattern").and_then(Value::as_str) {
+                if let Ok(re) = regex::Regex::new(pattern) {
+                    if !re.is_match(s) {
+                        out.push(format!("{path}: does not match pattern '{pattern}'"));
+                    }
+                }
+            }
+        }
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                let limit = |key: &str| obj.get(key).and_then(Value::as_f64);
+                if limit("minimum").is_some_and(|min| n < min)
+                    || limit("exclusiveMinimum").is_some_and(|min| n <= min)
+                    || limit("maximum").is_some_and(|max| n > max)
+                    || limit("exclusiveMaximum").is_some_and(|max| n >= max)
+                {
+                    out.push(format!("{path}: {n} is out of the allowed range"));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(all) = obj.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            validate_at(instance, sub, root, active_refs, path, out);
+        }
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        if let Some(variants) = obj.get(keyword).and_then(Value::as_array) {
+            let matching = variants
+                .iter()
+                .filter(|sub| {
+                    let mut scratch = Vec::new();
+                    validate_at(instance, sub, root, active_refs, path, &mut scratch);
+                    scratch.is_empty()
+                })
+                .count();
+            if matching == 0 || (exactly_one && matching > 1) {
+                out.push(format!("{path}: does not match {keyword}"));
+            }
+        }
+    }
+}
+
+fn check_bound(
+    schema: &serde_json::Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    unit: &str,
+    out: &mut Vec<String>,
+) {
+    let Some(limit) = schema.get(keyword).and_then(Value::as_u64) else {
+        return;
+    };
+    let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+    let violated = if keyword.starts_with("min") {
+        actual < limit
+    } else {
+        actual > limit
+    };
+    if violated {
+        out.push(format!("{path}: {keyword} is {limit} {unit}, got {actual}"));
+    }
+}
+
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        Some(root)
+    } else {
+        root.pointer(pointer)
+    }
+}
+
+fn matches_type(instance: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn format_instruction(format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::JsonObject => {
+            "Respond with a single JSON object and nothing else: no prose, no code fences."
+                .to_string()
+        }
+        ResponseFormat::JsonSchema { schema, .. } => format!(
+            "Respond with a single JSON value and nothing else: no prose, no code fences. \
+             It must conform to this JSON Schema:\n{}",
+            serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+        ),
+    }
+}
+
+/// Append the output contract to the system prompt (or add one).
+fn add_format_instruction(messages: &mut Vec<ChatMessage>, format: &ResponseFormat) {
+    let instruction = format_instruction(format);
+    match messages.iter_mut().find(|m| m.role == "system") {
+        Some(system) => {
+            system.content.push_str("\n\n");
+            system.content.push_str(&instruction);
+        }
+        None => messages.insert(0, ChatMessage::system(instruction)),
+    }
+}
+
+fn repair_prompt(problems: &[String], format: &ResponseFormat) -> String {
+    let mut prompt =
+        String::from("Your previous reply did not satisfy the required output format:\n");
+    for problem in problems {
+        prompt.push_str("- ");
+        prompt.push_str(problem);
+        prompt.push('\n');
+    }
+    prompt.push('\n');
+    prompt.push_str(&format_instruction(format));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ChatResponse;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct ScriptedProvider {
+        native: bool,
+        replies: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(native: bool, replies: Vec<&'static str>) -> Self {
+            Self {
+                native,
+                replies: Mutex::new(replies),
+                seen: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat() is overridden")
+        }
+
+        fn supports_structured_output(&self) -> bool {
+            self.native
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen.lock().unwrap().push(request.messages.to_vec());
+            let reply = self.replies.lock().unwrap().remove(0);
+            Ok(ChatResponse {
+                text: Some(reply.to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    fn person_format() -> ResponseFormat {
+        ResponseFormat::json_schema(
+            "person",
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "minLength": 1},
+                    "age": {"type": "integer", "minimum": 0},
+                    "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}}
+                },
+                "required": ["name", "age"],
+                "additionalProperties": false,
+                "$defs": {"tag": {"type": "string", "enum": ["a", "b"]}}
+            }),
+        )
+    }
+
+    #[test]
+    fn validate_json_reports_circular_refs_instead_of_overflowing() {
+        let self_ref = json!({"$ref": "#"});
+        assert_eq!(
+            validate_json(&json!({"a": 1}), &self_ref),
+            vec!["$: $ref '#' is circular".to_string()]
+        );
+
+        let mutual = json!({
+            "$ref": "#/$defs/a",
+            "$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"$ref": "#/$defs/a"}}
+        });
+        let problems = validate_json(&json!(1), &mutual);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("circular"));
+
+        // Recursion that consumes input is fine.
+        let tree = json!({
+            "type": "object",
+            "properties": {"children": {"type": "array", "items": {"$ref": "#"}}}
+        });
+        assert!(validate_json(&json!({"children": [{"children": []}]}), &tree).is_empty());
+        assert_eq!(
+            validate_json(&json!({"children": [1]}), &tree),
+            vec!["$.children[0]: expected type object, got number".to_string()]
+        );
+    }
+
+    #[test]
+    fn validate_json_reports_each_problem_with_path() {
+        let ResponseFormat::JsonSchema { schema, .. } = person_format() else {
+            unreachable!()
+        };
+        assert!(
+            validate_json(&json!({"name": "Ada", "age": 36, "tags": ["a"]}), &schema).is_empty()
+        );
+
+        let problems = validate_json(
+            &json!({"name": "", "age": -1.5, "tags": ["c"], "extra": true}),
+            &schema,
+        );
+        let joined = problems.join("\n");
+        assert!(joined.contains("$.name: minLength"), "{joined}");
+        assert!(joined.contains("$.age: expected type integer"), "{joined}");
+        assert!(
+            joined.contains("$.tags[0]: value \"c\" is not one of"),
+            "{joined}"
+        );
+        assert!(joined.contains("unexpected property 'extra'"), "{joined}");
+
+        let missing = validate_json(&json!({"name": "Ada"}), &schema);
+        assert_eq!(
+            missing,
+            vec!["$: missing required property 'age'".to_string()]
+        );
+    }
+
+    #[test]
+    fn extract_json_tolerates_fences_and_prose() {
+        assert_eq!(
+            extract_json("```json\n{\"a\":1}\n```"),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(
+            extract_json("Here you go: [1, 2] done"),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[tokio::test]
+    async fn non_native_provider_gets_instruction_and_one_repair_turn() {
+        let provider = ScriptedProvider::new(
+            false,
+            vec![
+                r#"{"name":"Ada"}"#,
+                "```json\n{\"name\":\"Ada\",\"age\":36}\n```",
+            ],
+        );
+        let format = person_format();
+        let messages = [ChatMessage::system("base"), ChatMessage::user("who?")];
+        let request = ChatRequest {
+            messages: &messages,
+            response_format: Some(&format),
+            ..ChatRequest::default()
+        };
+
+        let response = chat_with_response_format(&provider, request, "m", 0.0)
+            .await
+            .unwrap();
+        let value: serde_json::Value =
+            serde_json::from_str(response.text.as_deref().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"name": "Ada", "age": 36}));
+
+        let seen = provider.seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert!(seen[0][0].content.contains("JSON Schema"));
+        let repair = &seen[1].last().unwrap().content;
+        assert!(repair.contains("missing required property 'age'"));
+    }
+
+    #[tokio::test]
+    async fn native_provider_fails_after_repair_budget() {
+        let provider = ScriptedProvider::new(true, vec!["not json", "[]"]);
+        let messages = [ChatMessage::user("obj please")];
+        let request = ChatRequest {
+            messages: &messages,
+            response_format: Some(&ResponseFormat::JsonObject),
+            ..ChatRequest::default()
+        };
+
+        let err = chat_with_response_format(&provider, request, "m", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("after 2 attempt(s)"));
+        let seen = provider.seen.lock().unwrap();
+        assert_eq!(
+            seen[0].len(),
+            1,
+            "native providers get no extra instruction"
+        );
+    }
+}
@@ -85,10 +85,70 @@ impl ChatResponse {
 }
 
 /// Request payload for provider chat calls.
-#[derive(Debug, Clone, Copy)]
+///
+/// Generation controls are optional; providers that cannot honor one ignore
+/// it. Build with struct update syntax:
+/// `ChatRequest { messages, max_tokens: Some(256), ..ChatRequest::default() }`.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct ChatRequest<'a> {
     pub messages: &'a [ChatMessage],
     pub tools: Option<&'a [ToolSpec]>,
+    /// Output token cap; overrides the provider's configured default.
+    pub max_tokens: Option<u32>,
+    /// Sequences that end generation when produced.
+    pub stop: &'a [String],
+    /// How the model may use `tools` (native tool calling only).
+    pub tool_choice: Option<ToolChoice<'a>>,
+    /// Demand JSON output. See [`crate::providers::structured`] for
+    /// validation and repair when the provider lacks native support.
+    pub response_format: Option<&'a ResponseFormat>,
+}
+
+/// Tool selection policy for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolChoice<'a> {
+    /// Never call tools.
+    None,
+    /// The model decides (the default when tools are present).
+    Auto,
+    /// The model must call at least one tool.
+    Required,
+    /// The model must call the named tool.
+    Tool(&'a str),
+}
+
+/// Structured output requested from the model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// Any JSON object.
+    JsonObject,
+    /// JSON conforming to `schema`.
+    JsonSchema {
+        /// Short identifier (`[a-zA-Z0-9_-]`), required by some APIs.
+        name: String,
+        schema: serde_json::Value,
+        /// Ask the provider for strict schema adherence where supported.
+        strict: bool,
+    },
+}
+
+impl ResponseFormat {
+    /// Strict JSON Schema format.
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self::JsonSchema {
+            name: name.into(),
+            schema,
+            strict: true,
+        }
+    }
+
+    /// The schema, if any.
+    pub fn schema(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::JsonObject => None,
+            Self::JsonSchema { schema, .. } => Some(schema),
+        }
+    }
 }
 
 /// A tool result to feed back to the LLM.
@@ -116,8 +176,25 @@ pub enum ConversationMessage {
     ToolResults(Vec<ToolResultMessage>),
 }
 
+/// An incremental fragment of a native tool call in a streaming response.
+///
+/// Providers emit the `id` and `name` once (usually on the first fragment for
+/// a given `index`) followed by argument JSON pieces that concatenate into the
+/// full arguments string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolCallDelta {
+    /// Position of the tool call within the response.
+    pub index: usize,
+    /// Provider-assigned tool call id, if present in this fragment.
+    pub id: Option<String>,
+    /// Tool name, if present in this fragment.
+    pub name: Option<String>,
+    /// Argument JSON fragment (may be empty).
+    pub arguments: String,
+}
+
 /// A chunk of content from a streaming response.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct StreamChunk {
     /// Text delta for this chunk.
     pub delta: String,
@@ -125,6 +202,12 @@ pub struct StreamChunk {
     pub is_final: bool,
     /// Approximate token count for this chunk (estimated).
     pub token_count: usize,
+    /// Reasoning/thinking delta from thinking models (kept out of `delta`).
+    pub reasoning_delta: String,
+    /// Native tool-call fragments carried by this chunk.
+    pub tool_call_deltas: Vec<ToolCallDelta>,
+    /// Token usage reported by the provider (usually on the last chunks).
+    pub usage: Option<TokenUsage>,
 }
 
 impl StreamChunk {
@@ -132,17 +215,39 @@ impl StreamChunk {
     pub fn delta(text: impl Into<String>) -> Self {
         Self {
             delta: text.into(),
-            is_final: false,
-            token_count: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Create a non-final reasoning chunk.
+    pub fn reasoning(text: impl Into<String>) -> Self {
+        Self {
+            reasoning_delta: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Create a non-final chunk carrying a tool-call fragment.
+    pub fn tool_call(delta: ToolCallDelta) -> Self {
+        Self {
+            tool_call_deltas: vec![delta],
+            ..Self::default()
+        }
+    }
+
+    /// Create a non-final chunk carrying token usage.
+    pub fn usage(usage: TokenUsage) -> Self {
+        Self {
+            usage: Some(usage),
+            ..Self::default()
         }
     }
 
     /// Create a final chunk.
     pub fn final_chunk() -> Self {
         Self {
-            delta: String::new(),
             is_final: true,
-            token_count: 0,
+            ..Self::default()
         }
     }
 
@@ -151,7 +256,7 @@ impl StreamChunk {
         Self {
             delta: message.into(),
             is_final: true,
-            token_count: 0,
+            ..Self::default()
         }
     }
 
@@ -160,6 +265,107 @@ impl StreamChunk {
         self.token_count = self.delta.len().div_ceil(4);
         self
     }
+
+    /// True when the chunk carries nothing beyond the final marker.
+    pub fn is_empty(&self) -> bool {
+        self.delta.is_empty()
+            && self.reasoning_delta.is_empty()
+            && self.tool_call_deltas.is_empty()
+            && self.usage.is_none()
+    }
+}
+
+/// Folds streamed chunks back into a [`ChatResponse`].
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    text: String,
+    reasoning: String,
+    tool_calls: Vec<ToolCall>,
+    tool_call_slots: std::collections::HashMap<usize, usize>,
+    usage: Option<TokenUsage>,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply one chunk. Returns the tool names first seen in this chunk so
+    /// callers can surface "preparing tool" progress as soon as it is known.
+    pub fn push(&mut self, chunk: &StreamChunk) -> Vec<String> {
+        self.text.push_str(&chunk.delta);
+        self.reasoning.push_str(&chunk.reasoning_delta);
+
+        let mut started = Vec::new();
+        for delta in &chunk.tool_call_deltas {
+            let slot = match self.tool_call_slots.get(&delta.index) {
+                Some(slot) => *slot,
+                None => {
+                    self.tool_calls.push(ToolCall {
+                        id: String::new(),
+                        name: String::new(),
+                        arguments: String::new(),
+                    });
+                    let slot = self.tool_calls.len() - 1;
+                    self.tool_call_slots.insert(delta.index, slot);
+                    slot
+                }
+            };
+            let call = &mut self.tool_calls[slot];
+            if let Some(id) = delta.id.as_deref().filter(|id| !id.is_empty()) {
+                call.id = id.to_string();
+            }
+            if let Some(name) = delta.name.as_deref().filter(|name| !name.is_empty()) {
+                if call.name.is_empty() {
+                    started.push(name.to_string());
+                }
+                call.name.push_str(name);
+            }
+            call.arguments.push_str(&delta.arguments);
+        }
+
+        if let Some(usage) = &chunk.usage {
+            let merged = self.usage.get_or_insert_with(TokenUsage::default);
+            if usage.input_tokens.is_some() {
+                merged.input_tokens = usage.input_tokens;
+            }
+            if usage.output_tokens.is_some() {
+                merged.output_tokens = usage.output_tokens;
+            }
+        }
+        started
+    }
+
+    /// Text accumulated so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Build the final response. Tool calls without a provider id get a
+    /// generated one; empty argument strings become `{}`.
+    pub fn finish(self) -> ChatResponse {
+        let tool_calls = self
+            .tool_calls
+            .into_iter()
+            .filter(|call| !call.name.is_empty())
+            .map(|mut call| {
+                if call.id.is_empty() {
+                    call.id = uuid::Uuid::new_v4().to_string();
+                }
+                if call.arguments.trim().is_empty() {
+                    call.arguments = "{}".into();
+                }
+                call
+            })
+            .collect();
+
+        ChatResponse {
+            text: (!self.text.is_empty()).then_some(self.text),
+            tool_calls,
+            usage: self.usage,
+            reasoning_content: (!self.reasoning.is_empty()).then_some(self.reasoning),
+        }
+    }
 }
 
 /// Options for streaming chat requests.
@@ -419,6 +625,46 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Whether the provider enforces [`ChatRequest::response_format`] natively.
+    /// When false, [`crate::providers::structured::chat_with_response_format`]
+    /// adds the schema to the prompt instead.
+    /// Default implementation returns false.
+    fn supports_structured_output(&self) -> bool {
+        false
+    }
+
+    /// Whether [`Provider::stream_chat`] streams the complete request: full
+    /// history plus native tool definitions (emitting [`ToolCallDelta`]
+    /// fragments) when the provider supports native tools.
+    /// Default implementation returns false.
+    fn supports_streaming_tool_calls(&self) -> bool {
+        false
+    }
+
+    /// Streaming counterpart of [`Provider::chat`]: text, reasoning and
+    /// tool-call fragments plus final usage, folded back with
+    /// [`StreamAccumulator`].
+    ///
+    /// Default implementation streams tool-less requests through
+    /// `stream_chat_with_history` and rejects requests with tools.
+    fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        if request.tools.is_some_and(|tools| !tools.is_empty()) {
+            return stream::once(async {
+                Err(StreamError::Provider(
+                    "streaming with native tool calls is not supported by this provider".into(),
+                ))
+            })
+            .boxed();
+        }
+        self.stream_chat_with_history(request.messages, model, temperature, options)
+    }
+
     /// Streaming chat with optional system prompt.
     /// Returns an async stream of text chunks.
     /// Default implementation falls back to non-streaming chat.
@@ -784,6 +1030,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            ..Default::default()
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -801,6 +1048,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: None,
+            ..Default::default()
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -901,6 +1149,7 @@ mod tests {
                 ChatMessage::system("BASE_SYSTEM_PROMPT"),
             ],
             tools: Some(&tools),
+            ..Default::default()
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -923,6 +1172,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::system("BASE"), ChatMessage::user("Hello")],
             tools: Some(&tools),
+            ..Default::default()
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -945,6 +1195,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            ..Default::default()
         };
 
         let err = provider.chat(request, "model", 0.7).await.unwrap_err();
@@ -952,4 +1203,74 @@ mod tests {
 
         assert!(message.contains("non-prompt-guided"));
     }
+
+    #[test]
+    fn stream_accumulator_merges_tool_call_fragments() {
+        let mut acc = StreamAccumulator::new();
+        assert!(acc.push(&StreamChunk::reasoning("thinking ")).is_empty());
+        acc.push(&StreamChunk::delta("Let me check."));
+        let started = acc.push(&StreamChunk::tool_call(ToolCallDelta {
+            index: 0,
+            id: Some("call_1".into()),
+            name: Some("shell".into()),
+            arguments: "{\"comm".into(),
+        }));
+        assert_eq!(started, vec!["shell".to_string()]);
+        acc.push(&StreamChunk::tool_call(ToolCallDelta {
+            index: 1,
+            id: None,
+            name: Some("file_read".into()),
+            arguments: String::new(),
+        }));
+        let started = acc.push(&StreamChunk::tool_call(ToolCallDelta {
+            index: 0,
+            arguments: "and\":\"ls\"}".into(),
+            ..ToolCallDelta::default()
+        }));
+        assert!(started.is_empty());
+        acc.push(&StreamChunk::usage(TokenUsage {
+            input_tokens: Some(12),
+            output_tokens: None,
+        }));
+        acc.push(&StreamChunk::usage(TokenUsage {
+            input_tokens: None,
+            output_tokens: Some(7),
+        }));
+
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Let me check."));
+        assert_eq!(response.reasoning_content.as_deref(), Some("thinking "));
+        assert_eq!(response.tool_calls.len(), 2);
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.tool_calls[1].name, "file_read");
+        assert_eq!(response.tool_calls[1].arguments, "{}");
+        assert!(!response.tool_calls[1].id.is_empty());
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, Some(12));
+        assert_eq!(usage.output_tokens, Some(7));
+    }
+
+    #[tokio::test]
+    async fn default_stream_chat_rejects_tools() {
+        let provider = EchoSystemProvider {
+            supports_native: true,
+        };
+        let tools = vec![ToolSpec {
+            name: "shell".to_string(),
+            description: "Run commands".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let request = ChatRequest {
+            messages: &[ChatMessage::user("Hello")],
+            tools: Some(&tools),
+            ..Default::default()
+        };
+        assert!(!provider.supports_streaming_tool_calls());
+        let mut stream = provider.stream_chat(request, "model", 0.7, StreamOptions::new(true));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(StreamError::Provider(_)))
+        ));
+    }
 }
@@ -7,6 +7,17 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Built-in tools that reach the network and are halted by `network_kill`.
+const NETWORK_TOOLS: &[&str] = &[
+    "browser",
+    "browser_open",
+    "composio",
+    "http_request",
+    "pushover",
+    "web_fetch",
+    "web_search_tool",
+];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EstopLevel {
     KillAll,
@@ -55,6 +66,53 @@ impl EstopState {
             || !self.frozen_tools.is_empty()
     }
 
+    /// Explain why a tool call is blocked by the current estop state, if it is.
+    ///
+    /// `network_kill` blocks the built-in network tools; domain blocks apply to
+    /// any call whose `url` argument targets a blocked host.
+    pub fn tool_call_block_reason(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+    ) -> Option<String> {
+        if self.kill_all {
+            return Some("Emergency stop is engaged (kill-all); tool execution is halted.".into());
+        }
+
+        let normalized = tool_name.trim().to_ascii_lowercase();
+        if self.frozen_tools.iter().any(|frozen| frozen == &normalized) {
+            return Some(format!(
+                "Tool '{tool_name}' is frozen by emergency stop (tool-freeze)."
+            ));
+        }
+
+        if self.network_kill && NETWORK_TOOLS.contains(&normalized.as_str()) {
+            return Some(format!(
+                "Tool '{tool_name}' is blocked by emergency stop (network-kill)."
+            ));
+        }
+
+        if !self.blocked_domains.is_empty() {
+            let host = args
+                .get("url")
+                .and_then(serde_json::Value::as_str)
+                .and_then(|raw| reqwest::Url::parse(raw).ok())
+                .and_then(|url| url.host_str().map(ToString::to_string));
+            if let Some(host) = host {
+                // Patterns were validated on engage; an unparsable file fails closed.
+                let gated = DomainMatcher::new(&self.blocked_domains, &[])
+                    .map_or(true, |matcher| matcher.is_gated(&host));
+                if gated {
+                    return Some(format!(
+                        "Tool '{tool_name}' targets '{host}', which is blocked by emergency stop (domain-block)."
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+
     fn normalize(&mut self) {
         self.blocked_domains = dedup_sort(&self.blocked_domains);
         self.frozen_tools = dedup_sort(&self.frozen_tools);
@@ -347,6 +405,53 @@ mod tests {
         assert!(manager.status().frozen_tools.is_empty());
     }
 
+    #[test]
+    fn tool_call_block_reason_covers_each_level() {
+        let args = serde_json::json!({"url": "https://secure.chase.com/login"});
+        let clear = EstopState::default();
+        assert!(clear.tool_call_block_reason("shell", &args).is_none());
+
+        let kill_all = EstopState {
+            kill_all: true,
+            ..EstopState::default()
+        };
+        assert!(kill_all
+            .tool_call_block_reason("file_read", &serde_json::json!({}))
+            .unwrap()
+            .contains("kill-all"));
+
+        let frozen = EstopState {
+            frozen_tools: vec!["shell".into()],
+            ..EstopState::default()
+        };
+        assert!(frozen.tool_call_block_reason("Shell", &args).is_some());
+        assert!(frozen.tool_call_block_reason("file_read", &args).is_none());
+
+        let network = EstopState {
+            network_kill: true,
+            ..EstopState::default()
+        };
+        assert!(network
+            .tool_call_block_reason("http_request", &args)
+            .is_some());
+        assert!(network.tool_call_block_reason("file_read", &args).is_none());
+
+        let domains = EstopState {
+            blocked_domains: vec!["*.chase.com".into()],
+            ..EstopState::default()
+        };
+        assert!(domains
+            .tool_call_block_reason("web_fetch", &args)
+            .unwrap()
+            .contains("secure.chase.com"));
+        assert!(domains
+            .tool_call_block_reason(
+                "web_fetch",
+                &serde_json::json!({"url": "https://example.com"})
+            )
+            .is_none());
+    }
+
     #[test]
     fn estop_state_survives_reload() {
         let dir = tempdir().unwrap();
@@ -24,7 +24,7 @@ pub enum DispatchResult {
     Started {
         run_id: String,
         sop_name: String,
-        action: SopRunAction,
+        action: Box<SopRunAction>,
     },
     /// A matching SOP was found but could not start (cooldown / concurrency).
     Skipped { sop_name: String, reason: String },
@@ -35,9 +35,10 @@ pub enum DispatchResult {
 // ── Action helpers ──────────────────────────────────────────────
 
 /// Extract the `run_id` from any `SopRunAction` variant.
-fn extract_run_id_from_action(action: &SopRunAction) -> &str {
+pub(crate) fn extract_run_id_from_action(action: &SopRunAction) -> &str {
     match action {
         SopRunAction::ExecuteStep { run_id, .. }
+        | SopRunAction::ExecuteParallel { run_id, .. }
         | SopRunAction::WaitApproval { run_id, .. }
         | SopRunAction::Completed { run_id, .. }
         | SopRunAction::Failed { run_id, .. } => run_id,
@@ -48,6 +49,7 @@ fn extract_run_id_from_action(action: &SopRunAction) -> &str {
 fn action_label(action: &SopRunAction) -> &'static str {
     match action {
         SopRunAction::ExecuteStep { .. } => "ExecuteStep",
+        SopRunAction::ExecuteParallel { .. } => "ExecuteParallel",
         SopRunAction::WaitApproval { .. } => "WaitApproval",
         SopRunAction::Completed { .. } => "Completed",
         SopRunAction::Failed { .. } => "Failed",
@@ -124,7 +126,7 @@ pub async fn dispatch_sop_event(
                     results.push(DispatchResult::Started {
                         run_id,
                         sop_name: sop_name.clone(),
-                        action,
+                        action: Box::new(action),
                     });
                 }
                 Err(e) => {
@@ -155,9 +157,10 @@ pub async fn dispatch_sop_event(
 ///
 /// This handles audit and logging for fan-in callers (MQTT, webhook, cron)
 /// that cannot execute SOP steps interactively. For `WaitApproval` actions,
-/// approval timeout polling in the scheduler handles progression.
+/// the daemon's timeout tick handles progression (see `process_timeout_actions`).
 /// For `ExecuteStep` actions, the run is started in the engine but steps
 /// cannot be executed without an agent loop — this is logged as a warning.
+#[allow(clippy::unused_async)]
 pub async fn process_headless_results(results: &[DispatchResult]) {
     for result in results {
         match result {
@@ -165,7 +168,7 @@ pub async fn process_headless_results(results: &[DispatchResult]) {
                 run_id,
                 sop_name,
                 action,
-            } => match action {
+            } => match action.as_ref() {
                 SopRunAction::ExecuteStep { step, .. } => {
                     warn!(
                         "SOP headless dispatch: run {run_id} ('{sop_name}') ready for step {} \
@@ -173,6 +176,13 @@ pub async fn process_headless_results(results: &[DispatchResult]) {
                         step.number, step.title,
                     );
                 }
+                SopRunAction::ExecuteParallel { group, steps, .. } => {
+                    warn!(
+                        "SOP headless dispatch: run {run_id} ('{sop_name}') ready for parallel \
+                         group '{group}' ({} steps) but no agent loop available to execute",
+                        steps.len(),
+                    );
+                }
                 SopRunAction::WaitApproval { step, .. } => {
                     info!(
                         "SOP headless dispatch: run {run_id} ('{sop_name}') waiting for approval \
@@ -197,6 +207,38 @@ pub async fn process_headless_results(results: &[DispatchResult]) {
     }
 }
 
+/// Log the actions produced by [`SopEngine::check_timeouts`] on the daemon
+/// tick. Like headless dispatch, a step that becomes ready waits for an agent
+/// to pick it up via `sop_status`/`sop_advance`.
+pub fn process_timeout_actions(actions: &[SopRunAction]) {
+    for action in actions {
+        match action {
+            SopRunAction::ExecuteStep { run_id, step, .. }
+            | SopRunAction::WaitApproval { run_id, step, .. } => {
+                info!(
+                    "SOP timeout: run {run_id} moved to step {} '{}' ({})",
+                    step.number,
+                    step.title,
+                    action_label(action),
+                );
+            }
+            SopRunAction::ExecuteParallel { run_id, group, .. } => {
+                info!("SOP timeout: run {run_id} moved to parallel group '{group}'");
+            }
+            SopRunAction::Completed { run_id, sop_name } => {
+                info!("SOP timeout: run {run_id} ('{sop_name}') completed");
+            }
+            SopRunAction::Failed {
+                run_id,
+                sop_name,
+                reason,
+            } => {
+                warn!("SOP timeout: run {run_id} ('{sop_name}') failed: {reason}");
+            }
+        }
+    }
+}
+
 // ── Peripheral signal helper ────────────────────────────────────
 
 /// Convenience wrapper for peripheral hardware callbacks.
@@ -250,7 +292,7 @@ impl SopCronCache {
             for trigger in &sop.triggers {
                 if let super::types::SopTrigger::Cron { expression } = trigger {
                     // Normalize 5-field crontab to 6-field (prepend seconds)
-                    let normalized = match crate::cron::schedule::normalize_expression(expression) {
+                    let normalized = match crate::cron::normalize_expression(expression) {
                         Ok(n) => n,
                         Err(e) => {
                             warn!(
@@ -349,6 +391,7 @@ mod tests {
                 body: "Do step one".into(),
                 suggested_tools: vec![],
                 requires_confirmation: false,
+                ..Default::default()
             }],
             cooldown_secs: 0,
             max_concurrent: 2,
@@ -396,7 +439,7 @@ mod tests {
         let results = dispatch_sop_event(&engine, &audit, event).await;
         assert_eq!(results.len(), 1);
         assert!(
-            matches!(&results[0], DispatchResult::Started { sop_name, action, .. } if sop_name == "mqtt-sop" && matches!(action, SopRunAction::ExecuteStep { .. }))
+            matches!(&results[0], DispatchResult::Started { sop_name, action, .. } if sop_name == "mqtt-sop" && matches!(**action, SopRunAction::ExecuteStep { .. }))
         );
     }
 
@@ -534,7 +577,7 @@ mod tests {
                 assert_eq!(sop_name, "supervised-sop");
                 assert!(!run_id.is_empty());
                 assert!(
-                    matches!(action, SopRunAction::WaitApproval { .. }),
+                    matches!(**action, SopRunAction::WaitApproval { .. }),
                     "Supervised SOP must return WaitApproval, got {:?}",
                     action
                 );
@@ -561,7 +604,7 @@ mod tests {
         match &results[0] {
             DispatchResult::Started { action, .. } => {
                 assert!(
-                    matches!(action, SopRunAction::ExecuteStep { .. }),
+                    matches!(**action, SopRunAction::ExecuteStep { .. }),
                     "Auto SOP must return ExecuteStep, got {:?}",
                     action
                 );
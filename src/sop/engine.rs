@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::path::Path;
+use std::sync::Arc;
 
 use anyhow::{bail, Result};
 use tracing::{info, warn};
 
 use super::condition::evaluate_condition;
 use super::load_sops;
+use super::store::SopRunStore;
 use super::types::{
-    Sop, SopEvent, SopPriority, SopRun, SopRunAction, SopRunStatus, SopStep, SopStepResult,
-    SopStepStatus, SopTrigger, SopTriggerSource,
+    Sop, SopActiveStep, SopEvent, SopPriority, SopRun, SopRunAction, SopRunStatus, SopStep,
+    SopStepResult, SopStepStatus, SopStepTarget, SopTrigger, SopTriggerSource,
 };
 use crate::config::SopConfig;
 
+/// How often one run may enter the same step. `goto` and `on_failure` edges
+/// may point backwards; this stops a loop that never exits.
+const MAX_STEP_VISITS: usize = 25;
+
 /// Central SOP orchestrator: loads SOPs, matches triggers, manages run lifecycle.
 pub struct SopEngine {
     sops: Vec<Sop>,
@@ -21,6 +27,8 @@ pub struct SopEngine {
     finished_runs: Vec<SopRun>,
     config: SopConfig,
     run_counter: u64,
+    /// Durable run state; every transition is written through when set.
+    store: Option<Arc<SopRunStore>>,
 }
 
 impl SopEngine {
@@ -32,10 +40,18 @@ impl SopEngine {
             finished_runs: Vec::new(),
             config,
             run_counter: 0,
+            store: None,
         }
     }
 
-    /// Load/reload SOPs from the configured directory.
+    /// Persist run state to `store` and rehydrate from it on `reload()`.
+    pub fn with_store(mut self, store: Arc<SopRunStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load/reload SOPs from the configured directory and, when a run store
+    /// is attached, rehydrate active and finished runs from it.
     pub fn reload(&mut self, workspace_dir: &Path) {
         self.sops = load_sops(
             workspace_dir,
@@ -43,6 +59,7 @@ impl SopEngine {
             self.config.default_execution_mode,
         );
         info!("SOP engine loaded {} SOPs", self.sops.len());
+        self.rehydrate();
     }
 
     /// Return all loaded SOP definitions.
@@ -154,80 +171,80 @@ impl SopEngine {
             completed_at: None,
             step_results: Vec::new(),
             waiting_since: None,
+            active_steps: Vec::new(),
         };
 
         self.active_runs.insert(run_id.clone(), run);
 
         info!("SOP run {} started for '{}'", run_id, sop_name);
 
-        // Determine first action based on execution mode
-        let step = sop.steps[0].clone();
-        let context = format_step_context(&sop, &self.active_runs[&run_id], &step);
-        let action = resolve_step_action(&sop, &step, run_id.clone(), context);
-
-        // If the action is WaitApproval, update run status and record timestamp
-        if matches!(action, SopRunAction::WaitApproval { .. }) {
-            if let Some(run) = self.active_runs.get_mut(&run_id) {
-                run.status = SopRunStatus::WaitingApproval;
-                run.waiting_since = Some(now_iso8601());
-            }
-        }
-
-        Ok(action)
+        Ok(self.enter_step(&run_id, &sop, Some(sop.steps[0].number)))
     }
 
-    /// Report the result of the current step and advance the run.
+    /// Report the result of an active step and advance the run.
     /// Returns the next action to take.
+    ///
+    /// Failed steps follow their `on_failure` edge (or fail the run);
+    /// completed and skipped steps follow `goto` or continue in order. Steps
+    /// of a parallel group join once every member has reported.
     pub fn advance_step(&mut self, run_id: &str, result: SopStepResult) -> Result<SopRunAction> {
         let run = self
             .active_runs
-            .get_mut(run_id)
+            .get(run_id)
             .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
+        let sop = self.loaded_sop_for(run)?;
 
-        let sop = self
-            .sops
+        let step_number = result.step_number;
+        // Runs persisted before step flow existed track only `current_step`.
+        let is_active = run
+            .active_steps
             .iter()
-            .find(|s| s.name == run.sop_name)
-            .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
-            .clone();
+            .any(|a| a.step_number == step_number)
+            || (run.active_steps.is_empty() && step_number == run.current_step);
+        if !is_active {
+            bail!("Step {step_number} of run {run_id} is not awaiting a result");
+        }
+        let step = step_at(&sop, step_number)?;
 
         // Record step result
+        let run = self.active_runs.get_mut(run_id).unwrap();
+        run.active_steps.retain(|a| a.step_number != step_number);
         run.step_results.push(result.clone());
 
-        // Check if step failed
-        if result.status == SopStepStatus::Failed {
-            let reason = format!("Step {} failed: {}", result.step_number, result.output);
-            warn!("SOP run {run_id}: {reason}");
-            return Ok(self.finish_run(run_id, SopRunStatus::Failed, Some(reason)));
-        }
-
-        // Advance to next step
-        let next_step_num = run.current_step + 1;
-        if next_step_num > run.total_steps {
-            // All steps completed
-            info!("SOP run {run_id} completed successfully");
-            return Ok(self.finish_run(run_id, SopRunStatus::Completed, None));
-        }
-
-        // Update run state
-        let run = self.active_runs.get_mut(run_id).unwrap();
-        run.current_step = next_step_num;
-
-        let step_idx = (next_step_num - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-        let context = format_step_context(&sop, run, &step);
-        let run_id_str = run_id.to_string();
-        let action = resolve_step_action(&sop, &step, run_id_str.clone(), context);
-
-        // If the action is WaitApproval, update run status and record timestamp
-        if matches!(action, SopRunAction::WaitApproval { .. }) {
-            if let Some(run) = self.active_runs.get_mut(&run_id_str) {
-                run.status = SopRunStatus::WaitingApproval;
-                run.waiting_since = Some(now_iso8601());
+        if let Some(group) = step.parallel_group.as_deref() {
+            if !run.active_steps.is_empty() {
+                // Other members are still running: hand them out again.
+                let remaining: Vec<SopStep> = run
+                    .active_steps
+                    .iter()
+                    .filter_map(|a| step_at(&sop, a.step_number).ok())
+                    .collect();
+                run.current_step = remaining[0].number;
+                let action = SopRunAction::ExecuteParallel {
+                    run_id: run_id.to_string(),
+                    group: group.to_string(),
+                    context: format_group_context(&sop, run, group, &remaining),
+                    steps: remaining,
+                };
+                self.persist(run_id);
+                return Ok(action);
             }
+            return Ok(self.join_group(run_id, &sop, group));
         }
 
-        Ok(action)
+        let edge = if result.status == SopStepStatus::Failed {
+            let Some(edge) = step.on_failure else {
+                let reason = format!("Step {} failed: {}", result.step_number, result.output);
+                warn!("SOP run {run_id}: {reason}");
+                return Ok(self.finish_run(run_id, SopRunStatus::Failed, Some(reason)));
+            };
+            info!("SOP run {run_id}: step {step_number} failed, continuing at {edge}");
+            Some(edge)
+        } else {
+            step.goto
+        };
+
+        Ok(self.enter_step(run_id, &sop, follow_edge(&sop, step_number, edge)))
     }
 
     /// Cancel an active run.
@@ -244,7 +261,7 @@ impl SopEngine {
     pub fn approve_step(&mut self, run_id: &str) -> Result<SopRunAction> {
         let run = self
             .active_runs
-            .get_mut(run_id)
+            .get(run_id)
             .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
 
         if run.status != SopRunStatus::WaitingApproval {
@@ -254,25 +271,113 @@ impl SopEngine {
             );
         }
 
+        let sop = self.loaded_sop_for(run)?;
+        let steps = active_step_defs(&sop, run)?;
+
+        let run = self.active_runs.get_mut(run_id).unwrap();
         run.status = SopRunStatus::Running;
         run.waiting_since = None;
+        arm_deadlines(run, &steps);
+        let action = execution_action(&sop, run, steps);
+        self.persist(run_id);
 
-        let sop = self
-            .sops
+        Ok(action)
+    }
+
+    /// Re-issue the active step(s) of a running run, e.g. after a restart.
+    ///
+    /// Resuming never approves: a run waiting for approval must go through
+    /// [`Self::approve_step`] (the `sop_approve` tool).
+    pub fn resume_run(&self, run_id: &str) -> Result<SopRunAction> {
+        let run = self
+            .active_runs
+            .get(run_id)
+            .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
+
+        match run.status {
+            SopRunStatus::Running => {}
+            SopRunStatus::WaitingApproval => bail!(
+                "Run {run_id} is waiting for approval of step {}; use approve (sop_approve) instead of resume",
+                run.current_step
+            ),
+            status => bail!("Run {run_id} is {status}; only running runs can be resumed"),
+        }
+
+        let sop = self.loaded_sop_for(run)?;
+        let steps = active_step_defs(&sop, run)?;
+        Ok(execution_action(&sop, run, steps))
+    }
+
+    /// Re-run a step of an active, failed or cancelled run.
+    ///
+    /// `step` defaults to the last failed step (or the current step). The
+    /// results recorded from that step's last execution onwards are
+    /// discarded, and the run re-enters the normal flow at the step. Retrying
+    /// a member of a parallel group re-runs the whole group.
+    pub fn retry_step(&mut self, run_id: &str, step: Option<u32>) -> Result<SopRunAction> {
+        if !self.active_runs.contains_key(run_id) {
+            let idx = self
+                .finished_runs
+                .iter()
+                .position(|r| r.run_id == run_id)
+                .ok_or_else(|| anyhow::anyhow!("Run not found: {run_id}"))?;
+            if self.finished_runs[idx].status == SopRunStatus::Completed {
+                bail!("Run {run_id} already completed; start a new run instead");
+            }
+            let run = self.finished_runs.remove(idx);
+            self.active_runs.insert(run_id.to_string(), run);
+        }
+
+        let run = &self.active_runs[run_id];
+        let sop = match self.loaded_sop_for(run) {
+            Ok(sop) => sop,
+            Err(e) => {
+                self.return_to_finished(run_id);
+                return Err(e);
+            }
+        };
+        let step_number = step.unwrap_or_else(|| {
+            run.step_results
+                .iter()
+                .rev()
+                .find(|r| r.status == SopStepStatus::Failed)
+                .map_or(run.current_step, |r| r.step_number)
+        });
+        let step = match step_at(&sop, step_number) {
+            Ok(step) => step,
+            Err(e) => {
+                self.return_to_finished(run_id);
+                return Err(e);
+            }
+        };
+        let members: Vec<u32> = match step.parallel_group.as_deref() {
+            Some(group) => group_members(&sop, group)
+                .iter()
+                .map(|s| s.number)
+                .collect(),
+            None => vec![step_number],
+        };
+
+        let run = self.active_runs.get_mut(run_id).unwrap();
+        let truncate_at = members
             .iter()
-            .find(|s| s.name == run.sop_name)
-            .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
-            .clone();
+            .filter_map(|&n| run.step_results.iter().rposition(|r| r.step_number == n))
+            .min();
+        if let Some(pos) = truncate_at {
+            run.step_results.truncate(pos);
+        }
+        run.status = SopRunStatus::Running;
+        run.completed_at = None;
+        run.waiting_since = None;
+        run.active_steps.clear();
 
-        let step_idx = (run.current_step - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-        let context = format_step_context(&sop, run, &step);
+        info!("SOP run {run_id}: retrying step {step_number}");
+        Ok(self.enter_step(run_id, &sop, Some(members[0])))
+    }
 
-        Ok(SopRunAction::ExecuteStep {
-            run_id: run_id.to_string(),
-            step,
-            context,
-        })
+    /// Run store attached to this engine, if any.
+    pub fn store(&self) -> Option<&Arc<SopRunStore>> {
+        self.store.as_ref()
     }
 
     /// List finished runs, optionally filtered by SOP name.
@@ -336,6 +441,69 @@ impl SopEngine {
         actions
     }
 
+    // ── Step timeout ──────────────────────────────────────────────
+
+    /// Fail active steps whose `timeout_secs` has elapsed and route their
+    /// runs like any other step failure (`on_failure` or fail the run).
+    /// Returns the resulting actions.
+    pub fn check_step_timeouts(&mut self) -> Vec<SopRunAction> {
+        let now = epoch_secs();
+        let expired: Vec<(String, u32)> = self
+            .active_runs
+            .values()
+            .filter(|r| r.status == SopRunStatus::Running)
+            .flat_map(|r| {
+                r.active_steps
+                    .iter()
+                    .filter(|a| {
+                        a.deadline
+                            .as_deref()
+                            .and_then(parse_iso8601_secs)
+                            .is_some_and(|deadline| now >= deadline)
+                    })
+                    .map(|a| (r.run_id.clone(), a.step_number))
+            })
+            .collect();
+
+        let mut actions = Vec::new();
+        for (run_id, step_number) in expired {
+            if !self.active_runs.contains_key(&run_id) {
+                continue;
+            }
+            let timeout = self
+                .active_runs
+                .get(&run_id)
+                .and_then(|r| self.get_sop(&r.sop_name))
+                .and_then(|sop| step_at(sop, step_number).ok())
+                .and_then(|step| step.timeout_secs)
+                .unwrap_or_default();
+            warn!("SOP run {run_id}: step {step_number} timed out after {timeout}s");
+            let now = now_iso8601();
+            let result = SopStepResult {
+                step_number,
+                status: SopStepStatus::Failed,
+                output: format!("Timed out after {timeout}s"),
+                started_at: now.clone(),
+                completed_at: Some(now),
+            };
+            match self.advance_step(&run_id, result) {
+                Ok(action) => actions.push(action),
+                Err(e) => warn!("SOP run {run_id}: failed to time out step {step_number}: {e}"),
+            }
+        }
+
+        actions
+    }
+
+    /// Run both timeout checks — approvals, then step deadlines — and return
+    /// the resulting actions. Driven by the daemon tick and by the
+    /// `sop_status`/`sop_advance` tools.
+    pub fn check_timeouts(&mut self) -> Vec<SopRunAction> {
+        let mut actions = self.check_approval_timeouts();
+        actions.extend(self.check_step_timeouts());
+        actions
+    }
+
     // ── Test helpers ──────────────────────────────────────────────
 
     /// Replace loaded SOPs (for testing from other modules).
@@ -346,6 +514,238 @@ impl SopEngine {
 
     // ── Internal helpers ────────────────────────────────────────
 
+    /// Replace in-memory runs with the contents of the run store.
+    fn rehydrate(&mut self) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+        let runs = match store.load_runs() {
+            Ok(runs) => runs,
+            Err(e) => {
+                warn!("SOP engine: failed to load persisted runs: {e}");
+                return;
+            }
+        };
+
+        self.active_runs.clear();
+        self.finished_runs.clear();
+        for run in runs {
+            self.run_counter = self.run_counter.max(run_counter_of(&run.run_id));
+            if self.get_sop(&run.sop_name).is_none() {
+                warn!(
+                    "SOP run {} references SOP '{}' which is not loaded",
+                    run.run_id, run.sop_name
+                );
+            }
+            match run.status {
+                SopRunStatus::Completed | SopRunStatus::Failed | SopRunStatus::Cancelled => {
+                    self.finished_runs.push(run);
+                }
+                SopRunStatus::Pending | SopRunStatus::Running | SopRunStatus::WaitingApproval => {
+                    self.active_runs.insert(run.run_id.clone(), run);
+                }
+            }
+        }
+        self.finished_runs
+            .sort_by(|a, b| a.completed_at.cmp(&b.completed_at));
+        self.evict_finished();
+
+        info!(
+            "SOP engine restored {} active and {} finished run(s)",
+            self.active_runs.len(),
+            self.finished_runs.len()
+        );
+    }
+
+    /// Write a run's current state to the store (no-op without a store).
+    fn persist(&self, run_id: &str) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Some(run) = self.get_run(run_id) {
+            if let Err(e) = store.save_run(run) {
+                warn!("SOP run {run_id}: failed to persist state: {e}");
+            }
+        }
+    }
+
+    /// Evict the oldest finished runs when over capacity.
+    fn evict_finished(&mut self) {
+        let max = self.config.max_finished_runs;
+        if max == 0 || self.finished_runs.len() <= max {
+            return;
+        }
+        let excess = self.finished_runs.len() - max;
+        for run in self.finished_runs.drain(..excess) {
+            if let Some(store) = &self.store {
+                if let Err(e) = store.delete_run(&run.run_id) {
+                    warn!("SOP run {}: failed to evict from store: {e}", run.run_id);
+                }
+            }
+        }
+    }
+
+    /// Move a run that was pulled back for a retry into the finished list again.
+    fn return_to_finished(&mut self, run_id: &str) {
+        if let Some(run) = self.active_runs.remove(run_id) {
+            if run.completed_at.is_some() {
+                self.finished_runs.push(run);
+            } else {
+                self.active_runs.insert(run_id.to_string(), run);
+            }
+        }
+    }
+
+    /// Route a run to `target` and hand out the next step (or parallel
+    /// group). Steps whose condition does not hold are recorded as skipped
+    /// and the run continues in document order; `None` completes the run.
+    fn enter_step(&mut self, run_id: &str, sop: &Sop, target: Option<u32>) -> SopRunAction {
+        let mut target = target;
+        // Skipping only moves forward, so this terminates.
+        loop {
+            let Some(number) = target else {
+                info!("SOP run {run_id} completed successfully");
+                return self.finish_run(run_id, SopRunStatus::Completed, None);
+            };
+            let step = match step_at(sop, number) {
+                Ok(step) => step,
+                Err(e) => {
+                    warn!("SOP run {run_id}: {e}");
+                    return self.finish_run(run_id, SopRunStatus::Failed, Some(e.to_string()));
+                }
+            };
+            let members: Vec<SopStep> = match step.parallel_group.as_deref() {
+                Some(group) => group_members(sop, group).into_iter().cloned().collect(),
+                None => vec![step],
+            };
+
+            let run = self.active_runs.get_mut(run_id).unwrap();
+            let mut runnable = Vec::new();
+            for member in &members {
+                match member.condition.as_deref() {
+                    Some(condition) if !step_condition_holds(condition, run) => {
+                        info!(
+                            "SOP run {run_id}: skipping step {} (condition not met)",
+                            member.number
+                        );
+                        let now = now_iso8601();
+                        run.step_results.push(SopStepResult {
+                            step_number: member.number,
+                            status: SopStepStatus::Skipped,
+                            output: format!("Condition not met: {condition}"),
+                            started_at: now.clone(),
+                            completed_at: Some(now),
+                        });
+                    }
+                    _ => runnable.push(member.clone()),
+                }
+            }
+
+            if runnable.is_empty() {
+                let last = members.last().map_or(number, |s| s.number);
+                target = next_in_order(sop, last);
+                continue;
+            }
+            if let Some(step) = runnable.iter().find(|s| {
+                run.step_results
+                    .iter()
+                    .filter(|r| r.step_number == s.number)
+                    .count()
+                    >= MAX_STEP_VISITS
+            }) {
+                let reason = format!(
+                    "Step {} entered {MAX_STEP_VISITS} times; stopping a likely goto loop",
+                    step.number
+                );
+                warn!("SOP run {run_id}: {reason}");
+                return self.finish_run(run_id, SopRunStatus::Failed, Some(reason));
+            }
+            return self.hand_out(run_id, sop, runnable);
+        }
+    }
+
+    /// Make `steps` the run's active steps and decide whether they need
+    /// approval before executing.
+    fn hand_out(&mut self, run_id: &str, sop: &Sop, steps: Vec<SopStep>) -> SopRunAction {
+        let run = self.active_runs.get_mut(run_id).unwrap();
+        // Supervised approval applies before the first step that actually runs.
+        let is_first = run
+            .step_results
+            .iter()
+            .all(|r| r.status == SopStepStatus::Skipped);
+        run.current_step = steps[0].number;
+        run.status = SopRunStatus::Running;
+        run.waiting_since = None;
+        run.active_steps = steps
+            .iter()
+            .map(|s| SopActiveStep {
+                step_number: s.number,
+                deadline: None,
+            })
+            .collect();
+
+        let action = if steps.iter().any(|s| needs_approval(sop, s, is_first)) {
+            run.status = SopRunStatus::WaitingApproval;
+            run.waiting_since = Some(now_iso8601());
+            let context = match steps[0].parallel_group.as_deref() {
+                Some(group) => format_group_context(sop, run, group, &steps),
+                None => format_step_context(sop, run, &steps[0]),
+            };
+            SopRunAction::WaitApproval {
+                run_id: run_id.to_string(),
+                step: steps[0].clone(),
+                context,
+            }
+        } else {
+            arm_deadlines(run, &steps);
+            execution_action(sop, run, steps)
+        };
+        self.persist(run_id);
+        action
+    }
+
+    /// All members of `group` have reported: continue at the first failed
+    /// member's `on_failure` (or fail the run), otherwise at the group's
+    /// `goto` or the step after the group.
+    fn join_group(&mut self, run_id: &str, sop: &Sop, group: &str) -> SopRunAction {
+        let run = &self.active_runs[run_id];
+        let members = group_members(sop, group);
+        let last = members.last().map_or(0, |s| s.number);
+        let latest = |number: u32| {
+            run.step_results
+                .iter()
+                .rev()
+                .find(|r| r.step_number == number)
+        };
+
+        let failed = members
+            .iter()
+            .find(|m| latest(m.number).is_some_and(|r| r.status == SopStepStatus::Failed));
+        let edge = if let Some(failed) = failed {
+            let Some(edge) = failed.on_failure else {
+                let output = latest(failed.number).map_or("", |r| r.output.as_str());
+                let reason = format!(
+                    "Step {} failed: {output} (parallel group '{group}')",
+                    failed.number
+                );
+                warn!("SOP run {run_id}: {reason}");
+                return self.finish_run(run_id, SopRunStatus::Failed, Some(reason));
+            };
+            Some(edge)
+        } else {
+            members.iter().find_map(|m| m.goto)
+        };
+
+        info!("SOP run {run_id}: parallel group '{group}' joined");
+        self.enter_step(run_id, sop, follow_edge(sop, last, edge))
+    }
+
+    fn loaded_sop_for(&self, run: &SopRun) -> Result<Sop> {
+        self.get_sop(&run.sop_name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))
+    }
+
     fn last_finished_run(&self, sop_name: &str) -> Option<&SopRun> {
         self.finished_runs
             .iter()
@@ -362,16 +762,13 @@ impl SopEngine {
         let mut run = self.active_runs.remove(run_id).unwrap();
         run.status = status;
         run.completed_at = Some(now_iso8601());
+        run.waiting_since = None;
+        run.active_steps.clear();
         let sop_name = run.sop_name.clone();
         let run_id_owned = run.run_id.clone();
         self.finished_runs.push(run);
-
-        // Evict oldest finished runs when over capacity
-        let max = self.config.max_finished_runs;
-        if max > 0 && self.finished_runs.len() > max {
-            let excess = self.finished_runs.len() - max;
-            self.finished_runs.drain(..excess);
-        }
+        self.persist(&run_id_owned);
+        self.evict_finished();
 
         match status {
             SopRunStatus::Failed => SopRunAction::Failed {
@@ -473,24 +870,237 @@ fn mqtt_topic_matches(pattern: &str, topic: &str) -> bool {
     pi == pat_parts.len() && ti == top_parts.len()
 }
 
+/// Look up a step by its 1-based number.
+fn step_at(sop: &Sop, step_number: u32) -> Result<SopStep> {
+    step_number
+        .checked_sub(1)
+        .and_then(|idx| sop.steps.get(idx as usize))
+        .cloned()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "SOP '{}' has no step {step_number} (it has {} steps)",
+                sop.name,
+                sop.steps.len()
+            )
+        })
+}
+
+/// Extract the counter suffix from a `run-{epoch_ms}-{counter}` run ID.
+fn run_counter_of(run_id: &str) -> u64 {
+    run_id
+        .rsplit('-')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+// ── Step flow ───────────────────────────────────────────────────
+
+/// Steps of the parallel group `group`, in document order.
+fn group_members<'a>(sop: &'a Sop, group: &str) -> Vec<&'a SopStep> {
+    sop.steps
+        .iter()
+        .filter(|s| s.parallel_group.as_deref() == Some(group))
+        .collect()
+}
+
+/// The step after `step_number` in document order (`None` at the end).
+fn next_in_order(sop: &Sop, step_number: u32) -> Option<u32> {
+    sop.steps
+        .iter()
+        .map(|s| s.number)
+        .find(|&n| n > step_number)
+}
+
+/// Where to continue after `from`: the edge's step, the end of the run for
+/// `"end"`, or the next step when there is no edge.
+fn follow_edge(sop: &Sop, from: u32, edge: Option<SopStepTarget>) -> Option<u32> {
+    match edge {
+        Some(SopStepTarget::Step(n)) => Some(n),
+        Some(SopStepTarget::End) => None,
+        None => next_in_order(sop, from),
+    }
+}
+
+/// Definitions of the steps a run is waiting on.
+fn active_step_defs(sop: &Sop, run: &SopRun) -> Result<Vec<SopStep>> {
+    if run.active_steps.is_empty() {
+        return Ok(vec![step_at(sop, run.current_step)?]);
+    }
+    run.active_steps
+        .iter()
+        .map(|a| step_at(sop, a.step_number))
+        .collect()
+}
+
+/// (Re)build the active step list for `steps` with deadlines starting now.
+fn arm_deadlines(run: &mut SopRun, steps: &[SopStep]) {
+    let now = epoch_secs();
+    run.active_steps = steps
+        .iter()
+        .map(|s| SopActiveStep {
+            step_number: s.number,
+            deadline: s.timeout_secs.map(|t| iso8601_from_secs(now + t)),
+        })
+        .collect();
+}
+
+/// The action that hands `steps` to the agent for execution.
+fn execution_action(sop: &Sop, run: &SopRun, mut steps: Vec<SopStep>) -> SopRunAction {
+    match steps[0].parallel_group.clone() {
+        Some(group) => SopRunAction::ExecuteParallel {
+            run_id: run.run_id.clone(),
+            context: format_group_context(sop, run, &group, &steps),
+            group,
+            steps,
+        },
+        None => {
+            let step = steps.swap_remove(0);
+            SopRunAction::ExecuteStep {
+                run_id: run.run_id.clone(),
+                context: format_step_context(sop, run, &step),
+                step,
+            }
+        }
+    }
+}
+
+/// Evaluate a step condition with `sop/condition.rs`.
+///
+/// `$` paths resolve against `{"trigger": <payload>, "steps": {"<n>":
+/// {"status", "output"}}, "last": {...}}`, where payloads and outputs that
+/// are valid JSON are embedded as JSON. Direct comparisons (`> 0`) apply to
+/// the raw trigger payload, as for trigger conditions.
+fn step_condition_holds(condition: &str, run: &SopRun) -> bool {
+    if condition.trim_start().starts_with('$') {
+        let document = condition_document(run).to_string();
+        evaluate_condition(condition, Some(&document))
+    } else {
+        evaluate_condition(condition, run.trigger_event.payload.as_deref())
+    }
+}
+
+fn condition_document(run: &SopRun) -> serde_json::Value {
+    let embed = |text: &str| {
+        serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.into()))
+    };
+    let describe = |r: &SopStepResult| {
+        serde_json::json!({
+            "status": r.status,
+            "output": embed(&r.output),
+        })
+    };
+
+    let steps: serde_json::Map<String, serde_json::Value> = run
+        .step_results
+        .iter()
+        .map(|r| (r.step_number.to_string(), describe(r)))
+        .collect();
+    let last = run
+        .step_results
+        .iter()
+        .rev()
+        .find(|r| r.status != SopStepStatus::Skipped)
+        .map(describe);
+
+    serde_json::json!({
+        "trigger": run.trigger_event.payload.as_deref().map(embed),
+        "steps": steps,
+        "last": last,
+    })
+}
+
+/// Describe the actions returned by [`SopEngine::check_timeouts`] for the
+/// agent, including the next step's context when a run moved on. Empty when
+/// nothing timed out.
+pub fn format_timeout_actions(actions: &[SopRunAction]) -> String {
+    let mut out = String::new();
+    for action in actions {
+        let _ = match action {
+            SopRunAction::ExecuteStep {
+                run_id, context, ..
+            }
+            | SopRunAction::ExecuteParallel {
+                run_id, context, ..
+            } => writeln!(out, "Run {run_id} hit a timeout. Next:\n\n{context}\n"),
+            SopRunAction::WaitApproval {
+                run_id, context, ..
+            } => writeln!(
+                out,
+                "Run {run_id} hit a timeout. Next (waiting for approval):\n\n{context}\n"
+            ),
+            SopRunAction::Completed { run_id, sop_name } => {
+                writeln!(out, "SOP '{sop_name}' run {run_id} completed.\n")
+            }
+            SopRunAction::Failed {
+                run_id,
+                sop_name,
+                reason,
+            } => writeln!(out, "SOP '{sop_name}' run {run_id} failed: {reason}\n"),
+        };
+    }
+    out
+}
+
+/// Render the path a run has taken, e.g.
+/// `1 completed → 2 skipped → [checks: 3 completed, 4 failed] → 6 in progress`.
+pub fn format_run_path(run: &SopRun, sop: Option<&Sop>) -> String {
+    let group_of = |number: u32| {
+        sop.and_then(|sop| sop.steps.iter().find(|s| s.number == number))
+            .and_then(|s| s.parallel_group.clone())
+    };
+    let pending = if run.status == SopRunStatus::WaitingApproval {
+        "awaiting approval"
+    } else {
+        "in progress"
+    };
+    let visited = run
+        .step_results
+        .iter()
+        .map(|r| (r.step_number, r.status.to_string()))
+        .chain(
+            run.active_steps
+                .iter()
+                .map(|a| (a.step_number, pending.to_string())),
+        );
+
+    let mut segments: Vec<(Option<String>, Vec<String>)> = Vec::new();
+    for (number, label) in visited {
+        let entry = format!("{number} {label}");
+        let group = group_of(number);
+        match segments.last_mut() {
+            Some((Some(current), entries)) if group.as_ref() == Some(current) => {
+                entries.push(entry);
+            }
+            _ => segments.push((group, vec![entry])),
+        }
+    }
+
+    segments
+        .into_iter()
+        .map(|(group, entries)| match group {
+            Some(group) => format!("[{group}: {}]", entries.join(", ")),
+            None => entries.join(", "),
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
 // ── Execution mode resolution ───────────────────────────────────
 
-/// Determine the action for a step based on SOP execution mode.
-fn resolve_step_action(sop: &Sop, step: &SopStep, run_id: String, context: String) -> SopRunAction {
+/// Whether a step needs approval before executing, based on the SOP
+/// execution mode. `is_first` marks the first step of the run that executes.
+fn needs_approval(sop: &Sop, step: &SopStep, is_first: bool) -> bool {
     // Steps with requires_confirmation always need approval
     if step.requires_confirmation {
-        return SopRunAction::WaitApproval {
-            run_id,
-            step: step.clone(),
-            context,
-        };
+        return true;
     }
 
-    let needs_approval = match sop.execution_mode {
+    match sop.execution_mode {
         crate::sop::SopExecutionMode::Auto => false,
         crate::sop::SopExecutionMode::Supervised => {
             // Supervised: approval only before the first step
-            step.number == 1
+            is_first
         }
         crate::sop::SopExecutionMode::StepByStep => true,
         crate::sop::SopExecutionMode::PriorityBased => {
@@ -498,24 +1108,10 @@ fn resolve_step_action(sop: &Sop, step: &SopStep, run_id: String, context: Strin
                 SopPriority::Critical | SopPriority::High => false,
                 SopPriority::Normal | SopPriority::Low => {
                     // Supervised behavior for normal/low
-                    step.number == 1
+                    is_first
                 }
             }
         }
-    };
-
-    if needs_approval {
-        SopRunAction::WaitApproval {
-            run_id,
-            step: step.clone(),
-            context,
-        }
-    } else {
-        SopRunAction::ExecuteStep {
-            run_id,
-            step: step.clone(),
-            context,
-        }
     }
 }
 
@@ -527,7 +1123,63 @@ fn format_step_context(sop: &Sop, run: &SopRun, step: &SopStep) -> String {
         "[SOP: {} (run {}) — Step {} of {}]\n\n",
         sop.name, run.run_id, step.number, run.total_steps
     );
+    write_run_context(&mut ctx, run);
+
+    let _ = write!(ctx, "\nCurrent step: **{}**\n{}\n", step.title, step.body);
+
+    if !step.suggested_tools.is_empty() {
+        let _ = write!(
+            ctx,
+            "\nSuggested tools: {}\n",
+            step.suggested_tools.join(", ")
+        );
+    }
+    if let Some(timeout) = step.timeout_secs {
+        let _ = write!(ctx, "\nTime limit: {timeout}s\n");
+    }
+
+    ctx.push_str("\nWhen done, report your result.\n");
+
+    ctx
+}
+
+/// Context for the members of a parallel group still awaiting results.
+fn format_group_context(sop: &Sop, run: &SopRun, group: &str, steps: &[SopStep]) -> String {
+    let numbers: Vec<String> = steps.iter().map(|s| s.number.to_string()).collect();
+    let mut ctx = format!(
+        "[SOP: {} (run {}) — Parallel group '{group}': steps {} of {}]\n\n",
+        sop.name,
+        run.run_id,
+        numbers.join(", "),
+        run.total_steps
+    );
+    write_run_context(&mut ctx, run);
+
+    ctx.push_str("\nSteps to run in parallel:\n");
+    for step in steps {
+        let _ = write!(
+            ctx,
+            "\n{}. **{}**\n{}\n",
+            step.number, step.title, step.body
+        );
+        if !step.suggested_tools.is_empty() {
+            let _ = writeln!(ctx, "Suggested tools: {}", step.suggested_tools.join(", "));
+        }
+        if let Some(timeout) = step.timeout_secs {
+            let _ = writeln!(ctx, "Time limit: {timeout}s");
+        }
+    }
+
+    ctx.push_str(
+        "\nReport each step's result separately (with its step number). \
+         The run continues once every step has reported.\n",
+    );
+
+    ctx
+}
 
+/// Trigger, payload and previous-step lines shared by step contexts.
+fn write_run_context(ctx: &mut String, run: &SopRun) {
     let _ = writeln!(
         ctx,
         "Trigger: {} {}",
@@ -547,31 +1199,24 @@ fn format_step_context(sop: &Sop, run: &SopRun, step: &SopStep) -> String {
             prev.step_number, prev.status, prev.output
         );
     }
-
-    let _ = write!(ctx, "\nCurrent step: **{}**\n{}\n", step.title, step.body);
-
-    if !step.suggested_tools.is_empty() {
-        let _ = write!(
-            ctx,
-            "\nSuggested tools: {}\n",
-            step.suggested_tools.join(", ")
-        );
-    }
-
-    ctx.push_str("\nWhen done, report your result.\n");
-
-    ctx
 }
 
 // ── Utilities ───────────────────────────────────────────────────
 
 pub(crate) fn now_iso8601() -> String {
-    // Use chrono if available, otherwise fallback to SystemTime
-    let now = std::time::SystemTime::now()
+    iso8601_from_secs(epoch_secs())
+}
+
+fn epoch_secs() -> u64 {
+    std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default();
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Format seconds since the Unix epoch as a UTC ISO-8601 timestamp.
+fn iso8601_from_secs(secs: u64) -> String {
     // Simple UTC timestamp without chrono dependency
-    let secs = now.as_secs();
     let days = secs / 86400;
     let time_secs = secs % 86400;
     let hours = time_secs / 3600;
@@ -603,10 +1248,7 @@ fn days_to_ymd(mut days: u64) -> (u64, u64, u64) {
 fn cooldown_elapsed(completed_at: &str, cooldown_secs: u64) -> bool {
     // Parse the ISO-8601 timestamp we generate
     let completed = parse_iso8601_secs(completed_at);
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+    let now = epoch_secs();
 
     match completed {
         Some(ts) => now.saturating_sub(ts) >= cooldown_secs,
@@ -680,6 +1322,7 @@ mod tests {
                     body: "Do step one".into(),
                     suggested_tools: vec!["shell".into()],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
                 SopStep {
                     number: 2,
@@ -687,6 +1330,7 @@ mod tests {
                     body: "Do step two".into(),
                     suggested_tools: vec![],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
             ],
             cooldown_secs: 0,
@@ -705,6 +1349,7 @@ mod tests {
     fn extract_run_id(action: &SopRunAction) -> &str {
         match action {
             SopRunAction::ExecuteStep { run_id, .. }
+            | SopRunAction::ExecuteParallel { run_id, .. }
             | SopRunAction::WaitApproval { run_id, .. }
             | SopRunAction::Completed { run_id, .. }
             | SopRunAction::Failed { run_id, .. } => run_id,
@@ -1359,6 +2004,7 @@ mod tests {
             completed_at: None,
             step_results: Vec::new(),
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         let ctx = format_step_context(&sop, &run, &sop.steps[0]);
         assert!(ctx.contains("pump-shutdown"));
@@ -1628,4 +2274,425 @@ mod tests {
         assert_eq!(run.status, SopRunStatus::Running);
         assert!(run.waiting_since.is_none());
     }
+
+    // ── Persistence ───────────────────────────────────
+
+    fn write_sop(workspace: &Path, name: &str, mode: &str) {
+        let dir = workspace.join("sops").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SOP.toml"),
+            format!(
+                "[sop]\nname = \"{name}\"\ndescription = \"d\"\nexecution_mode = \"{mode}\"\n\n[[triggers]]\ntype = \"manual\"\n"
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("SOP.md"),
+            "## Steps\n\n1. **Check** — Check it.\n2. **Fix** — Fix it.\n",
+        )
+        .unwrap();
+    }
+
+    fn persistent_engine(workspace: &Path) -> SopEngine {
+        let store = Arc::new(SopRunStore::open(workspace).unwrap());
+        let mut engine = SopEngine::new(SopConfig::default()).with_store(store);
+        engine.reload(workspace);
+        engine
+    }
+
+    fn step_result(step_number: u32, status: SopStepStatus) -> SopStepResult {
+        SopStepResult {
+            step_number,
+            status,
+            output: format!("step {step_number} {status}"),
+            started_at: now_iso8601(),
+            completed_at: Some(now_iso8601()),
+        }
+    }
+
+    #[test]
+    fn pending_approval_survives_restart_and_resumes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_sop(tmp.path(), "s1", "supervised");
+
+        let run_id = {
+            let mut engine = persistent_engine(tmp.path());
+            let action = engine.start_run("s1", manual_event()).unwrap();
+            assert!(matches!(action, SopRunAction::WaitApproval { .. }));
+            extract_run_id(&action).to_string()
+        };
+
+        let mut engine = persistent_engine(tmp.path());
+        let run = engine.get_run(&run_id).expect("run rehydrated");
+        assert_eq!(run.status, SopRunStatus::WaitingApproval);
+        assert!(run.waiting_since.is_some());
+        assert_eq!(
+            engine.store().unwrap().pending_approvals().unwrap()[0].run_id,
+            run_id
+        );
+
+        let err = engine.resume_run(&run_id).unwrap_err().to_string();
+        assert!(err.contains("use approve"), "{err}");
+        assert_eq!(
+            engine.get_run(&run_id).unwrap().status,
+            SopRunStatus::WaitingApproval
+        );
+
+        let action = engine.approve_step(&run_id).unwrap();
+        assert!(matches!(action, SopRunAction::ExecuteStep { ref step, .. } if step.number == 1));
+        engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        assert!(engine
+            .store()
+            .unwrap()
+            .pending_approvals()
+            .unwrap()
+            .is_empty());
+
+        // Mid-step runs come back as Running at the same step.
+        let mut engine = persistent_engine(tmp.path());
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.status, SopRunStatus::Running);
+        assert_eq!(run.current_step, 2);
+        assert_eq!(run.step_results.len(), 1);
+        let action = engine.resume_run(&run_id).unwrap();
+        assert!(matches!(action, SopRunAction::ExecuteStep { ref step, .. } if step.number == 2));
+
+        // New runs do not reuse the restored run's ID.
+        engine.sops[0].max_concurrent = 2;
+        let next = engine.start_run("s1", manual_event()).unwrap();
+        assert_ne!(extract_run_id(&next), run_id);
+    }
+
+    #[test]
+    fn retry_step_reopens_failed_run_and_persists() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_sop(tmp.path(), "s1", "auto");
+
+        let mut engine = persistent_engine(tmp.path());
+        let action = engine.start_run("s1", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        let action = engine
+            .advance_step(&run_id, step_result(2, SopStepStatus::Failed))
+            .unwrap();
+        assert!(matches!(action, SopRunAction::Failed { .. }));
+
+        let mut engine = persistent_engine(tmp.path());
+        assert_eq!(
+            engine.get_run(&run_id).unwrap().status,
+            SopRunStatus::Failed
+        );
+        assert!(engine.retry_step(&run_id, Some(9)).is_err());
+        assert_eq!(
+            engine.finished_runs(None).len(),
+            1,
+            "bad retry keeps run finished"
+        );
+
+        let action = engine.retry_step(&run_id, None).unwrap();
+        assert!(matches!(action, SopRunAction::ExecuteStep { ref step, .. } if step.number == 2));
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.status, SopRunStatus::Running);
+        assert!(run.completed_at.is_none());
+        assert_eq!(run.step_results.len(), 1, "failed result discarded");
+
+        engine.cancel_run(&run_id).unwrap();
+        let engine = persistent_engine(tmp.path());
+        assert_eq!(
+            engine.get_run(&run_id).unwrap().status,
+            SopRunStatus::Cancelled
+        );
+        assert!(engine.active_runs().is_empty());
+    }
+
+    #[test]
+    fn retry_rejects_completed_runs() {
+        let mut engine = engine_with_sops(vec![test_sop(
+            "s1",
+            SopExecutionMode::Auto,
+            SopPriority::Normal,
+        )]);
+        let action = engine.start_run("s1", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        engine
+            .advance_step(&run_id, step_result(2, SopStepStatus::Completed))
+            .unwrap();
+        assert!(engine.retry_step(&run_id, Some(1)).is_err());
+        assert!(engine.resume_run(&run_id).is_err());
+    }
+
+    // ── Step flow ─────────────────────────────────────
+
+    fn flow_step(number: u32) -> SopStep {
+        SopStep {
+            number,
+            title: format!("Step {number}"),
+            body: format!("Do step {number}"),
+            ..Default::default()
+        }
+    }
+
+    fn flow_sop(steps: Vec<SopStep>) -> Sop {
+        Sop {
+            steps,
+            ..test_sop("flow", SopExecutionMode::Auto, SopPriority::Normal)
+        }
+    }
+
+    fn payload_event(payload: &str) -> SopEvent {
+        SopEvent {
+            payload: Some(payload.into()),
+            ..manual_event()
+        }
+    }
+
+    fn executed_step(action: &SopRunAction) -> u32 {
+        match action {
+            SopRunAction::ExecuteStep { step, .. } => step.number,
+            other => panic!("expected ExecuteStep, got {other:?}"),
+        }
+    }
+
+    /// 1: read sensor → 2: escalate if value > 85, then stop → 3: log.
+    fn threshold_sop() -> Sop {
+        flow_sop(vec![
+            flow_step(1),
+            SopStep {
+                condition: Some("$.trigger.value > 85".into()),
+                goto: Some(SopStepTarget::End),
+                ..flow_step(2)
+            },
+            flow_step(3),
+        ])
+    }
+
+    #[test]
+    fn condition_selects_branch() {
+        let mut engine = engine_with_sops(vec![threshold_sop()]);
+        let action = engine
+            .start_run("flow", payload_event(r#"{"value": 90}"#))
+            .unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        let action = engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        assert_eq!(executed_step(&action), 2);
+        let action = engine
+            .advance_step(&run_id, step_result(2, SopStepStatus::Completed))
+            .unwrap();
+        assert!(matches!(action, SopRunAction::Completed { .. }), "goto end");
+
+        let mut engine = engine_with_sops(vec![threshold_sop()]);
+        let action = engine
+            .start_run("flow", payload_event(r#"{"value": 40}"#))
+            .unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        let action = engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        assert_eq!(executed_step(&action), 3, "step 2 skipped");
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.step_results[1].step_number, 2);
+        assert_eq!(run.step_results[1].status, SopStepStatus::Skipped);
+    }
+
+    #[test]
+    fn condition_reads_previous_step_output() {
+        let sop = flow_sop(vec![
+            flow_step(1),
+            SopStep {
+                condition: Some("$.steps.1.output.healthy == false".into()),
+                ..flow_step(2)
+            },
+        ]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        let result = SopStepResult {
+            output: r#"{"healthy": true}"#.into(),
+            ..step_result(1, SopStepStatus::Completed)
+        };
+        let action = engine.advance_step(&run_id, result).unwrap();
+        assert!(matches!(action, SopRunAction::Completed { .. }));
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(
+            run.step_results.last().unwrap().status,
+            SopStepStatus::Skipped
+        );
+    }
+
+    #[test]
+    fn on_failure_edge_continues_run() {
+        let sop = flow_sop(vec![
+            SopStep {
+                on_failure: Some(SopStepTarget::Step(3)),
+                ..flow_step(1)
+            },
+            flow_step(2),
+            flow_step(3),
+        ]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        let action = engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Failed))
+            .unwrap();
+        assert_eq!(executed_step(&action), 3);
+        let action = engine
+            .advance_step(&run_id, step_result(3, SopStepStatus::Completed))
+            .unwrap();
+        assert!(matches!(action, SopRunAction::Completed { .. }));
+    }
+
+    #[test]
+    fn unconditional_goto_loop_fails_after_visit_limit() {
+        let sop = flow_sop(vec![
+            flow_step(1),
+            SopStep {
+                goto: Some(SopStepTarget::Step(1)),
+                ..flow_step(2)
+            },
+        ]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+
+        let mut action = action;
+        let mut advances = 0;
+        while let SopRunAction::ExecuteStep { ref step, .. } = action {
+            let number = step.number;
+            action = engine
+                .advance_step(&run_id, step_result(number, SopStepStatus::Completed))
+                .unwrap();
+            advances += 1;
+            assert!(advances <= 2 * MAX_STEP_VISITS, "loop was not stopped");
+        }
+        assert!(
+            matches!(action, SopRunAction::Failed { ref reason, .. } if reason.contains("Step 1 entered")),
+            "{action:?}"
+        );
+        assert_eq!(advances, 2 * MAX_STEP_VISITS);
+    }
+
+    #[test]
+    fn advance_rejects_inactive_step() {
+        let mut engine = engine_with_sops(vec![threshold_sop()]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        assert!(engine
+            .advance_step(&run_id, step_result(3, SopStepStatus::Completed))
+            .is_err());
+    }
+
+    fn parallel_sop() -> Sop {
+        let member = |number| SopStep {
+            parallel_group: Some("checks".into()),
+            ..flow_step(number)
+        };
+        flow_sop(vec![
+            flow_step(1),
+            member(2),
+            member(3),
+            member(4),
+            flow_step(5),
+        ])
+    }
+
+    #[test]
+    fn parallel_group_joins_before_continuing() {
+        let mut engine = engine_with_sops(vec![parallel_sop()]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        let action = engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        match &action {
+            SopRunAction::ExecuteParallel { group, steps, .. } => {
+                assert_eq!(group, "checks");
+                assert_eq!(steps.len(), 3);
+            }
+            other => panic!("expected ExecuteParallel, got {other:?}"),
+        }
+
+        let action = engine
+            .advance_step(&run_id, step_result(3, SopStepStatus::Completed))
+            .unwrap();
+        assert!(
+            matches!(action, SopRunAction::ExecuteParallel { ref steps, .. } if steps.len() == 2)
+        );
+        engine
+            .advance_step(&run_id, step_result(2, SopStepStatus::Completed))
+            .unwrap();
+        let action = engine
+            .advance_step(&run_id, step_result(4, SopStepStatus::Completed))
+            .unwrap();
+        assert_eq!(executed_step(&action), 5);
+
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(
+            format_run_path(run, engine.get_sop("flow")),
+            "1 completed → [checks: 3 completed, 2 completed, 4 completed] → 5 in progress"
+        );
+    }
+
+    #[test]
+    fn parallel_group_failure_fails_run_after_join() {
+        let mut engine = engine_with_sops(vec![parallel_sop()]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        engine
+            .advance_step(&run_id, step_result(1, SopStepStatus::Completed))
+            .unwrap();
+        engine
+            .advance_step(&run_id, step_result(2, SopStepStatus::Failed))
+            .unwrap();
+        assert_eq!(
+            engine.get_run(&run_id).unwrap().status,
+            SopRunStatus::Running,
+            "group still running"
+        );
+        engine
+            .advance_step(&run_id, step_result(3, SopStepStatus::Completed))
+            .unwrap();
+        let action = engine
+            .advance_step(&run_id, step_result(4, SopStepStatus::Completed))
+            .unwrap();
+        assert!(
+            matches!(action, SopRunAction::Failed { ref reason, .. } if reason.contains("Step 2"))
+        );
+    }
+
+    #[test]
+    fn expired_step_times_out() {
+        let sop = flow_sop(vec![
+            SopStep {
+                timeout_secs: Some(30),
+                on_failure: Some(SopStepTarget::Step(2)),
+                ..flow_step(1)
+            },
+            flow_step(2),
+        ]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        assert!(engine.check_step_timeouts().is_empty());
+
+        let run = engine.active_runs.get_mut(&run_id).unwrap();
+        assert!(run.active_steps[0].deadline.is_some());
+        run.active_steps[0].deadline = Some("2020-01-01T00:00:00Z".into());
+        let actions = engine.check_step_timeouts();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(executed_step(&actions[0]), 2);
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.step_results[0].status, SopStepStatus::Failed);
+        assert!(run.step_results[0].output.contains("Timed out"));
+    }
 }
@@ -645,9 +645,11 @@ mod tests {
             current_step: total_steps,
             total_steps,
             started_at: "2026-02-19T12:00:00Z".into(),
-            completed_at: Some("2026-02-19T12:05:00Z".into()),
+            // Windowed metrics are relative to now; keep runs inside the 7d window.
+            completed_at: Some(crate::sop::engine::now_iso8601()),
             step_results,
             waiting_since: None,
+            active_steps: Vec::new(),
         }
     }
 
@@ -1245,6 +1247,7 @@ mod tests {
             completed_at: None,
             step_results: vec![],
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         audit.log_run_start(&run).await.unwrap();
 
@@ -1342,6 +1345,7 @@ mod tests {
             completed_at: None,
             step_results: vec![],
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         audit.log_run_start(&running_run).await.unwrap();
         audit.log_approval(&running_run, 1).await.unwrap();
@@ -5,6 +5,7 @@ pub mod engine;
 #[cfg(feature = "ampersona-gates")]
 pub mod gates;
 pub mod metrics;
+pub mod store;
 pub mod types;
 
 pub use audit::SopAuditLogger;
@@ -12,17 +13,21 @@ pub use engine::SopEngine;
 #[cfg(feature = "ampersona-gates")]
 pub use gates::GateEvalState;
 pub use metrics::SopMetricsCollector;
+pub use store::SopRunStore;
 #[allow(unused_imports)]
 pub use types::{
-    Sop, SopEvent, SopExecutionMode, SopPriority, SopRun, SopRunAction, SopRunStatus, SopStep,
-    SopStepResult, SopStepStatus, SopTrigger, SopTriggerSource,
+    Sop, SopActiveStep, SopEvent, SopExecutionMode, SopPriority, SopRun, SopRunAction,
+    SopRunStatus, SopStep, SopStepResult, SopStepStatus, SopStepTarget, SopTrigger,
+    SopTriggerSource,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 use tracing::warn;
 
-use types::{SopManifest, SopMeta};
+use types::{SopManifest, SopMeta, SopStepFlow};
 
 // ── SOP directory helpers ───────────────────────────────────────
 
@@ -42,6 +47,71 @@ pub fn resolve_sops_dir(workspace_dir: &Path, config_dir: Option<&str>) -> PathB
     }
 }
 
+// ── Engine construction ─────────────────────────────────────────
+
+/// Build an engine for `config`, backed by the workspace run store and
+/// rehydrated from it.
+pub fn open_engine(config: &crate::config::Config) -> SopEngine {
+    let mut engine = SopEngine::new(config.sop.clone());
+    match SopRunStore::open(&config.workspace_dir) {
+        Ok(store) => engine = engine.with_store(Arc::new(store)),
+        Err(e) => warn!("SOP run store unavailable, runs will not survive restarts: {e}"),
+    }
+    engine.reload(&config.workspace_dir);
+    engine
+}
+
+/// Age after which the daemon's state-file heartbeat no longer counts as a
+/// running daemon (it is rewritten every few seconds).
+const DAEMON_HEARTBEAT_STALE_SECS: i64 = 30;
+
+/// Whether a daemon heartbeat in `state_file` is fresh.
+fn daemon_heartbeat_fresh(state_file: &Path) -> bool {
+    let Ok(raw) = std::fs::read_to_string(state_file) else {
+        return false;
+    };
+    serde_json::from_str::<serde_json::Value>(&raw)
+        .ok()
+        .as_ref()
+        .and_then(|snapshot| snapshot.get("written_at"))
+        .and_then(serde_json::Value::as_str)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .is_some_and(|ts| {
+            chrono::Utc::now().signed_duration_since(ts).num_seconds()
+                <= DAEMON_HEARTBEAT_STALE_SECS
+        })
+}
+
+/// Refuse to change a run from the CLI while the daemon holds the live
+/// engine: its next persist would overwrite a change made to `runs.db` here.
+fn ensure_daemon_stopped(config: &crate::config::Config, run_id: &str, op: &str) -> Result<()> {
+    if daemon_heartbeat_fresh(&crate::daemon::state_file_path(config)) {
+        bail!(
+            "The daemon is running and owns the live SOP engine; \
+             use `POST /api/sop/runs/{run_id}/{op}` on the gateway instead"
+        );
+    }
+    Ok(())
+}
+
+/// Process-wide engine for the workspace, shared by the `sop_*` tools and the
+/// gateway so they observe the same runs. `None` when `[sop]` is disabled.
+pub fn shared_engine(config: &crate::config::Config) -> Option<Arc<Mutex<SopEngine>>> {
+    static ENGINES: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<SopEngine>>>>> = OnceLock::new();
+
+    if !config.sop.enabled {
+        return None;
+    }
+    let mut engines = ENGINES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let engine = engines
+        .entry(config.workspace_dir.clone())
+        .or_insert_with(|| Arc::new(Mutex::new(open_engine(config))));
+    Some(Arc::clone(engine))
+}
+
 // ── SOP loading ─────────────────────────────────────────────────
 
 /// Load all SOPs from the configured directory.
@@ -97,12 +167,13 @@ fn load_sop(sop_dir: &Path, default_execution_mode: SopExecutionMode) -> Result<
     let manifest: SopManifest = toml::from_str(&toml_content)?;
 
     let md_path = sop_dir.join("SOP.md");
-    let steps = if md_path.exists() {
+    let mut steps = if md_path.exists() {
         let md_content = std::fs::read_to_string(&md_path)?;
         parse_steps(&md_content)
     } else {
         Vec::new()
     };
+    apply_step_flow(&mut steps, manifest.steps)?;
 
     let SopMeta {
         name,
@@ -128,6 +199,21 @@ fn load_sop(sop_dir: &Path, default_execution_mode: SopExecutionMode) -> Result<
     })
 }
 
+/// Merge `[[steps]]` flow tables from SOP.toml into the parsed steps.
+fn apply_step_flow(steps: &mut [SopStep], flows: Vec<SopStepFlow>) -> Result<()> {
+    for flow in flows {
+        let Some(step) = steps.iter_mut().find(|s| s.number == flow.step) else {
+            anyhow::bail!("[[steps]] entry references unknown step {}", flow.step);
+        };
+        step.condition = flow.condition.filter(|c| !c.trim().is_empty());
+        step.goto = flow.goto;
+        step.on_failure = flow.on_failure;
+        step.parallel_group = flow.parallel_group.filter(|g| !g.trim().is_empty());
+        step.timeout_secs = flow.timeout_secs;
+    }
+    Ok(())
+}
+
 // ── Markdown step parser ────────────────────────────────────────
 
 /// Parse procedure steps from SOP.md content.
@@ -265,6 +351,7 @@ fn flush_step(
             body: body.trim().to_string(),
             suggested_tools: std::mem::take(tools),
             requires_confirmation: *requires_confirmation,
+            ..Default::default()
         });
         *body = String::new();
         *requires_confirmation = false;
@@ -333,6 +420,71 @@ pub fn validate_sop(sop: &Sop) -> Vec<String> {
         if step.title.is_empty() {
             warnings.push(format!("Step {} has an empty title", step.number));
         }
+        if let Some(SopStepTarget::Step(n)) = step.goto {
+            let loop_has_exit = sop
+                .steps
+                .iter()
+                .filter(|s| (n..=step.number).contains(&s.number))
+                .any(|s| s.condition.is_some() || s.goto == Some(SopStepTarget::End));
+            if n <= step.number && !loop_has_exit {
+                warnings.push(format!(
+                    "Step {} goto loops back to step {n} with no condition to leave the loop",
+                    step.number
+                ));
+            }
+        }
+        for (edge, target) in [("goto", step.goto), ("on_failure", step.on_failure)] {
+            if let Some(SopStepTarget::Step(n)) = target {
+                if !sop.steps.iter().any(|s| s.number == n) {
+                    warnings.push(format!(
+                        "Step {} {edge} targets unknown step {n}",
+                        step.number
+                    ));
+                } else if let Some(group) = sop
+                    .steps
+                    .iter()
+                    .find(|s| s.number == n)
+                    .and_then(|s| s.parallel_group.as_deref())
+                {
+                    let first = sop
+                        .steps
+                        .iter()
+                        .find(|s| s.parallel_group.as_deref() == Some(group))
+                        .map(|s| s.number);
+                    if first != Some(n) {
+                        warnings.push(format!(
+                            "Step {} {edge} jumps into the middle of parallel group '{group}'",
+                            step.number
+                        ));
+                    }
+                }
+            }
+        }
+        if step.timeout_secs == Some(0) {
+            warnings.push(format!(
+                "Step {} has timeout_secs = 0 (omit it to disable the timeout)",
+                step.number
+            ));
+        }
+    }
+
+    // Parallel groups must be contiguous runs of steps
+    let mut seen_groups: Vec<&str> = Vec::new();
+    let mut previous: Option<&str> = None;
+    for step in &sop.steps {
+        let group = step.parallel_group.as_deref();
+        if let Some(group) = group {
+            if previous != Some(group) {
+                if seen_groups.contains(&group) {
+                    warnings.push(format!(
+                        "Parallel group '{group}' is not contiguous (step {})",
+                        step.number
+                    ));
+                }
+                seen_groups.push(group);
+            }
+        }
+        previous = group;
     }
 
     warnings
@@ -498,6 +650,166 @@ pub fn handle_command(command: crate::SopCommands, config: &crate::config::Confi
             println!();
             Ok(())
         }
+
+        crate::SopCommands::Runs { sop, all } => {
+            let engine = open_engine(config);
+            handle_runs(&engine, sop.as_deref(), all);
+            Ok(())
+        }
+
+        crate::SopCommands::Inspect { run_id } => {
+            let engine = open_engine(config);
+            let run = engine
+                .get_run(&run_id)
+                .ok_or_else(|| anyhow::anyhow!("Run not found: {run_id}"))?;
+            print_run(run, engine.get_sop(&run.sop_name));
+            Ok(())
+        }
+
+        crate::SopCommands::Resume { run_id } => {
+            ensure_daemon_stopped(config, &run_id, "resume")?;
+            let engine = open_engine(config);
+            let action = engine.resume_run(&run_id)?;
+            print_action(&action);
+            Ok(())
+        }
+
+        crate::SopCommands::Retry { run_id, step } => {
+            ensure_daemon_stopped(config, &run_id, "retry")?;
+            let mut engine = open_engine(config);
+            let action = engine.retry_step(&run_id, step)?;
+            print_action(&action);
+            Ok(())
+        }
+
+        crate::SopCommands::Cancel { run_id } => {
+            ensure_daemon_stopped(config, &run_id, "cancel")?;
+            let mut engine = open_engine(config);
+            engine.cancel_run(&run_id)?;
+            println!(
+                "{} Cancelled run {run_id}",
+                console::style("✓").green().bold()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn handle_runs(engine: &SopEngine, sop_name: Option<&str>, all: bool) {
+    let mut runs: Vec<&SopRun> = engine
+        .active_runs()
+        .values()
+        .filter(|r| sop_name.is_none_or(|name| r.sop_name == name))
+        .collect();
+    runs.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    if all {
+        runs.extend(engine.finished_runs(sop_name));
+    }
+
+    if runs.is_empty() {
+        if all {
+            println!("No SOP runs recorded.");
+        } else {
+            println!("No active SOP runs. Use --all to include finished runs.");
+        }
+        return;
+    }
+
+    println!("SOP runs ({}):", runs.len());
+    println!();
+    for run in runs {
+        println!(
+            "  {} {} [{}] step {}/{} — started {}",
+            console::style(&run.run_id).white().bold(),
+            run.sop_name,
+            console::style(run.status).cyan(),
+            run.current_step,
+            run.total_steps,
+            run.started_at
+        );
+        if let Some(ref since) = run.waiting_since {
+            println!("    Waiting for approval since {since}");
+        }
+    }
+    println!();
+}
+
+fn print_run(run: &SopRun, sop: Option<&Sop>) {
+    println!(
+        "{} ({})",
+        console::style(&run.run_id).white().bold(),
+        run.sop_name
+    );
+    println!();
+    println!("Status:       {}", run.status);
+    println!("Step:         {} of {}", run.current_step, run.total_steps);
+    println!("Started:      {}", run.started_at);
+    if let Some(ref completed) = run.completed_at {
+        println!("Finished:     {completed}");
+    }
+    if let Some(ref since) = run.waiting_since {
+        println!(
+            "Approval:     step {} pending since {since}",
+            run.current_step
+        );
+    }
+    println!(
+        "Trigger:      {} {}",
+        run.trigger_event.source,
+        run.trigger_event.topic.as_deref().unwrap_or("(no topic)")
+    );
+    if let Some(ref payload) = run.trigger_event.payload {
+        println!("Payload:      {payload}");
+    }
+    if !run.step_results.is_empty() || !run.active_steps.is_empty() {
+        println!("Path:         {}", engine::format_run_path(run, sop));
+    }
+
+    if !run.step_results.is_empty() {
+        println!();
+        println!("Step results:");
+        for result in &run.step_results {
+            println!(
+                "  {}. [{}] {}",
+                result.step_number, result.status, result.output
+            );
+        }
+    }
+    println!();
+}
+
+/// Describe the run's next action. The CLI executes nothing itself: the
+/// agent picks runs up through the `sop_*` tools once the daemon runs.
+fn print_action(action: &SopRunAction) {
+    match action {
+        SopRunAction::ExecuteStep { run_id, step, .. } => println!(
+            "Run {run_id} is at step {}: {}. Nothing was executed; start the daemon and the agent continues it with sop_status / sop_advance.",
+            step.number, step.title
+        ),
+        SopRunAction::ExecuteParallel {
+            run_id,
+            group,
+            steps,
+            ..
+        } => {
+            let numbers: Vec<String> = steps.iter().map(|s| s.number.to_string()).collect();
+            println!(
+                "Run {run_id} is at parallel group '{group}' (steps {}). Nothing was executed; start the daemon and the agent continues it with sop_status / sop_advance.",
+                numbers.join(", ")
+            );
+        }
+        SopRunAction::WaitApproval { run_id, step, .. } => println!(
+            "Run {run_id} is waiting for approval of step {}: {}. Approve it with sop_approve.",
+            step.number, step.title
+        ),
+        SopRunAction::Completed { run_id, sop_name } => {
+            println!("Run {run_id} of '{sop_name}' completed.");
+        }
+        SopRunAction::Failed {
+            run_id,
+            sop_name,
+            reason,
+        } => println!("Run {run_id} of '{sop_name}' failed: {reason}"),
     }
 }
 
@@ -729,6 +1041,7 @@ type = "manual"
                 body: "Do the thing".into(),
                 suggested_tools: vec!["shell".into()],
                 requires_confirmation: false,
+                ..Default::default()
             }],
             cooldown_secs: 0,
             max_concurrent: 1,
@@ -739,6 +1052,107 @@ type = "manual"
         assert!(warnings.is_empty());
     }
 
+    #[test]
+    fn load_sop_applies_step_flow() {
+        let dir = tempfile::tempdir().unwrap();
+        let sop_dir = dir.path().join("flow");
+        fs::create_dir_all(&sop_dir).unwrap();
+        fs::write(
+            sop_dir.join("SOP.toml"),
+            r#"
+[sop]
+name = "flow"
+description = "Branching SOP"
+
+[[triggers]]
+type = "manual"
+
+[[steps]]
+step = 1
+on_failure = "end"
+timeout_secs = 60
+
+[[steps]]
+step = 2
+condition = "$.trigger.value > 85"
+parallel_group = "checks"
+
+[[steps]]
+step = 3
+parallel_group = "checks"
+goto = 1
+"#,
+        )
+        .unwrap();
+        fs::write(
+            sop_dir.join("SOP.md"),
+            "## Steps\n\n1. **Read** — Read it.\n2. **Check A** — A.\n3. **Check B** — B.\n",
+        )
+        .unwrap();
+
+        let sops = load_sops_from_directory(dir.path(), SopExecutionMode::Auto);
+        assert_eq!(sops.len(), 1);
+        let steps = &sops[0].steps;
+        assert_eq!(steps[0].on_failure, Some(SopStepTarget::End));
+        assert_eq!(steps[0].timeout_secs, Some(60));
+        assert_eq!(steps[1].condition.as_deref(), Some("$.trigger.value > 85"));
+        assert_eq!(steps[2].parallel_group.as_deref(), Some("checks"));
+        assert_eq!(steps[2].goto, Some(SopStepTarget::Step(1)));
+        assert!(validate_sop(&sops[0]).is_empty());
+
+        // Flow for a step missing from SOP.md rejects the SOP.
+        fs::write(
+            sop_dir.join("SOP.toml"),
+            "[sop]\nname = \"flow\"\ndescription = \"d\"\n\n[[steps]]\nstep = 9\n",
+        )
+        .unwrap();
+        assert!(load_sops_from_directory(dir.path(), SopExecutionMode::Auto).is_empty());
+    }
+
+    #[test]
+    fn validate_sop_step_flow_warnings() {
+        let step = |number, group: Option<&str>| SopStep {
+            number,
+            title: format!("Step {number}"),
+            parallel_group: group.map(String::from),
+            ..Default::default()
+        };
+        let mut steps = vec![
+            step(1, None),
+            step(2, Some("a")),
+            step(3, Some("a")),
+            step(4, None),
+            step(5, Some("a")),
+        ];
+        steps[0].goto = Some(SopStepTarget::Step(3));
+        steps[4].goto = Some(SopStepTarget::Step(4));
+        steps[3].on_failure = Some(SopStepTarget::Step(7));
+        steps[3].timeout_secs = Some(0);
+        let sop = Sop {
+            name: "flow".into(),
+            description: "d".into(),
+            version: "1.0.0".into(),
+            priority: SopPriority::Normal,
+            execution_mode: SopExecutionMode::Auto,
+            triggers: vec![SopTrigger::Manual],
+            steps,
+            cooldown_secs: 0,
+            max_concurrent: 1,
+            location: None,
+        };
+
+        let warnings = validate_sop(&sop);
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("middle of parallel group")));
+        assert!(warnings.iter().any(|w| w.contains("unknown step 7")));
+        assert!(warnings.iter().any(|w| w.contains("timeout_secs = 0")));
+        assert!(warnings.iter().any(|w| w.contains("not contiguous")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("Step 5 goto loops back to step 4")));
+    }
+
     #[test]
     fn resolve_sops_dir_default() {
         let ws = Path::new("/home/user/.zeroclaw/workspace");
@@ -813,4 +1227,34 @@ type = "manual"
         ));
         assert!(matches!(manifest.triggers[4], SopTrigger::Manual));
     }
+
+    #[test]
+    fn cli_run_changes_refused_while_daemon_heartbeat_is_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::config::Config {
+            config_path: dir.path().join("config.toml"),
+            workspace_dir: dir.path().join("workspace"),
+            ..Default::default()
+        };
+        let state_file = crate::daemon::state_file_path(&config);
+        assert!(ensure_daemon_stopped(&config, "run-1", "resume").is_ok());
+
+        let stale = chrono::Utc::now() - chrono::Duration::seconds(300);
+        fs::write(
+            &state_file,
+            serde_json::json!({"written_at": stale.to_rfc3339()}).to_string(),
+        )
+        .unwrap();
+        assert!(ensure_daemon_stopped(&config, "run-1", "resume").is_ok());
+
+        fs::write(
+            &state_file,
+            serde_json::json!({"written_at": chrono::Utc::now().to_rfc3339()}).to_string(),
+        )
+        .unwrap();
+        let err = ensure_daemon_stopped(&config, "run-1", "cancel")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/api/sop/runs/run-1/cancel"), "{err}");
+    }
 }
@@ -0,0 +1,359 @@
+//! Durable SOP run state.
+//!
+//! The engine keeps runs in memory for fast access and writes every state
+//! transition through to a SQLite database in the workspace
+//! (`sop/runs.db`) so in-flight runs, step results and pending approvals
+//! survive daemon restarts. `SopEngine::reload` rehydrates from this store.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+use super::types::{SopRun, SopRunStatus, SopStepResult, SopStepStatus};
+
+/// A run step that is blocked on operator approval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingApproval {
+    pub run_id: String,
+    pub sop_name: String,
+    pub step_number: u32,
+    pub requested_at: String,
+}
+
+/// SQLite-backed store of SOP runs.
+pub struct SopRunStore {
+    conn: Mutex<Connection>,
+    db_path: PathBuf,
+}
+
+impl SopRunStore {
+    /// Open (or create) the run database under `workspace_dir`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = workspace_dir.join("sop").join("runs.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(&db_path).context("SQLite failed to open SOP run database")?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             PRAGMA foreign_keys = ON;",
+        )?;
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+        })
+    }
+
+    fn init_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sop_runs (
+                run_id       TEXT PRIMARY KEY,
+                sop_name     TEXT NOT NULL,
+                status       TEXT NOT NULL,
+                started_at   TEXT NOT NULL,
+                completed_at TEXT,
+                updated_at   TEXT NOT NULL,
+                data         TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_sop_runs_sop_name ON sop_runs(sop_name);
+            CREATE TABLE IF NOT EXISTS sop_step_results (
+                run_id       TEXT NOT NULL REFERENCES sop_runs(run_id) ON DELETE CASCADE,
+                seq          INTEGER NOT NULL,
+                step_number  INTEGER NOT NULL,
+                status       TEXT NOT NULL,
+                output       TEXT NOT NULL,
+                started_at   TEXT NOT NULL,
+                completed_at TEXT,
+                PRIMARY KEY (run_id, seq)
+            );
+            CREATE TABLE IF NOT EXISTS sop_approvals (
+                run_id       TEXT PRIMARY KEY REFERENCES sop_runs(run_id) ON DELETE CASCADE,
+                step_number  INTEGER NOT NULL,
+                requested_at TEXT NOT NULL
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Path of the backing database file.
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Insert or replace a run together with its step results and pending
+    /// approval (present only while the run is in `WaitingApproval`).
+    pub fn save_run(&self, run: &SopRun) -> Result<()> {
+        let mut record = run.clone();
+        record.step_results = Vec::new();
+        let data = serde_json::to_string(&record)?;
+
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO sop_runs (run_id, sop_name, status, started_at, completed_at, updated_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(run_id) DO UPDATE SET
+                status = excluded.status,
+                completed_at = excluded.completed_at,
+                updated_at = excluded.updated_at,
+                data = excluded.data",
+            params![
+                run.run_id,
+                run.sop_name,
+                run.status.to_string(),
+                run.started_at,
+                run.completed_at,
+                Utc::now().to_rfc3339(),
+                data,
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM sop_step_results WHERE run_id = ?1",
+            params![run.run_id],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO sop_step_results
+                    (run_id, seq, step_number, status, output, started_at, completed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (seq, result) in run.step_results.iter().enumerate() {
+                insert.execute(params![
+                    run.run_id,
+                    seq as i64,
+                    result.step_number,
+                    result.status.to_string(),
+                    result.output,
+                    result.started_at,
+                    result.completed_at,
+                ])?;
+            }
+        }
+
+        match (&run.status, &run.waiting_since) {
+            (SopRunStatus::WaitingApproval, Some(since)) => {
+                tx.execute(
+                    "INSERT INTO sop_approvals (run_id, step_number, requested_at)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(run_id) DO UPDATE SET
+                        step_number = excluded.step_number,
+                        requested_at = excluded.requested_at",
+                    params![run.run_id, run.current_step, since],
+                )?;
+            }
+            _ => {
+                tx.execute(
+                    "DELETE FROM sop_approvals WHERE run_id = ?1",
+                    params![run.run_id],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load one run by ID.
+    pub fn load_run(&self, run_id: &str) -> Result<Option<SopRun>> {
+        let conn = self.conn.lock();
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM sop_runs WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        data.map(|data| Self::hydrate(&conn, &data)).transpose()
+    }
+
+    /// Load every stored run, oldest first.
+    pub fn load_runs(&self) -> Result<Vec<SopRun>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT data FROM sop_runs ORDER BY started_at, run_id")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.iter().map(|data| Self::hydrate(&conn, data)).collect()
+    }
+
+    /// List steps currently blocked on operator approval, oldest first.
+    pub fn pending_approvals(&self) -> Result<Vec<PendingApproval>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT a.run_id, r.sop_name, a.step_number, a.requested_at
+             FROM sop_approvals a JOIN sop_runs r ON r.run_id = a.run_id
+             ORDER BY a.requested_at, a.run_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(PendingApproval {
+                run_id: row.get(0)?,
+                sop_name: row.get(1)?,
+                step_number: row.get(2)?,
+                requested_at: row.get(3)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    /// Delete one run. Returns whether it existed.
+    pub fn delete_run(&self, run_id: &str) -> Result<bool> {
+        let conn = self.conn.lock();
+        let removed = conn.execute("DELETE FROM sop_runs WHERE run_id = ?1", params![run_id])?;
+        Ok(removed > 0)
+    }
+
+    fn hydrate(conn: &Connection, data: &str) -> Result<SopRun> {
+        let mut run: SopRun =
+            serde_json::from_str(data).context("Failed to parse stored SOP run")?;
+
+        let mut stmt = conn.prepare(
+            "SELECT step_number, status, output, started_at, completed_at
+             FROM sop_step_results WHERE run_id = ?1 ORDER BY seq",
+        )?;
+        let rows = stmt.query_map(params![run.run_id], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (step_number, status, output, started_at, completed_at) = row?;
+            run.step_results.push(SopStepResult {
+                step_number,
+                status: parse_step_status(&status)?,
+                output,
+                started_at,
+                completed_at,
+            });
+        }
+
+        run.waiting_since = conn
+            .query_row(
+                "SELECT requested_at FROM sop_approvals WHERE run_id = ?1",
+                params![run.run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(run)
+    }
+}
+
+fn parse_step_status(status: &str) -> Result<SopStepStatus> {
+    serde_json::from_value(serde_json::Value::String(status.to_string()))
+        .with_context(|| format!("Unknown SOP step status: {status}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sop::types::{SopEvent, SopTriggerSource};
+    use tempfile::TempDir;
+
+    fn run(run_id: &str, status: SopRunStatus) -> SopRun {
+        SopRun {
+            run_id: run_id.into(),
+            sop_name: "pump-check".into(),
+            trigger_event: SopEvent {
+                source: SopTriggerSource::Manual,
+                topic: None,
+                payload: None,
+                timestamp: "2026-02-19T12:00:00Z".into(),
+            },
+            status,
+            current_step: 2,
+            total_steps: 3,
+            started_at: format!("2026-02-19T12:00:0{}Z", run_id.len() % 10),
+            completed_at: None,
+            step_results: vec![SopStepResult {
+                step_number: 1,
+                status: SopStepStatus::Completed,
+                output: "pressure nominal".into(),
+                started_at: "2026-02-19T12:00:00Z".into(),
+                completed_at: Some("2026-02-19T12:00:05Z".into()),
+            }],
+            waiting_since: None,
+            active_steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn save_and_load_roundtrip_with_pending_approval() {
+        let tmp = TempDir::new().unwrap();
+        let store = SopRunStore::open(tmp.path()).unwrap();
+
+        let mut waiting = run("run-1", SopRunStatus::WaitingApproval);
+        waiting.waiting_since = Some("2026-02-19T12:01:00Z".into());
+        store.save_run(&waiting).unwrap();
+        store
+            .save_run(&run("run-22", SopRunStatus::Running))
+            .unwrap();
+
+        let approvals = store.pending_approvals().unwrap();
+        assert_eq!(
+            approvals,
+            vec![PendingApproval {
+                run_id: "run-1".into(),
+                sop_name: "pump-check".into(),
+                step_number: 2,
+                requested_at: "2026-02-19T12:01:00Z".into(),
+            }]
+        );
+
+        // Reopen to make sure everything came from disk.
+        drop(store);
+        let store = SopRunStore::open(tmp.path()).unwrap();
+        let runs = store.load_runs().unwrap();
+        assert_eq!(runs.len(), 2);
+        let loaded = store.load_run("run-1").unwrap().unwrap();
+        assert_eq!(loaded.status, SopRunStatus::WaitingApproval);
+        assert_eq!(
+            loaded.waiting_since.as_deref(),
+            Some("2026-02-19T12:01:00Z")
+        );
+        assert_eq!(loaded.step_results.len(), 1);
+        assert_eq!(loaded.step_results[0].output, "pressure nominal");
+    }
+
+    #[test]
+    fn save_replaces_state_and_clears_resolved_approval() {
+        let tmp = TempDir::new().unwrap();
+        let store = SopRunStore::open(tmp.path()).unwrap();
+
+        let mut r = run("run-1", SopRunStatus::WaitingApproval);
+        r.waiting_since = Some("2026-02-19T12:01:00Z".into());
+        store.save_run(&r).unwrap();
+
+        r.status = SopRunStatus::Failed;
+        r.waiting_since = None;
+        r.completed_at = Some("2026-02-19T12:02:00Z".into());
+        r.step_results.push(SopStepResult {
+            step_number: 2,
+            status: SopStepStatus::Failed,
+            output: "valve stuck".into(),
+            started_at: "2026-02-19T12:01:30Z".into(),
+            completed_at: Some("2026-02-19T12:02:00Z".into()),
+        });
+        store.save_run(&r).unwrap();
+
+        assert!(store.pending_approvals().unwrap().is_empty());
+        let loaded = store.load_run("run-1").unwrap().unwrap();
+        assert_eq!(loaded.status, SopRunStatus::Failed);
+        assert_eq!(loaded.step_results.len(), 2);
+        assert_eq!(loaded.step_results[1].status, SopStepStatus::Failed);
+
+        assert!(store.delete_run("run-1").unwrap());
+        assert!(store.load_run("run-1").unwrap().is_none());
+        assert!(!store.delete_run("run-1").unwrap());
+    }
+}
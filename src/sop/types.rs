@@ -96,7 +96,10 @@ impl fmt::Display for SopTrigger {
 // ── Step ────────────────────────────────────────────────────────
 
 /// A single step in an SOP procedure, parsed from SOP.md.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Flow fields (`condition`, `goto`, `on_failure`, `parallel_group`,
+/// `timeout_secs`) come from `[[steps]]` entries in SOP.toml.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SopStep {
     pub number: u32,
     pub title: String,
@@ -105,6 +108,69 @@ pub struct SopStep {
     pub suggested_tools: Vec<String>,
     #[serde(default)]
     pub requires_confirmation: bool,
+    /// Run the step only when this condition holds; otherwise it is skipped.
+    #[serde(default)]
+    pub condition: Option<String>,
+    /// Where to continue after the step completes (default: the next step).
+    #[serde(default)]
+    pub goto: Option<SopStepTarget>,
+    /// Where to continue when the step fails (default: fail the run).
+    #[serde(default)]
+    pub on_failure: Option<SopStepTarget>,
+    /// Contiguous steps sharing a group name run in parallel and join before
+    /// the run moves on.
+    #[serde(default)]
+    pub parallel_group: Option<String>,
+    /// The step counts as failed when no result is reported within this time.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Target of a `goto` / `on_failure` edge: a step number or `"end"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawStepTarget", into = "RawStepTarget")]
+pub enum SopStepTarget {
+    Step(u32),
+    End,
+}
+
+impl fmt::Display for SopStepTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Step(n) => write!(f, "step {n}"),
+            Self::End => write!(f, "end"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawStepTarget {
+    Step(u32),
+    Name(String),
+}
+
+impl TryFrom<RawStepTarget> for SopStepTarget {
+    type Error = String;
+
+    fn try_from(raw: RawStepTarget) -> Result<Self, Self::Error> {
+        match raw {
+            RawStepTarget::Step(n) => Ok(Self::Step(n)),
+            RawStepTarget::Name(name) if name.eq_ignore_ascii_case("end") => Ok(Self::End),
+            RawStepTarget::Name(name) => Err(format!(
+                "invalid step target '{name}' (expected a step number or \"end\")"
+            )),
+        }
+    }
+}
+
+impl From<SopStepTarget> for RawStepTarget {
+    fn from(target: SopStepTarget) -> Self {
+        match target {
+            SopStepTarget::Step(n) => Self::Step(n),
+            SopStepTarget::End => Self::Name("end".into()),
+        }
+    }
 }
 
 // ── SOP ─────────────────────────────────────────────────────────
@@ -143,6 +209,26 @@ pub(crate) struct SopManifest {
     pub sop: SopMeta,
     #[serde(default)]
     pub triggers: Vec<SopTrigger>,
+    #[serde(default)]
+    pub steps: Vec<SopStepFlow>,
+}
+
+/// A `[[steps]]` table in SOP.toml: flow control for the SOP.md step with
+/// the same number.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SopStepFlow {
+    pub step: u32,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub goto: Option<SopStepTarget>,
+    #[serde(default)]
+    pub on_failure: Option<SopStepTarget>,
+    #[serde(default)]
+    pub parallel_group: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 /// The `[sop]` table in SOP.toml.
@@ -276,10 +362,23 @@ pub struct SopRun {
     /// ISO-8601 timestamp when the run entered WaitingApproval (for timeout tracking).
     #[serde(default)]
     pub waiting_since: Option<String>,
+    /// Steps handed out and awaiting a result (several for a parallel group).
+    #[serde(default)]
+    pub active_steps: Vec<SopActiveStep>,
+}
+
+/// A step of a run that is awaiting its result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SopActiveStep {
+    pub step_number: u32,
+    /// ISO-8601 time after which the step counts as timed out.
+    #[serde(default)]
+    pub deadline: Option<String>,
 }
 
 /// What the engine instructs the caller to do next after a state transition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
 pub enum SopRunAction {
     /// Inject this step into the agent for execution.
     ExecuteStep {
@@ -287,6 +386,14 @@ pub enum SopRunAction {
         step: SopStep,
         context: String,
     },
+    /// Inject these steps of a parallel group; the run joins once every
+    /// step has reported a result.
+    ExecuteParallel {
+        run_id: String,
+        group: String,
+        steps: Vec<SopStep>,
+        context: String,
+    },
     /// Pause and wait for operator approval before executing this step.
     WaitApproval {
         run_id: String,
@@ -408,6 +515,34 @@ path = "/sop/test"
         assert_eq!(manifest.sop.execution_mode, None);
     }
 
+    #[test]
+    fn manifest_parses_step_flow() {
+        let toml_str = r#"
+[sop]
+name = "flow"
+description = "Flow control"
+
+[[steps]]
+step = 2
+condition = "$.trigger.value > 85"
+goto = "end"
+on_failure = 4
+parallel_group = "checks"
+timeout_secs = 60
+"#;
+        let manifest: SopManifest = toml::from_str(toml_str).unwrap();
+        let flow = &manifest.steps[0];
+        assert_eq!(flow.step, 2);
+        assert_eq!(flow.goto, Some(SopStepTarget::End));
+        assert_eq!(flow.on_failure, Some(SopStepTarget::Step(4)));
+        assert_eq!(flow.parallel_group.as_deref(), Some("checks"));
+        assert_eq!(flow.timeout_secs, Some(60));
+
+        let bad =
+            "[sop]\nname = \"x\"\ndescription = \"x\"\n[[steps]]\nstep = 1\ngoto = \"nowhere\"\n";
+        assert!(toml::from_str::<SopManifest>(bad).is_err());
+    }
+
     #[test]
     fn trigger_source_display() {
         assert_eq!(SopTriggerSource::Mqtt.to_string(), "mqtt");
@@ -459,6 +594,7 @@ path = "/sop/test"
                 completed_at: Some("2026-02-19T12:00:05Z".into()),
             }],
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         let json = serde_json::to_string(&run).unwrap();
         let parsed: SopRun = serde_json::from_str(&json).unwrap();
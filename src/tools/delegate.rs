@@ -3,7 +3,7 @@ use crate::agent::loop_::run_tool_call_loop;
 use crate::config::DelegateAgentConfig;
 use crate::coordination::{CoordinationEnvelope, CoordinationPayload, InMemoryMessageBus};
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
-use crate::providers::{self, ChatMessage, Provider};
+use crate::providers::{self, ChatMessage, ChatRequest, Provider, ResponseFormat};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -206,6 +206,11 @@ impl Tool for DelegateTool {
                 "context": {
                     "type": "string",
                     "description": "Optional context to prepend (e.g. relevant code, prior findings)"
+                },
+                "response_schema": {
+                    "type": "object",
+                    "description": "Optional JSON Schema the sub-agent's answer must conform to \
+                                    (single-prompt agents only)"
                 }
             },
             "required": ["agent", "prompt"]
@@ -247,6 +252,21 @@ impl Tool for DelegateTool {
             .map(str::trim)
             .unwrap_or("");
 
+        let response_format = match args.get("response_schema") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(schema @ serde_json::Value::Object(_)) => Some(ResponseFormat::json_schema(
+                "delegate_result",
+                schema.clone(),
+            )),
+            Some(_) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some("'response_schema' must be a JSON Schema object".into()),
+                });
+            }
+        };
+
         // Look up agent config
         let agent_config = match self.agents.get(agent_name) {
             Some(cfg) => cfg,
@@ -340,6 +360,23 @@ impl Tool for DelegateTool {
 
         // Agentic mode: run full tool-call loop with allowlisted tools.
         if agent_config.agentic {
+            if response_format.is_some() {
+                let error_message = format!(
+                    "Agent '{agent_name}' is agentic; response_schema is only supported for single-prompt agents"
+                );
+                self.finish_coordination_trace(
+                    agent_name,
+                    &coordination_trace,
+                    false,
+                    &error_message,
+                );
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error_message),
+                });
+            }
+
             let result = self
                 .execute_agentic(
                     agent_name,
@@ -371,11 +408,12 @@ impl Tool for DelegateTool {
         // Wrap the provider call in a timeout to prevent indefinite blocking
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_TIMEOUT_SECS),
-            provider.chat_with_system(
-                agent_config.system_prompt.as_deref(),
+            Self::chat_once(
+                agent_config,
+                &*provider,
                 &full_prompt,
-                &agent_config.model,
                 temperature,
+                response_format.as_ref(),
             ),
         )
         .await;
@@ -437,6 +475,47 @@ impl Tool for DelegateTool {
 }
 
 impl DelegateTool {
+    /// Single-prompt call. With a `response_format` the answer goes through
+    /// [`providers::structured::chat_with_response_format`], which uses native
+    /// structured output when the provider supports it and validates the JSON.
+    async fn chat_once(
+        agent_config: &DelegateAgentConfig,
+        provider: &dyn Provider,
+        full_prompt: &str,
+        temperature: f64,
+        response_format: Option<&ResponseFormat>,
+    ) -> anyhow::Result<String> {
+        let Some(format) = response_format else {
+            return provider
+                .chat_with_system(
+                    agent_config.system_prompt.as_deref(),
+                    full_prompt,
+                    &agent_config.model,
+                    temperature,
+                )
+                .await;
+        };
+
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system_prompt) = agent_config.system_prompt.as_deref() {
+            messages.push(ChatMessage::system(system_prompt));
+        }
+        messages.push(ChatMessage::user(full_prompt));
+
+        let response = providers::structured::chat_with_response_format(
+            provider,
+            ChatRequest {
+                messages: &messages,
+                response_format: Some(format),
+                ..Default::default()
+            },
+            &agent_config.model,
+            temperature,
+        )
+        .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
     async fn execute_agentic(
         &self,
         agent_name: &str,
@@ -953,6 +1032,47 @@ mod tests {
         }
     }
 
+    /// Native structured output; records the format it was asked for.
+    #[derive(Default)]
+    struct StructuredProvider {
+        formats: std::sync::Mutex<Vec<Option<ResponseFormat>>>,
+    }
+
+    #[async_trait]
+    impl Provider for StructuredProvider {
+        fn supports_structured_output(&self) -> bool {
+            true
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("plain".to_string())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.formats
+                .lock()
+                .unwrap()
+                .push(request.response_format.cloned());
+            Ok(ChatResponse {
+                text: Some("{\"verdict\":\"ok\"}".to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
     fn agentic_config(allowed_tools: Vec<String>, max_iterations: usize) -> DelegateAgentConfig {
         DelegateAgentConfig {
             provider: "openrouter".to_string(),
@@ -983,6 +1103,55 @@ mod tests {
         assert_eq!(schema["properties"]["prompt"]["minLength"], json!(1));
     }
 
+    #[test]
+    fn schema_exposes_optional_response_schema() {
+        let tool = DelegateTool::new(sample_agents(), None, test_security());
+        let schema = tool.parameters_schema();
+        assert_eq!(
+            schema["properties"]["response_schema"]["type"],
+            json!("object")
+        );
+        let required = schema["required"].as_array().unwrap();
+        assert!(!required.contains(&json!("response_schema")));
+    }
+
+    #[tokio::test]
+    async fn chat_once_requests_structured_output_when_schema_given() {
+        let provider = StructuredProvider::default();
+        let config = sample_agents().remove("coder").unwrap();
+        let format = ResponseFormat::json_schema(
+            "delegate_result",
+            json!({
+                "type": "object",
+                "properties": {"verdict": {"type": "string"}},
+                "required": ["verdict"]
+            }),
+        );
+
+        let output = DelegateTool::chat_once(&config, &provider, "review", 0.2, Some(&format))
+            .await
+            .unwrap();
+        assert_eq!(output, "{\"verdict\":\"ok\"}");
+        assert_eq!(*provider.formats.lock().unwrap(), vec![Some(format)]);
+
+        let plain = DelegateTool::chat_once(&config, &provider, "review", 0.2, None)
+            .await
+            .unwrap();
+        assert_eq!(plain, "plain");
+        assert_eq!(provider.formats.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn non_object_response_schema_rejected() {
+        let tool = DelegateTool::new(sample_agents(), None, test_security());
+        let result = tool
+            .execute(json!({"agent": "coder", "prompt": "test", "response_schema": "json"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("response_schema"));
+    }
+
     #[test]
     fn description_not_empty() {
         let tool = DelegateTool::new(sample_agents(), None, test_security());
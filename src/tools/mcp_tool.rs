@@ -0,0 +1,215 @@
+use super::traits::{Tool, ToolResult};
+use crate::mcp::{McpClient, McpToolDef};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A tool exposed by an external MCP server.
+///
+/// Calls are gated by [`SecurityPolicy`] before they leave the process.
+/// Every tool counts as a side-effecting action subject to autonomy and rate
+/// limits, unless the server is trusted (`trust_annotations`) and annotates
+/// the tool with `readOnlyHint`, which makes it a read.
+pub struct McpTool {
+    client: Arc<McpClient>,
+    name: String,
+    remote_name: String,
+    description: String,
+    schema: serde_json::Value,
+    operation: ToolOperation,
+    security: Arc<SecurityPolicy>,
+}
+
+impl McpTool {
+    pub fn new(
+        client: Arc<McpClient>,
+        def: McpToolDef,
+        trust_annotations: bool,
+        security: Arc<SecurityPolicy>,
+    ) -> Self {
+        let server = client.server_name().to_string();
+        let description = match def.description.as_deref().map(str::trim) {
+            Some(desc) if !desc.is_empty() => format!("[MCP {server}] {desc}"),
+            _ => format!("[MCP {server}] Remote tool '{}'", def.name),
+        };
+        let schema = if def.input_schema.is_object() {
+            def.input_schema.clone()
+        } else {
+            crate::mcp::protocol::empty_object_schema()
+        };
+        let operation = if trust_annotations && def.is_read_only() {
+            ToolOperation::Read
+        } else {
+            ToolOperation::Act
+        };
+
+        Self {
+            name: crate::mcp::qualified_tool_name(&server, &def.name),
+            remote_name: def.name,
+            description,
+            schema,
+            operation,
+            client,
+            security,
+        }
+    }
+
+    /// Name of the tool on the remote server.
+    pub fn remote_name(&self) -> &str {
+        &self.remote_name
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.schema.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(self.operation, &self.name)
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+
+        match self.client.call_tool(&self.remote_name, args).await {
+            Ok(result) if result.is_error => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(result.to_text()),
+            }),
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: result.to_text(),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("MCP call failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{McpConfig, McpServerConfig};
+    use crate::mcp::client::tests::fixture_server_config;
+    use crate::security::AutonomyLevel;
+    use serde_json::json;
+
+    fn fixture_mcp_config(server: McpServerConfig) -> McpConfig {
+        McpConfig {
+            enabled: true,
+            servers: vec![server],
+            ..McpConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn registers_fixture_tools_with_qualified_names() {
+        let server = fixture_server_config("demo");
+        let security = Arc::new(SecurityPolicy::default());
+        let tools = crate::tools::mcp_tools(&fixture_mcp_config(server), &security).await;
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["demo__echo", "demo__add", "demo__fail"]);
+
+        let spec = tools[0].spec();
+        assert!(spec.description.starts_with("[MCP demo]"));
+        assert_eq!(spec.parameters["required"], json!(["text"]));
+
+        let result = tools[0].execute(json!({"text": "ping"})).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "ping");
+
+        let failed = tools[2].execute(json!({})).await.unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.error.as_deref(), Some("intentional failure"));
+    }
+
+    #[tokio::test]
+    async fn include_and_exclude_filters_apply() {
+        let mut server = fixture_server_config("demo");
+        server.include_tools = vec!["echo".into(), "add".into()];
+        server.exclude_tools = vec!["add".into()];
+        let security = Arc::new(SecurityPolicy::default());
+        let tools = crate::tools::mcp_tools(&fixture_mcp_config(server), &security).await;
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["demo__echo"]);
+    }
+
+    #[tokio::test]
+    async fn read_only_hint_ignored_unless_server_is_trusted() {
+        let server = fixture_server_config("demo");
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tools = crate::tools::mcp_tools(&fixture_mcp_config(server), &security).await;
+
+        // `add` claims readOnlyHint, but the server is not trusted.
+        let add = tools.iter().find(|t| t.name() == "demo__add").unwrap();
+        let blocked = add.execute(json!({"a": 1, "b": 2})).await.unwrap();
+        assert!(!blocked.success);
+        assert!(blocked.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_blocks_side_effecting_tools_only() {
+        let mut server = fixture_server_config("demo");
+        server.trust_annotations = true;
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tools = crate::tools::mcp_tools(&fixture_mcp_config(server), &security).await;
+
+        let echo = tools.iter().find(|t| t.name() == "demo__echo").unwrap();
+        let blocked = echo.execute(json!({"text": "x"})).await.unwrap();
+        assert!(!blocked.success);
+        assert!(blocked.error.unwrap().contains("read-only"));
+
+        // `add` is annotated readOnlyHint and stays available.
+        let add = tools.iter().find(|t| t.name() == "demo__add").unwrap();
+        let sum = add.execute(json!({"a": 1, "b": 2})).await.unwrap();
+        assert!(sum.success);
+        assert_eq!(sum.output, "3");
+    }
+
+    #[tokio::test]
+    async fn unreachable_servers_are_skipped() {
+        let config = fixture_mcp_config(McpServerConfig {
+            name: "ghost".into(),
+            command: Some("/nonexistent/zeroclaw-mcp-server".into()),
+            ..McpServerConfig::default()
+        });
+        let security = Arc::new(SecurityPolicy::default());
+        assert!(crate::tools::mcp_tools(&config, &security).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn disabled_config_registers_nothing() {
+        let server = fixture_server_config("demo");
+        let mut config = fixture_mcp_config(server);
+        config.enabled = false;
+        let security = Arc::new(SecurityPolicy::default());
+        assert!(crate::tools::mcp_tools(&config, &security).await.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::{namespace, Memory};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -16,6 +16,19 @@ impl MemoryForgetTool {
     pub fn new(memory: Arc<dyn Memory>, security: Arc<SecurityPolicy>) -> Self {
         Self { memory, security }
     }
+
+    /// Forget `key` from the namespaces the current sender may write; the
+    /// global namespace is only reachable outside channel scopes.
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let Some(scope) = namespace::current() else {
+            return self.memory.forget(key).await;
+        };
+        let mut forgotten = false;
+        for target in scope.writable_namespaces() {
+            forgotten |= self.memory.forget_in(target, key).await?;
+        }
+        Ok(forgotten)
+    }
 }
 
 #[async_trait]
@@ -58,7 +71,7 @@ impl Tool for MemoryForgetTool {
             });
         }
 
-        match self.memory.forget(key).await {
+        match self.forget(key).await {
             Ok(true) => Ok(ToolResult {
                 success: true,
                 output: format!("Forgot memory: {key}"),
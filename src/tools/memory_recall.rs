@@ -1,5 +1,6 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::ingest::split_citation;
+use crate::memory::{namespace, Memory, MemoryCategory};
 use async_trait::async_trait;
 use serde_json::json;
 use std::fmt::Write;
@@ -23,7 +24,7 @@ impl Tool for MemoryRecallTool {
     }
 
     fn description(&self) -> &str {
-        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance."
+        "Search long-term memory for relevant facts, preferences, context, or ingested documents. Returns scored results ranked by relevance; document results cite their source file and lines."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -37,6 +38,11 @@ impl Tool for MemoryRecallTool {
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return memories carrying all of these tags"
                 }
             },
             "required": ["query"]
@@ -55,7 +61,21 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit, None).await {
+        let tags: Vec<String> = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str())
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let scope = namespace::current();
+        match namespace::recall(self.memory.as_ref(), scope.as_ref(), query, limit, &tags).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
@@ -64,9 +84,24 @@ impl Tool for MemoryRecallTool {
             Ok(entries) => {
                 let mut output = format!("Found {} memories:\n", entries.len());
                 for entry in &entries {
-                    let score = entry
+                    let mut score = entry
                         .score
                         .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    if !entry.metadata.tags.is_empty() {
+                        let _ = write!(score, " #{}", entry.metadata.tags.join(" #"));
+                    }
+                    // Ingested documents cite their file instead of the chunk key.
+                    let citation = (entry.category == MemoryCategory::Knowledge)
+                        .then(|| split_citation(&entry.content))
+                        .flatten();
+                    if let Some((citation, body)) = citation {
+                        let _ = writeln!(
+                            output,
+                            "- [{}] {body}{score}\n  (source: {citation})",
+                            entry.category
+                        );
+                        continue;
+                    }
                     let _ = writeln!(
                         output,
                         "- [{}] {}: {}{score}",
@@ -91,7 +126,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::SqliteMemory;
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -149,6 +184,26 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_cites_ingested_documents() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store(
+            "kb:/docs/deploy.md#0",
+            "Source: /docs/deploy.md#L1-L4\n\n# Deploy\nUse the canary pipeline",
+            MemoryCategory::Knowledge,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool.execute(json!({"query": "canary"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("[knowledge] # Deploy"));
+        assert!(result.output.contains("(source: /docs/deploy.md#L1-L4)"));
+        assert!(!result.output.contains("kb:/docs"));
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
@@ -164,4 +219,40 @@ mod tests {
         assert_eq!(tool.name(), "memory_recall");
         assert!(tool.parameters_schema()["properties"]["query"].is_object());
     }
+
+    #[tokio::test]
+    async fn recall_filters_by_tags() {
+        let (_tmp, mem) = seeded_mem();
+        let infra = crate::memory::MemoryMetadata {
+            tags: vec!["infra".into()],
+            ..Default::default()
+        };
+        mem.store_with_metadata(
+            "db",
+            "Database runs Postgres",
+            MemoryCategory::Core,
+            None,
+            &infra,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "db_app",
+            "App database is SQLite",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "database", "tags": ["Infra"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("Postgres"));
+        assert!(result.output.contains("#infra"));
+    }
 }
@@ -1,8 +1,9 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::{Memory, MemoryCategory};
+use crate::memory::{namespace, Memory, MemoryCategory, MemoryMetadata};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
+use chrono::{Duration, Utc};
 use serde_json::json;
 use std::sync::Arc;
 
@@ -43,6 +44,23 @@ impl Tool for MemoryStoreTool {
                 "category": {
                     "type": "string",
                     "description": "Memory category: 'core' (permanent), 'daily' (session), 'conversation' (chat), or a custom category name. Defaults to 'core'."
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional labels (project, topic) that memory_recall can filter on"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Optional origin of the fact (channel, file, URL)"
+                },
+                "importance": {
+                    "type": "number",
+                    "description": "Optional 0.0-1.0; higher values rank earlier in recall (default 0.5)"
+                },
+                "ttl_days": {
+                    "type": "number",
+                    "description": "Optional lifetime in days; the memory is no longer recalled afterwards"
                 }
             },
             "required": ["key", "content"]
@@ -64,9 +82,21 @@ impl Tool for MemoryStoreTool {
             Some("core") | None => MemoryCategory::Core,
             Some("daily") => MemoryCategory::Daily,
             Some("conversation") => MemoryCategory::Conversation,
+            Some("knowledge") => MemoryCategory::Knowledge,
             Some(other) => MemoryCategory::Custom(other.to_string()),
         };
 
+        let metadata = match parse_metadata(&args) {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+
         if let Err(error) = self
             .security
             .enforce_tool_operation(ToolOperation::Act, "memory_store")
@@ -78,7 +108,23 @@ impl Tool for MemoryStoreTool {
             });
         }
 
-        match self.memory.store(key, content, category, None).await {
+        // Inside a channel message the memory lands in the sender's or the
+        // chat's namespace, depending on the category's sharing rule.
+        let stored = match namespace::current() {
+            Some(scope) => {
+                let target = scope.write_namespace(&category);
+                self.memory
+                    .store_in(target, key, content, category.clone(), None, &metadata)
+                    .await
+            }
+            None => {
+                self.memory
+                    .store_with_metadata(key, content, category, None, &metadata)
+                    .await
+            }
+        };
+
+        match stored {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
@@ -93,6 +139,59 @@ impl Tool for MemoryStoreTool {
     }
 }
 
+/// Read the optional `tags`, `source`, `importance` and `ttl_days` arguments.
+fn parse_metadata(args: &serde_json::Value) -> Result<MemoryMetadata, String> {
+    let tags = args
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|tag| tag.as_str())
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let source = args
+        .get("source")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let importance = match args.get("importance").and_then(serde_json::Value::as_f64) {
+        Some(value) if !(0.0..=1.0).contains(&value) => {
+            return Err(format!(
+                "'importance' must be between 0.0 and 1.0, got {value}"
+            ));
+        }
+        other => other,
+    };
+
+    let expires_at = match args.get("ttl_days").and_then(serde_json::Value::as_f64) {
+        Some(days) if !days.is_finite() || days <= 0.0 => {
+            return Err(format!("'ttl_days' must be a positive number, got {days}"));
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Some(days) => {
+            let ttl = Duration::try_seconds((days * 86_400.0) as i64)
+                .ok_or_else(|| format!("'ttl_days' is too large: {days}"))?;
+            Some((Utc::now() + ttl).to_rfc3339())
+        }
+        None => None,
+    };
+
+    Ok(MemoryMetadata {
+        tags,
+        source,
+        importance,
+        expires_at,
+        access_count: 0,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +217,7 @@ mod tests {
         let schema = tool.parameters_schema();
         assert!(schema["properties"]["key"].is_object());
         assert!(schema["properties"]["content"].is_object());
+        assert!(schema["properties"]["tags"].is_object());
     }
 
     #[tokio::test]
@@ -136,6 +236,44 @@ mod tests {
         assert_eq!(entry.unwrap().content, "Prefers Rust");
     }
 
+    #[tokio::test]
+    async fn store_inside_channel_scope_uses_sharing_rules() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let scope = crate::memory::MemoryScope::for_sender(
+            mem.as_ref(),
+            &crate::config::MemoryNamespaceConfig::default(),
+            "telegram",
+            "group-1",
+            "alice",
+        );
+        namespace::with_scope(scope, async {
+            let private = tool
+                .execute(json!({"key": "diet", "content": "Vegetarian", "category": "daily"}))
+                .await
+                .unwrap();
+            assert!(private.success);
+            let shared = tool
+                .execute(json!({"key": "topic", "content": "Trip planning"}))
+                .await
+                .unwrap();
+            assert!(shared.success);
+        })
+        .await;
+
+        assert!(mem.get("diet").await.unwrap().is_none());
+        assert!(mem
+            .get_in("telegram:user:alice", "diet")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(mem
+            .get_in("telegram:chat:group-1", "topic")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
     #[tokio::test]
     async fn store_with_category() {
         let (_tmp, mem) = test_mem();
@@ -164,6 +302,43 @@ mod tests {
         assert_eq!(entry.category, MemoryCategory::Custom("project".into()));
     }
 
+    #[tokio::test]
+    async fn store_with_metadata_fields() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let result = tool
+            .execute(json!({
+                "key": "deploy",
+                "content": "Deploys run on Fridays",
+                "tags": ["ops", " ", "release"],
+                "source": "slack",
+                "importance": 0.9,
+                "ttl_days": 7
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let entry = mem.get("deploy").await.unwrap().unwrap();
+        assert_eq!(entry.metadata.tags, vec!["ops", "release"]);
+        assert_eq!(entry.metadata.source.as_deref(), Some("slack"));
+        assert_eq!(entry.metadata.importance, Some(0.9));
+        assert!(entry.metadata.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn store_rejects_out_of_range_importance() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone(), test_security());
+        let result = tool
+            .execute(json!({"key": "k", "content": "v", "importance": 2.0}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("importance"));
+        assert!(mem.get("k").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn store_missing_key() {
         let (_tmp, mem) = test_mem();
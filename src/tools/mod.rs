@@ -7,7 +7,8 @@
 //!
 //! Tools are assembled into registries by [`default_tools`] (shell, file read/write)
 //! and [`all_tools`] (full set including memory, browser, cron, HTTP, delegation,
-//! and optional integrations). Security policy enforcement is injected via
+//! and optional integrations). Tools discovered on external MCP servers are
+//! added by [`mcp_tools`]. Security policy enforcement is injected via
 //! [`SecurityPolicy`](crate::security::SecurityPolicy) at construction time.
 //!
 //! # Extension
@@ -43,6 +44,7 @@ pub mod hardware_memory_map;
 pub mod hardware_memory_read;
 pub mod http_request;
 pub mod image_info;
+pub mod mcp_tool;
 pub mod memory_forget;
 pub mod memory_recall;
 pub mod memory_store;
@@ -55,6 +57,11 @@ pub mod schedule;
 pub mod schema;
 pub mod screenshot;
 pub mod shell;
+pub mod sop_advance;
+pub mod sop_approve;
+pub mod sop_execute;
+pub mod sop_list;
+pub mod sop_status;
 pub mod subagent_list;
 pub mod subagent_manage;
 pub mod subagent_registry;
@@ -92,6 +99,7 @@ pub use hardware_memory_map::HardwareMemoryMapTool;
 pub use hardware_memory_read::HardwareMemoryReadTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
+pub use mcp_tool::McpTool;
 pub use memory_forget::MemoryForgetTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
@@ -105,6 +113,11 @@ pub use schedule::ScheduleTool;
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
 pub use shell::ShellTool;
+pub use sop_advance::SopAdvanceTool;
+pub use sop_approve::SopApproveTool;
+pub use sop_execute::SopExecuteTool;
+pub use sop_list::SopListTool;
+pub use sop_status::SopStatusTool;
 pub use subagent_list::SubAgentListTool;
 pub use subagent_manage::SubAgentManageTool;
 pub use subagent_registry::SubAgentRegistry;
@@ -263,7 +276,7 @@ pub fn all_tools_with_runtime(
         Arc::new(CronRunsTool::new(config.clone())),
         Arc::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Arc::new(MemoryRecallTool::new(memory.clone())),
-        Arc::new(MemoryForgetTool::new(memory, security.clone())),
+        Arc::new(MemoryForgetTool::new(memory.clone(), security.clone())),
         Arc::new(ScheduleTool::new(security.clone(), root_config.clone())),
         Arc::new(TaskPlanTool::new(security.clone())),
         Arc::new(ModelRoutingConfigTool::new(
@@ -425,6 +438,7 @@ pub fn all_tools_with_runtime(
                 .map(|mode| mode.as_compatible_mode()),
             max_tokens_override: None,
             model_support_vision: root_config.model_support_vision,
+            azure_openai: root_config.provider.azure_openai.clone(),
         };
         let parent_tools = Arc::new(tool_arcs.clone());
         let mut delegate_tool = DelegateTool::new_with_options(
@@ -497,6 +511,20 @@ pub fn all_tools_with_runtime(
         )));
     }
 
+    // Standard Operating Procedures (opt-in)
+    if let Some(engine) = crate::sop::shared_engine(root_config) {
+        let audit = Arc::new(crate::sop::SopAuditLogger::new(memory));
+        tool_arcs.push(Arc::new(SopListTool::new(engine.clone())));
+        tool_arcs.push(Arc::new(
+            SopExecuteTool::new(engine.clone()).with_audit(audit.clone()),
+        ));
+        tool_arcs.push(Arc::new(SopStatusTool::new(engine.clone())));
+        tool_arcs.push(Arc::new(
+            SopApproveTool::new(engine.clone()).with_audit(audit.clone()),
+        ));
+        tool_arcs.push(Arc::new(SopAdvanceTool::new(engine).with_audit(audit)));
+    }
+
     // Inter-process agent communication (opt-in)
     if root_config.agents_ipc.enabled {
         match agents_ipc::IpcDb::open(workspace_dir, &root_config.agents_ipc) {
@@ -523,6 +551,61 @@ pub fn all_tools_with_runtime(
     boxed_registry_from_arcs(tool_arcs)
 }
 
+/// Connect to configured MCP servers and wrap each remote tool as a [`Tool`].
+///
+/// Servers that fail to start or handshake are logged and skipped so one bad
+/// entry does not take down the agent.
+pub async fn mcp_tools(
+    config: &crate::config::McpConfig,
+    security: &Arc<SecurityPolicy>,
+) -> Vec<Box<dyn Tool>> {
+    if !config.enabled {
+        return Vec::new();
+    }
+
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for server in &config.servers {
+        let client = match crate::mcp::McpClient::connect(server).await {
+            Ok(client) => Arc::new(client),
+            Err(e) => {
+                tracing::warn!(server = %server.name, "MCP server unavailable: {e:#}");
+                continue;
+            }
+        };
+        let defs = match client.list_tools().await {
+            Ok(defs) => defs,
+            Err(e) => {
+                tracing::warn!(server = %server.name, "MCP tools/list failed: {e:#}");
+                client.close().await;
+                continue;
+            }
+        };
+
+        let before = tools.len();
+        for def in defs {
+            if !server.include_tools.is_empty() && !server.include_tools.contains(&def.name) {
+                continue;
+            }
+            if server.exclude_tools.contains(&def.name) {
+                continue;
+            }
+            tools.push(Box::new(McpTool::new(
+                client.clone(),
+                def,
+                server.trust_annotations,
+                security.clone(),
+            )));
+        }
+        tracing::info!(
+            server = %server.name,
+            count = tools.len() - before,
+            "MCP tools registered"
+        );
+    }
+
+    tools
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,6 +5,8 @@ use serde_json::json;
 use tracing::warn;
 
 use super::traits::{Tool, ToolResult};
+use crate::sop::dispatch::extract_run_id_from_action;
+use crate::sop::engine::format_timeout_actions;
 use crate::sop::types::{SopRunAction, SopStepResult, SopStepStatus};
 use crate::sop::{SopAuditLogger, SopEngine, SopMetricsCollector};
 
@@ -61,6 +63,10 @@ impl Tool for SopAdvanceTool {
                 "output": {
                     "type": "string",
                     "description": "Brief summary of what happened in this step"
+                },
+                "step": {
+                    "type": "integer",
+                    "description": "Step number being reported (required for steps of a parallel group; defaults to the current step)"
                 }
             },
             "required": ["run_id", "status", "output"]
@@ -99,20 +105,42 @@ impl Tool for SopAdvanceTool {
         };
 
         // Lock engine, advance step, snapshot data for audit, then drop lock
-        let (action, step_result_ok, finished_run) = {
+        let (action, step_result_ok, finished_run, timeouts) = {
             let mut engine = self
                 .engine
                 .lock()
                 .map_err(|e| anyhow::anyhow!("Engine lock poisoned: {e}"))?;
 
+            // A step past its `timeout_secs` has already failed; its late
+            // result must not overwrite where the run was routed.
+            let timed_out = engine.check_timeouts();
+            let timeouts = format_timeout_actions(&timed_out);
+            if timed_out
+                .iter()
+                .any(|a| extract_run_id_from_action(a) == run_id)
+            {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Step result not recorded: run {run_id} timed out first.\n\n{timeouts}"
+                    )),
+                });
+            }
+
             let current_step = engine
                 .get_run(run_id)
                 .map(|r| r.current_step)
                 .ok_or_else(|| anyhow::anyhow!("Run not found: {run_id}"))?;
+            let step_number = args
+                .get("step")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(current_step);
 
             let now = now_iso8601();
             let step_result = SopStepResult {
-                step_number: current_step,
+                step_number,
                 status: step_status,
                 output: output.to_string(),
                 started_at: now.clone(),
@@ -129,9 +157,9 @@ impl Tool for SopAdvanceTool {
                         _ => None,
                     };
                     // Only audit step result when advance succeeded
-                    (Ok(action), Some(step_result_clone), finished)
+                    (Ok(action), Some(step_result_clone), finished, timeouts)
                 }
-                Err(e) => (Err(e), None, None),
+                Err(e) => (Err(e), None, None, timeouts),
             }
         };
 
@@ -164,6 +192,11 @@ impl Tool for SopAdvanceTool {
                     } => {
                         format!("Step recorded. Next step for run {run_id}:\n\n{context}")
                     }
+                    SopRunAction::ExecuteParallel {
+                        run_id, context, ..
+                    } => {
+                        format!("Step recorded. Next steps for run {run_id}:\n\n{context}")
+                    }
                     SopRunAction::WaitApproval {
                         run_id, context, ..
                     } => {
@@ -184,14 +217,14 @@ impl Tool for SopAdvanceTool {
                 };
                 Ok(ToolResult {
                     success: true,
-                    output: result_output,
+                    output: format!("{timeouts}{result_output}"),
                     error: None,
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Failed to advance step: {e}")),
+                error: Some(format!("{timeouts}Failed to advance step: {e}")),
             }),
         }
     }
@@ -222,6 +255,7 @@ mod tests {
                     body: "Do step one".into(),
                     suggested_tools: vec![],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
                 SopStep {
                     number: 2,
@@ -229,6 +263,7 @@ mod tests {
                     body: "Do step two".into(),
                     suggested_tools: vec![],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
             ],
             cooldown_secs: 0,
@@ -273,6 +308,49 @@ mod tests {
         assert!(result.output.contains("Step two"));
     }
 
+    #[tokio::test]
+    async fn late_result_for_timed_out_step_is_not_recorded() {
+        let mut sop = test_sop();
+        sop.steps[0].timeout_secs = Some(0);
+        sop.steps[0].on_failure = Some(SopStepTarget::Step(2));
+        let mut engine = SopEngine::new(SopConfig::default());
+        engine.set_sops_for_test(vec![sop]);
+        engine
+            .start_run(
+                "test-sop",
+                SopEvent {
+                    source: SopTriggerSource::Manual,
+                    topic: None,
+                    payload: None,
+                    timestamp: "2026-02-19T12:00:00Z".into(),
+                },
+            )
+            .unwrap();
+        let run_id = engine.active_runs().keys().next().unwrap().clone();
+        let engine = Arc::new(Mutex::new(engine));
+        let tool = SopAdvanceTool::new(engine.clone());
+
+        let result = tool
+            .execute(json!({
+                "run_id": run_id,
+                "status": "completed",
+                "output": "Step 1 done late"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("timed out first"));
+        assert!(error.contains("Step two"));
+
+        let engine = engine.lock().unwrap();
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.current_step, 2);
+        assert_eq!(run.step_results.len(), 1);
+        assert_eq!(run.step_results[0].status, SopStepStatus::Failed);
+        assert!(run.step_results[0].output.contains("Timed out after 0s"));
+    }
+
     #[tokio::test]
     async fn advance_to_completion() {
         let (engine, run_id) = engine_with_active_run();
@@ -104,6 +104,9 @@ impl Tool for SopExecuteTool {
                 let output = match action {
                     SopRunAction::ExecuteStep {
                         run_id, context, ..
+                    }
+                    | SopRunAction::ExecuteParallel {
+                        run_id, context, ..
                     } => {
                         format!("SOP run started: {run_id}\n\n{context}")
                     }
@@ -138,6 +141,7 @@ impl Tool for SopExecuteTool {
 fn action_run_id(action: &SopRunAction) -> Option<&str> {
     match action {
         SopRunAction::ExecuteStep { run_id, .. }
+        | SopRunAction::ExecuteParallel { run_id, .. }
         | SopRunAction::WaitApproval { run_id, .. }
         | SopRunAction::Completed { run_id, .. }
         | SopRunAction::Failed { run_id, .. } => Some(run_id),
@@ -168,6 +172,7 @@ mod tests {
                     body: "Do step one".into(),
                     suggested_tools: vec!["shell".into()],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
                 SopStep {
                     number: 2,
@@ -175,6 +180,7 @@ mod tests {
                     body: "Do step two".into(),
                     suggested_tools: vec![],
                     requires_confirmation: false,
+                    ..Default::default()
                 },
             ],
             cooldown_secs: 0,
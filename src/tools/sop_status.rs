@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use serde_json::json;
 
 use super::traits::{Tool, ToolResult};
+use crate::sop::engine::{format_run_path, format_timeout_actions};
 use crate::sop::{SopEngine, SopMetricsCollector};
 
 /// Query SOP execution status — active runs, finished runs, or a specific run by ID.
@@ -136,16 +137,22 @@ impl Tool for SopStatusTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        let engine = self
+        let mut engine = self
             .engine
             .lock()
             .map_err(|e| anyhow::anyhow!("Engine lock poisoned: {e}"))?;
 
+        // Expire overdue steps and approvals first so the report reflects
+        // them and the agent sees where the affected runs went next.
+        let timeouts = format_timeout_actions(&engine.check_timeouts());
+
         // Query specific run
         if let Some(run_id) = run_id {
             return match engine.get_run(run_id) {
                 Some(run) => {
-                    let mut output = format!(
+                    let mut output = timeouts;
+                    let _ = write!(
+                        output,
                         "Run: {}\nSOP: {}\nStatus: {}\nStep: {} of {}\nStarted: {}\n",
                         run.run_id,
                         run.sop_name,
@@ -157,6 +164,10 @@ impl Tool for SopStatusTool {
                     if let Some(ref completed) = run.completed_at {
                         let _ = writeln!(output, "Completed: {completed}");
                     }
+                    if !run.step_results.is_empty() || !run.active_steps.is_empty() {
+                        let path = format_run_path(run, engine.get_sop(&run.sop_name));
+                        let _ = writeln!(output, "Path: {path}");
+                    }
                     if !run.step_results.is_empty() {
                         let _ = writeln!(output, "\nStep results:");
                         for step in &run.step_results {
@@ -176,14 +187,14 @@ impl Tool for SopStatusTool {
                 }
                 None => Ok(ToolResult {
                     success: true,
-                    output: format!("No run found with ID '{run_id}'."),
+                    output: format!("{timeouts}No run found with ID '{run_id}'."),
                     error: None,
                 }),
             };
         }
 
         // List runs for a specific SOP or all active runs
-        let mut output = String::new();
+        let mut output = timeouts;
 
         // Active runs
         let active: Vec<_> = engine
@@ -309,6 +320,7 @@ mod tests {
                 body: "Do it".into(),
                 suggested_tools: vec![],
                 requires_confirmation: false,
+                ..Default::default()
             }],
             cooldown_secs: 0,
             max_concurrent: 2,
@@ -370,6 +382,24 @@ mod tests {
         assert!(result.output.contains("Status: running"));
     }
 
+    #[tokio::test]
+    async fn status_fails_step_past_its_timeout() {
+        let mut sop = test_sop("s1");
+        sop.steps[0].timeout_secs = Some(0);
+        let engine = engine_with_sops(vec![sop]);
+        let run_id = {
+            let mut e = engine.lock().unwrap();
+            e.start_run("s1", manual_event()).unwrap();
+            e.active_runs().keys().next().unwrap().clone()
+        };
+        let tool = SopStatusTool::new(engine);
+        let result = tool.execute(json!({"run_id": run_id})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains(&format!("run {run_id} failed")));
+        assert!(result.output.contains("Status: failed"));
+        assert!(result.output.contains("Timed out after 0s"));
+    }
+
     #[tokio::test]
     async fn status_unknown_run() {
         let engine = engine_with_sops(vec![]);
@@ -431,6 +461,7 @@ mod tests {
                 completed_at: Some("2026-02-19T12:01:00Z".into()),
             }],
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         collector.record_run_complete(&run);
 
@@ -466,6 +497,7 @@ mod tests {
                 completed_at: Some("2026-02-19T12:01:00Z".into()),
             }],
             waiting_since: None,
+            active_steps: Vec::new(),
         };
         collector.record_run_complete(&run);
 
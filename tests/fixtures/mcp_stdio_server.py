@@ -0,0 +1,116 @@
+#!/usr/bin/env python3
+"""Tiny stdio MCP server used by ZeroClaw's MCP client tests.
+
+Speaks newline-delimited JSON-RPC 2.0 on stdin/stdout and exposes three
+tools across two `tools/list` pages:
+
+- echo: returns the `text` argument
+- add: returns a + b (annotated read-only)
+- fail: always returns an error result
+"""
+
+import json
+import sys
+
+PAGE_ONE = [
+    {
+        "name": "echo",
+        "description": "Echo the given text back",
+        "inputSchema": {
+            "type": "object",
+            "properties": {"text": {"type": "string"}},
+            "required": ["text"],
+        },
+    }
+]
+
+PAGE_TWO = [
+    {
+        "name": "add",
+        "description": "Add two integers",
+        "inputSchema": {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+            "required": ["a", "b"],
+        },
+        "annotations": {"readOnlyHint": True},
+    },
+    {
+        "name": "fail",
+        "description": "Always fails",
+        "inputSchema": {"type": "object", "properties": {}},
+    },
+]
+
+
+def send(message):
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+
+def result(msg_id, payload):
+    send({"jsonrpc": "2.0", "id": msg_id, "result": payload})
+
+
+def error(msg_id, code, text):
+    send({"jsonrpc": "2.0", "id": msg_id, "error": {"code": code, "message": text}})
+
+
+def text_result(text, is_error=False):
+    return {"content": [{"type": "text", "text": text}], "isError": is_error}
+
+
+def handle(msg):
+    method = msg.get("method")
+    msg_id = msg.get("id")
+    params = msg.get("params") or {}
+
+    if msg_id is None:
+        # Notifications need no reply.
+        return
+
+    if method == "initialize":
+        result(
+            msg_id,
+            {
+                "protocolVersion": params.get("protocolVersion", "2025-06-18"),
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "zeroclaw-test-fixture", "version": "0.0.1"},
+            },
+        )
+    elif method == "ping":
+        result(msg_id, {})
+    elif method == "tools/list":
+        if params.get("cursor") == "page-2":
+            result(msg_id, {"tools": PAGE_TWO})
+        else:
+            result(msg_id, {"tools": PAGE_ONE, "nextCursor": "page-2"})
+    elif method == "tools/call":
+        name = params.get("name")
+        args = params.get("arguments") or {}
+        if name == "echo":
+            result(msg_id, text_result(str(args.get("text", ""))))
+        elif name == "add":
+            result(msg_id, text_result(str(int(args["a"]) + int(args["b"]))))
+        elif name == "fail":
+            result(msg_id, text_result("intentional failure", is_error=True))
+        else:
+            error(msg_id, -32602, "Unknown tool: %s" % name)
+    else:
+        error(msg_id, -32601, "Method not found: %s" % method)
+
+
+def main():
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            msg = json.loads(line)
+        except ValueError:
+            continue
+        handle(msg)
+
+
+if __name__ == "__main__":
+    main()
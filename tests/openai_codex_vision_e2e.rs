@@ -86,6 +86,7 @@ async fn provider_vision_support() -> Result<()> {
     let request = ChatRequest {
         messages: &messages,
         tools: None,
+        ..Default::default()
     };
 
     // Send request to provider
@@ -155,6 +156,7 @@ async fn openai_codex_second_vision_support() -> Result<()> {
         custom_provider_api_mode: None,
         max_tokens_override: None,
         model_support_vision: None,
+        azure_openai: Default::default(),
     };
 
     let provider = zeroclaw::providers::create_provider_with_options("openai-codex", None, &opts)?;
@@ -212,6 +214,7 @@ async fn openai_codex_second_vision_support() -> Result<()> {
     let request = ChatRequest {
         messages: &messages,
         tools: None,
+        ..Default::default()
     };
 
     // Send request to provider